@@ -25,14 +25,13 @@ async fn main() -> Result<(), std::io::Error> {
     }
     tracing_subscriber::fmt::init();
 
+    let reflection = Reflection::new().add_file_descriptor_set(FILE_DESCRIPTOR_SET);
+
     Server::new(TcpListener::bind("0.0.0.0:3000"))
         .run(
             RouteGrpc::new()
-                .add_service(
-                    Reflection::new()
-                        .add_file_descriptor_set(FILE_DESCRIPTOR_SET)
-                        .build(),
-                )
+                .add_service(reflection.clone().build())
+                .add_service(reflection.build_v1())
                 .add_service(GreeterServer::new(GreeterService))
                 .with(Tracing),
         )
@@ -88,8 +88,11 @@ impl<T: Serialize + Type> ApiResponse for Bcs<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
@@ -0,0 +1,143 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit, LitInt, LitStr, Meta, Result};
+
+use crate::utils::get_crate_name;
+
+pub(crate) fn generate(input: DeriveInput) -> Result<TokenStream> {
+    let mut internal = false;
+    for attr in &input.attrs {
+        if attr.path().is_ident("error_code") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("internal") {
+                    internal = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported error_code attribute"))
+                }
+            })?;
+        }
+    }
+    let crate_name = get_crate_name(internal);
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            ident,
+            "ErrorCode can only be applied to an enum.",
+        ));
+    };
+
+    let mut infos = Vec::new();
+    let mut arms = Vec::new();
+
+    for variant in &data.variants {
+        let mut code = None;
+        let mut status = None;
+
+        for attr in &variant.attrs {
+            if !attr.path().is_ident("error_code") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("code") {
+                    code = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("status") {
+                    let lit = meta.value()?.parse::<LitInt>()?;
+                    let value = lit.base10_parse::<u16>()?;
+                    if !(100..1000).contains(&value) {
+                        return Err(Error::new_spanned(lit, "invalid status code"));
+                    }
+                    status = Some(value);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported error_code attribute"))
+                }
+            })?;
+        }
+
+        let variant_ident = &variant.ident;
+        let status = status.ok_or_else(|| {
+            Error::new_spanned(
+                variant_ident,
+                "missing status, e.g. `#[error_code(status = 404)]`",
+            )
+        })?;
+        let code = code.unwrap_or_else(|| screaming_snake_case(&variant_ident.to_string()));
+        let description = match get_description(&variant.attrs) {
+            Some(description) => quote!(::std::option::Option::Some(#description)),
+            None => quote!(::std::option::Option::None),
+        };
+
+        let info = quote! {
+            #crate_name::error::ErrorCodeInfo {
+                code: #code,
+                status: #crate_name::http::StatusCode::from_u16(#status).unwrap(),
+                description: #description,
+            }
+        };
+        let pattern = match &variant.fields {
+            Fields::Named(_) => quote!(Self::#variant_ident { .. }),
+            Fields::Unnamed(_) => quote!(Self::#variant_ident(..)),
+            Fields::Unit => quote!(Self::#variant_ident),
+        };
+        arms.push(quote!(#pattern => #info));
+        infos.push(info);
+    }
+
+    Ok(quote! {
+        impl #impl_generics #crate_name::error::ErrorCode for #ident #type_generics #where_clause {
+            fn error_code(&self) -> #crate_name::error::ErrorCodeInfo {
+                match self {
+                    #(#arms,)*
+                }
+            }
+
+            fn error_codes() -> ::std::vec::Vec<#crate_name::error::ErrorCodeInfo> {
+                ::std::vec![#(#infos),*]
+            }
+        }
+
+        impl #impl_generics #crate_name::error::ResponseError for #ident #type_generics #where_clause {
+            fn status(&self) -> #crate_name::http::StatusCode {
+                #crate_name::error::ErrorCode::error_code(self).status
+            }
+
+            fn as_response(&self) -> #crate_name::Response
+            where
+                Self: ::std::error::Error + ::std::marker::Send + ::std::marker::Sync + 'static,
+            {
+                #crate_name::error::ErrorCode::error_code_response(self)
+            }
+        }
+    })
+}
+
+fn get_description(attrs: &[syn::Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if let Meta::NameValue(nv) = &attr.meta
+            && nv.path.is_ident("doc")
+            && let Expr::Lit(ExprLit {
+                lit: Lit::Str(doc), ..
+            }) = &nv.value
+        {
+            lines.push(doc.value().trim().to_string());
+        }
+    }
+    let description = lines.join("\n").trim().to_string();
+    (!description.is_empty()).then_some(description)
+}
+
+fn screaming_snake_case(s: &str) -> String {
+    let mut res = String::new();
+    for (idx, c) in s.chars().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            res.push('_');
+        }
+        res.push(c.to_ascii_uppercase());
+    }
+    res
+}
@@ -7,11 +7,12 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs)]
 
+mod error_code;
 mod utils;
 
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use syn::{FnArg, GenericParam, ItemFn, Member, Result, parse_macro_input};
+use syn::{DeriveInput, FnArg, GenericParam, ItemFn, Member, Result, parse_macro_input};
 
 /// Wrap an asynchronous function as an `Endpoint`.
 ///
@@ -131,6 +132,28 @@ fn generate_handler(internal: bool, input: TokenStream) -> Result<TokenStream> {
     Ok(expanded.into())
 }
 
+/// Implement `ErrorCode` and `ResponseError` for an error enum.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, ErrorCode)]
+/// enum UserError {
+///     /// The user does not exist.
+///     #[error("user not found")]
+///     #[error_code(code = "USER_NOT_FOUND", status = 404)]
+///     NotFound,
+/// }
+/// ```
+#[proc_macro_derive(ErrorCode, attributes(error_code))]
+pub fn derive_error_code(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match error_code::generate(input) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
 #[doc(hidden)]
 #[proc_macro]
 pub fn generate_implement_middlewares(_: TokenStream) -> TokenStream {
@@ -117,8 +117,8 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                 self
             }
 
-            /// Set the compression encoding for sending
-            pub fn set_send_compressed(&mut self, encoding: #crate_name::CompressionEncoding) {
+            /// Set the compression encoding for sending, `None` disables the compression
+            pub fn set_send_compressed(&mut self, encoding: impl ::std::convert::Into<::std::option::Option<#crate_name::CompressionEncoding>>) {
                 self.cli.set_send_compressed(encoding);
             }
 
@@ -127,6 +127,11 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                 self.cli.set_accept_compressed(encodings);
             }
 
+            /// Set the minimum size of the messages to be compressed, smaller messages are sent uncompressed
+            pub fn set_compression_min_size(&mut self, size: usize) {
+                self.cli.set_compression_min_size(size);
+            }
+
             #(
             #[allow(dead_code)]
             #methods
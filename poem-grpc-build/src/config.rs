@@ -1,10 +1,14 @@
 use std::{
+    collections::HashMap,
     ffi::OsString,
     io::Result,
     path::{Path, PathBuf},
 };
 
-use crate::service_generator::PoemServiceGenerator;
+use crate::{
+    http::{HttpBinding, load_http_rules},
+    service_generator::PoemServiceGenerator,
+};
 
 #[derive(Debug)]
 pub(crate) struct GrpcConfig {
@@ -15,6 +19,9 @@ pub(crate) struct GrpcConfig {
     pub(crate) build_server: bool,
     pub(crate) client_middlewares: Vec<String>,
     pub(crate) server_middlewares: Vec<String>,
+    pub(crate) transcoding: bool,
+    pub(crate) http_rules: HashMap<String, Vec<HttpBinding>>,
+    file_descriptor_set_path: Option<PathBuf>,
 }
 
 impl Default for GrpcConfig {
@@ -27,6 +34,9 @@ impl Default for GrpcConfig {
             build_server: true,
             client_middlewares: Vec::new(),
             server_middlewares: Vec::new(),
+            transcoding: false,
+            http_rules: HashMap::new(),
+            file_descriptor_set_path: None,
         }
     }
 }
@@ -249,6 +259,27 @@ impl Config {
         self
     }
 
+    /// Add additional attribute to matched messages.
+    ///
+    /// Unlike [`type_attribute`](#method.type_attribute), the attribute is
+    /// only placed before the `struct` definitions of the messages, and not
+    /// before the enums and the `oneof` types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # let mut config = poem_grpc_build::Config::new();
+    /// config.message_attribute(".", "#[serde(default)]");
+    /// ```
+    pub fn message_attribute<P, A>(mut self, path: P, attribute: A) -> Self
+    where
+        P: AsRef<str>,
+        A: AsRef<str>,
+    {
+        self.prost_config.message_attribute(path, attribute);
+        self
+    }
+
     /// Add additional attribute to matched fields.
     ///
     /// # Arguments
@@ -295,8 +326,9 @@ impl Config {
     /// When set, the `FileDescriptorSet` generated by `protoc` is written to
     /// the provided filesystem path.
     pub fn file_descriptor_set_path(mut self, path: impl AsRef<Path>) -> Self {
-        self.prost_config
-            .file_descriptor_set_path(PathBuf::from(std::env::var("OUT_DIR").unwrap()).join(path));
+        let path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join(path);
+        self.prost_config.file_descriptor_set_path(&path);
+        self.grpc_config.file_descriptor_set_path = Some(path);
         self
     }
 
@@ -360,6 +392,29 @@ impl Config {
         self
     }
 
+    /// Enable the JSON transcoding of the methods annotated with
+    /// [`google.api.http`](https://cloud.google.com/endpoints/docs/grpc-service-config/reference/rpc/google.api#google.api.HttpRule).
+    ///
+    /// The generated servers can be added to a `poem_grpc::RouteTranscoding`,
+    /// which serves the unary methods as RESTful JSON APIs. This requires the
+    /// `json-codec` feature of `poem-grpc`, and the messages must implement
+    /// `serde::Serialize` and `serde::Deserialize`. Since a field of the
+    /// request message can be mapped to the body, the messages should also
+    /// use the default values of the missing fields.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let config = poem_grpc_build::Config::new()
+    ///     .type_attribute(".", "#[derive(serde::Deserialize, serde::Serialize)]")
+    ///     .message_attribute(".", "#[serde(default)]")
+    ///     .enable_transcoding();
+    /// ```
+    pub fn enable_transcoding(mut self) -> Self {
+        self.grpc_config.transcoding = true;
+        self
+    }
+
     /// Add an argument to the `protoc` protobuf compilation invocation.
     pub fn protoc_arg(mut self, arg: impl Into<OsString>) -> Self {
         self.prost_config.protoc_arg(arg.into());
@@ -373,10 +428,31 @@ impl Config {
         protos: &[impl AsRef<Path>],
         includes: &[impl AsRef<Path>],
     ) -> Result<()> {
+        if !self.grpc_config.transcoding {
+            return self
+                .prost_config
+                .service_generator(Box::new(PoemServiceGenerator {
+                    config: self.grpc_config,
+                }))
+                .compile_protos(protos, includes);
+        }
+
+        // the `google.api.http` options are read from the encoded file descriptor set
+        let path = match self.grpc_config.file_descriptor_set_path.clone() {
+            Some(path) => path,
+            None => {
+                let path = PathBuf::from(std::env::var("OUT_DIR").unwrap())
+                    .join("poem-grpc-transcoding.bin");
+                self.prost_config.file_descriptor_set_path(&path);
+                path
+            }
+        };
+        let fds = self.prost_config.load_fds(protos, includes)?;
+        self.grpc_config.http_rules = load_http_rules(&std::fs::read(path)?)?;
         self.prost_config
             .service_generator(Box::new(PoemServiceGenerator {
                 config: self.grpc_config,
             }))
-            .compile_protos(protos, includes)
+            .compile_fds(fds)
     }
 }
@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+};
+
+use prost::Message;
+
+/// `google.api.HttpRule`
+#[derive(Clone, PartialEq, Message)]
+struct HttpRule {
+    #[prost(oneof = "Pattern", tags = "2, 3, 4, 5, 6, 8")]
+    pattern: Option<Pattern>,
+    #[prost(string, tag = "7")]
+    body: String,
+    #[prost(message, repeated, tag = "11")]
+    additional_bindings: Vec<HttpRule>,
+    #[prost(string, tag = "12")]
+    response_body: String,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+enum Pattern {
+    #[prost(string, tag = "2")]
+    Get(String),
+    #[prost(string, tag = "3")]
+    Put(String),
+    #[prost(string, tag = "4")]
+    Post(String),
+    #[prost(string, tag = "5")]
+    Delete(String),
+    #[prost(string, tag = "6")]
+    Patch(String),
+    #[prost(message, tag = "8")]
+    Custom(CustomHttpPattern),
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CustomHttpPattern {
+    #[prost(string, tag = "1")]
+    kind: String,
+    #[prost(string, tag = "2")]
+    path: String,
+}
+
+// The descriptor types of `prost-types` discard the extensions, so the file
+// descriptor set is decoded again with the fields needed to read the
+// `google.api.http` option.
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<FileDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct FileDescriptorProto {
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "6")]
+    service: Vec<ServiceDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ServiceDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    method: Vec<MethodDescriptorProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    options: Option<MethodOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MethodOptions {
+    /// `google.api.http`
+    #[prost(message, optional, tag = "72295728")]
+    http: Option<HttpRule>,
+}
+
+/// A HTTP binding of a GRPC method
+#[derive(Debug, Clone)]
+pub(crate) struct HttpBinding {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) body: String,
+    pub(crate) response_body: String,
+}
+
+impl HttpRule {
+    fn to_binding(&self) -> Option<HttpBinding> {
+        let (method, path) = match self.pattern.as_ref()? {
+            Pattern::Get(path) => ("GET", path),
+            Pattern::Put(path) => ("PUT", path),
+            Pattern::Post(path) => ("POST", path),
+            Pattern::Delete(path) => ("DELETE", path),
+            Pattern::Patch(path) => ("PATCH", path),
+            Pattern::Custom(custom) => (custom.kind.as_str(), &custom.path),
+        };
+        Some(HttpBinding {
+            method: method.to_string(),
+            path: path.clone(),
+            body: self.body.clone(),
+            response_body: self.response_body.clone(),
+        })
+    }
+
+    fn bindings(&self) -> Vec<HttpBinding> {
+        // nested additional bindings are not allowed
+        self.to_binding()
+            .into_iter()
+            .chain(
+                self.additional_bindings
+                    .iter()
+                    .filter_map(HttpRule::to_binding),
+            )
+            .collect()
+    }
+}
+
+/// Returns the key of a method in the map returned by [`load_http_rules`].
+pub(crate) fn method_key(package: &str, service: &str, method: &str) -> String {
+    if package.is_empty() {
+        format!("{service}/{method}")
+    } else {
+        format!("{package}.{service}/{method}")
+    }
+}
+
+/// Reads the `google.api.http` options of the methods from an encoded
+/// `FileDescriptorSet`.
+pub(crate) fn load_http_rules(data: &[u8]) -> Result<HashMap<String, Vec<HttpBinding>>> {
+    let fds =
+        FileDescriptorSet::decode(data).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+    let mut rules = HashMap::new();
+
+    for file in &fds.file {
+        let package = file.package.as_deref().unwrap_or_default();
+        for service in &file.service {
+            for method in &service.method {
+                let Some(http) = method
+                    .options
+                    .as_ref()
+                    .and_then(|options| options.http.as_ref())
+                else {
+                    continue;
+                };
+                rules.insert(
+                    method_key(
+                        package,
+                        service.name.as_deref().unwrap_or_default(),
+                        method.name.as_deref().unwrap_or_default(),
+                    ),
+                    http.bindings(),
+                );
+            }
+        }
+    }
+
+    Ok(rules)
+}
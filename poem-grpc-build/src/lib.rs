@@ -9,6 +9,7 @@
 
 mod client;
 mod config;
+mod http;
 mod server;
 mod service_generator;
 mod utils;
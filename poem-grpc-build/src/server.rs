@@ -3,7 +3,11 @@ use prost_build::Service;
 use quote::{format_ident, quote};
 use syn::{Expr, Path, Type};
 
-use crate::{config::GrpcConfig, utils::get_crate_name};
+use crate::{
+    config::GrpcConfig,
+    http::{HttpBinding, method_key},
+    utils::get_crate_name,
+};
 
 struct MethodInfo<'a> {
     path: &'a str,
@@ -20,6 +24,7 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
     let server_ident = format_ident!("{}Server", &service.name);
     let mut trait_methods = Vec::new();
     let mut endpoints = Vec::new();
+    let mut transcoding_routes = Vec::new();
     let codec_list = config
         .codec_list
         .iter()
@@ -63,6 +68,39 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                 trait_methods.push(quote! {
                     fn #method_ident(&self, request: #crate_name::Request<#input_type>) -> impl ::std::future::Future<Output = ::std::result::Result<#crate_name::Response<#output_type>, #crate_name::Status>> + Send;
                 });
+                if config.transcoding {
+                    let bindings = config
+                        .http_rules
+                        .get(&method_key(
+                            &service.package,
+                            &service.proto_name,
+                            &method.proto_name,
+                        ))
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    let operation = format!("{}.{}", service_name, method.proto_name);
+                    for binding in bindings {
+                        let HttpBinding {
+                            method: http_method,
+                            path,
+                            body,
+                            response_body,
+                        } = binding;
+                        transcoding_routes.push(quote! {
+                            route.add_unary(
+                                #crate_name::transcoding::HttpBinding::new(#http_method, #path, #body, #response_body),
+                                #operation,
+                                {
+                                    let inner = self.inner.clone();
+                                    move |request| {
+                                        let inner = inner.clone();
+                                        async move { inner.#method_ident(request).await }
+                                    }
+                                },
+                            );
+                        });
+                    }
+                }
                 endpoints.push(generate_unary(&codec_list, method_info));
             }
             (true, false) => {
@@ -92,6 +130,17 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
         }
     });
 
+    let transcoding = config.transcoding.then(|| {
+        quote! {
+            impl<T: #service_ident> #crate_name::transcoding::TranscodingService for #server_ident<T> {
+                #[allow(unused_variables)]
+                fn register(&self, route: &mut #crate_name::transcoding::RouteTranscoding) {
+                    #(#transcoding_routes)*
+                }
+            }
+        }
+    });
+
     let token_stream = quote! {
         #[allow(unused_imports)]
         pub trait #service_ident: Send + Sync + 'static {
@@ -103,6 +152,7 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
             inner: ::std::sync::Arc<T>,
             send_compressed: ::std::option::Option<#crate_name::CompressionEncoding>,
             accept_compressed: ::std::sync::Arc<[#crate_name::CompressionEncoding]>,
+            compression_min_size: usize,
         }
 
         impl<T> ::std::clone::Clone for #server_ident<T> {
@@ -112,6 +162,7 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                     inner: self.inner.clone(),
                     send_compressed: self.send_compressed,
                     accept_compressed: self.accept_compressed.clone(),
+                    compression_min_size: self.compression_min_size,
                 }
             }
         }
@@ -129,13 +180,16 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                     inner: ::std::sync::Arc::new(service),
                     send_compressed: ::std::option::Option::None,
                     accept_compressed: ::std::sync::Arc::new([]),
+                    compression_min_size: 0,
                 }
             }
 
-            /// Set the compression encoding for sending
-            pub fn send_compressed(self, encoding: #crate_name::CompressionEncoding) -> Self {
+            /// Set the compression encoding for sending, `None` disables the compression
+            ///
+            /// The responses are only compressed if the client accepts the encoding.
+            pub fn send_compressed(self, encoding: impl ::std::convert::Into<::std::option::Option<#crate_name::CompressionEncoding>>) -> Self {
                 Self {
-                    send_compressed: Some(encoding),
+                    send_compressed: encoding.into(),
                     ..self
                 }
             }
@@ -147,6 +201,14 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                     ..self
                 }
             }
+
+            /// Set the minimum size of the messages to be compressed, smaller messages are sent uncompressed
+            pub fn compression_min_size(self, size: usize) -> Self {
+                Self {
+                    compression_min_size: size,
+                    ..self
+                }
+            }
         }
 
         impl<T: #service_ident> ::poem::IntoEndpoint for #server_ident<T> {
@@ -170,6 +232,8 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
                 ep.boxed()
             }
         }
+
+        #transcoding
     };
 
     buf.push_str(&prettyplease::unparse(&syn::parse2(token_stream).unwrap()));
@@ -226,7 +290,7 @@ fn generate_unary(codec_list: &[Path], method_info: MethodInfo) -> TokenStream {
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).unary(#proxy_service_ident(server.inner.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).compression_min_size(server.compression_min_size).unary(#proxy_service_ident(server.inner.clone()), req).await
         },
     );
 
@@ -270,7 +334,7 @@ fn generate_client_streaming(codec_list: &[Path], method_info: MethodInfo) -> To
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).client_streaming(#proxy_service_ident(server.inner.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).compression_min_size(server.compression_min_size).client_streaming(#proxy_service_ident(server.inner.clone()), req).await
         },
     );
 
@@ -314,7 +378,7 @@ fn generate_server_streaming(codec_list: &[Path], method_info: MethodInfo) -> To
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).server_streaming(#proxy_service_ident(server.inner.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).compression_min_size(server.compression_min_size).server_streaming(#proxy_service_ident(server.inner.clone()), req).await
         },
     );
 
@@ -358,7 +422,7 @@ fn generate_bidirectional_streaming(codec_list: &[Path], method_info: MethodInfo
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).bidirectional_streaming(#proxy_service_ident(server.inner.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec, server.send_compressed, &server.accept_compressed).compression_min_size(server.compression_min_size).bidirectional_streaming(#proxy_service_ident(server.inner.clone()), req).await
         },
     );
 
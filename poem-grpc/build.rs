@@ -6,7 +6,11 @@ fn main() -> Result<()> {
         .internal()
         .file_descriptor_set_path("grpc-reflection.bin")
         .compile(
-            &["proto/reflection.proto", "proto/health.proto"],
+            &[
+                "proto/reflection.proto",
+                "proto/reflection_v1.proto",
+                "proto/health.proto",
+            ],
             &["proto/"],
         )?;
 
@@ -15,6 +19,16 @@ fn main() -> Result<()> {
         .internal()
         .compile(&["proto/test_harness.proto"], &["proto/"])?;
 
+    if std::env::var_os("CARGO_FEATURE_JSON_CODEC").is_some() {
+        poem_grpc_build::Config::new()
+            .build_client(false)
+            .internal()
+            .type_attribute(".", "#[derive(serde::Deserialize, serde::Serialize)]")
+            .message_attribute(".", "#[serde(default)]")
+            .enable_transcoding()
+            .compile(&["proto/transcoding.proto"], &["proto/"])?;
+    }
+
     // example
     poem_grpc_build::Config::new()
         .internal()
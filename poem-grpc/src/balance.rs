@@ -0,0 +1,435 @@
+use std::{
+    io::Error as IoError,
+    sync::{
+        Arc, Mutex, Weak,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{FutureExt, future::BoxFuture};
+use http::Uri;
+use tokio::time::Instant;
+use tower_service::Service;
+
+use crate::connector::{HttpsConnector, MaybeHttpsStream};
+
+/// The policy used to choose an endpoint for each call
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum LoadBalancingPolicy {
+    /// Choose a random endpoint.
+    #[default]
+    Random,
+    /// Use the first endpoint, the next ones are only used when the previous
+    /// endpoints are unavailable.
+    PickFirst,
+    /// Choose the endpoints in turn.
+    RoundRobin,
+}
+
+/// The parameters of the exponential backoff between the reconnection
+/// attempts
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Backoff {
+    pub(crate) initial: Duration,
+    pub(crate) max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(120),
+        }
+    }
+}
+
+impl Backoff {
+    const MULTIPLIER: f64 = 1.6;
+    const JITTER: f64 = 0.2;
+
+    /// Returns the delay before the next attempt after `failures` consecutive
+    /// failures.
+    fn delay(&self, failures: u32) -> Duration {
+        let delay = self
+            .initial
+            .mul_f64(Self::MULTIPLIER.powi(failures.saturating_sub(1).min(64) as i32))
+            .min(self.max);
+        delay.mul_f64(1.0 + Self::JITTER * (fastrand::f64() * 2.0 - 1.0))
+    }
+}
+
+#[derive(Default)]
+struct EndpointState {
+    failures: u32,
+    retry_at: Option<Instant>,
+    /// A connection established in advance, which is used by the next request
+    /// to this endpoint.
+    connection: Option<MaybeHttpsStream>,
+    connecting: bool,
+}
+
+struct Endpoint {
+    uri: Uri,
+    state: Mutex<EndpointState>,
+}
+
+/// Chooses the endpoints of the calls and tracks their availability
+pub(crate) struct Balancer {
+    policy: LoadBalancingPolicy,
+    backoff: Backoff,
+    endpoints: Vec<Endpoint>,
+    next: AtomicUsize,
+    /// `Some` if the connections are established eagerly.
+    connector: Option<HttpsConnector>,
+}
+
+impl Balancer {
+    pub(crate) fn new(
+        uris: Vec<Uri>,
+        policy: LoadBalancingPolicy,
+        backoff: Backoff,
+        connector: Option<HttpsConnector>,
+    ) -> Arc<Self> {
+        let balancer = Arc::new(Self {
+            policy,
+            backoff,
+            endpoints: uris
+                .into_iter()
+                .map(|uri| Endpoint {
+                    uri,
+                    state: Default::default(),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            connector,
+        });
+
+        if balancer.connector.is_some() && tokio::runtime::Handle::try_current().is_ok() {
+            for idx in 0..balancer.endpoints.len() {
+                balancer.connect(idx);
+            }
+        }
+        balancer
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn uri(&self, idx: usize) -> &Uri {
+        &self.endpoints[idx].uri
+    }
+
+    fn is_available(&self, idx: usize, now: Instant) -> bool {
+        let state = self.endpoints[idx].state.lock().unwrap();
+        state.retry_at.is_none_or(|retry_at| retry_at <= now)
+    }
+
+    /// Returns the index of the endpoint for the next call, or `None` if all
+    /// the endpoints are waiting to be retried.
+    pub(crate) fn pick(&self) -> Option<usize> {
+        let now = Instant::now();
+        let count = self.endpoints.len();
+        if count == 0 {
+            return None;
+        }
+
+        match self.policy {
+            LoadBalancingPolicy::PickFirst => (0..count).find(|idx| self.is_available(*idx, now)),
+            LoadBalancingPolicy::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..count)
+                    .map(|offset| (start + offset) % count)
+                    .find(|idx| self.is_available(*idx, now))
+            }
+            LoadBalancingPolicy::Random => {
+                let available = (0..count)
+                    .filter(|idx| self.is_available(*idx, now))
+                    .collect::<Vec<_>>();
+                (!available.is_empty()).then(|| available[fastrand::usize(0..available.len())])
+            }
+        }
+    }
+
+    /// Marks the endpoint as available.
+    pub(crate) fn report_success(&self, idx: usize) {
+        let mut state = self.endpoints[idx].state.lock().unwrap();
+        state.failures = 0;
+        state.retry_at = None;
+    }
+
+    /// Marks the endpoint as unavailable until the backoff delay has elapsed,
+    /// and reconnects to it in the background if the connections are
+    /// established eagerly.
+    pub(crate) fn report_failure(self: &Arc<Self>, idx: usize) {
+        {
+            let mut state = self.endpoints[idx].state.lock().unwrap();
+            state.failures += 1;
+            state.retry_at = Some(Instant::now() + self.backoff.delay(state.failures));
+            state.connection = None;
+        }
+        if self.connector.is_some() {
+            self.connect(idx);
+        }
+    }
+
+    /// Establishes a connection to the endpoint in the background, retrying
+    /// with backoff until it succeeds or the client is dropped.
+    fn connect(self: &Arc<Self>, idx: usize) {
+        {
+            let mut state = self.endpoints[idx].state.lock().unwrap();
+            if state.connecting {
+                return;
+            }
+            state.connecting = true;
+        }
+
+        let balancer = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let Some((retry_at, fut)) = Weak::upgrade(&balancer).and_then(|balancer| {
+                    let endpoint = &balancer.endpoints[idx];
+                    let retry_at = endpoint.state.lock().unwrap().retry_at;
+                    let fut = balancer.connector.clone()?.call(endpoint.uri.clone());
+                    Some((retry_at, fut))
+                }) else {
+                    return;
+                };
+                if let Some(retry_at) = retry_at {
+                    tokio::time::sleep_until(retry_at).await;
+                }
+
+                let res = fut.await;
+                let Some(balancer) = balancer.upgrade() else {
+                    return;
+                };
+                let mut state = balancer.endpoints[idx].state.lock().unwrap();
+                match res {
+                    Ok(stream) => {
+                        state.failures = 0;
+                        state.retry_at = None;
+                        state.connection = Some(stream);
+                        state.connecting = false;
+                        return;
+                    }
+                    Err(_) => {
+                        state.failures += 1;
+                        state.retry_at =
+                            Some(Instant::now() + balancer.backoff.delay(state.failures));
+                    }
+                }
+            }
+        });
+    }
+
+    /// Takes the connection established in advance to the endpoint of the uri.
+    fn take_connection(&self, uri: &Uri) -> Option<MaybeHttpsStream> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| {
+                endpoint.uri.scheme() == uri.scheme() && endpoint.uri.authority() == uri.authority()
+            })
+            .find_map(|endpoint| endpoint.state.lock().unwrap().connection.take())
+    }
+}
+
+/// A connector which uses the connections established in advance by the
+/// [`Balancer`]
+#[derive(Clone)]
+pub(crate) struct BalancedConnector {
+    inner: HttpsConnector,
+    balancer: Weak<Balancer>,
+}
+
+impl BalancedConnector {
+    pub(crate) fn new(inner: HttpsConnector, balancer: &Arc<Balancer>) -> Self {
+        Self {
+            inner,
+            balancer: Arc::downgrade(balancer),
+        }
+    }
+}
+
+impl Service<Uri> for BalancedConnector {
+    type Response = MaybeHttpsStream;
+    type Error = IoError;
+    type Future = BoxFuture<'static, Result<MaybeHttpsStream, IoError>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        match self
+            .balancer
+            .upgrade()
+            .and_then(|balancer| balancer.take_connection(&uri))
+        {
+            Some(stream) => futures_util::future::ready(Ok(stream)).boxed(),
+            None => self.inner.call(uri),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{
+        Server,
+        listener::{Acceptor, Listener, TcpListener},
+    };
+
+    use super::*;
+    use crate::{
+        ClientConfig, Code, Request, RouteGrpc,
+        test_harness::{
+            TestHarnessService,
+            proto::{TestHarnessClient, TestHarnessServer, UnaryRequest},
+        },
+    };
+
+    /// Returns the uris of a running server and of an address nothing
+    /// listens on.
+    async fn start_server() -> (String, String) {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor.local_addr()[0].as_socket_addr().cloned().unwrap();
+        tokio::spawn(
+            Server::new_with_acceptor(acceptor)
+                .run(RouteGrpc::new().add_service(TestHarnessServer::new(TestHarnessService))),
+        );
+
+        let dead = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let dead_addr = dead.local_addr().unwrap();
+        drop(dead);
+        (format!("http://{addr}"), format!("http://{dead_addr}"))
+    }
+
+    fn balancer(policy: LoadBalancingPolicy) -> Arc<Balancer> {
+        balancer_with_backoff(policy, Backoff::default())
+    }
+
+    fn balancer_with_backoff(policy: LoadBalancingPolicy, backoff: Backoff) -> Arc<Balancer> {
+        Balancer::new(
+            vec![
+                Uri::from_static("http://server1:3000"),
+                Uri::from_static("http://server2:3000"),
+                Uri::from_static("http://server3:3000"),
+            ],
+            policy,
+            backoff,
+            None,
+        )
+    }
+
+    #[test]
+    fn backoff() {
+        let backoff = Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(10),
+        };
+        let delay = backoff.delay(1);
+        assert!(delay >= Duration::from_millis(800) && delay <= Duration::from_millis(1200));
+        let delay = backoff.delay(3);
+        assert!(delay >= Duration::from_millis(2048) && delay <= Duration::from_millis(3072));
+        let delay = backoff.delay(100);
+        assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(12));
+    }
+
+    #[tokio::test]
+    async fn pick_first() {
+        let balancer = balancer(LoadBalancingPolicy::PickFirst);
+        assert_eq!(balancer.pick(), Some(0));
+        assert_eq!(balancer.pick(), Some(0));
+
+        balancer.report_failure(0);
+        assert_eq!(balancer.pick(), Some(1));
+        balancer.report_failure(1);
+        balancer.report_failure(2);
+        assert_eq!(balancer.pick(), None);
+
+        balancer.report_success(2);
+        assert_eq!(balancer.pick(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn round_robin() {
+        let balancer = balancer(LoadBalancingPolicy::RoundRobin);
+        assert_eq!(
+            (0..4).map(|_| balancer.pick().unwrap()).collect::<Vec<_>>(),
+            [0, 1, 2, 0]
+        );
+
+        balancer.report_failure(2);
+        assert_eq!(
+            (0..4).map(|_| balancer.pick().unwrap()).collect::<Vec<_>>(),
+            [1, 0, 0, 1]
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_after_backoff() {
+        let balancer = balancer_with_backoff(
+            LoadBalancingPolicy::Random,
+            Backoff {
+                initial: Duration::from_millis(10),
+                max: Duration::from_millis(10),
+            },
+        );
+        for idx in 0..3 {
+            balancer.report_failure(idx);
+        }
+        assert_eq!(balancer.pick(), None);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(balancer.pick().is_some());
+    }
+
+    #[tokio::test]
+    async fn failover() {
+        let (live, dead) = start_server().await;
+        let cli = TestHarnessClient::new(
+            ClientConfig::builder()
+                .uris([dead, live])
+                .load_balancing_policy(LoadBalancingPolicy::PickFirst)
+                .build()
+                .unwrap(),
+        );
+
+        let status = cli
+            .unary(Request::new(UnaryRequest { a: 1, b: 2 }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+        let resp = cli
+            .unary(Request::new(UnaryRequest { a: 1, b: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(resp.value, 3);
+    }
+
+    #[tokio::test]
+    async fn eager_connect() {
+        let (live, dead) = start_server().await;
+        let cli = TestHarnessClient::new(
+            ClientConfig::builder()
+                .uris([dead, live])
+                .load_balancing_policy(LoadBalancingPolicy::PickFirst)
+                .connect_lazy(false)
+                .build()
+                .unwrap(),
+        );
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let resp = cli
+            .unary(Request::new(UnaryRequest { a: 1, b: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(resp.value, 3);
+    }
+}
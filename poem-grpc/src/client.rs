@@ -1,9 +1,12 @@
-use std::{io::Error as IoError, sync::Arc};
+use std::{io::Error as IoError, sync::Arc, time::Duration};
 
 use bytes::Bytes;
 use futures_util::TryStreamExt;
 use http_body_util::BodyExt;
-use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+use hyper_util::{
+    client::legacy::Client,
+    rt::{TokioExecutor, TokioTimer},
+};
 use poem::{
     Endpoint, EndpointExt, IntoEndpoint, Middleware, Request as HttpRequest,
     Response as HttpResponse,
@@ -18,9 +21,14 @@ use rustls::ClientConfig as TlsClientConfig;
 
 use crate::{
     Code, CompressionEncoding, Metadata, Request, Response, Status, Streaming,
+    balance::{Backoff, BalancedConnector, Balancer, LoadBalancingPolicy},
     codec::Codec,
-    compression::get_incoming_encodings,
+    compression::{
+        GRPC_ACCEPT_ENCODING_HEADER, GRPC_ENCODING_HEADER, accept_encoding_header,
+        get_incoming_encodings,
+    },
     connector::HttpsConnector,
+    deadline::{CallContext, GRPC_TIMEOUT_HEADER, format_grpc_timeout, get_timeout},
     encoding::{create_decode_response_body, create_encode_request_body},
 };
 
@@ -33,6 +41,15 @@ pub struct ClientConfig {
     user_agent: Option<HeaderValue>,
     tls_config: Option<TlsClientConfig>,
     max_header_list_size: u32,
+    timeout: Option<Duration>,
+    http2_keep_alive_interval: Option<Duration>,
+    http2_keep_alive_timeout: Option<Duration>,
+    http2_keep_alive_while_idle: bool,
+    http2_initial_stream_window_size: Option<u32>,
+    http2_initial_connection_window_size: Option<u32>,
+    load_balancing_policy: LoadBalancingPolicy,
+    reconnect_backoff: Backoff,
+    connect_lazy: bool,
 }
 
 impl ClientConfig {
@@ -45,6 +62,15 @@ impl ClientConfig {
                 user_agent: None,
                 tls_config: None,
                 max_header_list_size: 16384,
+                timeout: None,
+                http2_keep_alive_interval: None,
+                http2_keep_alive_timeout: None,
+                http2_keep_alive_while_idle: false,
+                http2_initial_stream_window_size: None,
+                http2_initial_connection_window_size: None,
+                load_balancing_policy: LoadBalancingPolicy::default(),
+                reconnect_backoff: Backoff::default(),
+                connect_lazy: true,
             }),
         }
     }
@@ -162,6 +188,126 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Sets the default timeout of the calls.
+    ///
+    /// It is sent to the server with the `grpc-timeout` metadata, unless the
+    /// request specifies its own timeout with
+    /// [`Request::set_timeout`](crate::Request::set_timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.timeout = Some(timeout);
+        }
+        self
+    }
+
+    /// Sets an interval for HTTP2 Ping frames should be sent to keep a
+    /// connection alive.
+    ///
+    /// Default is `None`, which disables the keep-alive.
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.http2_keep_alive_interval = Some(interval);
+        }
+        self
+    }
+
+    /// Sets a timeout for receiving an acknowledgement of the keep-alive ping.
+    ///
+    /// If the ping is not acknowledged within the timeout, the connection will
+    /// be closed. Does nothing if `http2_keep_alive_interval` is disabled.
+    ///
+    /// Default is 20 seconds.
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.http2_keep_alive_timeout = Some(timeout);
+        }
+        self
+    }
+
+    /// Sets whether HTTP2 keep-alive should apply while the connection is
+    /// idle.
+    ///
+    /// Default is `false`.
+    pub fn http2_keep_alive_while_idle(mut self, enabled: bool) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.http2_keep_alive_while_idle = enabled;
+        }
+        self
+    }
+
+    /// Sets the [`SETTINGS_INITIAL_WINDOW_SIZE`][spec] option for HTTP2
+    /// stream-level flow control.
+    ///
+    /// [spec]: https://http2.github.io/http2-spec/#SETTINGS_INITIAL_WINDOW_SIZE
+    pub fn http2_initial_stream_window_size(mut self, size: u32) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.http2_initial_stream_window_size = Some(size);
+        }
+        self
+    }
+
+    /// Sets the max connection-level flow control for HTTP2.
+    pub fn http2_initial_connection_window_size(mut self, size: u32) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.http2_initial_connection_window_size = Some(size);
+        }
+        self
+    }
+
+    /// Sets the policy used to choose an endpoint for each call.
+    ///
+    /// Default is [`LoadBalancingPolicy::Random`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use poem_grpc::{ClientConfig, LoadBalancingPolicy};
+    /// let cfg = ClientConfig::builder()
+    ///     .uris(["http://server1:3000", "http://server2:3000"])
+    ///     .load_balancing_policy(LoadBalancingPolicy::RoundRobin)
+    ///     .build();
+    /// ```
+    pub fn load_balancing_policy(mut self, policy: LoadBalancingPolicy) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.load_balancing_policy = policy;
+        }
+        self
+    }
+
+    /// Sets the exponential backoff between the connection attempts to an
+    /// endpoint.
+    ///
+    /// When a call fails to reach an endpoint, the endpoint is not used again
+    /// until the delay has elapsed, starting from `initial` and growing up to
+    /// `max` with each consecutive failure. The calls fail with
+    /// [`Code::Unavailable`] if all the endpoints are waiting.
+    ///
+    /// Default is 1 second to 120 seconds.
+    pub fn reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.reconnect_backoff = Backoff {
+                initial,
+                max: max.max(initial),
+            };
+        }
+        self
+    }
+
+    /// Sets whether the connections are established by the first call to
+    /// each endpoint.
+    ///
+    /// If `false`, the client connects to all the endpoints when it is
+    /// created, and reconnects in the background when a connection fails.
+    /// This requires the client to be created within a Tokio runtime.
+    ///
+    /// Default is `true`.
+    pub fn connect_lazy(mut self, lazy: bool) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.connect_lazy = lazy;
+        }
+        self
+    }
+
     /// Consumes this builder and returns the `ClientConfig`
     pub fn build(self) -> Result<ClientConfig, ClientBuilderError> {
         self.config
@@ -174,6 +320,7 @@ pub struct GrpcClient {
     ep: Arc<dyn DynEndpoint<Output = HttpResponse> + 'static>,
     send_compressed: Option<CompressionEncoding>,
     accept_compressed: Arc<[CompressionEncoding]>,
+    compression_min_size: usize,
 }
 
 impl GrpcClient {
@@ -183,6 +330,7 @@ impl GrpcClient {
             ep: create_client_endpoint(config),
             send_compressed: None,
             accept_compressed: Arc::new([]),
+            compression_min_size: 0,
         }
     }
 
@@ -196,17 +344,22 @@ impl GrpcClient {
             ep: Arc::new(ToDynEndpoint(ep.map_to_response())),
             send_compressed: None,
             accept_compressed: Arc::new([]),
+            compression_min_size: 0,
         }
     }
 
-    pub fn set_send_compressed(&mut self, encoding: CompressionEncoding) {
-        self.send_compressed = Some(encoding);
+    pub fn set_send_compressed(&mut self, encoding: impl Into<Option<CompressionEncoding>>) {
+        self.send_compressed = encoding.into();
     }
 
     pub fn set_accept_compressed(&mut self, encodings: impl Into<Arc<[CompressionEncoding]>>) {
         self.accept_compressed = encodings.into();
     }
 
+    pub fn set_compression_min_size(&mut self, size: usize) {
+        self.compression_min_size = size;
+    }
+
     pub fn with<M>(mut self, middleware: M) -> Self
     where
         M: Middleware<Arc<dyn DynEndpoint<Output = HttpResponse> + 'static>>,
@@ -229,42 +382,51 @@ impl GrpcClient {
             message,
             extensions,
         } = request;
-        let mut http_request =
-            create_http_request::<T>(path, metadata, extensions, self.send_compressed);
-        http_request.set_body(create_encode_request_body(
-            codec.encoder(),
-            Streaming::new(futures_util::stream::once(async move { Ok(message) })),
-            self.send_compressed,
-        ));
-
-        let mut resp = self
-            .ep
-            .call(http_request)
-            .await
-            .map_err(|err| Status::new(Code::Internal).with_message(err))?;
-
-        if resp.status() != StatusCode::OK {
-            return Err(Status::new(Code::Internal).with_message(format!(
-                "invalid http status code: {}",
-                resp.status().as_u16()
-            )));
-        }
+        let ctx = CallContext::new(&metadata.headers);
+
+        ctx.run(async {
+            let mut http_request = self.create_http_request::<T>(path, metadata, extensions);
+            http_request.set_body(create_encode_request_body(
+                codec.encoder(),
+                Streaming::new(futures_util::stream::once(async move { Ok(message) })),
+                self.send_compressed,
+                self.compression_min_size,
+            ));
+
+            let mut resp = self
+                .ep
+                .call(http_request)
+                .await
+                .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+
+            if resp.status() != StatusCode::OK {
+                return Err(Status::new(Code::Internal).with_message(format!(
+                    "invalid http status code: {}",
+                    resp.status().as_u16()
+                )));
+            }
 
-        let body = resp.take_body();
-        let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let mut stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
-
-        let message = stream
-            .try_next()
-            .await?
-            .ok_or_else(|| Status::new(Code::Internal).with_message("missing response message"))?;
-        Ok(Response {
-            metadata: Metadata {
-                headers: std::mem::take(resp.headers_mut()),
-            },
-            message,
+            let body = resp.take_body();
+            let incoming_encoding =
+                get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
+            let mut stream = create_decode_response_body(
+                codec.decoder(),
+                resp.headers(),
+                body,
+                incoming_encoding,
+            )?;
+
+            let message = stream.try_next().await?.ok_or_else(|| {
+                Status::new(Code::Internal).with_message("missing response message")
+            })?;
+            Ok(Response {
+                metadata: Metadata {
+                    headers: std::mem::take(resp.headers_mut()),
+                },
+                message,
+            })
         })
+        .await
     }
 
     pub async fn client_streaming<T: Codec>(
@@ -278,42 +440,51 @@ impl GrpcClient {
             message,
             extensions,
         } = request;
-        let mut http_request =
-            create_http_request::<T>(path, metadata, extensions, self.send_compressed);
-        http_request.set_body(create_encode_request_body(
-            codec.encoder(),
-            message,
-            self.send_compressed,
-        ));
-
-        let mut resp = self
-            .ep
-            .call(http_request)
-            .await
-            .map_err(|err| Status::new(Code::Internal).with_message(err))?;
-
-        if resp.status() != StatusCode::OK {
-            return Err(Status::new(Code::Internal).with_message(format!(
-                "invalid http status code: {}",
-                resp.status().as_u16()
-            )));
-        }
+        let ctx = CallContext::new(&metadata.headers);
+
+        ctx.run(async {
+            let mut http_request = self.create_http_request::<T>(path, metadata, extensions);
+            http_request.set_body(create_encode_request_body(
+                codec.encoder(),
+                message,
+                self.send_compressed,
+                self.compression_min_size,
+            ));
+
+            let mut resp = self
+                .ep
+                .call(http_request)
+                .await
+                .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+
+            if resp.status() != StatusCode::OK {
+                return Err(Status::new(Code::Internal).with_message(format!(
+                    "invalid http status code: {}",
+                    resp.status().as_u16()
+                )));
+            }
 
-        let body = resp.take_body();
-        let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let mut stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
-
-        let message = stream
-            .try_next()
-            .await?
-            .ok_or_else(|| Status::new(Code::Internal).with_message("missing response message"))?;
-        Ok(Response {
-            metadata: Metadata {
-                headers: std::mem::take(resp.headers_mut()),
-            },
-            message,
+            let body = resp.take_body();
+            let incoming_encoding =
+                get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
+            let mut stream = create_decode_response_body(
+                codec.decoder(),
+                resp.headers(),
+                body,
+                incoming_encoding,
+            )?;
+
+            let message = stream.try_next().await?.ok_or_else(|| {
+                Status::new(Code::Internal).with_message("missing response message")
+            })?;
+            Ok(Response {
+                metadata: Metadata {
+                    headers: std::mem::take(resp.headers_mut()),
+                },
+                message,
+            })
         })
+        .await
     }
 
     pub async fn server_streaming<T: Codec>(
@@ -327,38 +498,48 @@ impl GrpcClient {
             message,
             extensions,
         } = request;
-        let mut http_request =
-            create_http_request::<T>(path, metadata, extensions, self.send_compressed);
-        http_request.set_body(create_encode_request_body(
-            codec.encoder(),
-            Streaming::new(futures_util::stream::once(async move { Ok(message) })),
-            self.send_compressed,
-        ));
-
-        let mut resp = self
-            .ep
-            .call(http_request)
-            .await
-            .map_err(|err| Status::new(Code::Internal).with_message(err))?;
-
-        if resp.status() != StatusCode::OK {
-            return Err(Status::new(Code::Internal).with_message(format!(
-                "invalid http status code: {}",
-                resp.status().as_u16()
-            )));
-        }
-
-        let body = resp.take_body();
-        let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
+        let ctx = CallContext::new(&metadata.headers);
+
+        ctx.run(async {
+            let mut http_request = self.create_http_request::<T>(path, metadata, extensions);
+            http_request.set_body(create_encode_request_body(
+                codec.encoder(),
+                Streaming::new(futures_util::stream::once(async move { Ok(message) })),
+                self.send_compressed,
+                self.compression_min_size,
+            ));
+
+            let mut resp = self
+                .ep
+                .call(http_request)
+                .await
+                .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+
+            if resp.status() != StatusCode::OK {
+                return Err(Status::new(Code::Internal).with_message(format!(
+                    "invalid http status code: {}",
+                    resp.status().as_u16()
+                )));
+            }
 
-        Ok(Response {
-            metadata: Metadata {
-                headers: std::mem::take(resp.headers_mut()),
-            },
-            message: stream,
+            let body = resp.take_body();
+            let incoming_encoding =
+                get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
+            let stream = create_decode_response_body(
+                codec.decoder(),
+                resp.headers(),
+                body,
+                incoming_encoding,
+            )?;
+
+            Ok(Response {
+                metadata: Metadata {
+                    headers: std::mem::take(resp.headers_mut()),
+                },
+                message: ctx.clone().wrap_stream(stream, None),
+            })
         })
+        .await
     }
 
     pub async fn bidirectional_streaming<T: Codec>(
@@ -372,74 +553,89 @@ impl GrpcClient {
             message,
             extensions,
         } = request;
-        let mut http_request =
-            create_http_request::<T>(path, metadata, extensions, self.send_compressed);
-        http_request.set_body(create_encode_request_body(
-            codec.encoder(),
-            message,
-            self.send_compressed,
-        ));
-
-        let mut resp = self
-            .ep
-            .call(http_request)
-            .await
-            .map_err(|err| Status::new(Code::Internal).with_message(err))?;
-
-        if resp.status() != StatusCode::OK {
-            return Err(Status::new(Code::Internal).with_message(format!(
-                "invalid http status code: {}",
-                resp.status().as_u16()
-            )));
-        }
-
-        let body = resp.take_body();
-        let incoming_encoding = get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
-        let stream =
-            create_decode_response_body(codec.decoder(), resp.headers(), body, incoming_encoding)?;
+        let ctx = CallContext::new(&metadata.headers);
+
+        ctx.run(async {
+            let mut http_request = self.create_http_request::<T>(path, metadata, extensions);
+            http_request.set_body(create_encode_request_body(
+                codec.encoder(),
+                message,
+                self.send_compressed,
+                self.compression_min_size,
+            ));
+
+            let mut resp = self
+                .ep
+                .call(http_request)
+                .await
+                .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+
+            if resp.status() != StatusCode::OK {
+                return Err(Status::new(Code::Internal).with_message(format!(
+                    "invalid http status code: {}",
+                    resp.status().as_u16()
+                )));
+            }
 
-        Ok(Response {
-            metadata: Metadata {
-                headers: std::mem::take(resp.headers_mut()),
-            },
-            message: stream,
+            let body = resp.take_body();
+            let incoming_encoding =
+                get_incoming_encodings(resp.headers(), &self.accept_compressed)?;
+            let stream = create_decode_response_body(
+                codec.decoder(),
+                resp.headers(),
+                body,
+                incoming_encoding,
+            )?;
+
+            Ok(Response {
+                metadata: Metadata {
+                    headers: std::mem::take(resp.headers_mut()),
+                },
+                message: ctx.clone().wrap_stream(stream, None),
+            })
         })
+        .await
     }
-}
 
-fn create_http_request<T: Codec>(
-    path: &str,
-    metadata: Metadata,
-    extensions: Extensions,
-    send_compressed: Option<CompressionEncoding>,
-) -> HttpRequest {
-    let mut http_request = HttpRequest::builder()
-        .uri_str(path)
-        .method(Method::POST)
-        .version(Version::HTTP_2)
-        .finish();
-    *http_request.headers_mut() = metadata.headers;
-    *http_request.extensions_mut() = extensions;
-    http_request
-        .headers_mut()
-        .insert("content-type", T::CONTENT_TYPES[0].parse().unwrap());
-    http_request
-        .headers_mut()
-        .insert(header::TE, "trailers".parse().unwrap());
-    if let Some(send_compressed) = send_compressed {
-        http_request.headers_mut().insert(
-            "grpc-encoding",
-            HeaderValue::from_str(send_compressed.as_str()).expect("BUG: invalid encoding"),
-        );
+    fn create_http_request<T: Codec>(
+        &self,
+        path: &str,
+        metadata: Metadata,
+        extensions: Extensions,
+    ) -> HttpRequest {
+        let mut http_request = HttpRequest::builder()
+            .uri_str(path)
+            .method(Method::POST)
+            .version(Version::HTTP_2)
+            .finish();
+        *http_request.headers_mut() = metadata.headers;
+        *http_request.extensions_mut() = extensions;
+        http_request
+            .headers_mut()
+            .insert("content-type", T::CONTENT_TYPES[0].parse().unwrap());
+        http_request
+            .headers_mut()
+            .insert(header::TE, "trailers".parse().unwrap());
+        if let Some(send_compressed) = self.send_compressed {
+            http_request.headers_mut().insert(
+                GRPC_ENCODING_HEADER,
+                HeaderValue::from_str(send_compressed.as_str()).expect("BUG: invalid encoding"),
+            );
+        }
+        if let Some(accept_encoding) = accept_encoding_header(&self.accept_compressed) {
+            http_request
+                .headers_mut()
+                .insert(GRPC_ACCEPT_ENCODING_HEADER, accept_encoding);
+        }
+        http_request
     }
-    http_request
 }
 
-#[inline]
-fn to_boxed_error(
-    err: impl std::error::Error + Send + Sync + 'static,
-) -> Box<dyn std::error::Error + Send + Sync> {
-    Box::new(err)
+/// Returns a trailers-only response, so the call fails with the status.
+fn status_response(status: Status) -> HttpResponse {
+    let mut resp = HttpResponse::default();
+    resp.headers_mut().extend(status.to_headers());
+    resp
 }
 
 fn make_uri(base_uri: &Uri, path: &Uri) -> Uri {
@@ -465,32 +661,49 @@ fn create_client_endpoint(
     config: ClientConfig,
 ) -> Arc<dyn DynEndpoint<Output = HttpResponse> + 'static> {
     let mut config = config;
-    let cli = Client::builder(TokioExecutor::new())
+    let mut builder = Client::builder(TokioExecutor::new());
+    builder
         .http2_only(true)
         .http2_max_header_list_size(config.max_header_list_size)
-        .build(HttpsConnector::new(config.tls_config.take()));
+        .http2_keep_alive_interval(config.http2_keep_alive_interval)
+        .http2_keep_alive_while_idle(config.http2_keep_alive_while_idle)
+        .http2_initial_stream_window_size(config.http2_initial_stream_window_size)
+        .http2_initial_connection_window_size(config.http2_initial_connection_window_size)
+        .timer(TokioTimer::new());
+    if let Some(timeout) = config.http2_keep_alive_timeout {
+        builder.http2_keep_alive_timeout(timeout);
+    }
+    let connector = HttpsConnector::new(config.tls_config.take());
+    let balancer = Balancer::new(
+        std::mem::take(&mut config.uris),
+        config.load_balancing_policy,
+        config.reconnect_backoff,
+        (!config.connect_lazy).then(|| connector.clone()),
+    );
+    let cli = builder.build(BalancedConnector::new(connector, &balancer));
 
     let config = Arc::new(config);
 
     Arc::new(ToDynEndpoint(poem::endpoint::make(move |request| {
         let config = config.clone();
+        let balancer = balancer.clone();
         let cli = cli.clone();
         async move {
             let mut request: hyper::Request<BoxBody> = request.into();
 
-            if config.uris.is_empty() {
+            if balancer.is_empty() {
                 return Err(poem::Error::from_string(
                     "uris is empty",
                     StatusCode::INTERNAL_SERVER_ERROR,
                 ));
             }
 
-            let base_uri = if config.uris.len() == 1 {
-                &config.uris[0]
-            } else {
-                &config.uris[fastrand::usize(0..config.uris.len())]
+            let Some(idx) = balancer.pick() else {
+                return Ok(status_response(
+                    Status::new(Code::Unavailable).with_message("no available endpoint"),
+                ));
             };
-            *request.uri_mut() = make_uri(base_uri, request.uri());
+            *request.uri_mut() = make_uri(balancer.uri(idx), request.uri());
 
             if let Some(origin) = &config.origin {
                 if let Ok(value) = HeaderValue::from_maybe_shared(origin.to_string()) {
@@ -504,7 +717,39 @@ fn create_client_endpoint(
                     .insert(header::ORIGIN, user_agent.clone());
             }
 
-            let resp = cli.request(request).await.map_err(to_boxed_error)?;
+            let mut timeout = get_timeout(request.headers());
+            if let (None, Some(default_timeout)) = (timeout, config.timeout) {
+                request.headers_mut().insert(
+                    GRPC_TIMEOUT_HEADER,
+                    HeaderValue::from_str(&format_grpc_timeout(default_timeout))
+                        .expect("valid timeout"),
+                );
+                timeout = Some(default_timeout);
+            }
+
+            let resp = match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, cli.request(request)).await {
+                    Ok(resp) => resp,
+                    Err(_) => {
+                        return Ok(status_response(
+                            Status::new(Code::DeadlineExceeded).with_message("deadline exceeded"),
+                        ));
+                    }
+                },
+                None => cli.request(request).await,
+            };
+            let resp = match resp {
+                Ok(resp) => {
+                    balancer.report_success(idx);
+                    resp
+                }
+                Err(err) => {
+                    balancer.report_failure(idx);
+                    return Ok(status_response(
+                        Status::new(Code::Unavailable).with_message(err),
+                    ));
+                }
+            };
             let (parts, body) = resp.into_parts();
 
             Ok::<_, poem::Error>(HttpResponse::from(hyper::Response::from_parts(
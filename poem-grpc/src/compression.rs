@@ -1,6 +1,6 @@
 use std::{io::Result as IoResult, str::FromStr};
 
-use http::HeaderMap;
+use http::{HeaderMap, HeaderValue};
 
 use crate::{Code, Metadata, Status};
 
@@ -148,18 +148,48 @@ impl CompressionEncoding {
     }
 }
 
+pub(crate) const GRPC_ENCODING_HEADER: &str = "grpc-encoding";
+pub(crate) const GRPC_ACCEPT_ENCODING_HEADER: &str = "grpc-accept-encoding";
+
+/// Returns the value of the `grpc-accept-encoding` header for the accepted
+/// encodings, or `None` if no encoding is accepted.
+pub(crate) fn accept_encoding_header(
+    accept_compressed: &[CompressionEncoding],
+) -> Option<HeaderValue> {
+    if accept_compressed.is_empty() {
+        return None;
+    }
+    let value = accept_compressed
+        .iter()
+        .map(CompressionEncoding::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(HeaderValue::from_str(&value).expect("BUG: invalid encoding"))
+}
+
+/// Returns the encoding for sending messages to the peer, which is
+/// `send_compressed` if the peer accepts it according to its
+/// `grpc-accept-encoding` header.
+pub(crate) fn negotiate_send_encoding(
+    headers: &HeaderMap,
+    send_compressed: Option<CompressionEncoding>,
+) -> Option<CompressionEncoding> {
+    let send_compressed = send_compressed?;
+    headers
+        .get_all(GRPC_ACCEPT_ENCODING_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|encoding| encoding.trim() == send_compressed.as_str())
+        .then_some(send_compressed)
+}
+
 fn unimplemented(accept_compressed: &[CompressionEncoding]) -> Status {
     let mut md = Metadata::new();
-    let mut accept_encoding = String::new();
-    let mut iter = accept_compressed.iter();
-    if let Some(encoding) = iter.next() {
-        accept_encoding.push_str(encoding.as_str());
-    }
-    for encoding in iter {
-        accept_encoding.push_str(", ");
-        accept_encoding.push_str(encoding.as_str());
+    if let Some(accept_encoding) = accept_encoding_header(accept_compressed) {
+        md.headers
+            .insert(GRPC_ACCEPT_ENCODING_HEADER, accept_encoding);
     }
-    md.append("grpc-accept-encoding", accept_encoding);
     Status::new(Code::Unimplemented)
         .with_metadata(md)
         .with_message("unsupported encoding")
@@ -170,7 +200,7 @@ pub(crate) fn get_incoming_encodings(
     headers: &HeaderMap,
     accept_compressed: &[CompressionEncoding],
 ) -> Result<Option<CompressionEncoding>, Status> {
-    let Some(value) = headers.get("grpc-encoding") else {
+    let Some(value) = headers.get(GRPC_ENCODING_HEADER) else {
         return Ok(None);
     };
     let Some(encoding) = value
@@ -185,3 +215,100 @@ pub(crate) fn get_incoming_encodings(
     }
     Ok(Some(encoding))
 }
+
+#[cfg(all(test, feature = "gzip"))]
+mod tests {
+    use poem::{
+        Endpoint, IntoEndpoint,
+        http::{Method, Version},
+    };
+
+    use super::*;
+    use crate::{
+        Request, RouteGrpc,
+        test_harness::{
+            TestHarnessService,
+            proto::{TestHarnessClient, TestHarnessServer, UnaryRequest, ValueResponse},
+        },
+    };
+
+    fn route(compression_min_size: usize) -> RouteGrpc {
+        RouteGrpc::new().add_service(
+            TestHarnessServer::new(TestHarnessService)
+                .send_compressed(CompressionEncoding::GZIP)
+                .accept_compressed([CompressionEncoding::GZIP])
+                .compression_min_size(compression_min_size),
+        )
+    }
+
+    #[test]
+    fn negotiate() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            negotiate_send_encoding(&headers, Some(CompressionEncoding::GZIP)),
+            None
+        );
+
+        headers.insert(
+            GRPC_ACCEPT_ENCODING_HEADER,
+            HeaderValue::from_static("identity, gzip"),
+        );
+        assert_eq!(
+            negotiate_send_encoding(&headers, Some(CompressionEncoding::GZIP)),
+            Some(CompressionEncoding::GZIP)
+        );
+        assert_eq!(negotiate_send_encoding(&headers, None), None);
+    }
+
+    #[tokio::test]
+    async fn compressed() {
+        let mut cli = TestHarnessClient::from_endpoint(route(0));
+        cli.set_send_compressed(CompressionEncoding::GZIP);
+        cli.set_accept_compressed([CompressionEncoding::GZIP]);
+
+        let resp = cli
+            .unary(Request::new(UnaryRequest { a: 1, b: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(resp.metadata().get(GRPC_ENCODING_HEADER), Some("gzip"));
+        assert_eq!(
+            resp.metadata().get(GRPC_ACCEPT_ENCODING_HEADER),
+            Some("gzip")
+        );
+        assert_eq!(resp.into_inner(), ValueResponse { value: 3 });
+    }
+
+    #[tokio::test]
+    async fn not_accepted_by_client() {
+        let cli = TestHarnessClient::from_endpoint(route(0));
+        let resp = cli
+            .unary(Request::new(UnaryRequest { a: 1, b: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(resp.metadata().get(GRPC_ENCODING_HEADER), None);
+        assert_eq!(resp.into_inner(), ValueResponse { value: 3 });
+    }
+
+    #[tokio::test]
+    async fn min_size() {
+        let resp = route(1024)
+            .into_endpoint()
+            .call(
+                poem::Request::builder()
+                    .method(Method::POST)
+                    .version(Version::HTTP_2)
+                    .uri_str("/test_harness.TestHarness/Unary")
+                    .content_type("application/grpc")
+                    .header(GRPC_ACCEPT_ENCODING_HEADER, "gzip")
+                    .body(vec![0, 0, 0, 0, 0]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.headers().get(GRPC_ENCODING_HEADER),
+            Some(&HeaderValue::from_static("gzip"))
+        );
+        let body = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(body[0], 0);
+    }
+}
@@ -0,0 +1,411 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode, Version, header};
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::Frame;
+use poem::{
+    Body, Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+};
+use serde_json::{Map, Value, json};
+use sync_wrapper::SyncStream;
+
+use crate::{Code, Status, client::BoxBody};
+
+const CONNECT_STREAMING_CONTENT_TYPE: &str = "application/connect+";
+const CONNECT_UNARY_CONTENT_TYPE: &str = "application/";
+
+/// Middleware for translating [Connect](https://connectrpc.com/docs/protocol)
+/// requests to GRPC requests
+///
+/// Unary calls are accepted with the `application/proto` and
+/// `application/json` content types, streaming calls with the
+/// `application/connect+proto` and `application/connect+json` content types.
+/// Other requests are passed to the inner endpoint unchanged.
+///
+/// The JSON content types require the services to be generated with the
+/// `JsonCodec`.
+///
+/// # Example
+///
+/// ```ignore
+/// use poem::EndpointExt;
+/// use poem_grpc::{Connect, RouteGrpc};
+///
+/// let route = RouteGrpc::new()
+///     .add_service(GreeterServer::new(GreeterService))
+///     .with(Connect::new());
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Connect;
+
+impl Connect {
+    /// Create a `Connect` middleware
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Connect {
+    type Output = ConnectEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ConnectEndpoint { inner: ep }
+    }
+}
+
+/// Endpoint for the [`Connect`] middleware
+pub struct ConnectEndpoint<E> {
+    inner: E,
+}
+
+impl<E: Endpoint> Endpoint for ConnectEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let Some((content_type, streaming)) = req.content_type().and_then(parse_content_type)
+        else {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        };
+        let connect_content_type = req.headers().get(header::CONTENT_TYPE).cloned();
+
+        req.set_version(Version::HTTP_2);
+        req.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(&content_type).expect("valid content type"),
+        );
+
+        let mut resp = if streaming {
+            rename_header(
+                req.headers_mut(),
+                "connect-content-encoding",
+                "grpc-encoding",
+            );
+            rename_header(
+                req.headers_mut(),
+                "connect-accept-encoding",
+                "grpc-accept-encoding",
+            );
+
+            let mut resp = self.inner.call(req).await?.into_response();
+            rename_header(
+                resp.headers_mut(),
+                "grpc-encoding",
+                "connect-content-encoding",
+            );
+            let status = take_status(resp.headers_mut());
+            let body = resp.take_body();
+            resp.set_body(encode_streaming_body(body, status));
+            resp
+        } else {
+            let compressed = req.headers().contains_key(header::CONTENT_ENCODING);
+            rename_header(req.headers_mut(), "content-encoding", "grpc-encoding");
+            rename_header(req.headers_mut(), "accept-encoding", "grpc-accept-encoding");
+            req.headers_mut().remove(header::CONTENT_LENGTH);
+            let message = match req.take_body().into_bytes().await {
+                Ok(message) => message,
+                Err(err) => {
+                    return Ok(unary_error(
+                        &Status::new(Code::InvalidArgument).with_message(err),
+                        HeaderMap::new(),
+                    ));
+                }
+            };
+            req.set_body(envelope(compressed, &message));
+
+            let resp = self.inner.call(req).await?.into_response();
+            into_unary_response(resp).await
+        };
+
+        resp.set_version(Version::HTTP_11);
+        // Errors of unary calls are always encoded as JSON
+        if streaming || resp.status().is_success() {
+            if let Some(connect_content_type) = connect_content_type {
+                resp.headers_mut()
+                    .insert(header::CONTENT_TYPE, connect_content_type);
+            }
+        }
+        Ok(resp)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+/// Returns the GRPC content type for a Connect content type, and whether the
+/// request is a streaming call.
+fn parse_content_type(content_type: &str) -> Option<(String, bool)> {
+    let (codec, streaming) = match content_type.strip_prefix(CONNECT_STREAMING_CONTENT_TYPE) {
+        Some(codec) => (codec, true),
+        None => (
+            content_type.strip_prefix(CONNECT_UNARY_CONTENT_TYPE)?,
+            false,
+        ),
+    };
+    match codec {
+        "proto" | "json" => Some((format!("application/grpc+{codec}"), streaming)),
+        _ => None,
+    }
+}
+
+fn rename_header(headers: &mut HeaderMap, from: &'static str, to: &'static str) {
+    if let Some(value) = headers.remove(from) {
+        headers.insert(to, value);
+    }
+}
+
+/// Removes the status of a trailers-only response from the headers.
+fn take_status(headers: &mut HeaderMap) -> Option<Status> {
+    let status = Status::from_headers(headers).unwrap_or_else(Some);
+    headers.remove("grpc-status");
+    headers.remove("grpc-message");
+    status
+}
+
+fn envelope(compressed: bool, message: &[u8]) -> Bytes {
+    let mut buf = BytesMut::with_capacity(message.len() + 5);
+    buf.put_u8(compressed as u8);
+    buf.put_u32(message.len() as u32);
+    buf.put_slice(message);
+    buf.freeze()
+}
+
+async fn into_unary_response(resp: Response) -> Response {
+    let (mut parts, body) = resp.into_parts();
+
+    if let Some(status) = take_status(&mut parts.headers) {
+        return unary_error(&status, parts.headers);
+    }
+
+    let mut body: BoxBody = body.into();
+    let mut data = BytesMut::new();
+    let mut trailers = HeaderMap::new();
+    while let Some(frame) = body.frame().await {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(err) => return unary_error(&Status::from_std_error(err), parts.headers),
+        };
+        match frame.into_data() {
+            Ok(frame_data) => data.extend_from_slice(&frame_data),
+            Err(frame) => {
+                if let Ok(frame_trailers) = frame.into_trailers() {
+                    trailers.extend(frame_trailers);
+                }
+            }
+        }
+    }
+
+    let status = take_status(&mut trailers)
+        .unwrap_or_else(|| Status::new(Code::Internal).with_message("missing trailers"));
+    for (name, value) in &trailers {
+        if let Ok(name) = HeaderName::from_bytes(format!("trailer-{name}").as_bytes()) {
+            parts.headers.append(name, value.clone());
+        }
+    }
+    if !status.is_ok() {
+        return unary_error(&status, parts.headers);
+    }
+    if data.len() < 5 {
+        return unary_error(
+            &Status::new(Code::Internal).with_message("missing response message"),
+            parts.headers,
+        );
+    }
+
+    let compressed = data[0] == 1;
+    if let Some(encoding) = parts.headers.remove("grpc-encoding") {
+        if compressed {
+            parts.headers.insert(header::CONTENT_ENCODING, encoding);
+        }
+    }
+    parts.status = StatusCode::OK;
+    Response::from_parts(parts, Body::from_bytes(data.split_off(5).freeze()))
+}
+
+fn unary_error(status: &Status, mut headers: HeaderMap) -> Response {
+    let (code, http_status) = connect_code(status.code());
+    headers.remove(header::CONTENT_TYPE);
+    headers.remove("grpc-encoding");
+
+    let mut resp = Response::builder()
+        .status(http_status)
+        .content_type("application/json")
+        .body(error_json(code, status).to_string());
+    resp.headers_mut().extend(headers);
+    resp
+}
+
+fn error_json(code: &str, status: &Status) -> Value {
+    let mut error = json!({ "code": code });
+    if let Some(message) = status.message() {
+        error["message"] = message.into();
+    }
+    error
+}
+
+fn encode_streaming_body(body: Body, status: Option<Status>) -> Body {
+    let mut body: BoxBody = body.into();
+
+    let stream = async_stream::try_stream! {
+        if let Some(status) = status {
+            yield Frame::data(end_stream(&status, &HeaderMap::new()));
+            return;
+        }
+
+        while let Some(frame) = body.frame().await.transpose()? {
+            match frame.into_data() {
+                Ok(data) => yield Frame::data(data),
+                Err(frame) => {
+                    if let Ok(mut trailers) = frame.into_trailers() {
+                        let status = take_status(&mut trailers)
+                            .unwrap_or_else(|| Status::new(Code::Internal).with_message("missing grpc-status"));
+                        yield Frame::data(end_stream(&status, &trailers));
+                    }
+                }
+            }
+        }
+    };
+
+    BodyExt::boxed(StreamBody::new(SyncStream::new(stream))).into()
+}
+
+/// Encodes the end of stream message with the status and the trailers.
+fn end_stream(status: &Status, trailers: &HeaderMap) -> Bytes {
+    let mut message = Map::new();
+    if !status.is_ok() {
+        message.insert(
+            "error".to_string(),
+            error_json(connect_code(status.code()).0, status),
+        );
+    }
+    if !trailers.is_empty() {
+        let mut metadata = Map::new();
+        for name in trailers.keys() {
+            let values = trailers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(|value| Value::String(value.to_string()))
+                .collect();
+            metadata.insert(name.to_string(), Value::Array(values));
+        }
+        message.insert("metadata".to_string(), Value::Object(metadata));
+    }
+
+    let data = Value::Object(message).to_string();
+    let mut buf = BytesMut::with_capacity(data.len() + 5);
+    buf.put_u8(0x02);
+    buf.put_u32(data.len() as u32);
+    buf.put_slice(data.as_bytes());
+    buf.freeze()
+}
+
+/// Returns the Connect error code and HTTP status for a GRPC status code.
+pub(crate) fn connect_code(code: Code) -> (&'static str, StatusCode) {
+    match code {
+        Code::Ok => ("ok", StatusCode::OK),
+        Code::Cancelled => ("canceled", StatusCode::from_u16(499).unwrap()),
+        Code::InvalidArgument => ("invalid_argument", StatusCode::BAD_REQUEST),
+        Code::DeadlineExceeded => ("deadline_exceeded", StatusCode::GATEWAY_TIMEOUT),
+        Code::NotFound => ("not_found", StatusCode::NOT_FOUND),
+        Code::AlreadyExists => ("already_exists", StatusCode::CONFLICT),
+        Code::PermissionDenied => ("permission_denied", StatusCode::FORBIDDEN),
+        Code::ResourceExhausted => ("resource_exhausted", StatusCode::TOO_MANY_REQUESTS),
+        Code::FailedPrecondition => ("failed_precondition", StatusCode::BAD_REQUEST),
+        Code::Aborted => ("aborted", StatusCode::CONFLICT),
+        Code::OutOfRange => ("out_of_range", StatusCode::BAD_REQUEST),
+        Code::Unimplemented => ("unimplemented", StatusCode::NOT_IMPLEMENTED),
+        Code::Internal => ("internal", StatusCode::INTERNAL_SERVER_ERROR),
+        Code::Unavailable => ("unavailable", StatusCode::SERVICE_UNAVAILABLE),
+        Code::DataLoss => ("data_loss", StatusCode::INTERNAL_SERVER_ERROR),
+        Code::Unauthenticated => ("unauthenticated", StatusCode::UNAUTHORIZED),
+        Code::Unknown | Code::Other(_) => ("unknown", StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::EndpointExt;
+    use prost::Message;
+
+    use super::*;
+    use crate::{
+        RouteGrpc,
+        test_harness::{
+            TestHarnessService,
+            proto::{TestHarnessServer, UnaryRequest, ValueRequest, ValueResponse},
+        },
+    };
+
+    async fn call(method: &str, content_type: &str, body: Vec<u8>) -> Response {
+        let ep = RouteGrpc::new()
+            .add_service(TestHarnessServer::new(TestHarnessService))
+            .with(Connect::new());
+        ep.call(
+            Request::builder()
+                .method(http::Method::POST)
+                .uri_str(format!("/test_harness.TestHarness/{method}"))
+                .content_type(content_type)
+                .body(body),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn content_type() {
+        assert_eq!(
+            parse_content_type("application/proto"),
+            Some(("application/grpc+proto".to_string(), false))
+        );
+        assert_eq!(
+            parse_content_type("application/connect+json"),
+            Some(("application/grpc+json".to_string(), true))
+        );
+        assert_eq!(parse_content_type("application/grpc"), None);
+        assert_eq!(parse_content_type("application/connect+xml"), None);
+    }
+
+    #[tokio::test]
+    async fn unary() {
+        let resp = call(
+            "Unary",
+            "application/proto",
+            UnaryRequest { a: 10, b: 20 }.encode_to_vec(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.content_type(), Some("application/proto"));
+        let body = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(
+            ValueResponse::decode(body.as_slice()).unwrap(),
+            ValueResponse { value: 30 }
+        );
+    }
+
+    #[tokio::test]
+    async fn unary_error() {
+        let resp = call("Unary", "application/proto", vec![0xff]).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.content_type(), Some("application/json"));
+        let body: Value = resp.into_body().into_json().await.unwrap();
+        assert_eq!(body["code"], "internal");
+    }
+
+    #[tokio::test]
+    async fn server_streaming() {
+        let resp = call(
+            "ServerStreaming",
+            "application/connect+proto",
+            envelope(false, &ValueRequest { value: 1 }.encode_to_vec()).to_vec(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.content_type(), Some("application/connect+proto"));
+
+        let mut expected = Vec::new();
+        expected.extend(envelope(false, &ValueResponse { value: 1 }.encode_to_vec()));
+        expected.extend(envelope(false, &ValueResponse { value: 0 }.encode_to_vec()));
+        expected.extend(b"\x02\x00\x00\x00\x02{}");
+        assert_eq!(resp.into_body().into_vec().await.unwrap(), expected);
+    }
+}
@@ -0,0 +1,233 @@
+use std::{future::Future, time::Duration};
+
+use futures_util::StreamExt;
+use http::HeaderMap;
+use tokio::time::Instant;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+use crate::{Code, Status, Streaming};
+
+pub(crate) const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// The maximum value of a `grpc-timeout`, which is at most 8 digits.
+const MAX_TIMEOUT_VALUE: u64 = 99_999_999;
+
+/// Parses the value of the `grpc-timeout` header.
+pub(crate) fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (value, unit) = value.split_at(value.len() - 1);
+    if !value.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value = value.parse::<u64>().ok()?;
+    Some(match unit {
+        "H" => Duration::from_secs(value * 60 * 60),
+        "M" => Duration::from_secs(value * 60),
+        "S" => Duration::from_secs(value),
+        "m" => Duration::from_millis(value),
+        "u" => Duration::from_micros(value),
+        "n" => Duration::from_nanos(value),
+        _ => return None,
+    })
+}
+
+/// Formats a duration as the value of the `grpc-timeout` header, using the
+/// most precise unit that fits in 8 digits.
+pub(crate) fn format_grpc_timeout(timeout: Duration) -> String {
+    let nanos = timeout.as_nanos();
+    let units: [(u128, char); 6] = [
+        (1, 'n'),
+        (1_000, 'u'),
+        (1_000_000, 'm'),
+        (1_000_000_000, 'S'),
+        (60 * 1_000_000_000, 'M'),
+        (60 * 60 * 1_000_000_000, 'H'),
+    ];
+
+    for (scale, unit) in units {
+        let value = nanos.div_ceil(scale);
+        if value <= MAX_TIMEOUT_VALUE as u128 {
+            return format!("{value}{unit}");
+        }
+    }
+    format!("{MAX_TIMEOUT_VALUE}H")
+}
+
+/// Returns the timeout of the call specified by the `grpc-timeout` header.
+pub(crate) fn get_timeout(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(GRPC_TIMEOUT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_grpc_timeout)
+}
+
+fn deadline_exceeded() -> Status {
+    Status::new(Code::DeadlineExceeded).with_message("deadline exceeded")
+}
+
+/// The deadline and the cancellation token of a call
+#[derive(Clone)]
+pub(crate) struct CallContext {
+    pub(crate) deadline: Option<Instant>,
+    pub(crate) token: CancellationToken,
+}
+
+impl CallContext {
+    pub(crate) fn new(headers: &HeaderMap) -> Self {
+        Self {
+            deadline: get_timeout(headers).map(|timeout| Instant::now() + timeout),
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// Returns a guard that cancels the call when dropped, it must be disarmed
+    /// when the call completes.
+    pub(crate) fn guard(&self) -> DropGuard {
+        self.token.clone().drop_guard()
+    }
+
+    /// Runs the future until the deadline, the call is cancelled if the
+    /// deadline is exceeded.
+    pub(crate) async fn run<T>(
+        &self,
+        fut: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Status> {
+        let Some(deadline) = self.deadline else {
+            return fut.await;
+        };
+        match tokio::time::timeout_at(deadline, fut).await {
+            Ok(res) => res,
+            Err(_) => {
+                self.token.cancel();
+                Err(deadline_exceeded())
+            }
+        }
+    }
+
+    /// Applies the deadline to a response stream.
+    ///
+    /// The guard is disarmed when the stream completes, so the call is
+    /// cancelled if the stream is dropped before that.
+    pub(crate) fn wrap_stream<T: Send + 'static>(
+        self,
+        mut stream: Streaming<T>,
+        guard: Option<DropGuard>,
+    ) -> Streaming<T> {
+        Streaming::new(async_stream::stream! {
+            loop {
+                match self.run(async { Ok(stream.next().await) }).await {
+                    Ok(Some(item)) => yield item,
+                    Ok(None) => break,
+                    Err(status) => {
+                        yield Err(status);
+                        break;
+                    }
+                }
+            }
+            if let Some(guard) = guard {
+                guard.disarm();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use prost::Message;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::{
+        Request, Response,
+        codec::ProstCodec,
+        server::GrpcServer,
+        service::UnaryService,
+        test_harness::proto::{ValueRequest, ValueResponse},
+    };
+
+    struct SlowService(std::sync::Mutex<Option<oneshot::Sender<()>>>);
+
+    impl UnaryService<ValueRequest> for SlowService {
+        type Response = ValueResponse;
+
+        async fn call(
+            &self,
+            req: Request<ValueRequest>,
+        ) -> Result<Response<ValueResponse>, Status> {
+            assert!(req.deadline().is_some());
+            let token = req.cancellation_token();
+            let tx = self.0.lock().unwrap().take().unwrap();
+            tokio::spawn(async move {
+                token.cancelled().await;
+                _ = tx.send(());
+            });
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(Response::new(ValueResponse { value: req.value }))
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_grpc_timeout("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_grpc_timeout("2M"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_grpc_timeout("3S"), Some(Duration::from_secs(3)));
+        assert_eq!(parse_grpc_timeout("10m"), Some(Duration::from_millis(10)));
+        assert_eq!(parse_grpc_timeout("5u"), Some(Duration::from_micros(5)));
+        assert_eq!(
+            parse_grpc_timeout("99999999n"),
+            Some(Duration::from_nanos(99_999_999))
+        );
+        assert_eq!(parse_grpc_timeout("100000000n"), None);
+        assert_eq!(parse_grpc_timeout("S"), None);
+        assert_eq!(parse_grpc_timeout("1s"), None);
+        assert_eq!(parse_grpc_timeout("+1S"), None);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_grpc_timeout(Duration::from_nanos(5)), "5n");
+        assert_eq!(format_grpc_timeout(Duration::from_millis(10)), "10000000n");
+        assert_eq!(format_grpc_timeout(Duration::from_secs(1)), "1000000u");
+        assert_eq!(format_grpc_timeout(Duration::from_secs(3600)), "3600000m");
+        assert_eq!(
+            format_grpc_timeout(Duration::from_secs(3650 * 24 * 3600)),
+            "5256000M"
+        );
+        assert_eq!(format_grpc_timeout(Duration::MAX), "99999999H");
+    }
+
+    #[tokio::test]
+    async fn deadline_exceeded() {
+        let (tx, rx) = oneshot::channel();
+        let data = ValueRequest { value: 1 }.encode_to_vec();
+        let mut body = vec![0];
+        body.extend((data.len() as u32).to_be_bytes());
+        body.extend(data);
+
+        let resp = GrpcServer::new(
+            ProstCodec::<ValueResponse, ValueRequest>::default(),
+            None,
+            &[],
+        )
+        .unary(
+            SlowService(std::sync::Mutex::new(Some(tx))),
+            poem::Request::builder()
+                .method(Method::POST)
+                .content_type("application/grpc")
+                .header(GRPC_TIMEOUT_HEADER, "10m")
+                .body(body),
+        )
+        .await;
+        assert_eq!(
+            resp.headers().get("grpc-status").unwrap(),
+            Code::DeadlineExceeded.as_u16().to_string().as_str()
+        );
+        tokio::time::timeout(Duration::from_secs(1), rx)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
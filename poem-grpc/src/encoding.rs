@@ -13,21 +13,23 @@ use crate::{
     codec::{Decoder, Encoder},
 };
 
+/// Encodes a message as a data frame, the message is compressed only if its
+/// size is at least `compression_min_size`.
 async fn encode_data_frame<T: Encoder>(
     encoder: &mut T,
     buf: &mut BytesMut,
     message: T::Item,
     compression: Option<CompressionEncoding>,
+    compression_min_size: usize,
 ) -> IoResult<Bytes> {
-    buf.put_slice(&[compression.is_some() as u8, 0, 0, 0, 0]);
+    buf.put_slice(&[0, 0, 0, 0, 0]);
+    encoder.encode(message, buf)?;
 
-    if let Some(compression) = compression {
-        let mut data = BytesMut::new();
-        encoder.encode(message, &mut data)?;
-        let data = compression.encode(&data).await?;
+    if let Some(compression) = compression.filter(|_| buf.len() - 5 >= compression_min_size) {
+        let data = compression.encode(&buf[5..]).await?;
+        buf.truncate(5);
         buf.extend(data);
-    } else {
-        encoder.encode(message, buf)?;
+        buf.as_mut()[0] = 1;
     }
 
     let msg_len = (buf.len() - 5) as u32;
@@ -133,6 +135,7 @@ pub(crate) fn create_encode_response_body<T: Encoder>(
     mut encoder: T,
     mut stream: Streaming<T::Item>,
     compression: Option<CompressionEncoding>,
+    compression_min_size: usize,
 ) -> Body {
     let stream = async_stream::try_stream! {
         let mut buf = BytesMut::new();
@@ -140,7 +143,7 @@ pub(crate) fn create_encode_response_body<T: Encoder>(
         while let Some(item) = stream.next().await {
             match item {
                 Ok(message) => {
-                    if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message, compression).await {
+                    if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message, compression, compression_min_size).await {
                         yield Frame::data(data);
                     }
                 }
@@ -160,12 +163,13 @@ pub(crate) fn create_encode_request_body<T: Encoder>(
     mut encoder: T,
     mut stream: Streaming<T::Item>,
     compression: Option<CompressionEncoding>,
+    compression_min_size: usize,
 ) -> Body {
     let stream = async_stream::try_stream! {
         let mut buf = BytesMut::new();
 
         while let Some(Ok(message)) = stream.next().await {
-            if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message, compression).await {
+            if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message, compression, compression_min_size).await {
                 yield Frame::data(data);
             }
         }
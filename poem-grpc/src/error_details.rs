@@ -0,0 +1,578 @@
+//! Rich error details
+//!
+//! The standard error payloads of the [`google.rpc`](https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto)
+//! package, sent in the `grpc-status-details-bin` metadata of a [`Status`](crate::Status).
+
+use std::{collections::HashMap, time::Duration};
+
+use prost::Message;
+use prost_types::Any;
+
+use crate::Code;
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/";
+
+/// The `google.rpc.Status` message
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<Any>,
+}
+
+/// Describes when the clients can retry a failed request.
+#[derive(Clone, PartialEq, Message)]
+pub struct RetryInfo {
+    /// Clients should wait at least this long between retrying the same
+    /// request.
+    #[prost(message, optional, tag = "1")]
+    pub retry_delay: Option<prost_types::Duration>,
+}
+
+/// Describes additional debugging info.
+#[derive(Clone, PartialEq, Message)]
+pub struct DebugInfo {
+    /// The stack trace entries indicating where the error occurred.
+    #[prost(string, repeated, tag = "1")]
+    pub stack_entries: Vec<String>,
+    /// Additional debugging information provided by the server.
+    #[prost(string, tag = "2")]
+    pub detail: String,
+}
+
+/// Describes how a quota check failed.
+#[derive(Clone, PartialEq, Message)]
+pub struct QuotaFailure {
+    /// Describes all quota violations.
+    #[prost(message, repeated, tag = "1")]
+    pub violations: Vec<QuotaViolation>,
+}
+
+/// A single quota violation.
+#[derive(Clone, PartialEq, Message)]
+pub struct QuotaViolation {
+    /// The subject on which the quota check failed.
+    #[prost(string, tag = "1")]
+    pub subject: String,
+    /// A description of how the quota check failed.
+    #[prost(string, tag = "2")]
+    pub description: String,
+}
+
+/// Describes the cause of the error with structured details.
+#[derive(Clone, PartialEq, Message)]
+pub struct ErrorInfo {
+    /// The reason of the error, in `UPPER_SNAKE_CASE`.
+    #[prost(string, tag = "1")]
+    pub reason: String,
+    /// The logical grouping to which the reason belongs.
+    #[prost(string, tag = "2")]
+    pub domain: String,
+    /// Additional structured details about this error.
+    #[prost(map = "string, string", tag = "3")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Describes what preconditions have failed.
+#[derive(Clone, PartialEq, Message)]
+pub struct PreconditionFailure {
+    /// Describes all precondition violations.
+    #[prost(message, repeated, tag = "1")]
+    pub violations: Vec<PreconditionViolation>,
+}
+
+/// A single precondition violation.
+#[derive(Clone, PartialEq, Message)]
+pub struct PreconditionViolation {
+    /// The type of precondition failure.
+    #[prost(string, tag = "1")]
+    pub r#type: String,
+    /// The subject, relative to the type, that failed.
+    #[prost(string, tag = "2")]
+    pub subject: String,
+    /// A description of how the precondition failed.
+    #[prost(string, tag = "3")]
+    pub description: String,
+}
+
+/// Describes violations in a client request.
+#[derive(Clone, PartialEq, Message)]
+pub struct BadRequest {
+    /// Describes all violations in a client request.
+    #[prost(message, repeated, tag = "1")]
+    pub field_violations: Vec<FieldViolation>,
+}
+
+/// A single bad request field.
+#[derive(Clone, PartialEq, Message)]
+pub struct FieldViolation {
+    /// A path that leads to a field in the request body.
+    #[prost(string, tag = "1")]
+    pub field: String,
+    /// A description of why the request element is bad.
+    #[prost(string, tag = "2")]
+    pub description: String,
+}
+
+/// Contains metadata about the request that clients can attach when filing a
+/// bug or providing other forms of feedback.
+#[derive(Clone, PartialEq, Message)]
+pub struct RequestInfo {
+    /// An opaque string that should only be interpreted by the service
+    /// generating it.
+    #[prost(string, tag = "1")]
+    pub request_id: String,
+    /// Any data that was used to serve this request.
+    #[prost(string, tag = "2")]
+    pub serving_data: String,
+}
+
+/// Describes the resource that is being accessed.
+#[derive(Clone, PartialEq, Message)]
+pub struct ResourceInfo {
+    /// A name for the type of resource being accessed.
+    #[prost(string, tag = "1")]
+    pub resource_type: String,
+    /// The name of the resource being accessed.
+    #[prost(string, tag = "2")]
+    pub resource_name: String,
+    /// The owner of the resource.
+    #[prost(string, tag = "3")]
+    pub owner: String,
+    /// Describes what error is encountered when accessing this resource.
+    #[prost(string, tag = "4")]
+    pub description: String,
+}
+
+/// Provides links to documentation or for performing an out of band action.
+#[derive(Clone, PartialEq, Message)]
+pub struct Help {
+    /// URL(s) pointing to additional information on handling the current
+    /// error.
+    #[prost(message, repeated, tag = "1")]
+    pub links: Vec<HelpLink>,
+}
+
+/// Describes a URL link.
+#[derive(Clone, PartialEq, Message)]
+pub struct HelpLink {
+    /// Describes what the link offers.
+    #[prost(string, tag = "1")]
+    pub description: String,
+    /// The URL of the link.
+    #[prost(string, tag = "2")]
+    pub url: String,
+}
+
+/// Provides a localized error message that is safe to return to the user.
+#[derive(Clone, PartialEq, Message)]
+pub struct LocalizedMessage {
+    /// The locale used following the specification defined at
+    /// <https://www.rfc-editor.org/rfc/bcp/bcp47.txt>.
+    #[prost(string, tag = "1")]
+    pub locale: String,
+    /// The localized error message in the above locale.
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
+/// The standard error details attached to a [`Status`](crate::Status)
+///
+/// # Example
+///
+/// ```
+/// use poem_grpc::{Code, ErrorDetails, Status};
+///
+/// let status = Status::new(Code::InvalidArgument)
+///     .with_message("invalid request")
+///     .with_details(
+///         ErrorDetails::new().with_bad_request_violation("name", "must not be empty"),
+///     );
+///
+/// let details = status.details().unwrap();
+/// assert_eq!(
+///     details.bad_request().unwrap().field_violations[0].field,
+///     "name"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorDetails {
+    retry_info: Option<RetryInfo>,
+    debug_info: Option<DebugInfo>,
+    quota_failure: Option<QuotaFailure>,
+    error_info: Option<ErrorInfo>,
+    precondition_failure: Option<PreconditionFailure>,
+    bad_request: Option<BadRequest>,
+    request_info: Option<RequestInfo>,
+    resource_info: Option<ResourceInfo>,
+    help: Option<Help>,
+    localized_message: Option<LocalizedMessage>,
+}
+
+impl ErrorDetails {
+    /// Create an empty `ErrorDetails`
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the delay before the clients can retry the request.
+    #[must_use]
+    pub fn with_retry_info(mut self, retry_delay: Duration) -> Self {
+        self.retry_info = Some(RetryInfo {
+            retry_delay: prost_types::Duration::try_from(retry_delay).ok(),
+        });
+        self
+    }
+
+    /// Sets the debugging info.
+    #[must_use]
+    pub fn with_debug_info(
+        mut self,
+        stack_entries: impl IntoIterator<Item = impl Into<String>>,
+        detail: impl Into<String>,
+    ) -> Self {
+        self.debug_info = Some(DebugInfo {
+            stack_entries: stack_entries.into_iter().map(Into::into).collect(),
+            detail: detail.into(),
+        });
+        self
+    }
+
+    /// Appends a quota violation.
+    #[must_use]
+    pub fn with_quota_failure_violation(
+        mut self,
+        subject: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.quota_failure
+            .get_or_insert_with(Default::default)
+            .violations
+            .push(QuotaViolation {
+                subject: subject.into(),
+                description: description.into(),
+            });
+        self
+    }
+
+    /// Sets the cause of the error.
+    #[must_use]
+    pub fn with_error_info(
+        mut self,
+        reason: impl Into<String>,
+        domain: impl Into<String>,
+        metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.error_info = Some(ErrorInfo {
+            reason: reason.into(),
+            domain: domain.into(),
+            metadata: metadata
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        });
+        self
+    }
+
+    /// Appends a precondition violation.
+    #[must_use]
+    pub fn with_precondition_failure_violation(
+        mut self,
+        ty: impl Into<String>,
+        subject: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.precondition_failure
+            .get_or_insert_with(Default::default)
+            .violations
+            .push(PreconditionViolation {
+                r#type: ty.into(),
+                subject: subject.into(),
+                description: description.into(),
+            });
+        self
+    }
+
+    /// Appends a violation of a field of the request.
+    #[must_use]
+    pub fn with_bad_request_violation(
+        mut self,
+        field: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.bad_request
+            .get_or_insert_with(Default::default)
+            .field_violations
+            .push(FieldViolation {
+                field: field.into(),
+                description: description.into(),
+            });
+        self
+    }
+
+    /// Sets the metadata about the request.
+    #[must_use]
+    pub fn with_request_info(
+        mut self,
+        request_id: impl Into<String>,
+        serving_data: impl Into<String>,
+    ) -> Self {
+        self.request_info = Some(RequestInfo {
+            request_id: request_id.into(),
+            serving_data: serving_data.into(),
+        });
+        self
+    }
+
+    /// Sets the resource that is being accessed.
+    #[must_use]
+    pub fn with_resource_info(
+        mut self,
+        resource_type: impl Into<String>,
+        resource_name: impl Into<String>,
+        owner: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.resource_info = Some(ResourceInfo {
+            resource_type: resource_type.into(),
+            resource_name: resource_name.into(),
+            owner: owner.into(),
+            description: description.into(),
+        });
+        self
+    }
+
+    /// Appends a help link.
+    #[must_use]
+    pub fn with_help_link(
+        mut self,
+        description: impl Into<String>,
+        url: impl Into<String>,
+    ) -> Self {
+        self.help
+            .get_or_insert_with(Default::default)
+            .links
+            .push(HelpLink {
+                description: description.into(),
+                url: url.into(),
+            });
+        self
+    }
+
+    /// Sets the localized error message.
+    #[must_use]
+    pub fn with_localized_message(
+        mut self,
+        locale: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        self.localized_message = Some(LocalizedMessage {
+            locale: locale.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Returns the retry info.
+    #[inline]
+    pub fn retry_info(&self) -> Option<&RetryInfo> {
+        self.retry_info.as_ref()
+    }
+
+    /// Returns the debugging info.
+    #[inline]
+    pub fn debug_info(&self) -> Option<&DebugInfo> {
+        self.debug_info.as_ref()
+    }
+
+    /// Returns the quota failure.
+    #[inline]
+    pub fn quota_failure(&self) -> Option<&QuotaFailure> {
+        self.quota_failure.as_ref()
+    }
+
+    /// Returns the cause of the error.
+    #[inline]
+    pub fn error_info(&self) -> Option<&ErrorInfo> {
+        self.error_info.as_ref()
+    }
+
+    /// Returns the precondition failure.
+    #[inline]
+    pub fn precondition_failure(&self) -> Option<&PreconditionFailure> {
+        self.precondition_failure.as_ref()
+    }
+
+    /// Returns the violations of the request fields.
+    #[inline]
+    pub fn bad_request(&self) -> Option<&BadRequest> {
+        self.bad_request.as_ref()
+    }
+
+    /// Returns the metadata about the request.
+    #[inline]
+    pub fn request_info(&self) -> Option<&RequestInfo> {
+        self.request_info.as_ref()
+    }
+
+    /// Returns the resource that is being accessed.
+    #[inline]
+    pub fn resource_info(&self) -> Option<&ResourceInfo> {
+        self.resource_info.as_ref()
+    }
+
+    /// Returns the help links.
+    #[inline]
+    pub fn help(&self) -> Option<&Help> {
+        self.help.as_ref()
+    }
+
+    /// Returns the localized error message.
+    #[inline]
+    pub fn localized_message(&self) -> Option<&LocalizedMessage> {
+        self.localized_message.as_ref()
+    }
+
+    fn to_any(&self) -> Vec<Any> {
+        let mut details = Vec::new();
+        push_any(&mut details, "google.rpc.RetryInfo", &self.retry_info);
+        push_any(&mut details, "google.rpc.DebugInfo", &self.debug_info);
+        push_any(&mut details, "google.rpc.QuotaFailure", &self.quota_failure);
+        push_any(&mut details, "google.rpc.ErrorInfo", &self.error_info);
+        push_any(
+            &mut details,
+            "google.rpc.PreconditionFailure",
+            &self.precondition_failure,
+        );
+        push_any(&mut details, "google.rpc.BadRequest", &self.bad_request);
+        push_any(&mut details, "google.rpc.RequestInfo", &self.request_info);
+        push_any(&mut details, "google.rpc.ResourceInfo", &self.resource_info);
+        push_any(&mut details, "google.rpc.Help", &self.help);
+        push_any(
+            &mut details,
+            "google.rpc.LocalizedMessage",
+            &self.localized_message,
+        );
+        details
+    }
+
+    fn from_any(details: &[Any]) -> Result<Self, prost::DecodeError> {
+        let mut error_details = ErrorDetails::new();
+
+        for any in details {
+            let value = any.value.as_slice();
+            match any.type_url.strip_prefix(TYPE_URL_PREFIX) {
+                Some("google.rpc.RetryInfo") => {
+                    error_details.retry_info = Some(RetryInfo::decode(value)?)
+                }
+                Some("google.rpc.DebugInfo") => {
+                    error_details.debug_info = Some(DebugInfo::decode(value)?)
+                }
+                Some("google.rpc.QuotaFailure") => {
+                    error_details.quota_failure = Some(QuotaFailure::decode(value)?)
+                }
+                Some("google.rpc.ErrorInfo") => {
+                    error_details.error_info = Some(ErrorInfo::decode(value)?)
+                }
+                Some("google.rpc.PreconditionFailure") => {
+                    error_details.precondition_failure = Some(PreconditionFailure::decode(value)?)
+                }
+                Some("google.rpc.BadRequest") => {
+                    error_details.bad_request = Some(BadRequest::decode(value)?)
+                }
+                Some("google.rpc.RequestInfo") => {
+                    error_details.request_info = Some(RequestInfo::decode(value)?)
+                }
+                Some("google.rpc.ResourceInfo") => {
+                    error_details.resource_info = Some(ResourceInfo::decode(value)?)
+                }
+                Some("google.rpc.Help") => error_details.help = Some(Help::decode(value)?),
+                Some("google.rpc.LocalizedMessage") => {
+                    error_details.localized_message = Some(LocalizedMessage::decode(value)?)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(error_details)
+    }
+}
+
+fn push_any<T: Message>(details: &mut Vec<Any>, name: &str, message: &Option<T>) {
+    if let Some(message) = message {
+        details.push(Any {
+            type_url: format!("{TYPE_URL_PREFIX}{name}"),
+            value: message.encode_to_vec(),
+        });
+    }
+}
+
+pub(crate) fn encode_status_details(code: Code, message: &str, details: &ErrorDetails) -> Vec<u8> {
+    RpcStatus {
+        code: code.as_u16() as i32,
+        message: message.to_string(),
+        details: details.to_any(),
+    }
+    .encode_to_vec()
+}
+
+pub(crate) fn decode_status_details(data: &[u8]) -> Result<ErrorDetails, prost::DecodeError> {
+    ErrorDetails::from_any(&RpcStatus::decode(data)?.details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Status;
+
+    #[test]
+    fn details_from_headers() {
+        let status = Status::new(Code::InvalidArgument)
+            .with_message("invalid request")
+            .with_details(
+                ErrorDetails::new()
+                    .with_bad_request_violation("name", "must not be empty")
+                    .with_bad_request_violation("age", "must be positive")
+                    .with_retry_info(Duration::from_secs(5))
+                    .with_error_info("INVALID", "example.com", [("key", "value")])
+                    .with_help_link("docs", "https://example.com"),
+            );
+
+        let headers = status.to_headers();
+        assert!(headers.contains_key("grpc-status-details-bin"));
+
+        let status = Status::from_headers(&headers).unwrap().unwrap();
+        let details = status.details().unwrap();
+        assert_eq!(
+            details.bad_request().unwrap().field_violations,
+            vec![
+                FieldViolation {
+                    field: "name".to_string(),
+                    description: "must not be empty".to_string(),
+                },
+                FieldViolation {
+                    field: "age".to_string(),
+                    description: "must be positive".to_string(),
+                }
+            ]
+        );
+        assert_eq!(
+            details.retry_info().unwrap().retry_delay,
+            Some(prost_types::Duration {
+                seconds: 5,
+                nanos: 0
+            })
+        );
+        assert_eq!(details.error_info().unwrap().metadata["key"], "value");
+        assert_eq!(details.help().unwrap().links[0].url, "https://example.com");
+        assert!(details.quota_failure().is_none());
+    }
+
+    #[test]
+    fn no_details() {
+        assert!(Status::new(Code::Internal).details().is_none());
+    }
+}
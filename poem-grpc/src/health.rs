@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use futures_util::StreamExt;
 use poem::{IntoEndpoint, endpoint::BoxEndpoint};
@@ -39,27 +42,45 @@ struct HealthService {
 }
 
 /// A handle providing methods to update the health status of GRPC services
+///
+/// The overall health of the server is reported under the empty service name
+/// and is [`ServingStatus::Serving`] until changed with
+/// [`HealthReporter::set_service_status`]. The handle can be cloned and
+/// shared between services.
+#[derive(Clone)]
 pub struct HealthReporter {
-    state: Mutex<(ServiceStatusMap, Sender<ServiceStatusMap>)>,
+    state: Arc<Mutex<(ServiceStatusMap, Sender<ServiceStatusMap>)>>,
 }
 
 impl HealthReporter {
-    fn set_status<S: Service>(&self, status: ServingStatus) {
+    fn new() -> (Self, Receiver<ServiceStatusMap>) {
+        let status = ServiceStatusMap::from([(String::new(), ServingStatus::Serving)]);
+        let (sender, receiver) = tokio::sync::watch::channel(status.clone());
+        let reporter = HealthReporter {
+            state: Arc::new(Mutex::new((status, sender))),
+        };
+        (reporter, receiver)
+    }
+
+    /// Sets the status of the service with the given name
+    ///
+    /// Use an empty name to set the overall health of the server.
+    pub fn set_service_status(&self, service_name: impl Into<String>, status: ServingStatus) {
         let mut state = self.state.lock().unwrap();
-        state.0.insert(S::NAME.to_string(), status);
+        state.0.insert(service_name.into(), status);
         let _ = state.1.send(state.0.clone());
     }
 
     /// Sets the status of the service implemented by `S` to
     /// [`ServingStatus::Serving`]
     pub fn set_serving<S: Service>(&self) {
-        self.set_status::<S>(ServingStatus::Serving);
+        self.set_service_status(S::NAME, ServingStatus::Serving);
     }
 
     /// Sets the status of the service implemented by `S` to
     /// [`ServingStatus::NotServing`]
     pub fn set_not_serving<S: Service>(&self) {
-        self.set_status::<S>(ServingStatus::NotServing);
+        self.set_service_status(S::NAME, ServingStatus::NotServing);
     }
 
     /// Clear the status of the given service.
@@ -92,13 +113,21 @@ impl proto::Health for HealthService {
         let mut stream = tokio_stream::wrappers::WatchStream::new(self.receiver.clone());
         let service_name = request.into_inner().service;
 
-        Ok(Response::new(Streaming::new(async_stream::try_stream! {
+        Ok(Response::new(Streaming::new(async_stream::stream! {
+            let mut last_status = None;
+
             while let Some(service_status) = stream.next().await {
-                let res = service_status.get(&service_name);
-                let status = res.ok_or_else(|| Status::new(Code::NotFound).with_message(format!("service `{service_name}` not found")))?
-                    .to_proto()
-                    .into();
-                yield proto::HealthCheckResponse { status };
+                // Unknown services are reported as `SERVICE_UNKNOWN` and the stream is
+                // kept open, so clients see the service once it gets registered.
+                let status = match service_status.get(&service_name) {
+                    Some(status) => status.to_proto(),
+                    None => proto::health_check_response::ServingStatus::ServiceUnknown,
+                };
+                if last_status == Some(status) {
+                    continue;
+                }
+                last_status = Some(status);
+                yield Ok(proto::HealthCheckResponse { status: status.into() });
             }
         })))
     }
@@ -109,13 +138,10 @@ pub fn health_service() -> (
     impl IntoEndpoint<Endpoint = BoxEndpoint<'static, poem::Response>> + Service,
     HealthReporter,
 ) {
-    let (sender, receiver) = tokio::sync::watch::channel(Default::default());
-
+    let (reporter, receiver) = HealthReporter::new();
     (
         proto::HealthServer::new(HealthService { receiver }),
-        HealthReporter {
-            state: Mutex::new((Default::default(), sender)),
-        },
+        reporter,
     )
 }
 
@@ -127,13 +153,8 @@ mod tests {
     use crate::health::proto::Health;
 
     fn create_service() -> (HealthService, HealthReporter) {
-        let (sender, receiver) = tokio::sync::watch::channel(Default::default());
-        (
-            HealthService { receiver },
-            HealthReporter {
-                state: Mutex::new((Default::default(), sender)),
-            },
-        )
+        let (reporter, receiver) = HealthReporter::new();
+        (HealthService { receiver }, reporter)
     }
 
     #[tokio::test]
@@ -182,6 +203,36 @@ mod tests {
         assert_eq!(res.unwrap_err().code(), Code::NotFound);
     }
 
+    #[tokio::test]
+    async fn overall_status() {
+        let (service, reporter) = create_service();
+
+        let res = service
+            .check(Request::new(proto::HealthCheckRequest {
+                service: String::new(),
+            }))
+            .await;
+        assert_eq!(
+            res.unwrap().into_inner(),
+            proto::HealthCheckResponse {
+                status: proto::health_check_response::ServingStatus::Serving.into()
+            }
+        );
+
+        reporter.set_service_status("", ServingStatus::NotServing);
+        let res = service
+            .check(Request::new(proto::HealthCheckRequest {
+                service: String::new(),
+            }))
+            .await;
+        assert_eq!(
+            res.unwrap().into_inner(),
+            proto::HealthCheckResponse {
+                status: proto::health_check_response::ServingStatus::NotServing.into()
+            }
+        );
+    }
+
     #[tokio::test]
     async fn watch() {
         let (service, reporter) = create_service();
@@ -193,17 +244,13 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(
-            stream.next().await.unwrap().unwrap_err().code(),
-            Code::NotFound
+            stream.next().await.unwrap().unwrap(),
+            proto::HealthCheckResponse {
+                status: proto::health_check_response::ServingStatus::ServiceUnknown.into()
+            }
         );
 
         reporter.set_serving::<proto::HealthServer<HealthService>>();
-        let mut stream = service
-            .watch(Request::new(proto::HealthCheckRequest {
-                service: <proto::HealthServer<HealthService>>::NAME.to_string(),
-            }))
-            .await
-            .unwrap();
         assert_eq!(
             stream.next().await.unwrap().unwrap(),
             proto::HealthCheckResponse {
@@ -211,6 +258,8 @@ mod tests {
             }
         );
 
+        // Changes of other services are not reported
+        reporter.set_service_status("other", ServingStatus::NotServing);
         reporter.set_not_serving::<proto::HealthServer<HealthService>>();
         assert_eq!(
             stream.next().await.unwrap().unwrap(),
@@ -221,8 +270,10 @@ mod tests {
 
         reporter.clear_service_status::<proto::HealthServer<HealthService>>();
         assert_eq!(
-            stream.next().await.unwrap().unwrap_err().code(),
-            Code::NotFound
+            stream.next().await.unwrap().unwrap(),
+            proto::HealthCheckResponse {
+                status: proto::health_check_response::ServingStatus::ServiceUnknown.into()
+            }
         );
     }
 }
@@ -18,10 +18,18 @@ pub mod server;
 pub mod service;
 
 pub mod codec;
+pub mod error_details;
 pub mod metadata;
+pub mod middleware;
+#[cfg(feature = "json-codec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json-codec")))]
+pub mod transcoding;
 
+mod balance;
 mod compression;
+mod connect;
 mod connector;
+mod deadline;
 mod encoding;
 #[cfg(feature = "example_generated")]
 pub mod example_generated;
@@ -34,15 +42,24 @@ mod status;
 mod streaming;
 #[cfg(test)]
 mod test_harness;
+mod web;
 
+pub use balance::LoadBalancingPolicy;
 pub use client::{ClientBuilderError, ClientConfig, ClientConfigBuilder};
 pub use compression::CompressionEncoding;
+pub use connect::{Connect, ConnectEndpoint};
+pub use error_details::ErrorDetails;
 pub use health::{HealthReporter, ServingStatus, health_service};
 pub use metadata::Metadata;
+pub use middleware::{GrpcCall, GrpcCallResponse, GrpcMiddleware, GrpcNext};
 pub use reflection::Reflection;
 pub use request::Request;
 pub use response::Response;
 pub use route::RouteGrpc;
 pub use service::Service;
 pub use status::{Code, Status};
-pub use streaming::Streaming;
+pub use streaming::{Streaming, StreamingSender};
+#[cfg(feature = "json-codec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json-codec")))]
+pub use transcoding::RouteTranscoding;
+pub use web::{GrpcWeb, GrpcWebEndpoint};
@@ -0,0 +1,391 @@
+//! GRPC middleware
+
+use std::{future::Future, sync::Arc};
+
+use http::Extensions;
+use http_body_util::{BodyExt, StreamBody};
+use poem::{
+    Endpoint, EndpointExt, IntoResponse, Middleware, Request, Response, Result,
+    endpoint::{BoxEndpoint, ShutdownHooks},
+    web::RemoteAddr,
+};
+use sync_wrapper::SyncStream;
+
+use crate::{Code, Metadata, Status, client::BoxBody};
+
+/// Represents a GRPC middleware
+///
+/// Unlike a poem middleware, a `GrpcMiddleware` is only called for GRPC
+/// requests, and can short-circuit the call by returning a [`Status`], which
+/// is sent to the client as the status of the call.
+///
+/// Use [`GrpcMiddleware::into_middleware`] to apply it to a [`RouteGrpc`] like
+/// any other poem middleware. When combined with [`GrpcWeb`] or [`Connect`],
+/// apply them after it, so the requests are translated before reaching it.
+///
+/// # Example
+///
+/// ```
+/// use poem::EndpointExt;
+/// use poem_grpc::{
+///     Code, GrpcCall, GrpcCallResponse, GrpcMiddleware, GrpcNext, RouteGrpc, Status,
+/// };
+///
+/// struct Auth;
+///
+/// impl GrpcMiddleware for Auth {
+///     async fn call(&self, call: GrpcCall, next: GrpcNext<'_>) -> Result<GrpcCallResponse, Status> {
+///         match call.metadata().get("authorization") {
+///             Some("Bearer secret") => Ok(next.run(call).await),
+///             _ => Err(Status::new(Code::Unauthenticated)),
+///         }
+///     }
+/// }
+///
+/// let route = RouteGrpc::new().with(Auth.into_middleware());
+/// ```
+///
+/// [`RouteGrpc`]: crate::RouteGrpc
+/// [`GrpcWeb`]: crate::GrpcWeb
+/// [`Connect`]: crate::Connect
+pub trait GrpcMiddleware: Send + Sync + 'static {
+    /// Process the call, `next` calls the inner service.
+    fn call(
+        &self,
+        call: GrpcCall,
+        next: GrpcNext<'_>,
+    ) -> impl Future<Output = Result<GrpcCallResponse, Status>> + Send;
+
+    /// Converts this middleware into a poem [`Middleware`].
+    fn into_middleware(self) -> GrpcMiddlewareAdapter<Self>
+    where
+        Self: Sized,
+    {
+        GrpcMiddlewareAdapter {
+            middleware: Arc::new(self),
+        }
+    }
+}
+
+/// A GRPC call processed by a [`GrpcMiddleware`]
+pub struct GrpcCall {
+    metadata: Metadata,
+    request: Request,
+}
+
+impl GrpcCall {
+    /// Returns the path of the call, for example
+    /// `/helloworld.Greeter/SayHello`.
+    #[inline]
+    pub fn path(&self) -> &str {
+        self.request.uri().path()
+    }
+
+    /// Returns the name of the GRPC service, for example
+    /// `helloworld.Greeter`.
+    pub fn service(&self) -> &str {
+        self.path().rsplit('/').nth(1).unwrap_or_default()
+    }
+
+    /// Returns the name of the method, for example `SayHello`.
+    pub fn method(&self) -> &str {
+        self.path().rsplit('/').next().unwrap_or_default()
+    }
+
+    /// Returns a reference to the metadata.
+    #[inline]
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the metadata.
+    #[inline]
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Returns a reference to the associated extensions, they are available
+    /// to the services with [`Request::extensions`](crate::Request::extensions).
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        self.request.extensions()
+    }
+
+    /// Returns a mutable reference to the associated extensions.
+    #[inline]
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        self.request.extensions_mut()
+    }
+
+    /// Get a reference from extensions, similar to `self.extensions().get()`.
+    #[inline]
+    pub fn data<D: Send + Sync + 'static>(&self) -> Option<&D> {
+        self.request.data()
+    }
+
+    /// Inserts a value to extensions, similar to
+    /// `self.extensions().insert(data)`.
+    #[inline]
+    pub fn set_data(&mut self, data: impl Send + Sync + Clone + 'static) {
+        self.request.set_data(data);
+    }
+
+    /// Returns the remote address of the call.
+    #[inline]
+    pub fn remote_addr(&self) -> &RemoteAddr {
+        self.request.remote_addr()
+    }
+}
+
+/// The inner service of a [`GrpcMiddleware`]
+pub struct GrpcNext<'a> {
+    inner: &'a BoxEndpoint<'static, Response>,
+}
+
+impl GrpcNext<'_> {
+    /// Calls the inner service.
+    pub async fn run(self, call: GrpcCall) -> GrpcCallResponse {
+        let GrpcCall {
+            metadata,
+            mut request,
+        } = call;
+        *request.headers_mut() = metadata.headers;
+
+        let resp = match self.inner.call(request).await {
+            Ok(resp) => resp,
+            Err(err) => err.into_response(),
+        };
+        let (mut parts, body) = resp.into_parts();
+        GrpcCallResponse {
+            metadata: Metadata {
+                headers: std::mem::take(&mut parts.headers),
+            },
+            response: Response::from_parts(parts, body),
+        }
+    }
+}
+
+/// The response of a GRPC call processed by a [`GrpcMiddleware`]
+pub struct GrpcCallResponse {
+    metadata: Metadata,
+    response: Response,
+}
+
+impl GrpcCallResponse {
+    /// Returns a reference to the metadata.
+    #[inline]
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns a mutable reference to the metadata.
+    #[inline]
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    /// Calls `f` with the final status of the call.
+    ///
+    /// For streaming calls, `f` is called after the last message has been
+    /// sent, it is not called if the client cancels the call.
+    pub fn on_complete(mut self, f: impl FnOnce(&Status) + Send + 'static) -> Self {
+        if let Some(status) = Status::from_headers(&self.metadata.headers).unwrap_or_else(Some) {
+            f(&status);
+            return self;
+        }
+
+        let mut body: BoxBody = self.response.take_body().into();
+        let stream = async_stream::stream! {
+            let mut f = Some(f);
+
+            while let Some(frame) = body.frame().await {
+                match &frame {
+                    Ok(frame) => {
+                        if let Some(trailers) = frame.trailers_ref() {
+                            let status = Status::from_headers(trailers)
+                                .unwrap_or_else(Some)
+                                .unwrap_or_else(|| Status::new(Code::Internal).with_message("missing grpc-status"));
+                            if let Some(f) = f.take() {
+                                f(&status);
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        if let Some(f) = f.take() {
+                            f(&Status::new(Code::Internal).with_message(err));
+                        }
+                    }
+                }
+                yield frame;
+            }
+
+            if let Some(f) = f.take() {
+                f(&Status::new(Code::Internal).with_message("missing trailers"));
+            }
+        };
+        self.response
+            .set_body(BodyExt::boxed(StreamBody::new(SyncStream::new(stream))));
+        self
+    }
+}
+
+impl IntoResponse for GrpcCallResponse {
+    fn into_response(self) -> Response {
+        let mut resp = self.response;
+        *resp.headers_mut() = self.metadata.headers;
+        resp
+    }
+}
+
+/// A poem [`Middleware`] that applies a [`GrpcMiddleware`]
+pub struct GrpcMiddlewareAdapter<M> {
+    middleware: Arc<M>,
+}
+
+impl<M: GrpcMiddleware, E: Endpoint + 'static> Middleware<E> for GrpcMiddlewareAdapter<M> {
+    type Output = GrpcMiddlewareEndpoint<M>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        GrpcMiddlewareEndpoint {
+            middleware: self.middleware.clone(),
+            inner: ep.map_to_response().boxed(),
+        }
+    }
+}
+
+/// Endpoint for the [`GrpcMiddlewareAdapter`] middleware
+pub struct GrpcMiddlewareEndpoint<M> {
+    middleware: Arc<M>,
+    inner: BoxEndpoint<'static, Response>,
+}
+
+impl<M: GrpcMiddleware> Endpoint for GrpcMiddlewareEndpoint<M> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        if !req
+            .content_type()
+            .is_some_and(|content_type| content_type.starts_with("application/grpc"))
+        {
+            return self.inner.call(req).await;
+        }
+
+        let call = GrpcCall {
+            metadata: Metadata {
+                headers: std::mem::take(req.headers_mut()),
+            },
+            request: req,
+        };
+        let next = GrpcNext { inner: &self.inner };
+        match self.middleware.call(call, next).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(status) => {
+                let mut resp = Response::default().set_content_type("application/grpc");
+                resp.headers_mut().extend(status.metadata().headers.clone());
+                resp.headers_mut().extend(status.to_headers());
+                Ok(resp)
+            }
+        }
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures_util::StreamExt;
+    use poem::EndpointExt;
+
+    use super::*;
+    use crate::{
+        Request as GrpcRequest, RouteGrpc,
+        test_harness::{
+            TestHarnessService,
+            proto::{
+                TestHarnessClient, TestHarnessServer, UnaryRequest, ValueRequest, ValueResponse,
+            },
+        },
+    };
+
+    struct Auth;
+
+    impl GrpcMiddleware for Auth {
+        async fn call(
+            &self,
+            mut call: GrpcCall,
+            next: GrpcNext<'_>,
+        ) -> Result<GrpcCallResponse, Status> {
+            if call.metadata().get("token") != Some("123") {
+                return Err(Status::new(Code::Unauthenticated).with_message("invalid token"));
+            }
+            call.metadata_mut().insert("mydata", "abc");
+            let mut resp = next.run(call).await;
+            resp.metadata_mut().insert("checked", "1");
+            Ok(resp)
+        }
+    }
+
+    struct Audit(Arc<Mutex<Vec<String>>>);
+
+    impl GrpcMiddleware for Audit {
+        async fn call(
+            &self,
+            call: GrpcCall,
+            next: GrpcNext<'_>,
+        ) -> Result<GrpcCallResponse, Status> {
+            let log = self.0.clone();
+            let name = format!("{}/{}", call.service(), call.method());
+            Ok(next.run(call).await.on_complete(move |status| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("{name} {}", status.code().as_u16()));
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn metadata() {
+        let route = RouteGrpc::new()
+            .add_service(TestHarnessServer::new(TestHarnessService))
+            .with(Auth.into_middleware());
+        let cli = TestHarnessClient::from_endpoint(route);
+
+        let status = cli
+            .unary_metadata(GrpcRequest::new(UnaryRequest { a: 1, b: 2 }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert_eq!(status.message(), Some("invalid token"));
+
+        let mut req = GrpcRequest::new(UnaryRequest { a: 1, b: 2 });
+        req.metadata_mut().insert("token", "123");
+        let resp = cli.unary_metadata(req).await.unwrap();
+        assert_eq!(resp.metadata().get("mydata"), Some("abc"));
+        assert_eq!(resp.metadata().get("checked"), Some("1"));
+        assert_eq!(resp.into_inner(), ValueResponse { value: 3 });
+    }
+
+    #[tokio::test]
+    async fn on_complete() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let route = RouteGrpc::new()
+            .add_service(TestHarnessServer::new(TestHarnessService))
+            .with(Audit(log.clone()).into_middleware());
+        let cli = TestHarnessClient::from_endpoint(route);
+
+        let resp = cli
+            .server_streaming(GrpcRequest::new(ValueRequest { value: 2 }))
+            .await
+            .unwrap();
+        assert!(log.lock().unwrap().is_empty());
+        assert_eq!(resp.into_inner().count().await, 3);
+        assert_eq!(
+            log.lock().unwrap().as_slice(),
+            ["test_harness.TestHarness/ServerStreaming 0"]
+        );
+    }
+}
@@ -4,9 +4,6 @@ use futures_util::StreamExt;
 use poem::{IntoEndpoint, endpoint::BoxEndpoint};
 use prost::Message;
 use prost_types::{DescriptorProto, EnumDescriptorProto, FileDescriptorProto, FileDescriptorSet};
-use proto::{
-    server_reflection_request::MessageRequest, server_reflection_response::MessageResponse,
-};
 
 use crate::{Code, Request, Response, Service, Status, Streaming};
 
@@ -17,101 +14,128 @@ mod proto {
     include!(concat!(env!("OUT_DIR"), "/grpc.reflection.v1alpha.rs"));
 }
 
+#[allow(unreachable_pub)]
+#[allow(clippy::enum_variant_names)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+mod proto_v1 {
+    include!(concat!(env!("OUT_DIR"), "/grpc.reflection.v1.rs"));
+}
+
 pub(crate) const FILE_DESCRIPTOR_SET: &[u8] = include_file_descriptor_set!("grpc-reflection.bin");
 
+/// A reply of the reflection service, independent of the protocol version.
+enum Reply {
+    FileDescriptors(Vec<Vec<u8>>),
+    Services(Vec<String>),
+}
+
 struct State {
-    service_names: Vec<proto::ServiceResponse>,
+    service_names: Vec<String>,
     files: HashMap<String, Arc<FileDescriptorProto>>,
     symbols: HashMap<String, Arc<FileDescriptorProto>>,
 }
 
 impl State {
     #[allow(clippy::result_large_err)]
-    fn file_by_filename(&self, filename: &str) -> Result<MessageResponse, Status> {
+    fn file_by_filename(&self, filename: &str) -> Result<Reply, Status> {
         match self.files.get(filename) {
             None => {
                 Err(Status::new(Code::NotFound)
                     .with_message(format!("file '{filename}' not found")))
             }
-            Some(fd) => {
-                let mut encoded_fd = Vec::new();
-                if fd.clone().encode(&mut encoded_fd).is_err() {
-                    return Err(Status::new(Code::Internal).with_message("encoding error"));
-                }
-
-                Ok(MessageResponse::FileDescriptorResponse(
-                    proto::FileDescriptorResponse {
-                        file_descriptor_proto: vec![encoded_fd],
-                    },
-                ))
-            }
+            Some(fd) => encode_file_descriptor(fd),
         }
     }
 
     #[allow(clippy::result_large_err)]
-    fn symbol_by_name(&self, symbol: &str) -> Result<MessageResponse, Status> {
+    fn symbol_by_name(&self, symbol: &str) -> Result<Reply, Status> {
         match self.symbols.get(symbol) {
             None => {
                 Err(Status::new(Code::NotFound)
                     .with_message(format!("symbol '{symbol}' not found")))
             }
-            Some(fd) => {
-                let mut encoded_fd = Vec::new();
-                if fd.clone().encode(&mut encoded_fd).is_err() {
-                    return Err(Status::new(Code::Internal).with_message("encoding error"));
-                };
-
-                Ok(MessageResponse::FileDescriptorResponse(
-                    proto::FileDescriptorResponse {
-                        file_descriptor_proto: vec![encoded_fd],
-                    },
-                ))
-            }
+            Some(fd) => encode_file_descriptor(fd),
         }
     }
 
-    fn list_services(&self) -> MessageResponse {
-        MessageResponse::ListServicesResponse(proto::ListServiceResponse {
-            service: self.service_names.clone(),
-        })
+    fn list_services(&self) -> Reply {
+        Reply::Services(self.service_names.clone())
     }
 }
 
-/// A service that serve for reflection
-struct ServerReflectionService {
-    state: Arc<State>,
+#[allow(clippy::result_large_err)]
+fn encode_file_descriptor(fd: &FileDescriptorProto) -> Result<Reply, Status> {
+    let mut encoded_fd = Vec::new();
+    if fd.encode(&mut encoded_fd).is_err() {
+        return Err(Status::new(Code::Internal).with_message("encoding error"));
+    }
+    Ok(Reply::FileDescriptors(vec![encoded_fd]))
 }
 
-impl proto::ServerReflection for ServerReflectionService {
-    async fn server_reflection_info(
-        &self,
-        request: Request<Streaming<proto::ServerReflectionRequest>>,
-    ) -> Result<Response<Streaming<proto::ServerReflectionResponse>>, Status> {
-        let mut request_stream = request.into_inner();
-        let state = self.state.clone();
-
-        Ok(Response::new(Streaming::new(async_stream::try_stream! {
-            while let Some(req) = request_stream.next().await.transpose()? {
-                let resp = match &req.message_request {
-                    Some(MessageRequest::FileByFilename(filename)) => state.file_by_filename(filename),
-                    Some(MessageRequest::FileContainingSymbol(symbol)) => state.symbol_by_name(symbol),
-                    Some(MessageRequest::FileContainingExtension(_) | MessageRequest::AllExtensionNumbersOfType(_)) => Err(Status::new(Code::Unimplemented)),
-                    Some(MessageRequest::ListServices(_)) => Ok(state.list_services()),
-                    None => Err(Status::new(Code::InvalidArgument)),
-                }?;
-
-                yield proto::ServerReflectionResponse {
-                    valid_host: req.host.clone(),
-                    original_request: Some(req.clone()),
-                    message_response: Some(resp),
+/// Implements the reflection service of a version of the protocol, the
+/// messages of the versions are the same.
+macro_rules! impl_reflection_service {
+    ($service:ident, $proto:ident) => {
+        /// A service that serve for reflection
+        struct $service {
+            state: Arc<State>,
+        }
+
+        impl $proto::ServerReflection for $service {
+            async fn server_reflection_info(
+                &self,
+                request: Request<Streaming<$proto::ServerReflectionRequest>>,
+            ) -> Result<Response<Streaming<$proto::ServerReflectionResponse>>, Status> {
+                use $proto::{
+                    server_reflection_request::MessageRequest,
+                    server_reflection_response::MessageResponse,
                 };
+
+                let mut request_stream = request.into_inner();
+                let state = self.state.clone();
+
+                Ok(Response::new(Streaming::new(async_stream::try_stream! {
+                    while let Some(req) = request_stream.next().await.transpose()? {
+                        let reply = match &req.message_request {
+                            Some(MessageRequest::FileByFilename(filename)) => state.file_by_filename(filename),
+                            Some(MessageRequest::FileContainingSymbol(symbol)) => state.symbol_by_name(symbol),
+                            Some(MessageRequest::FileContainingExtension(_) | MessageRequest::AllExtensionNumbersOfType(_)) => Err(Status::new(Code::Unimplemented)),
+                            Some(MessageRequest::ListServices(_)) => Ok(state.list_services()),
+                            None => Err(Status::new(Code::InvalidArgument)),
+                        }?;
+                        let resp = match reply {
+                            Reply::FileDescriptors(file_descriptor_proto) => {
+                                MessageResponse::FileDescriptorResponse($proto::FileDescriptorResponse {
+                                    file_descriptor_proto,
+                                })
+                            }
+                            Reply::Services(names) => {
+                                MessageResponse::ListServicesResponse($proto::ListServiceResponse {
+                                    service: names
+                                        .into_iter()
+                                        .map(|name| $proto::ServiceResponse { name })
+                                        .collect(),
+                                })
+                            }
+                        };
+
+                        yield $proto::ServerReflectionResponse {
+                            valid_host: req.host.clone(),
+                            original_request: Some(req.clone()),
+                            message_response: Some(resp),
+                        };
+                    }
+                })))
             }
-        })))
-    }
+        }
+    };
 }
 
+impl_reflection_service!(ServerReflectionService, proto);
+impl_reflection_service!(ServerReflectionServiceV1, proto_v1);
+
 /// A builder for creating reflection service
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Reflection {
     file_descriptor_sets: Vec<FileDescriptorSet>,
     service_names: Vec<String>,
@@ -131,10 +155,35 @@ impl Reflection {
         self
     }
 
-    /// Build a reflection service
+    /// Build a reflection service for the `grpc.reflection.v1alpha` protocol
     pub fn build(
         self,
     ) -> impl IntoEndpoint<Endpoint = BoxEndpoint<'static, poem::Response>> + Service {
+        proto::ServerReflectionServer::new(ServerReflectionService {
+            state: Arc::new(self.into_state()),
+        })
+    }
+
+    /// Build a reflection service for the `grpc.reflection.v1` protocol
+    ///
+    /// Newer clients such as `grpcurl` try this version first, clone the
+    /// builder to serve both versions:
+    ///
+    /// ```ignore
+    /// let reflection = Reflection::new().add_file_descriptor_set(FILE_DESCRIPTOR_SET);
+    /// let route = RouteGrpc::new()
+    ///     .add_service(reflection.clone().build())
+    ///     .add_service(reflection.build_v1());
+    /// ```
+    pub fn build_v1(
+        self,
+    ) -> impl IntoEndpoint<Endpoint = BoxEndpoint<'static, poem::Response>> + Service {
+        proto_v1::ServerReflectionServer::new(ServerReflectionServiceV1 {
+            state: Arc::new(self.into_state()),
+        })
+    }
+
+    fn into_state(self) -> State {
         let mut this = self.add_file_descriptor_set(FILE_DESCRIPTOR_SET);
 
         let fd_iter = std::mem::take(&mut this.file_descriptor_sets)
@@ -175,17 +224,11 @@ impl Reflection {
             }
         }
 
-        proto::ServerReflectionServer::new(ServerReflectionService {
-            state: Arc::new(State {
-                service_names: this
-                    .service_names
-                    .into_iter()
-                    .map(|name| proto::ServiceResponse { name })
-                    .collect(),
-                files,
-                symbols: this.symbols,
-            }),
-        })
+        State {
+            service_names: this.service_names,
+            files,
+            symbols: this.symbols,
+        }
     }
 
     fn process_message(
@@ -239,3 +282,47 @@ fn qualified_name(prefix: &str, ty: &str, name: Option<&str>) -> String {
         None => panic!("missing {ty} name"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_services() {
+        let state = Reflection::new().into_state();
+        let Reply::Services(names) = state.list_services() else {
+            panic!("expected services");
+        };
+        assert!(
+            names
+                .iter()
+                .any(|name| name == "grpc.reflection.v1alpha.ServerReflection")
+        );
+        assert!(
+            names
+                .iter()
+                .any(|name| name == "grpc.reflection.v1.ServerReflection")
+        );
+    }
+
+    #[test]
+    fn file_containing_symbol() {
+        let state = Reflection::new().into_state();
+        let Ok(Reply::FileDescriptors(files)) =
+            state.symbol_by_name("grpc.reflection.v1.ServerReflection")
+        else {
+            panic!("expected file descriptors");
+        };
+        let fd = FileDescriptorProto::decode(files[0].as_slice()).unwrap();
+        assert_eq!(fd.package.as_deref(), Some("grpc.reflection.v1"));
+
+        assert_eq!(
+            state
+                .symbol_by_name("grpc.reflection.v1.Unknown")
+                .err()
+                .unwrap()
+                .code(),
+            Code::NotFound
+        );
+    }
+}
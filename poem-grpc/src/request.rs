@@ -1,12 +1,17 @@
 use std::{
     fmt::Debug,
     ops::{Deref, DerefMut},
+    time::{Duration, Instant},
 };
 
 use futures_util::Stream;
-use http::Extensions;
+use http::{Extensions, HeaderValue};
+use tokio_util::sync::CancellationToken;
 
-use crate::{Metadata, Status, Streaming};
+use crate::{
+    Metadata, Status, Streaming,
+    deadline::{CallContext, GRPC_TIMEOUT_HEADER, format_grpc_timeout},
+};
 
 /// A GRPC request
 pub struct Request<T> {
@@ -93,6 +98,38 @@ impl<T> Request<T> {
     pub fn set_data(&mut self, data: impl Send + Sync + Clone + 'static) {
         self.extensions.insert(data);
     }
+
+    /// Sets the timeout of the call, which is sent to the server with the
+    /// `grpc-timeout` metadata.
+    ///
+    /// The call fails with [`Code::DeadlineExceeded`](crate::Code) if it does
+    /// not complete in time.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.metadata.headers.insert(
+            GRPC_TIMEOUT_HEADER,
+            HeaderValue::from_str(&format_grpc_timeout(timeout)).expect("valid timeout"),
+        );
+    }
+
+    /// Returns the deadline of the call specified by the client, if any.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.extensions
+            .get::<CallContext>()
+            .and_then(|ctx| ctx.deadline)
+            .map(|deadline| deadline.into_std())
+    }
+
+    /// Returns a token that is cancelled when the deadline of the call is
+    /// exceeded or the client cancels the call.
+    ///
+    /// This can be used to stop work spawned by the service, the service
+    /// future itself is dropped in these cases.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.extensions
+            .get::<CallContext>()
+            .map(|ctx| ctx.token.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl<T> Request<Streaming<T>> {
@@ -6,7 +6,11 @@ use crate::{
     Code, CompressionEncoding, Metadata, Request as GrpcRequest, Response as GrpcResponse, Status,
     Streaming,
     codec::Codec,
-    compression::get_incoming_encodings,
+    compression::{
+        GRPC_ACCEPT_ENCODING_HEADER, GRPC_ENCODING_HEADER, accept_encoding_header,
+        get_incoming_encodings, negotiate_send_encoding,
+    },
+    deadline::CallContext,
     encoding::{create_decode_request_body, create_encode_response_body},
     service::{
         BidirectionalStreamingService, ClientStreamingService, ServerStreamingService, UnaryService,
@@ -18,6 +22,7 @@ pub struct GrpcServer<'a, T> {
     codec: T,
     send_compressed: Option<CompressionEncoding>,
     accept_compressed: &'a [CompressionEncoding],
+    compression_min_size: usize,
 }
 
 impl<'a, T: Codec> GrpcServer<'a, T> {
@@ -31,6 +36,15 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
             codec,
             send_compressed,
             accept_compressed,
+            compression_min_size: 0,
+        }
+    }
+
+    #[inline]
+    pub fn compression_min_size(self, size: usize) -> Self {
+        Self {
+            compression_min_size: size,
+            ..self
         }
     }
 
@@ -38,7 +52,11 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
     where
         S: UnaryService<T::Decode, Response = T::Encode>,
     {
-        let (parts, body) = request.into_parts();
+        let (mut parts, body) = request.into_parts();
+        let ctx = CallContext::new(&parts.headers);
+        parts.extensions.insert(ctx.clone());
+        let guard = ctx.guard();
+        let send_compressed = negotiate_send_encoding(&parts.headers, self.send_compressed);
         let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
         let incoming_encoding = match get_incoming_encodings(&parts.headers, self.accept_compressed)
         {
@@ -50,31 +68,45 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
         };
         let mut stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
 
-        let res = match stream.next().await {
-            Some(Ok(message)) => {
-                service
-                    .call(GrpcRequest {
-                        metadata: Metadata {
-                            headers: parts.headers,
-                        },
-                        message,
-                        extensions: parts.extensions,
-                    })
-                    .await
-            }
-            Some(Err(status)) => Err(status),
-            None => Err(Status::new(Code::Internal).with_message("missing request message")),
-        };
+        let res = ctx
+            .run(async move {
+                match stream.next().await {
+                    Some(Ok(message)) => {
+                        service
+                            .call(GrpcRequest {
+                                metadata: Metadata {
+                                    headers: parts.headers,
+                                },
+                                message,
+                                extensions: parts.extensions,
+                            })
+                            .await
+                    }
+                    Some(Err(status)) => Err(status),
+                    None => {
+                        Err(Status::new(Code::Internal).with_message("missing request message"))
+                    }
+                }
+            })
+            .await;
 
+        guard.disarm();
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
                 let body = create_encode_response_body(
                     self.codec.encoder(),
                     Streaming::new(futures_util::stream::once(async move { Ok(message) })),
-                    self.send_compressed,
+                    send_compressed,
+                    self.compression_min_size,
+                );
+                update_http_response(
+                    &mut resp,
+                    metadata,
+                    body,
+                    send_compressed,
+                    self.accept_compressed,
                 );
-                update_http_response(&mut resp, metadata, body, self.send_compressed);
             }
             Err(status) => resp.headers_mut().extend(status.to_headers()),
         }
@@ -86,7 +118,11 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
     where
         S: ClientStreamingService<T::Decode, Response = T::Encode>,
     {
-        let (parts, body) = request.into_parts();
+        let (mut parts, body) = request.into_parts();
+        let ctx = CallContext::new(&parts.headers);
+        parts.extensions.insert(ctx.clone());
+        let guard = ctx.guard();
+        let send_compressed = negotiate_send_encoding(&parts.headers, self.send_compressed);
         let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
         let incoming_encoding = match get_incoming_encodings(&parts.headers, self.accept_compressed)
         {
@@ -98,25 +134,33 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
         };
         let stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
 
-        let res = service
-            .call(GrpcRequest {
+        let res = ctx
+            .run(service.call(GrpcRequest {
                 metadata: Metadata {
                     headers: parts.headers,
                 },
                 extensions: parts.extensions,
                 message: stream,
-            })
+            }))
             .await;
 
+        guard.disarm();
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
                 let body = create_encode_response_body(
                     self.codec.encoder(),
                     Streaming::new(futures_util::stream::once(async move { Ok(message) })),
-                    self.send_compressed,
+                    send_compressed,
+                    self.compression_min_size,
+                );
+                update_http_response(
+                    &mut resp,
+                    metadata,
+                    body,
+                    send_compressed,
+                    self.accept_compressed,
                 );
-                update_http_response(&mut resp, metadata, body, self.send_compressed);
             }
             Err(status) => {
                 resp.headers_mut().extend(status.to_headers());
@@ -130,7 +174,11 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
     where
         S: ServerStreamingService<T::Decode, Response = T::Encode>,
     {
-        let (parts, body) = request.into_parts();
+        let (mut parts, body) = request.into_parts();
+        let ctx = CallContext::new(&parts.headers);
+        parts.extensions.insert(ctx.clone());
+        let guard = ctx.guard();
+        let send_compressed = negotiate_send_encoding(&parts.headers, self.send_compressed);
         let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
         let incoming_encoding = match get_incoming_encodings(&parts.headers, self.accept_compressed)
         {
@@ -142,33 +190,47 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
         };
         let mut stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
 
-        let res = match stream.next().await {
-            Some(Ok(message)) => {
-                service
-                    .call(GrpcRequest {
-                        metadata: Metadata {
-                            headers: parts.headers,
-                        },
-                        message,
-                        extensions: parts.extensions,
-                    })
-                    .await
-            }
-            Some(Err(status)) => Err(status),
-            None => Err(Status::new(Code::Internal).with_message("missing request message")),
-        };
+        let res = ctx
+            .run(async move {
+                match stream.next().await {
+                    Some(Ok(message)) => {
+                        service
+                            .call(GrpcRequest {
+                                metadata: Metadata {
+                                    headers: parts.headers,
+                                },
+                                message,
+                                extensions: parts.extensions,
+                            })
+                            .await
+                    }
+                    Some(Err(status)) => Err(status),
+                    None => {
+                        Err(Status::new(Code::Internal).with_message("missing request message"))
+                    }
+                }
+            })
+            .await;
 
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
                 let body = create_encode_response_body(
                     self.codec.encoder(),
-                    message,
-                    self.send_compressed,
+                    ctx.wrap_stream(message, Some(guard)),
+                    send_compressed,
+                    self.compression_min_size,
+                );
+                update_http_response(
+                    &mut resp,
+                    metadata,
+                    body,
+                    send_compressed,
+                    self.accept_compressed,
                 );
-                update_http_response(&mut resp, metadata, body, self.send_compressed);
             }
             Err(status) => {
+                guard.disarm();
                 resp.headers_mut().extend(status.to_headers());
             }
         }
@@ -180,7 +242,11 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
     where
         S: BidirectionalStreamingService<T::Decode, Response = T::Encode>,
     {
-        let (parts, body) = request.into_parts();
+        let (mut parts, body) = request.into_parts();
+        let ctx = CallContext::new(&parts.headers);
+        parts.extensions.insert(ctx.clone());
+        let guard = ctx.guard();
+        let send_compressed = negotiate_send_encoding(&parts.headers, self.send_compressed);
         let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
         let incoming_encoding = match get_incoming_encodings(&parts.headers, self.accept_compressed)
         {
@@ -192,14 +258,14 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
         };
         let stream = create_decode_request_body(self.codec.decoder(), body, incoming_encoding);
 
-        let res = service
-            .call(GrpcRequest {
+        let res = ctx
+            .run(service.call(GrpcRequest {
                 metadata: Metadata {
                     headers: parts.headers,
                 },
                 message: stream,
                 extensions: parts.extensions,
-            })
+            }))
             .await;
 
         match res {
@@ -207,12 +273,20 @@ impl<'a, T: Codec> GrpcServer<'a, T> {
                 let GrpcResponse { metadata, message } = grpc_resp;
                 let body = create_encode_response_body(
                     self.codec.encoder(),
-                    message,
-                    self.send_compressed,
+                    ctx.wrap_stream(message, Some(guard)),
+                    send_compressed,
+                    self.compression_min_size,
+                );
+                update_http_response(
+                    &mut resp,
+                    metadata,
+                    body,
+                    send_compressed,
+                    self.accept_compressed,
                 );
-                update_http_response(&mut resp, metadata, body, self.send_compressed);
             }
             Err(status) => {
+                guard.disarm();
                 resp.headers_mut().extend(status.to_headers());
             }
         }
@@ -226,13 +300,18 @@ fn update_http_response(
     metadata: Metadata,
     body: Body,
     send_compressed: Option<CompressionEncoding>,
+    accept_compressed: &[CompressionEncoding],
 ) {
     resp.headers_mut().extend(metadata.headers);
     if let Some(send_compressed) = send_compressed {
         resp.headers_mut().insert(
-            "grpc-encoding",
+            GRPC_ENCODING_HEADER,
             HeaderValue::from_str(send_compressed.as_str()).expect("BUG: invalid encoding"),
         );
     }
+    if let Some(accept_encoding) = accept_encoding_header(accept_compressed) {
+        resp.headers_mut()
+            .insert(GRPC_ACCEPT_ENCODING_HEADER, accept_encoding);
+    }
     resp.set_body(body);
 }
@@ -1,12 +1,26 @@
 use std::fmt::Display;
 
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, Engine, GeneralPurpose, GeneralPurposeConfig},
+};
 use http::{HeaderMap, header::HeaderValue};
 use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, percent_encode};
 
-use crate::Metadata;
+use crate::{
+    ErrorDetails, Metadata,
+    error_details::{decode_status_details, encode_status_details},
+};
 
 const GRPC_STATUS_HEADER_CODE: &str = "grpc-status";
 const GRPC_STATUS_MESSAGE_HEADER: &str = "grpc-message";
+const GRPC_STATUS_DETAILS_HEADER: &str = "grpc-status-details-bin";
+
+/// Binary values may be sent with or without padding
+const BINARY_VALUE_ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
 
 const ENCODING_SET: &AsciiSet = &CONTROLS
     .add(b' ')
@@ -315,6 +329,31 @@ impl Status {
         &mut self.metadata
     }
 
+    /// Attach the rich error details to this status.
+    ///
+    /// The details are sent in the `grpc-status-details-bin` metadata as a
+    /// `google.rpc.Status` message, so the code and the message must be set
+    /// before calling this method.
+    pub fn with_details(mut self, details: ErrorDetails) -> Self {
+        let data = encode_status_details(
+            self.code,
+            self.message.as_deref().unwrap_or_default(),
+            &details,
+        );
+        self.metadata.insert_bin("grpc-status-details", data);
+        self
+    }
+
+    /// Returns the rich error details of this status.
+    ///
+    /// Returns `None` if the status has no details or they can not be
+    /// decoded.
+    pub fn details(&self) -> Option<ErrorDetails> {
+        let value = self.metadata.headers.get(GRPC_STATUS_DETAILS_HEADER)?;
+        let data = BINARY_VALUE_ENGINE.decode(value.as_bytes()).ok()?;
+        decode_status_details(&data).ok()
+    }
+
     pub(crate) fn to_headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
 
@@ -329,6 +368,10 @@ impl Status {
             headers.insert(GRPC_STATUS_MESSAGE_HEADER, message);
         }
 
+        if let Some(details) = self.metadata.headers.get(GRPC_STATUS_DETAILS_HEADER) {
+            headers.insert(GRPC_STATUS_DETAILS_HEADER, details.clone());
+        }
+
         headers
     }
 
@@ -349,6 +392,12 @@ impl Status {
             {
                 status = status.with_message(message);
             }
+            if let Some(details) = headers.get(GRPC_STATUS_DETAILS_HEADER) {
+                status
+                    .metadata
+                    .headers
+                    .insert(GRPC_STATUS_DETAILS_HEADER, details.clone());
+            }
             Ok(Some(status))
         } else {
             Ok(None)
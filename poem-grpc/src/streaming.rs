@@ -1,11 +1,14 @@
 use std::{
+    future::Future,
     pin::Pin,
     task::{Context, Poll},
 };
 
 use futures_util::{Stream, StreamExt, stream::BoxStream};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
 
-use crate::Status;
+use crate::{Code, Status};
 
 /// Message stream
 pub struct Streaming<T>(BoxStream<'static, Result<T, Status>>);
@@ -19,6 +22,82 @@ impl<T> Streaming<T> {
     {
         Self(stream.boxed())
     }
+
+    /// Create a bounded message stream and the sender of its messages.
+    ///
+    /// At most `buffer` messages are buffered, so
+    /// [`StreamingSender::send`] waits until the stream is polled when the
+    /// buffer is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is `0`.
+    pub fn channel(buffer: usize) -> (StreamingSender<T>, Self)
+    where
+        T: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(buffer);
+        (StreamingSender { tx }, rx.into())
+    }
+
+    /// Create a message stream whose messages are produced by a spawned task.
+    ///
+    /// The stream ends when the future completes, if it returns an error, the
+    /// error is the last item of the stream. The sending fails when the stream
+    /// is dropped, for example if the call is cancelled, so the task stops at
+    /// the next send.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::TryStreamExt;
+    /// use poem_grpc::{Request, Response, Status, Streaming};
+    ///
+    /// async fn echo(
+    ///     request: Request<Streaming<String>>,
+    /// ) -> Result<Response<Streaming<String>>, Status> {
+    ///     let mut requests = request.into_inner();
+    ///     Ok(Response::new(Streaming::spawn(16, |tx| async move {
+    ///         while let Some(message) = requests.try_next().await? {
+    ///             tx.send(message).await?;
+    ///         }
+    ///         Ok(())
+    ///     })))
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is `0`, or if called outside of a Tokio runtime.
+    pub fn spawn<F, Fut>(buffer: usize, f: F) -> Self
+    where
+        T: Send + 'static,
+        F: FnOnce(StreamingSender<T>) -> Fut,
+        Fut: Future<Output = Result<(), Status>> + Send + 'static,
+    {
+        let (tx, stream) = Self::channel(buffer);
+        let fut = f(tx.clone());
+        tokio::spawn(async move {
+            if let Err(status) = fut.await {
+                _ = tx.send_error(status).await;
+            }
+        });
+        stream
+    }
+}
+
+impl<T: Send + 'static> From<mpsc::Receiver<Result<T, Status>>> for Streaming<T> {
+    #[inline]
+    fn from(rx: mpsc::Receiver<Result<T, Status>>) -> Self {
+        Self::new(ReceiverStream::new(rx))
+    }
+}
+
+impl<T: Send + 'static> From<mpsc::UnboundedReceiver<Result<T, Status>>> for Streaming<T> {
+    #[inline]
+    fn from(rx: mpsc::UnboundedReceiver<Result<T, Status>>) -> Self {
+        Self::new(UnboundedReceiverStream::new(rx))
+    }
 }
 
 impl<T> Stream for Streaming<T> {
@@ -29,3 +108,137 @@ impl<T> Stream for Streaming<T> {
         self.0.poll_next_unpin(cx)
     }
 }
+
+fn closed() -> Status {
+    Status::new(Code::Cancelled).with_message("the stream has been closed")
+}
+
+/// The sender of the messages of a [`Streaming`] created with
+/// [`Streaming::channel`] or [`Streaming::spawn`]
+pub struct StreamingSender<T> {
+    tx: mpsc::Sender<Result<T, Status>>,
+}
+
+impl<T> Clone for StreamingSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<T> StreamingSender<T> {
+    /// Sends a message, waiting for the buffer to have room for it.
+    ///
+    /// Returns a [`Code::Cancelled`] status if the stream has been dropped.
+    pub async fn send(&self, message: T) -> Result<(), Status> {
+        self.tx.send(Ok(message)).await.map_err(|_| closed())
+    }
+
+    /// Sends an error, which ends the stream with this status.
+    pub async fn send_error(&self, status: Status) -> Result<(), Status> {
+        self.tx.send(Err(status)).await.map_err(|_| closed())
+    }
+
+    /// Tries to send a message without waiting.
+    ///
+    /// Returns the message if the buffer is full, or a [`Code::Cancelled`]
+    /// status if the stream has been dropped.
+    #[allow(clippy::result_large_err)]
+    pub fn try_send(&self, message: T) -> Result<Option<T>, Status> {
+        match self.tx.try_send(Ok(message)) {
+            Ok(()) => Ok(None),
+            Err(mpsc::error::TrySendError::Full(message)) => Ok(message.ok()),
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(closed()),
+        }
+    }
+
+    /// Waits until all the buffered messages have been taken from the stream.
+    pub async fn flush(&self) -> Result<(), Status> {
+        self.tx
+            .reserve_many(self.tx.max_capacity())
+            .await
+            .map(|_| ())
+            .map_err(|_| closed())
+    }
+
+    /// Returns the number of messages which can be sent without waiting.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.tx.capacity()
+    }
+
+    /// Returns `true` if the stream has been dropped.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    /// Waits until the stream is dropped.
+    #[inline]
+    pub async fn closed(&self) {
+        self.tx.closed().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn channel() {
+        let (tx, mut stream) = Streaming::channel(2);
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        assert_eq!(tx.capacity(), 0);
+        assert_eq!(tx.try_send(3).unwrap(), Some(3));
+
+        let flush = tokio::spawn({
+            let tx = tx.clone();
+            async move { tx.flush().await }
+        });
+        assert_eq!(stream.try_next().await.unwrap(), Some(1));
+        assert!(!flush.is_finished());
+        assert_eq!(stream.try_next().await.unwrap(), Some(2));
+        tokio::time::timeout(Duration::from_secs(1), flush)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        tx.send_error(Status::new(Code::Aborted)).await.unwrap();
+        assert_eq!(stream.try_next().await.unwrap_err().code(), Code::Aborted);
+
+        drop(stream);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(4).await.unwrap_err().code(), Code::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn spawn() {
+        let stream = Streaming::spawn(1, |tx| async move {
+            for i in 0..3 {
+                tx.send(i).await?;
+            }
+            Err(Status::new(Code::Internal))
+        });
+        let items = stream.collect::<Vec<_>>().await;
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[2].as_ref().unwrap(), &2);
+        assert_eq!(items[3].as_ref().unwrap_err().code(), Code::Internal);
+    }
+
+    #[tokio::test]
+    async fn from_receiver() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(Ok(1)).unwrap();
+        tx.send(Ok(2)).unwrap();
+        drop(tx);
+        let stream: Streaming<i32> = rx.into();
+        assert_eq!(stream.try_collect::<Vec<_>>().await.unwrap(), [1, 2]);
+    }
+}
@@ -1,5 +1,5 @@
 #[allow(unreachable_pub)]
-mod proto {
+pub(crate) mod proto {
     include!(concat!(env!("OUT_DIR"), "/test_harness.rs"));
 }
 
@@ -0,0 +1,863 @@
+//! JSON transcoding of GRPC services
+//!
+//! The unary methods annotated with
+//! [`google.api.http`](https://cloud.google.com/endpoints/docs/grpc-service-config/reference/rpc/google.api#google.api.HttpRule)
+//! can be served as RESTful JSON APIs by [`RouteTranscoding`], the servers
+//! must be generated with `poem_grpc_build::Config::enable_transcoding`.
+//!
+//! The fields of the request message are read from the path variables, the
+//! body and the query parameters as described by the rule. Since the values
+//! of the path variables and the query parameters are strings, they are
+//! converted to numbers or booleans according to the JSON representation of
+//! the default value of the field.
+
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use futures_util::future::BoxFuture;
+use http::Method;
+use percent_encoding::percent_decode_str;
+use poem::{
+    Endpoint, Request, Response, Result,
+    error::{MethodNotAllowedError, NotFoundError},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Code, Metadata, Request as GrpcRequest, Response as GrpcResponse, Status, connect::connect_code,
+};
+
+/// A GRPC service whose methods can be served by [`RouteTranscoding`]
+///
+/// This trait is implemented by the servers generated with transcoding
+/// enabled.
+pub trait TranscodingService {
+    /// Registers the HTTP bindings of the service.
+    fn register(&self, route: &mut RouteTranscoding);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Wildcard,
+    DoubleWildcard,
+}
+
+#[derive(Debug, Clone)]
+struct Variable {
+    field: String,
+    start: usize,
+    /// `None` if the variable captures the remaining segments.
+    end: Option<usize>,
+}
+
+/// A parsed [path template](https://cloud.google.com/endpoints/docs/grpc-service-config/reference/rpc/google.api#path-template-syntax)
+#[derive(Debug, Clone)]
+struct PathTemplate {
+    segments: Vec<Segment>,
+    variables: Vec<Variable>,
+    verb: Option<String>,
+}
+
+/// Splits the path by the slashes which are not in a variable.
+fn split_segments(path: &str) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (idx, c) in path.char_indices() {
+        match c {
+            '{' if depth == 0 => depth = 1,
+            '}' if depth == 1 => depth = 0,
+            '{' | '}' => return None,
+            '/' if depth == 0 => {
+                parts.push(&path[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+    parts.push(&path[start..]);
+    Some(parts)
+}
+
+fn parse_segment(segment: &str) -> Option<Segment> {
+    match segment {
+        "" => None,
+        "*" => Some(Segment::Wildcard),
+        "**" => Some(Segment::DoubleWildcard),
+        _ if segment.contains(['{', '}', '=', '*']) => None,
+        _ => Some(Segment::Literal(segment.to_string())),
+    }
+}
+
+impl PathTemplate {
+    fn parse(template: &str) -> Option<Self> {
+        let mut path = template.strip_prefix('/')?;
+        let mut verb = None;
+        if let Some(idx) = path.rfind(':') {
+            if !path[idx..].contains(['/', '}']) {
+                verb = Some(path[idx + 1..].to_string());
+                path = &path[..idx];
+            }
+        }
+
+        let mut segments = Vec::new();
+        let mut variables = Vec::new();
+        for part in split_segments(path)? {
+            let Some(variable) = part.strip_prefix('{').and_then(|s| s.strip_suffix('}')) else {
+                segments.push(parse_segment(part)?);
+                continue;
+            };
+            let (field, pattern) = variable.split_once('=').unwrap_or((variable, "*"));
+            if field.is_empty() {
+                return None;
+            }
+            let start = segments.len();
+            for segment in pattern.split('/') {
+                segments.push(parse_segment(segment)?);
+            }
+            let end = (segments.last() != Some(&Segment::DoubleWildcard)).then_some(segments.len());
+            variables.push(Variable {
+                field: field.to_string(),
+                start,
+                end,
+            });
+        }
+
+        // `**` can only be the last segment
+        if let Some(idx) = segments
+            .iter()
+            .position(|segment| *segment == Segment::DoubleWildcard)
+        {
+            if idx != segments.len() - 1 {
+                return None;
+            }
+        }
+
+        Some(Self {
+            segments,
+            variables,
+            verb,
+        })
+    }
+
+    /// Returns the values of the variables if the path matches this template.
+    fn matches(&self, path: &str) -> Option<Vec<(String, String)>> {
+        let mut path = path.strip_prefix('/')?;
+        if let Some(verb) = &self.verb {
+            path = path.strip_suffix(verb.as_str())?.strip_suffix(':')?;
+        }
+        let parts = if path.is_empty() {
+            Vec::new()
+        } else {
+            path.split('/').collect::<Vec<_>>()
+        };
+
+        match self.segments.last() {
+            Some(Segment::DoubleWildcard) if parts.len() + 1 >= self.segments.len() => {}
+            _ if parts.len() == self.segments.len() => {}
+            _ => return None,
+        }
+        for (segment, part) in self.segments.iter().zip(&parts) {
+            match segment {
+                Segment::Literal(literal) if literal != part => return None,
+                Segment::Wildcard if part.is_empty() => return None,
+                _ => {}
+            }
+        }
+
+        Some(
+            self.variables
+                .iter()
+                .map(|variable| {
+                    let start = variable.start.min(parts.len());
+                    let end = variable.end.unwrap_or(parts.len());
+                    let value = parts[start..end].join("/");
+                    (
+                        variable.field.clone(),
+                        percent_decode_str(&value).decode_utf8_lossy().into_owned(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Returns the path template in the OpenAPI syntax.
+fn openapi_path(template: &str) -> String {
+    let mut path = String::with_capacity(template.len());
+    let mut in_variable = false;
+    let mut skip = false;
+
+    for c in template.chars() {
+        match c {
+            '{' => {
+                in_variable = true;
+                path.push(c);
+            }
+            '}' => {
+                in_variable = false;
+                skip = false;
+                path.push(c);
+            }
+            '=' if in_variable => skip = true,
+            _ if !skip => path.push(c),
+            _ => {}
+        }
+    }
+    path
+}
+
+/// A HTTP binding of a GRPC method
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct HttpBinding {
+    method: Method,
+    path: String,
+    template: PathTemplate,
+    body: String,
+    response_body: String,
+}
+
+impl HttpBinding {
+    /// Create a HTTP binding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the method or the path template is invalid.
+    pub fn new(method: &str, path: &str, body: &str, response_body: &str) -> Self {
+        Self {
+            method: Method::from_bytes(method.as_bytes())
+                .unwrap_or_else(|_| panic!("invalid http method: `{method}`")),
+            path: path.to_string(),
+            template: PathTemplate::parse(path)
+                .unwrap_or_else(|| panic!("invalid path template: `{path}`")),
+            body: body.to_string(),
+            response_body: response_body.to_string(),
+        }
+    }
+}
+
+type BoxHandler = Arc<
+    dyn Fn(Request, Vec<(String, String)>) -> BoxFuture<'static, Result<Response, Status>>
+        + Send
+        + Sync,
+>;
+
+struct TranscodingRoute {
+    binding: Arc<HttpBinding>,
+    operation: String,
+    handler: BoxHandler,
+    request: Value,
+    response: Value,
+}
+
+/// A router that serves GRPC services as RESTful JSON APIs
+///
+/// # Example
+///
+/// ```ignore
+/// use poem::{Route, endpoint::make_sync, web::Json};
+/// use poem_grpc::{RouteGrpc, RouteTranscoding};
+///
+/// let transcoding = RouteTranscoding::new().add_service(GreeterServer::new(GreeterService));
+/// let spec = transcoding.openapi("Greeter", "1.0.0");
+///
+/// let app = Route::new()
+///     .nest("/grpc", RouteGrpc::new().add_service(GreeterServer::new(GreeterService)))
+///     .at("/openapi.json", make_sync(move |_| Json(spec.clone())))
+///     .nest("/", transcoding);
+/// ```
+#[derive(Default)]
+pub struct RouteTranscoding {
+    routes: Vec<TranscodingRoute>,
+}
+
+impl RouteTranscoding {
+    /// Create a `RouteTranscoding`
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a GRPC service
+    pub fn add_service<S: TranscodingService>(mut self, service: S) -> Self {
+        service.register(&mut self);
+        self
+    }
+
+    #[doc(hidden)]
+    pub fn add_unary<Req, Resp, F, Fut>(&mut self, binding: HttpBinding, operation: &str, f: F)
+    where
+        Req: Serialize + DeserializeOwned + Default + Send + 'static,
+        Resp: Serialize + Default + Send + 'static,
+        F: Fn(GrpcRequest<Req>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GrpcResponse<Resp>, Status>> + Send + 'static,
+    {
+        let binding = Arc::new(binding);
+        let f = Arc::new(f);
+        let handler: BoxHandler = {
+            let binding = binding.clone();
+            Arc::new(move |req, params| {
+                let binding = binding.clone();
+                let f = f.clone();
+                Box::pin(async move {
+                    let request = decode_request::<Req>(req, &binding, params).await?;
+                    encode_response(f(request).await?, &binding)
+                })
+            })
+        };
+
+        self.routes.push(TranscodingRoute {
+            binding,
+            operation: operation.to_string(),
+            handler,
+            request: serde_json::to_value(Req::default()).unwrap_or_default(),
+            response: serde_json::to_value(Resp::default()).unwrap_or_default(),
+        });
+    }
+
+    /// Returns an [OpenAPI](https://spec.openapis.org/oas/v3.0.3) document
+    /// describing the HTTP bindings.
+    ///
+    /// The schemas are inferred from the JSON representation of the default
+    /// values of the messages, so the fields of nested messages are not
+    /// described.
+    pub fn openapi(&self, title: &str, version: &str) -> Value {
+        let mut paths = Map::new();
+        let mut operation_ids = HashMap::<&str, usize>::new();
+
+        for route in &self.routes {
+            let binding = &route.binding;
+            let count = operation_ids.entry(&route.operation).or_default();
+            let operation_id = match *count {
+                0 => route.operation.clone(),
+                n => format!("{}_{n}", route.operation),
+            };
+            *count += 1;
+
+            let path_fields = binding
+                .template
+                .variables
+                .iter()
+                .map(|variable| variable.field.as_str())
+                .collect::<Vec<_>>();
+            let mut parameters = path_fields
+                .iter()
+                .map(|field| json!({"name": field, "in": "path", "required": true, "schema": {"type": "string"}}))
+                .collect::<Vec<_>>();
+            if binding.body != "*" {
+                if let Value::Object(fields) = &route.request {
+                    parameters.extend(
+                        fields
+                            .iter()
+                            .filter(|(name, _)| {
+                                !path_fields.contains(&name.as_str()) && **name != binding.body
+                            })
+                            .map(|(name, value)| {
+                                json!({"name": name, "in": "query", "schema": schema_of(value)})
+                            }),
+                    );
+                }
+            }
+
+            let mut operation = json!({
+                "operationId": operation_id,
+                "tags": [route.operation.rsplit_once('.').map(|(service, _)| service).unwrap_or_default()],
+                "parameters": parameters,
+                "responses": {
+                    "200": {
+                        "description": "OK",
+                        "content": {"application/json": {"schema": schema_of(field_value(&route.response, &binding.response_body))}},
+                    },
+                    "default": {
+                        "description": "Error",
+                        "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Status"}}},
+                    },
+                },
+            });
+            if !binding.body.is_empty() {
+                operation["requestBody"] = json!({
+                    "required": true,
+                    "content": {"application/json": {"schema": schema_of(field_value(&route.request, &binding.body))}},
+                });
+            }
+
+            if let Value::Object(path) = paths
+                .entry(openapi_path(&binding.path))
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                path.insert(binding.method.as_str().to_lowercase(), operation);
+            }
+        }
+
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": title, "version": version},
+            "paths": paths,
+            "components": {
+                "schemas": {
+                    "Status": {
+                        "type": "object",
+                        "properties": {
+                            "code": {"type": "integer"},
+                            "message": {"type": "string"},
+                        },
+                    },
+                },
+            },
+        })
+    }
+}
+
+impl Endpoint for RouteTranscoding {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let mut path_matched = false;
+
+        for route in &self.routes {
+            let Some(params) = route.binding.template.matches(req.uri().path()) else {
+                continue;
+            };
+            if route.binding.method != req.method() {
+                path_matched = true;
+                continue;
+            }
+            return Ok(match (route.handler)(req, params).await {
+                Ok(resp) => resp,
+                Err(status) => error_response(&status),
+            });
+        }
+
+        if path_matched {
+            Err(MethodNotAllowedError.into())
+        } else {
+            Err(NotFoundError.into())
+        }
+    }
+}
+
+fn schema_of(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({}),
+        Value::Bool(_) => json!({"type": "boolean"}),
+        Value::Number(n) if n.is_f64() => json!({"type": "number"}),
+        Value::Number(_) => json!({"type": "integer"}),
+        Value::String(_) => json!({"type": "string"}),
+        Value::Array(_) => json!({"type": "array", "items": {}}),
+        Value::Object(fields) => json!({
+            "type": "object",
+            "properties": fields
+                .iter()
+                .map(|(name, value)| (name.clone(), schema_of(value)))
+                .collect::<Map<_, _>>(),
+        }),
+    }
+}
+
+/// Returns the value of a field, or the value itself if the path is empty.
+fn field_value<'a>(value: &'a Value, path: &str) -> &'a Value {
+    if path.is_empty() || path == "*" {
+        return value;
+    }
+    path.split('.')
+        .try_fold(value, |value, name| value.get(name))
+        .unwrap_or(&Value::Null)
+}
+
+fn invalid_argument(err: impl std::fmt::Display) -> Status {
+    Status::new(Code::InvalidArgument).with_message(err)
+}
+
+/// Converts the string to the type of the current value of the field.
+fn convert_value(current: &Value, value: String) -> Value {
+    match current {
+        Value::Number(_) => {
+            if let Ok(n) = value.parse::<i64>() {
+                Value::from(n)
+            } else if let Ok(n) = value.parse::<u64>() {
+                Value::from(n)
+            } else {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .unwrap_or(Value::String(value))
+            }
+        }
+        Value::Bool(_) => match value.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::String(value),
+        },
+        _ => Value::String(value),
+    }
+}
+
+/// Sets the field specified by a dot-separated path, the values of repeated
+/// fields are appended.
+#[allow(clippy::result_large_err)]
+fn set_field(mut current: &mut Value, path: &str, value: Value) -> Result<(), Status> {
+    let mut names = path.split('.').peekable();
+
+    while let Some(name) = names.next() {
+        if current.is_null() {
+            *current = Value::Object(Map::new());
+        }
+        let Value::Object(fields) = current else {
+            return Err(invalid_argument(format!("invalid field path: `{path}`")));
+        };
+        if names.peek().is_none() {
+            match (fields.get_mut(name), value) {
+                (Some(Value::Array(items)), Value::String(value)) => {
+                    items.push(Value::String(value))
+                }
+                (Some(field), Value::String(value)) => *field = convert_value(field, value),
+                (_, value) => {
+                    fields.insert(name.to_string(), value);
+                }
+            }
+            break;
+        }
+        current = fields.entry(name).or_insert(Value::Null);
+    }
+
+    Ok(())
+}
+
+async fn decode_request<T: Serialize + DeserializeOwned + Default>(
+    mut req: Request,
+    binding: &HttpBinding,
+    params: Vec<(String, String)>,
+) -> Result<GrpcRequest<T>, Status> {
+    let mut value = serde_json::to_value(T::default())
+        .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+
+    if !binding.body.is_empty() {
+        let data = req
+            .take_body()
+            .into_bytes()
+            .await
+            .map_err(invalid_argument)?;
+        if !data.is_empty() {
+            let body = serde_json::from_slice::<Value>(&data).map_err(invalid_argument)?;
+            if binding.body == "*" {
+                let (Value::Object(fields), Value::Object(body)) = (&mut value, body) else {
+                    return Err(invalid_argument("the body must be an object"));
+                };
+                fields.extend(body);
+            } else {
+                set_field(&mut value, &binding.body, body)?;
+            }
+        }
+    }
+
+    if binding.body != "*" {
+        if let Some(query) = req.uri().query() {
+            let query = serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+                .map_err(invalid_argument)?;
+            for (name, param) in query {
+                if !params.iter().any(|(field, _)| *field == name) {
+                    set_field(&mut value, &name, Value::String(param))?;
+                }
+            }
+        }
+    }
+
+    for (field, param) in params {
+        set_field(&mut value, &field, Value::String(param))?;
+    }
+
+    let message = serde_json::from_value(value).map_err(invalid_argument)?;
+    let (parts, _) = req.into_parts();
+    Ok(GrpcRequest {
+        metadata: Metadata {
+            headers: parts.headers,
+        },
+        message,
+        extensions: parts.extensions,
+    })
+}
+
+#[allow(clippy::result_large_err)]
+fn encode_response<T: Serialize>(
+    resp: GrpcResponse<T>,
+    binding: &HttpBinding,
+) -> Result<Response, Status> {
+    let GrpcResponse { metadata, message } = resp;
+    let value = serde_json::to_value(message)
+        .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+    let data = serde_json::to_vec(field_value(&value, &binding.response_body))
+        .map_err(|err| Status::new(Code::Internal).with_message(err))?;
+
+    let mut resp = Response::builder()
+        .content_type("application/json")
+        .body(data);
+    resp.headers_mut().extend(metadata.headers);
+    Ok(resp)
+}
+
+fn error_response(status: &Status) -> Response {
+    let (_, http_status) = connect_code(status.code());
+    let body = json!({
+        "code": status.code().as_u16(),
+        "message": status.message().unwrap_or_default(),
+    });
+    let mut resp = Response::builder()
+        .status(http_status)
+        .content_type("application/json")
+        .body(body.to_string());
+    resp.headers_mut().extend(status.metadata().headers.clone());
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::Streaming;
+
+    #[allow(unreachable_pub)]
+    mod proto {
+        include!(concat!(env!("OUT_DIR"), "/transcoding.rs"));
+    }
+
+    use proto::{
+        Book, CreateBookRequest, GetBookRequest, Library, LibraryServer, ListBooksRequest,
+        ListBooksResponse,
+    };
+
+    struct LibraryService;
+
+    impl Library for LibraryService {
+        async fn get_book(
+            &self,
+            req: GrpcRequest<GetBookRequest>,
+        ) -> Result<GrpcResponse<Book>, Status> {
+            if req.id == 0 {
+                return Err(Status::new(Code::NotFound).with_message("book not found"));
+            }
+            let title = if req.verbose { "verbose" } else { "title" };
+            let mut resp = GrpcResponse::new(Book {
+                shelf: req.shelf.clone(),
+                id: req.id,
+                title: title.to_string(),
+                tags: Vec::new(),
+            });
+            if let Some(value) = req.metadata().get("x-request-id") {
+                resp.metadata_mut()
+                    .insert("x-request-id", value.to_string());
+            }
+            Ok(resp)
+        }
+
+        async fn create_book(
+            &self,
+            req: GrpcRequest<CreateBookRequest>,
+        ) -> Result<GrpcResponse<Book>, Status> {
+            let req = req.into_inner();
+            Ok(GrpcResponse::new(Book {
+                shelf: req.shelf,
+                ..req.book.unwrap_or_default()
+            }))
+        }
+
+        async fn list_books(
+            &self,
+            req: GrpcRequest<ListBooksRequest>,
+        ) -> Result<GrpcResponse<ListBooksResponse>, Status> {
+            let req = req.into_inner();
+            Ok(GrpcResponse::new(ListBooksResponse {
+                books: vec![Book {
+                    shelf: req.shelf,
+                    id: 1,
+                    title: "title".to_string(),
+                    tags: req.tags,
+                }],
+            }))
+        }
+
+        async fn server_streaming(
+            &self,
+            _req: GrpcRequest<GetBookRequest>,
+        ) -> Result<GrpcResponse<Streaming<Book>>, Status> {
+            Ok(GrpcResponse::new(Streaming::new(stream::empty())))
+        }
+    }
+
+    fn route() -> RouteTranscoding {
+        RouteTranscoding::new().add_service(LibraryServer::new(LibraryService))
+    }
+
+    #[test]
+    fn path_template() {
+        let template = PathTemplate::parse("/v1/shelves/{shelf}/books/{id}").unwrap();
+        assert_eq!(
+            template.matches("/v1/shelves/a%20b/books/1"),
+            Some(vec![
+                ("shelf".to_string(), "a b".to_string()),
+                ("id".to_string(), "1".to_string())
+            ])
+        );
+        assert_eq!(template.matches("/v1/shelves/a/books"), None);
+        assert_eq!(template.matches("/v1/shelves//books/1"), None);
+
+        let template = PathTemplate::parse("/v1/{name=shelves/*/books/*}:publish").unwrap();
+        assert_eq!(
+            template.matches("/v1/shelves/a/books/1:publish"),
+            Some(vec![("name".to_string(), "shelves/a/books/1".to_string())])
+        );
+        assert_eq!(template.matches("/v1/shelves/a/books/1"), None);
+
+        let template = PathTemplate::parse("/v1/files/{path=**}").unwrap();
+        assert_eq!(
+            template.matches("/v1/files/a/b/c"),
+            Some(vec![("path".to_string(), "a/b/c".to_string())])
+        );
+
+        assert!(PathTemplate::parse("v1/books").is_none());
+        assert!(PathTemplate::parse("/v1/{name").is_none());
+        assert!(PathTemplate::parse("/v1/**/books").is_none());
+        assert_eq!(
+            openapi_path("/v1/{name=shelves/*}/books/{id}"),
+            "/v1/{name}/books/{id}"
+        );
+    }
+
+    async fn call(req: Request) -> Response {
+        route()
+            .call(req)
+            .await
+            .unwrap_or_else(|err| err.into_response())
+    }
+
+    async fn json_body(resp: Response) -> Value {
+        serde_json::from_slice(&resp.into_body().into_bytes().await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn path_and_query() {
+        let resp = call(
+            Request::builder()
+                .uri_str("/v1/shelves/s1/books/2?verbose=true")
+                .header("x-request-id", "abc")
+                .finish(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-request-id").unwrap(), "abc");
+        assert_eq!(
+            json_body(resp).await,
+            json!({"shelf": "s1", "id": 2, "title": "verbose", "tags": []})
+        );
+
+        let resp = call(
+            Request::builder()
+                .uri_str("/v1/shelves/s1/books?tags=a&tags=b")
+                .finish(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(resp).await,
+            json!([{"shelf": "shelves/s1", "id": 1, "title": "title", "tags": ["a", "b"]}])
+        );
+
+        let resp = call(
+            Request::builder()
+                .uri_str("/v1/shelves/s1/books/abc")
+                .finish(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn body() {
+        let resp = call(
+            Request::builder()
+                .method(Method::POST)
+                .uri_str("/v1/shelves/s1/books")
+                .content_type("application/json")
+                .body(json!({"id": 3, "title": "hello"}).to_string()),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(resp).await,
+            json!({"shelf": "s1", "id": 3, "title": "hello", "tags": []})
+        );
+
+        let resp = call(
+            Request::builder()
+                .method(Method::PUT)
+                .uri_str("/v1/books")
+                .content_type("application/json")
+                .body(json!({"shelf": "s2", "book": {"id": 4, "title": "world"}}).to_string()),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(resp).await,
+            json!({"shelf": "s2", "id": 4, "title": "world", "tags": []})
+        );
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let resp = call(
+            Request::builder()
+                .uri_str("/v1/shelves/s1/books/0")
+                .finish(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            json_body(resp).await,
+            json!({"code": Code::NotFound.as_u16(), "message": "book not found"})
+        );
+
+        let resp = call(Request::builder().uri_str("/v2/books").finish()).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = call(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri_str("/v1/books")
+                .finish(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn openapi() {
+        let spec = route().openapi("Library", "1.0.0");
+        assert_eq!(spec["info"]["title"], "Library");
+
+        let get_book = &spec["paths"]["/v1/shelves/{shelf}/books/{id}"]["get"];
+        assert_eq!(get_book["operationId"], "transcoding.Library.GetBook");
+        assert_eq!(get_book["parameters"].as_array().unwrap().len(), 3);
+        assert_eq!(get_book["parameters"][2]["name"], "verbose");
+        assert_eq!(get_book["parameters"][2]["schema"]["type"], "boolean");
+
+        let create_book = &spec["paths"]["/v1/shelves/{shelf}/books"]["post"];
+        assert!(create_book.get("requestBody").is_some());
+        assert_eq!(
+            spec["paths"]["/v1/books"]["put"]["operationId"],
+            "transcoding.Library.CreateBook_1"
+        );
+        assert_eq!(
+            spec["paths"]["/v1/{shelf}/books"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["schema"]["type"],
+            "array"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::io::{Error as IoError, ErrorKind};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bytes::{BufMut, Bytes, BytesMut};
+use http::{HeaderMap, HeaderValue, Version, header};
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::Frame;
+use poem::{
+    Body, Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+};
+use sync_wrapper::SyncStream;
+
+use crate::client::BoxBody;
+
+const GRPC_WEB_CONTENT_TYPE: &str = "application/grpc-web";
+const GRPC_WEB_TEXT_CONTENT_TYPE: &str = "application/grpc-web-text";
+
+/// Middleware for translating [gRPC-Web](https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-WEB.md)
+/// requests to GRPC requests
+///
+/// This allows browsers to call the services over HTTP/1.1 without a
+/// separate proxy. Both the binary (`application/grpc-web`) and the text
+/// (`application/grpc-web-text`) modes are supported, other requests are
+/// passed to the inner endpoint unchanged.
+///
+/// Browsers require CORS for cross-origin calls, the `grpc-status` and
+/// `grpc-message` headers must be exposed to the client.
+///
+/// # Example
+///
+/// ```ignore
+/// use poem::{EndpointExt, middleware::Cors};
+/// use poem_grpc::{GrpcWeb, RouteGrpc};
+///
+/// let route = RouteGrpc::new()
+///     .add_service(GreeterServer::new(GreeterService))
+///     .with(GrpcWeb::new())
+///     .with(Cors::new().expose_headers(["grpc-status", "grpc-message"]));
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GrpcWeb;
+
+impl GrpcWeb {
+    /// Create a `GrpcWeb` middleware
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for GrpcWeb {
+    type Output = GrpcWebEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        GrpcWebEndpoint { inner: ep }
+    }
+}
+
+/// Endpoint for the [`GrpcWeb`] middleware
+pub struct GrpcWebEndpoint<E> {
+    inner: E,
+}
+
+impl<E: Endpoint> Endpoint for GrpcWebEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let Some((content_type, text)) = req.content_type().and_then(parse_content_type) else {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        };
+        let web_content_type = req.headers().get(header::CONTENT_TYPE).cloned();
+
+        req.set_version(Version::HTTP_2);
+        req.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(&content_type).expect("valid content type"),
+        );
+        if text {
+            req.headers_mut().remove(header::CONTENT_LENGTH);
+            let body = req.take_body();
+            req.set_body(decode_text_body(body));
+        }
+
+        let mut resp = self.inner.call(req).await?.into_response();
+        resp.set_version(Version::HTTP_11);
+        if let Some(web_content_type) = web_content_type {
+            resp.headers_mut()
+                .insert(header::CONTENT_TYPE, web_content_type);
+        }
+        let body = resp.take_body();
+        resp.set_body(encode_body(body, text));
+        Ok(resp)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+/// Returns the GRPC content type for a gRPC-Web content type, and whether the
+/// request uses the text mode.
+fn parse_content_type(content_type: &str) -> Option<(String, bool)> {
+    let (suffix, text) = match content_type.strip_prefix(GRPC_WEB_TEXT_CONTENT_TYPE) {
+        Some(suffix) => (suffix, true),
+        None => (content_type.strip_prefix(GRPC_WEB_CONTENT_TYPE)?, false),
+    };
+    if !suffix.is_empty() && !suffix.starts_with('+') {
+        return None;
+    }
+    Some((format!("application/grpc{suffix}"), text))
+}
+
+fn decode_text_body(body: Body) -> Body {
+    let mut body: BoxBody = body.into();
+
+    Body::from_bytes_stream::<_, Bytes, IoError>(async_stream::try_stream! {
+        let mut buf = BytesMut::new();
+
+        while let Some(frame) = body.frame().await.transpose()? {
+            if let Ok(data) = frame.into_data() {
+                buf.extend_from_slice(&data);
+                yield decode_base64(&mut buf)?;
+            }
+        }
+
+        if !buf.is_empty() {
+            Err(IoError::new(ErrorKind::InvalidData, "incomplete base64 data"))?;
+        }
+    })
+}
+
+/// Decodes the complete base64 quantums of the buffer.
+///
+/// Every message can be encoded separately, so padding can occur in the middle
+/// of the data.
+fn decode_base64(buf: &mut BytesMut) -> Result<Bytes, IoError> {
+    let data = buf.split_to(buf.len() / 4 * 4);
+    let mut decoded = Vec::with_capacity(data.len() / 4 * 3);
+    let mut start = 0;
+
+    for (idx, quantum) in data.chunks(4).enumerate() {
+        if quantum[3] == b'=' {
+            let end = (idx + 1) * 4;
+            STANDARD
+                .decode_vec(&data[start..end], &mut decoded)
+                .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+            start = end;
+        }
+    }
+    STANDARD
+        .decode_vec(&data[start..], &mut decoded)
+        .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+
+    Ok(decoded.into())
+}
+
+fn encode_body(body: Body, text: bool) -> Body {
+    let mut body: BoxBody = body.into();
+
+    let stream = async_stream::try_stream! {
+        while let Some(frame) = body.frame().await.transpose()? {
+            let data = match frame.into_data() {
+                Ok(data) => data,
+                Err(frame) => match frame.into_trailers() {
+                    Ok(trailers) => encode_trailers(&trailers),
+                    Err(_) => continue,
+                },
+            };
+            if text {
+                yield Frame::data(Bytes::from(STANDARD.encode(data)));
+            } else {
+                yield Frame::data(data);
+            }
+        }
+    };
+
+    BodyExt::boxed(StreamBody::new(SyncStream::new(stream))).into()
+}
+
+/// Encodes the trailers as a message with the most significant bit of the flag
+/// set.
+fn encode_trailers(trailers: &HeaderMap) -> Bytes {
+    let mut data = BytesMut::new();
+    for (name, value) in trailers {
+        data.put_slice(name.as_str().as_bytes());
+        data.put_slice(b": ");
+        data.put_slice(value.as_bytes());
+        data.put_slice(b"\r\n");
+    }
+
+    let mut buf = BytesMut::with_capacity(data.len() + 5);
+    buf.put_u8(0x80);
+    buf.put_u32(data.len() as u32);
+    buf.put_slice(&data);
+    buf.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::EndpointExt;
+    use prost::Message;
+
+    use super::*;
+    use crate::{
+        RouteGrpc,
+        test_harness::{
+            TestHarnessService,
+            proto::{TestHarnessServer, UnaryRequest, ValueRequest, ValueResponse},
+        },
+    };
+
+    fn envelope(message: impl Message) -> Vec<u8> {
+        let data = message.encode_to_vec();
+        let mut buf = vec![0];
+        buf.extend((data.len() as u32).to_be_bytes());
+        buf.extend(data);
+        buf
+    }
+
+    async fn call(content_type: &str, body: Vec<u8>) -> Response {
+        let ep = RouteGrpc::new()
+            .add_service(TestHarnessServer::new(TestHarnessService))
+            .with(GrpcWeb::new());
+        ep.call(
+            Request::builder()
+                .method(http::Method::POST)
+                .uri_str("/test_harness.TestHarness/Unary")
+                .content_type(content_type)
+                .body(body),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn expected_body(value: i32) -> Vec<u8> {
+        let mut expected = envelope(ValueResponse { value });
+        expected.extend(b"\x80\x00\x00\x00\x10grpc-status: 0\r\n");
+        expected
+    }
+
+    #[test]
+    fn content_type() {
+        assert_eq!(
+            parse_content_type("application/grpc-web"),
+            Some(("application/grpc".to_string(), false))
+        );
+        assert_eq!(
+            parse_content_type("application/grpc-web+proto"),
+            Some(("application/grpc+proto".to_string(), false))
+        );
+        assert_eq!(
+            parse_content_type("application/grpc-web-text+json"),
+            Some(("application/grpc+json".to_string(), true))
+        );
+        assert_eq!(parse_content_type("application/grpc"), None);
+        assert_eq!(parse_content_type("application/grpc-webx"), None);
+    }
+
+    #[test]
+    fn padding_in_the_middle() {
+        let mut buf =
+            BytesMut::from(format!("{}{}AA", STANDARD.encode("a"), STANDARD.encode("bc")).as_str());
+        assert_eq!(decode_base64(&mut buf).unwrap(), Bytes::from_static(b"abc"));
+        assert_eq!(&buf[..], b"AA");
+    }
+
+    #[tokio::test]
+    async fn binary() {
+        let resp = call(
+            "application/grpc-web+proto",
+            envelope(UnaryRequest { a: 10, b: 20 }),
+        )
+        .await;
+        assert_eq!(resp.content_type(), Some("application/grpc-web+proto"));
+        let body = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(body, expected_body(30));
+    }
+
+    #[tokio::test]
+    async fn text() {
+        let resp = call(
+            "application/grpc-web-text",
+            STANDARD
+                .encode(envelope(UnaryRequest { a: 10, b: 20 }))
+                .into_bytes(),
+        )
+        .await;
+        assert_eq!(resp.content_type(), Some("application/grpc-web-text"));
+        let body = resp.into_body().into_string().await.unwrap();
+        let mut buf = BytesMut::from(body.as_str());
+        assert_eq!(decode_base64(&mut buf).unwrap(), expected_body(30));
+    }
+
+    #[tokio::test]
+    async fn error() {
+        let resp = call(
+            "application/grpc-web",
+            envelope(ValueRequest { value: 1 })[..3].to_vec(),
+        )
+        .await;
+        assert_eq!(
+            resp.headers().get("grpc-status"),
+            Some(&HeaderValue::from_static("13"))
+        );
+    }
+}
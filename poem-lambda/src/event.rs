@@ -0,0 +1,168 @@
+use std::{collections::HashMap, ops::Deref};
+
+use lambda_http::{RequestExt, aws_lambda_events::query_map::QueryMap, request::RequestContext};
+use poem::{Error, FromRequest, Request, RequestBody, Result, http::StatusCode};
+use serde_json::Value;
+
+/// The context of the event which triggered the function, which depends on
+/// the service that sent it.
+///
+/// It implements [`poem::FromRequest`], so it can be used as an extractor.
+///
+/// # Example
+///
+/// ```
+/// use poem::handler;
+/// use poem_lambda::{EventContext, RequestContext};
+///
+/// #[handler]
+/// fn index(ctx: EventContext) -> String {
+///     match &*ctx {
+///         RequestContext::ApiGatewayV1(ctx) => ctx.stage.clone().unwrap_or_default(),
+///         RequestContext::ApiGatewayV2(ctx) => ctx.stage.clone().unwrap_or_default(),
+///         _ => String::new(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventContext(pub RequestContext);
+
+impl Deref for EventContext {
+    type Target = RequestContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> FromRequest<'a> for EventContext {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        req.extensions()
+            .request_context_ref()
+            .cloned()
+            .map(EventContext)
+            .ok_or_else(|| {
+                Error::from_string("missing event context", StatusCode::INTERNAL_SERVER_ERROR)
+            })
+    }
+}
+
+/// The [stage variables](https://docs.aws.amazon.com/apigateway/latest/developerguide/stage-variables.html)
+/// of an API Gateway request.
+///
+/// It implements [`poem::FromRequest`], so it can be used as an extractor,
+/// the variables are empty if the request was not sent by API Gateway.
+///
+/// # Example
+///
+/// ```
+/// use poem::handler;
+/// use poem_lambda::StageVariables;
+///
+/// #[handler]
+/// fn index(vars: StageVariables) -> String {
+///     vars.first("table").unwrap_or("default").to_string()
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StageVariables(pub QueryMap);
+
+impl Deref for StageVariables {
+    type Target = QueryMap;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> FromRequest<'a> for StageVariables {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(StageVariables(req.extensions().stage_variables()))
+    }
+}
+
+/// The result of the authorizer of an API Gateway request.
+///
+/// It implements [`poem::FromRequest`], so it can be used as an extractor, the
+/// extraction fails with `401 Unauthorized` if the request was not
+/// authorized by an authorizer.
+///
+/// # Example
+///
+/// ```
+/// use poem::handler;
+/// use poem_lambda::Authorizer;
+///
+/// #[handler]
+/// fn index(authorizer: Authorizer) -> String {
+///     authorizer
+///         .claims
+///         .get("custom:tenant")
+///         .cloned()
+///         .unwrap_or_default()
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Authorizer {
+    /// The claims of the JWT, from a JWT or Cognito user pool authorizer.
+    pub claims: HashMap<String, String>,
+    /// The scopes of the JWT.
+    pub scopes: Vec<String>,
+    /// The context returned by a Lambda authorizer.
+    pub fields: HashMap<String, Value>,
+    /// The principal identifier returned by a Lambda authorizer.
+    pub principal_id: Option<String>,
+}
+
+impl Authorizer {
+    fn from_context(ctx: &RequestContext) -> Option<Self> {
+        let authorizer = match ctx {
+            RequestContext::ApiGatewayV1(ctx) => &ctx.authorizer,
+            RequestContext::ApiGatewayV2(ctx) => ctx.authorizer.as_ref()?,
+            RequestContext::WebSocket(ctx) => &ctx.authorizer,
+            _ => return None,
+        };
+
+        let mut fields = authorizer.fields.clone();
+        let principal_id = match fields.remove("principalId") {
+            Some(Value::String(principal_id)) => Some(principal_id),
+            _ => None,
+        };
+        let (mut claims, scopes) = match &authorizer.jwt {
+            Some(jwt) => (jwt.claims.clone(), jwt.scopes.clone().unwrap_or_default()),
+            None => Default::default(),
+        };
+        // the Cognito user pool authorizers of REST APIs put the claims in the
+        // context
+        if let Some(Value::Object(values)) = fields.remove("claims") {
+            claims.extend(values.into_iter().map(|(name, value)| match value {
+                Value::String(value) => (name, value),
+                value => (name, value.to_string()),
+            }));
+        }
+
+        if claims.is_empty()
+            && scopes.is_empty()
+            && fields.is_empty()
+            && principal_id.is_none()
+            && authorizer.iam.is_none()
+        {
+            return None;
+        }
+        Some(Self {
+            claims,
+            scopes,
+            fields,
+            principal_id,
+        })
+    }
+}
+
+impl<'a> FromRequest<'a> for Authorizer {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        req.extensions()
+            .request_context_ref()
+            .and_then(Authorizer::from_context)
+            .ok_or_else(|| Error::from_string("missing authorizer", StatusCode::UNAUTHORIZED))
+    }
+}
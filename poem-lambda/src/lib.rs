@@ -7,13 +7,26 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(missing_docs)]
 
-use std::{ops::Deref, sync::Arc};
+mod event;
 
-pub use lambda_http::lambda_runtime::Error;
+use std::{
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+pub use event::{Authorizer, EventContext, StageVariables};
 use lambda_http::{
-    Body as LambdaBody, Request as LambdaRequest, RequestExt, lambda_runtime, service_fn,
+    Adapter, Body as LambdaBody, Request as LambdaRequest, RequestExt, Service,
+    lambda_runtime::{self, LambdaEvent},
+    request::LambdaRequest as LambdaEventPayload,
+    service_fn,
+};
+pub use lambda_http::{lambda_runtime::Error, request::RequestContext};
+use poem::{
+    Body, Endpoint, EndpointExt, FromRequest, IntoEndpoint, Request, RequestBody, Result,
+    http::{Method, Uri, request::Parts, uri::PathAndQuery},
 };
-use poem::{Body, Endpoint, EndpointExt, FromRequest, IntoEndpoint, Request, RequestBody, Result};
 
 /// The Lambda function execution context.
 ///
@@ -28,6 +41,8 @@ use poem::{Body, Endpoint, EndpointExt, FromRequest, IntoEndpoint, Request, Requ
 /// #[handler]
 /// fn index(req: &Request, ctx: &Context) {
 ///     println!("request_id: {}", ctx.request_id);
+///     println!("function: {}", ctx.invoked_function_arn);
+///     println!("remaining time: {:?}", ctx.remaining_time());
 /// }
 /// ```
 #[derive(Debug, Clone)]
@@ -41,8 +56,30 @@ impl Deref for Context {
     }
 }
 
+impl Context {
+    /// Returns the remaining execution time of the invocation, which is zero
+    /// if the deadline has passed.
+    pub fn remaining_time(&self) -> Duration {
+        self.0
+            .deadline()
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+}
+
 /// Starts the AWS Lambda runtime.
 ///
+/// The events of API Gateway REST and HTTP APIs, Lambda function URLs and
+/// Application Load Balancer target groups (including the health checks) are
+/// translated into requests with the same method, path, headers and query
+/// string, the multi-value headers and query parameters are preserved.
+///
+/// The events of API Gateway WebSocket APIs have no path, so they are
+/// translated into requests whose path is the route key, for example
+/// `/$connect`, `/$disconnect`, `/$default` or `/sendmessage`. The `$connect`
+/// and `$disconnect` events are `GET` requests, and the messages are `POST`
+/// requests with the message as body.
+///
 /// # Example
 ///
 /// ```no_run
@@ -61,7 +98,7 @@ impl Deref for Context {
 /// ```
 pub async fn run(ep: impl IntoEndpoint) -> Result<(), Error> {
     let ep = Arc::new(ep.map_to_response().into_endpoint());
-    lambda_http::run(service_fn(move |req: LambdaRequest| {
+    let mut adapter = Adapter::from(service_fn(move |req: LambdaRequest| {
         let ctx = req.lambda_context();
         let ep = ep.clone();
         async move {
@@ -89,12 +126,35 @@ pub async fn run(ep: impl IntoEndpoint) -> Result<(), Error> {
 
             Ok::<_, Error>(lambda_resp)
         }
-    }))
+    }));
+    lambda_runtime::run(service_fn(
+        move |mut event: LambdaEvent<LambdaEventPayload>| {
+            merge_multi_value_headers(&mut event.payload);
+            adapter.call(event)
+        },
+    ))
     .await
 }
 
+/// Removes the single-value headers which are also multi-value headers.
+///
+/// `lambda_http` extends the multi-value headers with the single-value
+/// headers, which only keeps the last value of the headers present in both.
+fn merge_multi_value_headers(payload: &mut LambdaEventPayload) {
+    let (headers, multi_value_headers) = match payload {
+        LambdaEventPayload::ApiGatewayV1(event) => (&mut event.headers, &event.multi_value_headers),
+        LambdaEventPayload::Alb(event) => (&mut event.headers, &event.multi_value_headers),
+        LambdaEventPayload::WebSocket(event) => (&mut event.headers, &event.multi_value_headers),
+        _ => return,
+    };
+    for name in multi_value_headers.keys() {
+        headers.remove(name);
+    }
+}
+
 fn from_lambda_request(req: LambdaRequest) -> Request {
-    let (parts, lambda_body) = req.into_parts();
+    let (mut parts, lambda_body) = req.into_parts();
+    route_websocket_event(&mut parts);
     let body = match lambda_body {
         LambdaBody::Empty => Body::empty(),
         LambdaBody::Text(data) => Body::from_string(data),
@@ -110,6 +170,35 @@ fn from_lambda_request(req: LambdaRequest) -> Request {
     req
 }
 
+/// Sets the path and the method of a WebSocket event according to its route
+/// key.
+fn route_websocket_event(parts: &mut Parts) {
+    let Some(RequestContext::WebSocket(ctx)) = parts.extensions.get::<RequestContext>() else {
+        return;
+    };
+    let Some(route_key) = ctx.route_key.as_deref() else {
+        return;
+    };
+    let method = match ctx.event_type.as_deref() {
+        Some("CONNECT" | "DISCONNECT") => Method::GET,
+        _ => Method::POST,
+    };
+    let path_and_query = match parts.uri.query() {
+        Some(query) => format!("/{route_key}?{query}"),
+        None => format!("/{route_key}"),
+    };
+    let Ok(path_and_query) = PathAndQuery::try_from(path_and_query) else {
+        return;
+    };
+
+    let mut uri_parts = parts.uri.clone().into_parts();
+    uri_parts.path_and_query = Some(path_and_query);
+    if let Ok(uri) = Uri::from_parts(uri_parts) {
+        parts.uri = uri;
+        parts.method = method;
+    }
+}
+
 impl<'a> FromRequest<'a> for &'a Context {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
         let ctx = match req.extensions().get::<Context>() {
@@ -119,3 +208,242 @@ impl<'a> FromRequest<'a> for &'a Context {
         Ok(ctx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use poem::{Route, get, handler, http::StatusCode, post, web::Path};
+
+    use super::*;
+
+    fn request(event: &str) -> Request {
+        let mut payload = serde_json::from_str::<LambdaEventPayload>(event).unwrap();
+        merge_multi_value_headers(&mut payload);
+        from_lambda_request(payload.into())
+    }
+
+    #[test]
+    fn alb() {
+        let req = request(
+            r#"{
+                "requestContext": {"elb": {"targetGroupArn": "arn:aws:elasticloadbalancing:region:123456789012:targetgroup/my-target-group/6d0ecf831eec9f09"}},
+                "httpMethod": "POST",
+                "path": "/items",
+                "queryStringParameters": {"tag": "b"},
+                "multiValueQueryStringParameters": {"tag": ["a", "b"]},
+                "headers": {"host": "example.com", "cookie": "b=2"},
+                "multiValueHeaders": {"host": ["example.com"], "cookie": ["a=1", "b=2"]},
+                "isBase64Encoded": false,
+                "body": "hello"
+            }"#,
+        );
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(req.uri().path(), "/items");
+        assert_eq!(req.uri().query(), Some("tag=a&tag=b"));
+        assert_eq!(
+            req.headers()
+                .get_all("cookie")
+                .iter()
+                .map(|value| value.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            ["a=1", "b=2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn alb_health_check() {
+        let req = request(
+            r#"{
+                "requestContext": {"elb": {"targetGroupArn": "arn:aws:elasticloadbalancing:region:123456789012:targetgroup/my-target-group/6d0ecf831eec9f09"}},
+                "httpMethod": "GET",
+                "path": "/health",
+                "queryStringParameters": {},
+                "headers": {"user-agent": "ELB-HealthChecker/2.0"},
+                "body": "",
+                "isBase64Encoded": false
+            }"#,
+        );
+
+        #[handler]
+        fn health() -> &'static str {
+            "ok"
+        }
+
+        let resp = Route::new()
+            .at("/health", get(health))
+            .get_response(req)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn websocket() {
+        #[handler]
+        fn connect() -> &'static str {
+            "connected"
+        }
+
+        #[handler]
+        fn message(Path(route): Path<String>, body: String) -> String {
+            format!("{route}: {body}")
+        }
+
+        let app = Route::new()
+            .at("/$connect", get(connect))
+            .at("/:route", post(message));
+
+        let req = request(
+            r#"{
+                "headers": {"Host": "abcd1234.execute-api.us-east-1.amazonaws.com"},
+                "multiValueHeaders": {"Host": ["abcd1234.execute-api.us-east-1.amazonaws.com"]},
+                "requestContext": {
+                    "routeKey": "$connect",
+                    "eventType": "CONNECT",
+                    "stage": "dev",
+                    "connectedAt": 1645556857902,
+                    "requestTimeEpoch": 1645556857902,
+                    "identity": {"sourceIp": "10.0.0.13"},
+                    "requestId": "abc=",
+                    "domainName": "abcd1234.execute-api.us-east-1.amazonaws.com",
+                    "connectionId": "conn=",
+                    "apiId": "abcd1234"
+                },
+                "isBase64Encoded": false
+            }"#,
+        );
+        assert_eq!(req.uri().path(), "/$connect");
+        let resp = app.get_response(req).await;
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "connected");
+
+        let req = request(
+            r#"{
+                "requestContext": {
+                    "routeKey": "sendmessage",
+                    "eventType": "MESSAGE",
+                    "stage": "dev",
+                    "connectedAt": 1645556857902,
+                    "requestTimeEpoch": 1645556857902,
+                    "identity": {"sourceIp": "10.0.0.13"},
+                    "requestId": "abc=",
+                    "domainName": "abcd1234.execute-api.us-east-1.amazonaws.com",
+                    "connectionId": "conn=",
+                    "apiId": "abcd1234"
+                },
+                "body": "hello",
+                "isBase64Encoded": false
+            }"#,
+        );
+        let resp = app.get_response(req).await;
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "sendmessage: hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn rest_api_metadata() {
+        let req = request(
+            r#"{
+                "resource": "/items",
+                "path": "/items",
+                "httpMethod": "GET",
+                "headers": {"Host": "example.com"},
+                "multiValueHeaders": {"Host": ["example.com"]},
+                "stageVariables": {"table": "items-dev"},
+                "requestContext": {
+                    "accountId": "123456789012",
+                    "resourceId": "abc",
+                    "stage": "$default",
+                    "requestId": "c6af9ac6-7b61-11e6-9a41-93e8deadbeef",
+                    "identity": {"sourceIp": "127.0.0.1"},
+                    "resourcePath": "/items",
+                    "httpMethod": "GET",
+                    "apiId": "1234567890",
+                    "authorizer": {
+                        "principalId": "user-1",
+                        "claims": {"sub": "user-1", "custom:tenant": "acme"},
+                        "plan": "pro"
+                    }
+                },
+                "isBase64Encoded": false
+            }"#,
+        );
+        let (req, mut body) = req.split();
+
+        let vars = StageVariables::from_request(&req, &mut body).await.unwrap();
+        assert_eq!(vars.first("table"), Some("items-dev"));
+
+        let ctx = EventContext::from_request(&req, &mut body).await.unwrap();
+        assert!(matches!(&*ctx, RequestContext::ApiGatewayV1(_)));
+
+        let authorizer = Authorizer::from_request(&req, &mut body).await.unwrap();
+        assert_eq!(authorizer.principal_id.as_deref(), Some("user-1"));
+        assert_eq!(authorizer.claims["custom:tenant"], "acme");
+        assert_eq!(authorizer.fields["plan"], "pro");
+    }
+
+    #[tokio::test]
+    async fn http_api_metadata() {
+        let req = request(
+            r#"{
+                "version": "2.0",
+                "routeKey": "$default",
+                "rawPath": "/items",
+                "rawQueryString": "",
+                "headers": {"host": "example.com"},
+                "requestContext": {
+                    "accountId": "123456789012",
+                    "apiId": "api-id",
+                    "authorizer": {
+                        "jwt": {
+                            "claims": {"sub": "user-2"},
+                            "scopes": ["read"]
+                        }
+                    },
+                    "domainName": "example.com",
+                    "http": {
+                        "method": "GET",
+                        "path": "/items",
+                        "protocol": "HTTP/1.1",
+                        "sourceIp": "127.0.0.1",
+                        "userAgent": "agent"
+                    },
+                    "requestId": "id",
+                    "routeKey": "$default",
+                    "stage": "$default",
+                    "time": "12/Mar/2020:19:03:58 +0000",
+                    "timeEpoch": 1583348638390
+                },
+                "isBase64Encoded": false
+            }"#,
+        );
+        let (req, mut body) = req.split();
+
+        let authorizer = Authorizer::from_request(&req, &mut body).await.unwrap();
+        assert_eq!(authorizer.claims["sub"], "user-2");
+        assert_eq!(authorizer.scopes, ["read"]);
+        assert!(
+            StageVariables::from_request(&req, &mut body)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_authorizer() {
+        let req = request(
+            r#"{
+                "requestContext": {"elb": {"targetGroupArn": "arn:aws:elasticloadbalancing:region:123456789012:targetgroup/my-target-group/6d0ecf831eec9f09"}},
+                "httpMethod": "GET",
+                "path": "/",
+                "headers": {},
+                "body": "",
+                "isBase64Encoded": false
+            }"#,
+        );
+        let (req, mut body) = req.split();
+
+        let err = Authorizer::from_request(&req, &mut body).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::UNAUTHORIZED);
+    }
+}
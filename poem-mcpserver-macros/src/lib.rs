@@ -1,3 +1,4 @@
+mod resources;
 mod tools;
 mod utils;
 
@@ -33,3 +34,14 @@ pub fn Tools(args: TokenStream, input: TokenStream) -> TokenStream {
         Err(err) => err.write_errors().into(),
     }
 }
+
+#[proc_macro_attribute]
+#[allow(non_snake_case)]
+pub fn Resources(args: TokenStream, input: TokenStream) -> TokenStream {
+    let resources_args = parse_nested_meta!(resources::ResourcesArgs, args);
+    let item_impl = parse_macro_input!(input as ItemImpl);
+    match resources::generate(resources_args, item_impl) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.write_errors().into(),
+    }
+}
@@ -0,0 +1,133 @@
+use darling::{Error, FromMeta, Result};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{FnArg, ImplItem, ItemImpl, Pat};
+
+use crate::utils::*;
+
+#[derive(FromMeta, Default)]
+pub(crate) struct ResourcesArgs {}
+
+#[derive(FromMeta, Default)]
+pub(crate) struct ResourceArgs {
+    uri: Option<String>,
+    name: Option<String>,
+    mime_type: Option<String>,
+}
+
+#[derive(FromMeta, Default)]
+pub(crate) struct VariableArgs {
+    name: Option<String>,
+}
+
+pub(crate) fn generate(_args: ResourcesArgs, mut item_impl: ItemImpl) -> Result<TokenStream> {
+    let crate_name = get_crate_name();
+    let ident = item_impl.self_ty.clone();
+    let mut templates = vec![];
+    let mut read = vec![];
+
+    for item in &mut item_impl.items {
+        if let ImplItem::Fn(method) = item {
+            let resource_args = parse_mcp_attrs::<ResourceArgs>(&method.attrs)?;
+            remove_mcp_attrs(&mut method.attrs);
+
+            let Some(uri_template) = &resource_args.uri else {
+                return Err(
+                    Error::custom("the `uri` template of the resource is required.")
+                        .with_span(&method.sig.ident),
+                );
+            };
+            let name = match &resource_args.name {
+                Some(name) => name.clone(),
+                None => method.sig.ident.to_string(),
+            };
+            let description = get_description(&method.attrs).unwrap_or_default();
+            let mime_type = match &resource_args.mime_type {
+                Some(mime_type) => quote!(::std::option::Option::Some(#mime_type)),
+                None => quote!(::std::option::Option::None),
+            };
+
+            if method.sig.asyncness.is_none() {
+                return Err(Error::custom("must be asynchronous").with_span(&method.sig.ident));
+            }
+
+            if !matches!(method.sig.inputs.first(), Some(FnArg::Receiver(_))) {
+                return Err(
+                    Error::custom("the first parameter must be a `&self` receiver.")
+                        .with_span(&method.sig.ident),
+                );
+            }
+
+            let mut variables = vec![];
+            let mut call_args = vec![];
+            for arg in method.sig.inputs.iter_mut().skip(1) {
+                let FnArg::Typed(pat) = arg else {
+                    unreachable!()
+                };
+                let Pat::Ident(ident) = &*pat.pat else {
+                    return Err(Error::custom("expected ident").with_span(&pat.pat));
+                };
+
+                let variable_args = parse_mcp_attrs::<VariableArgs>(&pat.attrs)?;
+                remove_mcp_attrs(&mut pat.attrs);
+                let variable_name = match &variable_args.name {
+                    Some(name) => name.clone(),
+                    None => ident.ident.to_string(),
+                };
+                if !uri_template.contains(&format!("{{{variable_name}}}"))
+                    && !uri_template.contains(&format!("{{+{variable_name}}}"))
+                {
+                    return Err(Error::custom(format!(
+                        "the variable `{variable_name}` is not in the URI template."
+                    ))
+                    .with_span(&pat.pat));
+                }
+
+                let ty = &pat.ty;
+                let arg_ident = &ident.ident;
+                variables.push(quote! {
+                    let #arg_ident: #ty = #crate_name::private::parse_variable(&variables, #variable_name)?;
+                });
+                call_args.push(quote!(#arg_ident));
+            }
+
+            templates.push(quote! {
+                #crate_name::protocol::resources::ResourceTemplate {
+                    uri_template: ::std::borrow::Cow::Borrowed(#uri_template),
+                    name: ::std::borrow::Cow::Borrowed(#name),
+                    description: ::std::borrow::Cow::Borrowed(#description),
+                    mime_type: #mime_type.map(::std::borrow::Cow::Borrowed),
+                },
+            });
+
+            let method_ident = &method.sig.ident;
+            read.push(quote! {
+                if let ::std::option::Option::Some(variables) = #crate_name::resource::UriTemplate::new(#uri_template).match_uri(uri) {
+                    #(#variables)*
+                    let contents = self.#method_ident(#(#call_args),*).await;
+                    return ::std::result::Result::Ok(#crate_name::protocol::resources::ResourcesReadResponse {
+                        contents: #crate_name::private::IntoResourceContents::into_resource_contents(contents, uri, #mime_type)?,
+                    });
+                }
+            });
+        }
+    }
+
+    Ok(quote! {
+        #item_impl
+
+        impl #crate_name::resource::Resources for #ident {
+            fn templates() -> ::std::vec::Vec<#crate_name::protocol::resources::ResourceTemplate> {
+                ::std::vec![#(#templates)*]
+            }
+
+            async fn read(
+                &mut self,
+                uri: &::std::primitive::str,
+            ) -> ::std::result::Result<#crate_name::protocol::resources::ResourcesReadResponse, #crate_name::protocol::rpc::RpcError> {
+                #(#read)*
+                ::std::result::Result::Err(#crate_name::private::resource_not_found(uri))
+            }
+        }
+    })
+}
@@ -1,7 +1,7 @@
 use darling::{Error, FromMeta, Result};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
-use syn::{FnArg, ImplItem, ItemImpl, Pat};
+use syn::{Expr, FnArg, ImplItem, ItemImpl, Lit, Pat, Path, ReturnType, Type};
 
 use crate::utils::*;
 
@@ -11,11 +11,131 @@ pub(crate) struct ToolsArgs {}
 #[derive(FromMeta, Default)]
 pub(crate) struct ToolArgs {
     name: Option<String>,
+    title: Option<String>,
+    read_only_hint: Option<bool>,
+    destructive_hint: Option<bool>,
+    idempotent_hint: Option<bool>,
+    open_world_hint: Option<bool>,
+}
+
+impl ToolArgs {
+    fn annotations(&self, crate_name: &TokenStream) -> TokenStream {
+        let ToolArgs {
+            title,
+            read_only_hint,
+            destructive_hint,
+            idempotent_hint,
+            open_world_hint,
+            ..
+        } = self;
+        if title.is_none()
+            && read_only_hint.is_none()
+            && destructive_hint.is_none()
+            && idempotent_hint.is_none()
+            && open_world_hint.is_none()
+        {
+            return quote!(::std::option::Option::None);
+        }
+
+        let title = match title {
+            Some(title) => {
+                quote!(::std::option::Option::Some(::std::borrow::Cow::Borrowed(#title)))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+        let hint = |hint: &Option<bool>| match hint {
+            Some(hint) => quote!(::std::option::Option::Some(#hint)),
+            None => quote!(::std::option::Option::None),
+        };
+        let read_only_hint = hint(read_only_hint);
+        let destructive_hint = hint(destructive_hint);
+        let idempotent_hint = hint(idempotent_hint);
+        let open_world_hint = hint(open_world_hint);
+        quote! {
+            ::std::option::Option::Some(#crate_name::protocol::tool::ToolAnnotations {
+                title: #title,
+                read_only_hint: #read_only_hint,
+                destructive_hint: #destructive_hint,
+                idempotent_hint: #idempotent_hint,
+                open_world_hint: #open_world_hint,
+            })
+        }
+    }
 }
 
 #[derive(FromMeta, Default)]
 pub(crate) struct ParamArgs {
     name: Option<String>,
+    default: Option<DefaultValue>,
+    validator: Option<Validators>,
+}
+
+/// The default value of a parameter, `Default::default()` or the value
+/// returned by a function.
+pub(crate) enum DefaultValue {
+    Default,
+    Function(Path),
+}
+
+impl FromMeta for DefaultValue {
+    fn from_word() -> Result<Self> {
+        Ok(DefaultValue::Default)
+    }
+
+    fn from_value(value: &Lit) -> Result<Self> {
+        match value {
+            Lit::Str(str) => Ok(DefaultValue::Function(syn::parse_str(&str.value())?)),
+            _ => Err(Error::unexpected_lit_type(value).with_span(value)),
+        }
+    }
+}
+
+#[derive(FromMeta, Default)]
+pub(crate) struct Validators {
+    minimum: Option<Expr>,
+    maximum: Option<Expr>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl Validators {
+    /// Returns the `schemars` attributes adding the constraints to the schema.
+    fn schema_attrs(&self) -> TokenStream {
+        let mut attrs = vec![];
+        if self.minimum.is_some() || self.maximum.is_some() {
+            let min = self.minimum.as_ref().map(|min| quote!(min = #min));
+            let max = self.maximum.as_ref().map(|max| quote!(max = #max));
+            let args = min.into_iter().chain(max);
+            attrs.push(quote!(#[schemars(range(#(#args),*))]));
+        }
+        if self.min_length.is_some() || self.max_length.is_some() {
+            let min = self.min_length.map(|min| quote!(min = #min));
+            let max = self.max_length.map(|max| quote!(max = #max));
+            let args = min.into_iter().chain(max);
+            attrs.push(quote!(#[schemars(length(#(#args),*))]));
+        }
+        quote!(#(#attrs)*)
+    }
+
+    /// Returns the statements checking the argument of the parameter.
+    fn checks(&self, crate_name: &TokenStream, name: &str) -> TokenStream {
+        let mut validators = vec![];
+        if let Some(minimum) = &self.minimum {
+            validators.push(quote!(Minimum((#minimum) as ::std::primitive::f64)));
+        }
+        if let Some(maximum) = &self.maximum {
+            validators.push(quote!(Maximum((#maximum) as ::std::primitive::f64)));
+        }
+        if let Some(min_length) = self.min_length {
+            validators.push(quote!(MinLength(#min_length)));
+        }
+        if let Some(max_length) = self.max_length {
+            validators.push(quote!(MaxLength(#max_length)));
+        }
+        quote! {
+            #(#crate_name::private::Validator::#validators.check(&arguments, #name)?;)*
+        }
+    }
 }
 
 pub(crate) fn generate(_args: ToolsArgs, mut item_impl: ItemImpl) -> Result<TokenStream> {
@@ -57,6 +177,8 @@ pub(crate) fn generate(_args: ToolsArgs, mut item_impl: ItemImpl) -> Result<Toke
             let request_type = format_ident!("{}_Request", method.sig.ident);
             let mut args = vec![];
             let mut arg_names = vec![];
+            let mut call_args = vec![];
+            let mut checks = vec![];
 
             for arg in method.sig.inputs.iter_mut().skip(1) {
                 let FnArg::Typed(pat) = arg else {
@@ -66,31 +188,67 @@ pub(crate) fn generate(_args: ToolsArgs, mut item_impl: ItemImpl) -> Result<Toke
                     return Err(Error::custom("expected ident").with_span(&pat.pat));
                 };
 
+                if let Some(arg) = context_arg(&pat.ty) {
+                    call_args.push(arg);
+                    continue;
+                }
+
                 let param_args = parse_mcp_attrs::<ParamArgs>(&pat.attrs)?;
-                let param_name = match &param_args.name {
-                    Some(name) => quote!(#name),
-                    None => quote!(#ident),
-                };
+                remove_mcp_attrs(&mut pat.attrs);
+                let param_name = &ident.ident;
+                let rename = param_args
+                    .name
+                    .as_ref()
+                    .map(|name| quote!(#[serde(rename = #name)]));
+                let default = param_args.default.as_ref().map(|default| match default {
+                    DefaultValue::Default => quote!(#[serde(default)]),
+                    DefaultValue::Function(path) => {
+                        let path = quote!(#path).to_string();
+                        quote!(#[serde(default = #path)])
+                    }
+                });
+                let schema_attrs = param_args.validator.as_ref().map(Validators::schema_attrs);
+                if let Some(validator) = &param_args.validator {
+                    let name = param_args
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| param_name.to_string());
+                    checks.push(validator.checks(&crate_name, &name));
+                }
                 let param_desc = get_description(&pat.attrs).map(|desc| quote!( #[doc = #desc]));
                 remove_description(&mut pat.attrs);
                 let param_ty = &pat.ty;
                 args.push(quote! {
                     #param_desc
+                    #rename
+                    #default
+                    #schema_attrs
                     #param_name: #param_ty,
                 });
                 arg_names.push(quote! {
                     #param_name
                 });
+                call_args.push(quote! {
+                    #param_name
+                });
             }
 
+            let output_ty = match &method.sig.output {
+                ReturnType::Default => quote!(()),
+                ReturnType::Type(_, ty) => quote!(#ty),
+            };
+
+            let annotations = tool_args.annotations(&crate_name);
             tools_descriptions.push(quote! {
                 #crate_name::protocol::tool::Tool {
-                    name: #tool_name,
-                    description: #tool_description,
+                    name: ::std::borrow::Cow::Borrowed(#tool_name),
+                    description: ::std::borrow::Cow::Borrowed(#tool_description),
                     input_schema: {
                         let schema = schemars::SchemaGenerator::default().into_root_schema_for::<#request_type>();
                         #crate_name::private::serde_json::to_value(schema).expect("serialize schema")
                     },
+                    output_schema: <#output_ty as #crate_name::tool::IntoToolResponse>::output_schema(),
+                    annotations: #annotations,
                 },
             });
 
@@ -110,9 +268,10 @@ pub(crate) fn generate(_args: ToolsArgs, mut item_impl: ItemImpl) -> Result<Toke
 
             call.push(quote! {
                 #tool_name => {
+                    #(#checks)*
                     let #request_type { #(#arg_names),* } = #crate_name::private::serde_json::from_value(arguments.clone())
                         .map_err(|e| #crate_name::protocol::rpc::RpcError::invalid_params(format!("invalid parameters: {}", e)))?;
-                    let response = self.#method_ident(#(#arg_names),*).await;
+                    let response = self.#method_ident(#(#call_args),*).await;
                     Ok(#crate_name::tool::IntoToolResponse::into_tool_response(response))
                 }
             });
@@ -125,19 +284,28 @@ pub(crate) fn generate(_args: ToolsArgs, mut item_impl: ItemImpl) -> Result<Toke
         #(#req_types)*
 
         impl #crate_name::tool::Tools for #ident {
-            fn instructions() -> &'static ::std::primitive::str {
-                #instructions
+            fn instructions() -> ::std::borrow::Cow<'static, ::std::primitive::str> {
+                ::std::borrow::Cow::Borrowed(#instructions)
             }
 
             fn list() -> ::std::vec::Vec<#crate_name::protocol::tool::Tool> {
                 ::std::vec![#(#tools_descriptions)*]
             }
 
+            fn contains(name: &::std::primitive::str) -> ::std::primitive::bool {
+                match name {
+                    #(#contains)*
+                    _ => false,
+                }
+            }
+
             async fn call(
                 &mut self,
                 name: &::std::primitive::str,
                 arguments: #crate_name::private::serde_json::Value,
+                ctx: &#crate_name::tool::ToolContext,
             ) -> ::std::result::Result<#crate_name::protocol::tool::ToolsCallResponse, #crate_name::protocol::rpc::RpcError> {
+                let _ = ctx;
                 match name {
                     #(#call)*
                     _ => Err(#crate_name::protocol::rpc::RpcError::method_not_found(format!("method not found: {}", name))),
@@ -146,3 +314,26 @@ pub(crate) fn generate(_args: ToolsArgs, mut item_impl: ItemImpl) -> Result<Toke
         }
     })
 }
+
+/// Returns the argument passed to the parameters that are not tool arguments,
+/// `&ToolContext`, `SamplingClient` and `ElicitationClient`.
+fn context_arg(ty: &Type) -> Option<TokenStream> {
+    match ty {
+        Type::Reference(reference) if last_ident_is(&reference.elem, "ToolContext") => {
+            Some(quote!(ctx))
+        }
+        ty if last_ident_is(ty, "SamplingClient") => Some(quote!(ctx.sampling())),
+        ty if last_ident_is(ty, "ElicitationClient") => Some(quote!(ctx.elicitation())),
+        _ => None,
+    }
+}
+
+fn last_ident_is(ty: &Type, name: &str) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == name)
+}
@@ -0,0 +1,201 @@
+use std::{future::Future, sync::Arc};
+
+use poem::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+    endpoint::ShutdownHooks,
+    http::{StatusCode, header},
+    web::Json,
+};
+use serde::Serialize;
+
+use crate::auth::Principal;
+
+/// Represents a type that can validate bearer tokens.
+///
+/// This trait is implemented for the functions taking the token and returning
+/// a future of `Option<Principal>`.
+pub trait TokenValidator: Send + Sync + 'static {
+    /// Validates the token, and returns the principal it was issued to, or
+    /// `None` if the token is invalid or expired.
+    fn validate(&self, token: &str) -> impl Future<Output = Option<Principal>> + Send;
+}
+
+impl<F, Fut> TokenValidator for F
+where
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Option<Principal>> + Send,
+{
+    fn validate(&self, token: &str) -> impl Future<Output = Option<Principal>> + Send {
+        (self)(token.to_string())
+    }
+}
+
+/// Middleware for validating the bearer token of the requests.
+///
+/// The requests without a valid token are rejected with `401 Unauthorized`,
+/// and the requests of the principals missing a required scope are rejected
+/// with `403 Forbidden`.
+///
+/// ```no_run
+/// use poem::EndpointExt;
+/// use poem_mcpserver::{
+///     McpServer,
+///     auth::{BearerAuth, Principal},
+///     streamable_http,
+/// };
+///
+/// let ep = streamable_http::endpoint(|_| McpServer::new()).with(
+///     BearerAuth::new(|token: String| async move {
+///         (token == "secret").then(|| Principal::new("alice").with_scopes(["mcp"]))
+///     })
+///     .required_scopes(["mcp"])
+///     .resource_metadata("https://example.com/.well-known/oauth-protected-resource"),
+/// );
+/// ```
+pub struct BearerAuth<V> {
+    validator: Arc<V>,
+    required_scopes: Arc<Vec<String>>,
+    resource_metadata: Option<String>,
+}
+
+impl<V: TokenValidator> BearerAuth<V> {
+    /// Creates a `BearerAuth` middleware with the token validator.
+    pub fn new(validator: V) -> Self {
+        Self {
+            validator: Arc::new(validator),
+            required_scopes: Default::default(),
+            resource_metadata: None,
+        }
+    }
+
+    /// Sets the scopes that a principal must have.
+    #[must_use]
+    pub fn required_scopes<I, T>(self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            required_scopes: Arc::new(scopes.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Sets the URL of the protected resource metadata, which is sent in the
+    /// `WWW-Authenticate` header of the unauthorized responses.
+    #[must_use]
+    pub fn resource_metadata(self, url: impl Into<String>) -> Self {
+        Self {
+            resource_metadata: Some(url.into()),
+            ..self
+        }
+    }
+}
+
+impl<E, V> Middleware<E> for BearerAuth<V>
+where
+    E: Endpoint,
+    V: TokenValidator,
+{
+    type Output = BearerAuthEndpoint<E, V>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        BearerAuthEndpoint {
+            inner: ep,
+            validator: self.validator.clone(),
+            required_scopes: self.required_scopes.clone(),
+            resource_metadata: self.resource_metadata.clone(),
+        }
+    }
+}
+
+/// Endpoint for the BearerAuth middleware.
+pub struct BearerAuthEndpoint<E, V> {
+    inner: E,
+    validator: Arc<V>,
+    required_scopes: Arc<Vec<String>>,
+    resource_metadata: Option<String>,
+}
+
+impl<E, V> BearerAuthEndpoint<E, V> {
+    fn challenge(&self, status: StatusCode, error: Option<&str>) -> Response {
+        let mut params = vec![];
+        if let Some(url) = &self.resource_metadata {
+            params.push(format!("resource_metadata=\"{url}\""));
+        }
+        if let Some(error) = error {
+            params.push(format!("error=\"{error}\""));
+        }
+        if status == StatusCode::FORBIDDEN {
+            params.push(format!("scope=\"{}\"", self.required_scopes.join(" ")));
+        }
+
+        let value = match params.is_empty() {
+            true => "Bearer".to_string(),
+            false => format!("Bearer {}", params.join(", ")),
+        };
+        Response::builder()
+            .status(status)
+            .header(header::WWW_AUTHENTICATE, value)
+            .finish()
+    }
+}
+
+impl<E, V> Endpoint for BearerAuthEndpoint<E, V>
+where
+    E: Endpoint,
+    V: TokenValidator,
+{
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let Some(token) = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return Ok(self.challenge(StatusCode::UNAUTHORIZED, None));
+        };
+
+        let Some(principal) = self.validator.validate(token.trim()).await else {
+            tracing::info!("invalid bearer token");
+            return Ok(self.challenge(StatusCode::UNAUTHORIZED, Some("invalid_token")));
+        };
+
+        if !self
+            .required_scopes
+            .iter()
+            .all(|scope| principal.has_scope(scope))
+        {
+            tracing::info!(subject = principal.subject(), "insufficient scope");
+            return Ok(self.challenge(StatusCode::FORBIDDEN, Some("insufficient_scope")));
+        }
+
+        req.extensions_mut().insert(principal);
+        self.inner.call(req).await.map(IntoResponse::into_response)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+/// The OAuth 2.0 protected resource metadata, as described in
+/// [RFC 9728](https://datatracker.ietf.org/doc/html/rfc9728).
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtectedResourceMetadata {
+    /// The resource identifier.
+    pub resource: String,
+    /// The authorization servers that can issue tokens for the resource.
+    pub authorization_servers: Vec<String>,
+    /// The scopes used to access the resource.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scopes_supported: Vec<String>,
+}
+
+/// An endpoint serving the protected resource metadata, usually mounted at
+/// `/.well-known/oauth-protected-resource`.
+pub fn protected_resource_metadata(metadata: ProtectedResourceMetadata) -> impl Endpoint {
+    poem::endpoint::make_sync(move |_| Json(metadata.clone()))
+}
@@ -0,0 +1,64 @@
+//! Authentication and authorization.
+//!
+//! The [`BearerAuth`] middleware validates the OAuth 2.1 bearer token of the
+//! requests sent to the streamable http endpoint, as described in the
+//! [MCP authorization](https://modelcontextprotocol.io/specification/2025-03-26/basic/authorization)
+//! specification. The verified [`Principal`] is available to the tools with
+//! [`ToolContext::principal`](crate::tool::ToolContext::principal), and
+//! [`McpServer::authorize_tools`](crate::McpServer::authorize_tools) restricts
+//! the tools of each principal.
+
+#[cfg(feature = "streamable-http")]
+mod bearer;
+
+#[cfg(feature = "streamable-http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "streamable-http")))]
+pub use bearer::{
+    BearerAuth, BearerAuthEndpoint, ProtectedResourceMetadata, TokenValidator,
+    protected_resource_metadata,
+};
+
+/// An authenticated identity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    subject: String,
+    scopes: Vec<String>,
+}
+
+impl Principal {
+    /// Creates a principal with the given subject.
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            scopes: vec![],
+        }
+    }
+
+    /// Sets the scopes granted to the principal.
+    #[must_use]
+    pub fn with_scopes<I, T>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the subject of the principal.
+    #[inline]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the scopes granted to the principal.
+    #[inline]
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Returns `true` if the scope is granted to the principal.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
@@ -4,6 +4,7 @@ use std::fmt::Display;
 
 use base64::{Engine, engine::general_purpose::STANDARD};
 use serde::Serialize;
+use serde_json::Value;
 
 use crate::protocol::content::Content;
 
@@ -37,6 +38,38 @@ where
     }
 }
 
+macro_rules! impl_into_contents_for_tuple {
+    ($($ty:ident),*) => {
+        impl<$($ty),*> IntoContents for ($($ty,)*)
+        where
+            $($ty: IntoContent,)*
+        {
+            #[allow(non_snake_case)]
+            fn into_contents(self) -> Vec<Content> {
+                let ($($ty,)*) = self;
+                vec![$($ty.into_content()),*]
+            }
+        }
+    };
+}
+
+impl_into_contents_for_tuple!(T1, T2);
+impl_into_contents_for_tuple!(T1, T2, T3);
+impl_into_contents_for_tuple!(T1, T2, T3, T4);
+impl_into_contents_for_tuple!(T1, T2, T3, T4, T5);
+impl_into_contents_for_tuple!(T1, T2, T3, T4, T5, T6);
+impl_into_contents_for_tuple!(T1, T2, T3, T4, T5, T6, T7);
+impl_into_contents_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8);
+
+/// A content is returned as is, so a vector of contents can mix different
+/// kinds of content.
+impl IntoContent for Content {
+    #[inline]
+    fn into_content(self) -> Content {
+        self
+    }
+}
+
 /// A text response.
 #[derive(Debug)]
 pub struct Text<T>(pub T);
@@ -53,6 +86,8 @@ where
 }
 
 /// An image response.
+///
+/// The data is encoded in base64 when converted into a content.
 #[derive(Debug)]
 pub struct Image<T> {
     data: T,
@@ -60,7 +95,7 @@ pub struct Image<T> {
 }
 
 impl<T> Image<T> {
-    /// Creates a image content.
+    /// Creates an image content.
     #[inline]
     pub fn new(data: T, mime_type: impl Into<String>) -> Self {
         Self {
@@ -82,17 +117,56 @@ where
     }
 }
 
+/// An audio response.
+///
+/// The data is encoded in base64 when converted into a content.
+#[derive(Debug)]
+pub struct Audio<T> {
+    data: T,
+    mime_type: String,
+}
+
+impl<T> Audio<T> {
+    /// Creates an audio content.
+    #[inline]
+    pub fn new(data: T, mime_type: impl Into<String>) -> Self {
+        Self {
+            data,
+            mime_type: mime_type.into(),
+        }
+    }
+}
+
+impl<T> IntoContent for Audio<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn into_content(self) -> Content {
+        Content::Audio {
+            data: STANDARD.encode(self.data),
+            mime_type: self.mime_type,
+        }
+    }
+}
+
 /// A Json response.
+///
+/// The value is returned as structured content, and its JSON schema is
+/// advertised as the output schema of the tool. The serialized value is also
+/// returned as a text content for the clients that don't support structured
+/// content.
 #[derive(Debug, Clone, Copy)]
 pub struct Json<T>(pub T);
 
-impl<T> IntoContent for Json<T>
+impl<T> Json<T>
 where
     T: Serialize,
 {
-    fn into_content(self) -> Content {
-        Content::Text {
-            text: serde_json::to_string(&self.0).unwrap_or_default(),
-        }
+    pub(crate) fn into_structured_content(self) -> (Vec<Content>, Value) {
+        let value = serde_json::to_value(&self.0).unwrap_or_default();
+        let text = Content::Text {
+            text: value.to_string(),
+        };
+        (vec![text], value)
     }
 }
@@ -0,0 +1,125 @@
+//! Elicitation, the requests sent by the server to the client to ask the user
+//! for additional input.
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    peer::Peer,
+    protocol::{
+        elicitation::{ElicitAction, ElicitRequest, ElicitResult},
+        rpc::RpcError,
+    },
+    tool::schema_for,
+};
+
+/// The response of the user to an elicitation request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Elicitation<T> {
+    /// The user submitted the requested input.
+    Accept(T),
+    /// The user explicitly declined to submit the input.
+    Decline,
+    /// The user dismissed the request.
+    Cancel,
+}
+
+/// A handle to ask the user of the client for additional input during a tool
+/// call.
+///
+/// A tool receives the handle by declaring an `ElicitationClient` parameter,
+/// or with [`ToolContext::elicitation`](crate::tool::ToolContext::elicitation).
+///
+/// ```
+/// use poem_mcpserver::{
+///     Tools,
+///     content::Text,
+///     elicitation::{Elicitation, ElicitationClient},
+/// };
+///
+/// #[derive(serde::Deserialize, schemars::JsonSchema)]
+/// struct Confirmation {
+///     confirm: bool,
+/// }
+///
+/// struct Files;
+///
+/// #[Tools]
+/// impl Files {
+///     /// Delete a file.
+///     async fn delete(&self, elicitation: ElicitationClient, path: String) -> Text<String> {
+///         match elicitation
+///             .elicit::<Confirmation>(format!("Delete {path}?"))
+///             .await
+///         {
+///             Ok(Elicitation::Accept(Confirmation { confirm: true })) => {
+///                 Text(format!("{path} deleted"))
+///             }
+///             _ => Text(format!("{path} kept")),
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ElicitationClient {
+    peer: Peer,
+}
+
+impl ElicitationClient {
+    pub(crate) fn new(peer: Peer) -> Self {
+        Self { peer }
+    }
+
+    /// Returns `true` if the client declared the elicitation capability.
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        self.peer.capabilities.elicitation.is_some()
+    }
+
+    /// Sends an `elicitation/create` request to the client, asking the user
+    /// for an input of type `T`, and waits for the response of the user.
+    ///
+    /// The requested schema is generated from `T`, which should be an object
+    /// with properties of primitive types.
+    pub async fn elicit<T>(&self, message: impl Into<String>) -> Result<Elicitation<T>, RpcError>
+    where
+        T: DeserializeOwned + JsonSchema,
+    {
+        if !self.is_supported() {
+            return Err(RpcError::method_not_found(
+                "the client doesn't support elicitation",
+            ));
+        }
+
+        let mut requested_schema = schema_for::<T>();
+        if let Some(schema) = requested_schema.as_object_mut() {
+            schema.remove("$schema");
+        }
+        let result = self
+            .peer
+            .send_request(
+                "elicitation/create",
+                ElicitRequest {
+                    message: message.into(),
+                    requested_schema,
+                },
+            )
+            .await?;
+        let result = serde_json::from_value::<ElicitResult>(result).map_err(|err| {
+            RpcError::internal_error(format!("invalid elicitation result: {err}"))
+        })?;
+
+        match result.action {
+            ElicitAction::Accept => {
+                let content = result.content.unwrap_or_default();
+                serde_json::from_value(content)
+                    .map(Elicitation::Accept)
+                    .map_err(|err| {
+                        RpcError::invalid_params(format!("invalid elicitation content: {err}"))
+                    })
+            }
+            ElicitAction::Decline => Ok(Elicitation::Decline),
+            ElicitAction::Cancel => Ok(Elicitation::Cancel),
+        }
+    }
+}
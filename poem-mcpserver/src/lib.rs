@@ -6,20 +6,39 @@
 #![warn(rustdoc::broken_intra_doc_links)]
 #![warn(missing_docs)]
 
+pub mod auth;
 pub mod content;
+pub mod elicitation;
+#[cfg(feature = "tracing-subscriber")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing-subscriber")))]
+pub mod logging;
+#[cfg(feature = "opentelemetry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+pub mod metrics;
+mod peer;
 pub mod protocol;
+pub mod resource;
+pub mod sampling;
 mod server;
 pub mod stdio;
 #[cfg(feature = "streamable-http")]
 #[cfg_attr(docsrs, doc(cfg(feature = "streamable-http")))]
 pub mod streamable_http;
+#[cfg(feature = "test")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test")))]
+pub mod test;
 pub mod tool;
-pub use poem_mcpserver_macros::Tools;
+mod validation;
+pub use poem_mcpserver_macros::{Resources, Tools};
 pub use server::McpServer;
 
 #[doc(hidden)]
 pub mod private {
     pub use serde_json;
 
-    pub use crate::tool::IntoToolResponse;
+    pub use crate::{
+        resource::{IntoResourceContents, parse_variable, resource_not_found},
+        tool::IntoToolResponse,
+        validation::Validator,
+    };
 }
@@ -0,0 +1,93 @@
+//! Forwarding of the `tracing` events to the client.
+
+use std::{fmt, future::Future};
+
+use serde_json::{Map, Value};
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+use crate::{peer::Peer, protocol::logging::LoggingLevel};
+
+tokio::task_local! {
+    static CURRENT_PEER: Peer;
+}
+
+/// Runs the tool call, the events emitted while it runs are sent to the peer.
+pub(crate) async fn scope<F: Future>(peer: Peer, f: F) -> F::Output {
+    CURRENT_PEER.scope(peer, f).await
+}
+
+/// A tracing layer that sends the events emitted during a tool call to the
+/// client, as `notifications/message` log messages.
+///
+/// The fields of the event are sent as the data of the message, and its target
+/// as the name of the logger.
+///
+/// ```
+/// use poem_mcpserver::logging::LoggingLayer;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let subscriber = tracing_subscriber::registry().with(LoggingLayer);
+/// tracing::subscriber::set_global_default(subscriber).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoggingLayer;
+
+impl<S: Subscriber> Layer<S> for LoggingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // the events of the server itself are not about the tool call
+        if metadata.target().starts_with("poem_mcpserver") {
+            return;
+        }
+
+        _ = CURRENT_PEER.try_with(|peer| {
+            let level = match *metadata.level() {
+                Level::ERROR => LoggingLevel::Error,
+                Level::WARN => LoggingLevel::Warning,
+                Level::INFO => LoggingLevel::Info,
+                Level::DEBUG | Level::TRACE => LoggingLevel::Debug,
+            };
+            let mut visitor = JsonVisitor::default();
+            event.record(&mut visitor);
+            peer.log(
+                level,
+                Some(metadata.target().to_string()),
+                Value::Object(visitor.0),
+            );
+        });
+    }
+}
+
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
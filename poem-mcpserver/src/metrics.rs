@@ -0,0 +1,74 @@
+//! Metrics of the requests with OpenTelemetry.
+
+use std::time::Duration;
+
+use opentelemetry::{
+    KeyValue, global,
+    metrics::{Counter, Histogram},
+};
+
+use crate::protocol::rpc::RpcError;
+
+/// The metrics of the requests handled by a server, recorded with the global
+/// meter provider.
+///
+/// The metrics are labeled with the method of the request, the name of the
+/// called tool and the error code.
+#[derive(Debug, Clone)]
+pub struct OpenTelemetryMetrics {
+    request_count: Counter<u64>,
+    error_count: Counter<u64>,
+    duration: Histogram<f64>,
+}
+
+impl Default for OpenTelemetryMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenTelemetryMetrics {
+    /// Creates the metrics with the `poem-mcpserver` meter.
+    pub fn new() -> Self {
+        let meter = global::meter("poem-mcpserver");
+        Self {
+            request_count: meter
+                .u64_counter("mcp_requests_count")
+                .with_description("total request count (since start of service)")
+                .build(),
+            error_count: meter
+                .u64_counter("mcp_errors_count")
+                .with_description("failed request count (since start of service)")
+                .build(),
+            duration: meter
+                .f64_histogram("mcp_request_duration_ms")
+                .with_unit("milliseconds")
+                .with_description(
+                    "request duration histogram (in milliseconds, since start of service)",
+                )
+                .build(),
+        }
+    }
+
+    pub(crate) fn record(
+        &self,
+        method: &'static str,
+        tool: Option<&str>,
+        elapsed: Duration,
+        error: Option<&RpcError>,
+    ) {
+        let mut labels = Vec::with_capacity(3);
+        labels.push(KeyValue::new("rpc.method", method));
+        if let Some(tool) = tool {
+            labels.push(KeyValue::new("mcp.tool.name", tool.to_string()));
+        }
+        if let Some(error) = error {
+            labels.push(KeyValue::new("rpc.jsonrpc.error_code", error.code() as i64));
+            self.error_count.add(1, &labels);
+        }
+
+        self.request_count.add(1, &labels);
+        self.duration
+            .record(elapsed.as_secs_f64() * 1000.0, &labels);
+    }
+}
@@ -0,0 +1,209 @@
+//! The client connected to the server.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::{
+    protocol::{
+        JSON_RPC_VERSION,
+        initialize::ClientCapabilities,
+        logging::{LoggingLevel, LoggingMessageNotification},
+        roots::{ListRootsRequest, ListRootsResult, Root},
+        rpc::{ClientResponse, Notification, RequestId, RpcError, ServerRequest},
+    },
+    server::Notifier,
+};
+
+type ResponseSender = oneshot::Sender<Result<Value, RpcError>>;
+
+/// The requests sent to the client, waiting for their responses.
+///
+/// The transports keep a clone to complete a request while the server is busy
+/// handling the request that sent it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PendingRequests(Arc<Mutex<PendingRequestsState>>);
+
+#[derive(Debug, Default)]
+struct PendingRequestsState {
+    last_id: i64,
+    senders: HashMap<RequestId, ResponseSender>,
+}
+
+impl PendingRequests {
+    fn register(&self) -> (RequestId, oneshot::Receiver<Result<Value, RpcError>>) {
+        let (tx, rx) = oneshot::channel();
+        let mut state = self.0.lock().unwrap();
+        state.last_id += 1;
+        let id = RequestId::Int(state.last_id);
+        state.senders.insert(id.clone(), tx);
+        (id, rx)
+    }
+
+    fn remove(&self, id: &RequestId) {
+        self.0.lock().unwrap().senders.remove(id);
+    }
+
+    /// Completes the request of the response.
+    pub(crate) fn complete(&self, response: ClientResponse) {
+        let Some(sender) = self.0.lock().unwrap().senders.remove(&response.id) else {
+            tracing::warn!(id = ?response.id, "received a response to an unknown request");
+            return;
+        };
+        let res = match (response.result, response.error) {
+            (_, Some(err)) => Err(RpcError::new(err.code(), err.message())),
+            (result, None) => Ok(result.unwrap_or_default()),
+        };
+        _ = sender.send(res);
+    }
+}
+
+/// The roots of the client, fetched with a `roots/list` request and cleared
+/// when the client notifies that they changed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RootsCache {
+    state: Arc<Mutex<RootsCacheState>>,
+    // held while fetching, so that the concurrent calls wait for the same roots
+    fetching: Arc<tokio::sync::Mutex<()>>,
+}
+
+#[derive(Debug, Default)]
+struct RootsCacheState {
+    // incremented when the roots change, to discard the roots fetched before
+    generation: u64,
+    roots: Option<Vec<Root>>,
+}
+
+impl RootsCache {
+    /// Clears the roots after the client notified that they changed.
+    fn get(&self) -> Result<Vec<Root>, u64> {
+        let state = self.state.lock().unwrap();
+        state.roots.clone().ok_or(state.generation)
+    }
+
+    fn set(&self, generation: u64, roots: Vec<Root>) {
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            state.roots = Some(roots);
+        }
+    }
+
+    pub(crate) fn invalidate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.roots = None;
+    }
+}
+
+/// The client of a request, which can receive notifications and requests
+/// from the server while the request is handled.
+#[derive(Debug, Clone)]
+pub(crate) struct Peer {
+    pub(crate) notifier: Option<Notifier>,
+    pub(crate) pending_requests: PendingRequests,
+    pub(crate) capabilities: ClientCapabilities,
+    pub(crate) log_level: LoggingLevel,
+    pub(crate) roots: RootsCache,
+}
+
+impl Default for Peer {
+    fn default() -> Self {
+        Self {
+            notifier: None,
+            pending_requests: PendingRequests::default(),
+            capabilities: ClientCapabilities::default(),
+            log_level: LoggingLevel::Info,
+            roots: RootsCache::default(),
+        }
+    }
+}
+
+impl Peer {
+    /// Sends a notification to the client, which is dropped if the transport
+    /// can't send notifications.
+    pub(crate) fn notify(&self, message: impl Serialize) {
+        if let Some(notifier) = &self.notifier {
+            _ = notifier.send(serde_json::to_string(&message).unwrap());
+        }
+    }
+
+    /// Sends a log message to the client, if its level is at least the level
+    /// set by the client.
+    pub(crate) fn log(&self, level: LoggingLevel, logger: Option<String>, data: Value) {
+        if level < self.log_level {
+            return;
+        }
+        self.notify(Notification::new(
+            "notifications/message",
+            LoggingMessageNotification {
+                level,
+                logger,
+                data,
+            },
+        ));
+    }
+
+    /// Returns the roots of the client, sending a `roots/list` request if they
+    /// are not known yet.
+    pub(crate) async fn roots(&self) -> Result<Vec<Root>, RpcError> {
+        if self.capabilities.roots.is_none() {
+            return Err(RpcError::method_not_found(
+                "the client doesn't support roots",
+            ));
+        }
+
+        if let Ok(roots) = self.roots.get() {
+            return Ok(roots);
+        }
+        let _fetching = self.roots.fetching.lock().await;
+        let generation = match self.roots.get() {
+            Ok(roots) => return Ok(roots),
+            Err(generation) => generation,
+        };
+        let result = self
+            .send_request("roots/list", ListRootsRequest::default())
+            .await?;
+        let result: ListRootsResult = serde_json::from_value(result)
+            .map_err(|err| RpcError::internal_error(format!("invalid roots result: {err}")))?;
+
+        self.roots.set(generation, result.roots.clone());
+        Ok(result.roots)
+    }
+
+    /// Sends a request to the client and waits for its response.
+    pub(crate) async fn send_request(
+        &self,
+        method: &str,
+        params: impl Serialize,
+    ) -> Result<Value, RpcError> {
+        let Some(notifier) = &self.notifier else {
+            return Err(RpcError::internal_error(
+                "the transport can't send requests to the client",
+            ));
+        };
+
+        let (id, rx) = self.pending_requests.register();
+        let request = ServerRequest {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: id.clone(),
+            method: method.to_string(),
+            params,
+        };
+        tracing::info!(id = ?id, method = method, "sending request to the client");
+        if notifier
+            .send(serde_json::to_string(&request).unwrap())
+            .is_err()
+        {
+            self.pending_requests.remove(&id);
+            return Err(RpcError::internal_error("the connection is closed"));
+        }
+
+        rx.await
+            .unwrap_or_else(|_| Err(RpcError::internal_error("the request was dropped")))
+    }
+}
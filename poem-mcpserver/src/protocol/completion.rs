@@ -0,0 +1,60 @@
+//! Completion protocol.
+
+use serde::{Deserialize, Serialize};
+
+/// The prompt or resource template whose argument is completed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    /// A prompt.
+    #[serde(rename = "ref/prompt")]
+    Prompt {
+        /// The name of the prompt.
+        name: String,
+    },
+    /// A resource template.
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// The URI or URI template of the resource.
+        uri: String,
+    },
+}
+
+/// The argument to complete.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompletionArgument {
+    /// The name of the argument.
+    pub name: String,
+    /// The value of the argument typed so far.
+    pub value: String,
+}
+
+/// A completion request.
+#[derive(Debug, Deserialize)]
+pub struct CompleteRequest {
+    /// The prompt or resource template.
+    #[serde(rename = "ref")]
+    pub reference: CompletionReference,
+    /// The argument to complete.
+    pub argument: CompletionArgument,
+}
+
+/// The completion values.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    /// The values, at most 100.
+    pub values: Vec<String>,
+    /// The total number of values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    /// Whether there are more values than returned.
+    pub has_more: bool,
+}
+
+/// A completion response.
+#[derive(Debug, Serialize)]
+pub struct CompleteResponse {
+    /// The completion values.
+    pub completion: Completion,
+}
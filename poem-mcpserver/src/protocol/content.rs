@@ -1,10 +1,14 @@
 //! Content type.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// A content that can be sent to the client.
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase", tag = "type")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase",
+    tag = "type"
+)]
 pub enum Content {
     /// A text content.
     Text {
@@ -18,4 +22,11 @@ pub enum Content {
         /// The MIME type of the image.
         mime_type: String,
     },
+    /// An audio content.
+    Audio {
+        /// The base64-encoded audio data.
+        data: String,
+        /// The MIME type of the audio.
+        mime_type: String,
+    },
 }
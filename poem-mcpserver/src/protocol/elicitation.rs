@@ -0,0 +1,36 @@
+//! Elicitation protocol.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// An `elicitation/create` request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitRequest {
+    /// The message presented to the user.
+    pub message: String,
+    /// The JSON schema of the requested input.
+    pub requested_schema: Value,
+}
+
+/// The action of the user in response to an elicitation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ElicitAction {
+    /// The user submitted the requested input.
+    Accept,
+    /// The user explicitly declined to submit the input.
+    Decline,
+    /// The user dismissed the request.
+    Cancel,
+}
+
+/// The result of an `elicitation/create` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitResult {
+    /// The action of the user.
+    pub action: ElicitAction,
+    /// The submitted input, if the user accepted the request.
+    pub content: Option<Value>,
+}
@@ -1,11 +1,19 @@
 //! Initialize request and response.
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use time::Date;
 
 /// The client capabilities.
-#[derive(Debug, Deserialize)]
-pub struct ClientCapabilities {}
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClientCapabilities {
+    /// Present if the client supports sampling.
+    pub sampling: Option<Value>,
+    /// Present if the client supports elicitation.
+    pub elicitation: Option<Value>,
+    /// Present if the client supports roots.
+    pub roots: Option<Value>,
+}
 
 /// The client information.
 #[derive(Debug, Deserialize)]
@@ -59,6 +67,14 @@ pub struct ToolsCapability {
     pub list_changed: bool,
 }
 
+/// Logging capability.
+#[derive(Debug, Serialize)]
+pub struct LoggingCapability {}
+
+/// Completions capability.
+#[derive(Debug, Serialize)]
+pub struct CompletionsCapability {}
+
 /// The server capabilities.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -69,6 +85,11 @@ pub struct ServerCapabilities {
     pub resources: ResourcesCapability,
     /// Tools capability.
     pub tools: ToolsCapability,
+    /// Logging capability.
+    pub logging: LoggingCapability,
+    /// Completions capability.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completions: Option<CompletionsCapability>,
 }
 
 /// The server information.
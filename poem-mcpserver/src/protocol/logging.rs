@@ -0,0 +1,48 @@
+//! Logging protocol.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The severity of a log message, as defined in
+/// [RFC 5424](https://datatracker.ietf.org/doc/html/rfc5424#section-6.2.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LoggingLevel {
+    /// Debug-level messages.
+    Debug,
+    /// Informational messages.
+    Info,
+    /// Normal but significant conditions.
+    Notice,
+    /// Warning conditions.
+    Warning,
+    /// Error conditions.
+    Error,
+    /// Critical conditions.
+    Critical,
+    /// Action must be taken immediately.
+    Alert,
+    /// System is unusable.
+    Emergency,
+}
+
+/// A request to set the minimum level of the log messages sent to the client.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLevelRequest {
+    /// The minimum level.
+    pub level: LoggingLevel,
+}
+
+/// A log message sent to the client.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggingMessageNotification {
+    /// The severity of the message.
+    pub level: LoggingLevel,
+    /// The name of the logger.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// The data to log.
+    pub data: Value,
+}
@@ -1,11 +1,17 @@
 //! The protocol module contains all the necessary components to implement the
 //! MCP protocol.
 
+pub mod completion;
 pub mod content;
+pub mod elicitation;
 pub mod initialize;
+pub mod logging;
+pub mod notifications;
 pub mod prompts;
 pub mod resources;
+pub mod roots;
 pub mod rpc;
+pub mod sampling;
 pub mod tool;
 
 /// The JSON-RPC version.
@@ -0,0 +1,28 @@
+//! Notifications.
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::rpc::{ProgressToken, RequestId};
+
+/// A notification sent by the client to cancel a request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelledNotification {
+    /// The ID of the request to cancel.
+    pub request_id: RequestId,
+    /// An optional reason string that can be logged or displayed.
+    pub reason: Option<String>,
+}
+
+/// A notification sent by the server to report the progress of a request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressNotification {
+    /// The token of the progress notifications given in the request.
+    pub progress_token: ProgressToken,
+    /// The progress so far.
+    pub progress: f64,
+    /// The total progress, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+}
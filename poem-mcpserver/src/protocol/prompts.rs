@@ -41,6 +41,9 @@ pub struct Prompt {
 pub struct PromptsListResponse {
     /// Prompts list.
     pub prompts: Vec<Prompt>,
+    /// The cursor to get the next page, if there are more items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// A role type to indicate the speaker.
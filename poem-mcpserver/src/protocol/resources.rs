@@ -1,5 +1,7 @@
 //! Resource protocol.
 
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 
 /// A request to list resources.
@@ -30,4 +32,74 @@ pub struct Resource {
 pub struct ResourcesListResponse {
     /// Resources list.
     pub resources: Vec<Resource>,
+    /// The cursor to get the next page, if there are more items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Resource template information, a parameterized URI of resources.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    /// The URI template of the resources, as defined in
+    /// [RFC 6570](https://datatracker.ietf.org/doc/html/rfc6570).
+    pub uri_template: Cow<'static, str>,
+    /// The name of the template.
+    pub name: Cow<'static, str>,
+    /// The description of the template.
+    pub description: Cow<'static, str>,
+    /// The mime type of the resources.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<Cow<'static, str>>,
+}
+
+/// A response to a resources/templates/list request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplatesListResponse {
+    /// Resource templates list.
+    pub resource_templates: Vec<ResourceTemplate>,
+    /// The cursor to get the next page, if there are more items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// A request to read a resource.
+#[derive(Debug, Deserialize)]
+pub struct ResourcesReadRequest {
+    /// The URI of the resource.
+    pub uri: String,
+}
+
+/// The contents of a resource.
+#[derive(Debug, Serialize)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub enum ResourceContents {
+    /// A text resource.
+    Text {
+        /// The URI of the resource.
+        uri: String,
+        /// The mime type of the resource.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// The text of the resource.
+        text: String,
+    },
+    /// A binary resource.
+    Blob {
+        /// The URI of the resource.
+        uri: String,
+        /// The mime type of the resource.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// The base64-encoded data of the resource.
+        blob: String,
+    },
+}
+
+/// A response to a resources/read request.
+#[derive(Debug, Serialize)]
+pub struct ResourcesReadResponse {
+    /// The contents of the resource.
+    pub contents: Vec<ResourceContents>,
 }
@@ -0,0 +1,24 @@
+//! Roots protocol.
+
+use serde::{Deserialize, Serialize};
+
+/// A root, a directory or file the client gives the server access to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Root {
+    /// The URI of the root, a `file://` URI.
+    pub uri: String,
+    /// The name of the root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A `roots/list` request sent to the client.
+#[derive(Debug, Default, Serialize)]
+pub struct ListRootsRequest {}
+
+/// The result of a `roots/list` request.
+#[derive(Debug, Deserialize)]
+pub struct ListRootsResult {
+    /// The roots.
+    pub roots: Vec<Root>,
+}
@@ -5,13 +5,18 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::protocol::{
+    JSON_RPC_VERSION,
+    completion::CompleteRequest,
     initialize::InitializeRequest,
+    logging::SetLevelRequest,
+    notifications::CancelledNotification,
     prompts::PromptsListRequest,
+    resources::{ResourcesListRequest, ResourcesReadRequest},
     tool::{ToolsCallRequest, ToolsListRequest},
 };
 
 /// A JSON-RPC request id.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RequestId {
     /// A numeric request id.
@@ -37,11 +42,12 @@ pub enum Requests {
     /// Cancelled notification.
     #[serde(rename = "notifications/cancelled")]
     Cancelled {
-        /// The ID of the request to cancel
-        request_id: RequestId,
-        /// An optional reason string that can be logged or displayed
-        reason: Option<String>,
+        /// Cancelled notification parameters.
+        params: CancelledNotification,
     },
+    /// Roots list changed notification.
+    #[serde(rename = "notifications/roots/list_changed")]
+    RootsListChanged,
     /// Tools list.
     #[serde(rename = "tools/list")]
     ToolsList {
@@ -62,6 +68,18 @@ pub enum Requests {
         #[serde(default)]
         params: PromptsListRequest,
     },
+    /// Set the minimum level of the log messages.
+    #[serde(rename = "logging/setLevel")]
+    LoggingSetLevel {
+        /// Set level request parameters.
+        params: SetLevelRequest,
+    },
+    /// Complete an argument of a prompt or resource template.
+    #[serde(rename = "completion/complete")]
+    CompletionComplete {
+        /// Complete request parameters.
+        params: CompleteRequest,
+    },
     /// Resources list.
     #[serde(rename = "resources/list")]
     ResourcesList {
@@ -69,6 +87,51 @@ pub enum Requests {
         #[serde(default)]
         params: PromptsListRequest,
     },
+    /// Resource templates list.
+    #[serde(rename = "resources/templates/list")]
+    ResourcesTemplatesList {
+        /// Resource templates list request parameters.
+        #[serde(default)]
+        params: ResourcesListRequest,
+    },
+    /// Read a resource.
+    #[serde(rename = "resources/read")]
+    ResourcesRead {
+        /// Read request parameters.
+        params: ResourcesReadRequest,
+    },
+}
+
+impl Requests {
+    /// Returns the method of the request.
+    pub fn method(&self) -> &'static str {
+        match self {
+            Requests::Ping => "ping",
+            Requests::Initialize { .. } => "initialize",
+            Requests::Initialized => "notifications/initialized",
+            Requests::Cancelled { .. } => "notifications/cancelled",
+            Requests::RootsListChanged => "notifications/roots/list_changed",
+            Requests::ToolsList { .. } => "tools/list",
+            Requests::ToolsCall { .. } => "tools/call",
+            Requests::PromptsList { .. } => "prompts/list",
+            Requests::LoggingSetLevel { .. } => "logging/setLevel",
+            Requests::CompletionComplete { .. } => "completion/complete",
+            Requests::ResourcesList { .. } => "resources/list",
+            Requests::ResourcesTemplatesList { .. } => "resources/templates/list",
+            Requests::ResourcesRead { .. } => "resources/read",
+        }
+    }
+}
+
+/// A token used to associate the progress notifications with the request.
+pub type ProgressToken = RequestId;
+
+/// The metadata of a request.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestMeta {
+    /// The token of the progress notifications the client wants to receive.
+    pub progress_token: Option<ProgressToken>,
 }
 
 /// A JSON-RPC batch request.
@@ -171,6 +234,55 @@ where
     }
 }
 
+/// A JSON-RPC notification sent by the server.
+#[derive(Debug, Serialize)]
+pub struct Notification<T = Value> {
+    /// The JSON-RPC version.
+    pub jsonrpc: String,
+    /// The notification method.
+    pub method: String,
+    /// The notification parameters.
+    pub params: T,
+}
+
+impl<T> Notification<T> {
+    /// Creates a notification with the given method and parameters.
+    #[inline]
+    pub fn new(method: impl Into<String>, params: T) -> Self {
+        Self {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC request sent by the server.
+#[derive(Debug, Serialize)]
+pub struct ServerRequest<T = Value> {
+    /// The JSON-RPC version.
+    pub jsonrpc: String,
+    /// The request id.
+    pub id: RequestId,
+    /// The request method.
+    pub method: String,
+    /// The request parameters.
+    pub params: T,
+}
+
+/// A JSON-RPC response sent by the client.
+#[derive(Debug, Deserialize)]
+pub struct ClientResponse {
+    /// The JSON-RPC version.
+    pub jsonrpc: String,
+    /// The request id.
+    pub id: RequestId,
+    /// The response result.
+    pub result: Option<Value>,
+    /// The response error.
+    pub error: Option<RpcError<Value>>,
+}
+
 /// A JSON-RPC batch response
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
@@ -200,7 +312,7 @@ const INVALID_PARAMS: i32 = -32602;
 const INTERNAL_ERROR: i32 = -32603;
 
 /// A JSON-RPC error.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcError<E = ()> {
     code: i32,
@@ -220,6 +332,18 @@ impl<E> RpcError<E> {
         }
     }
 
+    /// Returns the error code.
+    #[inline]
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// Returns the error message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
     /// Attach data to the JSON-RPC error.
     #[inline]
     pub fn with_data<Q>(self, data: Q) -> RpcError<Q> {
@@ -0,0 +1,167 @@
+//! Sampling protocol.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::protocol::content::Content;
+
+/// The role of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    /// The user.
+    User,
+    /// The assistant.
+    Assistant,
+}
+
+/// A message of a sampling request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingMessage {
+    /// The role of the message.
+    pub role: Role,
+    /// The message content.
+    pub content: Content,
+}
+
+impl SamplingMessage {
+    /// Creates a text message of the user.
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: Content::Text { text: text.into() },
+        }
+    }
+
+    /// Creates a text message of the assistant.
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: Content::Text { text: text.into() },
+        }
+    }
+}
+
+/// A hint to select a model.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelHint {
+    /// A full or partial model name.
+    pub name: String,
+}
+
+/// The preferences of the server for the model selection.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPreferences {
+    /// The hints to select a model, in order of preference.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<ModelHint>,
+    /// How much to prioritize the cost, from 0 to 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_priority: Option<f64>,
+    /// How much to prioritize the speed, from 0 to 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_priority: Option<f64>,
+    /// How much to prioritize the intelligence, from 0 to 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intelligence_priority: Option<f64>,
+}
+
+/// The context of the MCP servers to include in the prompt.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncludeContext {
+    /// No context.
+    None,
+    /// The context of this server.
+    ThisServer,
+    /// The context of all the servers.
+    AllServers,
+}
+
+/// A `sampling/createMessage` request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageRequest {
+    /// The messages of the conversation.
+    pub messages: Vec<SamplingMessage>,
+    /// The preferences for the model selection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+    /// The system prompt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// The context of the MCP servers to include in the prompt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<IncludeContext>,
+    /// The sampling temperature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// The maximum number of tokens to sample.
+    pub max_tokens: u32,
+    /// The sequences that stop the sampling.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// The metadata passed to the model provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+impl CreateMessageRequest {
+    /// Creates a request with the messages and the maximum number of tokens.
+    pub fn new(messages: Vec<SamplingMessage>, max_tokens: u32) -> Self {
+        Self {
+            messages,
+            model_preferences: None,
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens,
+            stop_sequences: vec![],
+            metadata: None,
+        }
+    }
+
+    /// Sets the system prompt.
+    #[must_use]
+    pub fn system_prompt(self, system_prompt: impl Into<String>) -> Self {
+        Self {
+            system_prompt: Some(system_prompt.into()),
+            ..self
+        }
+    }
+
+    /// Sets the preferences for the model selection.
+    #[must_use]
+    pub fn model_preferences(self, model_preferences: ModelPreferences) -> Self {
+        Self {
+            model_preferences: Some(model_preferences),
+            ..self
+        }
+    }
+
+    /// Sets the sampling temperature.
+    #[must_use]
+    pub fn temperature(self, temperature: f64) -> Self {
+        Self {
+            temperature: Some(temperature),
+            ..self
+        }
+    }
+}
+
+/// The result of a `sampling/createMessage` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    /// The role of the message.
+    pub role: Role,
+    /// The message content.
+    pub content: Content,
+    /// The name of the model that generated the message.
+    pub model: String,
+    /// The reason why the sampling stopped.
+    pub stop_reason: Option<String>,
+}
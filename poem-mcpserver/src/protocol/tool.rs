@@ -1,9 +1,11 @@
 //! Tool protocol.
 
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::protocol::content::Content;
+use crate::protocol::{content::Content, rpc::RequestMeta};
 
 /// A request to list tools.
 #[derive(Debug, Deserialize, Default)]
@@ -18,11 +20,41 @@ pub struct ToolsListRequest {
 #[serde(rename_all = "camelCase")]
 pub struct Tool {
     /// The name of the tool.
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// The description of the tool.
-    pub description: &'static str,
+    pub description: Cow<'static, str>,
     /// The input schema of the tool.
     pub input_schema: Value,
+    /// The schema of the structured content returned by the tool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    /// The hints about the behavior of the tool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+/// Hints about the behavior of a tool, the clients must not rely on them for
+/// security decisions.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// A human-readable title of the tool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Cow<'static, str>>,
+    /// Whether the tool doesn't modify its environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// Whether the tool may perform destructive updates, only meaningful if
+    /// the tool is not read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Whether calling the tool repeatedly with the same arguments has no
+    /// additional effect, only meaningful if the tool is not read-only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// Whether the tool interacts with external entities.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
 }
 
 /// A response to a tools/list request.
@@ -31,6 +63,9 @@ pub struct Tool {
 pub struct ToolsListResponse {
     /// Tools list.
     pub tools: Vec<Tool>,
+    /// The cursor to get the next page, if there are more items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// A request to call a tool.
@@ -42,6 +77,9 @@ pub struct ToolsCallRequest {
     #[serde(default)]
     /// The arguments passed to the tool.
     pub arguments: Value,
+    /// The metadata of the request.
+    #[serde(rename = "_meta", default)]
+    pub meta: Option<RequestMeta>,
 }
 
 /// A response to a tools/call request.
@@ -50,6 +88,9 @@ pub struct ToolsCallRequest {
 pub struct ToolsCallResponse {
     /// Response content.
     pub content: Vec<Content>,
+    /// Structured response content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
     /// Whether the response is an error.
     pub is_error: bool,
 }
@@ -0,0 +1,261 @@
+//! Types for resource.
+
+use std::{collections::HashMap, fmt::Display, future::Future, str::FromStr};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Serialize;
+
+use crate::{
+    content::{Json, Text},
+    protocol::{
+        resources::{ResourceContents, ResourceTemplate, ResourcesReadResponse},
+        rpc::RpcError,
+    },
+};
+
+/// The error code of a resource that doesn't exist.
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// Returns the error of a resource that doesn't exist.
+#[doc(hidden)]
+pub fn resource_not_found(uri: &str) -> RpcError {
+    RpcError::new(RESOURCE_NOT_FOUND, format!("resource not found: {uri}"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part<'a> {
+    Literal(&'a str),
+    Variable(&'a str),
+}
+
+/// A URI template with simple variables, such as `db://{table}/{id}`.
+///
+/// A variable matches the characters up to the next literal of the template,
+/// and the last variable matches the rest of the URI, so that
+/// `file:///{path}` matches the paths with several segments.
+#[derive(Debug, Clone)]
+pub struct UriTemplate<'a> {
+    parts: Vec<Part<'a>>,
+}
+
+impl<'a> UriTemplate<'a> {
+    /// Parses a URI template.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable is not closed.
+    pub fn new(template: &'a str) -> Self {
+        let mut parts = vec![];
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Literal(&rest[..start]));
+            }
+            let end = rest[start..]
+                .find('}')
+                .unwrap_or_else(|| panic!("unclosed variable in URI template: {template}"));
+            let name = &rest[start + 1..start + end];
+            // the reserved expansion `{+path}` is matched like `{path}`
+            parts.push(Part::Variable(name.trim_start_matches('+')));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest));
+        }
+        Self { parts }
+    }
+
+    /// Returns the names of the variables.
+    pub fn variables(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Variable(name) => Some(*name),
+            Part::Literal(_) => None,
+        })
+    }
+
+    /// Matches the URI and returns the values of the variables, or `None` if
+    /// the URI doesn't match the template.
+    pub fn match_uri(&self, uri: &str) -> Option<HashMap<&'a str, String>> {
+        let mut variables = HashMap::new();
+        let mut rest = uri;
+        let mut parts = self.parts.iter().peekable();
+        while let Some(part) = parts.next() {
+            match part {
+                Part::Literal(literal) => rest = rest.strip_prefix(literal)?,
+                Part::Variable(name) => {
+                    let end = match parts.peek() {
+                        Some(Part::Literal(literal)) => rest.find(literal)?,
+                        _ => rest.len(),
+                    };
+                    if end == 0 {
+                        return None;
+                    }
+                    variables.insert(*name, rest[..end].to_string());
+                    rest = &rest[end..];
+                }
+            }
+        }
+        rest.is_empty().then_some(variables)
+    }
+}
+
+/// Parses the value of a variable of a matched URI template.
+#[doc(hidden)]
+pub fn parse_variable<T>(variables: &HashMap<&str, String>, name: &str) -> Result<T, RpcError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = variables
+        .get(name)
+        .ok_or_else(|| RpcError::invalid_params(format!("missing variable `{name}`")))?;
+    value
+        .parse()
+        .map_err(|err| RpcError::invalid_params(format!("invalid variable `{name}`: {err}")))
+}
+
+/// Represents a type that can be converted into the contents of a resource.
+pub trait IntoResourceContents {
+    /// Consumes the object and converts it into the contents of the resource
+    /// with the URI, the mime type is the one declared by the template if
+    /// any.
+    fn into_resource_contents(
+        self,
+        uri: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError>;
+}
+
+impl<T> IntoResourceContents for Text<T>
+where
+    T: Display,
+{
+    fn into_resource_contents(
+        self,
+        uri: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        Ok(vec![ResourceContents::Text {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.unwrap_or("text/plain").to_string()),
+            text: self.0.to_string(),
+        }])
+    }
+}
+
+impl<T> IntoResourceContents for Json<T>
+where
+    T: Serialize,
+{
+    fn into_resource_contents(
+        self,
+        uri: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        let text = serde_json::to_string(&self.0)
+            .map_err(|err| RpcError::internal_error(err.to_string()))?;
+        Ok(vec![ResourceContents::Text {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.unwrap_or("application/json").to_string()),
+            text,
+        }])
+    }
+}
+
+/// The bytes are encoded in base64 as a binary resource.
+impl IntoResourceContents for Vec<u8> {
+    fn into_resource_contents(
+        self,
+        uri: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        Ok(vec![ResourceContents::Blob {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.unwrap_or("application/octet-stream").to_string()),
+            blob: STANDARD.encode(self),
+        }])
+    }
+}
+
+impl IntoResourceContents for ResourceContents {
+    fn into_resource_contents(
+        self,
+        _uri: &str,
+        _mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        Ok(vec![self])
+    }
+}
+
+impl IntoResourceContents for Vec<ResourceContents> {
+    fn into_resource_contents(
+        self,
+        _uri: &str,
+        _mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        Ok(self)
+    }
+}
+
+/// `None` is returned as a resource not found error.
+impl<T> IntoResourceContents for Option<T>
+where
+    T: IntoResourceContents,
+{
+    fn into_resource_contents(
+        self,
+        uri: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        match self {
+            Some(value) => value.into_resource_contents(uri, mime_type),
+            None => Err(resource_not_found(uri)),
+        }
+    }
+}
+
+/// An error is returned as an internal error.
+impl<T, E> IntoResourceContents for Result<T, E>
+where
+    T: IntoResourceContents,
+    E: Display,
+{
+    fn into_resource_contents(
+        self,
+        uri: &str,
+        mime_type: Option<&str>,
+    ) -> Result<Vec<ResourceContents>, RpcError> {
+        match self {
+            Ok(value) => value.into_resource_contents(uri, mime_type),
+            Err(err) => Err(RpcError::internal_error(err.to_string())),
+        }
+    }
+}
+
+/// Represents a resources collection.
+pub trait Resources {
+    /// Returns the list of resource templates.
+    fn templates() -> Vec<ResourceTemplate>;
+
+    /// Reads the resource with the URI.
+    fn read(
+        &mut self,
+        uri: &str,
+    ) -> impl Future<Output = Result<ResourcesReadResponse, RpcError>> + Send;
+}
+
+/// Empty resources collection.
+#[derive(Debug, Clone, Copy)]
+pub struct NoResources;
+
+impl Resources for NoResources {
+    #[inline]
+    fn templates() -> Vec<ResourceTemplate> {
+        vec![]
+    }
+
+    #[inline]
+    async fn read(&mut self, uri: &str) -> Result<ResourcesReadResponse, RpcError> {
+        Err(resource_not_found(uri))
+    }
+}
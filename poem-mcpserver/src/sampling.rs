@@ -0,0 +1,82 @@
+//! Sampling, the requests sent by the server to the client to generate a
+//! message with a language model.
+
+use crate::{
+    peer::Peer,
+    protocol::{
+        rpc::RpcError,
+        sampling::{CreateMessageRequest, CreateMessageResult},
+    },
+};
+
+/// A handle to send sampling requests to the client of a tool call.
+///
+/// A tool receives the handle by declaring a `SamplingClient` parameter, or
+/// with [`ToolContext::sampling`](crate::tool::ToolContext::sampling).
+///
+/// ```
+/// use poem_mcpserver::{
+///     Tools,
+///     content::Text,
+///     protocol::{
+///         content::Content,
+///         sampling::{CreateMessageRequest, SamplingMessage},
+///     },
+///     sampling::SamplingClient,
+/// };
+///
+/// struct Writer;
+///
+/// #[Tools]
+/// impl Writer {
+///     /// Write a poem about the subject.
+///     async fn write_poem(&self, sampling: SamplingClient, subject: String) -> Text<String> {
+///         let request = CreateMessageRequest::new(
+///             vec![SamplingMessage::user(format!("Write a poem about {subject}"))],
+///             500,
+///         );
+///         match sampling.create_message(request).await {
+///             Ok(result) => match result.content {
+///                 Content::Text { text } => Text(text),
+///                 _ => Text("unexpected content".to_string()),
+///             },
+///             Err(err) => Text(err.message().to_string()),
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SamplingClient {
+    peer: Peer,
+}
+
+impl SamplingClient {
+    pub(crate) fn new(peer: Peer) -> Self {
+        Self { peer }
+    }
+
+    /// Returns `true` if the client declared the sampling capability.
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        self.peer.capabilities.sampling.is_some()
+    }
+
+    /// Sends a `sampling/createMessage` request to the client and waits for
+    /// the generated message.
+    pub async fn create_message(
+        &self,
+        request: CreateMessageRequest,
+    ) -> Result<CreateMessageResult, RpcError> {
+        if !self.is_supported() {
+            return Err(RpcError::method_not_found(
+                "the client doesn't support sampling",
+            ));
+        }
+        let result = self
+            .peer
+            .send_request("sampling/createMessage", request)
+            .await?;
+        serde_json::from_value(result)
+            .map_err(|err| RpcError::internal_error(format!("invalid sampling result: {err}")))
+    }
+}
@@ -1,27 +1,129 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Serialize;
 use serde_json::Value;
+use tokio::sync::{broadcast, mpsc::UnboundedSender};
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, field::Empty};
 
 use crate::{
+    auth::Principal,
+    peer::{Peer, PendingRequests, RootsCache},
     protocol::{
         JSON_RPC_VERSION,
+        completion::{
+            CompleteRequest, CompleteResponse, Completion, CompletionArgument, CompletionReference,
+        },
         initialize::{
-            InitializeRequest, InitializeResponse, PromptsCapability, ResourcesCapability,
-            ServerCapabilities, ServerInfo, ToolsCapability,
+            ClientCapabilities, CompletionsCapability, InitializeRequest, InitializeResponse,
+            LoggingCapability, PromptsCapability, ResourcesCapability, ServerCapabilities,
+            ServerInfo, ToolsCapability,
         },
+        logging::LoggingLevel,
+        notifications::CancelledNotification,
         prompts::PromptsListResponse,
-        resources::ResourcesListResponse,
-        rpc::{Request, RequestId, Requests, Response},
+        resources::{ResourceTemplatesListResponse, ResourcesListResponse},
+        rpc::{Notification, Request, RequestId, Requests, Response, RpcError},
         tool::{ToolsCallRequest, ToolsListResponse},
     },
-    tool::{NoTools, Tools},
+    resource::{NoResources, Resources},
+    tool::{DynamicTools, NoTools, ToolContext, Tools},
 };
 
+/// The sender of the serialized messages sent to the client outside of the
+/// responses.
+pub(crate) type Notifier = UnboundedSender<String>;
+
+/// The cancellation tokens of the running tool calls.
+///
+/// The transports keep a clone to cancel a call while the server is busy
+/// handling it.
+#[derive(Clone, Default)]
+pub(crate) struct Cancellations(Arc<Mutex<HashMap<RequestId, CancellationToken>>>);
+
+impl Cancellations {
+    fn register(&self, id: RequestId) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.0.lock().unwrap().insert(id, token.clone());
+        token
+    }
+
+    fn remove(&self, id: &RequestId) {
+        self.0.lock().unwrap().remove(id);
+    }
+
+    pub(crate) fn cancel(&self, notification: &CancelledNotification) {
+        if let Some(token) = self.0.lock().unwrap().remove(&notification.request_id) {
+            tracing::info!(
+                request_id = ?notification.request_id,
+                reason = notification.reason,
+                "request cancelled"
+            );
+            token.cancel();
+        }
+    }
+}
+
+type AuthorizeToolsFn = Box<dyn Fn(&Principal, &str) -> bool + Send + Sync>;
+
+type CompletionFn =
+    Box<dyn Fn(&CompletionReference, &CompletionArgument) -> Vec<String> + Send + Sync>;
+
+/// The maximum number of values of a completion response.
+const MAX_COMPLETION_VALUES: usize = 100;
+
+/// Returns the serialized `notifications/tools/list_changed` notification.
+pub(crate) fn tools_list_changed_notification() -> String {
+    serde_json::to_string(&Notification::new(
+        "notifications/tools/list_changed",
+        Value::Object(Default::default()),
+    ))
+    .unwrap()
+}
+
+/// Waits until the list of tools changes, never returns if the list can't
+/// change.
+pub(crate) async fn tools_changed(receiver: &mut Option<broadcast::Receiver<()>>) {
+    if let Some(rx) = receiver {
+        match rx.recv().await {
+            Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => return,
+            Err(broadcast::error::RecvError::Closed) => *receiver = None,
+        }
+    }
+    std::future::pending().await
+}
+
+fn encode_cursor(offset: usize) -> String {
+    STANDARD.encode(offset.to_string())
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    let cursor = STANDARD.decode(cursor).ok()?;
+    std::str::from_utf8(&cursor).ok()?.parse().ok()
+}
+
 /// A server that can be used to handle MCP requests.
-pub struct McpServer<ToolsType = NoTools> {
+pub struct McpServer<ToolsType = NoTools, ResourcesType = NoResources> {
     tools: ToolsType,
+    resources: ResourcesType,
+    dynamic_tools: Option<DynamicTools>,
     disabled_tools: HashSet<String>,
+    authorize_tools: Option<AuthorizeToolsFn>,
+    completion: Option<CompletionFn>,
+    page_size: Option<usize>,
     server_info: ServerInfo,
+    cancellations: Cancellations,
+    pending_requests: PendingRequests,
+    client_capabilities: ClientCapabilities,
+    log_level: LoggingLevel,
+    roots: RootsCache,
+    #[cfg(feature = "opentelemetry")]
+    metrics: Option<crate::metrics::OpenTelemetryMetrics>,
 }
 
 impl Default for McpServer<NoTools> {
@@ -37,32 +139,108 @@ impl McpServer<NoTools> {
     pub fn new() -> Self {
         Self {
             tools: NoTools,
+            resources: NoResources,
+            dynamic_tools: None,
             disabled_tools: HashSet::new(),
+            authorize_tools: None,
+            completion: None,
+            page_size: None,
             server_info: ServerInfo {
                 name: "poem-mcpserver".to_string(),
                 version: "0.1.0".to_string(),
             },
+            cancellations: Cancellations::default(),
+            pending_requests: PendingRequests::default(),
+            client_capabilities: ClientCapabilities::default(),
+            log_level: LoggingLevel::Info,
+            roots: RootsCache::default(),
+            #[cfg(feature = "opentelemetry")]
+            metrics: None,
         }
     }
 }
 
-impl<ToolsType> McpServer<ToolsType>
+impl<ToolsType, ResourcesType> McpServer<ToolsType, ResourcesType>
 where
     ToolsType: Tools,
+    ResourcesType: Resources,
 {
     /// Sets the tools that the server will use.
-    #[inline]
-    pub fn tools<T>(self, tools: T) -> McpServer<T>
+    ///
+    /// The tools of several collections are combined with a tuple, such as
+    /// `(Files, Clock)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if several tools have the same name.
+    pub fn tools<T>(self, tools: T) -> McpServer<T, ResourcesType>
     where
         T: Tools,
     {
+        let mut names = HashSet::new();
+        for tool in T::list() {
+            if !names.insert(tool.name.clone()) {
+                panic!("duplicate tool name: {}", tool.name);
+            }
+        }
+
         McpServer {
             tools,
+            resources: self.resources,
+            dynamic_tools: self.dynamic_tools,
             disabled_tools: self.disabled_tools,
+            authorize_tools: self.authorize_tools,
+            completion: self.completion,
+            page_size: self.page_size,
             server_info: self.server_info,
+            cancellations: self.cancellations,
+            pending_requests: self.pending_requests,
+            client_capabilities: self.client_capabilities,
+            log_level: self.log_level,
+            roots: self.roots,
+            #[cfg(feature = "opentelemetry")]
+            metrics: self.metrics,
         }
     }
 
+    /// Sets the resources that the server will use.
+    ///
+    /// The resource templates are listed with `resources/templates/list`, and
+    /// the resources matching them are read with `resources/read`.
+    pub fn resources<R>(self, resources: R) -> McpServer<ToolsType, R>
+    where
+        R: Resources,
+    {
+        McpServer {
+            tools: self.tools,
+            resources,
+            dynamic_tools: self.dynamic_tools,
+            disabled_tools: self.disabled_tools,
+            authorize_tools: self.authorize_tools,
+            completion: self.completion,
+            page_size: self.page_size,
+            server_info: self.server_info,
+            cancellations: self.cancellations,
+            pending_requests: self.pending_requests,
+            client_capabilities: self.client_capabilities,
+            log_level: self.log_level,
+            roots: self.roots,
+            #[cfg(feature = "opentelemetry")]
+            metrics: self.metrics,
+        }
+    }
+
+    /// Sets the tools that can be added and removed at runtime, alongside the
+    /// tools of [`McpServer::tools`].
+    ///
+    /// The server advertises the `listChanged` capability of the tools, and
+    /// the transports notify the clients when the tools change.
+    #[inline]
+    pub fn dynamic_tools(mut self, tools: DynamicTools) -> Self {
+        self.dynamic_tools = Some(tools);
+        self
+    }
+
     /// Disables tools by their names.
     pub fn disable_tools<I, T>(mut self, names: I) -> Self
     where
@@ -74,6 +252,80 @@ where
         self
     }
 
+    /// Restricts the tools that an authenticated principal can list and call.
+    ///
+    /// The function is called with the principal and the name of a tool, and
+    /// returns `true` if the principal is allowed to use the tool. The
+    /// requests without a principal are not restricted.
+    pub fn authorize_tools<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Principal, &str) -> bool + Send + Sync + 'static,
+    {
+        self.authorize_tools = Some(Box::new(f));
+        self
+    }
+
+    fn is_tool_allowed(&self, principal: Option<&Principal>, name: &str) -> bool {
+        match (&self.authorize_tools, principal) {
+            (Some(authorize_tools), Some(principal)) => authorize_tools(principal, name),
+            _ => true,
+        }
+    }
+
+    /// Sets the function that completes the arguments of the prompts and
+    /// resource templates, for the `completion/complete` requests.
+    ///
+    /// The function is called with the prompt or resource template and the
+    /// argument typed so far, and returns the completion values. The server
+    /// advertises the `completions` capability.
+    ///
+    /// ```
+    /// use poem_mcpserver::{McpServer, protocol::completion::CompletionReference};
+    ///
+    /// const LANGUAGES: &[&str] = &["python", "rust", "typescript"];
+    ///
+    /// let server = McpServer::new().completion(|reference, argument| match reference {
+    ///     CompletionReference::Prompt { name } if name == "code_review" => LANGUAGES
+    ///         .iter()
+    ///         .filter(|language| language.starts_with(&argument.value))
+    ///         .map(|language| language.to_string())
+    ///         .collect(),
+    ///     _ => vec![],
+    /// });
+    /// ```
+    pub fn completion<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&CompletionReference, &CompletionArgument) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.completion = Some(Box::new(f));
+        self
+    }
+
+    /// Records the metrics of the requests with OpenTelemetry.
+    #[cfg(feature = "opentelemetry")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+    pub fn metrics(mut self, metrics: crate::metrics::OpenTelemetryMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets the maximum number of items of the responses to the
+    /// `tools/list`, `resources/list`, `resources/templates/list` and
+    /// `prompts/list` requests.
+    ///
+    /// The responses include a `nextCursor` when there are more items, which
+    /// the client sends to get the next page. By default, the lists are not
+    /// paginated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the page size is zero.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        assert!(page_size > 0, "the page size must be greater than zero");
+        self.page_size = Some(page_size);
+        self
+    }
+
     /// Sets the server info (name and version).
     pub fn with_server_info(mut self, name: &str, version: &str) -> Self {
         self.server_info = ServerInfo {
@@ -83,6 +335,113 @@ where
         self
     }
 
+    /// Runs the server using standard input and output, with one JSON-RPC
+    /// message per line.
+    ///
+    /// This is the transport used by the MCP clients that launch the server
+    /// as a subprocess.
+    ///
+    /// ```no_run
+    /// use poem_mcpserver::{McpServer, Tools, content::Text};
+    ///
+    /// struct Counter {
+    ///     count: i32,
+    /// }
+    ///
+    /// #[Tools]
+    /// impl Counter {
+    ///     /// Increment the counter by 1
+    ///     async fn increment(&mut self) -> Text<i32> {
+    ///         self.count += 1;
+    ///         Text(self.count)
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     McpServer::new()
+    ///         .tools(Counter { count: 0 })
+    ///         .run_stdio()
+    ///         .await
+    /// }
+    /// ```
+    pub async fn run_stdio(self) -> std::io::Result<()> {
+        crate::stdio::stdio(self).await
+    }
+
+    #[inline]
+    pub(crate) fn cancellations(&self) -> Cancellations {
+        self.cancellations.clone()
+    }
+
+    #[inline]
+    pub(crate) fn pending_requests(&self) -> PendingRequests {
+        self.pending_requests.clone()
+    }
+
+    /// Returns a receiver notified when the list of tools changes, if the
+    /// tools can change.
+    pub(crate) fn subscribe_tools_changed(&self) -> Option<broadcast::Receiver<()>> {
+        self.dynamic_tools.as_ref().map(DynamicTools::subscribe)
+    }
+
+    /// Returns the client of a request, the requests to the client are sent
+    /// to the notifier.
+    fn peer(&self, notifier: Option<&Notifier>) -> Peer {
+        Peer {
+            notifier: notifier.cloned(),
+            pending_requests: self.pending_requests.clone(),
+            capabilities: self.client_capabilities.clone(),
+            log_level: self.log_level,
+            roots: self.roots.clone(),
+        }
+    }
+
+    /// Fetches the roots of the client in the background, if the client
+    /// supports roots and the transport can send requests.
+    fn refresh_roots(&self, notifier: Option<&Notifier>) {
+        if self.client_capabilities.roots.is_none() || notifier.is_none() {
+            return;
+        }
+        let peer = self.peer(notifier);
+        tokio::spawn(async move {
+            if let Err(err) = peer.roots().await {
+                tracing::warn!(error = err.message(), "failed to list the roots");
+            }
+        });
+    }
+
+    fn handle_complete(&self, request: CompleteRequest, id: Option<RequestId>) -> Response<Value> {
+        let Some(completion) = &self.completion else {
+            return Response::<()> {
+                jsonrpc: JSON_RPC_VERSION.to_string(),
+                id,
+                result: None,
+                error: Some(RpcError::method_not_found(
+                    "the server doesn't support completions",
+                )),
+            }
+            .map_result_to_value();
+        };
+
+        let mut values = completion(&request.reference, &request.argument);
+        let total = values.len();
+        values.truncate(MAX_COMPLETION_VALUES);
+        Response {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id,
+            result: Some(CompleteResponse {
+                completion: Completion {
+                    has_more: total > values.len(),
+                    values,
+                    total: Some(total),
+                },
+            }),
+            error: None,
+        }
+        .map_result_to_value()
+    }
+
     fn handle_ping(&self, id: Option<RequestId>) -> Response<Value> {
         Response {
             jsonrpc: JSON_RPC_VERSION.to_string(),
@@ -93,10 +452,11 @@ where
     }
 
     fn handle_initialize(
-        &self,
+        &mut self,
         request: InitializeRequest,
         id: Option<RequestId>,
     ) -> Response<Value> {
+        self.client_capabilities = request.capabilities;
         Response {
             jsonrpc: JSON_RPC_VERSION.to_string(),
             id,
@@ -111,8 +471,10 @@ where
                         subscribe: false,
                     },
                     tools: ToolsCapability {
-                        list_changed: false,
+                        list_changed: self.dynamic_tools.is_some(),
                     },
+                    logging: LoggingCapability {},
+                    completions: self.completion.as_ref().map(|_| CompletionsCapability {}),
                 },
                 server_info: self.server_info.clone(),
                 instructions: Some(ToolsType::instructions().to_string()),
@@ -122,28 +484,71 @@ where
         .map_result_to_value()
     }
 
-    fn handle_tools_list(&self, id: Option<RequestId>) -> Response<Value> {
+    fn handle_tools_list(
+        &self,
+        id: Option<RequestId>,
+        cursor: Option<&str>,
+        principal: Option<&Principal>,
+    ) -> Response<Value> {
+        let mut tools = ToolsType::list();
+        if let Some(dynamic_tools) = &self.dynamic_tools {
+            tools.extend(dynamic_tools.list());
+        }
+        tools.retain(|tool| {
+            !self.disabled_tools.contains(&*tool.name)
+                && self.is_tool_allowed(principal, &tool.name)
+        });
+
+        for tool in &mut tools {
+            if let Some(object) = tool.input_schema.as_object_mut() {
+                if !object.contains_key("properties") {
+                    object.insert("properties".to_string(), Value::Object(Default::default()));
+                }
+            }
+        }
+        self.list_response(id, tools, cursor, |tools, next_cursor| ToolsListResponse {
+            tools,
+            next_cursor,
+        })
+    }
+
+    /// Returns the response with the page of the items starting at the
+    /// cursor, and the cursor of the next page if there are more items.
+    fn list_response<T, R>(
+        &self,
+        id: Option<RequestId>,
+        mut items: Vec<T>,
+        cursor: Option<&str>,
+        f: impl FnOnce(Vec<T>, Option<String>) -> R,
+    ) -> Response<Value>
+    where
+        R: Serialize,
+    {
+        let offset = match cursor.map(decode_cursor) {
+            Some(Some(offset)) => offset.min(items.len()),
+            Some(None) => {
+                return Response::<()> {
+                    jsonrpc: JSON_RPC_VERSION.to_string(),
+                    id,
+                    result: None,
+                    error: Some(RpcError::invalid_params("invalid cursor")),
+                }
+                .map_result_to_value();
+            }
+            None => 0,
+        };
+        let mut items = items.split_off(offset);
+        let next_cursor = match self.page_size {
+            Some(page_size) if items.len() > page_size => {
+                items.truncate(page_size);
+                Some(encode_cursor(offset + page_size))
+            }
+            _ => None,
+        };
         Response {
             jsonrpc: JSON_RPC_VERSION.to_string(),
             id,
-            result: Some(ToolsListResponse {
-                tools: {
-                    let mut tools = ToolsType::list();
-                    tools.retain(|tool| !self.disabled_tools.contains(tool.name));
-
-                    for tool in &mut tools {
-                        if let Some(object) = tool.input_schema.as_object_mut() {
-                            if !object.contains_key("properties") {
-                                object.insert(
-                                    "properties".to_string(),
-                                    Value::Object(Default::default()),
-                                );
-                            }
-                        }
-                    }
-                    tools
-                },
-            }),
+            result: Some(f(items, next_cursor)),
             error: None,
         }
         .map_result_to_value()
@@ -153,8 +558,59 @@ where
         &mut self,
         request: ToolsCallRequest,
         id: Option<RequestId>,
-    ) -> Response<Value> {
-        match self.tools.call(&request.name, request.arguments).await {
+        notifier: Option<&Notifier>,
+        principal: Option<&Principal>,
+    ) -> Option<Response<Value>> {
+        if !self.is_tool_allowed(principal, &request.name) {
+            return Some(
+                Response::<()> {
+                    jsonrpc: JSON_RPC_VERSION.to_string(),
+                    id,
+                    result: None,
+                    error: Some(RpcError::invalid_request(format!(
+                        "tool '{}' is not allowed",
+                        request.name
+                    ))),
+                }
+                .map_result_to_value(),
+            );
+        }
+
+        let cancellation_token = match &id {
+            Some(id) => self.cancellations.register(id.clone()),
+            None => CancellationToken::new(),
+        };
+        let ctx = ToolContext {
+            progress_token: request.meta.and_then(|meta| meta.progress_token),
+            peer: self.peer(notifier),
+            cancellation_token,
+            principal: principal.cloned(),
+        };
+        let call = async {
+            match &self.dynamic_tools {
+                Some(dynamic_tools) if dynamic_tools.contains(&request.name) => {
+                    dynamic_tools.call(&request.name, request.arguments).await
+                }
+                _ => {
+                    self.tools
+                        .call(&request.name, request.arguments, &ctx)
+                        .await
+                }
+            }
+        };
+        #[cfg(feature = "tracing-subscriber")]
+        let call = crate::logging::scope(ctx.peer.clone(), call);
+        let res = call.await;
+        if let Some(id) = &id {
+            self.cancellations.remove(id);
+        }
+
+        // the client doesn't expect a response to a cancelled request
+        if ctx.is_cancelled() {
+            return None;
+        }
+
+        Some(match res {
             Ok(response) => Response {
                 jsonrpc: JSON_RPC_VERSION.to_string(),
                 id,
@@ -169,38 +625,141 @@ where
                 error: Some(err),
             }
             .map_result_to_value(),
-        }
+        })
     }
 
     /// Handles a request and returns a response.
+    #[inline]
     pub async fn handle_request(&mut self, request: Request) -> Option<Response<Value>> {
+        self.handle_request_with(request, None, None).await
+    }
+
+    /// Handles a request of the principal, the notifications sent while
+    /// handling it are sent to the notifier.
+    ///
+    /// The request is handled in a `mcp.request` span, with the duration of
+    /// the request and its error recorded when it completes.
+    pub(crate) async fn handle_request_with(
+        &mut self,
+        request: Request,
+        notifier: Option<&Notifier>,
+        principal: Option<&Principal>,
+    ) -> Option<Response<Value>> {
+        let method = request.body.method();
+        let tool = match &request.body {
+            Requests::ToolsCall { params } => Some(params.name.clone()),
+            _ => None,
+        };
+        let span = tracing::info_span!(
+            "mcp.request",
+            method,
+            id = ?request.id,
+            tool = tool.as_deref(),
+            duration_ms = Empty,
+            error = Empty,
+        );
+
+        let start = Instant::now();
+        let resp = self
+            .dispatch_request(request, notifier, principal)
+            .instrument(span.clone())
+            .await;
+        let elapsed = start.elapsed();
+
+        let error = resp.as_ref().and_then(|resp| resp.error.as_ref());
+        span.record("duration_ms", elapsed.as_secs_f64() * 1000.0);
+        if let Some(error) = error {
+            span.record("error", error.message());
+        }
+        #[cfg(feature = "opentelemetry")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record(method, tool.as_deref(), elapsed, error);
+        }
+
+        resp
+    }
+
+    async fn dispatch_request(
+        &mut self,
+        request: Request,
+        notifier: Option<&Notifier>,
+        principal: Option<&Principal>,
+    ) -> Option<Response<Value>> {
         match request.body {
             Requests::Ping => Some(self.handle_ping(request.id)),
             Requests::Initialize { params } => Some(self.handle_initialize(params, request.id)),
-            Requests::Initialized => None,
-            Requests::Cancelled { .. } => None,
-            Requests::ToolsList { .. } => Some(self.handle_tools_list(request.id)),
+            Requests::Initialized => {
+                self.refresh_roots(notifier);
+                None
+            }
+            Requests::RootsListChanged => {
+                self.roots.invalidate();
+                self.refresh_roots(notifier);
+                None
+            }
+            Requests::Cancelled { params } => {
+                self.cancellations.cancel(&params);
+                None
+            }
+            Requests::ToolsList { params } => {
+                Some(self.handle_tools_list(request.id, params.cursor.as_deref(), principal))
+            }
             Requests::ToolsCall { params } => {
-                Some(self.handle_tools_call(params, request.id).await)
+                self.handle_tools_call(params, request.id, notifier, principal)
+                    .await
+            }
+            Requests::LoggingSetLevel { params } => {
+                self.log_level = params.level;
+                Some(self.handle_ping(request.id))
+            }
+            Requests::CompletionComplete { params } => {
+                Some(self.handle_complete(params, request.id))
+            }
+            Requests::PromptsList { params } => Some(self.list_response(
+                request.id,
+                vec![],
+                params.cursor.as_deref(),
+                |prompts, next_cursor| PromptsListResponse {
+                    prompts,
+                    next_cursor,
+                },
+            )),
+            Requests::ResourcesList { params } => Some(self.list_response(
+                request.id,
+                vec![],
+                params.cursor.as_deref(),
+                |resources, next_cursor| ResourcesListResponse {
+                    resources,
+                    next_cursor,
+                },
+            )),
+            Requests::ResourcesTemplatesList { params } => Some(self.list_response(
+                request.id,
+                ResourcesType::templates(),
+                params.cursor.as_deref(),
+                |resource_templates, next_cursor| ResourceTemplatesListResponse {
+                    resource_templates,
+                    next_cursor,
+                },
+            )),
+            Requests::ResourcesRead { params } => {
+                Some(match self.resources.read(&params.uri).await {
+                    Ok(response) => Response {
+                        jsonrpc: JSON_RPC_VERSION.to_string(),
+                        id: request.id,
+                        result: Some(response),
+                        error: None,
+                    }
+                    .map_result_to_value(),
+                    Err(err) => Response::<()> {
+                        jsonrpc: JSON_RPC_VERSION.to_string(),
+                        id: request.id,
+                        result: None,
+                        error: Some(err),
+                    }
+                    .map_result_to_value(),
+                })
             }
-            Requests::PromptsList { .. } => Some(
-                Response {
-                    jsonrpc: JSON_RPC_VERSION.to_string(),
-                    id: request.id,
-                    result: Some(PromptsListResponse { prompts: vec![] }),
-                    error: None,
-                }
-                .map_result_to_value(),
-            ),
-            Requests::ResourcesList { .. } => Some(
-                Response {
-                    jsonrpc: JSON_RPC_VERSION.to_string(),
-                    id: request.id,
-                    result: Some(ResourcesListResponse { resources: vec![] }),
-                    error: None,
-                }
-                .map_result_to_value(),
-            ),
         }
     }
 }
@@ -1,59 +1,173 @@
 //! Standard input and output server implementation.
 
 use serde::Serialize;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Result as IoResult},
+    sync::mpsc,
+};
 
 use crate::{
     McpServer,
     protocol::{
         JSON_RPC_VERSION,
-        rpc::{BatchRequest, Response, RpcError},
+        rpc::{BatchRequest, ClientResponse, Requests, Response, RpcError},
     },
+    resource::Resources,
+    server::{Notifier, tools_changed, tools_list_changed_notification},
     tool::Tools,
 };
 
-fn print_response(response: impl Serialize) {
-    println!("{}", serde_json::to_string(&response).unwrap());
+fn send_message(sender: &Notifier, message: impl Serialize) {
+    _ = sender.send(serde_json::to_string(&message).unwrap());
 }
 
 /// Run the server using standard input and output.
-pub async fn stdio<ToolsType>(server: McpServer<ToolsType>) -> std::io::Result<()>
+pub async fn stdio<ToolsType, ResourcesType>(
+    server: McpServer<ToolsType, ResourcesType>,
+) -> IoResult<()>
+where
+    ToolsType: Tools,
+    ResourcesType: Resources,
+{
+    serve(server, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+/// Run the server using a reader and a writer, each line is a JSON-RPC
+/// message.
+///
+/// The responses of a batch request are written as a batch response. The
+/// requests are handled one at a time, while the cancellation notifications
+/// are handled as soon as they are received. The server stops when the reader
+/// is closed.
+pub async fn serve<ToolsType, ResourcesType, R, W>(
+    server: McpServer<ToolsType, ResourcesType>,
+    reader: R,
+    mut writer: W,
+) -> IoResult<()>
 where
     ToolsType: Tools,
+    ResourcesType: Resources,
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
 {
     let mut server = server;
-    let mut input = BufReader::new(tokio::io::stdin()).lines();
+    let cancellations = server.cancellations();
+    let pending_requests = server.pending_requests();
+    let (requests_tx, mut requests_rx) =
+        mpsc::unbounded_channel::<Result<BatchRequest, RpcError>>();
+    let (messages_tx, mut messages_rx) = mpsc::unbounded_channel::<String>();
 
     tracing::info!("stdio server started");
 
-    while let Some(line) = input.next_line().await? {
-        tracing::info!(request = &line, "received request");
-
-        let Ok(batch_request) = serde_json::from_str::<BatchRequest>(&line).inspect_err(|err| {
-            tracing::error!(error = ?err, "failed to parse request");
-        }) else {
-            continue;
-        };
-
-        for request in batch_request.into_iter() {
-            if request.jsonrpc != JSON_RPC_VERSION {
-                print_response(Response::<()> {
-                    jsonrpc: JSON_RPC_VERSION.to_string(),
-                    id: request.id,
-                    result: None,
-                    error: Some(RpcError::invalid_request(
-                        "invalid JSON-RPC version, expected `2.0`",
-                    )),
-                });
+    let read = async move {
+        let mut input = BufReader::new(reader).lines();
+        while let Some(line) = input.next_line().await? {
+            if line.trim().is_empty() {
                 continue;
             }
+            tracing::info!(request = &line, "received request");
+
+            let batch_request = match serde_json::from_str::<BatchRequest>(&line) {
+                Ok(batch_request) => batch_request,
+                Err(err) => {
+                    // the response to a request sent by the server
+                    if let Ok(response) = serde_json::from_str::<ClientResponse>(&line) {
+                        pending_requests.complete(response);
+                        continue;
+                    }
+
+                    tracing::error!(error = ?err, "failed to parse request");
+                    _ = requests_tx.send(Err(RpcError::parse_error(err.to_string())));
+                    continue;
+                }
+            };
+
+            // the running request may be the one to cancel
+            for request in batch_request.requests() {
+                if let Requests::Cancelled { params } = &request.body {
+                    cancellations.cancel(params);
+                }
+            }
+            _ = requests_tx.send(Ok(batch_request));
+        }
+        IoResult::Ok(())
+    };
+
+    let handle = async move {
+        let mut tools_changed_rx = server.subscribe_tools_changed();
+        loop {
+            let batch_request = tokio::select! {
+                batch_request = requests_rx.recv() => match batch_request {
+                    Some(batch_request) => batch_request,
+                    None => break,
+                },
+                _ = tools_changed(&mut tools_changed_rx) => {
+                    _ = messages_tx.send(tools_list_changed_notification());
+                    continue;
+                }
+            };
+            let batch_request = match batch_request {
+                Ok(batch_request) => batch_request,
+                Err(err) => {
+                    send_message(
+                        &messages_tx,
+                        Response::<()> {
+                            jsonrpc: JSON_RPC_VERSION.to_string(),
+                            id: None,
+                            result: None,
+                            error: Some(err),
+                        },
+                    );
+                    continue;
+                }
+            };
+            let is_batch = matches!(batch_request, BatchRequest::Batch(_));
+            let mut resps = Vec::new();
+            for request in batch_request.into_iter() {
+                if request.jsonrpc != JSON_RPC_VERSION {
+                    resps.push(
+                        Response::<()> {
+                            jsonrpc: JSON_RPC_VERSION.to_string(),
+                            id: request.id,
+                            result: None,
+                            error: Some(RpcError::invalid_request(
+                                "invalid JSON-RPC version, expected `2.0`",
+                            )),
+                        }
+                        .map_result_to_value(),
+                    );
+                    continue;
+                }
+
+                if let Some(resp) = server
+                    .handle_request_with(request, Some(&messages_tx), None)
+                    .await
+                {
+                    tracing::info!(response = ?resp, "sending response");
+                    resps.push(resp);
+                }
+            }
 
-            if let Some(resp) = server.handle_request(request).await {
-                tracing::info!(response = ?resp, "sending response");
-                print_response(resp);
+            match (is_batch, resps.pop()) {
+                (_, None) => {}
+                (false, Some(resp)) => send_message(&messages_tx, resp),
+                (true, Some(resp)) => {
+                    resps.push(resp);
+                    send_message(&messages_tx, resps);
+                }
             }
         }
-    }
+    };
+
+    let write = async move {
+        while let Some(mut message) = messages_rx.recv().await {
+            message.push('\n');
+            writer.write_all(message.as_bytes()).await?;
+            writer.flush().await?;
+        }
+        IoResult::Ok(())
+    };
 
-    Ok(())
+    let (read_res, (), write_res) = tokio::join!(read, handle, write);
+    read_res.and(write_res)
 }
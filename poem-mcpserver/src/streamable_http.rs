@@ -1,11 +1,12 @@
 //! Streamable HTTP endpoint for handling MCP requests.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{Arc, Mutex},
     time::Duration,
 };
 
+use itertools::Either;
 use mime::Mime;
 use poem::{
     EndpointExt, IntoEndpoint, IntoResponse, Request, handler,
@@ -16,93 +17,283 @@ use poem::{
         sse::{Event, SSE},
     },
 };
-use tokio::time::Instant;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{
+    sync::{broadcast, mpsc},
+    time::Instant,
+};
+use tracing::Instrument;
 
 use crate::{
     McpServer,
-    protocol::rpc::{BatchRequest as McpBatchRequest, Request as McpRequest},
+    auth::Principal,
+    peer::PendingRequests,
+    protocol::rpc::{BatchRequest as McpBatchRequest, ClientResponse, Requests},
+    resource::Resources,
+    server::{Cancellations, tools_changed, tools_list_changed_notification},
     tool::Tools,
 };
 
 const SESSION_TIMEOUT: Duration = Duration::from_secs(60 * 5);
 
-type ServerFactoryFn<ToolsType> = Box<dyn Fn(&Request) -> McpServer<ToolsType> + Send + Sync>;
+/// The maximum number of events of a session that can be replayed.
+const MAX_EVENT_HISTORY: usize = 256;
+
+type ServerFactoryFn<ToolsType, ResourcesType> =
+    Box<dyn Fn(&Request) -> McpServer<ToolsType, ResourcesType> + Send + Sync>;
+
+type SharedServer<ToolsType, ResourcesType> =
+    Arc<tokio::sync::Mutex<McpServer<ToolsType, ResourcesType>>>;
 
-struct Session<ToolsType> {
-    server: Arc<tokio::sync::Mutex<McpServer<ToolsType>>>,
+type SharedEvents = Arc<Mutex<EventHistory>>;
+
+struct Session<ToolsType, ResourcesType> {
+    server: SharedServer<ToolsType, ResourcesType>,
+    events: SharedEvents,
+    cancellations: Cancellations,
+    pending_requests: PendingRequests,
+    tools_changed: Option<broadcast::Receiver<()>>,
     last_active: Instant,
+    /// The principal which created the session.
+    principal: Option<Principal>,
+}
+
+impl<ToolsType, ResourcesType> Session<ToolsType, ResourcesType> {
+    /// Returns `true` if the session can be used by the principal, which must
+    /// have the subject of the principal that created it.
+    fn is_owned_by(&self, principal: Option<&Principal>) -> bool {
+        self.principal.as_ref().map(Principal::subject) == principal.map(Principal::subject)
+    }
+}
+
+/// The events sent in the streams of a session, which are replayed when the
+/// client resumes a stream with the `Last-Event-ID` header.
+#[derive(Default)]
+struct EventHistory {
+    last_id: u64,
+    events: VecDeque<(u64, String)>,
+}
+
+impl EventHistory {
+    fn push(&mut self, data: String) -> Event {
+        self.last_id += 1;
+        if self.events.len() == MAX_EVENT_HISTORY {
+            self.events.pop_front();
+        }
+        self.events.push_back((self.last_id, data.clone()));
+        message_event(self.last_id, data)
+    }
+
+    fn events_after(&self, last_event_id: u64) -> Vec<Event> {
+        self.events
+            .iter()
+            .filter(|(id, _)| *id > last_event_id)
+            .map(|(id, data)| message_event(*id, data.clone()))
+            .collect()
+    }
 }
 
-struct State<ToolsType> {
-    server_factory: ServerFactoryFn<ToolsType>,
-    sessions: Mutex<HashMap<String, Session<ToolsType>>>,
+fn message_event(id: u64, data: String) -> Event {
+    Event::message(data)
+        .event_type("message")
+        .id(id.to_string())
 }
 
-async fn handle_request<ToolsType>(
-    server: Arc<tokio::sync::Mutex<McpServer<ToolsType>>>,
-    session_id: &str,
+struct State<ToolsType, ResourcesType> {
+    server_factory: ServerFactoryFn<ToolsType, ResourcesType>,
+    sessions: Mutex<HashMap<String, Session<ToolsType, ResourcesType>>>,
+}
+
+impl<ToolsType, ResourcesType> State<ToolsType, ResourcesType> {
+    /// Returns the session of the `Mcp-Session-Id` header.
+    ///
+    /// The sessions created by another principal are not found, so that their
+    /// existence is not disclosed.
+    fn session(
+        &self,
+        request: &Request,
+    ) -> Result<SessionHandle<ToolsType, ResourcesType>, StatusCode> {
+        let session_id = session_id_header(request.headers()).ok_or(StatusCode::BAD_REQUEST)?;
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(session_id)
+            .filter(|session| session.is_owned_by(request.extensions().get()))
+            .ok_or(StatusCode::NOT_FOUND)?;
+        session.last_active = Instant::now();
+        Ok(SessionHandle {
+            session_id: session_id.to_string(),
+            server: session.server.clone(),
+            events: session.events.clone(),
+            cancellations: session.cancellations.clone(),
+            pending_requests: session.pending_requests.clone(),
+            tools_changed: session
+                .tools_changed
+                .as_ref()
+                .map(broadcast::Receiver::resubscribe),
+        })
+    }
+}
+
+struct SessionHandle<ToolsType, ResourcesType> {
+    session_id: String,
+    server: SharedServer<ToolsType, ResourcesType>,
+    events: SharedEvents,
+    cancellations: Cancellations,
+    pending_requests: PendingRequests,
+    tools_changed: Option<broadcast::Receiver<()>>,
+}
+
+async fn handle_request<ToolsType, ResourcesType>(
+    session: SessionHandle<ToolsType, ResourcesType>,
+    principal: Option<Principal>,
     accept: &Mime,
-    requests: impl Iterator<Item = McpRequest> + Send + 'static,
+    batch_request: McpBatchRequest,
 ) -> impl IntoResponse
 where
     ToolsType: Tools + Send + Sync + 'static,
+    ResourcesType: Resources + Send + Sync + 'static,
 {
+    let SessionHandle {
+        session_id,
+        server,
+        events,
+        cancellations,
+        ..
+    } = session;
+
     tracing::info!(
         session_id = session_id,
         accept = accept.essence_str(),
         "handling requests"
     );
 
+    // the notifications and the responses are only acknowledged
+    if batch_request
+        .requests()
+        .iter()
+        .all(|request| request.id.is_none())
+    {
+        for request in batch_request {
+            tracing::info!(session_id = session_id, request = ?request, "received notification");
+            // the server is locked while the request to cancel is running
+            match &request.body {
+                Requests::Cancelled { params } => cancellations.cancel(params),
+                _ => {
+                    _ = server
+                        .lock()
+                        .await
+                        .handle_request_with(request, None, principal.as_ref())
+                        .instrument(tracing::info_span!("mcp.session", session_id))
+                        .await
+                }
+            }
+        }
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    let is_batch = matches!(batch_request, McpBatchRequest::Batch(_));
     match accept.essence_str() {
         "application/json" => {
             let mut resps = vec![];
-            for request in requests {
+            for request in batch_request {
                 tracing::info!(session_id = session_id, request = ?request, "received request");
-                let resp = server.lock().await.handle_request(request).await;
+                let resp = server
+                    .lock()
+                    .await
+                    .handle_request_with(request, None, principal.as_ref())
+                    .instrument(tracing::info_span!("mcp.session", session_id))
+                    .await;
                 tracing::info!(session_id = session_id, response = ?resp, "sending response");
                 resps.extend(resp);
             }
-            Json(resps)
-                .with_content_type("application/json")
-                .into_response()
+            match is_batch {
+                true => Json(resps).into_response(),
+                false => Json(resps.pop()).into_response(),
+            }
         }
-        "text/event-stream" => {
-            let session_id = session_id.to_string();
-            SSE::new(async_stream::stream! {
-                for request in requests {
-                    tracing::info!(session_id = session_id, request = ?request, "received request");
-                    let resp = server.lock().await.handle_request(request).await;
-                    tracing::info!(session_id = session_id, response = ?resp, "sending response");
-                    yield Event::message(serde_json::to_string(&resp).unwrap()).event_type("message");
+        "text/event-stream" => SSE::new(async_stream::stream! {
+            for request in batch_request {
+                tracing::info!(session_id = session_id, request = ?request, "received request");
+                let (notifier, mut notifications) = mpsc::unbounded_channel();
+                let mut guard = server.lock().await;
+                let mut handle = std::pin::pin!(guard
+                    .handle_request_with(request, Some(&notifier), principal.as_ref())
+                    .instrument(tracing::info_span!("mcp.session", session_id)));
+
+                // the notifications sent while handling the request precede the response
+                let resp = loop {
+                    let item = tokio::select! {
+                        resp = &mut handle => Either::Right(resp),
+                        Some(data) = notifications.recv() => Either::Left(data),
+                    };
+                    match item {
+                        Either::Left(data) => {
+                            let event = events.lock().unwrap().push(data);
+                            yield event;
+                        }
+                        Either::Right(resp) => break resp,
+                    }
+                };
+                while let Ok(data) = notifications.try_recv() {
+                    let event = events.lock().unwrap().push(data);
+                    yield event;
                 }
-            })
-            .into_response()
-        }
+
+                tracing::info!(session_id = session_id, response = ?resp, "sending response");
+                if let Some(resp) = resp {
+                    let data = serde_json::to_string(&resp).unwrap();
+                    let event = events.lock().unwrap().push(data);
+                    yield event;
+                }
+            }
+        })
+        .into_response(),
         _ => StatusCode::BAD_REQUEST.into_response(),
     }
 }
 
 #[handler]
-async fn post_handler<ToolsType>(
-    data: Data<&Arc<State<ToolsType>>>,
+async fn post_handler<ToolsType, ResourcesType>(
+    data: Data<&Arc<State<ToolsType, ResourcesType>>>,
     request: &Request,
-    batch_request: Json<McpBatchRequest>,
+    body: Json<Value>,
     accept: Accept,
 ) -> impl IntoResponse
 where
     ToolsType: Tools + Send + Sync + 'static,
+    ResourcesType: Resources + Send + Sync + 'static,
 {
     let Some(accept) = accept.0.first() else {
         return StatusCode::BAD_REQUEST.into_response();
     };
 
+    let batch_request = match McpBatchRequest::deserialize(&body.0) {
+        Ok(batch_request) => batch_request,
+        Err(err) => {
+            // the response to a request sent by the server
+            if let Ok(response) = ClientResponse::deserialize(&body.0) {
+                return match data.0.session(request) {
+                    Ok(session) => {
+                        session.pending_requests.complete(response);
+                        StatusCode::ACCEPTED.into_response()
+                    }
+                    Err(status) => status.into_response(),
+                };
+            }
+            tracing::error!(error = ?err, "failed to parse request");
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
     if batch_request.len() == 1
         && batch_request.requests()[0].is_initialize()
         && !request.headers().contains_key("Mcp-Session-Id")
     {
         let session_id = session_id();
+        let principal = request.extensions().get::<Principal>().cloned();
         let mut server = (data.0.server_factory)(request);
-        let initialize_request = batch_request.0.into_iter().next().unwrap();
+        let initialize_request = batch_request.into_iter().next().unwrap();
         let resp = server
             .handle_request(initialize_request)
             .await
@@ -111,8 +302,13 @@ where
         sessions.insert(
             session_id.clone(),
             Session {
+                cancellations: server.cancellations(),
+                pending_requests: server.pending_requests(),
+                tools_changed: server.subscribe_tools_changed(),
                 server: Arc::new(tokio::sync::Mutex::new(server)),
+                events: Default::default(),
                 last_active: Instant::now(),
+                principal,
             },
         );
 
@@ -122,45 +318,91 @@ where
             .into_response();
     }
 
-    let Some(session_id) = request
-        .headers()
-        .get("Mcp-Session-Id")
-        .and_then(|value| value.to_str().ok())
-    else {
-        return StatusCode::BAD_REQUEST.into_response();
-    };
-
-    let server = {
-        let mut sessions = data.0.sessions.lock().unwrap();
-        let Some(session) = sessions.get_mut(session_id) else {
-            return StatusCode::NOT_FOUND.into_response();
-        };
-        session.last_active = Instant::now();
-        session.server.clone()
+    let session = match data.0.session(request) {
+        Ok(session) => session,
+        Err(status) => return status.into_response(),
     };
 
-    handle_request(server, session_id, accept, batch_request.0.into_iter())
+    let principal = request.extensions().get::<Principal>().cloned();
+    handle_request(session, principal, accept, batch_request)
         .await
         .into_response()
 }
 
+/// Resumes a stream of the session, the events after the `Last-Event-ID`
+/// header are sent again.
 #[handler]
-async fn delete_handler<ToolsType>(
-    data: Data<&Arc<State<ToolsType>>>,
-    headers: &HeaderMap,
+async fn get_handler<ToolsType, ResourcesType>(
+    data: Data<&Arc<State<ToolsType, ResourcesType>>>,
+    request: &Request,
 ) -> impl IntoResponse
 where
     ToolsType: Tools + Send + Sync + 'static,
+    ResourcesType: Resources + Send + Sync + 'static,
 {
-    let Some(session_id) = headers
-        .get("Mcp-Session-Id")
+    let SessionHandle {
+        session_id,
+        events,
+        tools_changed: tools_changed_rx,
+        ..
+    } = match data.0.session(request) {
+        Ok(session) => session,
+        Err(status) => return status.into_response(),
+    };
+
+    let Some(last_event_id) = request
+        .headers()
+        .get("Last-Event-ID")
         .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
     else {
+        // the server only sends messages outside of the responses when the
+        // tools change
+        if tools_changed_rx.is_none() {
+            return StatusCode::METHOD_NOT_ALLOWED.into_response();
+        }
+
+        tracing::info!(session_id = session_id, "opening stream");
+        return SSE::new(async_stream::stream! {
+            let mut tools_changed_rx = tools_changed_rx;
+            loop {
+                tools_changed(&mut tools_changed_rx).await;
+                let event = events.lock().unwrap().push(tools_list_changed_notification());
+                yield event;
+            }
+        })
+        .into_response();
+    };
+
+    let events = events.lock().unwrap().events_after(last_event_id);
+    tracing::info!(
+        session_id = session_id,
+        last_event_id = last_event_id,
+        events = events.len(),
+        "resuming stream"
+    );
+    SSE::new(tokio_stream::iter(events)).into_response()
+}
+
+#[handler]
+async fn delete_handler<ToolsType, ResourcesType>(
+    data: Data<&Arc<State<ToolsType, ResourcesType>>>,
+    request: &Request,
+) -> impl IntoResponse
+where
+    ToolsType: Tools + Send + Sync + 'static,
+    ResourcesType: Resources + Send + Sync + 'static,
+{
+    let Some(session_id) = session_id_header(request.headers()) else {
         return StatusCode::BAD_REQUEST;
     };
 
-    if data.sessions.lock().unwrap().remove(session_id).is_none() {
-        return StatusCode::NOT_FOUND;
+    let mut sessions = data.sessions.lock().unwrap();
+    match sessions.get(session_id) {
+        Some(session) if session.is_owned_by(request.extensions().get()) => {
+            sessions.remove(session_id);
+        }
+        _ => return StatusCode::NOT_FOUND,
     }
 
     tracing::info!(session_id = session_id, "deleted session");
@@ -168,10 +410,27 @@ where
 }
 
 /// A streamable http endpoint that can be used to handle MCP requests.
-pub fn endpoint<F, ToolsType>(server_factory: F) -> impl IntoEndpoint
+///
+/// The endpoint implements the
+/// [Streamable HTTP](https://modelcontextprotocol.io/specification/2025-03-26/basic/transports#streamable-http)
+/// transport:
+///
+/// - `POST` sends JSON-RPC messages, the responses are returned as JSON or as a
+///   stream of server-sent events, depending on the `Accept` header. The first
+///   request must be an `initialize` request, which creates a session whose id
+///   is returned in the `Mcp-Session-Id` header.
+/// - `GET` resumes a stream with the `Last-Event-ID` header, or opens a stream
+///   of the `notifications/tools/list_changed` notifications if the server has
+///   [dynamic tools](crate::McpServer::dynamic_tools).
+/// - `DELETE` terminates the session.
+///
+/// The session can only be used by the [`Principal`] which created it, the
+/// requests of other principals get a `404 Not Found` response.
+pub fn endpoint<F, ToolsType, ResourcesType>(server_factory: F) -> impl IntoEndpoint
 where
-    F: Fn(&Request) -> McpServer<ToolsType> + Send + Sync + 'static,
+    F: Fn(&Request) -> McpServer<ToolsType, ResourcesType> + Send + Sync + 'static,
     ToolsType: Tools + Send + Sync + 'static,
+    ResourcesType: Resources + Send + Sync + 'static,
 {
     let state = Arc::new(State {
         server_factory: Box::new(server_factory),
@@ -190,11 +449,18 @@ where
         }
     });
 
-    post(post_handler::<ToolsType>::default())
-        .delete(delete_handler::<ToolsType>::default())
+    post(post_handler::<ToolsType, ResourcesType>::default())
+        .get(get_handler::<ToolsType, ResourcesType>::default())
+        .delete(delete_handler::<ToolsType, ResourcesType>::default())
         .data(state)
 }
 
+fn session_id_header(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("Mcp-Session-Id")
+        .and_then(|value| value.to_str().ok())
+}
+
 fn session_id() -> String {
     format!("{:016x}", rand::random::<u128>())
 }
@@ -0,0 +1,275 @@
+//! Test utilities to test the tools of a server in-process.
+
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+
+use crate::{
+    McpServer,
+    protocol::{
+        JSON_RPC_VERSION,
+        rpc::{ClientResponse, RequestId, RpcError},
+    },
+    resource::{NoResources, Resources},
+    tool::Tools,
+};
+
+type ClientHandler = Box<dyn FnMut(&str, Value) -> Result<Value, RpcError> + Send>;
+
+/// A client to test a [`McpServer`] without a transport.
+///
+/// The requests are handled by the server in-process, and the notifications
+/// sent by the server while handling them are collected.
+///
+/// ```
+/// use poem_mcpserver::{McpServer, Tools, content::Text, test::TestMcpClient};
+/// use serde_json::json;
+///
+/// struct Counter {
+///     count: i32,
+/// }
+///
+/// #[Tools]
+/// impl Counter {
+///     /// Increment the counter by the value.
+///     async fn increment(&mut self, value: i32) -> Text<i32> {
+///         self.count += value;
+///         Text(self.count)
+///     }
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let mut client = TestMcpClient::new(McpServer::new().tools(Counter { count: 0 }));
+/// client.initialize().await.assert_ok();
+/// client.list_tools().await.assert_tool_names(&["increment"]);
+/// client
+///     .call_tool("increment", json!({ "value": 2 }))
+///     .await
+///     .assert_text("2");
+/// # });
+/// ```
+pub struct TestMcpClient<ToolsType, ResourcesType = NoResources> {
+    server: McpServer<ToolsType, ResourcesType>,
+    last_id: i64,
+    notifications: Vec<Value>,
+    handler: ClientHandler,
+}
+
+impl<ToolsType, ResourcesType> TestMcpClient<ToolsType, ResourcesType>
+where
+    ToolsType: Tools,
+    ResourcesType: Resources,
+{
+    /// Creates a client of the server.
+    pub fn new(server: McpServer<ToolsType, ResourcesType>) -> Self {
+        Self {
+            server,
+            last_id: 0,
+            notifications: vec![],
+            handler: Box::new(|method, _| {
+                Err(RpcError::method_not_found(format!(
+                    "method not found: {method}"
+                )))
+            }),
+        }
+    }
+
+    /// Sets the function that answers the requests sent by the server, such
+    /// as `sampling/createMessage`.
+    ///
+    /// The function is called with the method and the parameters of the
+    /// request. By default, the requests fail with a method not found error.
+    pub fn client_handler<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&str, Value) -> Result<Value, RpcError> + Send + 'static,
+    {
+        self.handler = Box::new(f);
+        self
+    }
+
+    /// Returns the server.
+    #[inline]
+    pub fn server(&mut self) -> &mut McpServer<ToolsType, ResourcesType> {
+        &mut self.server
+    }
+
+    /// Sends a request and returns its response.
+    pub async fn request(&mut self, method: &str, params: Value) -> TestMcpResponse {
+        self.last_id += 1;
+        let message = json!({
+            "jsonrpc": JSON_RPC_VERSION,
+            "id": self.last_id,
+            "method": method,
+            "params": params,
+        });
+        let resp = self.send(message).await;
+        TestMcpResponse(resp.expect("the request has a response"))
+    }
+
+    /// Sends a notification.
+    pub async fn notify(&mut self, method: &str, params: Value) {
+        let message = json!({
+            "jsonrpc": JSON_RPC_VERSION,
+            "method": method,
+            "params": params,
+        });
+        self.send(message).await;
+    }
+
+    async fn send(&mut self, message: Value) -> Option<Value> {
+        let request = serde_json::from_value(message).expect("valid request");
+        let pending_requests = self.server.pending_requests();
+        let (notifier, mut messages) = mpsc::unbounded_channel();
+
+        let handle = self
+            .server
+            .handle_request_with(request, Some(&notifier), None);
+        let mut handle = std::pin::pin!(handle);
+        let resp = loop {
+            tokio::select! {
+                resp = &mut handle => break resp,
+                Some(message) = messages.recv() => {
+                    let message: Value = serde_json::from_str(&message).unwrap();
+                    match message.get("id") {
+                        // a request sent to the client
+                        Some(id) => {
+                            let id: RequestId = serde_json::from_value(id.clone()).unwrap();
+                            let method = message["method"].as_str().unwrap_or_default();
+                            let params = message.get("params").cloned().unwrap_or_default();
+                            let (result, error) = match (self.handler)(method, params) {
+                                Ok(result) => (Some(result), None),
+                                Err(err) => (None, Some(RpcError::new(err.code(), err.message()))),
+                            };
+                            pending_requests.complete(ClientResponse {
+                                jsonrpc: JSON_RPC_VERSION.to_string(),
+                                id,
+                                result,
+                                error,
+                            });
+                        }
+                        None => self.notifications.push(message),
+                    }
+                }
+            }
+        };
+        while let Ok(message) = messages.try_recv() {
+            self.notifications
+                .push(serde_json::from_str(&message).unwrap());
+        }
+        resp.map(|resp| serde_json::to_value(resp).unwrap())
+    }
+
+    /// Initializes the session, without client capabilities.
+    pub async fn initialize(&mut self) -> TestMcpResponse {
+        self.initialize_with(json!({})).await
+    }
+
+    /// Initializes the session with the client capabilities.
+    pub async fn initialize_with(&mut self, capabilities: Value) -> TestMcpResponse {
+        let resp = self
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": capabilities,
+                    "clientInfo": { "name": "poem-mcpserver-test", "version": "0.1.0" },
+                }),
+            )
+            .await;
+        self.notify("notifications/initialized", json!({})).await;
+        resp
+    }
+
+    /// Lists the tools.
+    pub async fn list_tools(&mut self) -> TestMcpResponse {
+        self.request("tools/list", json!({})).await
+    }
+
+    /// Calls a tool with the arguments.
+    pub async fn call_tool(&mut self, name: &str, arguments: Value) -> TestMcpResponse {
+        self.request(
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        )
+        .await
+    }
+
+    /// Returns the notifications received since the last call.
+    pub fn take_notifications(&mut self) -> Vec<Value> {
+        std::mem::take(&mut self.notifications)
+    }
+}
+
+/// A response of a [`TestMcpClient`] request.
+#[derive(Debug, Clone)]
+pub struct TestMcpResponse(Value);
+
+impl TestMcpResponse {
+    /// Returns the JSON-RPC response.
+    #[inline]
+    pub fn json(&self) -> &Value {
+        &self.0
+    }
+
+    /// Returns the result of the response.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the response is an error.
+    #[track_caller]
+    pub fn result(&self) -> &Value {
+        match self.0.get("result") {
+            Some(result) => result,
+            None => panic!("expected a result, got {}", self.0),
+        }
+    }
+
+    /// Asserts that the response is not an error.
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        self.result();
+    }
+
+    /// Asserts that the response is an error with the code.
+    #[track_caller]
+    pub fn assert_error_code(&self, code: i32) {
+        assert_eq!(self.0["error"]["code"], code, "response: {}", self.0);
+    }
+
+    /// Asserts that the response is an error with the message.
+    #[track_caller]
+    pub fn assert_error_message(&self, message: &str) {
+        assert_eq!(self.0["error"]["message"], message, "response: {}", self.0);
+    }
+
+    /// Asserts the names of the tools of a `tools/list` response.
+    #[track_caller]
+    pub fn assert_tool_names(&self, names: &[&str]) {
+        let tools = self.result()["tools"].as_array().expect("tools");
+        let tool_names = tools
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(tool_names, names);
+    }
+
+    /// Asserts that the contents of a `tools/call` response are a single text.
+    #[track_caller]
+    pub fn assert_text(&self, text: &str) {
+        assert_eq!(
+            self.result()["content"],
+            json!([{ "type": "text", "text": text }])
+        );
+    }
+
+    /// Asserts the structured content of a `tools/call` response.
+    #[track_caller]
+    pub fn assert_structured_content(&self, value: Value) {
+        assert_eq!(self.result()["structuredContent"], value);
+    }
+
+    /// Asserts whether a `tools/call` response is an error.
+    #[track_caller]
+    pub fn assert_is_error(&self, is_error: bool) {
+        assert_eq!(self.result()["isError"], is_error);
+    }
+}
@@ -1,24 +1,157 @@
 //! Types for tool.
 
-use std::{fmt::Display, future::Future};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
+use schemars::JsonSchema;
+use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    content::IntoContents,
+    auth::Principal,
+    content::{IntoContents, Json},
+    elicitation::ElicitationClient,
+    peer::Peer,
     protocol::{
         content::Content,
-        rpc::RpcError,
+        logging::LoggingLevel,
+        notifications::ProgressNotification,
+        roots::Root,
+        rpc::{Notification, ProgressToken, RpcError},
         tool::{Tool as PTool, ToolsCallResponse},
     },
+    sampling::SamplingClient,
 };
 
+/// The context of a tool call.
+///
+/// A tool receives the context by declaring a `&ToolContext` parameter, which
+/// is not part of the tool arguments.
+///
+/// ```
+/// use poem_mcpserver::{Tools, content::Text, tool::ToolContext};
+///
+/// struct Worker;
+///
+/// #[Tools]
+/// impl Worker {
+///     /// Process the items one by one.
+///     async fn process(&self, ctx: &ToolContext, items: u32) -> Text<String> {
+///         for item in 0..items {
+///             if ctx.is_cancelled() {
+///                 return Text("cancelled".to_string());
+///             }
+///             ctx.report_progress(item as f64, Some(items as f64));
+///         }
+///         Text("done".to_string())
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ToolContext {
+    pub(crate) progress_token: Option<ProgressToken>,
+    pub(crate) peer: Peer,
+    pub(crate) cancellation_token: CancellationToken,
+    pub(crate) principal: Option<Principal>,
+}
+
+impl ToolContext {
+    /// Returns a handle to send sampling requests to the client.
+    #[inline]
+    pub fn sampling(&self) -> SamplingClient {
+        SamplingClient::new(self.peer.clone())
+    }
+
+    /// Returns a handle to ask the user for additional input.
+    #[inline]
+    pub fn elicitation(&self) -> ElicitationClient {
+        ElicitationClient::new(self.peer.clone())
+    }
+
+    /// Returns the authenticated principal of the request, if any.
+    #[inline]
+    pub fn principal(&self) -> Option<&Principal> {
+        self.principal.as_ref()
+    }
+
+    /// Sends a progress notification to the client.
+    ///
+    /// The notification is only sent if the client asked for it with a
+    /// progress token, and if the transport can send notifications.
+    pub fn report_progress(&self, progress: f64, total: Option<f64>) {
+        let Some(progress_token) = &self.progress_token else {
+            return;
+        };
+        self.peer.notify(Notification::new(
+            "notifications/progress",
+            ProgressNotification {
+                progress_token: progress_token.clone(),
+                progress,
+                total,
+            },
+        ));
+    }
+
+    /// Returns the roots the client gives the server access to.
+    ///
+    /// The roots are requested from the client once, and again after the
+    /// client notifies that they changed.
+    pub async fn roots(&self) -> Result<Vec<Root>, RpcError> {
+        self.peer.roots().await
+    }
+
+    /// Sends a log message to the client with a `notifications/message`
+    /// notification.
+    ///
+    /// The message is only sent if its level is at least the level set by the
+    /// client with `logging/setLevel`, which is `info` by default.
+    pub fn log(&self, level: LoggingLevel, data: impl Serialize) {
+        let data = serde_json::to_value(data).unwrap_or_default();
+        self.peer.log(level, None, data);
+    }
+
+    /// Returns the token cancelled when the client sends a
+    /// `notifications/cancelled` notification for this call.
+    #[inline]
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Returns `true` if the call has been cancelled by the client.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
+    /// Waits until the call is cancelled by the client.
+    pub async fn cancelled(&self) {
+        self.cancellation_token.cancelled().await
+    }
+}
+
 /// Represents the result of a tool call.
 pub trait IntoToolResponse {
+    /// Returns the JSON schema of the structured content of the response.
+    fn output_schema() -> Option<Value> {
+        None
+    }
+
     /// Consumes the object and converts it into a tool response.
     fn into_tool_response(self) -> ToolsCallResponse;
 }
 
+pub(crate) fn schema_for<T: JsonSchema>() -> Value {
+    let schema = schemars::SchemaGenerator::default().into_root_schema_for::<T>();
+    serde_json::to_value(schema).expect("serialize schema")
+}
+
 impl<T> IntoToolResponse for T
 where
     T: IntoContents,
@@ -26,6 +159,7 @@ where
     fn into_tool_response(self) -> ToolsCallResponse {
         ToolsCallResponse {
             content: self.into_contents(),
+            structured_content: None,
             is_error: false,
         }
     }
@@ -40,31 +174,108 @@ where
         match self {
             Ok(value) => ToolsCallResponse {
                 content: value.into_contents(),
+                structured_content: None,
                 is_error: false,
             },
-            Err(error) => ToolsCallResponse {
-                content: vec![Content::Text {
-                    text: error.to_string(),
-                }],
-                is_error: true,
-            },
+            Err(error) => error_response(error),
+        }
+    }
+}
+
+impl<T> IntoToolResponse for Json<T>
+where
+    T: Serialize + JsonSchema,
+{
+    fn output_schema() -> Option<Value> {
+        Some(schema_for::<T>())
+    }
+
+    fn into_tool_response(self) -> ToolsCallResponse {
+        let (content, structured_content) = self.into_structured_content();
+        ToolsCallResponse {
+            content,
+            structured_content: Some(structured_content),
+            is_error: false,
+        }
+    }
+}
+
+impl<T, E> IntoToolResponse for Result<Json<T>, E>
+where
+    T: Serialize + JsonSchema,
+    E: Display,
+{
+    fn output_schema() -> Option<Value> {
+        Some(schema_for::<T>())
+    }
+
+    fn into_tool_response(self) -> ToolsCallResponse {
+        match self {
+            Ok(value) => value.into_tool_response(),
+            Err(error) => error_response(error),
         }
     }
 }
 
+fn error_response(error: impl Display) -> ToolsCallResponse {
+    ToolsCallResponse {
+        content: vec![Content::Text {
+            text: error.to_string(),
+        }],
+        structured_content: None,
+        is_error: true,
+    }
+}
+
 /// Represents a tools collection.
+///
+/// A tuple of tools collections is a tools collection with the tools of all
+/// of them, so that the tools of a server can be organized across several
+/// types.
+///
+/// ```
+/// use poem_mcpserver::{McpServer, Tools, content::Text};
+///
+/// struct Files;
+///
+/// #[Tools]
+/// impl Files {
+///     /// Read a file.
+///     async fn read_file(&self, path: String) -> Text<String> {
+///         Text(format!("content of {path}"))
+///     }
+/// }
+///
+/// struct Clock;
+///
+/// #[Tools]
+/// impl Clock {
+///     /// Get the current time.
+///     async fn now(&self) -> Text<&'static str> {
+///         Text("12:00")
+///     }
+/// }
+///
+/// let server = McpServer::new().tools((Files, Clock));
+/// ```
 pub trait Tools {
     /// Returns the instructions for the tools.
-    fn instructions() -> &'static str;
+    fn instructions() -> Cow<'static, str>;
 
     /// Returns a list of tools.
     fn list() -> Vec<PTool>;
 
+    /// Returns `true` if the collection contains a tool with the name.
+    fn contains(name: &str) -> bool {
+        Self::list().iter().any(|tool| tool.name == name)
+    }
+
     /// Calls a tool.
     fn call(
         &mut self,
         name: &str,
         arguments: Value,
+        ctx: &ToolContext,
     ) -> impl Future<Output = Result<ToolsCallResponse, RpcError>> + Send;
 }
 
@@ -74,8 +285,8 @@ pub struct NoTools;
 
 impl Tools for NoTools {
     #[inline]
-    fn instructions() -> &'static str {
-        ""
+    fn instructions() -> Cow<'static, str> {
+        Cow::Borrowed("")
     }
 
     #[inline]
@@ -84,9 +295,256 @@ impl Tools for NoTools {
     }
 
     #[inline]
-    async fn call(&mut self, name: &str, _arguments: Value) -> Result<ToolsCallResponse, RpcError> {
+    fn contains(_name: &str) -> bool {
+        false
+    }
+
+    #[inline]
+    async fn call(
+        &mut self,
+        name: &str,
+        _arguments: Value,
+        _ctx: &ToolContext,
+    ) -> Result<ToolsCallResponse, RpcError> {
         Err(RpcError::method_not_found(format!(
             "tool '{name}' not found"
         )))
     }
 }
+
+macro_rules! impl_tools_for_tuple {
+    ($(($ty:ident, $idx:tt)),*) => {
+        impl<$($ty),*> Tools for ($($ty,)*)
+        where
+            $($ty: Tools + Send,)*
+        {
+            fn instructions() -> Cow<'static, str> {
+                let instructions = [$($ty::instructions()),*];
+                let instructions = instructions
+                    .iter()
+                    .filter(|instructions| !instructions.is_empty())
+                    .map(|instructions| &**instructions)
+                    .collect::<Vec<_>>();
+                match instructions.as_slice() {
+                    [] => Cow::Borrowed(""),
+                    [instructions] => Cow::Owned(instructions.to_string()),
+                    instructions => Cow::Owned(instructions.join("\n\n")),
+                }
+            }
+
+            fn list() -> Vec<PTool> {
+                let mut tools = vec![];
+                $(tools.extend($ty::list());)*
+                tools
+            }
+
+            fn contains(name: &str) -> bool {
+                $($ty::contains(name))||*
+            }
+
+            async fn call(
+                &mut self,
+                name: &str,
+                arguments: Value,
+                ctx: &ToolContext,
+            ) -> Result<ToolsCallResponse, RpcError> {
+                $(
+                    if $ty::contains(name) {
+                        return self.$idx.call(name, arguments, ctx).await;
+                    }
+                )*
+                Err(RpcError::method_not_found(format!(
+                    "tool '{name}' not found"
+                )))
+            }
+        }
+    };
+}
+
+impl_tools_for_tuple!((T1, 0), (T2, 1));
+impl_tools_for_tuple!((T1, 0), (T2, 1), (T3, 2));
+impl_tools_for_tuple!((T1, 0), (T2, 1), (T3, 2), (T4, 3));
+impl_tools_for_tuple!((T1, 0), (T2, 1), (T3, 2), (T4, 3), (T5, 4));
+impl_tools_for_tuple!((T1, 0), (T2, 1), (T3, 2), (T4, 3), (T5, 4), (T6, 5));
+impl_tools_for_tuple!(
+    (T1, 0),
+    (T2, 1),
+    (T3, 2),
+    (T4, 3),
+    (T5, 4),
+    (T6, 5),
+    (T7, 6)
+);
+impl_tools_for_tuple!(
+    (T1, 0),
+    (T2, 1),
+    (T3, 2),
+    (T4, 3),
+    (T5, 4),
+    (T6, 5),
+    (T7, 6),
+    (T8, 7)
+);
+
+type DynamicToolFuture = Pin<Box<dyn Future<Output = Result<ToolsCallResponse, RpcError>> + Send>>;
+
+type DynamicToolFn = Arc<dyn Fn(Value) -> DynamicToolFuture + Send + Sync>;
+
+struct DynamicTool {
+    name: String,
+    description: String,
+    input_schema: Value,
+    output_schema: Option<Value>,
+    handler: DynamicToolFn,
+}
+
+/// A collection of tools that can be added and removed at runtime.
+///
+/// The registry is cloned to keep a handle to it after it has been given to
+/// [`McpServer::dynamic_tools`](crate::McpServer::dynamic_tools), and the
+/// connected clients are notified with `notifications/tools/list_changed` when
+/// it changes.
+///
+/// ```
+/// use poem_mcpserver::{McpServer, content::Text, tool::DynamicTools};
+///
+/// #[derive(serde::Deserialize, schemars::JsonSchema)]
+/// struct EchoArgs {
+///     message: String,
+/// }
+///
+/// let tools = DynamicTools::new();
+/// let server = McpServer::new().dynamic_tools(tools.clone());
+///
+/// tools.add("echo", "Echo the message", |args: EchoArgs| async move {
+///     Text(args.message)
+/// });
+/// tools.remove("echo");
+/// ```
+#[derive(Clone)]
+pub struct DynamicTools {
+    tools: Arc<Mutex<Vec<DynamicTool>>>,
+    changed: broadcast::Sender<()>,
+}
+
+impl Default for DynamicTools {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicTools {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            tools: Default::default(),
+            changed: broadcast::channel(1).0,
+        }
+    }
+
+    /// Adds a tool, replacing the tool with the same name.
+    ///
+    /// The input schema of the tool is generated from the type of the
+    /// arguments.
+    pub fn add<F, Fut, A, R>(&self, name: impl Into<String>, description: impl Into<String>, f: F)
+    where
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + Send + 'static,
+        A: DeserializeOwned + JsonSchema,
+        R: IntoToolResponse,
+    {
+        let handler: DynamicToolFn =
+            Arc::new(
+                move |arguments| match serde_json::from_value::<A>(arguments) {
+                    Ok(arguments) => {
+                        let fut = f(arguments);
+                        Box::pin(async move { Ok(fut.await.into_tool_response()) })
+                    }
+                    Err(err) => Box::pin(std::future::ready(Err(RpcError::invalid_params(
+                        format!("invalid parameters: {err}"),
+                    )))),
+                },
+            );
+        let tool = DynamicTool {
+            name: name.into(),
+            description: description.into(),
+            input_schema: schema_for::<A>(),
+            output_schema: R::output_schema(),
+            handler,
+        };
+
+        let mut tools = self.tools.lock().unwrap();
+        match tools.iter_mut().find(|t| t.name == tool.name) {
+            Some(t) => *t = tool,
+            None => tools.push(tool),
+        }
+        drop(tools);
+        self.notify_changed();
+    }
+
+    /// Removes a tool, and returns `true` if it was registered.
+    pub fn remove(&self, name: &str) -> bool {
+        let mut tools = self.tools.lock().unwrap();
+        let len = tools.len();
+        tools.retain(|tool| tool.name != name);
+        let removed = tools.len() != len;
+        drop(tools);
+        if removed {
+            self.notify_changed();
+        }
+        removed
+    }
+
+    /// Returns `true` if the tool is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.tools
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|tool| tool.name == name)
+    }
+
+    fn notify_changed(&self) {
+        _ = self.changed.send(());
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.changed.subscribe()
+    }
+
+    pub(crate) fn list(&self) -> Vec<PTool> {
+        self.tools
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|tool| PTool {
+                name: Cow::Owned(tool.name.clone()),
+                description: Cow::Owned(tool.description.clone()),
+                input_schema: tool.input_schema.clone(),
+                output_schema: tool.output_schema.clone(),
+                annotations: None,
+            })
+            .collect()
+    }
+
+    pub(crate) async fn call(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolsCallResponse, RpcError> {
+        let handler = self
+            .tools
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|tool| tool.name == name)
+            .map(|tool| tool.handler.clone());
+        match handler {
+            Some(handler) => handler(arguments).await,
+            None => Err(RpcError::method_not_found(format!(
+                "tool '{name}' not found"
+            ))),
+        }
+    }
+}
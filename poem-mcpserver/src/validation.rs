@@ -0,0 +1,65 @@
+//! Validation of the tool arguments against the constraints of their schema.
+
+use serde_json::Value;
+
+use crate::protocol::rpc::RpcError;
+
+/// A constraint of a tool parameter, declared with
+/// `#[mcp(validator(...))]`.
+#[derive(Debug, Clone, Copy)]
+pub enum Validator {
+    /// The number must be greater than or equal to the value.
+    Minimum(f64),
+    /// The number must be less than or equal to the value.
+    Maximum(f64),
+    /// The string or array must have at least this length.
+    MinLength(usize),
+    /// The string or array must have at most this length.
+    MaxLength(usize),
+}
+
+impl Validator {
+    /// Checks the argument of the parameter.
+    ///
+    /// A missing argument or an argument of another type is accepted, the
+    /// deserialization of the arguments reports them.
+    pub fn check(&self, arguments: &Value, name: &str) -> Result<(), RpcError> {
+        let Some(value) = arguments.get(name) else {
+            return Ok(());
+        };
+        let error = match (*self, value) {
+            (Validator::Minimum(minimum), Value::Number(n))
+                if n.as_f64().is_some_and(|n| n < minimum) =>
+            {
+                format!("must be greater than or equal to {minimum}")
+            }
+            (Validator::Maximum(maximum), Value::Number(n))
+                if n.as_f64().is_some_and(|n| n > maximum) =>
+            {
+                format!("must be less than or equal to {maximum}")
+            }
+            (Validator::MinLength(min_length), value)
+                if length(value).is_some_and(|len| len < min_length) =>
+            {
+                format!("length must be greater than or equal to {min_length}")
+            }
+            (Validator::MaxLength(max_length), value)
+                if length(value).is_some_and(|len| len > max_length) =>
+            {
+                format!("length must be less than or equal to {max_length}")
+            }
+            _ => return Ok(()),
+        };
+        Err(RpcError::invalid_params(format!(
+            "invalid parameters: `{name}` {error}"
+        )))
+    }
+}
+
+fn length(value: &Value) -> Option<usize> {
+    match value {
+        Value::String(s) => Some(s.chars().count()),
+        Value::Array(array) => Some(array.len()),
+        _ => None,
+    }
+}
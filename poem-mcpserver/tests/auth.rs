@@ -0,0 +1,234 @@
+#![cfg(feature = "streamable-http")]
+
+use poem::{EndpointExt, http::StatusCode, test::TestClient};
+use poem_mcpserver::{
+    McpServer, Tools,
+    auth::{BearerAuth, Principal},
+    content::Text,
+    streamable_http,
+    tool::ToolContext,
+};
+use serde_json::json;
+
+struct Account;
+
+#[Tools]
+impl Account {
+    /// Get the name of the current user
+    async fn whoami(&self, ctx: &ToolContext) -> Text<String> {
+        Text(ctx.principal().unwrap().subject().to_string())
+    }
+
+    /// Delete everything
+    async fn delete_all(&self) -> Text<&'static str> {
+        Text("deleted")
+    }
+}
+
+async fn validate(token: String) -> Option<Principal> {
+    match token.as_str() {
+        "admin-token" => Some(Principal::new("admin").with_scopes(["mcp", "admin"])),
+        "user-token" => Some(Principal::new("user").with_scopes(["mcp"])),
+        "guest-token" => Some(Principal::new("guest")),
+        _ => None,
+    }
+}
+
+fn initialize_request() -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "test", "version": "1.0" },
+        },
+    })
+}
+
+#[tokio::test]
+async fn bearer_auth() {
+    let cli = TestClient::new(
+        streamable_http::endpoint(|_| {
+            McpServer::new()
+                .tools(Account)
+                .authorize_tools(|principal, name| {
+                    name != "delete_all" || principal.has_scope("admin")
+                })
+        })
+        .with(
+            BearerAuth::new(validate)
+                .required_scopes(["mcp"])
+                .resource_metadata("https://example.com/.well-known/oauth-protected-resource"),
+        ),
+    );
+
+    let resp = cli.post("/").body_json(&initialize_request()).send().await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+    resp.assert_header(
+        "www-authenticate",
+        r#"Bearer resource_metadata="https://example.com/.well-known/oauth-protected-resource""#,
+    );
+
+    let resp = cli
+        .post("/")
+        .header("authorization", "Bearer invalid")
+        .body_json(&initialize_request())
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+
+    let resp = cli
+        .post("/")
+        .header("authorization", "Bearer guest-token")
+        .body_json(&initialize_request())
+        .send()
+        .await;
+    resp.assert_status(StatusCode::FORBIDDEN);
+    resp.assert_header(
+        "www-authenticate",
+        r#"Bearer resource_metadata="https://example.com/.well-known/oauth-protected-resource", error="insufficient_scope", scope="mcp""#,
+    );
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("authorization", "Bearer user-token")
+        .body_json(&initialize_request())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let session_id = resp
+        .0
+        .headers()
+        .get("Mcp-Session-Id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("authorization", "Bearer user-token")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let value = resp.json().await;
+    let tools = value.value().object().get("result").object().get("tools");
+    tools.array().assert_len(1);
+    tools
+        .array()
+        .get(0)
+        .object()
+        .get("name")
+        .assert_string("whoami");
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("authorization", "Bearer user-token")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": { "name": "whoami", "arguments": {} },
+        }))
+        .send()
+        .await;
+    resp.assert_json(json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "result": { "content": [{ "type": "text", "text": "user" }], "isError": false },
+    }))
+    .await;
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("authorization", "Bearer user-token")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": { "name": "delete_all", "arguments": {} },
+        }))
+        .send()
+        .await;
+    resp.assert_json(json!({
+        "jsonrpc": "2.0",
+        "id": 4,
+        "error": { "code": -32600, "message": "tool 'delete_all' is not allowed" },
+    }))
+    .await;
+}
+
+#[tokio::test]
+async fn session_of_another_principal() {
+    let cli = TestClient::new(
+        streamable_http::endpoint(|_| McpServer::new().tools(Account))
+            .with(BearerAuth::new(validate)),
+    );
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("authorization", "Bearer user-token")
+        .body_json(&initialize_request())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let session_id = resp
+        .0
+        .headers()
+        .get("Mcp-Session-Id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("authorization", "Bearer admin-token")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "whoami", "arguments": {} },
+        }))
+        .send()
+        .await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+
+    let resp = cli
+        .get("/")
+        .header("authorization", "Bearer admin-token")
+        .header("Mcp-Session-Id", &session_id)
+        .header("Last-Event-ID", "0")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+
+    let resp = cli
+        .delete("/")
+        .header("authorization", "Bearer admin-token")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+
+    let resp = cli
+        .delete("/")
+        .header("authorization", "Bearer user-token")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await;
+    resp.assert_status(StatusCode::ACCEPTED);
+}
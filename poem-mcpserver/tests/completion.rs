@@ -0,0 +1,107 @@
+use poem_mcpserver::{McpServer, protocol::completion::CompletionReference};
+use serde_json::{Value, json};
+
+fn complete_request(reference: Value, argument: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "completion/complete",
+        "params": { "ref": reference, "argument": argument },
+    })
+}
+
+#[tokio::test]
+async fn complete() {
+    let mut server = McpServer::new().completion(|reference, argument| match reference {
+        CompletionReference::Prompt { name } if name == "greet" && argument.name == "name" => {
+            ["alice", "albert", "bob"]
+                .iter()
+                .filter(|name| name.starts_with(&argument.value))
+                .map(|name| name.to_string())
+                .collect()
+        }
+        CompletionReference::Resource { uri } if uri == "file:///{path}" => {
+            (0..150).map(|i| format!("{}{i}", argument.value)).collect()
+        }
+        _ => vec![],
+    });
+
+    let resp = server
+        .handle_request(
+            serde_json::from_value(complete_request(
+                json!({ "type": "ref/prompt", "name": "greet" }),
+                json!({ "name": "name", "value": "al" }),
+            ))
+            .unwrap(),
+        )
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap(),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "completion": { "values": ["alice", "albert"], "total": 2, "hasMore": false },
+            },
+        })
+    );
+
+    let resp = server
+        .handle_request(
+            serde_json::from_value(complete_request(
+                json!({ "type": "ref/resource", "uri": "file:///{path}" }),
+                json!({ "name": "path", "value": "dir/" }),
+            ))
+            .unwrap(),
+        )
+        .await;
+    let completion = &serde_json::to_value(&resp).unwrap()["result"]["completion"];
+    assert_eq!(completion["values"].as_array().unwrap().len(), 100);
+    assert_eq!(completion["values"][0], "dir/0");
+    assert_eq!(completion["total"], 150);
+    assert_eq!(completion["hasMore"], true);
+}
+
+#[tokio::test]
+async fn completions_capability() {
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "test", "version": "1.0" },
+        },
+    });
+
+    let resp = McpServer::new()
+        .handle_request(serde_json::from_value(initialize.clone()).unwrap())
+        .await;
+    let capabilities = &serde_json::to_value(&resp).unwrap()["result"]["capabilities"];
+    assert!(capabilities.get("completions").is_none());
+
+    let resp = McpServer::new()
+        .completion(|_, _| vec![])
+        .handle_request(serde_json::from_value(initialize).unwrap())
+        .await;
+    let capabilities = &serde_json::to_value(&resp).unwrap()["result"]["capabilities"];
+    assert_eq!(capabilities["completions"], json!({}));
+}
+
+#[tokio::test]
+async fn completions_not_supported() {
+    let resp = McpServer::new()
+        .handle_request(
+            serde_json::from_value(complete_request(
+                json!({ "type": "ref/prompt", "name": "greet" }),
+                json!({ "name": "name", "value": "al" }),
+            ))
+            .unwrap(),
+        )
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap()["error"]["code"],
+        -32601
+    );
+}
@@ -0,0 +1,120 @@
+use poem_mcpserver::{
+    McpServer, Tools,
+    content::Text,
+    elicitation::{Elicitation, ElicitationClient},
+    stdio::serve,
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct Booking {
+    /// The number of guests
+    guests: u32,
+}
+
+struct Restaurant;
+
+#[Tools]
+impl Restaurant {
+    /// Book a table
+    async fn book(&self, elicitation: ElicitationClient) -> Text<String> {
+        match elicitation.elicit::<Booking>("How many guests?").await {
+            Ok(Elicitation::Accept(booking)) => Text(format!("booked for {}", booking.guests)),
+            Ok(Elicitation::Decline) => Text("declined".to_string()),
+            Ok(Elicitation::Cancel) => Text("cancelled".to_string()),
+            Err(err) => Text(format!("error: {}", err.message())),
+        }
+    }
+}
+
+#[tokio::test]
+async fn elicit() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let handle = tokio::spawn(serve(
+        McpServer::new().tools(Restaurant),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: Value| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": { "elicitation": {} },
+            "clientInfo": { "name": "test", "version": "1.0" },
+        },
+    }))
+    .await;
+    next().await;
+
+    for (id, response, text) in [
+        (
+            2,
+            json!({ "action": "accept", "content": { "guests": 4 } }),
+            "booked for 4",
+        ),
+        (3, json!({ "action": "decline" }), "declined"),
+        (
+            4,
+            json!({ "action": "accept", "content": { "guests": "four" } }),
+            "error: invalid elicitation content: invalid type: string \"four\", expected u32",
+        ),
+    ] {
+        send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": "book", "arguments": {} },
+        }))
+        .await;
+        let request = next().await;
+        assert_eq!(request["method"], "elicitation/create");
+        assert_eq!(
+            request["params"],
+            json!({
+                "message": "How many guests?",
+                "requestedSchema": {
+                    "type": "object",
+                    "title": "Booking",
+                    "properties": {
+                        "guests": {
+                            "description": "The number of guests",
+                            "type": "integer",
+                            "format": "uint32",
+                            "minimum": 0,
+                        },
+                    },
+                    "required": ["guests"],
+                },
+            })
+        );
+
+        send(json!({ "jsonrpc": "2.0", "id": request["id"], "result": response })).await;
+        assert_eq!(
+            next().await,
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": { "content": [{ "type": "text", "text": text }], "isError": false },
+            })
+        );
+    }
+
+    client_writer.shutdown().await.unwrap();
+    handle.await.unwrap().unwrap();
+}
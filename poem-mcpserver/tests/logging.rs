@@ -0,0 +1,142 @@
+use poem_mcpserver::{
+    McpServer, Tools, content::Text, protocol::logging::LoggingLevel, stdio::serve,
+    tool::ToolContext,
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+struct Logger;
+
+#[Tools]
+impl Logger {
+    /// Log a message at every level
+    async fn log(&self, ctx: &ToolContext) -> Text<&'static str> {
+        ctx.log(LoggingLevel::Debug, "debug");
+        ctx.log(LoggingLevel::Info, json!({ "message": "info" }));
+        ctx.log(LoggingLevel::Error, "error");
+        Text("done")
+    }
+
+    /// Emit a tracing event
+    async fn trace(&self) -> Text<&'static str> {
+        tracing::warn!(target: "app", count = 3, "almost full");
+        Text("done")
+    }
+}
+
+fn request(id: i64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn log_message(level: &str, logger: Option<&str>, data: Value) -> Value {
+    let mut params = json!({ "level": level, "data": data });
+    if let Some(logger) = logger {
+        params["logger"] = logger.into();
+    }
+    json!({ "jsonrpc": "2.0", "method": "notifications/message", "params": params })
+}
+
+#[tokio::test]
+async fn set_level() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    tokio::spawn(serve(
+        McpServer::new().tools(Logger),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: Value| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    let call_log = json!({ "name": "log", "arguments": {} });
+
+    send(request(1, "tools/call", call_log.clone())).await;
+    assert_eq!(
+        next().await,
+        log_message("info", None, json!({ "message": "info" }))
+    );
+    assert_eq!(next().await, log_message("error", None, json!("error")));
+    assert_eq!(next().await["id"], 1);
+
+    send(request(2, "logging/setLevel", json!({ "level": "debug" }))).await;
+    assert_eq!(
+        next().await,
+        json!({ "jsonrpc": "2.0", "id": 2, "result": {} })
+    );
+
+    send(request(3, "tools/call", call_log.clone())).await;
+    assert_eq!(next().await, log_message("debug", None, json!("debug")));
+    assert_eq!(
+        next().await,
+        log_message("info", None, json!({ "message": "info" }))
+    );
+    assert_eq!(next().await, log_message("error", None, json!("error")));
+    assert_eq!(next().await["id"], 3);
+
+    send(request(
+        4,
+        "logging/setLevel",
+        json!({ "level": "critical" }),
+    ))
+    .await;
+    next().await;
+
+    send(request(5, "tools/call", call_log)).await;
+    assert_eq!(next().await["id"], 5);
+}
+
+#[cfg(feature = "tracing-subscriber")]
+#[tokio::test]
+async fn tracing_events() {
+    use poem_mcpserver::logging::LoggingLayer;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(LoggingLayer));
+
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    tokio::spawn(serve(
+        McpServer::new().tools(Logger),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+
+    // events outside of a tool call are not sent
+    tracing::error!(target: "app", "not in a tool call");
+
+    client_writer
+        .write_all(
+            format!(
+                "{}\n",
+                request(1, "tools/call", json!({ "name": "trace", "arguments": {} }))
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    assert_eq!(
+        next().await,
+        log_message(
+            "warning",
+            Some("app"),
+            json!({ "message": "almost full", "count": 3 })
+        )
+    );
+    assert_eq!(next().await["id"], 1);
+}
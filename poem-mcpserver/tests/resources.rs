@@ -0,0 +1,192 @@
+use poem_mcpserver::{
+    McpServer, Resources,
+    content::{Json, Text},
+    resource::UriTemplate,
+    tool::NoTools,
+};
+use serde_json::{Value, json};
+
+struct Database;
+
+#[Resources]
+impl Database {
+    /// A row of a table.
+    #[mcp(uri = "db://{table}/{id}", mime_type = "application/json")]
+    async fn row(&self, table: String, id: u64) -> Option<Json<Value>> {
+        (table == "users").then(|| Json(json!({ "id": id, "name": "alice" })))
+    }
+
+    /// A file.
+    #[mcp(uri = "file:///{path}", name = "file")]
+    async fn read_file(&self, path: String) -> Result<Text<String>, String> {
+        match path.as_str() {
+            "missing.txt" => Err(format!("{path} not found")),
+            _ => Ok(Text(format!("content of {path}"))),
+        }
+    }
+
+    /// The bytes of a blob.
+    #[mcp(uri = "blob://{+key}")]
+    async fn blob(&self, key: String) -> Vec<u8> {
+        key.into_bytes()
+    }
+}
+
+async fn request(server: &mut McpServer<NoTools, Database>, method: &str, params: Value) -> Value {
+    let resp = server
+        .handle_request(
+            serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .unwrap(),
+        )
+        .await;
+    serde_json::to_value(resp).unwrap()
+}
+
+#[test]
+fn match_uri_template() {
+    let template = UriTemplate::new("db://{table}/{id}");
+    assert_eq!(template.variables().collect::<Vec<_>>(), ["table", "id"]);
+
+    let variables = template.match_uri("db://users/1").unwrap();
+    assert_eq!(variables["table"], "users");
+    assert_eq!(variables["id"], "1");
+    assert!(template.match_uri("db://users").is_none());
+    assert!(template.match_uri("db:///1").is_none());
+    assert!(template.match_uri("file://users/1").is_none());
+
+    let template = UriTemplate::new("file:///{path}.txt");
+    assert_eq!(
+        template.match_uri("file:///a/b.txt").unwrap()["path"],
+        "a/b"
+    );
+    assert!(template.match_uri("file:///a/b.md").is_none());
+}
+
+#[tokio::test]
+async fn list_templates() {
+    let mut server = McpServer::new().resources(Database);
+    let resp = request(&mut server, "resources/templates/list", json!({})).await;
+    assert_eq!(
+        resp["result"]["resourceTemplates"],
+        json!([
+            {
+                "uriTemplate": "db://{table}/{id}",
+                "name": "row",
+                "description": "A row of a table.",
+                "mimeType": "application/json",
+            },
+            {
+                "uriTemplate": "file:///{path}",
+                "name": "file",
+                "description": "A file.",
+            },
+            {
+                "uriTemplate": "blob://{+key}",
+                "name": "blob",
+                "description": "The bytes of a blob.",
+            },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn read_resources() {
+    let mut server = McpServer::new().resources(Database);
+
+    let resp = request(
+        &mut server,
+        "resources/read",
+        json!({ "uri": "db://users/7" }),
+    )
+    .await;
+    assert_eq!(
+        resp["result"],
+        json!({
+            "contents": [{
+                "uri": "db://users/7",
+                "mimeType": "application/json",
+                "text": r#"{"id":7,"name":"alice"}"#,
+            }],
+        })
+    );
+
+    let resp = request(
+        &mut server,
+        "resources/read",
+        json!({ "uri": "file:///docs/readme.md" }),
+    )
+    .await;
+    assert_eq!(
+        resp["result"]["contents"],
+        json!([{
+            "uri": "file:///docs/readme.md",
+            "mimeType": "text/plain",
+            "text": "content of docs/readme.md",
+        }])
+    );
+
+    let resp = request(
+        &mut server,
+        "resources/read",
+        json!({ "uri": "blob://a/b" }),
+    )
+    .await;
+    assert_eq!(
+        resp["result"]["contents"],
+        json!([{
+            "uri": "blob://a/b",
+            "mimeType": "application/octet-stream",
+            "blob": "YS9i",
+        }])
+    );
+}
+
+#[tokio::test]
+async fn read_errors() {
+    let mut server = McpServer::new().resources(Database);
+
+    for (uri, code, message) in [
+        ("db://orders/1", -32002, "resource not found: db://orders/1"),
+        (
+            "db://users/x",
+            -32602,
+            "invalid variable `id`: invalid digit found in string",
+        ),
+        ("file:///missing.txt", -32603, "missing.txt not found"),
+        ("unknown://1", -32002, "resource not found: unknown://1"),
+    ] {
+        let resp = request(&mut server, "resources/read", json!({ "uri": uri })).await;
+        assert_eq!(resp["error"]["code"], code, "{uri}");
+        assert_eq!(resp["error"]["message"], message, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn paginate_templates() {
+    let mut server = McpServer::new().resources(Database).page_size(2);
+
+    let resp = request(&mut server, "resources/templates/list", json!({})).await;
+    assert_eq!(resp["result"]["resourceTemplates"][1]["name"], "file");
+    let cursor = resp["result"]["nextCursor"].clone();
+    assert!(cursor.is_string());
+
+    let resp = request(
+        &mut server,
+        "resources/templates/list",
+        json!({ "cursor": cursor }),
+    )
+    .await;
+    assert_eq!(
+        resp["result"]["resourceTemplates"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(resp["result"].get("nextCursor").is_none());
+}
@@ -0,0 +1,146 @@
+use poem_mcpserver::{McpServer, Tools, content::Text, stdio::serve, tool::ToolContext};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+struct Files;
+
+#[Tools]
+impl Files {
+    /// List the roots
+    async fn roots(&self, ctx: &ToolContext) -> Text<String> {
+        match ctx.roots().await {
+            Ok(roots) => Text(
+                roots
+                    .into_iter()
+                    .map(|root| root.uri)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            Err(err) => Text(format!("error: {}", err.message())),
+        }
+    }
+}
+
+fn initialize_request(capabilities: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": capabilities,
+            "clientInfo": { "name": "test", "version": "1.0" },
+        },
+    })
+}
+
+fn roots_request(id: i64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "roots", "arguments": {} },
+    })
+}
+
+fn roots_text(response: &Value) -> &str {
+    response["result"]["content"][0]["text"].as_str().unwrap()
+}
+
+#[tokio::test]
+async fn list_roots() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    tokio::spawn(serve(
+        McpServer::new().tools(Files),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: Value| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    send(initialize_request(
+        json!({ "roots": { "listChanged": true } }),
+    ))
+    .await;
+    next().await;
+
+    // the roots are requested when the client is initialized
+    send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await;
+    let request = next().await;
+    assert_eq!(
+        request,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "roots/list", "params": {} })
+    );
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": { "roots": [{ "uri": "file:///a", "name": "a" }] },
+    }))
+    .await;
+
+    // the roots are known, the tool doesn't send a request
+    send(roots_request(2)).await;
+    let response = next().await;
+    assert_eq!(response["id"], 2);
+    assert_eq!(roots_text(&response), "file:///a");
+
+    // the roots are requested again when they change
+    send(json!({ "jsonrpc": "2.0", "method": "notifications/roots/list_changed" })).await;
+    let request = next().await;
+    assert_eq!(request["method"], "roots/list");
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": request["id"],
+        "result": { "roots": [{ "uri": "file:///a" }, { "uri": "file:///b" }] },
+    }))
+    .await;
+
+    send(roots_request(3)).await;
+    let response = next().await;
+    assert_eq!(response["id"], 3);
+    assert_eq!(roots_text(&response), "file:///a,file:///b");
+}
+
+#[tokio::test]
+async fn roots_not_supported() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    tokio::spawn(serve(
+        McpServer::new().tools(Files),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: Value| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    send(initialize_request(json!({}))).await;
+    next().await;
+    send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await;
+
+    send(roots_request(2)).await;
+    let response = next().await;
+    assert_eq!(response["id"], 2);
+    assert_eq!(
+        roots_text(&response),
+        "error: the client doesn't support roots"
+    );
+}
@@ -0,0 +1,157 @@
+use poem_mcpserver::{
+    McpServer, Tools,
+    content::Text,
+    protocol::{
+        content::Content,
+        sampling::{CreateMessageRequest, SamplingMessage},
+    },
+    sampling::SamplingClient,
+    stdio::serve,
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+struct Writer;
+
+#[Tools]
+impl Writer {
+    /// Write a poem about the subject
+    async fn write_poem(&self, sampling: SamplingClient, subject: String) -> Text<String> {
+        let request = CreateMessageRequest::new(
+            vec![SamplingMessage::user(format!(
+                "Write a poem about {subject}"
+            ))],
+            100,
+        )
+        .system_prompt("You are a poet");
+        match sampling.create_message(request).await {
+            Ok(result) => match result.content {
+                Content::Text { text } => Text(format!("{}: {text}", result.model)),
+                _ => Text("unexpected content".to_string()),
+            },
+            Err(err) => Text(format!("error: {}", err.message())),
+        }
+    }
+}
+
+fn initialize_request(capabilities: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": capabilities,
+            "clientInfo": { "name": "test", "version": "1.0" },
+        },
+    })
+}
+
+fn write_poem_request(id: i64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "write_poem", "arguments": { "subject": "the sea" } },
+    })
+}
+
+#[tokio::test]
+async fn create_message() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let handle = tokio::spawn(serve(
+        McpServer::new().tools(Writer),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: Value| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    send(initialize_request(json!({ "sampling": {} }))).await;
+    next().await;
+
+    send(write_poem_request(2)).await;
+    let request = next().await;
+    assert_eq!(
+        request,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sampling/createMessage",
+            "params": {
+                "messages": [{
+                    "role": "user",
+                    "content": { "type": "text", "text": "Write a poem about the sea" },
+                }],
+                "systemPrompt": "You are a poet",
+                "maxTokens": 100,
+            },
+        })
+    );
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "role": "assistant",
+            "content": { "type": "text", "text": "waves" },
+            "model": "test-model",
+            "stopReason": "endTurn",
+        },
+    }))
+    .await;
+    assert_eq!(
+        next().await,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "result": {
+                "content": [{ "type": "text", "text": "test-model: waves" }],
+                "isError": false,
+            },
+        })
+    );
+
+    client_writer.shutdown().await.unwrap();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn sampling_not_supported() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let handle = tokio::spawn(serve(
+        McpServer::new().tools(Writer),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    for request in [initialize_request(json!({})), write_poem_request(2)] {
+        client_writer
+            .write_all(format!("{request}\n").as_bytes())
+            .await
+            .unwrap();
+    }
+
+    lines.next_line().await.unwrap().unwrap();
+    let resp = serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(
+        resp["result"]["content"][0]["text"],
+        "error: the client doesn't support sampling"
+    );
+
+    client_writer.shutdown().await.unwrap();
+    handle.await.unwrap().unwrap();
+}
@@ -0,0 +1,182 @@
+use poem_mcpserver::{
+    McpServer, Tools,
+    content::Text,
+    stdio::serve,
+    tool::{DynamicTools, ToolContext},
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+struct Counter {
+    count: i32,
+}
+
+#[Tools]
+impl Counter {
+    /// Increment the counter by 1
+    async fn increment(&mut self) -> Text<i32> {
+        self.count += 1;
+        Text(self.count)
+    }
+
+    /// Report the first step and wait until cancelled
+    async fn wait(&self, ctx: &ToolContext, steps: u32) -> Text<&'static str> {
+        ctx.report_progress(1.0, Some(steps as f64));
+        ctx.cancelled().await;
+        Text("cancelled")
+    }
+}
+
+fn increment_request(id: i64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "increment", "arguments": {} },
+    })
+}
+
+fn increment_response(id: i64, count: i32) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": {
+            "content": [{ "type": "text", "text": count.to_string() }],
+            "isError": false,
+        },
+    })
+}
+
+#[tokio::test]
+async fn serve_lines() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let handle = tokio::spawn(serve(
+        McpServer::new().tools(Counter { count: 0 }),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: &str| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+
+    send(&increment_request(1).to_string()).await;
+    // notifications have no response
+    send(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).await;
+    send(&json!([increment_request(2), increment_request(3)]).to_string()).await;
+    send("not json").await;
+
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(next().await, increment_response(1, 1));
+    assert_eq!(
+        next().await,
+        json!([increment_response(2, 2), increment_response(3, 3)])
+    );
+    let resp = next().await;
+    assert_eq!(resp["id"], Value::Null);
+    assert_eq!(resp["error"]["code"], -32700);
+
+    client_writer.shutdown().await.unwrap();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn progress_and_cancellation() {
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let handle = tokio::spawn(serve(
+        McpServer::new().tools(Counter { count: 0 }),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+    let mut send = async |message: Value| {
+        client_writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await
+            .unwrap();
+    };
+    let mut next =
+        async || serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {
+            "name": "wait",
+            "arguments": { "steps": 4 },
+            "_meta": { "progressToken": "wait-1" },
+        },
+    }))
+    .await;
+    assert_eq!(
+        next().await,
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": { "progressToken": "wait-1", "progress": 1.0, "total": 4.0 },
+        })
+    );
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/cancelled",
+        "params": { "requestId": 1, "reason": "timeout" },
+    }))
+    .await;
+    // the cancelled request has no response
+    send(increment_request(2)).await;
+    assert_eq!(next().await, increment_response(2, 1));
+
+    client_writer.shutdown().await.unwrap();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn tools_list_changed() {
+    let dynamic_tools = DynamicTools::new();
+    let (client, server) = tokio::io::duplex(4096);
+    let (server_reader, server_writer) = tokio::io::split(server);
+    let handle = tokio::spawn(serve(
+        McpServer::new()
+            .tools(Counter { count: 0 })
+            .dynamic_tools(dynamic_tools.clone()),
+        server_reader,
+        server_writer,
+    ));
+
+    let (client_reader, mut client_writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(client_reader).lines();
+
+    // wait for the server to handle a request, so it listens to the changes
+    client_writer
+        .write_all(format!("{}\n", increment_request(1)).as_bytes())
+        .await
+        .unwrap();
+    lines.next_line().await.unwrap().unwrap();
+
+    dynamic_tools.add(
+        "reset",
+        "Reset the counter",
+        |_: serde_json::Map<String, Value>| async { Text(0) },
+    );
+    let notification =
+        serde_json::from_str::<Value>(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(
+        notification,
+        json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed", "params": {} })
+    );
+
+    client_writer.shutdown().await.unwrap();
+    handle.await.unwrap().unwrap();
+}
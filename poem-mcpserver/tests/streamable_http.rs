@@ -0,0 +1,226 @@
+#![cfg(feature = "streamable-http")]
+
+use futures_util::StreamExt;
+use poem::{http::StatusCode, test::TestClient, web::sse::Event};
+use poem_mcpserver::{McpServer, Tools, content::Text, streamable_http, tool::ToolContext};
+use serde_json::json;
+
+struct Counter {
+    count: i32,
+}
+
+#[Tools]
+impl Counter {
+    /// Increment the counter by 1
+    async fn increment(&mut self) -> Text<i32> {
+        self.count += 1;
+        Text(self.count)
+    }
+
+    /// Count to the given number, reporting each step
+    async fn count_to(&self, ctx: &ToolContext, n: u32) -> Text<u32> {
+        for i in 1..=n {
+            ctx.report_progress(i as f64, Some(n as f64));
+        }
+        Text(n)
+    }
+}
+
+fn initialize_request() -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "test", "version": "1.0" },
+        },
+    })
+}
+
+fn increment_request(id: i64) -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "increment", "arguments": {} },
+    })
+}
+
+#[tokio::test]
+async fn streamable_http() {
+    let cli = TestClient::new(streamable_http::endpoint(|_| {
+        McpServer::new().tools(Counter { count: 0 })
+    }));
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .body_json(&initialize_request())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let session_id = resp
+        .0
+        .headers()
+        .get("Mcp-Session-Id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // notifications are acknowledged without a body
+    cli.post("/")
+        .header("accept", "application/json")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .send()
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+
+    // a single request has a single response
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&increment_request(2))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "result": { "content": [{ "type": "text", "text": "1" }], "isError": false },
+    }))
+    .await;
+
+    // the responses of the events are numbered
+    let resp = cli
+        .post("/")
+        .header("accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!([increment_request(3), increment_request(4)]))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let events = resp.sse_stream().collect::<Vec<_>>().await;
+    let ids = events
+        .iter()
+        .map(|event| match event {
+            Event::Message { id, .. } => id.as_str(),
+            Event::Retry { .. } => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["1", "2"]);
+
+    // the stream is resumed after the last event id
+    let resp = cli
+        .get("/")
+        .header("Mcp-Session-Id", &session_id)
+        .header("Last-Event-ID", "1")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let mut stream = resp.sse();
+    let (id, _, data) = stream.next_message().await;
+    assert_eq!(id, "2");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&data).unwrap(),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "result": { "content": [{ "type": "text", "text": "3" }], "isError": false },
+        })
+    );
+    stream.assert_end().await;
+
+    cli.get("/")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    cli.get("/")
+        .header("Mcp-Session-Id", "unknown")
+        .header("Last-Event-ID", "1")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    cli.delete("/")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+    cli.post("/")
+        .header("accept", "application/json")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&increment_request(5))
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn progress_events() {
+    let cli = TestClient::new(streamable_http::endpoint(|_| {
+        McpServer::new().tools(Counter { count: 0 })
+    }));
+
+    let resp = cli
+        .post("/")
+        .header("accept", "application/json")
+        .body_json(&initialize_request())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let session_id = resp
+        .0
+        .headers()
+        .get("Mcp-Session-Id")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // the progress notifications precede the response
+    let resp = cli
+        .post("/")
+        .header("accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .body_json(&json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "count_to",
+                "arguments": { "n": 2 },
+                "_meta": { "progressToken": 7 },
+            },
+        }))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    let mut stream = resp.sse();
+    for progress in [1.0, 2.0] {
+        let (_, _, data) = stream.next_message().await;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&data).unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": { "progressToken": 7, "progress": progress, "total": 2.0 },
+            })
+        );
+    }
+    let (_, _, data) = stream.next_message().await;
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&data).unwrap(),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "result": { "content": [{ "type": "text", "text": "2" }], "isError": false },
+        })
+    );
+    stream.assert_end().await;
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "test")]
+
+use poem_mcpserver::{
+    McpServer, Tools,
+    content::{Json, Text},
+    protocol::sampling::CreateMessageRequest,
+    sampling::SamplingClient,
+    test::TestMcpClient,
+    tool::ToolContext,
+};
+use serde_json::json;
+
+#[derive(serde::Serialize, schemars::JsonSchema)]
+struct Total {
+    total: i32,
+}
+
+struct Counter {
+    count: i32,
+}
+
+#[Tools]
+impl Counter {
+    /// Increment the counter by the value.
+    async fn increment(&mut self, ctx: &ToolContext, value: i32) -> Json<Total> {
+        ctx.report_progress(1.0, None);
+        self.count += value;
+        Json(Total { total: self.count })
+    }
+
+    /// Ask the model for a number.
+    async fn ask(&self, sampling: SamplingClient) -> Text<String> {
+        match sampling
+            .create_message(CreateMessageRequest::new(vec![], 10))
+            .await
+        {
+            Ok(result) => Text(result.model),
+            Err(err) => Text(err.message().to_string()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn call_tools() {
+    let mut client = TestMcpClient::new(McpServer::new().tools(Counter { count: 0 }));
+
+    let resp = client.initialize().await;
+    assert_eq!(resp.result()["serverInfo"]["name"], "poem-mcpserver");
+
+    client
+        .list_tools()
+        .await
+        .assert_tool_names(&["increment", "ask"]);
+
+    let resp = client.call_tool("increment", json!({ "value": 3 })).await;
+    resp.assert_is_error(false);
+    resp.assert_structured_content(json!({ "total": 3 }));
+
+    client
+        .call_tool("increment", json!({}))
+        .await
+        .assert_error_code(-32602);
+
+    client
+        .call_tool("unknown", json!({}))
+        .await
+        .assert_error_message("method not found: unknown");
+}
+
+#[tokio::test]
+async fn notifications() {
+    let mut client = TestMcpClient::new(McpServer::new().tools(Counter { count: 0 }));
+    client.initialize().await.assert_ok();
+
+    client
+        .request(
+            "tools/call",
+            json!({
+                "name": "increment",
+                "arguments": { "value": 1 },
+                "_meta": { "progressToken": "p" },
+            }),
+        )
+        .await
+        .assert_ok();
+    assert_eq!(
+        client.take_notifications(),
+        [json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": { "progressToken": "p", "progress": 1.0 },
+        })]
+    );
+    assert!(client.take_notifications().is_empty());
+}
+
+#[tokio::test]
+async fn client_requests() {
+    let mut client = TestMcpClient::new(McpServer::new().tools(Counter { count: 0 }));
+    client.initialize_with(json!({ "sampling": {} })).await;
+    client
+        .call_tool("ask", json!({}))
+        .await
+        .assert_text("method not found: sampling/createMessage");
+
+    let mut client = TestMcpClient::new(McpServer::new().tools(Counter { count: 0 }))
+        .client_handler(|method, _params| {
+            assert_eq!(method, "sampling/createMessage");
+            Ok(json!({
+                "role": "assistant",
+                "content": { "type": "text", "text": "42" },
+                "model": "test-model",
+            }))
+        });
+    client.initialize_with(json!({ "sampling": {} })).await;
+    client
+        .call_tool("ask", json!({}))
+        .await
+        .assert_text("test-model");
+}
@@ -1,11 +1,12 @@
 use poem_mcpserver::{
     McpServer, Tools,
-    content::Text,
+    content::{Audio, Image, IntoContent, Json, Text},
     protocol::{
         JSON_RPC_VERSION,
         rpc::{Request, RequestId, Requests},
         tool::{ToolsCallRequest, ToolsListRequest},
     },
+    tool::DynamicTools,
 };
 
 struct TestTools {
@@ -47,6 +48,7 @@ async fn call_tool() {
                     arguments: serde_json::json!({
                         "value": 10,
                     }),
+                    meta: None,
                 },
             },
         })
@@ -73,6 +75,7 @@ async fn call_tool() {
                     arguments: serde_json::json!({
                         "value": 30,
                     }),
+                    meta: None,
                 },
             },
         })
@@ -97,6 +100,7 @@ async fn call_tool() {
                 params: ToolsCallRequest {
                     name: "get_value".to_string(),
                     arguments: serde_json::json!({}),
+                    meta: None,
                 },
             },
         })
@@ -209,3 +213,391 @@ async fn disable_tools() {
         })
     );
 }
+
+#[derive(serde::Serialize, schemars::JsonSchema)]
+struct Stats {
+    count: u32,
+}
+
+struct StatsTools;
+
+#[Tools]
+impl StatsTools {
+    /// Get the statistics.
+    async fn stats(&self) -> Json<Stats> {
+        Json(Stats { count: 3 })
+    }
+}
+
+#[tokio::test]
+async fn structured_output() {
+    let mut server = McpServer::new().tools(StatsTools);
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsList {
+                params: ToolsListRequest { cursor: None },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap()["result"]["tools"][0]["outputSchema"],
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "count": {
+                    "format": "uint32",
+                    "minimum": 0,
+                    "type": "integer",
+                },
+            },
+            "required": ["count"],
+            "title": "Stats",
+        })
+    );
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(2)),
+            body: Requests::ToolsCall {
+                params: ToolsCallRequest {
+                    name: "stats".to_string(),
+                    arguments: serde_json::json!({}),
+                    meta: None,
+                },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap(),
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "result": {
+                "content": [{"type": "text", "text": "{\"count\":3}"}],
+                "structuredContent": {"count": 3},
+                "isError": false,
+            },
+        })
+    );
+}
+
+struct MediaTools;
+
+#[Tools]
+impl MediaTools {
+    /// Draw a chart.
+    async fn chart(&self) -> (Text<&'static str>, Image<Vec<u8>>) {
+        (Text("chart"), Image::new(vec![1, 2, 3], "image/png"))
+    }
+
+    /// Record sounds.
+    async fn record(&self, count: usize) -> Vec<poem_mcpserver::protocol::content::Content> {
+        let mut contents = vec![Text(count).into_content()];
+        for _ in 0..count {
+            contents.push(Audio::new(b"wav", "audio/wav").into_content());
+        }
+        contents
+    }
+}
+
+#[tokio::test]
+async fn media_content() {
+    let mut server = McpServer::new().tools(MediaTools);
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsCall {
+                params: ToolsCallRequest {
+                    name: "chart".to_string(),
+                    arguments: serde_json::json!({}),
+                    meta: None,
+                },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap()["result"]["content"],
+        serde_json::json!([
+            {"type": "text", "text": "chart"},
+            {"type": "image", "data": "AQID", "mimeType": "image/png"},
+        ])
+    );
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(2)),
+            body: Requests::ToolsCall {
+                params: ToolsCallRequest {
+                    name: "record".to_string(),
+                    arguments: serde_json::json!({"count": 2}),
+                    meta: None,
+                },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap()["result"]["content"],
+        serde_json::json!([
+            {"type": "text", "text": "2"},
+            {"type": "audio", "data": "d2F2", "mimeType": "audio/wav"},
+            {"type": "audio", "data": "d2F2", "mimeType": "audio/wav"},
+        ])
+    );
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct EchoArgs {
+    message: String,
+}
+
+#[tokio::test]
+async fn dynamic_tools() {
+    let dynamic_tools = DynamicTools::new();
+    let mut server = McpServer::new()
+        .tools(TestTools::new())
+        .dynamic_tools(dynamic_tools.clone());
+    dynamic_tools.add("echo", "Echo the message.", |args: EchoArgs| async move {
+        Text(args.message)
+    });
+
+    let list_tools = async |server: &mut McpServer<TestTools>| {
+        let resp = server
+            .handle_request(Request {
+                jsonrpc: JSON_RPC_VERSION.to_string(),
+                id: Some(RequestId::Int(1)),
+                body: Requests::ToolsList {
+                    params: ToolsListRequest { cursor: None },
+                },
+            })
+            .await;
+        serde_json::to_value(&resp).unwrap()["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        list_tools(&mut server).await,
+        ["add_value", "get_value", "echo"]
+    );
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(2)),
+            body: Requests::ToolsCall {
+                params: ToolsCallRequest {
+                    name: "echo".to_string(),
+                    arguments: serde_json::json!({"message": "hello"}),
+                    meta: None,
+                },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap(),
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "result": {
+                "content": [{"type": "text", "text": "hello"}],
+                "isError": false,
+            },
+        })
+    );
+
+    assert!(dynamic_tools.remove("echo"));
+    assert!(!dynamic_tools.remove("echo"));
+    assert_eq!(list_tools(&mut server).await, ["add_value", "get_value"]);
+}
+
+struct AnnotatedTools;
+
+#[Tools]
+impl AnnotatedTools {
+    /// Read a file.
+    #[mcp(title = "Read File", read_only_hint)]
+    async fn read_file(&self) -> Text<&'static str> {
+        Text("content")
+    }
+
+    /// Delete a file.
+    #[mcp(
+        name = "delete",
+        destructive_hint,
+        idempotent_hint = true,
+        open_world_hint = false
+    )]
+    async fn delete_file(&self) -> Text<&'static str> {
+        Text("deleted")
+    }
+
+    /// Touch a file.
+    async fn touch(&self) -> Text<&'static str> {
+        Text("touched")
+    }
+}
+
+#[tokio::test]
+async fn tool_annotations() {
+    let mut server = McpServer::new().tools(AnnotatedTools);
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsList {
+                params: ToolsListRequest { cursor: None },
+            },
+        })
+        .await;
+    let tools = &serde_json::to_value(&resp).unwrap()["result"]["tools"];
+    assert_eq!(
+        tools[0]["annotations"],
+        serde_json::json!({ "title": "Read File", "readOnlyHint": true })
+    );
+    assert_eq!(tools[1]["name"], "delete");
+    assert_eq!(
+        tools[1]["annotations"],
+        serde_json::json!({
+            "destructiveHint": true,
+            "idempotentHint": true,
+            "openWorldHint": false,
+        })
+    );
+    assert!(tools[2].get("annotations").is_none());
+}
+
+#[tokio::test]
+async fn combine_tools() {
+    let mut server = McpServer::new().tools((TestTools::new(), AnnotatedTools));
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsList {
+                params: ToolsListRequest { cursor: None },
+            },
+        })
+        .await;
+    let names = serde_json::to_value(&resp).unwrap()["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["add_value", "get_value", "read_file", "delete", "touch"]
+    );
+
+    for (name, text) in [("add_value", "5"), ("delete", "deleted")] {
+        let resp = server
+            .handle_request(Request {
+                jsonrpc: JSON_RPC_VERSION.to_string(),
+                id: Some(RequestId::Int(2)),
+                body: Requests::ToolsCall {
+                    params: ToolsCallRequest {
+                        name: name.to_string(),
+                        arguments: serde_json::json!({"value": 5}),
+                        meta: None,
+                    },
+                },
+            })
+            .await;
+        assert_eq!(
+            serde_json::to_value(&resp).unwrap()["result"]["content"][0]["text"],
+            text
+        );
+    }
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(3)),
+            body: Requests::ToolsCall {
+                params: ToolsCallRequest {
+                    name: "unknown".to_string(),
+                    arguments: serde_json::json!({}),
+                    meta: None,
+                },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap()["error"]["message"],
+        "tool 'unknown' not found"
+    );
+}
+
+#[test]
+#[should_panic(expected = "duplicate tool name: add_value")]
+fn duplicate_tools() {
+    let _ = McpServer::new().tools((TestTools::new(), TestTools::new()));
+}
+
+#[tokio::test]
+async fn paginate_tools() {
+    let mut server = McpServer::new()
+        .tools((TestTools::new(), AnnotatedTools))
+        .page_size(2);
+
+    let mut names = vec![];
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let resp = server
+            .handle_request(Request {
+                jsonrpc: JSON_RPC_VERSION.to_string(),
+                id: Some(RequestId::Int(1)),
+                body: Requests::ToolsList {
+                    params: ToolsListRequest { cursor },
+                },
+            })
+            .await;
+        let result = serde_json::to_value(&resp).unwrap()["result"].take();
+        let tools = result["tools"].as_array().unwrap();
+        assert!(tools.len() <= 2);
+        names.extend(
+            tools
+                .iter()
+                .map(|tool| tool["name"].as_str().unwrap().to_string()),
+        );
+        pages += 1;
+        match result.get("nextCursor") {
+            Some(next_cursor) => cursor = Some(next_cursor.as_str().unwrap().to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(pages, 3);
+    assert_eq!(
+        names,
+        ["add_value", "get_value", "read_file", "delete", "touch"]
+    );
+
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsList {
+                params: ToolsListRequest {
+                    cursor: Some("invalid".to_string()),
+                },
+            },
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(&resp).unwrap()["error"],
+        serde_json::json!({ "code": -32602, "message": "invalid cursor" })
+    );
+}
@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use poem_mcpserver::{
+    McpServer, Tools,
+    content::Text,
+    protocol::{
+        JSON_RPC_VERSION,
+        rpc::{Request, RequestId, Requests},
+        tool::ToolsCallRequest,
+    },
+};
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{Layer, layer::Context, layer::SubscriberExt, registry::LookupSpan};
+
+type Fields = HashMap<&'static str, String>;
+
+/// Collects the fields of the `mcp.request` spans.
+#[derive(Clone, Default)]
+struct SpansLayer(Arc<Mutex<HashMap<u64, Fields>>>);
+
+struct FieldsVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldsVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpansLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() == "mcp.request" {
+            let mut fields = Fields::new();
+            attrs.record(&mut FieldsVisitor(&mut fields));
+            self.0.lock().unwrap().insert(id.into_u64(), fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        if let Some(fields) = self.0.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(&mut FieldsVisitor(fields));
+        }
+    }
+}
+
+struct Echo;
+
+#[Tools]
+impl Echo {
+    /// Echo the message.
+    async fn echo(&self, message: String) -> Text<String> {
+        Text(message)
+    }
+}
+
+fn call_request(id: i64, name: &str) -> Request {
+    Request {
+        jsonrpc: JSON_RPC_VERSION.to_string(),
+        id: Some(RequestId::Int(id)),
+        body: Requests::ToolsCall {
+            params: ToolsCallRequest {
+                name: name.to_string(),
+                arguments: serde_json::json!({ "message": "hello" }),
+                meta: None,
+            },
+        },
+    }
+}
+
+#[tokio::test]
+async fn request_spans() {
+    let layer = SpansLayer::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+
+    let mut server = McpServer::new().tools(Echo);
+    server.handle_request(call_request(1, "echo")).await;
+    server.handle_request(call_request(2, "unknown")).await;
+    server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(3)),
+            body: Requests::Ping,
+        })
+        .await;
+
+    let mut spans = layer
+        .0
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    spans.sort_by(|a, b| a["id"].cmp(&b["id"]));
+    assert_eq!(spans.len(), 3);
+
+    assert_eq!(spans[0]["method"], "tools/call");
+    assert_eq!(spans[0]["id"], "Some(Int(1))");
+    assert_eq!(spans[0]["tool"], "echo");
+    assert!(spans[0].contains_key("duration_ms"));
+    assert!(!spans[0].contains_key("error"));
+
+    assert_eq!(spans[1]["tool"], "unknown");
+    assert_eq!(spans[1]["error"], "method not found: unknown");
+
+    assert_eq!(spans[2]["method"], "ping");
+    assert!(!spans[2].contains_key("tool"));
+}
@@ -0,0 +1,164 @@
+use poem_mcpserver::{
+    McpServer, Tools,
+    content::Text,
+    protocol::{
+        JSON_RPC_VERSION,
+        rpc::{Request, RequestId, Requests},
+        tool::{ToolsCallRequest, ToolsListRequest},
+    },
+};
+use serde_json::{Value, json};
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+fn default_city() -> String {
+    "Paris".to_string()
+}
+
+struct Weather;
+
+#[Tools]
+impl Weather {
+    /// Get the forecast.
+    async fn forecast(
+        &self,
+        /// The city of the forecast.
+        #[mcp(default = "default_city", validator(min_length = 1, max_length = 20))]
+        city: String,
+        /// The number of days.
+        #[mcp(name = "numDays", validator(minimum = 1, maximum = 7))]
+        days: u32,
+        /// The unit of the temperatures.
+        unit: Option<Unit>,
+        /// Whether to include the hourly forecast.
+        #[mcp(default)]
+        hourly: bool,
+    ) -> Text<String> {
+        Text(format!("{city} {days} {unit:?} {hourly}"))
+    }
+}
+
+async fn call(server: &mut McpServer<Weather>, arguments: Value) -> Value {
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsCall {
+                params: ToolsCallRequest {
+                    name: "forecast".to_string(),
+                    arguments,
+                    meta: None,
+                },
+            },
+        })
+        .await;
+    serde_json::to_value(&resp).unwrap()
+}
+
+#[tokio::test]
+async fn input_schema() {
+    let mut server = McpServer::new().tools(Weather);
+    let resp = server
+        .handle_request(Request {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id: Some(RequestId::Int(1)),
+            body: Requests::ToolsList {
+                params: ToolsListRequest { cursor: None },
+            },
+        })
+        .await;
+    let schema = &serde_json::to_value(&resp).unwrap()["result"]["tools"][0]["inputSchema"];
+    assert_eq!(
+        schema["properties"]["city"],
+        json!({
+            "description": "The city of the forecast.",
+            "type": "string",
+            "default": "Paris",
+            "minLength": 1,
+            "maxLength": 20,
+        })
+    );
+    assert_eq!(
+        schema["properties"]["numDays"],
+        json!({
+            "description": "The number of days.",
+            "type": "integer",
+            "format": "uint32",
+            "minimum": 1,
+            "maximum": 7,
+        })
+    );
+    assert_eq!(
+        schema["properties"]["hourly"],
+        json!({
+            "description": "Whether to include the hourly forecast.",
+            "type": "boolean",
+            "default": false,
+        })
+    );
+    assert_eq!(
+        schema["$defs"]["Unit"]["enum"],
+        json!(["celsius", "fahrenheit"])
+    );
+    assert_eq!(schema["required"], json!(["numDays"]));
+}
+
+#[tokio::test]
+async fn valid_arguments() {
+    let mut server = McpServer::new().tools(Weather);
+
+    let resp = call(&mut server, json!({ "numDays": 3 })).await;
+    assert_eq!(resp["result"]["content"][0]["text"], "Paris 3 None false");
+
+    let resp = call(
+        &mut server,
+        json!({ "city": "Rome", "numDays": 7, "unit": "celsius", "hourly": true }),
+    )
+    .await;
+    assert_eq!(
+        resp["result"]["content"][0]["text"],
+        "Rome 7 Some(Celsius) true"
+    );
+}
+
+#[tokio::test]
+async fn invalid_arguments() {
+    let mut server = McpServer::new().tools(Weather);
+
+    for (arguments, message) in [
+        (
+            json!({ "numDays": 0 }),
+            "invalid parameters: `numDays` must be greater than or equal to 1",
+        ),
+        (
+            json!({ "numDays": 8 }),
+            "invalid parameters: `numDays` must be less than or equal to 7",
+        ),
+        (
+            json!({ "city": "", "numDays": 1 }),
+            "invalid parameters: `city` length must be greater than or equal to 1",
+        ),
+        (
+            json!({ "city": "a".repeat(21), "numDays": 1 }),
+            "invalid parameters: `city` length must be less than or equal to 20",
+        ),
+    ] {
+        let resp = call(&mut server, arguments).await;
+        assert_eq!(resp["error"]["code"], -32602);
+        assert_eq!(resp["error"]["message"], message);
+    }
+
+    let resp = call(&mut server, json!({ "numDays": 1, "unit": "kelvin" })).await;
+    assert_eq!(resp["error"]["code"], -32602);
+    assert!(
+        resp["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("invalid parameters: unknown variant `kelvin`")
+    );
+}
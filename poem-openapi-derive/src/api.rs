@@ -8,7 +8,8 @@ use syn::{
 
 use crate::{
     common_args::{
-        APIMethod, CodeSample, DefaultValue, ExampleValue, ExternalDocument, ExtraHeader,
+        APIMethod, Callback, CodeSample, DefaultValue, ExampleValue, Extension, ExternalDocument,
+        ExtraHeader, NamedExamples, RateLimit,
     },
     error::GeneratorResult,
     parameter_style::ParameterStyle,
@@ -34,6 +35,24 @@ pub(crate) struct APIArgs {
     request_headers: Vec<ExtraHeader>,
     #[darling(default)]
     ignore_case: Option<bool>,
+    #[darling(default)]
+    transform: Option<Path>,
+    #[darling(default)]
+    shared_params: Option<Ident>,
+    #[darling(default, multiple, rename = "shared_param")]
+    shared_param: Vec<SharedParam>,
+}
+
+#[derive(FromMeta)]
+struct SharedParam {
+    name: String,
+    ty: SpannedValue<String>,
+    #[darling(default)]
+    ident: Option<SpannedValue<String>>,
+    #[darling(default)]
+    description: Option<String>,
+    #[darling(default)]
+    deprecated: bool,
 }
 
 #[derive(FromMeta)]
@@ -45,8 +64,8 @@ struct APIOperation {
     deprecated: bool,
     #[darling(default, multiple, rename = "tag")]
     tags: Vec<Path>,
-    #[darling(default)]
-    transform: Option<Ident>,
+    #[darling(default, multiple, rename = "transform")]
+    transforms: Vec<Path>,
     #[darling(default)]
     operation_id: Option<String>,
     #[darling(default)]
@@ -59,10 +78,26 @@ struct APIOperation {
     actual_type: Option<Type>,
     #[darling(default, multiple, rename = "code_sample")]
     code_samples: Vec<CodeSample>,
+    #[darling(default, multiple, rename = "callback")]
+    callbacks: Vec<Callback>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
     #[darling(default)]
     hidden: bool,
     #[darling(default)]
     ignore_case: Option<bool>,
+    #[darling(default)]
+    cache_control: Option<String>,
+    #[darling(default)]
+    rate_limit: Option<RateLimit>,
+    #[darling(default)]
+    max_request_size: Option<SpannedValue<String>>,
+    #[darling(default)]
+    sunset: Option<SpannedValue<String>>,
+    #[darling(default)]
+    replacement: Option<SpannedValue<String>>,
+    #[darling(default, multiple, rename = "version")]
+    versions: Vec<SpannedValue<String>>,
 }
 
 #[derive(FromMeta, Default)]
@@ -79,6 +114,10 @@ struct APIOperationParam {
     #[darling(default)]
     example: Option<ExampleValue>,
     #[darling(default)]
+    examples: Option<NamedExamples>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
+    #[darling(default)]
     validator: Option<Validators>,
     #[darling(default)]
     explode: Option<bool>,
@@ -105,6 +144,8 @@ pub(crate) fn generate(args: APIArgs, mut item_impl: ItemImpl) -> GeneratorResul
         register_items: Default::default(),
     };
 
+    let shared_params_struct = generate_shared_params(&mut ctx, &crate_name, &args)?;
+
     for item in &mut item_impl.items {
         if let ImplItem::Fn(method) = item {
             if let Some(operation_args) = parse_oai_attrs::<APIOperation>(&method.attrs)? {
@@ -140,6 +181,8 @@ pub(crate) fn generate(args: APIArgs, mut item_impl: ItemImpl) -> GeneratorResul
     let expanded = quote! {
         #item_impl
 
+        #shared_params_struct
+
         impl #impl_generics #crate_name::OpenApi for #ident #where_clause {
             fn meta() -> ::std::vec::Vec<#crate_name::registry::MetaApi> {
                 ::std::vec![#crate_name::registry::MetaApi {
@@ -169,6 +212,69 @@ pub(crate) fn generate(args: APIArgs, mut item_impl: ItemImpl) -> GeneratorResul
     Ok(expanded)
 }
 
+/// Returns the field name and the type of the shared parameters.
+fn parse_shared_params(api_args: &APIArgs) -> GeneratorResult<Vec<(Ident, Type, &SharedParam)>> {
+    let mut items = Vec::new();
+    for param in &api_args.shared_param {
+        let ty = match syn::parse_str::<Type>(&param.ty) {
+            Ok(ty) => ty,
+            Err(_) => return Err(Error::new(param.ty.span(), "Invalid type").into()),
+        };
+        let field_ident = match &param.ident {
+            Some(ident) => syn::parse_str::<Ident>(ident)
+                .map_err(|_| Error::new(ident.span(), "Invalid identifier"))?,
+            None => syn::parse_str::<Ident>(&param.name.to_lowercase().replace('-', "_"))
+                .map_err(|_| {
+                    Error::new(
+                        param.ty.span(),
+                        "The name is not a valid identifier, use the `ident` attribute to specify it",
+                    )
+                })?,
+        };
+        items.push((field_ident, ty, param));
+    }
+    Ok(items)
+}
+
+fn generate_shared_params(
+    ctx: &mut Context,
+    crate_name: &TokenStream,
+    api_args: &APIArgs,
+) -> GeneratorResult<Option<TokenStream>> {
+    let Some(struct_ident) = &api_args.shared_params else {
+        if let Some(param) = api_args.shared_param.first() {
+            return Err(Error::new(
+                param.ty.span(),
+                "The `shared_params` attribute is required to name the struct of the shared parameters",
+            )
+            .into());
+        }
+        return Ok(None);
+    };
+
+    let mut fields = Vec::new();
+    for (field_ident, ty, param) in parse_shared_params(api_args)? {
+        let doc = param
+            .description
+            .as_ref()
+            .map(|desc| quote!(#[doc = #desc]));
+        fields.push(quote! {
+            #doc
+            pub #field_ident: #ty,
+        });
+        ctx.register_items.push(quote! {
+            <#ty as #crate_name::ApiExtractor>::register(registry);
+        });
+    }
+
+    Ok(Some(quote! {
+        /// The parameters shared by all the operations.
+        pub struct #struct_ident {
+            #(#fields)*
+        }
+    }))
+}
+
 fn generate_operation(
     ctx: &mut Context,
     crate_name: &TokenStream,
@@ -181,15 +287,23 @@ fn generate_operation(
         methods,
         deprecated,
         tags,
-        transform,
+        transforms,
         operation_id,
         external_docs,
         response_headers,
         request_headers,
         actual_type,
         code_samples,
+        callbacks,
+        extensions,
         hidden,
         ignore_case,
+        cache_control,
+        rate_limit,
+        max_request_size,
+        sunset,
+        replacement,
+        versions,
     } = args;
     if methods.is_empty() {
         return Err(Error::new_spanned(
@@ -198,6 +312,33 @@ fn generate_operation(
         )
         .into());
     }
+    if !deprecated {
+        if let Some(value) = sunset.as_ref().or(replacement.as_ref()) {
+            return Err(Error::new(value.span(), "The operation must be `deprecated`").into());
+        }
+    }
+    if let Some(version) = versions
+        .iter()
+        .find(|version| version.is_empty() || version.contains('/'))
+    {
+        return Err(Error::new(version.span(), "Invalid version").into());
+    }
+    let max_request_size = match &max_request_size {
+        Some(size) => Some(parse_size(size).ok_or_else(|| {
+            Error::new(
+                size.span(),
+                "Invalid size, expected e.g. `\"512KB\"` or `\"5MB\"`",
+            )
+        })?),
+        None => None,
+    };
+    let sunset_date = match &sunset {
+        Some(sunset) => Some(
+            http_date(sunset)
+                .ok_or_else(|| Error::new(sunset.span(), "Invalid date, expected `YYYY-MM-DD`"))?,
+        ),
+        None => None,
+    };
     let fn_ident = &item_method.sig.ident;
     let (summary, description) = get_summary_and_description(&item_method.attrs)?;
     let summary = optional_literal(&summary);
@@ -207,12 +348,25 @@ fn generate_operation(
     let (oai_path, new_path) = convert_oai_path(&path)?;
     let oai_path = prefix_path
         .as_ref()
-        .map(|prefix| quote! { #crate_name::__private::join_path(#prefix, #oai_path) })
+        .map(|prefix| {
+            quote! { #crate_name::__private::join_path(&#crate_name::__private::convert_path_params(#prefix), #oai_path) }
+        })
         .unwrap_or_else(|| quote! { ::std::string::ToString::to_string(#oai_path) });
     let new_path: TokenStream = prefix_path
         .as_ref()
         .map(|prefix| quote! { #crate_name::__private::join_path(#prefix, #new_path) })
         .unwrap_or_else(|| quote! { ::std::string::ToString::to_string(#new_path) });
+    // the operations of versions are served under the path of each version
+    let versions = versions
+        .iter()
+        .map(|version| &**version)
+        .collect::<Vec<_>>();
+    let route_paths = match versions.is_empty() {
+        true => quote! { ::std::vec![#new_path] },
+        false => quote! {
+            ::std::vec![#(#crate_name::__private::join_path(&::std::format!("/{}", #versions), &#new_path)),*]
+        },
+    };
 
     if item_method.sig.inputs.is_empty() {
         return Err(Error::new_spanned(
@@ -252,6 +406,62 @@ fn generate_operation(
 
     let mut path_param_count = 0;
 
+    // shared parameters
+    let mut shared_fields = Vec::new();
+    let mut shared_params_meta = Vec::new();
+    let shared_ignore_case = ignore_case.or(api_args.ignore_case).unwrap_or(false);
+    for (field_ident, ty, param) in parse_shared_params(api_args)? {
+        let name = &param.name;
+        let var = format_ident!("__shared_{}", field_ident);
+        parse_args.push(quote! {
+            let param_opts = #crate_name::ExtractParamOptions {
+                name: #name,
+                ignore_case: #shared_ignore_case,
+                default_value: ::std::option::Option::None,
+                example_value: ::std::option::Option::None,
+                explode: true,
+                style: ::std::option::Option::None,
+            };
+
+            let #var = match <#ty as #crate_name::ApiExtractor>::from_request(&request, &mut body, param_opts).await {
+                ::std::result::Result::Ok(value) => value,
+                ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
+                    let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err);
+                    let res = #crate_name::__private::poem::error::IntoResult::into_result(res);
+                    return ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response);
+                }
+                ::std::result::Result::Err(err) => return ::std::result::Result::Err(::std::convert::Into::into(err)),
+            };
+        });
+        shared_fields.push(quote!(#field_ident: #var));
+
+        let description = optional_literal_string(&param.description);
+        let deprecated = param.deprecated;
+        shared_params_meta.push(quote! {
+            if <#ty as #crate_name::ApiExtractor>::TYPES.contains(&#crate_name::ApiExtractorType::Parameter) {
+                params.push(#crate_name::registry::MetaOperationParam {
+                    name: ::std::string::ToString::to_string(#name),
+                    schema: <#ty as #crate_name::ApiExtractor>::param_schema_ref().unwrap(),
+                    in_type: <#ty as #crate_name::ApiExtractor>::param_in().unwrap(),
+                    description: #description,
+                    required: <#ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED,
+                    deprecated: #deprecated,
+                    explode: true,
+                    style: ::std::option::Option::None,
+                    examples: ::std::vec![],
+                    extensions: ::std::collections::BTreeMap::new(),
+                });
+            }
+        });
+    }
+    if let Some(struct_ident) = &api_args.shared_params {
+        parse_args.push(quote! {
+            let __shared_params = #struct_ident {
+                #(#shared_fields),*
+            };
+        });
+    }
+
     for i in 1..item_method.sig.inputs.len() {
         let arg = &mut item_method.sig.inputs[i];
         let (arg_ident, mut arg_ty, operation_param, param_description) = match arg {
@@ -283,6 +493,15 @@ fn generate_operation(
                 return Err(Error::new_spanned(item_method, "Invalid method definition.").into());
             }
         };
+        if let (Some(struct_ident), syn::Type::Path(syn::TypePath { qself: None, path })) =
+            (&api_args.shared_params, &*arg_ty)
+        {
+            if path.is_ident(struct_ident) {
+                use_args.push(format_ident!("__shared_params"));
+                continue;
+            }
+        }
+
         let is_path = match &*arg_ty {
             syn::Type::Path(syn::TypePath { qself: _, path }) => {
                 path.segments.iter().any(|v| v.ident == "Path")
@@ -360,6 +579,9 @@ fn generate_operation(
             None => quote!(::std::option::Option::None),
         };
 
+        let meta_examples = NamedExamples::to_meta(operation_param.examples.as_ref(), crate_name);
+        let meta_extensions = Extension::to_token_stream(&operation_param.extensions, crate_name)?;
+
         // validator
         let validator = operation_param.validator.clone().unwrap_or_default();
         let param_checker = validator.create_param_checker(crate_name, &res_ty, &param_name)?.map(|stream| {
@@ -429,6 +651,8 @@ fn generate_operation(
                     deprecated: #deprecated,
                     explode: #explode,
                     style: #style,
+                    examples: #meta_examples,
+                    extensions: #meta_extensions,
                 };
                 params.push(meta_param);
             }
@@ -436,6 +660,13 @@ fn generate_operation(
 
         // request object meta
         let param_desc = optional_literal(&param_description);
+        let update_request_examples = operation_param.examples.is_some().then(|| {
+            quote! {
+                for content in &mut request.content {
+                    content.examples = #meta_examples;
+                }
+            }
+        });
         request_meta.push(quote! {
             if <#arg_ty as #crate_name::ApiExtractor>::TYPES.contains(&#crate_name::ApiExtractorType::RequestObject) {
                 request = <#arg_ty as #crate_name::ApiExtractor>::request_meta();
@@ -443,6 +674,7 @@ fn generate_operation(
                     if request.description.is_none() {
                         request.description = #param_desc;
                     }
+                    #update_request_examples
                 }
             }
         });
@@ -451,14 +683,14 @@ fn generate_operation(
         let scopes = &operation_param.scopes;
         security.push(quote! {
             if <#arg_ty as #crate_name::ApiExtractor>::TYPES.contains(&#crate_name::ApiExtractorType::SecurityScheme) {
-                for security_name in <#arg_ty as #crate_name::ApiExtractor>::security_schemes() {
-                    security.push(<::std::collections::HashMap<&'static str, ::std::vec::Vec<&'static str>> as ::std::convert::From<_>>::from([
-                        (security_name, ::std::vec![#(#crate_name::OAuthScopes::name(&#scopes)),*])
-                    ]));
-                }
-                if <#arg_ty as #crate_name::ApiExtractor>::has_security_fallback() {
-                    security.push(::std::collections::HashMap::<&'static str, ::std::vec::Vec<&'static str>>::new());
-                }
+                let requirements = ::std::iter::Iterator::collect(::std::iter::Iterator::map(
+                    ::std::iter::IntoIterator::into_iter(<#arg_ty as #crate_name::ApiExtractor>::security_requirements()),
+                    |names| ::std::iter::Iterator::collect(::std::iter::Iterator::map(
+                        ::std::iter::IntoIterator::into_iter(names),
+                        |name| (name, ::std::vec![#(#crate_name::OAuthScopes::name(&#scopes)),*]),
+                    )),
+                ));
+                requirements_list = #crate_name::__private::and_security_requirements(requirements_list, requirements);
             }
         });
     }
@@ -473,9 +705,57 @@ fn generate_operation(
         }
     }
 
-    let transform = transform.map(|transform| {
+    let limit_request_size = max_request_size.map(|size| {
         quote! {
-            let ep = #crate_name::__private::poem::EndpointExt::map_to_response(#transform(ep));
+            let ep = #crate_name::__private::limit_request_size(ep, #size);
+        }
+    });
+
+    // the transforms of the operation are applied in order, before the
+    // transform of the API
+    let transform = transforms
+        .iter()
+        .chain(&api_args.transform)
+        .map(|transform| {
+            quote! {
+                let ep = #crate_name::__private::poem::EndpointExt::map_to_response(#transform(ep));
+            }
+        })
+        .collect::<Vec<_>>();
+    let set_cache_control = cache_control.as_ref().map(|cache_control| {
+        quote! {
+            if resp.status().is_success() && !resp.headers().contains_key(#crate_name::__private::poem::http::header::CACHE_CONTROL) {
+                resp.headers_mut().insert(#crate_name::__private::poem::http::header::CACHE_CONTROL,
+                    #crate_name::__private::poem::http::HeaderValue::from_static(#cache_control));
+            }
+        }
+    });
+    let set_deprecation_headers = deprecated.then(|| {
+        let set_sunset = sunset_date.as_ref().map(|sunset| {
+            quote! {
+                headers.insert(#crate_name::__private::poem::http::HeaderName::from_static("sunset"),
+                    #crate_name::__private::poem::http::HeaderValue::from_static(#sunset));
+            }
+        });
+        let set_link = replacement.as_ref().map(|replacement| {
+            let link = format!("<{}>; rel=\"successor-version\"", &**replacement);
+            quote! {
+                headers.append(#crate_name::__private::poem::http::header::LINK,
+                    #crate_name::__private::poem::http::HeaderValue::from_static(#link));
+            }
+        });
+        quote! {
+            let ep = #crate_name::__private::poem::EndpointExt::after(ep, |mut res| async move {
+                let headers = match &mut res {
+                    ::std::result::Result::Ok(resp) => resp.headers_mut(),
+                    ::std::result::Result::Err(err) => err.headers_mut(),
+                };
+                headers.insert(#crate_name::__private::poem::http::HeaderName::from_static("deprecation"),
+                    #crate_name::__private::poem::http::HeaderValue::from_static("true"));
+                #set_sunset
+                #set_link
+                res
+            });
         }
     });
     let update_content_type = match &actual_type {
@@ -503,7 +783,8 @@ fn generate_operation(
         });
 
         ctx.add_routes.push(quote! {
-            route_table.entry(#new_path)
+            for path in #route_paths {
+                route_table.entry(path)
                 .or_default()
                 .insert(#crate_name::__private::poem::http::Method::#http_method, {
                     let api_obj = ::std::clone::Clone::clone(&api_obj);
@@ -517,16 +798,20 @@ fn generate_operation(
                             match ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response) {
                                 ::std::result::Result::Ok(mut resp) => {
                                     #update_content_type
+                                    #set_cache_control
                                     ::std::result::Result::Ok(resp)
                                 }
                                 ::std::result::Result::Err(err) => ::std::result::Result::Err(err),
                             }
                         }
                     });
-                    #transform
+                    #limit_request_size
+                    #(#transform)*
                     #set_operation_id
+                    #set_deprecation_headers
                     #crate_name::__private::poem::EndpointExt::boxed(ep)
                 });
+            }
         });
     }
 
@@ -566,6 +851,8 @@ fn generate_operation(
                 deprecated: #deprecated,
                 explode: true,
                 style: None,
+                examples: ::std::vec![],
+                extensions: ::std::collections::BTreeMap::new(),
             });
         });
     }
@@ -599,11 +886,136 @@ fn generate_operation(
         });
     }
 
+    // cache control header
+    let update_cache_control_header = cache_control.as_ref().map(|cache_control| {
+        let description = format!("The caching directives of the response, `{cache_control}`.");
+        quote! {
+            for resp in &mut meta.responses {
+                let is_success = resp.status.is_some_and(|status| (200..300).contains(&status) || status == 304)
+                    || resp.status_range.as_deref() == ::std::option::Option::Some("2XX");
+                if is_success {
+                    resp.headers.push(#crate_name::registry::MetaHeader {
+                        name: ::std::string::ToString::to_string("Cache-Control"),
+                        description: ::std::option::Option::Some(::std::string::ToString::to_string(#description)),
+                        required: true,
+                        deprecated: false,
+                        schema: <::std::string::String as #crate_name::types::Type>::schema_ref(),
+                    });
+                }
+            }
+        }
+    });
+
+    let update_deprecation_headers = deprecated.then(|| {
+        let mut headers = vec![(
+            "Deprecation",
+            "The operation is deprecated.".to_string(),
+        )];
+        if let Some(sunset) = &sunset {
+            headers.push((
+                "Sunset",
+                format!("The operation will be removed on {}.", &**sunset),
+            ));
+        }
+        if let Some(replacement) = &replacement {
+            headers.push((
+                "Link",
+                format!("The replacement of the operation, `{}`.", &**replacement),
+            ));
+        }
+        let headers = headers.into_iter().map(|(name, description)| {
+            quote! {
+                resp.headers.push(#crate_name::registry::MetaHeader {
+                    name: ::std::string::ToString::to_string(#name),
+                    description: ::std::option::Option::Some(::std::string::ToString::to_string(#description)),
+                    required: true,
+                    deprecated: false,
+                    schema: <::std::string::String as #crate_name::types::Type>::schema_ref(),
+                });
+            }
+        });
+        quote! {
+            for resp in &mut meta.responses {
+                #(#headers)*
+            }
+        }
+    });
+    let sunset_extension = sunset.as_ref().map(|sunset| {
+        let sunset = &**sunset;
+        quote! {
+            extensions.insert(
+                ::std::string::ToString::to_string("x-sunset"),
+                #crate_name::__private::serde_json::Value::from(#sunset),
+            );
+        }
+    });
+    let replacement_extension = replacement.as_ref().map(|replacement| {
+        let replacement = &**replacement;
+        quote! {
+            extensions.insert(
+                ::std::string::ToString::to_string("x-replacement"),
+                #crate_name::__private::serde_json::Value::from(#replacement),
+            );
+        }
+    });
+
+    let mut update_rate_limit_response = None;
+    let mut rate_limit_value = quote!(::std::option::Option::None);
+    if let Some(RateLimit { requests, period }) = &rate_limit {
+        if **requests == 0 {
+            return Err(Error::new(requests.span(), "`requests` must be greater than 0").into());
+        }
+        if **period == 0 {
+            return Err(Error::new(period.span(), "`period` must be greater than 0").into());
+        }
+        let (requests, period) = (**requests, **period);
+        update_rate_limit_response = Some(quote! {
+            if !meta.responses.iter().any(|resp| resp.status == ::std::option::Option::Some(429)) {
+                meta.responses.push(#crate_name::registry::MetaResponse {
+                    description: "Too many requests",
+                    status: ::std::option::Option::Some(429),
+                    status_range: ::std::option::Option::None,
+                    content: ::std::vec::Vec::new(),
+                    headers: ::std::vec![#crate_name::registry::MetaHeader {
+                        name: ::std::string::ToString::to_string("Retry-After"),
+                        description: ::std::option::Option::Some(::std::string::ToString::to_string(
+                            "The number of seconds to wait before the next request.",
+                        )),
+                        required: true,
+                        deprecated: false,
+                        schema: <u64 as #crate_name::types::Type>::schema_ref(),
+                    }],
+                    links: ::std::vec::Vec::new(),
+                });
+            }
+        });
+        rate_limit_value = quote! {
+            ::std::option::Option::Some(#crate_name::rate_limit::RateLimit {
+                requests: #requests,
+                period: ::std::time::Duration::from_secs(#period),
+            })
+        };
+    }
+
+    let update_request_size_response = max_request_size.map(|_| {
+        quote! {
+            if !meta.responses.iter().any(|resp| resp.status == ::std::option::Option::Some(413)) {
+                meta.responses.push(#crate_name::__private::payload_too_large_response());
+            }
+        }
+    });
+    let max_request_size_value = match max_request_size {
+        Some(size) => quote!(::std::option::Option::Some(#size)),
+        None => quote!(::std::option::Option::None),
+    };
+
     let resp_meta = match &actual_type {
         Some(actual_type) => quote!(<#actual_type as #crate_name::ApiResponse>::meta()),
         None => quote!(<#res_ty as #crate_name::ApiResponse>::meta()),
     };
 
+    let extensions = Extension::to_token_stream(&extensions, crate_name)?;
+
     let code_samples = code_samples
         .iter()
         .map(|item| {
@@ -623,6 +1035,26 @@ fn generate_operation(
         })
         .collect::<Vec<_>>();
 
+    let mut meta_callbacks = Vec::new();
+    for Callback { name, url, ty } in &callbacks {
+        let ty = match syn::parse_str::<Type>(ty) {
+            Ok(ty) => ty,
+            Err(_) => return Err(Error::new(ty.span(), "Invalid type").into()),
+        };
+        ctx.register_items
+            .push(quote!(<#ty as #crate_name::Webhook>::register(registry);));
+        meta_callbacks.push(quote! {
+            #crate_name::registry::MetaCallback {
+                name: #name,
+                url: #url,
+                operations: ::std::iter::Iterator::collect(::std::iter::Iterator::map(
+                    ::std::iter::IntoIterator::into_iter(<#ty as #crate_name::Webhook>::meta()),
+                    |webhook| webhook.operation,
+                )),
+            }
+        });
+    }
+
     if !hidden {
         for method in &methods {
             let http_method = method.to_http_method();
@@ -635,6 +1067,7 @@ fn generate_operation(
                     external_docs: #external_docs,
                     params: {
                         let mut params = ::std::vec::Vec::new();
+                        #(#shared_params_meta)*
                         #(#update_extra_request_headers)*
                         #(#params_meta)*
                         params
@@ -647,16 +1080,49 @@ fn generate_operation(
                     responses: {
                         let mut meta = #resp_meta;
                         #(#update_extra_response_headers)*
+                        #update_cache_control_header
+                        #update_rate_limit_response
+                        #update_request_size_response
+                        #update_deprecation_headers
                         meta
                     },
+                    callbacks: ::std::vec![#(#meta_callbacks),*],
                     deprecated: #deprecated,
                     security: {
-                        let mut security = ::std::vec![];
+                        let mut requirements_list: ::std::vec::Vec<::std::vec::Vec<(&'static str, ::std::vec::Vec<&'static str>)>> = ::std::vec![::std::vec![]];
                         #(#security)*
-                        security
+                        if requirements_list == [::std::vec![]] {
+                            ::std::vec![]
+                        } else {
+                            ::std::iter::Iterator::collect(::std::iter::Iterator::map(
+                                ::std::iter::IntoIterator::into_iter(requirements_list),
+                                |requirement| {
+                                    let mut security = ::std::collections::HashMap::<&'static str, ::std::vec::Vec<&'static str>>::new();
+                                    for (name, scopes) in requirement {
+                                        let entry = security.entry(name).or_default();
+                                        for scope in scopes {
+                                            if !entry.contains(&scope) {
+                                                entry.push(scope);
+                                            }
+                                        }
+                                    }
+                                    security
+                                },
+                            ))
+                        }
                     },
                     operation_id: #operation_id,
                     code_samples: ::std::vec![#(#code_samples),*],
+                    rate_limit: #rate_limit_value,
+                    max_request_size: #max_request_size_value,
+                    versions: ::std::vec![#(#versions),*],
+                    extensions: {
+                        #[allow(unused_mut)]
+                        let mut extensions: ::std::collections::BTreeMap<::std::string::String, #crate_name::__private::serde_json::Value> = #extensions;
+                        #sunset_extension
+                        #replacement_extension
+                        extensions
+                    },
                 }
             };
             ctx.operations.push((oai_path.clone(), meta_operation));
@@ -665,3 +1131,63 @@ fn generate_operation(
 
     Ok(())
 }
+
+/// Converts a `YYYY-MM-DD` date into an HTTP date at midnight UTC.
+fn http_date(date: &str) -> Option<String> {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next().filter(|s| s.len() == 4)?.parse::<i64>().ok()?;
+    let month = parts.next().filter(|s| s.len() == 2)?.parse::<i64>().ok()?;
+    let day = parts.next().filter(|s| s.len() == 2)?.parse::<i64>().ok()?;
+    let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_month = match month {
+        2 if is_leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if !(1..=days_in_month).contains(&day) {
+        return None;
+    }
+
+    // the number of days since 1970-01-01
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * m + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(format!(
+        "{}, {day:02} {} {year} 00:00:00 GMT",
+        WEEKDAYS[days.rem_euclid(7) as usize],
+        MONTHS[month as usize - 1]
+    ))
+}
+
+/// Parses a size with an optional unit, e.g. `512KB` or `5MB`, into a number
+/// of bytes.
+fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let unit_start = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let value = size[..unit_start].parse::<u64>().ok()?;
+    let multiplier: u64 = match size[unit_start..].trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" => 1024,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    value.checked_mul(multiplier)
+}
@@ -1,7 +1,9 @@
-use darling::{FromMeta, util::SpannedValue};
+use darling::{FromMeta, ast::NestedMeta, util::SpannedValue};
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Lit, Path};
+use syn::{Error, Lit, Meta, Path};
+
+use crate::{error::GeneratorResult, utils::optional_literal};
 
 #[derive(Debug, Copy, Clone, FromMeta)]
 #[allow(clippy::enum_variant_names)]
@@ -162,6 +164,44 @@ impl FromMeta for ExampleValue {
     }
 }
 
+/// Named examples, e.g. `examples(first = "example_first", second =
+/// "example_second")`, where each value is a function returning the example.
+pub(crate) struct NamedExamples(Vec<(String, Path)>);
+
+impl FromMeta for NamedExamples {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let mut examples = Vec::new();
+        for item in items {
+            match item {
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    let Some(name) = nv.path.get_ident() else {
+                        return Err(
+                            darling::Error::custom("expected an example name").with_span(&nv.path)
+                        );
+                    };
+                    examples.push((name.to_string(), Path::from_expr(&nv.value)?));
+                }
+                _ => return Err(darling::Error::unsupported_format("example").with_span(item)),
+            }
+        }
+        Ok(Self(examples))
+    }
+}
+
+impl NamedExamples {
+    pub(crate) fn to_meta(examples: Option<&Self>, crate_name: &TokenStream) -> TokenStream {
+        let examples = examples.into_iter().flat_map(|examples| &examples.0).map(|(name, func)| {
+            quote! {
+                #crate_name::registry::MetaExample {
+                    name: #name,
+                    value: ::std::option::Option::unwrap_or_default(#crate_name::types::ToJSON::to_json(&#func())),
+                }
+            }
+        });
+        quote!(::std::vec![#(#examples),*])
+    }
+}
+
 #[derive(FromMeta, Clone)]
 pub(crate) struct MaximumValidator {
     pub(crate) value: f64,
@@ -200,6 +240,79 @@ impl ExternalDocument {
     }
 }
 
+#[derive(FromMeta)]
+pub(crate) struct Extension {
+    name: SpannedValue<String>,
+    value: SpannedValue<String>,
+}
+
+impl Extension {
+    pub(crate) fn to_token_stream(
+        extensions: &[Extension],
+        crate_name: &TokenStream,
+    ) -> GeneratorResult<TokenStream> {
+        if extensions.is_empty() {
+            return Ok(quote!(::std::collections::BTreeMap::new()));
+        }
+
+        let mut items = Vec::new();
+        for Extension { name, value } in extensions {
+            if !name.starts_with("x-") {
+                return Err(Error::new(
+                    name.span(),
+                    "The name of an extension must start with `x-`.",
+                )
+                .into());
+            }
+            if let Err(err) = serde_json::from_str::<serde_json::Value>(value) {
+                return Err(Error::new(value.span(), format!("Invalid JSON value: {err}")).into());
+            }
+            let name = &**name;
+            let value = &**value;
+            items.push(quote! {
+                (
+                    ::std::string::ToString::to_string(#name),
+                    #crate_name::__private::serde_json::from_str(#value).unwrap(),
+                )
+            });
+        }
+        Ok(quote!(::std::iter::FromIterator::from_iter([#(#items),*])))
+    }
+}
+
+#[derive(FromMeta)]
+pub(crate) struct XmlObject {
+    #[darling(default)]
+    name: Option<String>,
+    #[darling(default)]
+    namespace: Option<String>,
+    #[darling(default)]
+    prefix: Option<String>,
+    #[darling(default)]
+    attribute: bool,
+    #[darling(default)]
+    wrapped: bool,
+}
+
+impl XmlObject {
+    pub(crate) fn to_token_stream(&self, crate_name: &TokenStream) -> TokenStream {
+        let name = optional_literal(&self.name);
+        let namespace = optional_literal(&self.namespace);
+        let prefix = optional_literal(&self.prefix);
+        let attribute = self.attribute;
+        let wrapped = self.wrapped;
+        quote! {
+            #crate_name::registry::MetaXml {
+                name: #name,
+                namespace: #namespace,
+                prefix: #prefix,
+                attribute: #attribute,
+                wrapped: #wrapped,
+            }
+        }
+    }
+}
+
 #[derive(FromMeta)]
 pub(crate) struct ExtraHeader {
     pub(crate) name: String,
@@ -280,6 +393,19 @@ where
     }
 }
 
+#[derive(FromMeta)]
+pub(crate) struct Callback {
+    pub(crate) name: String,
+    pub(crate) url: String,
+    pub(crate) ty: SpannedValue<String>,
+}
+
+#[derive(FromMeta)]
+pub(crate) struct RateLimit {
+    pub(crate) requests: SpannedValue<u64>,
+    pub(crate) period: SpannedValue<u64>,
+}
+
 #[derive(FromMeta)]
 pub(crate) struct CodeSample {
     pub(crate) lang: String,
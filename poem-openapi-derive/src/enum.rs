@@ -5,10 +5,12 @@ use darling::{
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{Attribute, DeriveInput, Error, Path, ext::IdentExt};
+use syn::{
+    Attribute, DeriveInput, Error, Expr, ExprLit, ExprUnary, Lit, Path, UnOp, ext::IdentExt,
+};
 
 use crate::{
-    common_args::{ExternalDocument, RenameRule, apply_rename_rule_variant},
+    common_args::{Extension, ExternalDocument, RenameRule, apply_rename_rule_variant},
     error::GeneratorResult,
     utils::{get_crate_name, get_description, optional_literal},
 };
@@ -18,9 +20,12 @@ use crate::{
 struct EnumItem {
     ident: Ident,
     fields: Fields<Ignored>,
+    discriminant: Option<Expr>,
 
     #[darling(default)]
     rename: Option<String>,
+    #[darling(default)]
+    other: bool,
 }
 
 #[derive(FromDeriveInput)]
@@ -39,9 +44,13 @@ struct EnumArgs {
     #[darling(default)]
     remote: Option<Path>,
     #[darling(default)]
+    integer: bool,
+    #[darling(default)]
     deprecated: bool,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -58,8 +67,29 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut enum_items = Vec::new();
     let mut ident_to_item = Vec::new();
     let mut item_to_ident = Vec::new();
+    let mut other = None;
+    let mut next_value = 0;
 
     for variant in e {
+        if variant.other {
+            if other.is_some() {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "Only one variant can be the `other` variant.",
+                )
+                .into());
+            }
+            if !variant.fields.is_tuple() || variant.fields.len() != 1 {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "The `other` variant must have exactly one unnamed field.",
+                )
+                .into());
+            }
+            other = Some(&variant.ident);
+            continue;
+        }
+
         if !variant.fields.is_empty() {
             return Err(Error::new_spanned(
                 &variant.ident,
@@ -72,27 +102,51 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
 
         let item_ident = &variant.ident;
-        let oai_item_name = variant.rename.clone().unwrap_or_else(|| {
-            apply_rename_rule_variant(args.rename_all, variant.ident.unraw().to_string())
-        });
-
         enum_items.push(quote!(#crate_name::types::ToJSON::to_json(&#ident::#item_ident).unwrap()));
-        ident_to_item.push(quote!(#ident::#item_ident => #oai_item_name));
-        item_to_ident
-            .push(quote!(#oai_item_name => ::std::result::Result::Ok(#ident::#item_ident)));
+
+        if args.integer {
+            if variant.rename.is_some() {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "The items of an integer enum cannot be renamed.",
+                )
+                .into());
+            }
+            let value = match &variant.discriminant {
+                Some(discriminant) => parse_discriminant(discriminant)?,
+                None => next_value,
+            };
+            next_value = value.wrapping_add(1);
+            ident_to_item.push(quote!(#ident::#item_ident => #crate_name::__private::serde_json::Value::from(#value)));
+            item_to_ident.push(quote!(#value => ::std::result::Result::Ok(#ident::#item_ident)));
+        } else {
+            let oai_item_name = variant.rename.clone().unwrap_or_else(|| {
+                apply_rename_rule_variant(args.rename_all, variant.ident.unraw().to_string())
+            });
+            ident_to_item.push(quote!(#ident::#item_ident => #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(#oai_item_name))));
+            item_to_ident
+                .push(quote!(#oai_item_name => ::std::result::Result::Ok(#ident::#item_ident)));
+        }
+    }
+    if let Some(other) = other {
+        ident_to_item.push(quote!(#ident::#other(value) => #crate_name::__private::serde_json::Value::from(::std::clone::Clone::clone(value))));
     }
 
     let remote_conversion = if let Some(remote_ty) = &args.remote {
         let local_to_remote_items = e.iter().map(|item| {
+            let is_other = item.other;
             let item = &item.ident;
-            quote! {
-                #ident::#item => #remote_ty::#item,
+            match is_other {
+                true => quote!(#ident::#item(value) => #remote_ty::#item(value),),
+                false => quote!(#ident::#item => #remote_ty::#item,),
             }
         });
         let remote_to_local_items = e.iter().map(|item| {
+            let is_other = item.other;
             let item = &item.ident;
-            quote! {
-                #remote_ty::#item => #ident::#item,
+            match is_other {
+                true => quote!(#remote_ty::#item(value) => #ident::#item(value),),
+                false => quote!(#remote_ty::#item => #ident::#item,),
             }
         });
 
@@ -118,6 +172,87 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     };
     let description = optional_literal(&description);
     let deprecated = args.deprecated;
+    let extensions = Extension::to_token_stream(&args.extensions, &crate_name)?;
+    let ty = if args.integer { "integer" } else { "string" };
+    // the values of an open enum are not restricted, the known values are
+    // only documented
+    let (enum_items, extensions) = match other {
+        Some(_) => (
+            quote!(::std::vec::Vec::new()),
+            quote!({
+                let mut extensions: ::std::collections::BTreeMap<::std::string::String, #crate_name::__private::serde_json::Value> = #extensions;
+                extensions.insert(
+                    ::std::string::ToString::to_string("x-extensible-enum"),
+                    #crate_name::__private::serde_json::Value::Array(::std::vec![#(#enum_items),*]),
+                );
+                extensions
+            }),
+        ),
+        None => (quote!(::std::vec![#(#enum_items),*]), extensions),
+    };
+    let parse_json = match (args.integer, other) {
+        (false, other) => {
+            let fallback = match other {
+                Some(other) => {
+                    quote!(::std::result::Result::Ok(#ident::#other(::std::string::ToString::to_string(item))))
+                }
+                None => {
+                    quote!(::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)))
+                }
+            };
+            quote! {
+                #crate_name::__private::serde_json::Value::String(item) => match item.as_str() {
+                    #(#item_to_ident,)*
+                    _ => #fallback,
+                }
+            }
+        }
+        (true, other) => {
+            let fallback_pat = match other {
+                Some(_) => quote!(item),
+                None => quote!(_),
+            };
+            let fallback = match other {
+                Some(other) => quote!(::std::result::Result::Ok(#ident::#other(item))),
+                None => {
+                    quote!(::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)))
+                }
+            };
+            quote! {
+                #crate_name::__private::serde_json::Value::Number(item) if item.is_i64() => match item.as_i64().unwrap() {
+                    #(#item_to_ident,)*
+                    #fallback_pat => #fallback,
+                }
+            }
+        }
+    };
+    let parse_parameter = {
+        let fallback_pat = match other {
+            Some(_) => quote!(value),
+            None => quote!(_),
+        };
+        let fallback = match (args.integer, other) {
+            (false, Some(other)) => {
+                quote!(::std::result::Result::Ok(#ident::#other(::std::string::ToString::to_string(value))))
+            }
+            (true, Some(other)) => quote!(::std::result::Result::Ok(#ident::#other(value))),
+            (_, None) => {
+                quote!(::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value.")))
+            }
+        };
+        let value = match args.integer {
+            true => quote! {
+                value.parse::<i64>().map_err(|_| #crate_name::types::ParseError::custom("Expect a valid enumeration value."))?
+            },
+            false => quote!(value),
+        };
+        quote! {
+            match #value {
+                #(#item_to_ident,)*
+                #fallback_pat => #fallback,
+            }
+        }
+    };
     let external_docs = match &args.external_docs {
         Some(external_docs) => {
             let s = external_docs.to_token_stream(&crate_name);
@@ -150,9 +285,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 registry.create_schema::<Self, _>(<Self as #crate_name::types::Type>::name().into_owned(), |registry| #crate_name::registry::MetaSchema {
                     description: #description,
                     external_docs: #external_docs,
+                    extensions: #extensions,
                     deprecated: #deprecated,
-                    enum_items: ::std::vec![#(#enum_items),*],
-                    ..#crate_name::registry::MetaSchema::new("string")
+                    enum_items: #enum_items,
+                    ..#crate_name::registry::MetaSchema::new(#ty)
                 });
             }
 
@@ -165,10 +301,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             fn parse_from_json(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> #crate_name::types::ParseResult<Self> {
                 let value = value.unwrap_or_default();
                 match &value {
-                    #crate_name::__private::serde_json::Value::String(item) => match item.as_str() {
-                        #(#item_to_ident,)*
-                        _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
-                    }
+                    #parse_json
                     _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
                 }
             }
@@ -176,19 +309,15 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         impl #crate_name::types::ParseFromParameter for #ident {
             fn parse_from_parameter(value: &str) -> #crate_name::types::ParseResult<Self> {
-                match value {
-                    #(#item_to_ident,)*
-                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value.")),
-                }
+                #parse_parameter
             }
         }
 
         impl #crate_name::types::ToJSON for #ident {
             fn to_json(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
-                let name = match self {
+                ::std::option::Option::Some(match self {
                     #(#ident_to_item),*
-                };
-                ::std::option::Option::Some(#crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(name)))
+                })
             }
         }
 
@@ -210,3 +339,24 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
     Ok(expanded)
 }
+
+/// Returns the value of an integer literal discriminant.
+fn parse_discriminant(expr: &Expr) -> GeneratorResult<i64> {
+    let (negative, lit) = match expr {
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => (true, &**expr),
+        expr => (false, expr),
+    };
+    match lit {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => {
+            let value = lit.base10_parse::<i64>()?;
+            Ok(if negative { -value } else { value })
+        }
+        _ => Err(Error::new_spanned(expr, "Expect an integer literal.").into()),
+    }
+}
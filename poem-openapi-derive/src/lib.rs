@@ -19,6 +19,7 @@ mod object;
 mod request;
 mod response;
 mod response_content;
+mod response_headers;
 mod security_scheme;
 mod tags;
 mod union;
@@ -103,6 +104,15 @@ pub fn derive_response_content(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(ResponseHeaders, attributes(oai))]
+pub fn derive_response_headers(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as DeriveInput);
+    match response_headers::generate(args) {
+        Ok(stream) => stream.into(),
+        Err(err) => err.write_errors().into(),
+    }
+}
+
 #[proc_macro_attribute]
 #[allow(non_snake_case)]
 pub fn OpenApi(args: TokenStream, input: TokenStream) -> TokenStream {
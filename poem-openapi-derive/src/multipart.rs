@@ -28,6 +28,10 @@ struct MultipartField {
     default: Option<DefaultValue>,
     #[darling(default)]
     validator: Option<Validators>,
+    #[darling(default)]
+    limit: Option<usize>,
+    #[darling(default)]
+    content_type: Option<String>,
 }
 
 #[derive(FromDeriveInput)]
@@ -68,6 +72,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut meta_fields = Vec::new();
     let mut register_fields = Vec::new();
     let mut required_fields = Vec::new();
+    let mut encoding_fields = Vec::new();
 
     for field in &s.fields {
         let field_ident = field.ident.as_ref().unwrap();
@@ -99,8 +104,15 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
         }};
 
+        let limit = field.limit.map(|limit| {
+            quote! {
+                let field = field.limit(#limit);
+            }
+        });
+
         deserialize_fields.push(quote! {
             if field.name() == ::std::option::Option::Some(#field_name) {
+                #limit
                 #field_ident = match #field_ident {
                     ::std::option::Option::Some(value) => {
                         ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field(value, field).await.map_err(|err| #parse_err )?)
@@ -109,6 +121,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_multipart(::std::option::Option::Some(field)).await.map_err(|err| #parse_err )?)
                     }
                 };
+                if <#field_ty as #crate_name::types::ParseFromMultipartField>::IS_STREAMING {
+                    break;
+                }
                 continue;
             }
         });
@@ -183,6 +198,18 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             <#field_ty as #crate_name::types::Type>::register(registry);
         });
 
+        let content_type = match &field.content_type {
+            Some(content_type) => quote!(::std::option::Option::Some(#content_type)),
+            None => {
+                quote!(<#field_ty as #crate_name::types::ParseFromMultipartField>::CONTENT_TYPE)
+            }
+        };
+        encoding_fields.push(quote! {
+            if let ::std::option::Option::Some(content_type) = #content_type {
+                encoding.push((#field_name, #crate_name::registry::MetaEncoding { content_type }));
+            }
+        });
+
         required_fields.push(quote! {
             if <#field_ty as #crate_name::types::Type>::IS_REQUIRED && !#has_default {
                 fields.push(#field_name);
@@ -232,6 +259,12 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         fields
                     },
                     properties: ::std::vec![#(#meta_fields),*],
+                    encoding: {
+                        #[allow(unused_mut)]
+                        let mut encoding = ::std::vec::Vec::new();
+                        #(#encoding_fields)*
+                        encoding
+                    },
                     ..#crate_name::registry::MetaSchema::new("object")
                 };
                 #crate_name::registry::MetaSchemaRef::Inline(Box::new(schema))
@@ -274,6 +307,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     content: ::std::vec![#crate_name::registry::MetaMediaType {
                         content_type: <Self as #crate_name::payload::Payload>::CONTENT_TYPE,
                         schema: <Self as #crate_name::payload::Payload>::schema_ref(),
+                        example: ::std::option::Option::None,
+                        examples: ::std::vec![],
                     }],
                     required: <Self as #crate_name::payload::ParsePayload>::IS_REQUIRED,
                 })
@@ -4,13 +4,19 @@ use darling::{
     util::{Ignored, SpannedValue},
 };
 use proc_macro2::{Ident, TokenStream};
-use quote::quote;
-use syn::{Attribute, DeriveInput, Error, Generics, Path, Type, ext::IdentExt};
+use quote::{ToTokens, quote};
+use syn::{
+    Attribute, DeriveInput, Error, GenericParam, Generics, Path, Type, ext::IdentExt, parse_quote,
+};
 
 use crate::{
-    common_args::{DefaultValue, ExternalDocument, RenameRule, apply_rename_rule_field},
+    common_args::{
+        DefaultValue, Extension, ExternalDocument, RenameRule, XmlObject, apply_rename_rule_field,
+    },
     error::GeneratorResult,
-    utils::{create_object_name, get_crate_name, get_description, optional_literal},
+    utils::{
+        contains_ident, create_object_name, get_crate_name, get_description, optional_literal,
+    },
     validators::Validators,
 };
 
@@ -26,6 +32,8 @@ struct ObjectField {
 
     #[darling(default)]
     rename: Option<String>,
+    #[darling(default, multiple, rename = "alias")]
+    aliases: Vec<String>,
     #[darling(default)]
     default: Option<DefaultValue>,
     #[darling(default)]
@@ -37,6 +45,10 @@ struct ObjectField {
     #[darling(default)]
     read_only: bool,
     #[darling(default)]
+    skip_serializing: bool,
+    #[darling(default)]
+    skip_deserializing: bool,
+    #[darling(default)]
     validator: Option<Validators>,
     #[darling(default)]
     flatten: SpannedValue<bool>,
@@ -50,6 +62,10 @@ struct ObjectField {
     serialize_with: Option<Path>,
     #[darling(default)]
     deserialize_with: Option<Path>,
+    #[darling(default)]
+    with: Option<Path>,
+    #[darling(default)]
+    xml: Option<XmlObject>,
 }
 
 #[derive(FromDeriveInput)]
@@ -77,9 +93,15 @@ struct ObjectArgs {
     #[darling(default)]
     deny_unknown_fields: bool,
     #[darling(default)]
+    ignore_read_only: bool,
+    #[darling(default)]
     example: bool,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
+    #[darling(default)]
+    xml: Option<XmlObject>,
     #[darling(default)]
     remote: Option<Path>,
     #[darling(default)]
@@ -88,12 +110,15 @@ struct ObjectArgs {
     skip_serializing_if_is_empty: bool,
     #[darling(default)]
     default: Option<DefaultValue>,
+    #[darling(default)]
+    validate: Option<Path>,
+    #[darling(default)]
+    parameter: bool,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let args: ObjectArgs = ObjectArgs::from_derive_input(&args)?;
     let crate_name = get_crate_name(args.internal);
-    let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
     let ident = &args.ident;
     let s = match &args.data {
         Data::Struct(s) => s,
@@ -103,16 +128,38 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             );
         }
     };
+
+    // type parameters used by the fields must be object field types
+    let mut generics = args.generics.clone();
+    for param in &args.generics.params {
+        let GenericParam::Type(param) = param else {
+            continue;
+        };
+        let ty_ident = &param.ident;
+        if s.fields
+            .iter()
+            .any(|field| !field.skip && contains_ident(field.ty.to_token_stream(), ty_ident))
+        {
+            generics.make_where_clause().predicates.push(parse_quote! {
+                #ty_ident: #crate_name::types::ParseFromJSON + #crate_name::types::ToJSON
+            });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let oai_typename = args.rename.clone().unwrap_or_else(|| ident.to_string());
     let description = get_description(&args.attrs)?;
     let mut deserialize_fields = Vec::new();
+    let mut deserialize_flatten_fields = Vec::new();
+    let mut deserialize_flatten_maps = Vec::new();
     let mut serialize_fields = Vec::new();
     let mut register_types = Vec::new();
     let mut fields = Vec::new();
     let mut meta_fields = Vec::new();
     let mut additional_properties = quote! { ::std::option::Option::None };
     let mut required_fields = Vec::new();
-    let object_name = create_object_name(&crate_name, &oai_typename, &args.generics);
+    let mut parse_properties = Vec::new();
+    let mut property_names = Vec::new();
+    let object_name = create_object_name(&crate_name, &oai_typename, &args.generics)?;
 
     for field in &s.fields {
         let field_ident = field
@@ -120,8 +167,26 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             .as_ref()
             .ok_or_else(|| Error::new_spanned(ident, "All fields must be named."))?;
         let field_ty = &field.ty;
-        let read_only = args.read_only_all || field.read_only;
-        let write_only = args.write_only_all || field.write_only;
+        // the type that describes the field, which is a remote object that mirrors
+        // the field type if `with` is specified
+        let schema_ty = match &field.with {
+            Some(with) => quote!(#with),
+            None => quote!(#field_ty),
+        };
+        let to_json_function = match &field.with {
+            Some(with) => quote! {
+                (|value: &#field_ty| #crate_name::types::ToJSON::to_json(&<#with as ::std::convert::From<#field_ty>>::from(::std::clone::Clone::clone(value))))
+            },
+            None => quote!(#crate_name::types::ToJSON::to_json),
+        };
+        let parse_function = match &field.with {
+            Some(with) => quote! {
+                (|value| <#with as #crate_name::types::ParseFromJSON>::parse_from_json(value).map(::std::convert::Into::into))
+            },
+            None => quote!(#crate_name::types::ParseFromJSON::parse_from_json),
+        };
+        let read_only = args.read_only_all || field.read_only || field.skip_deserializing;
+        let write_only = args.write_only_all || field.write_only || field.skip_serializing;
         let nullable = args.nullable_all || field.nullable;
         let skip_serializing_if_is_none =
             field.skip_serializing_if_is_none || args.skip_serializing_if_is_none;
@@ -138,6 +203,32 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             continue;
         }
 
+        if let Some(with) = &field.with {
+            if *field.flatten || field.validator.is_some() {
+                return Err(Error::new_spanned(
+                    with,
+                    "The `with` attribute cannot be used with `flatten` or `validator`.",
+                )
+                .into());
+            }
+        }
+
+        if field.skip_serializing && field.skip_deserializing {
+            return Err(Error::new_spanned(
+                field_ident,
+                "Use the `skip` attribute instead of `skip_serializing` and `skip_deserializing`.",
+            )
+            .into());
+        }
+
+        if *field.flatten && !field.aliases.is_empty() {
+            return Err(Error::new(
+                field.flatten.span(),
+                "The `alias` attribute cannot be used with `flatten`.",
+            )
+            .into());
+        }
+
         if read_only && write_only {
             return Err(Error::new_spanned(
                 field_ident,
@@ -149,14 +240,63 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         let field_name = field.rename.clone().unwrap_or_else(|| {
             apply_rename_rule_field(args.rename_all, field_ident.unraw().to_string())
         });
+        let aliases = &field.aliases;
+        // the value of the field, the first of the names that is found
+        let take_value = quote! {{
+            #[allow(unused_mut)]
+            let mut value = obj.remove(#field_name);
+            #(
+                if let ::std::option::Option::Some(alias_value) = obj.remove(#aliases) {
+                    value.get_or_insert(alias_value);
+                }
+            )*
+            value
+        }};
         let field_description = get_description(&field.attrs)?;
         let field_description = optional_literal(&field_description);
         let validators = field.validator.clone().unwrap_or_default();
         let validators_checker = validators.create_obj_field_checker(&crate_name, &field_name)?;
         let validators_update_meta = validators.create_update_meta(&crate_name)?;
+        let field_xml = match &field.xml {
+            Some(xml) => {
+                let xml = xml.to_token_stream(&crate_name);
+                quote!(::std::option::Option::Some(#xml))
+            }
+            None => quote!(::std::option::Option::None),
+        };
 
         fields.push(field_ident);
 
+        if args.parameter && !read_only {
+            if *field.flatten {
+                parse_properties.push(quote! {
+                    let value = <#field_ty as #crate_name::types::ParseFromParameter>::parse_from_properties(properties)
+                        .map_err(#crate_name::types::ParseError::propagate)?;
+                    if let ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(value)) = #crate_name::types::ToJSON::to_json(&value) {
+                        obj.extend(value);
+                    }
+                });
+            } else {
+                property_names.push(field_name.clone());
+                property_names.extend(aliases.iter().cloned());
+                parse_properties.push(quote! {
+                    let mut values = ::std::iter::Iterator::peekable(
+                        ::std::iter::Iterator::filter_map(
+                            ::std::iter::IntoIterator::into_iter(properties),
+                            |(name, value)| (*name == #field_name #(|| *name == #aliases)*).then_some(*value),
+                        )
+                    );
+                    if values.peek().is_some() {
+                        let value = <#schema_ty as #crate_name::types::ParseFromParameter>::parse_from_parameters(values)
+                            .map_err(#crate_name::types::ParseError::propagate)?;
+                        if let ::std::option::Option::Some(value) = #crate_name::types::ToJSON::to_json(&value) {
+                            obj.insert(::std::string::ToString::to_string(#field_name), value);
+                        }
+                    }
+                });
+            }
+        }
+
         let create_default_value = match (&field.default, &args.default) {
             // field default
             (Some(default_value), _) => Some(match default_value {
@@ -181,12 +321,19 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             let create_default_value = create_default_value
                 .clone()
                 .unwrap_or_else(|| quote! { ::std::default::Default::default() });
+            let check_read_only = if args.ignore_read_only || field.skip_deserializing {
+                quote!(#take_value;)
+            } else {
+                quote! {
+                    if obj.contains_key(#field_name) #(|| obj.contains_key(#aliases))* {
+                        return Err(#crate_name::types::ParseError::custom(format!("properties `{}` is read only.", #field_name)));
+                    }
+                }
+            };
             deserialize_fields.push(quote! {
                 #[allow(non_snake_case)]
                 let #field_ident: #field_ty = {
-                    if obj.contains_key(#field_name) {
-                        return Err(#crate_name::types::ParseError::custom(format!("properties `{}` is read only.", #field_name)));
-                    }
+                    #check_read_only
                     #create_default_value
                 };
             });
@@ -196,10 +343,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     deserialize_fields.push(quote! {
                         #[allow(non_snake_case)]
                         let #field_ident: #field_ty = {
-                            match obj.remove(#field_name) {
+                            match #take_value {
                                 ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Null) | ::std::option::Option::None => #create_default_value,
                                 value => {
-                                    let value = #crate_name::types::ParseFromJSON::parse_from_json(value).map_err(#crate_name::types::ParseError::propagate)?;
+                                    let value = #parse_function(value).map_err(#crate_name::types::ParseError::propagate)?;
                                     #validators_checker
                                     value
                                 }
@@ -210,13 +357,13 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 None => {
                     let deserialize_function = match field.deserialize_with {
                         Some(ref function) => quote! { #function },
-                        None => quote! { #crate_name::types::ParseFromJSON::parse_from_json },
+                        None => parse_function,
                     };
 
                     deserialize_fields.push(quote! {
                         #[allow(non_snake_case)]
                         let #field_ident: #field_ty = {
-                            let value = #deserialize_function(obj.remove(#field_name))
+                            let value = #deserialize_function(#take_value)
                                 .map_err(#crate_name::types::ParseError::propagate)?;
                             #validators_checker
                             value
@@ -232,23 +379,34 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 )
                 .into());
             }
-            deserialize_fields.push(quote! {
+            // the flattened fields are parsed from the remaining properties, and
+            // a flattened map receives the properties of no other field
+            let deserialize_flatten_fields = match is_map_type(field_ty) {
+                true => &mut deserialize_flatten_maps,
+                false => &mut deserialize_flatten_fields,
+            };
+            deserialize_flatten_fields.push(quote! {
                 #[allow(non_snake_case)]
                 let #field_ident: #field_ty = {
                     #crate_name::types::ParseFromJSON::parse_from_json(::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(::std::clone::Clone::clone(&obj))))
                         .map_err(#crate_name::types::ParseError::propagate)?
                 };
+                if let ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(value)) = #crate_name::types::ToJSON::to_json(&#field_ident) {
+                    for name in value.keys() {
+                        obj.remove(name);
+                    }
+                }
             });
         }
 
         if !*field.flatten {
             if !write_only {
-                let check_is_none = if skip_serializing_if_is_none {
+                let check_is_none = if skip_serializing_if_is_none && field.with.is_none() {
                     quote!(!#crate_name::types::Type::is_none(&self.#field_ident))
                 } else {
                     quote!(true)
                 };
-                let check_is_empty = if skip_serializing_if_is_empty {
+                let check_is_empty = if skip_serializing_if_is_empty && field.with.is_none() {
                     quote!(!#crate_name::types::Type::is_empty(&self.#field_ident))
                 } else {
                     quote!(true)
@@ -261,7 +419,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
                 let serialize_function = match field.serialize_with {
                     Some(ref function) => quote! { #function },
-                    None => quote! { #crate_name::types::ToJSON::to_json },
+                    None => to_json_function.clone(),
                 };
 
                 serialize_fields.push(quote! {
@@ -282,15 +440,15 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         let field_meta_default = match &create_default_value {
             Some(create_default_value) if !read_only => {
-                quote!(#crate_name::types::ToJSON::to_json(&#create_default_value))
+                quote!(#to_json_function(&#create_default_value))
             }
             _ => quote!(::std::option::Option::None),
         };
 
-        register_types.push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
+        register_types.push(quote!(<#schema_ty as #crate_name::types::Type>::register(registry);));
         if !*field.flatten {
             meta_fields.push(quote! {{
-                let original_schema = <#field_ty as #crate_name::types::Type>::schema_ref();
+                let original_schema = <#schema_ty as #crate_name::types::Type>::schema_ref();
                 let patch_schema = {
                     let mut schema = #crate_name::registry::MetaSchema::ANY;
                     schema.default = #field_meta_default;
@@ -298,6 +456,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     schema.read_only = #read_only;
                     schema.write_only = #write_only;
                     schema.deprecated = #deprecated;
+                    schema.xml = #field_xml;
 
                     if let ::std::option::Option::Some(field_description) = #field_description {
                         schema.description = ::std::option::Option::Some(field_description);
@@ -311,7 +470,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
             let has_default = field.default.is_some();
             required_fields.push(quote! {
-                if <#field_ty>::IS_REQUIRED && !#has_default {
+                if <#schema_ty>::IS_REQUIRED && !#has_default {
                     fields.push(#field_name);
                 }
             });
@@ -320,7 +479,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 fields.extend(registry.create_fake_schema::<#field_ty>().properties);
             });
             additional_properties = quote! {
-                registry.create_fake_schema::<#field_ty>().additional_properties
+                ::std::option::Option::or_else(#additional_properties, || registry.create_fake_schema::<#field_ty>().additional_properties)
             };
             required_fields.push(quote! {
                 fields.extend(registry.create_fake_schema::<#field_ty>().required);
@@ -330,6 +489,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
     let description = optional_literal(&description);
     let deprecated = args.deprecated;
+    let extensions = Extension::to_token_stream(&args.extensions, &crate_name)?;
     let external_docs = match &args.external_docs {
         Some(external_docs) => {
             let s = external_docs.to_token_stream(&crate_name);
@@ -337,11 +497,19 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
         None => quote!(::std::option::Option::None),
     };
+    let xml = match &args.xml {
+        Some(xml) => {
+            let xml = xml.to_token_stream(&crate_name);
+            quote!(::std::option::Option::Some(#xml))
+        }
+        None => quote!(::std::option::Option::None),
+    };
     let meta = quote! {
         #crate_name::registry::MetaSchema {
             title: ::std::option::Option::Some(#object_name),
             description: #description,
             external_docs: #external_docs,
+            extensions: #extensions,
             required: {
                 #[allow(unused_mut)]
                 let mut fields = ::std::vec::Vec::new();
@@ -355,6 +523,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             },
             additional_properties: #additional_properties,
             deprecated: #deprecated,
+            xml: #xml,
             ..#crate_name::registry::MetaSchema::new("object")
         }
     };
@@ -368,6 +537,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         None
     };
 
+    let validate_obj = args.validate.as_ref().map(|validate| {
+        quote! {
+            if let ::std::result::Result::Err(err) = #validate(&obj) {
+                return ::std::result::Result::Err(#crate_name::types::ParseError::custom(err));
+            }
+        }
+    });
+
     let (example, where_clause) = if args.example {
         let new_where_clause = match where_clause {
             Some(where_clause) => {
@@ -434,8 +611,12 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 match value {
                     #crate_name::__private::serde_json::Value::Object(mut obj) => {
                         #(#deserialize_fields)*
+                        #(#deserialize_flatten_fields)*
+                        #(#deserialize_flatten_maps)*
                         #deny_unknown_fields
-                        ::std::result::Result::Ok(Self { #(#fields),* })
+                        let obj = Self { #(#fields),* };
+                        #validate_obj
+                        ::std::result::Result::Ok(obj)
                     }
                     _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
                 }
@@ -452,23 +633,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         impl #impl_generics #crate_name::types::ParseFromXML for #ident #ty_generics #where_clause {
             fn parse_from_xml(value: ::std::option::Option<#crate_name::__private::serde_json::Value>) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
-                let value = value.unwrap_or_default();
-                match value {
-                    #crate_name::__private::serde_json::Value::Object(mut obj) => {
-                        #(#deserialize_fields)*
-                        #deny_unknown_fields
-                        ::std::result::Result::Ok(Self { #(#fields),* })
-                    }
-                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
-                }
+                let value = value.map(#crate_name::__private::from_xml_value::<Self>);
+                <Self as #crate_name::types::ParseFromJSON>::parse_from_json(value)
             }
         }
 
         impl #impl_generics #crate_name::types::ToXML for #ident #ty_generics #where_clause {
             fn to_xml(&self) -> ::std::option::Option<#crate_name::__private::serde_json::Value> {
-                let mut object = #crate_name::__private::serde_json::Map::new();
-                #(#serialize_fields)*
-                ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(object))
+                <Self as #crate_name::types::ToJSON>::to_json(self).map(#crate_name::__private::to_xml_value::<Self>)
             }
         }
 
@@ -478,8 +650,12 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 match value {
                     #crate_name::__private::serde_json::Value::Object(mut obj) => {
                         #(#deserialize_fields)*
+                        #(#deserialize_flatten_fields)*
+                        #(#deserialize_flatten_maps)*
                         #deny_unknown_fields
-                        ::std::result::Result::Ok(Self { #(#fields),* })
+                        let obj = Self { #(#fields),* };
+                        #validate_obj
+                        ::std::result::Result::Ok(obj)
                     }
                     _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
                 }
@@ -495,6 +671,50 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     };
 
+    // parameter
+    let parameter = if args.parameter {
+        let mut generics = generics.clone();
+        for param in &args.generics.params {
+            if let GenericParam::Type(param) = param {
+                let ty_ident = &param.ident;
+                generics.make_where_clause().predicates.push(parse_quote! {
+                    #ty_ident: #crate_name::types::ParseFromParameter
+                });
+            }
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        // the unknown properties are rejected when parsing the object
+        let unknown_properties = args.deny_unknown_fields.then(|| {
+            quote! {
+                for (name, value) in properties {
+                    if ![#(#property_names),*].contains(name) {
+                        obj.insert(::std::string::ToString::to_string(name), #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(value)));
+                    }
+                }
+            }
+        });
+
+        Some(quote! {
+            impl #impl_generics #crate_name::types::ParseFromParameter for #ident #ty_generics #where_clause {
+                const IS_OBJECT: bool = true;
+
+                fn parse_from_parameter(value: &str) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                    <Self as #crate_name::types::ParseFromParameter>::parse_from_properties(&#crate_name::__private::split_properties(value))
+                }
+
+                fn parse_from_properties(properties: &[(&str, &str)]) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                    let mut obj = #crate_name::__private::serde_json::Map::new();
+                    #(#parse_properties)*
+                    #unknown_properties
+                    <Self as #crate_name::types::ParseFromJSON>::parse_from_json(::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(obj)))
+                }
+            }
+        })
+    } else {
+        None
+    };
+
     // remote
     let remote = if let Some(remote) = &args.remote {
         let fields = s
@@ -526,6 +746,21 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
     Ok(quote! {
         #define_obj
+        #parameter
         #remote
     })
 }
+
+/// Returns `true` if the type is a map, which is parsed after the other
+/// flattened fields.
+fn is_map_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            matches!(
+                segment.ident.to_string().as_str(),
+                "HashMap" | "BTreeMap" | "IndexMap"
+            )
+        }),
+        _ => false,
+    }
+}
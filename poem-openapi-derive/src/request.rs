@@ -7,9 +7,10 @@ use darling::{
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{Attribute, DeriveInput, Error, Generics, Type};
+use syn::{Attribute, DeriveInput, Error, Generics, Path, Type};
 
 use crate::{
+    common_args::NamedExamples,
     error::GeneratorResult,
     utils::{get_crate_name, get_description, optional_literal},
 };
@@ -22,6 +23,10 @@ struct RequestItem {
 
     #[darling(default)]
     content_type: Option<SpannedValue<String>>,
+    #[darling(default)]
+    example: Option<Path>,
+    #[darling(default)]
+    examples: Option<NamedExamples>,
 }
 
 #[derive(FromDeriveInput)]
@@ -86,7 +91,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 let check_content_type = match &variant.content_type {
                     Some(content_type) => {
                         let content_type = &**content_type;
-                        quote!(content_type == #content_type)
+                        quote!(#crate_name::__private::content_type_is(content_type, #content_type))
                     }
                     None => {
                         quote!(<#payload_ty as #crate_name::payload::Payload>::check_content_type(content_type))
@@ -99,10 +104,17 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         ));
                     }
                 });
+                let example = match &variant.example {
+                    Some(func) => quote!(#crate_name::types::ToJSON::to_json(&#func())),
+                    None => quote!(::std::option::Option::None),
+                };
+                let examples = NamedExamples::to_meta(variant.examples.as_ref(), &crate_name);
                 content.push(quote! {
                     #crate_name::registry::MetaMediaType {
                         content_type: #content_type,
                         schema: <#payload_ty as #crate_name::payload::Payload>::schema_ref(),
+                        example: #example,
+                        examples: #examples,
                     }
                 });
                 schemas.push(payload_ty);
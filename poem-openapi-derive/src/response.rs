@@ -1,5 +1,5 @@
 use darling::{
-    FromDeriveInput, FromField, FromVariant,
+    FromDeriveInput, FromField, FromMeta, FromVariant,
     ast::{Data, Fields},
     util::Ignored,
 };
@@ -8,7 +8,7 @@ use quote::quote;
 use syn::{Attribute, DeriveInput, Error, Generics, Path, Type};
 
 use crate::{
-    common_args::{ExtraHeader, LitOrPath},
+    common_args::{ExtraHeader, LitOrPath, NamedExamples},
     error::GeneratorResult,
     utils::{get_crate_name, get_description, optional_literal, optional_literal_string},
 };
@@ -25,6 +25,24 @@ struct ResponseField {
     deprecated: bool,
 }
 
+#[derive(FromMeta)]
+struct LinkParameter {
+    name: String,
+    value: String,
+}
+
+#[derive(FromMeta)]
+struct Link {
+    name: String,
+    operation_id: String,
+    #[darling(default, multiple, rename = "parameter")]
+    parameters: Vec<LinkParameter>,
+    #[darling(default)]
+    request_body: Option<String>,
+    #[darling(default)]
+    description: Option<String>,
+}
+
 #[derive(FromVariant)]
 #[darling(attributes(oai), forward_attrs(doc))]
 struct ResponseItem {
@@ -37,9 +55,17 @@ struct ResponseItem {
     #[darling(default)]
     status_range: Option<String>,
     #[darling(default)]
+    default: bool,
+    #[darling(default)]
     content_type: Option<String>,
     #[darling(default, multiple, rename = "header")]
     headers: Vec<ExtraHeader>,
+    #[darling(default, multiple, rename = "link")]
+    links: Vec<Link>,
+    #[darling(default)]
+    example: Option<Path>,
+    #[darling(default)]
+    examples: Option<NamedExamples>,
     #[darling(default)]
     actual_type: Option<Type>,
 }
@@ -59,6 +85,8 @@ struct ResponseArgs {
     headers: Vec<ExtraHeader>,
     #[darling(default)]
     display: bool,
+    #[darling(default)]
+    from_error: bool,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -79,6 +107,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut error_messages = Vec::new();
     let mut responses_meta = Vec::new();
     let mut schemas = Vec::new();
+    let mut from_error_status = Vec::new();
+    let mut from_error_range = Vec::new();
+    let mut from_error_default = None;
 
     for variant in e {
         if matches!((&variant.status, &variant.status_range), (Some(_), Some(_))) {
@@ -88,6 +119,13 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             )
             .into());
         }
+        if variant.default && (variant.status.is_some() || variant.status_range.is_some()) {
+            return Err(Error::new(
+                variant.ident.span(),
+                "default cannot be used together with status or status_range.",
+            )
+            .into());
+        }
 
         let item_ident = &variant.ident;
         let item_description = get_description(&variant.attrs)?;
@@ -185,11 +223,66 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             (update_response_content_type, update_meta_content_type)
         }
 
+        // links
+        let mut meta_links = Vec::new();
+        for link in &variant.links {
+            let name = &link.name;
+            let operation_id = &link.operation_id;
+            let parameters = link
+                .parameters
+                .iter()
+                .map(|LinkParameter { name, value }| quote!((#name, #value)));
+            let request_body = optional_literal(&link.request_body);
+            let description = optional_literal(&link.description);
+            meta_links.push(quote! {
+                #crate_name::registry::MetaLink {
+                    name: #name,
+                    operation_id: #operation_id,
+                    parameters: ::std::vec![#(#parameters),*],
+                    request_body: #request_body,
+                    description: #description,
+                }
+            });
+        }
+
+        // examples
+        let mut update_meta_examples = Vec::new();
+        if let Some(func) = &variant.example {
+            update_meta_examples.push(quote! {
+                media.example = #crate_name::types::ToJSON::to_json(&#func());
+            });
+        }
+        if variant.examples.is_some() {
+            let examples = NamedExamples::to_meta(variant.examples.as_ref(), &crate_name);
+            update_meta_examples.push(quote!(media.examples = #examples;));
+        }
+        let update_meta_examples = (!update_meta_examples.is_empty()).then(|| {
+            quote! {
+                for media in &mut content {
+                    #(#update_meta_examples)*
+                }
+            }
+        });
+        let is_range = variant.status_range.is_some() || variant.default;
+        let has_body = match values.len() {
+            2 => true,
+            1 => !is_range,
+            _ => false,
+        };
+        if !has_body && (variant.example.is_some() || variant.examples.is_some()) {
+            return Err(Error::new(
+                variant.ident.span(),
+                "Examples can only be specified for responses with a body.",
+            )
+            .into());
+        }
+
         match values.len() {
-            2 if variant.status_range.is_some() => {
+            2 if is_range => {
                 // #[oai(status_range = "2XX")]
                 // Item(StatusCode, media)
-                let status_range = get_status_range(variant.ident.span(), &variant.status_range)?;
+                let status_range =
+                    get_status_range(variant.ident.span(), variant.default, &variant.status_range)?;
                 let media_ty = &values[1].ty;
                 let (update_response_content_type, update_meta_content_type) = update_content_type(
                     &crate_name,
@@ -212,13 +305,15 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     #crate_name::registry::MetaResponse {
                         description: #item_description.unwrap_or_default(),
                         status: ::std::option::Option::None,
-                        status_range: ::std::option::Option::Some(#status_range),
+                        status_range: #status_range,
                         content: {
                             let mut content = <#media_ty as #crate_name::ResponseContent>::media_types();
                             #update_meta_content_type
+                            #update_meta_examples
                             content
                         },
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
                 if let Some(actual_type) = variant.actual_type.as_ref() {
@@ -227,10 +322,11 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     schemas.push(media_ty);
                 }
             }
-            1 if variant.status_range.is_some() => {
+            1 if is_range => {
                 // #[oai(status_range = "2XX")]
                 // Item(StatusCode)
-                let status_range = get_status_range(variant.ident.span(), &variant.status_range)?;
+                let status_range =
+                    get_status_range(variant.ident.span(), variant.default, &variant.status_range)?;
                 into_responses.push(quote! {
                     #ident::#item_ident(status, #(#match_headers),*) => {
                         let mut resp = <#crate_name::__private::poem::http::StatusCode as #crate_name::__private::poem::IntoResponse>::into_response(status);
@@ -245,9 +341,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     #crate_name::registry::MetaResponse {
                         description: #item_description.unwrap_or_default(),
                         status: ::std::option::Option::None,
-                        status_range: ::std::option::Option::Some(#status_range),
+                        status_range: #status_range,
                         content: ::std::vec![],
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
             }
@@ -279,9 +376,11 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         content: {
                             let mut content = <#media_ty as #crate_name::ResponseContent>::media_types();
                             #update_meta_content_type
+                            #update_meta_examples
                             content
                         },
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
                 if let Some(actual_type) = variant.actual_type.as_ref() {
@@ -320,9 +419,11 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         content: {
                             let mut content = <#media_ty as #crate_name::ResponseContent>::media_types();
                             #update_meta_content_type
+                            #update_meta_examples
                             content
                         },
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
                 if let Some(actual_type) = variant.actual_type.as_ref() {
@@ -359,6 +460,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         status_range: ::std::option::Option::None,
                         content: ::std::vec![],
                         headers: ::std::vec![#(#meta_headers),*],
+                        links: ::std::vec![#(#meta_links),*],
                     }
                 });
             }
@@ -368,23 +470,82 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 );
             }
         }
+
+        // the variants constructed from an error, which can't have header fields
+        if args.from_error && headers.is_empty() {
+            let with_body = has_body.then(|| quote!(::std::convert::From::from(err)));
+            match (values.len(), &variant.status, &variant.status_range) {
+                (0 | 1, Some(_), _) => {
+                    let status = get_status(variant.ident.span(), &variant.status)?;
+                    let fields = has_body.then(|| quote!((#with_body)));
+                    from_error_status.push(quote! {
+                        if status.as_u16() == #status {
+                            return #ident::#item_ident #fields;
+                        }
+                    });
+                }
+                (_, _, Some(status_range)) => {
+                    let first = status_range.as_bytes()[0] - b'0';
+                    let start = first as u16 * 100;
+                    let end = start + 100;
+                    from_error_range.push(quote! {
+                        if (#start..#end).contains(&status.as_u16()) {
+                            return #ident::#item_ident(status, #with_body);
+                        }
+                    });
+                }
+                (1 | 2, None, None) if values.len() == 2 || variant.default => {
+                    from_error_default = Some(quote! {
+                        #ident::#item_ident(status, #with_body)
+                    });
+                }
+                _ => {}
+            }
+        }
     }
 
-    let bad_request_handler_const = match &args.bad_request_handler {
-        Some(_) => quote!(
+    let from_error = if args.from_error {
+        let from_error_default = from_error_default.ok_or_else(|| {
+            Error::new(
+                ident.span(),
+                "from_error requires a default response, e.g. `#[oai(default)] Default(StatusCode, Json<Error>)`.",
+            )
+        })?;
+        Some(quote! {
+            impl #impl_generics ::std::convert::From<#crate_name::__private::poem::Error> for #ident #ty_generics #where_clause {
+                fn from(err: #crate_name::__private::poem::Error) -> Self {
+                    let status = err.status();
+                    #(#from_error_status)*
+                    #(#from_error_range)*
+                    #from_error_default
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let bad_request_handler_const = match (&args.bad_request_handler, args.from_error) {
+        (Some(_), _) | (None, true) => quote!(
             const BAD_REQUEST_HANDLER: bool = true;
         ),
-        None => quote!(
+        (None, false) => quote!(
             const BAD_REQUEST_HANDLER: bool = false;
         ),
     };
-    let bad_request_handler = args.bad_request_handler.as_ref().map(|path| {
-        quote! {
+    let bad_request_handler = match &args.bad_request_handler {
+        Some(path) => Some(quote! {
             fn from_parse_request_error(err: #crate_name::__private::poem::Error) -> Self {
                 #path(err)
             }
-        }
-    });
+        }),
+        None if args.from_error => Some(quote! {
+            fn from_parse_request_error(err: #crate_name::__private::poem::Error) -> Self {
+                ::std::convert::From::from(err)
+            }
+        }),
+        None => None,
+    };
     let error_msg = if args.display {
         quote! {
             let error_msg = ::std::option::Option::Some(::std::string::ToString::to_string(&resp));
@@ -423,6 +584,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 #bad_request_handler
             }
 
+            #from_error
+
             impl #impl_generics ::std::convert::From<#ident #ty_generics> for #crate_name::__private::poem::Error #where_clause {
                 fn from(resp: #ident #ty_generics) -> #crate_name::__private::poem::Error {
                     use #crate_name::__private::poem::IntoResponse;
@@ -459,15 +622,22 @@ fn get_status(span: Span, status: &Option<LitOrPath<u16>>) -> GeneratorResult<To
     }
 }
 
-fn get_status_range(span: Span, status_range: &Option<String>) -> GeneratorResult<TokenStream> {
+fn get_status_range(
+    span: Span,
+    default: bool,
+    status_range: &Option<String>,
+) -> GeneratorResult<TokenStream> {
+    if default {
+        return Ok(quote!(::std::option::Option::None));
+    }
     let status_range = status_range
         .as_ref()
         .ok_or_else(|| Error::new(span, "Missing status range attribute"))?;
 
     match status_range.as_str() {
-        "1XX" | "2XX" | "3XX" | "4XX" | "5XX" => {
-            Ok(quote!(<_ as ::std::convert::Into<String>>::into(#status_range)))
-        }
+        "1XX" | "2XX" | "3XX" | "4XX" | "5XX" => Ok(
+            quote!(::std::option::Option::Some(<_ as ::std::convert::Into<String>>::into(#status_range))),
+        ),
         _ => Err(Error::new(
             span,
             "Invalid status range, you may only use 1XX, 2XX, 3XX, 4XX, or 5XX.",
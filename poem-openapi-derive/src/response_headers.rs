@@ -0,0 +1,103 @@
+use darling::{FromDeriveInput, FromField, ast::Data, util::Ignored};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Attribute, DeriveInput, Error, Generics, Type};
+
+use crate::{
+    error::GeneratorResult,
+    utils::{get_crate_name, get_description, optional_literal_string},
+};
+
+#[derive(FromField)]
+#[darling(attributes(oai), forward_attrs(doc))]
+struct HeaderField {
+    ident: Option<Ident>,
+    ty: Type,
+    attrs: Vec<Attribute>,
+
+    #[darling(default)]
+    name: Option<String>,
+    #[darling(default)]
+    deprecated: bool,
+}
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(oai))]
+struct ResponseHeadersArgs {
+    ident: Ident,
+    generics: Generics,
+    data: Data<Ignored, HeaderField>,
+
+    #[darling(default)]
+    internal: bool,
+}
+
+pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
+    let args: ResponseHeadersArgs = ResponseHeadersArgs::from_derive_input(&args)?;
+    let crate_name = get_crate_name(args.internal);
+    let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
+    let ident = &args.ident;
+    let s = match &args.data {
+        Data::Struct(s) => s,
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "ResponseHeaders can only be applied to an struct.",
+            )
+            .into());
+        }
+    };
+
+    let mut meta_headers = Vec::new();
+    let mut register_types = Vec::new();
+    let mut insert_headers = Vec::new();
+
+    for field in &s.fields {
+        let field_ident = field
+            .ident
+            .as_ref()
+            .ok_or_else(|| Error::new_spanned(ident, "All fields must be named."))?;
+        let field_ty = &field.ty;
+        let name = field
+            .name
+            .clone()
+            .unwrap_or_else(|| field_ident.to_string().replace('_', "-"));
+        let header_name = name.to_lowercase();
+        let description = optional_literal_string(&get_description(&field.attrs)?);
+        let deprecated = field.deprecated;
+
+        meta_headers.push(quote! {
+            #crate_name::registry::MetaHeader {
+                name: ::std::string::ToString::to_string(#name),
+                description: #description,
+                required: <#field_ty as #crate_name::types::Type>::IS_REQUIRED,
+                deprecated: #deprecated,
+                schema: <#field_ty as #crate_name::types::Type>::schema_ref(),
+            }
+        });
+        register_types.push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
+        insert_headers.push(quote! {
+            if let ::std::option::Option::Some(value) = #crate_name::types::ToHeader::to_header(&self.#field_ident) {
+                headers.insert(#header_name, value);
+            }
+        });
+    }
+
+    let expanded = quote! {
+        impl #impl_generics #crate_name::ResponseHeaders for #ident #ty_generics #where_clause {
+            fn meta() -> ::std::vec::Vec<#crate_name::registry::MetaHeader> {
+                ::std::vec![#(#meta_headers),*]
+            }
+
+            fn register(registry: &mut #crate_name::registry::Registry) {
+                #(#register_types)*
+            }
+
+            fn insert_into(&self, headers: &mut #crate_name::__private::poem::http::HeaderMap) {
+                #(#insert_headers)*
+            }
+        }
+    };
+
+    Ok(expanded)
+}
@@ -9,6 +9,7 @@ use quote::quote;
 use syn::{Attribute, DeriveInput, Error, Path};
 
 use crate::{
+    common_args::Extension,
     error::GeneratorResult,
     utils::{get_crate_name, get_description, optional_literal},
 };
@@ -185,6 +186,32 @@ pub(crate) enum ApiKeyInType {
     Cookie,
 }
 
+impl ApiKeyInType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyInType::Query => "query",
+            ApiKeyInType::Header => "header",
+            ApiKeyInType::Cookie => "cookie",
+        }
+    }
+
+    fn param_in(&self, crate_name: &TokenStream) -> TokenStream {
+        match self {
+            ApiKeyInType::Query => quote!(#crate_name::registry::MetaParamIn::Query),
+            ApiKeyInType::Header => quote!(#crate_name::registry::MetaParamIn::Header),
+            ApiKeyInType::Cookie => quote!(#crate_name::registry::MetaParamIn::Cookie),
+        }
+    }
+}
+
+#[derive(FromMeta)]
+struct KeyLocation {
+    key_in: ApiKeyInType,
+    key_name: SpannedValue<String>,
+    #[darling(default)]
+    rename: Option<String>,
+}
+
 #[derive(FromVariant)]
 #[darling(attributes(oai), forward_attrs(doc))]
 struct SecuritySchemeItem {
@@ -211,6 +238,8 @@ struct SecuritySchemeArgs {
     key_in: Option<ApiKeyInType>,
     #[darling(default)]
     key_name: Option<SpannedValue<String>>,
+    #[darling(default, multiple, rename = "key_location")]
+    key_locations: Vec<KeyLocation>,
     #[darling(default)]
     bearer_format: Option<String>,
     #[darling(default)]
@@ -218,7 +247,15 @@ struct SecuritySchemeArgs {
     #[darling(default)]
     openid_connect_url: Option<String>,
     #[darling(default)]
+    verify_jwt: bool,
+    #[darling(default)]
+    audience: Option<String>,
+    #[darling(default, multiple, rename = "required_scope")]
+    required_scopes: Vec<String>,
+    #[darling(default)]
     checker: Option<Path>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
 }
 
 impl SecuritySchemeArgs {
@@ -230,6 +267,32 @@ impl SecuritySchemeArgs {
     }
 
     fn validate(&self) -> GeneratorResult<()> {
+        if self.auth_type()? != AuthType::OpenIdConnect
+            && (self.verify_jwt || self.audience.is_some() || !self.required_scopes.is_empty())
+        {
+            return Err(Error::new_spanned(
+                &self.ident,
+                r#"JWT validation is only supported by the `openid_connect` auth type."#,
+            )
+            .into());
+        }
+
+        if self.auth_type()? != AuthType::ApiKey && !self.key_locations.is_empty() {
+            return Err(Error::new_spanned(
+                &self.ident,
+                r#"`key_location` is only supported by the `api_key` auth type."#,
+            )
+            .into());
+        }
+
+        if !self.verify_jwt && (self.audience.is_some() || !self.required_scopes.is_empty()) {
+            return Err(Error::new_spanned(
+                &self.ident,
+                r#"The `audience` and `required_scope` attributes require `verify_jwt`."#,
+            )
+            .into());
+        }
+
         match self.auth_type()? {
             AuthType::ApiKey => self.validate_api_key(),
             AuthType::OAuth2 => self.validate_oauth2(),
@@ -239,15 +302,22 @@ impl SecuritySchemeArgs {
     }
 
     fn validate_api_key(&self) -> GeneratorResult<()> {
-        match &self.key_name {
-            Some(name) => {
-                HeaderName::try_from(&**name).map_err(|_| {
-                    Error::new(
-                        name.span(),
-                        format!("`{}` is not a valid header name.", &**name),
-                    )
-                })?;
+        if !self.key_locations.is_empty() {
+            if self.key_name.is_some() || self.key_in.is_some() {
+                return Err(Error::new_spanned(
+                    &self.ident,
+                    r#"`key_location` cannot be used with `key_name` or `key_in`."#,
+                )
+                .into());
             }
+            for location in &self.key_locations {
+                validate_key_name(&location.key_name)?;
+            }
+            return Ok(());
+        }
+
+        match &self.key_name {
+            Some(name) => validate_key_name(name)?,
             None => {
                 return Err(Error::new_spanned(
                     &self.ident,
@@ -268,6 +338,27 @@ impl SecuritySchemeArgs {
         Ok(())
     }
 
+    /// Returns the names of the security schemes of the key locations with
+    /// their locations, in priority order.
+    fn key_locations(&self, name: &str) -> GeneratorResult<Vec<(String, &KeyLocation)>> {
+        let mut locations: Vec<(String, &KeyLocation)> = Vec::new();
+        for location in &self.key_locations {
+            let scheme_name = location
+                .rename
+                .clone()
+                .unwrap_or_else(|| format!("{}_{}", name, location.key_in.as_str()));
+            if locations.iter().any(|(other, _)| *other == scheme_name) {
+                return Err(Error::new(
+                    location.key_name.span(),
+                    format!("Duplicate security scheme name `{scheme_name}`, use `rename` to specify another name."),
+                )
+                .into());
+            }
+            locations.push((scheme_name, location));
+        }
+        Ok(locations)
+    }
+
     fn validate_oauth2(&self) -> GeneratorResult<()> {
         match &self.flows {
             Some(flows) => flows.validate(flows.span())?,
@@ -311,9 +402,10 @@ impl SecuritySchemeArgs {
             None => quote!(::std::option::Option::None),
         };
         let key_in = match &self.key_in {
-            Some(ApiKeyInType::Query) => quote!(::std::option::Option::Some("query")),
-            Some(ApiKeyInType::Header) => quote!(::std::option::Option::Some("header")),
-            Some(ApiKeyInType::Cookie) => quote!(::std::option::Option::Some("cookie")),
+            Some(key_in) => {
+                let key_in = key_in.as_str();
+                quote!(::std::option::Option::Some(#key_in))
+            }
             None => quote!(::std::option::Option::None),
         };
         let bearer_format = match &self.bearer_format {
@@ -324,8 +416,30 @@ impl SecuritySchemeArgs {
             Some(openid_connect_url) => quote!(::std::option::Option::Some(#openid_connect_url)),
             None => quote!(::std::option::Option::None),
         };
+        let extensions = Extension::to_token_stream(&self.extensions, crate_name)?;
 
         let ts = match self.auth_type()? {
+            AuthType::ApiKey if !self.key_locations.is_empty() => {
+                let mut registers = Vec::new();
+                for (scheme_name, location) in self.key_locations(name)? {
+                    let key_name = location.key_name.as_str();
+                    let key_in = location.key_in.as_str();
+                    registers.push(quote! {
+                        registry.create_security_scheme(#scheme_name, #crate_name::registry::MetaSecurityScheme {
+                            ty: "apiKey",
+                            description: #description,
+                            name: ::std::option::Option::Some(#key_name),
+                            key_in: ::std::option::Option::Some(#key_in),
+                            scheme: ::std::option::Option::None,
+                            bearer_format: ::std::option::Option::None,
+                            flows: ::std::option::Option::None,
+                            openid_connect_url: ::std::option::Option::None,
+                            extensions: #extensions,
+                        });
+                    });
+                }
+                quote!(#(#registers)*)
+            }
             AuthType::ApiKey => {
                 quote! {
                     registry.create_security_scheme(#name, #crate_name::registry::MetaSecurityScheme {
@@ -337,6 +451,7 @@ impl SecuritySchemeArgs {
                         bearer_format: ::std::option::Option::None,
                         flows: ::std::option::Option::None,
                         openid_connect_url: ::std::option::Option::None,
+                        extensions: #extensions,
                     });
                 }
             }
@@ -351,6 +466,7 @@ impl SecuritySchemeArgs {
                         bearer_format: #bearer_format,
                         flows: ::std::option::Option::None,
                         openid_connect_url: ::std::option::Option::None,
+                        extensions: #extensions,
                     });
                 }
             }
@@ -365,6 +481,7 @@ impl SecuritySchemeArgs {
                         bearer_format: #bearer_format,
                         flows: ::std::option::Option::None,
                         openid_connect_url: ::std::option::Option::None,
+                        extensions: #extensions,
                     });
                 }
             }
@@ -380,6 +497,7 @@ impl SecuritySchemeArgs {
                         bearer_format: ::std::option::Option::None,
                         flows: ::std::option::Option::Some(#flows),
                         openid_connect_url: ::std::option::Option::None,
+                        extensions: #extensions,
                     });
                 }
             }
@@ -394,6 +512,7 @@ impl SecuritySchemeArgs {
                         bearer_format: ::std::option::Option::None,
                         flows: ::std::option::Option::None,
                         openid_connect_url: #openid_connect_url,
+                        extensions: #extensions,
                     });
                 }
             }
@@ -403,13 +522,28 @@ impl SecuritySchemeArgs {
 
     fn generate_from_request(&self, crate_name: &TokenStream) -> GeneratorResult<TokenStream> {
         match self.auth_type()? {
+            AuthType::ApiKey if !self.key_locations.is_empty() => {
+                // the locations are tried in order and the first key found is used
+                let key_names = self
+                    .key_locations
+                    .iter()
+                    .map(|location| location.key_name.as_str());
+                let params_in = self
+                    .key_locations
+                    .iter()
+                    .map(|location| location.key_in.param_in(crate_name));
+                Ok(quote! {
+                    [#((#key_names, #params_in)),*]
+                        .into_iter()
+                        .find_map(|(name, param_in)| {
+                            <#crate_name::auth::ApiKey as #crate_name::auth::ApiKeyAuthorization>::from_request(req, query, name, param_in).ok()
+                        })
+                        .ok_or_else(|| #crate_name::__private::poem::Error::from(#crate_name::error::AuthorizationError))
+                })
+            }
             AuthType::ApiKey => {
                 let key_name = self.key_name.as_ref().unwrap().as_str();
-                let param_in = match self.key_in.as_ref().unwrap() {
-                    ApiKeyInType::Query => quote!(#crate_name::registry::MetaParamIn::Query),
-                    ApiKeyInType::Header => quote!(#crate_name::registry::MetaParamIn::Header),
-                    ApiKeyInType::Cookie => quote!(#crate_name::registry::MetaParamIn::Cookie),
-                };
+                let param_in = self.key_in.as_ref().unwrap().param_in(crate_name);
                 Ok(
                     quote!(<#crate_name::auth::ApiKey as #crate_name::auth::ApiKeyAuthorization>::from_request(req, query, #key_name, #param_in)),
                 )
@@ -423,6 +557,26 @@ impl SecuritySchemeArgs {
             AuthType::OAuth2 => Ok(
                 quote!(<#crate_name::auth::Bearer as #crate_name::auth::BearerAuthorization>::from_request(req)),
             ),
+            AuthType::OpenIdConnect if self.verify_jwt => {
+                let openid_connect_url = self.openid_connect_url.as_ref().unwrap();
+                let audience = self
+                    .audience
+                    .as_ref()
+                    .map(|audience| quote!(.audience(#audience)));
+                let required_scopes = &self.required_scopes;
+                Ok(quote! {
+                    (async {
+                        static VERIFIER: ::std::sync::LazyLock<#crate_name::auth::OpenIdConnectVerifier> =
+                            ::std::sync::LazyLock::new(|| {
+                                #crate_name::auth::OpenIdConnectVerifier::new(#openid_connect_url)
+                                    #audience
+                                    #(.required_scope(#required_scopes))*
+                            });
+                        let bearer = <#crate_name::auth::Bearer as #crate_name::auth::BearerAuthorization>::from_request(req)?;
+                        VERIFIER.verify(&bearer.token).await
+                    }).await
+                })
+            }
             AuthType::OpenIdConnect => Ok(
                 quote!(<#crate_name::auth::Bearer as #crate_name::auth::BearerAuthorization>::from_request(req)),
             ),
@@ -436,6 +590,64 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let ident = &args.ident;
 
     match &args.data {
+        Data::Struct(fields) if args.ty.is_none() && fields.style == Style::Tuple => {
+            // all the security schemes of the fields must be satisfied
+            if fields.fields.is_empty() {
+                return Err(Error::new_spanned(ident, "At least one field is required.").into());
+            }
+            if args.checker.is_some() {
+                return Err(Error::new_spanned(
+                    ident,
+                    "The checker cannot be used without an auth type.",
+                )
+                .into());
+            }
+
+            let field_types = &fields.fields;
+            let expanded = quote! {
+                impl<'a> #crate_name::ApiExtractor<'a> for #ident {
+                    const TYPES: &'static [#crate_name::ApiExtractorType] = &[#crate_name::ApiExtractorType::SecurityScheme];
+
+                    type ParamType = ();
+                    type ParamRawType = ();
+
+                    fn register(registry: &mut #crate_name::registry::Registry) {
+                        #(<#field_types as #crate_name::ApiExtractor>::register(registry);)*
+                    }
+
+                    fn security_schemes() -> ::std::vec::Vec<&'static str> {
+                        let mut security_schemes = ::std::vec![];
+                        #(security_schemes.extend(<#field_types as #crate_name::ApiExtractor>::security_schemes());)*
+                        security_schemes
+                    }
+
+                    fn has_security_fallback() -> bool {
+                        <Self as #crate_name::ApiExtractor>::security_requirements().iter().any(::std::vec::Vec::is_empty)
+                    }
+
+                    fn security_requirements() -> ::std::vec::Vec<::std::vec::Vec<&'static str>> {
+                        let requirements = ::std::vec![::std::vec![]];
+                        #(let requirements = #crate_name::__private::and_security_requirements(
+                            requirements,
+                            <#field_types as #crate_name::ApiExtractor>::security_requirements(),
+                        );)*
+                        requirements
+                    }
+
+                    async fn from_request(
+                        req: &'a #crate_name::__private::poem::Request,
+                        body: &mut #crate_name::__private::poem::RequestBody,
+                        param_opts: #crate_name::ExtractParamOptions<Self::ParamType>,
+                    ) -> #crate_name::__private::poem::Result<Self> {
+                        ::std::result::Result::Ok(Self(
+                            #(<#field_types as #crate_name::ApiExtractor>::from_request(req, body, ::std::clone::Clone::clone(&param_opts)).await?,)*
+                        ))
+                    }
+                }
+            };
+
+            Ok(expanded)
+        }
         Data::Struct(fields) => {
             let oai_typename = args.rename.clone().unwrap_or_else(|| ident.to_string());
 
@@ -448,6 +660,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             let register_security_scheme =
                 args.generate_register_security_scheme(&crate_name, &oai_typename)?;
             let from_request = args.generate_from_request(&crate_name)?;
+            let security_schemes = match args.key_locations.is_empty() {
+                true => vec![oai_typename.clone()],
+                false => args
+                    .key_locations(&oai_typename)?
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect(),
+            };
             let path = args.checker.as_ref();
 
             let output = match path {
@@ -471,7 +691,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     }
 
                     fn security_schemes() -> ::std::vec::Vec<&'static str> {
-                        ::std::vec![#oai_typename]
+                        ::std::vec![#(#security_schemes),*]
                     }
 
                     async fn from_request(
@@ -495,6 +715,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
             let mut registers = Vec::new();
             let mut security_schemes = Vec::new();
+            let mut security_requirements = Vec::new();
             let mut from_requests = Vec::new();
             let mut has_fallback = false;
 
@@ -523,6 +744,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 security_schemes.push(quote! {
                     security_schemes.extend(<#item_type as #crate_name::ApiExtractor>::security_schemes());
                 });
+                security_requirements.push(quote! {
+                    requirements.extend(<#item_type as #crate_name::ApiExtractor>::security_requirements());
+                });
                 from_requests.push(quote! {
                     match <#item_type as #crate_name::ApiExtractor>::from_request(req, body, param_opts.clone()).await {
                         ::std::result::Result::Ok(item) => return Ok(#ident::#item_ident(item)),
@@ -566,6 +790,15 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         #has_fallback
                     }
 
+                    fn security_requirements() -> ::std::vec::Vec<::std::vec::Vec<&'static str>> {
+                        let mut requirements = ::std::vec![];
+                        #(#security_requirements)*
+                        if #has_fallback {
+                            requirements.push(::std::vec![]);
+                        }
+                        requirements
+                    }
+
                     async fn from_request(
                         req: &'a #crate_name::__private::poem::Request,
                         body: &mut #crate_name::__private::poem::RequestBody,
@@ -582,3 +815,13 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     }
 }
+
+fn validate_key_name(name: &SpannedValue<String>) -> GeneratorResult<()> {
+    HeaderName::try_from(name.as_str()).map_err(|_| {
+        Error::new(
+            name.span(),
+            format!("`{}` is not a valid header name.", &**name),
+        )
+    })?;
+    Ok(())
+}
@@ -8,7 +8,7 @@ use quote::quote;
 use syn::{Attribute, DeriveInput, Error, ext::IdentExt};
 
 use crate::{
-    common_args::{ExternalDocument, RenameRule, apply_rename_rule_variant},
+    common_args::{Extension, ExternalDocument, RenameRule, apply_rename_rule_variant},
     error::GeneratorResult,
     utils::{get_crate_name, get_description, optional_literal},
 };
@@ -24,6 +24,8 @@ struct TagItem {
     rename: Option<String>,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
 }
 
 #[derive(FromDeriveInput)]
@@ -76,11 +78,13 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
             None => quote!(::std::option::Option::None),
         };
+        let extensions = Extension::to_token_stream(&variant.extensions, &crate_name)?;
 
         meta_items.push(quote!(#crate_name::registry::MetaTag {
             name: #oai_item_name,
             description: #description,
             external_docs: #external_docs,
+            extensions: #extensions,
         }));
         to_names.push(quote!(Self::#item_ident => #oai_item_name));
     }
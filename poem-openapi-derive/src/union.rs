@@ -8,7 +8,7 @@ use quote::quote;
 use syn::{Attribute, DeriveInput, Error, Generics, Type, ext::IdentExt};
 
 use crate::{
-    common_args::{ExternalDocument, RenameRule, apply_rename_rule_variant},
+    common_args::{Extension, ExternalDocument, RenameRule, apply_rename_rule_variant},
     error::GeneratorResult,
     utils::{create_object_name, get_crate_name, get_description, optional_literal},
 };
@@ -40,9 +40,13 @@ struct UnionArgs {
     #[darling(default)]
     discriminator_name: Option<String>,
     #[darling(default)]
+    content: Option<String>,
+    #[darling(default)]
     externally_tagged: bool,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
     #[darling(default)]
     rename_all: Option<RenameRule>,
 }
@@ -69,6 +73,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         .into());
     }
 
+    if args.content.is_some() && discriminator_name.is_none() {
+        return Err(Error::new_spanned(
+            ident,
+            "Content name can only be used with a discriminator name.",
+        )
+        .into());
+    }
+
     let mut types = Vec::new();
     let mut from_json = Vec::new();
     let mut to_json = Vec::new();
@@ -80,7 +92,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         Some(discriminator_name) => quote!(::std::vec![#discriminator_name]),
         None => quote!(::std::vec![]),
     };
-    let object_name = create_object_name(&crate_name, &oai_typename, &args.generics);
+    let object_name = create_object_name(&crate_name, &oai_typename, &args.generics)?;
 
     for variant in e {
         let item_ident = &variant.ident;
@@ -108,6 +120,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         .map_err(#crate_name::types::ParseError::propagate);
                 }
             });
+        } else if let Some(content) = &args.content {
+            from_json.push(quote! {
+                if ::std::matches!(discriminator_name, ::std::option::Option::Some(discriminator_name) if discriminator_name == #mapping_name) {
+                    return <#object_ty as #crate_name::types::ParseFromJSON>::parse_from_json(value.as_object().and_then(|obj| obj.get(#content)).cloned())
+                        .map(Self::#item_ident)
+                        .map_err(#crate_name::types::ParseError::propagate);
+                }
+            });
         } else if discriminator_name.is_some() {
             from_json.push(quote! {
                 if ::std::matches!(discriminator_name, ::std::option::Option::Some(discriminator_name) if discriminator_name == #mapping_name) {
@@ -146,6 +166,19 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(wrapped))
                 }
             });
+        } else if let (Some(discriminator_name), Some(content)) =
+            (&discriminator_name, &args.content)
+        {
+            to_json.push(quote! {
+                Self::#item_ident(obj) => {
+                    let mut wrapped = #crate_name::__private::serde_json::Map::new();
+                    wrapped.insert(::std::convert::Into::into(#discriminator_name), ::std::convert::Into::into(#mapping_name));
+                    if let ::std::option::Option::Some(value) = <#object_ty as #crate_name::types::ToJSON>::to_json(obj) {
+                        wrapped.insert(::std::convert::Into::into(#content), value);
+                    }
+                    ::std::option::Option::Some(#crate_name::__private::serde_json::Value::Object(wrapped))
+                }
+            });
         } else if let Some(discriminator_name) = &discriminator_name {
             to_json.push(quote! {
                 Self::#item_ident(obj) => {
@@ -192,6 +225,45 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 registry.schemas.insert(#schema_name, schema);
             });
 
+            schemas.push(quote! {
+                #crate_name::registry::MetaSchemaRef::Reference(#schema_name)
+            });
+        } else if let (Some(discriminator_name), Some(content)) =
+            (&args.discriminator_name, &args.content)
+        {
+            create_schemas.push(quote! {
+                let schema = #crate_name::registry::MetaSchema {
+                    description: #description,
+                    required: {
+                        let mut required = ::std::vec![#discriminator_name];
+                        if <#object_ty as #crate_name::types::Type>::IS_REQUIRED {
+                            required.push(#content);
+                        }
+                        required
+                    },
+                    properties: ::std::vec![
+                        (
+                            #discriminator_name,
+                            #crate_name::registry::MetaSchemaRef::Inline(::std::boxed::Box::new(
+                                #crate_name::registry::MetaSchema {
+                                    ty: "string",
+                                    enum_items: ::std::vec![::std::convert::Into::into(#mapping_name)],
+                                    example: ::std::option::Option::Some(::std::convert::Into::into(#mapping_name)),
+                                    ..#crate_name::registry::MetaSchema::ANY
+                                }
+                            )),
+                        ),
+                        (
+                            #content,
+                            <#object_ty as #crate_name::types::Type>::schema_ref(),
+                        ),
+                    ],
+                    ..#crate_name::registry::MetaSchema::new("object")
+                };
+
+                registry.schemas.insert(#schema_name, schema);
+            });
+
             schemas.push(quote! {
                 #crate_name::registry::MetaSchemaRef::Reference(#schema_name)
             });
@@ -276,6 +348,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         },
     };
 
+    let extensions = Extension::to_token_stream(&args.extensions, &crate_name)?;
     let external_docs = match &args.external_docs {
         Some(external_docs) => {
             let s = external_docs.to_token_stream(&crate_name);
@@ -301,6 +374,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             ty: "object",
             description: #description,
             external_docs: #external_docs,
+            extensions: #extensions,
             one_of: #one_of,
             any_of: #any_of,
             discriminator: #discriminator,
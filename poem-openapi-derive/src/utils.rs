@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use darling::{FromMeta, util::SpannedValue};
 use proc_macro_crate::{FoundCrate, crate_name};
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 use quote::quote;
 use syn::{
     Attribute, Error, Expr, ExprLit, GenericParam, Generics, Lifetime, Lit, Meta, Result,
@@ -168,11 +168,15 @@ impl VisitMut for RemoveLifetime {
     }
 }
 
+/// Creates the expression of the schema name of a type.
+///
+/// The names of the generic types are appended to `name`, unless it contains
+/// `{T}` placeholders that specify where they are inserted.
 pub(crate) fn create_object_name(
     crate_name: &TokenStream,
     name: &str,
     generics: &Generics,
-) -> TokenStream {
+) -> Result<TokenStream> {
     let types = generics
         .params
         .iter()
@@ -182,14 +186,38 @@ pub(crate) fn create_object_name(
         })
         .collect::<Vec<_>>();
 
+    if name.contains('{') {
+        let mut parts = Vec::new();
+        let mut s = name;
+        while let Some(start) = s.find('{') {
+            let literal = &s[..start];
+            parts.push(quote!(name.push_str(#literal);));
+            let end = s[start..].find('}').map(|end| start + end).ok_or_else(|| {
+                Error::new_spanned(generics, format!("Invalid name template `{name}`."))
+            })?;
+            let param = s[start + 1..end].trim();
+            let ty = types.iter().find(|ty| **ty == param).ok_or_else(|| {
+                Error::new_spanned(generics, format!("Unknown type parameter `{param}`."))
+            })?;
+            parts.push(quote!(name.push_str(&<#ty as #crate_name::types::Type>::name());));
+            s = &s[end + 1..];
+        }
+        parts.push(quote!(name.push_str(#s);));
+        return Ok(quote!({
+            let mut name = ::std::string::String::new();
+            #(#parts)*
+            name
+        }));
+    }
+
     if types.is_empty() {
-        quote!({
+        Ok(quote!({
             use ::std::convert::From;
             ::std::string::String::from(#name)
-        })
+        }))
     } else {
         let (first, tail) = types.split_first().unwrap();
-        quote!({
+        Ok(quote!({
             use ::std::convert::From;
             let mut name = ::std::string::String::from(#name);
 
@@ -201,10 +229,19 @@ pub(crate) fn create_object_name(
             )*
 
             name
-        })
+        }))
     }
 }
 
+/// Returns `true` if `ident` appears in `tokens`.
+pub(crate) fn contains_ident(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(i) => &i == ident,
+        TokenTree::Group(group) => contains_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
 pub(crate) fn preserve_str_literal(meta: &Meta) -> darling::Result<Option<Expr>> {
     match meta {
         Meta::Path(_) => Err(darling::Error::unsupported_format("path").with_span(meta)),
@@ -2,7 +2,7 @@ use darling::{FromMeta, util::SpannedValue};
 use proc_macro2::TokenStream;
 use quote::quote;
 use regex::Regex;
-use syn::{Error, Expr, Type};
+use syn::{Error, Expr, Path, Type};
 
 use crate::{
     common_args::{MaximumValidator, MinimumValidator},
@@ -13,6 +13,7 @@ struct ValidatorsTokenStream {
     container_validators: Vec<TokenStream>,
     elem_validators: Vec<TokenStream>,
     custom_validators: Vec<TokenStream>,
+    fn_validators: Vec<TokenStream>,
 }
 
 #[derive(FromMeta, Default, Clone)]
@@ -46,6 +47,9 @@ pub(crate) struct Validators {
     // custom validators for elements
     #[darling(default, multiple)]
     custom: Vec<SpannedValue<String>>,
+    // functions returning `Result<(), impl Display>`
+    #[darling(default, multiple)]
+    custom_fn: Vec<SpannedValue<String>>,
 }
 
 impl Validators {
@@ -56,6 +60,7 @@ impl Validators {
         let mut container_validators = Vec::new();
         let mut elem_validators = Vec::new();
         let mut custom_validators = Vec::new();
+        let mut fn_validators = Vec::new();
 
         //////////////////////////////////////////////////////////////////////////////
         // element validators
@@ -117,6 +122,12 @@ impl Validators {
             custom_validators.push(quote!(#create_custom_validator));
         }
 
+        for custom_fn in &self.custom_fn {
+            let path: Path = syn::parse_str(custom_fn)
+                .map_err(|err| Error::new(custom_fn.span(), err.to_string()))?;
+            fn_validators.push(quote!(#path));
+        }
+
         //////////////////////////////////////////////////////////////////////////////
         // container validators
         //////////////////////////////////////////////////////////////////////////////
@@ -154,6 +165,7 @@ impl Validators {
             container_validators,
             elem_validators,
             custom_validators,
+            fn_validators,
         })
     }
 
@@ -166,10 +178,19 @@ impl Validators {
             container_validators,
             elem_validators,
             custom_validators,
+            fn_validators,
         } = self.create_validators(crate_name)?;
         let elem_validators = elem_validators.into_iter().chain(custom_validators);
 
         Ok(quote! {
+            #(
+            if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_raw_value(&value) {
+                if let ::std::result::Result::Err(err) = #fn_validators(value) {
+                    return Err(#crate_name::types::ParseError::<Self>::custom(format!("field `{}` verification failed. {}", #field_name, err)));
+                }
+            }
+            )*
+
             #(
             for elem in #crate_name::types::Type::raw_element_iter(&value) {
                 let validator = #elem_validators;
@@ -200,10 +221,30 @@ impl Validators {
             container_validators,
             elem_validators,
             custom_validators,
+            fn_validators,
         } = self.create_validators(crate_name)?;
         let elem_validators = elem_validators.into_iter().chain(custom_validators);
 
         Ok(Some(quote! {
+            #(
+            if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_raw_value(&value) {
+                if let ::std::result::Result::Err(err) = #fn_validators(value) {
+                    let err = #crate_name::error::ParseParamError {
+                        name: #arg_name,
+                        reason: ::std::format!("verification failed. {}", err),
+                    };
+
+                    if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER {
+                        let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(std::convert::Into::into(err));
+                        let res = #crate_name::__private::poem::error::IntoResult::into_result(res);
+                        return ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response);
+                    } else {
+                        return ::std::result::Result::Err(std::convert::Into::into(err));
+                    }
+                }
+            }
+            )*
+
             #(
             if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_raw_value(&value) {
                 let validator = #container_validators;
@@ -255,10 +296,21 @@ impl Validators {
             container_validators,
             elem_validators,
             custom_validators,
+            fn_validators,
         } = self.create_validators(crate_name)?;
         let elem_validators = elem_validators.into_iter().chain(custom_validators);
 
         Ok(quote! {
+            #(
+            if let ::std::option::Option::Some(value) = #crate_name::types::Type::as_raw_value(&value) {
+                if let ::std::result::Result::Err(err) = #fn_validators(value) {
+                    return Err(::std::convert::Into::into(#crate_name::error::ParseMultipartError {
+                        reason: ::std::format!("field `{}` verification failed. {}", #field_name, err),
+                    }));
+                }
+            }
+            )*
+
             #(
             for item in #crate_name::types::Type::raw_element_iter(&value) {
                 let validator = #elem_validators;
@@ -234,7 +234,9 @@ fn generate_operation(
                     required: <#arg_ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED,
                     deprecated: #deprecated,
                     explode: #explode,
-                    style: ::std::option::Option::None
+                    style: ::std::option::Option::None,
+                    examples: ::std::vec![],
+                    extensions: ::std::collections::BTreeMap::new(),
                 };
                 params.push(meta_param);
             }
@@ -287,10 +289,15 @@ fn generate_operation(
                             request
                         },
                         responses: <#res_ty as #crate_name::ApiResponse>::meta(),
+                        callbacks: ::std::vec![],
                         deprecated: #deprecated,
                         security: ::std::vec![],
                         operation_id: #operation_id,
                         code_samples: ::std::vec![],
+                        rate_limit: ::std::option::Option::None,
+                        max_request_size: ::std::option::Option::None,
+                        versions: ::std::vec![],
+                        extensions: ::std::collections::BTreeMap::new(),
                     }
                 }
             },
@@ -41,7 +41,7 @@ impl ApiKeyAuthorization for ApiKey {
                     key: cookie.value_str().to_string(),
                 })
                 .ok_or_else(|| AuthorizationError.into()),
-            _ => unreachable!(),
+            _ => Err(AuthorizationError.into()),
         }
     }
 }
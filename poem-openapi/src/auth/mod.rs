@@ -3,9 +3,14 @@
 mod api_key;
 mod basic;
 mod bearer;
+#[cfg(feature = "openid-connect")]
+mod openid_connect;
 
 use poem::{Request, Result};
 
+#[cfg(feature = "openid-connect")]
+#[cfg_attr(docsrs, doc(cfg(feature = "openid-connect")))]
+pub use self::openid_connect::OpenIdConnectVerifier;
 pub use self::{api_key::ApiKey, basic::Basic, bearer::Bearer};
 use crate::{base::UrlQuery, error::AuthorizationError, registry::MetaParamIn};
 
@@ -32,6 +37,34 @@ pub trait ApiKeyAuthorization: Sized {
     ) -> Result<Self>;
 }
 
+/// Combines two lists of security requirements so that a requirement of each
+/// list must be satisfied.
+#[doc(hidden)]
+pub fn and_security_requirements<T: Clone + PartialEq>(
+    a: Vec<Vec<T>>,
+    b: Vec<Vec<T>>,
+) -> Vec<Vec<T>> {
+    let mut requirements: Vec<Vec<T>> = Vec::new();
+    for x in &a {
+        for y in &b {
+            let mut requirement = x.clone();
+            for item in y {
+                if !requirement.contains(item) {
+                    requirement.push(item.clone());
+                }
+            }
+            let exists = requirements.iter().any(|other| {
+                other.len() == requirement.len()
+                    && other.iter().all(|item| requirement.contains(item))
+            });
+            if !exists {
+                requirements.push(requirement);
+            }
+        }
+    }
+    requirements
+}
+
 /// Facilitates the conversion of `Option` into `Results`, for `SecuritySchema`
 /// checker.
 #[doc(hidden)]
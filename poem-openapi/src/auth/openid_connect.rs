@@ -0,0 +1,265 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation,
+    jwk::{JwkSet, KeyAlgorithm},
+};
+use poem::Result;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::error::OpenIdConnectError;
+
+/// The minimum interval between two refreshes of the key set, which
+/// prevents tokens with unknown key ids from flooding the identity provider.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The path of the discovery document, relative to the issuer URL.
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+
+#[derive(Deserialize)]
+struct DiscoveryDocument {
+    issuer: String,
+    jwks_uri: String,
+}
+
+struct Cache {
+    issuer: String,
+    jwks_uri: String,
+    jwks: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Validates JSON Web Tokens issued by an OpenID Connect provider.
+///
+/// The discovery document and the JSON Web Key Set of the provider are
+/// fetched on first use and cached. The key set is fetched again when a
+/// token is signed with an unknown key, so that key rotations are picked up.
+///
+/// The issuer of the discovery document must be the issuer URL, which is the
+/// discovery URL without the `/.well-known/openid-configuration` suffix
+/// unless it is set with [`OpenIdConnectVerifier::issuer`].
+///
+/// A token is accepted if its signature is valid, it is not expired, its
+/// issuer is the one of the discovery document, its audience matches (if
+/// configured) and it grants all the required scopes (read from the `scope`
+/// or `scp` claim).
+///
+/// This is used by `#[derive(SecurityScheme)]` when `verify_jwt` is
+/// specified, but can also be used directly.
+pub struct OpenIdConnectVerifier {
+    discovery_url: String,
+    issuer: Option<String>,
+    audience: Option<String>,
+    required_scopes: Vec<String>,
+    client: reqwest::Client,
+    cache: Mutex<Option<Cache>>,
+}
+
+impl OpenIdConnectVerifier {
+    /// Create a verifier with the URL of the discovery document of the
+    /// provider.
+    pub fn new(discovery_url: impl Into<String>) -> Self {
+        Self {
+            discovery_url: discovery_url.into(),
+            issuer: None,
+            audience: None,
+            required_scopes: Vec::new(),
+            client: reqwest::Client::new(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Sets the expected issuer of the discovery document.
+    #[must_use]
+    pub fn issuer(self, issuer: impl Into<String>) -> Self {
+        Self {
+            issuer: Some(issuer.into()),
+            ..self
+        }
+    }
+
+    /// Sets the expected audience of the tokens.
+    #[must_use]
+    pub fn audience(self, audience: impl Into<String>) -> Self {
+        Self {
+            audience: Some(audience.into()),
+            ..self
+        }
+    }
+
+    /// Appends a scope that the tokens must grant.
+    #[must_use]
+    pub fn required_scope(mut self, scope: impl Into<String>) -> Self {
+        self.required_scopes.push(scope.into());
+        self
+    }
+
+    /// Sets the HTTP client used to fetch the discovery document and the key
+    /// set.
+    #[must_use]
+    pub fn http_client(self, client: reqwest::Client) -> Self {
+        Self { client, ..self }
+    }
+
+    /// Validates the token and returns its claims.
+    pub async fn verify<C: DeserializeOwned>(&self, token: &str) -> Result<C> {
+        let header = jsonwebtoken::decode_header(token)
+            .map_err(|err| OpenIdConnectError::InvalidToken(err.to_string()))?;
+
+        let (issuer, key) = self.find_key(header.kid.as_deref(), header.alg).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[issuer]);
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let claims = jsonwebtoken::decode::<Value>(token, &key, &validation)
+            .map_err(|err| OpenIdConnectError::InvalidToken(err.to_string()))?
+            .claims;
+        self.check_scopes(&claims)?;
+        serde_json::from_value(claims)
+            .map_err(|err| OpenIdConnectError::InvalidToken(err.to_string()).into())
+    }
+
+    fn check_scopes(&self, claims: &Value) -> Result<(), OpenIdConnectError> {
+        let granted: Vec<&str> = match claims.get("scope").or_else(|| claims.get("scp")) {
+            Some(Value::String(scopes)) => scopes.split_whitespace().collect(),
+            Some(Value::Array(scopes)) => scopes.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        match self
+            .required_scopes
+            .iter()
+            .find(|scope| !granted.contains(&scope.as_str()))
+        {
+            Some(scope) => Err(OpenIdConnectError::InsufficientScope(scope.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the issuer and the key with the specified id, refreshing the
+    /// key set if the key is unknown.
+    async fn find_key(
+        &self,
+        kid: Option<&str>,
+        alg: Algorithm,
+    ) -> Result<(String, DecodingKey), OpenIdConnectError> {
+        let cached = {
+            let cache = self.cache.lock().unwrap();
+            match &*cache {
+                Some(cache) => {
+                    if let Some(key) = Self::lookup(&cache.jwks, kid, alg)? {
+                        return Ok((cache.issuer.clone(), key));
+                    }
+                    if cache.fetched_at.elapsed() < MIN_REFRESH_INTERVAL {
+                        return Err(OpenIdConnectError::InvalidToken(
+                            "unknown signing key".into(),
+                        ));
+                    }
+                    Some((cache.issuer.clone(), cache.jwks_uri.clone()))
+                }
+                None => None,
+            }
+        };
+
+        let (issuer, jwks_uri) = match cached {
+            Some(cached) => cached,
+            None => {
+                let discovery: DiscoveryDocument = self.fetch(&self.discovery_url).await?;
+                let expected = self.issuer.as_deref().unwrap_or_else(|| {
+                    self.discovery_url
+                        .strip_suffix(DISCOVERY_PATH)
+                        .unwrap_or(&self.discovery_url)
+                });
+                if discovery.issuer != expected {
+                    return Err(OpenIdConnectError::Fetch(format!(
+                        "the issuer `{}` of the discovery document is not `{expected}`",
+                        discovery.issuer
+                    )));
+                }
+                (discovery.issuer, discovery.jwks_uri)
+            }
+        };
+        let jwks: JwkSet = self.fetch(&jwks_uri).await?;
+        let key = Self::lookup(&jwks, kid, alg)?;
+
+        *self.cache.lock().unwrap() = Some(Cache {
+            issuer: issuer.clone(),
+            jwks_uri,
+            jwks,
+            fetched_at: Instant::now(),
+        });
+        key.map(|key| (issuer, key))
+            .ok_or_else(|| OpenIdConnectError::InvalidToken("unknown signing key".into()))
+    }
+
+    fn lookup(
+        jwks: &JwkSet,
+        kid: Option<&str>,
+        alg: Algorithm,
+    ) -> Result<Option<DecodingKey>, OpenIdConnectError> {
+        let jwk = match kid {
+            Some(kid) => jwks.find(kid),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        };
+        let Some(jwk) = jwk else {
+            return Ok(None);
+        };
+
+        // the token must not be able to choose another algorithm than the one of
+        // the key
+        if let Some(key_alg) = jwk.common.key_algorithm {
+            if signature_algorithm(key_alg) != Some(alg) {
+                return Err(OpenIdConnectError::InvalidToken(format!(
+                    "the key requires the `{key_alg}` algorithm"
+                )));
+            }
+        }
+
+        DecodingKey::from_jwk(jwk)
+            .map(Some)
+            .map_err(|err| OpenIdConnectError::InvalidToken(err.to_string()))
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, url: &str) -> Result<T, OpenIdConnectError> {
+        let fetch = async {
+            self.client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        };
+        fetch
+            .await
+            .map_err(|err: reqwest::Error| OpenIdConnectError::Fetch(err.to_string()))
+    }
+}
+
+/// Returns the signature algorithm of a key algorithm, the encryption
+/// algorithms have none.
+fn signature_algorithm(alg: KeyAlgorithm) -> Option<Algorithm> {
+    match alg {
+        KeyAlgorithm::HS256 => Some(Algorithm::HS256),
+        KeyAlgorithm::HS384 => Some(Algorithm::HS384),
+        KeyAlgorithm::HS512 => Some(Algorithm::HS512),
+        KeyAlgorithm::ES256 => Some(Algorithm::ES256),
+        KeyAlgorithm::ES384 => Some(Algorithm::ES384),
+        KeyAlgorithm::RS256 => Some(Algorithm::RS256),
+        KeyAlgorithm::RS384 => Some(Algorithm::RS384),
+        KeyAlgorithm::RS512 => Some(Algorithm::RS512),
+        KeyAlgorithm::PS256 => Some(Algorithm::PS256),
+        KeyAlgorithm::PS384 => Some(Algorithm::PS384),
+        KeyAlgorithm::PS512 => Some(Algorithm::PS512),
+        KeyAlgorithm::EdDSA => Some(Algorithm::EdDSA),
+        KeyAlgorithm::RSA1_5 | KeyAlgorithm::RSA_OAEP | KeyAlgorithm::RSA_OAEP_256 => None,
+    }
+}
@@ -6,13 +6,17 @@ use std::{
 };
 
 use futures_util::FutureExt;
-use poem::{Error, FromRequest, Request, RequestBody, Result, endpoint::BoxEndpoint, http::Method};
+use poem::{
+    Error, FromRequest, Request, RequestBody, Result,
+    endpoint::BoxEndpoint,
+    http::{HeaderMap, Method},
+};
 use serde::Serialize;
 
 use crate::{
     payload::Payload,
     registry::{
-        MetaApi, MetaMediaType, MetaOAuthScope, MetaParamIn, MetaRequest, MetaResponse,
+        MetaApi, MetaHeader, MetaMediaType, MetaOAuthScope, MetaParamIn, MetaRequest, MetaResponse,
         MetaResponses, MetaSchemaRef, MetaWebhook, Registry,
     },
 };
@@ -55,6 +59,19 @@ pub enum ApiExtractorType {
     PoemExtractor,
 }
 
+/// Returns `true` if the content type of a request is the expected one,
+/// ignoring the parameters and the case.
+#[doc(hidden)]
+pub fn content_type_is(content_type: &str, expected: &str) -> bool {
+    match (
+        content_type.parse::<mime::Mime>(),
+        expected.parse::<mime::Mime>(),
+    ) {
+        (Ok(content_type), Ok(expected)) => content_type.essence_str() == expected.essence_str(),
+        _ => content_type.eq_ignore_ascii_case(expected),
+    }
+}
+
 #[doc(hidden)]
 #[derive(Clone)]
 pub struct UrlQuery(pub Vec<(String, String)>);
@@ -223,6 +240,24 @@ pub trait ApiExtractor<'a>: Sized {
         false
     }
 
+    /// Returns the security requirements if this extractor is security
+    /// scheme.
+    ///
+    /// The request is authorized if any of the requirements is satisfied, and
+    /// a requirement is satisfied if all of its security schemes are
+    /// satisfied. An empty requirement means that the authentication is
+    /// optional.
+    fn security_requirements() -> Vec<Vec<&'static str>> {
+        let mut requirements = Self::security_schemes()
+            .into_iter()
+            .map(|name| vec![name])
+            .collect::<Vec<_>>();
+        if Self::has_security_fallback() {
+            requirements.push(vec![]);
+        }
+        requirements
+    }
+
     /// Returns the location of the parameter if this extractor is parameter.
     fn param_in() -> Option<MetaParamIn> {
         None
@@ -283,6 +318,8 @@ impl<T: Payload> ResponseContent for T {
         vec![MetaMediaType {
             content_type: T::CONTENT_TYPE,
             schema: T::schema_ref(),
+            example: None,
+            examples: vec![],
         }]
     }
 
@@ -316,6 +353,10 @@ impl<T: Payload> ResponseContent for T {
 ///
 ///  A response type use it to modify the status code and HTTP headers.
 ///
+/// - **WithHeaders&lt;T: ApiResponse, H: ResponseHeaders>**
+///
+///  A response type wrapper that adds typed headers to a response.
+///
 /// - **()**
 ///
 ///  It means that this API does not have any response body.
@@ -354,6 +395,7 @@ impl ApiResponse for () {
                 status_range: None,
                 content: vec![],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
@@ -376,7 +418,7 @@ where
     T: ApiResponse,
     E: ApiResponse + Into<Error> + Send + Sync + 'static,
 {
-    const BAD_REQUEST_HANDLER: bool = T::BAD_REQUEST_HANDLER;
+    const BAD_REQUEST_HANDLER: bool = T::BAD_REQUEST_HANDLER || E::BAD_REQUEST_HANDLER;
 
     fn meta() -> MetaResponses {
         let mut meta = T::meta();
@@ -390,7 +432,10 @@ where
     }
 
     fn from_parse_request_error(err: Error) -> Self {
-        Ok(T::from_parse_request_error(err))
+        match T::BAD_REQUEST_HANDLER {
+            true => Ok(T::from_parse_request_error(err)),
+            false => Err(E::from_parse_request_error(err)),
+        }
     }
 }
 
@@ -408,6 +453,7 @@ where
                 status_range: None,
                 content: vec![],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
@@ -415,6 +461,24 @@ where
     fn register(_registry: &mut Registry) {}
 }
 
+/// Represents a set of typed response headers.
+///
+/// Use it with [`WithHeaders`](crate::payload::WithHeaders) to add the
+/// headers to any response.
+///
+/// Reference: <https://spec.openapis.org/oas/v3.1.0#header-object>
+pub trait ResponseHeaders: Send {
+    /// Gets metadata of the headers.
+    fn meta() -> Vec<MetaHeader>;
+
+    /// Register the schema contained in the headers to the registry.
+    #[allow(unused_variables)]
+    fn register(registry: &mut Registry) {}
+
+    /// Insert the headers into a header map.
+    fn insert_into(&self, headers: &mut HeaderMap);
+}
+
 /// Represents an OpenAPI tags.
 pub trait Tags {
     /// Register this tag type to registry.
@@ -0,0 +1,450 @@
+//! Generation of the client types from the specification.
+//!
+//! The TypeScript declarations of the schemas and of the operations can be
+//! generated at runtime with [`OpenApiService::typescript`], or in a build
+//! script from a specification file with [`typescript`].
+//!
+//! The operations are declared as a `Paths` interface, which maps the paths
+//! and the methods to their parameters, request body and responses.
+//!
+//! The schemas, the paths, the responses and the properties are written in
+//! alphabetical order, so the output does not depend on the order of the
+//! keys in the specification.
+//!
+//! # Examples
+//!
+//! ```
+//! use poem_openapi::{Object, OpenApi, OpenApiService, param::Path, payload::Json};
+//!
+//! #[derive(Object)]
+//! struct User {
+//!     id: i64,
+//!     name: Option<String>,
+//! }
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/users/:id", method = "get")]
+//!     async fn user(&self, id: Path<i64>) -> Json<User> {
+//!         todo!()
+//!     }
+//! }
+//!
+//! let ts = OpenApiService::new(Api, "Demo", "1.0").typescript();
+//! assert!(ts.contains("export interface User {\n  id: number;\n  name?: string;\n}"));
+//! ```
+//!
+//! [`OpenApiService::typescript`]: crate::OpenApiService::typescript
+
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+const PARAM_LOCATIONS: &[&str] = &["path", "query", "header", "cookie"];
+
+/// Generates the TypeScript declarations of the schemas and of the
+/// operations of a specification.
+pub fn typescript(spec: &Value) -> String {
+    let mut out = String::new();
+
+    if let Some(schemas) = spec["components"]["schemas"].as_object() {
+        for (name, schema) in sorted(schemas) {
+            write_doc(&mut out, schema, "");
+            let name = type_name(name);
+            match is_interface(schema) {
+                true => {
+                    let _ = writeln!(out, "export interface {name} {}\n", ts_type(schema, ""));
+                }
+                false => {
+                    let _ = writeln!(out, "export type {name} = {};\n", ts_type(schema, ""));
+                }
+            }
+        }
+    }
+
+    out.push_str("export interface Paths {\n");
+    if let Some(paths) = spec["paths"].as_object() {
+        for (path, item) in sorted(paths) {
+            let _ = writeln!(out, "  {}: {{", property_name(path));
+            for method in METHODS {
+                if let Some(operation) = item.get(method) {
+                    write_doc(&mut out, operation, "    ");
+                    let _ = writeln!(out, "    {method}: {{");
+                    write_operation(&mut out, item, operation);
+                    out.push_str("    };\n");
+                }
+            }
+            out.push_str("  };\n");
+        }
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+fn write_operation(out: &mut String, item: &Value, operation: &Value) {
+    let params = item["parameters"]
+        .as_array()
+        .into_iter()
+        .chain(operation["parameters"].as_array())
+        .flatten()
+        .collect::<Vec<_>>();
+    out.push_str("      parameters: {\n");
+    for location in PARAM_LOCATIONS {
+        let params = params
+            .iter()
+            .filter(|param| param["in"] == *location)
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "        {location}: {{");
+        for param in params {
+            let name = param["name"].as_str().unwrap_or_default();
+            let optional = if param["required"] == true { "" } else { "?" };
+            write_doc(out, param, "          ");
+            let _ = writeln!(
+                out,
+                "          {}{optional}: {};",
+                property_name(name),
+                ts_type(&param["schema"], "          ")
+            );
+        }
+        out.push_str("        };\n");
+    }
+    out.push_str("      };\n");
+
+    if let Some(body) = operation.get("requestBody") {
+        let optional = if body["required"] == true { "" } else { "?" };
+        let _ = writeln!(
+            out,
+            "      requestBody{optional}: {};",
+            content_type(body, "      ")
+        );
+    }
+
+    out.push_str("      responses: {\n");
+    if let Some(responses) = operation["responses"].as_object() {
+        for (status, response) in sorted(responses) {
+            let _ = writeln!(
+                out,
+                "        {}: {};",
+                property_name(status),
+                content_type(response, "        ")
+            );
+        }
+    }
+    out.push_str("      };\n");
+}
+
+/// Returns the type of the first content type of a request or a response.
+fn content_type(value: &Value, indent: &str) -> String {
+    value["content"]
+        .as_object()
+        .and_then(|content| sorted(content).into_iter().next())
+        .map(|(_, media)| media)
+        .map(|media| ts_type(&media["schema"], indent))
+        .unwrap_or_else(|| "void".to_string())
+}
+
+/// Returns the entries of an object sorted by key.
+fn sorted(map: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn is_interface(schema: &Value) -> bool {
+    schema["type"] == "object"
+        && schema.get("properties").is_some()
+        && schema.get("additionalProperties").is_none()
+}
+
+fn write_doc(out: &mut String, value: &Value, indent: &str) {
+    let doc = value
+        .get("description")
+        .or_else(|| value.get("summary"))
+        .and_then(Value::as_str);
+    if let Some(doc) = doc {
+        let _ = writeln!(out, "{indent}/**");
+        for line in doc.lines() {
+            let _ = writeln!(out, "{indent} * {}", line.replace("*/", "*\\/"));
+        }
+        let _ = writeln!(out, "{indent} */");
+    }
+}
+
+/// Converts the name of a schema into a TypeScript identifier.
+fn type_name(name: &str) -> String {
+    name.chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect()
+}
+
+fn property_name(name: &str) -> String {
+    let is_ident = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    match is_ident {
+        true => name.to_string(),
+        false => Value::String(name.to_string()).to_string(),
+    }
+}
+
+fn union(types: Vec<String>) -> String {
+    let mut items = Vec::new();
+    for ty in types {
+        if !items.contains(&ty) {
+            items.push(ty);
+        }
+    }
+    match items.is_empty() {
+        true => "never".to_string(),
+        false => items.join(" | "),
+    }
+}
+
+fn ts_type(schema: &Value, indent: &str) -> String {
+    let ty = ts_type_non_null(schema, indent);
+    match schema["nullable"] == true {
+        true => format!("{ty} | null"),
+        false => ty,
+    }
+}
+
+fn ts_type_non_null(schema: &Value, indent: &str) -> String {
+    if let Some(reference) = schema["$ref"].as_str() {
+        return type_name(reference.rsplit('/').next().unwrap_or_default());
+    }
+    if let Some(values) = schema["enum"].as_array() {
+        return union(values.iter().map(Value::to_string).collect());
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(schemas) = schema[key].as_array() {
+            return union(
+                schemas
+                    .iter()
+                    .map(|schema| ts_type(schema, indent))
+                    .collect(),
+            );
+        }
+    }
+    if let Some(schemas) = schema["allOf"].as_array() {
+        return schemas
+            .iter()
+            .map(
+                |schema| match schema.get("oneOf").or_else(|| schema.get("anyOf")) {
+                    Some(_) => format!("({})", ts_type(schema, indent)),
+                    None => ts_type(schema, indent),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(" & ");
+    }
+
+    match &schema["type"] {
+        Value::Array(types) => union(
+            types
+                .iter()
+                .map(|ty| {
+                    let mut schema = schema.clone();
+                    schema["type"] = ty.clone();
+                    ts_type_non_null(&schema, indent)
+                })
+                .collect(),
+        ),
+        Value::String(ty) => match ty.as_str() {
+            "null" => "null".to_string(),
+            "boolean" => "boolean".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "string" => match schema["format"] == "binary" {
+                true => "Blob".to_string(),
+                false => "string".to_string(),
+            },
+            "array" => {
+                let item = ts_type(&schema["items"], indent);
+                match item.contains(' ') {
+                    true => format!("({item})[]"),
+                    false => format!("{item}[]"),
+                }
+            }
+            "object" => object_type(schema, indent),
+            _ => "unknown".to_string(),
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+fn object_type(schema: &Value, indent: &str) -> String {
+    let properties = schema["properties"].as_object();
+    let additional = match &schema["additionalProperties"] {
+        Value::Null | Value::Bool(false) => None,
+        Value::Bool(true) => Some("unknown".to_string()),
+        schema => Some(ts_type(schema, indent)),
+    };
+    let (properties, additional) = match (properties, additional) {
+        (None, None) => return "Record<string, unknown>".to_string(),
+        (None, Some(additional)) => return format!("Record<string, {additional}>"),
+        (Some(properties), additional) => (properties, additional),
+    };
+
+    let inner = format!("{indent}  ");
+    let required = schema["required"].as_array();
+    let mut out = String::from("{\n");
+    for (name, schema) in sorted(properties) {
+        let is_required =
+            required.is_some_and(|required| required.contains(&Value::from(name.as_str())));
+        let optional = if is_required { "" } else { "?" };
+        write_doc(&mut out, schema, &inner);
+        let _ = writeln!(
+            out,
+            "{inner}{}{optional}: {};",
+            property_name(name),
+            ts_type(schema, &inner)
+        );
+    }
+    out.push_str(indent);
+    out.push('}');
+    // an index signature would have to match the types of all the properties
+    if let Some(additional) = additional {
+        let _ = write!(out, " & Record<string, {additional}>");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn schemas() {
+        let spec = json!({
+            "components": {
+                "schemas": {
+                    "Color": { "type": "string", "enum": ["red", "green"] },
+                    "Pet": {
+                        "type": "object",
+                        "description": "A pet",
+                        "required": ["id", "color"],
+                        "properties": {
+                            "id": { "type": "integer", "format": "int64" },
+                            "color": { "$ref": "#/components/schemas/Color" },
+                            "tags": { "type": "array", "items": { "type": "string" } },
+                            "owner-name": { "type": "string", "nullable": true },
+                            "extra": {
+                                "type": "object",
+                                "additionalProperties": { "type": "number" }
+                            },
+                        },
+                    },
+                    "Shape": {
+                        "oneOf": [
+                            { "$ref": "#/components/schemas/Pet" },
+                            { "type": "boolean" },
+                        ],
+                    },
+                },
+            },
+        });
+
+        assert_eq!(
+            typescript(&spec),
+            r#"export type Color = "red" | "green";
+
+/**
+ * A pet
+ */
+export interface Pet {
+  color: Color;
+  extra?: Record<string, number>;
+  id: number;
+  "owner-name"?: string | null;
+  tags?: string[];
+}
+
+export type Shape = Pet | boolean;
+
+export interface Paths {
+}
+"#
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let spec = json!({
+            "paths": {
+                "/pets/{id}": {
+                    "put": {
+                        "summary": "Update a pet",
+                        "parameters": [
+                            { "name": "id", "in": "path", "required": true, "schema": { "type": "integer" } },
+                            { "name": "dry_run", "in": "query", "schema": { "type": "boolean" } },
+                        ],
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+                            },
+                        },
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } }
+                                },
+                            },
+                            "404": {},
+                        },
+                    },
+                },
+            },
+        });
+
+        assert_eq!(
+            typescript(&spec),
+            r#"export interface Paths {
+  "/pets/{id}": {
+    /**
+     * Update a pet
+     */
+    put: {
+      parameters: {
+        path: {
+          id: number;
+        };
+        query: {
+          dry_run?: boolean;
+        };
+      };
+      requestBody: Pet;
+      responses: {
+        "200": Pet;
+        "404": void;
+      };
+    };
+  };
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,551 @@
+//! Detection of the breaking changes between two versions of a specification.
+//!
+//! This is intended to be used in tests, to prevent accidental API breakage
+//! between releases.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use poem_openapi::{OpenApi, OpenApiService, diff};
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {}
+//!
+//! let old = serde_json::from_str(&std::fs::read_to_string("spec-v1.json").unwrap()).unwrap();
+//! let new = serde_json::from_str(&OpenApiService::new(Api, "Demo", "1.1").spec()).unwrap();
+//! let changes = diff::breaking_changes(&old, &new);
+//! assert!(changes.is_empty(), "breaking changes: {changes:#?}");
+//! ```
+
+use std::collections::HashSet;
+
+use derive_more::Display;
+use serde_json::{Map, Value};
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// A breaking change between two versions of a specification.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[display("{location}: {kind}")]
+pub struct BreakingChange {
+    /// The location of the change, e.g. `GET /pets/{id} response 200
+    /// application/json .name`.
+    pub location: String,
+
+    /// The kind of the change.
+    pub kind: BreakingChangeKind,
+}
+
+/// The kind of a breaking change.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum BreakingChangeKind {
+    /// The operation was removed.
+    #[display("the operation was removed")]
+    OperationRemoved,
+
+    /// A required parameter was added.
+    #[display("the required parameter `{_0}` was added")]
+    RequiredParameterAdded(String),
+
+    /// An optional parameter became required.
+    #[display("the parameter `{_0}` became required")]
+    ParameterBecameRequired(String),
+
+    /// The request body became required.
+    #[display("the request body became required")]
+    RequestBodyBecameRequired,
+
+    /// A content type of the request body is no longer accepted.
+    #[display("the content type `{_0}` is no longer accepted")]
+    RequestContentTypeRemoved(String),
+
+    /// A response was removed.
+    #[display("the response `{_0}` was removed")]
+    ResponseRemoved(String),
+
+    /// A content type of a response is no longer returned.
+    #[display("the content type `{_0}` is no longer returned")]
+    ResponseContentTypeRemoved(String),
+
+    /// The type of a value changed.
+    #[display("the type changed from `{old}` to `{new}`")]
+    TypeChanged {
+        /// The old type.
+        old: String,
+        /// The new type.
+        new: String,
+    },
+
+    /// A value of an enumeration is no longer accepted.
+    #[display("the enum value `{_0}` was removed")]
+    EnumValueRemoved(String),
+
+    /// A value of an enumeration can be returned.
+    #[display("the enum value `{_0}` was added")]
+    EnumValueAdded(String),
+
+    /// A required property was added to a request.
+    #[display("the required property `{_0}` was added")]
+    RequiredPropertyAdded(String),
+
+    /// A property was removed from a response.
+    #[display("the property `{_0}` was removed")]
+    PropertyRemoved(String),
+
+    /// A required property of a response became optional.
+    #[display("the property `{_0}` became optional")]
+    PropertyBecameOptional(String),
+
+    /// A validation constraint of a request rejects more values.
+    #[display("the `{_0}` constraint was narrowed")]
+    ConstraintNarrowed(&'static str),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Direction {
+    Request,
+    Response,
+}
+
+struct Context<'a> {
+    old: &'a Value,
+    new: &'a Value,
+    changes: Vec<BreakingChange>,
+    visited: HashSet<(String, String, Direction)>,
+}
+
+/// Returns the breaking changes from the `old` to the `new` specification,
+/// which are OpenAPI documents in JSON.
+///
+/// A change is breaking if a request accepted by the old version can be
+/// rejected by the new version, or if a response of the new version can be
+/// unexpected for a client of the old version.
+///
+/// The changes are sorted by location.
+pub fn breaking_changes(old: &Value, new: &Value) -> Vec<BreakingChange> {
+    let mut ctx = Context {
+        old,
+        new,
+        changes: Vec::new(),
+        visited: HashSet::new(),
+    };
+
+    let new_paths = new
+        .get("paths")
+        .and_then(Value::as_object)
+        .map(|paths| {
+            paths
+                .iter()
+                .map(|(path, item)| (normalize_path(path), item))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    for (path, old_item) in old
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let new_item = new_paths
+            .iter()
+            .find(|(new_path, _)| *new_path == normalize_path(path))
+            .map(|(_, item)| *item);
+        for method in METHODS {
+            let Some(old_operation) = old_item.get(method) else {
+                continue;
+            };
+            let location = format!("{} {}", method.to_uppercase(), path);
+            match new_item.and_then(|item| item.get(method)) {
+                Some(new_operation) => ctx.diff_operation(&location, old_operation, new_operation),
+                None => ctx.push(&location, BreakingChangeKind::OperationRemoved),
+            }
+        }
+    }
+
+    let mut changes = ctx.changes;
+    changes.sort_by(|a, b| a.location.cmp(&b.location));
+    changes
+}
+
+/// Replaces the names of the path parameters, which can be renamed without
+/// breaking the clients.
+fn normalize_path(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+    let mut in_param = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                normalized.push_str("{}");
+            }
+            '}' => in_param = false,
+            _ if !in_param => normalized.push(c),
+            _ => {}
+        }
+    }
+    normalized
+}
+
+fn is_required(value: &Value) -> bool {
+    value
+        .get("required")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+impl Context<'_> {
+    fn push(&mut self, location: &str, kind: BreakingChangeKind) {
+        self.changes.push(BreakingChange {
+            location: location.to_string(),
+            kind,
+        });
+    }
+
+    fn diff_operation(&mut self, location: &str, old: &Value, new: &Value) {
+        self.diff_params(location, old, new);
+
+        // request body
+        match (old.get("requestBody"), new.get("requestBody")) {
+            (Some(old_body), Some(new_body)) => {
+                if !is_required(old_body) && is_required(new_body) {
+                    self.push(location, BreakingChangeKind::RequestBodyBecameRequired);
+                }
+                self.diff_content(
+                    &format!("{location} request body"),
+                    old_body,
+                    new_body,
+                    Direction::Request,
+                );
+            }
+            (None, Some(new_body)) if is_required(new_body) => {
+                self.push(location, BreakingChangeKind::RequestBodyBecameRequired);
+            }
+            _ => {}
+        }
+
+        // responses
+        let empty = Map::new();
+        let old_responses = old
+            .get("responses")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_responses = new
+            .get("responses")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        for (status, old_response) in old_responses {
+            match new_responses.get(status) {
+                Some(new_response) => self.diff_content(
+                    &format!("{location} response {status}"),
+                    old_response,
+                    new_response,
+                    Direction::Response,
+                ),
+                None => self.push(
+                    location,
+                    BreakingChangeKind::ResponseRemoved(status.clone()),
+                ),
+            }
+        }
+    }
+
+    fn diff_params(&mut self, location: &str, old: &Value, new: &Value) {
+        let params = |operation: &Value| {
+            operation
+                .get("parameters")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let old_params = params(old);
+        for new_param in params(new) {
+            let name = value_to_string(&new_param["name"]);
+            let old_param = old_params.iter().find(|old_param| {
+                old_param["name"] == new_param["name"] && old_param["in"] == new_param["in"]
+            });
+            match old_param {
+                Some(old_param) => {
+                    if !is_required(old_param) && is_required(&new_param) {
+                        self.push(
+                            location,
+                            BreakingChangeKind::ParameterBecameRequired(name.clone()),
+                        );
+                    }
+                    if let (Some(old_schema), Some(new_schema)) =
+                        (old_param.get("schema"), new_param.get("schema"))
+                    {
+                        self.diff_schema(
+                            &format!("{location} parameter `{name}`"),
+                            old_schema,
+                            new_schema,
+                            Direction::Request,
+                        );
+                    }
+                }
+                None if is_required(&new_param) && new_param["in"] != "path" => {
+                    self.push(location, BreakingChangeKind::RequiredParameterAdded(name));
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn diff_content(&mut self, location: &str, old: &Value, new: &Value, direction: Direction) {
+        let empty = Map::new();
+        let old_content = old
+            .get("content")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_content = new
+            .get("content")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        for (content_type, old_media) in old_content {
+            match new_content.get(content_type) {
+                Some(new_media) => {
+                    if let (Some(old_schema), Some(new_schema)) =
+                        (old_media.get("schema"), new_media.get("schema"))
+                    {
+                        self.diff_schema(
+                            &format!("{location} {content_type}"),
+                            old_schema,
+                            new_schema,
+                            direction,
+                        );
+                    }
+                }
+                None => self.push(
+                    location,
+                    match direction {
+                        Direction::Request => {
+                            BreakingChangeKind::RequestContentTypeRemoved(content_type.clone())
+                        }
+                        Direction::Response => {
+                            BreakingChangeKind::ResponseContentTypeRemoved(content_type.clone())
+                        }
+                    },
+                ),
+            }
+        }
+    }
+
+    fn diff_schema(&mut self, location: &str, old: &Value, new: &Value, direction: Direction) {
+        let old_ref = old.get("$ref").and_then(Value::as_str);
+        let new_ref = new.get("$ref").and_then(Value::as_str);
+        if old_ref.is_some() || new_ref.is_some() {
+            // the schemas are only compared once, which handles the recursive schemas
+            let key = (
+                old_ref.unwrap_or_default().to_string(),
+                new_ref.unwrap_or_default().to_string(),
+                direction,
+            );
+            if !self.visited.insert(key) {
+                return;
+            }
+        }
+        let old = resolve(self.old, old);
+        let new = resolve(self.new, new);
+
+        // types
+        let old_types = types(old);
+        let new_types = types(new);
+        let narrowed = match direction {
+            Direction::Request => !is_subset(old_types.as_deref(), new_types.as_deref()),
+            Direction::Response => !is_subset(new_types.as_deref(), old_types.as_deref()),
+        };
+        if narrowed {
+            let to_string = |types: Option<Vec<&str>>| match types {
+                Some(types) => types.join(" | "),
+                None => "any".to_string(),
+            };
+            self.push(
+                location,
+                BreakingChangeKind::TypeChanged {
+                    old: to_string(old_types),
+                    new: to_string(new_types),
+                },
+            );
+            return;
+        }
+
+        // enums
+        if let (Some(old_values), Some(new_values)) = (
+            old.get("enum").and_then(Value::as_array),
+            new.get("enum").and_then(Value::as_array),
+        ) {
+            match direction {
+                Direction::Request => {
+                    for value in old_values
+                        .iter()
+                        .filter(|value| !new_values.contains(value))
+                    {
+                        self.push(
+                            location,
+                            BreakingChangeKind::EnumValueRemoved(value_to_string(value)),
+                        );
+                    }
+                }
+                Direction::Response => {
+                    for value in new_values
+                        .iter()
+                        .filter(|value| !old_values.contains(value))
+                    {
+                        self.push(
+                            location,
+                            BreakingChangeKind::EnumValueAdded(value_to_string(value)),
+                        );
+                    }
+                }
+            }
+        }
+
+        if direction == Direction::Request {
+            self.diff_constraints(location, old, new);
+        }
+
+        // properties
+        let empty = Map::new();
+        let old_properties = old
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_properties = new
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let required = |schema: &Value, name: &str| {
+            schema
+                .get("required")
+                .and_then(Value::as_array)
+                .is_some_and(|required| required.iter().any(|item| item == name))
+        };
+        match direction {
+            Direction::Request => {
+                for name in new_properties.keys() {
+                    if required(new, name) && !required(old, name) {
+                        self.push(
+                            location,
+                            BreakingChangeKind::RequiredPropertyAdded(name.clone()),
+                        );
+                    }
+                }
+            }
+            Direction::Response => {
+                for name in old_properties.keys() {
+                    if !new_properties.contains_key(name) {
+                        self.push(location, BreakingChangeKind::PropertyRemoved(name.clone()));
+                    } else if required(old, name) && !required(new, name) {
+                        self.push(
+                            location,
+                            BreakingChangeKind::PropertyBecameOptional(name.clone()),
+                        );
+                    }
+                }
+            }
+        }
+        for (name, old_property) in old_properties {
+            if let Some(new_property) = new_properties.get(name) {
+                self.diff_schema(
+                    &format!("{location} .{name}"),
+                    old_property,
+                    new_property,
+                    direction,
+                );
+            }
+        }
+
+        // items
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            self.diff_schema(&format!("{location} []"), old_items, new_items, direction);
+        }
+    }
+
+    fn diff_constraints(&mut self, location: &str, old: &Value, new: &Value) {
+        for name in [
+            "maximum",
+            "exclusiveMaximum",
+            "maxLength",
+            "maxItems",
+            "maxProperties",
+        ] {
+            let old_value = old.get(name).and_then(Value::as_f64);
+            let new_value = new.get(name).and_then(Value::as_f64);
+            if let Some(new_value) = new_value {
+                if old_value.is_none_or(|old_value| new_value < old_value) {
+                    self.push(location, BreakingChangeKind::ConstraintNarrowed(name));
+                }
+            }
+        }
+        for name in [
+            "minimum",
+            "exclusiveMinimum",
+            "minLength",
+            "minItems",
+            "minProperties",
+        ] {
+            let old_value = old.get(name).and_then(Value::as_f64);
+            let new_value = new.get(name).and_then(Value::as_f64);
+            if let Some(new_value) = new_value {
+                if old_value.is_none_or(|old_value| new_value > old_value) {
+                    self.push(location, BreakingChangeKind::ConstraintNarrowed(name));
+                }
+            }
+        }
+        if let Some(pattern) = new.get("pattern") {
+            if old.get("pattern") != Some(pattern) {
+                self.push(location, BreakingChangeKind::ConstraintNarrowed("pattern"));
+            }
+        }
+    }
+}
+
+/// Resolves a reference to a schema of the components.
+fn resolve<'a>(document: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/components/schemas/"))
+    {
+        Some(name) => document["components"]["schemas"]
+            .get(name)
+            .unwrap_or(&Value::Null),
+        None => schema,
+    }
+}
+
+/// Returns the types of a schema, or `None` if it accepts any type.
+fn types(schema: &Value) -> Option<Vec<&str>> {
+    let mut types = match schema.get("type")? {
+        Value::String(ty) => vec![ty.as_str()],
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        _ => return None,
+    };
+    if schema.get("nullable") == Some(&Value::Bool(true)) && !types.contains(&"null") {
+        types.push("null");
+    }
+    Some(types)
+}
+
+/// Returns `true` if all the values of the types `a` are values of the types
+/// `b`.
+fn is_subset(a: Option<&[&str]>, b: Option<&[&str]>) -> bool {
+    match (a, b) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(a), Some(b)) => a
+            .iter()
+            .all(|ty| b.contains(ty) || (*ty == "integer" && b.contains(&"number"))),
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use futures_util::{FutureExt, future::BoxFuture};
+use poem::{
+    Endpoint, Error, IntoResponse, Request, RequestBody, Response, Result,
+    endpoint::ShutdownHooks,
+    http::{StatusCode, header},
+    web::headers::{Authorization, HeaderMapExt, authorization::Basic},
+};
+
+use crate::{ApiExtractor, ExtractParamOptions, base::UrlQuery, error::AuthorizationError};
+
+type CheckFn = dyn for<'a> Fn(&'a Request) -> BoxFuture<'a, Result<()>> + Send + Sync;
+
+/// Restricts the access to the specification and documentation UI
+/// endpoints.
+///
+/// # Examples
+///
+/// ```
+/// use poem_openapi::{DocsGuard, OpenApiService};
+///
+/// struct Api;
+///
+/// #[poem_openapi::OpenApi]
+/// impl Api {}
+///
+/// let api_service =
+///     OpenApiService::new(Api, "Demo", "1.0").docs_guard(DocsGuard::basic("admin", "123456"));
+/// let spec = api_service.spec_endpoint();
+/// ```
+#[derive(Clone)]
+pub struct DocsGuard {
+    check: Arc<CheckFn>,
+}
+
+impl DocsGuard {
+    /// Requires the HTTP basic authentication with the specified
+    /// credentials.
+    ///
+    /// The browser prompts for the credentials when the documentation is
+    /// opened.
+    pub fn basic(username: impl Into<String>, password: impl Into<String>) -> Self {
+        let username = username.into();
+        let password = password.into();
+        Self {
+            check: Arc::new(move |req| {
+                let authorized = req
+                    .headers()
+                    .typed_get::<Authorization<Basic>>()
+                    .is_some_and(|auth| auth.username() == username && auth.password() == password);
+                async move {
+                    match authorized {
+                        true => Ok(()),
+                        false => Err(Error::from_response(
+                            Response::builder()
+                                .status(StatusCode::UNAUTHORIZED)
+                                .header(
+                                    header::WWW_AUTHENTICATE,
+                                    "Basic realm=\"API documentation\"",
+                                )
+                                .finish(),
+                        )),
+                    }
+                }
+                .boxed()
+            }),
+        }
+    }
+
+    /// Allows the requests for which the function returns `true`.
+    pub fn checker<F>(f: F) -> Self
+    where
+        F: Fn(&Request) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            check: Arc::new(move |req| {
+                let authorized = f(req);
+                async move {
+                    match authorized {
+                        true => Ok(()),
+                        false => Err(AuthorizationError.into()),
+                    }
+                }
+                .boxed()
+            }),
+        }
+    }
+
+    /// Requires the authentication of a security scheme, which can be any
+    /// type that derives [`SecurityScheme`](crate::SecurityScheme).
+    pub fn security_scheme<T>() -> Self
+    where
+        T: for<'a> ApiExtractor<'a>,
+    {
+        Self {
+            check: Arc::new(|req| {
+                async move {
+                    T::from_request(
+                        req,
+                        &mut RequestBody::default(),
+                        ExtractParamOptions::default(),
+                    )
+                    .await
+                    .map(|_| ())
+                }
+                .boxed()
+            }),
+        }
+    }
+}
+
+/// An endpoint that is only called if the guard (if any) allows the request.
+pub(crate) struct GuardedEndpoint<E> {
+    pub(crate) inner: E,
+    pub(crate) guard: Option<DocsGuard>,
+}
+
+impl<E: Endpoint> Endpoint for GuardedEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        if let Some(guard) = &self.guard {
+            if req.extensions().get::<UrlQuery>().is_none() {
+                let url_query: Vec<(String, String)> = req.params().unwrap_or_default();
+                req.extensions_mut().insert(UrlQuery(url_query));
+            }
+            (guard.check)(&req).await?;
+        }
+        self.inner.call(req).await.map(IntoResponse::into_response)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
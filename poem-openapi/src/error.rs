@@ -1,6 +1,12 @@
 //! Some common error types.
 
-use poem::{error::ResponseError, http::StatusCode};
+use std::time::Duration;
+
+use poem::{
+    Response,
+    error::ResponseError,
+    http::{StatusCode, header},
+};
 use thiserror::Error;
 
 /// Parameter error.
@@ -96,3 +102,93 @@ impl ResponseError for AuthorizationError {
         StatusCode::UNAUTHORIZED
     }
 }
+
+/// OpenID Connect token validation error.
+#[cfg(feature = "openid-connect")]
+#[cfg_attr(docsrs, doc(cfg(feature = "openid-connect")))]
+#[derive(Debug, Error)]
+pub enum OpenIdConnectError {
+    /// Failed to fetch the discovery document or the key set of the provider.
+    #[error("failed to fetch the OpenID Connect provider metadata: {0}")]
+    Fetch(String),
+
+    /// The token is invalid.
+    #[error("invalid token: {0}")]
+    InvalidToken(String),
+
+    /// The token does not grant a required scope.
+    #[error("the token does not grant the `{0}` scope")]
+    InsufficientScope(String),
+}
+
+#[cfg(feature = "openid-connect")]
+impl ResponseError for OpenIdConnectError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Fetch(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::InvalidToken(_) => StatusCode::UNAUTHORIZED,
+            Self::InsufficientScope(_) => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+/// Schema validation error, see [`SchemaValidation`](crate::SchemaValidation).
+#[derive(Debug, Error)]
+pub enum SchemaValidationError {
+    /// The request body does not match its schema.
+    #[error("the request body does not match its schema: {}", .0.join(", "))]
+    Request(Vec<String>),
+
+    /// The response body does not match its schema.
+    #[error("the response body does not match its schema: {}", .0.join(", "))]
+    Response(Vec<String>),
+}
+
+impl ResponseError for SchemaValidationError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Request(_) => StatusCode::BAD_REQUEST,
+            Self::Response(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// The rate limit of an operation is exceeded, see
+/// [`RateLimiter`](crate::rate_limit::RateLimiter).
+#[derive(Debug, Error)]
+#[error("too many requests, retry after {} seconds", retry_after_secs(.retry_after))]
+pub struct TooManyRequestsError {
+    /// The time to wait before the next request is allowed.
+    pub retry_after: Duration,
+}
+
+fn retry_after_secs(retry_after: &Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
+
+impl ResponseError for TooManyRequestsError {
+    fn status(&self) -> StatusCode {
+        StatusCode::TOO_MANY_REQUESTS
+    }
+
+    fn as_response(&self) -> Response {
+        Response::builder()
+            .status(self.status())
+            .header(header::RETRY_AFTER, retry_after_secs(&self.retry_after))
+            .body(self.to_string())
+    }
+}
+
+/// The request payload exceeds the maximum size of the operation.
+#[derive(Debug, Error)]
+#[error("the payload exceeds the limit of {limit} bytes")]
+pub struct PayloadTooLargeError {
+    /// The maximum size of the payload in bytes.
+    pub limit: u64,
+}
+
+impl ResponseError for PayloadTooLargeError {
+    fn status(&self) -> StatusCode {
+        StatusCode::PAYLOAD_TOO_LARGE
+    }
+}
@@ -0,0 +1,58 @@
+use std::{error::Error as StdError, sync::Arc};
+
+use poem::{Endpoint, Error, IntoResponse, Request, Response, Result, endpoint::ShutdownHooks};
+
+use crate::{
+    ApiResponse,
+    registry::{MetaResponses, Registry},
+};
+
+type ConvertFn = dyn Fn(&Error) -> Option<Response> + Send + Sync;
+
+/// A conversion from an error type to a documented response.
+#[derive(Clone)]
+pub(crate) struct ErrorMapping {
+    pub(crate) meta: fn() -> MetaResponses,
+    pub(crate) register: fn(&mut Registry),
+    convert: Arc<ConvertFn>,
+}
+
+impl ErrorMapping {
+    pub(crate) fn new<E, R, F>(f: F) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+        R: ApiResponse + IntoResponse,
+        F: Fn(&E) -> R + Send + Sync + 'static,
+    {
+        Self {
+            meta: R::meta,
+            register: R::register,
+            convert: Arc::new(move |err| err.downcast_ref::<E>().map(|err| f(err).into_response())),
+        }
+    }
+}
+
+/// An endpoint that converts the errors with the first matching mapping.
+pub(crate) struct ErrorMappingEndpoint<E> {
+    pub(crate) inner: E,
+    pub(crate) mappings: Vec<ErrorMapping>,
+}
+
+impl<E: Endpoint> Endpoint for ErrorMappingEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        match self.inner.call(req).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(err) => self
+                .mappings
+                .iter()
+                .find_map(|mapping| (mapping.convert)(&err))
+                .ok_or(err),
+        }
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
@@ -0,0 +1,175 @@
+use poem::{
+    Endpoint, Error, IntoResponse, Request, Response, Result,
+    endpoint::ShutdownHooks,
+    http::header,
+    i18n::{I18NBundle, I18NResources, unic_langid::LanguageIdentifier},
+};
+use serde_json::Value;
+
+use crate::error::{
+    AuthorizationError, ContentTypeError, ParseMultipartError, ParseParamError, ParsePathError,
+    ParseRequestPayloadError,
+};
+
+/// The prefix of the descriptions that are message ids.
+const MESSAGE_PREFIX: &str = "i18n:";
+
+/// Returns the languages requested by the `lang` query parameter, or by the
+/// `Accept-Language` header.
+fn request_languages(req: &Request) -> Vec<LanguageIdentifier> {
+    let lang = req
+        .uri()
+        .query()
+        .and_then(|query| {
+            serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+                .ok()?
+                .into_iter()
+                .find(|(name, _)| name == "lang")
+        })
+        .and_then(|(_, lang)| lang.parse().ok());
+    if let Some(lang) = lang {
+        return vec![lang];
+    }
+
+    let mut languages = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.trim().split(';');
+            let language = parts.next()?.trim().parse().ok()?;
+            let quality = parts
+                .next()
+                .and_then(|quality| quality.trim().strip_prefix("q=")?.parse().ok())
+                .unwrap_or(1.0);
+            Some((language, quality))
+        })
+        .collect::<Vec<(LanguageIdentifier, f32)>>();
+    languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    languages
+        .into_iter()
+        .map(|(language, _)| language)
+        .collect()
+}
+
+/// Returns the specification translated to the languages of the request.
+pub(crate) fn localize_request_spec(
+    spec: &Value,
+    resources: &I18NResources,
+    req: &Request,
+) -> Value {
+    let mut spec = spec.clone();
+    localize_spec(
+        &mut spec,
+        &resources.negotiate_languages(&request_languages(req)),
+    );
+    spec
+}
+
+/// Replaces the descriptions, summaries and titles of the specification that
+/// are message ids with the translated messages.
+pub(crate) fn localize_spec(spec: &mut Value, bundle: &I18NBundle) {
+    match spec {
+        Value::Object(obj) => {
+            for (key, value) in obj {
+                match value {
+                    Value::String(s)
+                        if matches!(key.as_str(), "description" | "summary" | "title") =>
+                    {
+                        if let Some(id) = s.strip_prefix(MESSAGE_PREFIX) {
+                            *s = bundle.text(id).unwrap_or_else(|_| id.to_string());
+                        }
+                    }
+                    value => localize_spec(value, bundle),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                localize_spec(value, bundle);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the translated message of the errors of this crate.
+fn localize_error(err: &Error, bundle: &I18NBundle) -> Option<String> {
+    if let Some(err) = err.downcast_ref::<ParseParamError>() {
+        bundle.text_with_args(
+            "openapi-parse-param-error",
+            (("name", err.name), ("reason", err.reason.as_str())),
+        )
+    } else if let Some(err) = err.downcast_ref::<ParsePathError>() {
+        bundle.text_with_args(
+            "openapi-parse-path-error",
+            (("name", err.name), ("reason", err.reason.as_str())),
+        )
+    } else if let Some(err) = err.downcast_ref::<ParseRequestPayloadError>() {
+        bundle.text_with_args(
+            "openapi-parse-request-payload-error",
+            (("reason", err.reason.as_str()),),
+        )
+    } else if let Some(err) = err.downcast_ref::<ParseMultipartError>() {
+        bundle.text_with_args(
+            "openapi-parse-multipart-error",
+            (("reason", err.reason.as_str()),),
+        )
+    } else if let Some(err) = err.downcast_ref::<ContentTypeError>() {
+        match err {
+            ContentTypeError::NotSupported { content_type } => bundle.text_with_args(
+                "openapi-content-type-not-supported",
+                (("content_type", content_type.as_str()),),
+            ),
+            ContentTypeError::ExpectContentType => bundle.text("openapi-content-type-expected"),
+        }
+    } else if err.is::<AuthorizationError>() {
+        bundle.text("openapi-authorization-error")
+    } else {
+        return None;
+    }
+    .ok()
+}
+
+/// An endpoint that translates the messages of the errors of this crate.
+///
+/// The translated errors are replaced with plain text errors, which keep the
+/// status code and the headers.
+pub(crate) struct LocalizeErrorEndpoint<E> {
+    pub(crate) inner: E,
+    pub(crate) resources: Option<I18NResources>,
+}
+
+impl<E: Endpoint> Endpoint for LocalizeErrorEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let Some(resources) = &self.resources else {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        };
+
+        let languages = request_languages(&req);
+        match self.inner.call(req).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(mut err) => {
+                let bundle = resources.negotiate_languages(&languages);
+                match localize_error(&err, &bundle) {
+                    Some(msg) => {
+                        let mut localized = Error::from_string(msg, err.status());
+                        localized
+                            .headers_mut()
+                            .extend(std::mem::take(err.headers_mut()));
+                        Err(localized)
+                    }
+                    None => Err(err),
+                }
+            }
+        }
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
@@ -111,9 +111,17 @@
 //! | geo                | Integrate with the [`geo-types` crate](https://crates.io/crates/geo-types)             |
 //! | bson               | Integrate with the [`bson` crate](https://crates.io/crates/bson)                       |
 //! | rust_decimal       | Integrate with the [`rust_decimal` crate](https://crates.io/crates/rust_decimal)       |
+//! | bigdecimal         | Integrate with the [`bigdecimal` crate](https://crates.io/crates/bigdecimal)           |
+//! | jiff               | Integrate with the [`jiff` crate](https://crates.io/crates/jiff)                       |
 //! | prost-wkt-types    | Integrate with the [`prost-wkt-types` crate](https://crates.io/crates/prost-wkt-types) |
 //! | static-files       | Support for static file response                                                       |
 //! | websocket          | Support for websocket                                                                  |
+//! | openid-connect     | Support for validating OpenID Connect tokens in security schemes                       |
+//! | codegen            | Generate the TypeScript declarations of the API                                        |
+//! | i18n               | Translate the specification and the error messages with the Poem i18n resources        |
+//! | cbor               | Support for the CBOR payload                                                           |
+//! | msgpack            | Support for the MessagePack payload                                                    |
+//! | csv                | Support for the CSV payloads                                                           |
 //! | sonic-rs           | Uses [`sonic-rs`](https://github.com/cloudwego/sonic-rs) instead of `serde_json`. Pls, checkout `sonic-rs` requirements to properly enable `sonic-rs` capabilities |
 
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/poem-web/poem/master/favicon.ico")]
@@ -129,9 +137,14 @@
 pub mod macros;
 
 pub mod auth;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+pub mod diff;
 pub mod error;
+pub mod pagination;
 pub mod param;
 pub mod payload;
+pub mod rate_limit;
 #[doc(hidden)]
 pub mod registry;
 mod response;
@@ -140,24 +153,27 @@ pub mod types;
 pub mod validation;
 
 mod base;
+mod docs_guard;
+mod error_mapping;
+#[cfg(feature = "i18n")]
+mod i18n;
+mod mock;
 mod openapi;
 mod path_util;
-#[cfg(any(
-    feature = "openapi-explorer",
-    feature = "rapidoc",
-    feature = "redoc",
-    feature = "scalar",
-    feature = "stoplight-elements",
-    feature = "swagger-ui",
-))]
+mod request_size;
+mod schema_validation;
+mod spec_merge;
 mod ui;
+mod xml;
 
 pub use base::{
     ApiExtractor, ApiExtractorType, ApiResponse, ExtractParamOptions, OAuthScopes, OpenApi,
-    OperationId, ParameterStyle, ResponseContent, Tags, Webhook,
+    OperationId, ParameterStyle, ResponseContent, ResponseHeaders, Tags, Webhook,
 };
+pub use docs_guard::DocsGuard;
 pub use openapi::{
-    ContactObject, ExternalDocumentObject, ExtraHeader, LicenseObject, OpenApiService, ServerObject,
+    ContactObject, ExternalDocumentObject, ExtraHeader, LicenseObject, OpenApiService,
+    OpenApiVersion, ServerObject, TagObject,
 };
 #[doc = include_str!("docs/request.md")]
 pub use poem_openapi_derive::ApiRequest;
@@ -177,6 +193,8 @@ pub use poem_openapi_derive::Object;
 pub use poem_openapi_derive::OpenApi;
 #[doc = include_str!("docs/response_content.md")]
 pub use poem_openapi_derive::ResponseContent;
+#[doc = include_str!("docs/response_headers.md")]
+pub use poem_openapi_derive::ResponseHeaders;
 #[doc = include_str!("docs/security_scheme.md")]
 pub use poem_openapi_derive::SecurityScheme;
 #[doc = include_str!("docs/tags.md")]
@@ -185,6 +203,9 @@ pub use poem_openapi_derive::Tags;
 pub use poem_openapi_derive::Union;
 #[doc = include_str!("docs/webhook.md")]
 pub use poem_openapi_derive::Webhook;
+pub use response::ErrorCodeResponse;
+pub use schema_validation::SchemaValidation;
+pub use ui::UiConfig;
 pub use validation::Validator;
 
 #[doc(hidden)]
@@ -195,5 +216,12 @@ pub mod __private {
     pub use serde;
     pub use serde_json;
 
-    pub use crate::{auth::CheckerReturn, base::UrlQuery, path_util::join_path};
+    pub use crate::{
+        auth::{CheckerReturn, and_security_requirements},
+        base::{UrlQuery, content_type_is},
+        param::style::split_properties,
+        path_util::{convert_path_params, join_path},
+        request_size::{limit_request_size, payload_too_large_response},
+        xml::{from_xml_value, to_xml_value},
+    };
 }
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use poem::{
+    Endpoint, EndpointExt, Request, Response, Result,
+    endpoint::BoxEndpoint,
+    http::{Method, StatusCode, header},
+};
+use serde_json::{Map, Value};
+
+use crate::schema_validation::map_operations;
+
+/// The maximum depth of the generated values, which prevents the infinite
+/// recursions of the recursive schemas.
+const MAX_DEPTH: usize = 16;
+
+/// Replaces the endpoints of the routes with the fake responses of the
+/// operations of the specification.
+pub(crate) fn mock_routes(
+    spec: &Value,
+    url_prefix: Option<&str>,
+    routes: &mut HashMap<String, HashMap<Method, BoxEndpoint<'static>>>,
+) {
+    let generator = Generator {
+        schemas: spec["components"]["schemas"].as_object(),
+    };
+    map_operations(spec, url_prefix, routes, |_, operation, _| {
+        generator.mock_endpoint(operation).boxed()
+    });
+}
+
+/// An endpoint that always returns the same fake response.
+struct MockEndpoint {
+    status: StatusCode,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Endpoint for MockEndpoint {
+    type Output = Response;
+
+    async fn call(&self, _req: Request) -> Result<Self::Output> {
+        let mut resp = Response::builder().status(self.status);
+        if let Some(content_type) = &self.content_type {
+            resp = resp.header(header::CONTENT_TYPE, content_type);
+        }
+        Ok(resp.body(self.body.clone()))
+    }
+}
+
+struct Generator<'a> {
+    schemas: Option<&'a Map<String, Value>>,
+}
+
+impl<'a> Generator<'a> {
+    fn mock_endpoint(&self, operation: &Value) -> MockEndpoint {
+        let responses = operation.get("responses").and_then(Value::as_object);
+        // the first successful response, or the default one
+        let response = responses.and_then(|responses| {
+            responses
+                .iter()
+                .find(|(status, _)| status.starts_with('2'))
+                .or_else(|| responses.get_key_value("default"))
+        });
+        let Some((status, response)) = response else {
+            return MockEndpoint {
+                status: StatusCode::OK,
+                content_type: None,
+                body: Vec::new(),
+            };
+        };
+        let status = status.parse().unwrap_or(StatusCode::OK);
+
+        let content = response.get("content").and_then(Value::as_object);
+        let media = content.and_then(|content| {
+            content
+                .get_key_value("application/json")
+                .or_else(|| content.iter().next())
+        });
+        let Some((content_type, media)) = media else {
+            return MockEndpoint {
+                status,
+                content_type: None,
+                body: Vec::new(),
+            };
+        };
+
+        let value = media
+            .get("example")
+            .cloned()
+            .or_else(|| {
+                media
+                    .get("examples")?
+                    .as_object()?
+                    .values()
+                    .find_map(|example| example.get("value").cloned())
+            })
+            .unwrap_or_else(|| self.value(&media["schema"], 0));
+        let body = match (&value, content_type.contains("json")) {
+            (Value::String(s), false) => s.clone().into_bytes(),
+            _ => serde_json::to_vec(&value).unwrap_or_default(),
+        };
+
+        MockEndpoint {
+            status,
+            content_type: Some(content_type.clone()),
+            body,
+        }
+    }
+
+    fn resolve(&self, schema: &'a Value) -> &'a Value {
+        schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| {
+                self.schemas?
+                    .get(reference.strip_prefix("#/components/schemas/")?)
+            })
+            .unwrap_or(schema)
+    }
+
+    /// Generates a value that matches the schema, the examples and the
+    /// default values are preferred.
+    fn value(&self, schema: &'a Value, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        let schema = self.resolve(schema);
+
+        for key in ["example", "default", "const"] {
+            if let Some(value) = schema.get(key) {
+                return value.clone();
+            }
+        }
+        if let Some(value) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|values| values.first())
+        {
+            return value.clone();
+        }
+        if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+            let mut obj = Map::new();
+            for schema in schemas {
+                match self.value(schema, depth + 1) {
+                    Value::Object(value) => obj.extend(value),
+                    value => return value,
+                }
+            }
+            return Value::Object(obj);
+        }
+        if let Some(schema) = ["oneOf", "anyOf"]
+            .into_iter()
+            .find_map(|key| schema.get(key)?.as_array()?.first())
+        {
+            return self.value(schema, depth + 1);
+        }
+
+        let ty = match schema.get("type") {
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|ty| *ty != "null"),
+            ty => ty.and_then(Value::as_str),
+        };
+        match ty {
+            Some("object") => {
+                let required = schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let mut obj = Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (name, schema) in properties {
+                        // the optional properties are omitted from the deep values
+                        if depth < MAX_DEPTH / 2 || required.iter().any(|required| required == name)
+                        {
+                            obj.insert(name.clone(), self.value(schema, depth + 1));
+                        }
+                    }
+                }
+                Value::Object(obj)
+            }
+            Some("array") => {
+                let min_items = schema.get("minItems").and_then(Value::as_u64).unwrap_or(1);
+                let item = self.value(&schema["items"], depth + 1);
+                Value::Array(vec![item; min_items.max(1) as usize])
+            }
+            Some("string") => Value::String(fake_string(schema)),
+            Some("integer") => schema
+                .get("minimum")
+                .and_then(Value::as_f64)
+                .map(|minimum| Value::from(minimum.ceil() as i64))
+                .unwrap_or_else(|| Value::from(0)),
+            Some("number") => schema
+                .get("minimum")
+                .cloned()
+                .unwrap_or_else(|| Value::from(0.0)),
+            Some("boolean") => Value::Bool(true),
+            _ => Value::Null,
+        }
+    }
+}
+
+fn fake_string(schema: &Value) -> String {
+    let s = match schema.get("format").and_then(Value::as_str) {
+        Some("date-time") => "1970-01-01T00:00:00Z",
+        Some("date") => "1970-01-01",
+        Some("time") => "00:00:00",
+        Some("uuid") => "00000000-0000-0000-0000-000000000000",
+        Some("email") => "user@example.com",
+        Some("uri" | "url") => "https://example.com",
+        Some("ipv4") => "127.0.0.1",
+        Some("ipv6") => "::1",
+        Some("hostname") => "example.com",
+        Some("binary" | "byte") => "",
+        _ => "string",
+    };
+    let min_length = schema
+        .get("minLength")
+        .and_then(Value::as_u64)
+        .unwrap_or_default() as usize;
+    match s.len() < min_length {
+        true => format!("{s:x<min_length$}"),
+        false => s.to_string(),
+    }
+}
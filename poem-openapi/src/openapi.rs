@@ -1,22 +1,38 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    error::Error as StdError,
     marker::PhantomData,
+    sync::Arc,
 };
 
+#[cfg(feature = "i18n")]
+use poem::i18n::{I18NResources, unic_langid::LanguageIdentifier};
 use poem::{
-    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result, Route, RouteMethod,
+    Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response, Result, Route,
+    RouteMethod,
     endpoint::{BoxEndpoint, make_sync},
+    http::Method,
 };
 #[cfg(feature = "cookie")]
 use poem::{middleware::CookieJarManager, web::cookie::CookieKey};
+use serde_json::Value;
 
 use crate::{
-    OpenApi, Webhook,
+    ApiResponse, DocsGuard, OpenApi, SchemaValidation, UiConfig, Webhook,
     base::UrlQuery,
+    docs_guard::GuardedEndpoint,
+    error_mapping::{ErrorMapping, ErrorMappingEndpoint},
+    mock,
+    path_util::join_path,
+    payload::ConditionalEndpoint,
+    rate_limit::{self, RateLimiter},
     registry::{
-        Document, MetaContact, MetaExternalDocument, MetaHeader, MetaInfo, MetaLicense,
-        MetaOperationParam, MetaParamIn, MetaSchemaRef, MetaServer, MetaServerVariable, Registry,
+        Document, MetaApi, MetaContact, MetaExternalDocument, MetaHeader, MetaInfo, MetaLicense,
+        MetaOperation, MetaOperationParam, MetaParamIn, MetaPath, MetaSchemaRef, MetaServer,
+        MetaServerVariable, MetaTagDefinition, MetaTagGroup, Registry,
     },
+    request_size::{self, payload_too_large_response},
+    spec_merge::merge_spec,
     types::Type,
 };
 
@@ -215,6 +231,58 @@ impl ExternalDocumentObject {
     }
 }
 
+impl From<ExternalDocumentObject> for MetaExternalDocument {
+    fn from(external_document: ExternalDocumentObject) -> Self {
+        Self {
+            url: external_document.url,
+            description: external_document.description,
+        }
+    }
+}
+
+/// An object representing the metadata of a tag.
+#[derive(Debug, Clone)]
+pub struct TagObject {
+    name: String,
+    description: Option<String>,
+    external_document: Option<ExternalDocumentObject>,
+}
+
+impl<T: Into<String>> From<T> for TagObject {
+    fn from(name: T) -> Self {
+        Self::new(name)
+    }
+}
+
+impl TagObject {
+    /// Create a tag object by name.
+    pub fn new(name: impl Into<String>) -> TagObject {
+        Self {
+            name: name.into(),
+            description: None,
+            external_document: None,
+        }
+    }
+
+    /// Sets a description of the tag.
+    #[must_use]
+    pub fn description(self, description: impl Into<String>) -> Self {
+        Self {
+            description: Some(description.into()),
+            ..self
+        }
+    }
+
+    /// Sets a external document of the tag.
+    #[must_use]
+    pub fn external_document(self, external_document: impl Into<ExternalDocumentObject>) -> Self {
+        Self {
+            external_document: Some(external_document.into()),
+            ..self
+        }
+    }
+}
+
 /// An extra header
 #[derive(Debug, Clone)]
 pub struct ExtraHeader {
@@ -257,6 +325,20 @@ impl ExtraHeader {
     }
 }
 
+/// The version of the OpenAPI specification of the generated documents.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum OpenApiVersion {
+    /// OpenAPI 3.0
+    #[default]
+    V3_0,
+    /// OpenAPI 3.1, the schemas use the JSON Schema 2020-12 dialect.
+    V3_1,
+}
+
+/// The functions to register a type and get its schema reference.
+type ExtraType = (fn(&mut Registry), fn() -> MetaSchemaRef);
+
 /// An OpenAPI service for Poem.
 #[derive(Clone)]
 pub struct OpenApiService<T, W> {
@@ -264,12 +346,29 @@ pub struct OpenApiService<T, W> {
     _webhook: PhantomData<W>,
     info: MetaInfo,
     external_document: Option<MetaExternalDocument>,
+    operation_external_documents: HashMap<String, MetaExternalDocument>,
+    tags: Vec<MetaTagDefinition>,
+    tag_groups: Vec<MetaTagGroup>,
     servers: Vec<MetaServer>,
     #[cfg(feature = "cookie")]
     cookie_key: Option<CookieKey>,
     extra_response_headers: Vec<(ExtraHeader, MetaSchemaRef, bool)>,
     extra_request_headers: Vec<(ExtraHeader, MetaSchemaRef, bool)>,
     url_prefix: Option<String>,
+    versions: Vec<String>,
+    version: OpenApiVersion,
+    extensions: BTreeMap<String, Value>,
+    ui_config: UiConfig,
+    docs_guard: Option<DocsGuard>,
+    schema_validation: Option<SchemaValidation>,
+    mock: bool,
+    max_request_size: Option<u64>,
+    rate_limiter: Option<Arc<dyn RateLimiter>>,
+    error_mappings: Vec<ErrorMapping>,
+    extra_types: Vec<ExtraType>,
+    spec_fragments: Vec<Value>,
+    #[cfg(feature = "i18n")]
+    i18n_resources: Option<I18NResources>,
 }
 
 impl<T> OpenApiService<T, ()> {
@@ -289,12 +388,29 @@ impl<T> OpenApiService<T, ()> {
                 license: None,
             },
             external_document: None,
+            operation_external_documents: HashMap::new(),
+            tags: Vec::new(),
+            tag_groups: Vec::new(),
             servers: Vec::new(),
             #[cfg(feature = "cookie")]
             cookie_key: None,
             extra_response_headers: vec![],
             extra_request_headers: vec![],
             url_prefix: None,
+            versions: Vec::new(),
+            version: OpenApiVersion::default(),
+            extensions: BTreeMap::new(),
+            ui_config: UiConfig::default(),
+            docs_guard: None,
+            schema_validation: None,
+            mock: false,
+            max_request_size: None,
+            rate_limiter: None,
+            error_mappings: Vec::new(),
+            extra_types: Vec::new(),
+            spec_fragments: Vec::new(),
+            #[cfg(feature = "i18n")]
+            i18n_resources: None,
         }
     }
 }
@@ -307,12 +423,29 @@ impl<T, W> OpenApiService<T, W> {
             _webhook: PhantomData,
             info: self.info,
             external_document: self.external_document,
+            operation_external_documents: self.operation_external_documents,
+            tags: self.tags,
+            tag_groups: self.tag_groups,
             servers: self.servers,
             #[cfg(feature = "cookie")]
             cookie_key: self.cookie_key,
             extra_response_headers: self.extra_response_headers,
             extra_request_headers: self.extra_request_headers,
             url_prefix: None,
+            versions: self.versions,
+            version: self.version,
+            extensions: self.extensions,
+            ui_config: self.ui_config,
+            docs_guard: self.docs_guard,
+            schema_validation: self.schema_validation,
+            mock: self.mock,
+            max_request_size: self.max_request_size,
+            rate_limiter: self.rate_limiter,
+            error_mappings: self.error_mappings,
+            extra_types: self.extra_types,
+            spec_fragments: self.spec_fragments,
+            #[cfg(feature = "i18n")]
+            i18n_resources: self.i18n_resources,
         }
     }
 
@@ -384,14 +517,112 @@ impl<T, W> OpenApiService<T, W> {
         mut self,
         external_document: impl Into<ExternalDocumentObject>,
     ) -> Self {
-        let external_document = external_document.into();
-        self.external_document = Some(MetaExternalDocument {
-            url: external_document.url,
-            description: external_document.description,
+        self.external_document = Some(external_document.into().into());
+        self
+    }
+
+    /// Sets the external document of the operation with the operation id.
+    #[must_use]
+    pub fn operation_external_document(
+        mut self,
+        operation_id: impl Into<String>,
+        external_document: impl Into<ExternalDocumentObject>,
+    ) -> Self {
+        self.operation_external_documents
+            .insert(operation_id.into(), external_document.into().into());
+        self
+    }
+
+    /// Adds the metadata of a tag.
+    ///
+    /// The tags are listed in the order they are added, followed by the other
+    /// tags of the operations in alphabetical order. The description and the
+    /// external document override the ones of the [`Tags`](crate::Tags) with
+    /// the same name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem_openapi::{OpenApiService, TagObject};
+    ///
+    /// struct Api;
+    ///
+    /// #[poem_openapi::OpenApi]
+    /// impl Api {}
+    ///
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0")
+    ///     .tag(TagObject::new("users").description("Operations about users"))
+    ///     .tag(TagObject::new("orders").external_document("https://example.com/docs/orders"))
+    ///     .tag_group("Store", ["users", "orders"]);
+    /// ```
+    ///
+    /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#tag-object>
+    #[must_use]
+    pub fn tag(mut self, tag: impl Into<TagObject>) -> Self {
+        let tag = tag.into();
+        let definition = MetaTagDefinition {
+            name: tag.name,
+            description: tag.description,
+            external_docs: tag.external_document.map(Into::into),
+        };
+        match self
+            .tags
+            .iter_mut()
+            .find(|item| item.name == definition.name)
+        {
+            Some(item) => *item = definition,
+            None => self.tags.push(definition),
+        }
+        self
+    }
+
+    /// Adds a group of tags, which is added to the `x-tagGroups` extension
+    /// of the generated documents.
+    #[must_use]
+    pub fn tag_group(
+        mut self,
+        name: impl Into<String>,
+        tags: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.tag_groups.push(MetaTagGroup {
+            name: name.into(),
+            tags: tags.into_iter().map(Into::into).collect(),
         });
         self
     }
 
+    /// Adds a specification extension to the root of the generated documents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` does not start with `x-`.
+    ///
+    /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#specification-extensions>
+    #[must_use]
+    pub fn extension(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        assert!(
+            name.starts_with("x-"),
+            "the name of an extension must start with `x-`"
+        );
+        self.extensions.insert(name, value.into());
+        self
+    }
+
+    /// Sets the version of the OpenAPI specification of the generated
+    /// documents.
+    ///
+    /// Default is [`OpenApiVersion::V3_0`]. With [`OpenApiVersion::V3_1`],
+    /// the schemas use the JSON Schema 2020-12 dialect, e.g. nullable types
+    /// are described as `type: ["string", "null"]`.
+    ///
+    /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md>
+    #[must_use]
+    pub fn openapi_version(mut self, version: OpenApiVersion) -> Self {
+        self.version = version;
+        self
+    }
+
     /// Add extra response header
     #[must_use]
     pub fn extra_response_header<HT, H>(mut self, header: H) -> Self
@@ -436,6 +667,345 @@ impl<T, W> OpenApiService<T, W> {
         }
     }
 
+    /// Serves the API in multiple versions.
+    ///
+    /// The operations of each version are served under `/{version}`. The
+    /// operations with `#[oai(version = "...")]` attributes are only
+    /// contained in these versions, and the other operations are contained in
+    /// all versions, unless the version has an operation with the same path
+    /// and method.
+    ///
+    /// The specification contains the operations of all versions, use
+    /// [`OpenApiService::version_spec`] for the specification of a version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::test::TestClient;
+    /// use poem_openapi::{OpenApi, OpenApiService, payload::PlainText};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn hello(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    ///
+    ///     #[oai(path = "/users", method = "get", version = "v1")]
+    ///     async fn users_v1(&self) -> PlainText<&'static str> {
+    ///         PlainText("v1")
+    ///     }
+    ///
+    ///     #[oai(path = "/users", method = "get", version = "v2")]
+    ///     async fn users_v2(&self) -> PlainText<&'static str> {
+    ///         PlainText("v2")
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0").versions(["v1", "v2"]);
+    /// let v1_spec = api_service.version_spec("v1");
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let cli = TestClient::new(api_service);
+    /// cli.get("/v1/hello").send().await.assert_text("hello").await;
+    /// cli.get("/v2/users").send().await.assert_text("v2").await;
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn versions(self, versions: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            versions: versions.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Sets the configuration of the documentation UIs.
+    #[must_use]
+    pub fn ui_config(self, ui_config: UiConfig) -> Self {
+        Self { ui_config, ..self }
+    }
+
+    /// Restricts the access to the specification and documentation UI
+    /// endpoints.
+    #[must_use]
+    pub fn docs_guard(self, guard: DocsGuard) -> Self {
+        Self {
+            docs_guard: Some(guard),
+            ..self
+        }
+    }
+
+    /// Validates the requests and responses of the operations against the
+    /// schemas of the specification.
+    #[must_use]
+    pub fn schema_validation(self, schema_validation: SchemaValidation) -> Self {
+        Self {
+            schema_validation: Some(schema_validation),
+            ..self
+        }
+    }
+
+    /// Serves fake responses for all the operations instead of calling their
+    /// handlers.
+    ///
+    /// The response of an operation is its first successful response of the
+    /// specification, and the body is the example of the content, or a value
+    /// generated from its schema. This allows to develop the clients against
+    /// the API before it is implemented.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::test::TestClient;
+    /// use poem_openapi::{Object, OpenApi, OpenApiService, payload::Json};
+    ///
+    /// #[derive(Object)]
+    /// struct User {
+    ///     id: i64,
+    ///     #[oai(default = "default_name")]
+    ///     name: String,
+    /// }
+    ///
+    /// fn default_name() -> String {
+    ///     "sunli".to_string()
+    /// }
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/user", method = "get")]
+    ///     async fn user(&self) -> Json<User> {
+    ///         todo!()
+    ///     }
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0").mock();
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let cli = TestClient::new(api_service);
+    /// let resp = cli.get("/user").send().await;
+    /// resp.assert_status_is_ok();
+    /// resp.assert_json(serde_json::json!({ "id": 0, "name": "sunli" }))
+    ///     .await;
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn mock(self) -> Self {
+        Self { mock: true, ..self }
+    }
+
+    /// Sets the default maximum size in bytes of the request payloads, for the
+    /// operations without a `#[oai(max_request_size = "...")]` attribute.
+    ///
+    /// The requests with larger payloads are rejected with
+    /// `413 Payload Too Large`, which is added to the responses of the
+    /// operations in the specification.
+    #[must_use]
+    pub fn max_request_size(self, max_request_size: u64) -> Self {
+        Self {
+            max_request_size: Some(max_request_size),
+            ..self
+        }
+    }
+
+    /// Enforces the rate limits of the operations with the rate limiter.
+    ///
+    /// Without a rate limiter, the rate limits of the operations are only
+    /// documented in the specification. See [`rate_limit`] for more details.
+    #[must_use]
+    pub fn rate_limiter(self, rate_limiter: impl RateLimiter) -> Self {
+        Self {
+            rate_limiter: Some(Arc::new(rate_limiter)),
+            ..self
+        }
+    }
+
+    /// Converts the errors of type `E` returned by the operations into the
+    /// response `R`, which is added to the responses of all the operations
+    /// in the specification.
+    ///
+    /// The mappings are tried in the order they are added, and the errors
+    /// that don't match any of them are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem_openapi::{ApiResponse, OpenApiService, payload::PlainText};
+    ///
+    /// #[derive(Debug, thiserror::Error)]
+    /// #[error("the database is unavailable")]
+    /// struct DatabaseError;
+    ///
+    /// #[derive(ApiResponse)]
+    /// enum DatabaseErrorResponse {
+    ///     /// The service is temporarily unavailable.
+    ///     #[oai(status = 503)]
+    ///     Unavailable(PlainText<String>),
+    /// }
+    ///
+    /// struct Api;
+    ///
+    /// #[poem_openapi::OpenApi]
+    /// impl Api {}
+    ///
+    /// let api_service =
+    ///     OpenApiService::new(Api, "Demo", "1.0").error_mapping(|err: &DatabaseError| {
+    ///         DatabaseErrorResponse::Unavailable(PlainText(err.to_string()))
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn error_mapping<E, R, F>(mut self, f: F) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+        R: ApiResponse + IntoResponse,
+        F: Fn(&E) -> R + Send + Sync + 'static,
+    {
+        self.error_mappings.push(ErrorMapping::new(f));
+        self
+    }
+
+    /// Adds a type to the schemas of the components, even if it is not
+    /// referenced by any operation.
+    ///
+    /// This can be used to describe the types that are not part of the HTTP
+    /// API, such as the events sent to a message queue.
+    #[must_use]
+    pub fn extra_type<S: Type>(mut self) -> Self {
+        self.extra_types.push((S::register, S::schema_ref));
+        self
+    }
+
+    /// Merges a hand-written fragment into the generated specification.
+    ///
+    /// This can be used to describe the endpoints that are not implemented
+    /// with this crate, or to add shared components. The objects are merged
+    /// recursively, and the arrays are merged without duplicates.
+    ///
+    /// # Panics
+    ///
+    /// The generation of the specification panics if an operation or a
+    /// component is defined differently by the fragment and the generated
+    /// specification, or if any other value conflicts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem_openapi::{OpenApi, OpenApiService};
+    /// use serde_json::json;
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {}
+    ///
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0").merge_spec(json!({
+    ///     "paths": {
+    ///         "/health": {
+    ///             "get": {
+    ///                 "responses": { "200": { "description": "The service is healthy" } }
+    ///             }
+    ///         }
+    ///     }
+    /// }));
+    /// ```
+    #[must_use]
+    pub fn merge_spec(mut self, fragment: Value) -> Self {
+        self.spec_fragments.push(fragment);
+        self
+    }
+
+    /// Translates the specification and the error messages with the i18n
+    /// resources.
+    ///
+    /// The descriptions, summaries and titles of the specification that start
+    /// with `i18n:` are the ids of the messages, e.g. `/// i18n:get-user`. The
+    /// endpoints of the specification negotiate the language with the `lang`
+    /// query parameter or the `Accept-Language` header, and the other
+    /// specifications, such as the ones of the UIs, use the default language.
+    ///
+    /// The errors of the operations that fail to parse the requests are
+    /// translated with the following messages, and are replaced with plain
+    /// text errors:
+    ///
+    /// | Message id                            | Arguments              |
+    /// |---------------------------------------|------------------------|
+    /// | `openapi-parse-param-error`           | `name`, `reason`       |
+    /// | `openapi-parse-path-error`            | `name`, `reason`       |
+    /// | `openapi-parse-request-payload-error` | `reason`               |
+    /// | `openapi-parse-multipart-error`       | `reason`               |
+    /// | `openapi-content-type-not-supported`  | `content_type`         |
+    /// | `openapi-content-type-expected`       |                        |
+    /// | `openapi-authorization-error`         |                        |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::{http::StatusCode, i18n::I18NResources, test::TestClient};
+    /// use poem_openapi::{OpenApi, OpenApiService, param::Query};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     /// i18n:get-value
+    ///     #[oai(path = "/value", method = "get")]
+    ///     async fn value(&self, value: Query<i32>) {}
+    /// }
+    ///
+    /// let resources = I18NResources::builder()
+    ///     .add_ftl("en-US", "openapi-parse-param-error = Invalid parameter.")
+    ///     .add_ftl("fr", "get-value = Obtenir la valeur")
+    ///     .build()
+    ///     .unwrap();
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0").i18n(resources);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let cli = TestClient::new(api_service.spec_endpoint());
+    /// let resp = cli.get("/").query("lang", &"fr").send().await;
+    /// let spec = resp.json().await;
+    /// spec.value()
+    ///     .object()
+    ///     .get("paths")
+    ///     .object()
+    ///     .get("/value")
+    ///     .object()
+    ///     .get("get")
+    ///     .object()
+    ///     .get("summary")
+    ///     .assert_string("Obtenir la valeur");
+    ///
+    /// let cli = TestClient::new(api_service);
+    /// let resp = cli.get("/value").query("value", &"abc").send().await;
+    /// resp.assert_status(StatusCode::BAD_REQUEST);
+    /// resp.assert_text("Invalid parameter.").await;
+    /// # });
+    /// ```
+    #[must_use]
+    #[cfg(feature = "i18n")]
+    pub fn i18n(self, resources: I18NResources) -> Self {
+        Self {
+            i18n_resources: Some(resources),
+            ..self
+        }
+    }
+
+    fn guard_docs<E: Endpoint + 'static>(&self, ep: E) -> impl Endpoint + 'static + use<E, T, W> {
+        let ep = GuardedEndpoint {
+            inner: ep,
+            guard: self.docs_guard.clone(),
+        };
+        // a security scheme of the guard may read the cookies
+        #[cfg(feature = "cookie")]
+        let ep = ep.with(match &self.cookie_key {
+            Some(key) => CookieJarManager::with_key(key.clone()),
+            None => CookieJarManager::new(),
+        });
+        ep
+    }
+
     /// Create the OpenAPI Explorer endpoint.
     #[must_use]
     #[cfg(feature = "openapi-explorer")]
@@ -444,7 +1014,10 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::openapi_explorer::create_endpoint(self.spec())
+        self.guard_docs(crate::ui::openapi_explorer::create_endpoint(
+            self.spec(),
+            &self.ui_config,
+        ))
     }
 
     /// Create the OpenAPI Explorer HTML
@@ -454,7 +1027,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::openapi_explorer::create_html(&self.spec())
+        crate::ui::openapi_explorer::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Swagger UI endpoint.
@@ -465,7 +1038,10 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::swagger_ui::create_endpoint(self.spec())
+        self.guard_docs(crate::ui::swagger_ui::create_endpoint(
+            self.spec(),
+            &self.ui_config,
+        ))
     }
 
     /// Create the Swagger UI HTML
@@ -475,7 +1051,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::swagger_ui::create_html(&self.spec())
+        crate::ui::swagger_ui::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Rapidoc endpoint.
@@ -486,7 +1062,10 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::rapidoc::create_endpoint(self.spec())
+        self.guard_docs(crate::ui::rapidoc::create_endpoint(
+            self.spec(),
+            &self.ui_config,
+        ))
     }
 
     /// Create the Rapidoc HTML
@@ -496,7 +1075,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::rapidoc::create_html(&self.spec())
+        crate::ui::rapidoc::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Redoc endpoint.
@@ -507,7 +1086,10 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::redoc::create_endpoint(self.spec())
+        self.guard_docs(crate::ui::redoc::create_endpoint(
+            self.spec(),
+            &self.ui_config,
+        ))
     }
 
     /// Create the Redoc HTML
@@ -518,7 +1100,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::redoc::create_html(&self.spec())
+        crate::ui::redoc::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Scalar endpoint.
@@ -529,7 +1111,10 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::scalar::create_endpoint(self.spec())
+        self.guard_docs(crate::ui::scalar::create_endpoint(
+            self.spec(),
+            &self.ui_config,
+        ))
     }
 
     /// Create the Scalar HTML
@@ -540,7 +1125,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::scalar::create_html(&self.spec())
+        crate::ui::scalar::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create the Stoplight Elements endpoint.
@@ -551,7 +1136,10 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::stoplight_elements::create_endpoint(self.spec())
+        self.guard_docs(crate::ui::stoplight_elements::create_endpoint(
+            self.spec(),
+            &self.ui_config,
+        ))
     }
 
     /// Create the Stoplight Elements HTML.
@@ -562,7 +1150,7 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        crate::ui::stoplight_elements::create_html(&self.spec())
+        crate::ui::stoplight_elements::create_html(&self.spec(), &self.ui_config)
     }
 
     /// Create an endpoint to serve the open api specification as JSON.
@@ -572,11 +1160,20 @@ impl<T, W> OpenApiService<T, W> {
         W: Webhook,
     {
         let spec = self.spec();
-        make_sync(move |_| {
+        #[cfg(feature = "i18n")]
+        let localized = self.localized_spec();
+        self.guard_docs(make_sync(move |_req| {
+            #[cfg(feature = "i18n")]
+            if let Some((resources, spec)) = &localized {
+                let spec = crate::i18n::localize_request_spec(spec, resources, &_req);
+                return Response::builder()
+                    .content_type("application/json")
+                    .body(serde_json::to_string_pretty(&spec).unwrap());
+            }
             Response::builder()
                 .content_type("application/json")
                 .body(spec.clone())
-        })
+        }))
     }
 
     /// Create an endpoint to serve the open api specification as YAML.
@@ -586,21 +1183,48 @@ impl<T, W> OpenApiService<T, W> {
         W: Webhook,
     {
         let spec = self.spec_yaml();
-        make_sync(move |_| {
-            Response::builder()
+        #[cfg(feature = "i18n")]
+        let localized = self.localized_spec();
+        self.guard_docs(make_sync(move |_req| {
+            let resp = Response::builder()
                 .content_type("application/x-yaml")
-                .header("Content-Disposition", "inline; filename=\"spec.yaml\"")
-                .body(spec.clone())
-        })
+                .header("Content-Disposition", "inline; filename=\"spec.yaml\"");
+            #[cfg(feature = "i18n")]
+            if let Some((resources, spec)) = &localized {
+                let spec = crate::i18n::localize_request_spec(spec, resources, &_req);
+                return resp.body(serde_yaml::to_string(&spec).unwrap());
+            }
+            resp.body(spec.clone())
+        }))
+    }
+
+    /// Returns the i18n resources and the untranslated specification.
+    #[cfg(feature = "i18n")]
+    fn localized_spec(&self) -> Option<(I18NResources, Value)>
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        let resources = self.i18n_resources.clone()?;
+        Some((resources, self.merged_spec_value(None)))
     }
 
     fn document(&self) -> Document<'_>
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        self.version_document(None)
+    }
+
+    /// Returns the document of a version, or of all versions.
+    fn version_document(&self, version: Option<&str>) -> Document<'_>
     where
         T: OpenApi,
         W: Webhook,
     {
         let mut registry = Registry::new();
-        let mut apis = T::meta();
+        let mut apis = self.apis(version);
 
         // update extra request headers
         for operation in apis
@@ -622,11 +1246,66 @@ impl<T, W> OpenApiService<T, W> {
                         deprecated: header.deprecated,
                         explode: true,
                         style: None,
+                        examples: vec![],
+                        extensions: Default::default(),
                     },
                 );
             }
         }
 
+        // update the external documents of the operations
+        for operation in apis
+            .iter_mut()
+            .flat_map(|meta_api| meta_api.paths.iter_mut())
+            .flat_map(|path| path.operations.iter_mut())
+        {
+            if let Some(external_document) = operation
+                .operation_id
+                .and_then(|operation_id| self.operation_external_documents.get(operation_id))
+            {
+                operation.external_docs = Some(external_document.clone());
+            }
+        }
+
+        // add the responses of the error mappings
+        for operation in apis
+            .iter_mut()
+            .flat_map(|meta_api| meta_api.paths.iter_mut())
+            .flat_map(|path| path.operations.iter_mut())
+        {
+            for mapping in &self.error_mappings {
+                for resp in (mapping.meta)().responses {
+                    let exists = operation.responses.responses.iter().any(|item| {
+                        item.status == resp.status && item.status_range == resp.status_range
+                    });
+                    if !exists {
+                        operation.responses.responses.push(resp);
+                    }
+                }
+            }
+        }
+
+        // add the response of the default maximum size of the payloads
+        if self.max_request_size.is_some() {
+            for operation in apis
+                .iter_mut()
+                .flat_map(|meta_api| meta_api.paths.iter_mut())
+                .flat_map(|path| path.operations.iter_mut())
+            {
+                if !operation
+                    .responses
+                    .responses
+                    .iter()
+                    .any(|resp| resp.status == Some(413))
+                {
+                    operation
+                        .responses
+                        .responses
+                        .push(payload_too_large_response());
+                }
+            }
+        }
+
         // update extra response headers
         for resp in apis
             .iter_mut()
@@ -652,6 +1331,12 @@ impl<T, W> OpenApiService<T, W> {
 
         T::register(&mut registry);
         W::register(&mut registry);
+        for (register, _) in &self.extra_types {
+            register(&mut registry);
+        }
+        for mapping in &self.error_mappings {
+            (mapping.register)(&mut registry);
+        }
 
         let webhooks = W::meta();
 
@@ -660,23 +1345,73 @@ impl<T, W> OpenApiService<T, W> {
             servers: &self.servers,
             apis,
             webhooks,
+            extra_schemas: self
+                .extra_types
+                .iter()
+                .map(|(_, schema_ref)| schema_ref())
+                .collect(),
             registry,
             external_document: self.external_document.as_ref(),
+            tags: &self.tags,
+            tag_groups: &self.tag_groups,
             url_prefix: self.url_prefix.as_deref(),
+            version: self.version,
+            extensions: &self.extensions,
         };
         doc.remove_unused_schemas();
 
         doc
     }
 
+    /// Returns the operations of a version, or of all versions, the paths of
+    /// the operations of the versions are prefixed with the version.
+    fn apis(&self, version: Option<&str>) -> Vec<MetaApi>
+    where
+        T: OpenApi,
+    {
+        let shared = !self.versions.is_empty();
+        if let Some(version) = version {
+            return version_apis(T::meta(), version, shared);
+        }
+
+        let apis = T::meta();
+        let versions = match shared {
+            true => self.versions.iter().map(String::as_str).collect(),
+            false => operation_versions(&apis),
+        };
+        let mut apis = match shared {
+            true => Vec::new(),
+            false => apis
+                .into_iter()
+                .map(|api| MetaApi {
+                    paths: api
+                        .paths
+                        .into_iter()
+                        .filter_map(|mut path| {
+                            path.operations
+                                .retain(|operation| operation.versions.is_empty());
+                            (!path.operations.is_empty()).then_some(path)
+                        })
+                        .collect(),
+                })
+                .collect(),
+        };
+        for version in versions {
+            apis.extend(version_apis(T::meta(), version, shared));
+        }
+        apis
+    }
+
     /// Returns the OAS specification file as JSON.
     pub fn spec(&self) -> String
     where
         T: OpenApi,
         W: Webhook,
     {
-        let doc = self.document();
-        serde_json::to_string_pretty(&doc).unwrap()
+        match self.is_generated_spec() {
+            true => serde_json::to_string_pretty(&self.document()).unwrap(),
+            false => serde_json::to_string_pretty(&self.spec_value(None)).unwrap(),
+        }
     }
 
     /// Returns the OAS specification file as YAML.
@@ -685,8 +1420,128 @@ impl<T, W> OpenApiService<T, W> {
         T: OpenApi,
         W: Webhook,
     {
-        let doc = self.document();
-        serde_yaml::to_string(&doc).unwrap()
+        match self.is_generated_spec() {
+            true => serde_yaml::to_string(&self.document()).unwrap(),
+            false => serde_yaml::to_string(&self.spec_value(None)).unwrap(),
+        }
+    }
+
+    /// Returns the OAS specification file of a version as JSON.
+    ///
+    /// See [`OpenApiService::versions`] for more details.
+    pub fn version_spec(&self, version: &str) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        match self.is_generated_spec() {
+            true => serde_json::to_string_pretty(&self.version_document(Some(version))).unwrap(),
+            false => serde_json::to_string_pretty(&self.spec_value(Some(version))).unwrap(),
+        }
+    }
+
+    /// Returns the OAS specification file of a version as YAML.
+    ///
+    /// See [`OpenApiService::versions`] for more details.
+    pub fn version_spec_yaml(&self, version: &str) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        match self.is_generated_spec() {
+            true => serde_yaml::to_string(&self.version_document(Some(version))).unwrap(),
+            false => serde_yaml::to_string(&self.spec_value(Some(version))).unwrap(),
+        }
+    }
+
+    /// Returns the TypeScript declarations of the schemas and of the
+    /// operations.
+    ///
+    /// See the [`codegen`](crate::codegen) module for details.
+    #[cfg(feature = "codegen")]
+    pub fn typescript(&self) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        crate::codegen::typescript(&self.spec_value(None))
+    }
+
+    /// Returns the schemas of the components as JSON, without the
+    /// operations.
+    pub fn schemas(&self) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        serde_json::to_string_pretty(&self.schemas_value()).unwrap()
+    }
+
+    /// Returns the schemas of the components as YAML, without the
+    /// operations.
+    pub fn schemas_yaml(&self) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        serde_yaml::to_string(&self.schemas_value()).unwrap()
+    }
+
+    fn schemas_value(&self) -> Value
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        self.spec_value(None)["components"]["schemas"].take()
+    }
+
+    /// Returns `true` if the specification is the generated document, without
+    /// the fragments and the translations.
+    fn is_generated_spec(&self) -> bool {
+        #[cfg(feature = "i18n")]
+        if self.i18n_resources.is_some() {
+            return false;
+        }
+        self.spec_fragments.is_empty()
+    }
+
+    /// Returns the specification of a version, or of all versions, translated
+    /// to the default language.
+    fn spec_value(&self, version: Option<&str>) -> Value
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        #[allow(unused_mut)]
+        let mut spec = self.merged_spec_value(version);
+        #[cfg(feature = "i18n")]
+        if let Some(resources) = &self.i18n_resources {
+            let bundle = resources.negotiate_languages(&[] as &[LanguageIdentifier]);
+            crate::i18n::localize_spec(&mut spec, &bundle);
+        }
+        spec
+    }
+
+    /// Returns the specification of a version, or of all versions, merged
+    /// with the fragments.
+    fn merged_spec_value(&self, version: Option<&str>) -> Value
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        let mut spec = serde_json::to_value(self.version_document(version)).unwrap();
+        let conflicts = self
+            .spec_fragments
+            .iter()
+            .flat_map(|fragment| merge_spec(&mut spec, fragment))
+            .collect::<Vec<_>>();
+        if !conflicts.is_empty() {
+            panic!(
+                "conflicting definitions in the merged specification: {}",
+                conflicts.join(", ")
+            );
+        }
+        spec
     }
 }
 
@@ -700,6 +1555,16 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
             Ok(req)
         }
 
+        let spec = (self.schema_validation.is_some()
+            || self.mock
+            || self.max_request_size.is_some()
+            || self.rate_limiter.is_some())
+        .then(|| self.spec_value(None));
+        let operations = match self.max_request_size.is_some() || self.rate_limiter.is_some() {
+            true => operations_by_name(self.apis(None), self.url_prefix.as_deref()),
+            false => HashMap::new(),
+        };
+
         #[cfg(feature = "cookie")]
         let cookie_jar_manager = match self.cookie_key {
             Some(key) => CookieJarManager::with_key(key),
@@ -723,6 +1588,38 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
         let mut items = HashMap::new();
         self.api.add_routes(&mut items);
 
+        if !self.versions.is_empty() {
+            items = mount_versions(&T::meta(), &self.versions, items);
+        }
+
+        if let (true, Some(spec)) = (self.mock, &spec) {
+            mock::mock_routes(spec, self.url_prefix.as_deref(), &mut items);
+        }
+
+        if let (Some(schema_validation), Some(spec)) = (&self.schema_validation, &spec) {
+            schema_validation.wrap_routes(spec.clone(), self.url_prefix.as_deref(), &mut items);
+        }
+
+        if let (Some(max_request_size), Some(spec)) = (self.max_request_size, &spec) {
+            request_size::wrap_routes(
+                max_request_size,
+                &operations,
+                spec,
+                self.url_prefix.as_deref(),
+                &mut items,
+            );
+        }
+
+        if let (Some(rate_limiter), Some(spec)) = (&self.rate_limiter, &spec) {
+            rate_limit::wrap_routes(
+                rate_limiter,
+                &operations,
+                spec,
+                self.url_prefix.as_deref(),
+                &mut items,
+            );
+        }
+
         let route = items
             .into_iter()
             .fold(Route::new(), |route, (path, paths)| {
@@ -739,12 +1636,146 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
         #[cfg(feature = "cookie")]
         let route = route.with(cookie_jar_manager);
 
-        route.before(extract_query).map_to_response().boxed()
+        #[cfg(feature = "i18n")]
+        let route = crate::i18n::LocalizeErrorEndpoint {
+            inner: route,
+            resources: self.i18n_resources,
+        };
+
+        ErrorMappingEndpoint {
+            inner: ConditionalEndpoint {
+                inner: route.before(extract_query),
+            },
+            mappings: self.error_mappings,
+        }
+        .boxed()
     }
 }
 
+/// Returns the versions of the operations, in the order they are declared.
+fn operation_versions(apis: &[MetaApi]) -> Vec<&'static str> {
+    let mut versions = Vec::new();
+    for version in apis
+        .iter()
+        .flat_map(|api| &api.paths)
+        .flat_map(|path| &path.operations)
+        .flat_map(|operation| &operation.versions)
+    {
+        if !versions.contains(version) {
+            versions.push(*version);
+        }
+    }
+    versions
+}
+
+/// Returns the operations of a version, with the paths prefixed with the
+/// version.
+///
+/// If `shared` is `true`, the operations without versions are contained in the
+/// version, unless it has an operation with the same path and method.
+fn version_apis(apis: Vec<MetaApi>, version: &str, shared: bool) -> Vec<MetaApi> {
+    let prefix = format!("/{version}");
+    let overridden = apis
+        .iter()
+        .flat_map(|api| &api.paths)
+        .flat_map(|path| {
+            path.operations
+                .iter()
+                .filter(|operation| operation.versions.contains(&version))
+                .map(|operation| (path.path.clone(), operation.method.clone()))
+        })
+        .collect::<HashSet<_>>();
+
+    apis.into_iter()
+        .map(|api| MetaApi {
+            paths: api
+                .paths
+                .into_iter()
+                .filter_map(|mut path| {
+                    path.operations
+                        .retain(|operation| match operation.versions.is_empty() {
+                            true => {
+                                shared
+                                    && !overridden
+                                        .contains(&(path.path.clone(), operation.method.clone()))
+                            }
+                            false => operation.versions.contains(&version),
+                        });
+                    (!path.operations.is_empty()).then(|| MetaPath {
+                        path: join_path(&prefix, &path.path),
+                        operations: path.operations,
+                    })
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Mounts the routes of the operations without versions under the path of
+/// each version, unless the version has an operation with the same path and
+/// method.
+/// Returns the operations of the APIs by their names in the specification,
+/// e.g. `GET /users/{id}`.
+fn operations_by_name(
+    apis: Vec<MetaApi>,
+    url_prefix: Option<&str>,
+) -> HashMap<String, MetaOperation> {
+    let mut operations = HashMap::new();
+    for path in apis.into_iter().flat_map(|api| api.paths) {
+        for operation in path.operations {
+            operations.insert(
+                format!(
+                    "{} {}{}",
+                    operation.method,
+                    url_prefix.unwrap_or_default(),
+                    path.path
+                ),
+                operation,
+            );
+        }
+    }
+    operations
+}
+
+fn mount_versions(
+    apis: &[MetaApi],
+    versions: &[String],
+    routes: HashMap<String, HashMap<Method, BoxEndpoint<'static>>>,
+) -> HashMap<String, HashMap<Method, BoxEndpoint<'static>>> {
+    let prefixes = versions
+        .iter()
+        .map(String::as_str)
+        .chain(operation_versions(apis))
+        .map(|version| format!("/{version}"))
+        .collect::<Vec<_>>();
+    let (versioned, shared): (HashMap<_, _>, HashMap<_, _>) =
+        routes.into_iter().partition(|(path, _)| {
+            prefixes.iter().any(|prefix| {
+                path.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+        });
+
+    let mut routes = versioned;
+    for (path, methods) in shared {
+        for (method, ep) in methods {
+            let ep = Arc::new(ep);
+            for version in versions {
+                routes
+                    .entry(join_path(&format!("/{version}"), &path))
+                    .or_default()
+                    .entry(method.clone())
+                    .or_insert_with(|| ep.clone().boxed());
+            }
+        }
+    }
+    routes
+}
+
 #[cfg(test)]
 mod tests {
+    use serde_json::Value;
+
     use super::*;
     use crate::OpenApi;
 
@@ -816,4 +1847,96 @@ mod tests {
         assert!(params[2].deprecated);
         assert_eq!(params[2].schema, f32::schema_ref());
     }
+
+    #[test]
+    fn openapi_v3_1() {
+        use serde_json::json;
+
+        use crate::{Object, payload::Json};
+
+        /// A
+        #[derive(Object)]
+        #[oai(internal)]
+        struct A {
+            #[oai(nullable, validator(maximum(value = "10", exclusive)))]
+            n: i32,
+            #[oai(nullable)]
+            b: Option<B>,
+        }
+
+        #[derive(Object)]
+        #[oai(internal, example)]
+        struct B {
+            #[oai(validator(minimum(value = "1")))]
+            value: i32,
+        }
+
+        impl crate::types::Example for B {
+            fn example() -> Self {
+                B { value: 1 }
+            }
+        }
+
+        struct Api;
+
+        #[OpenApi(internal)]
+        impl Api {
+            #[oai(path = "/", method = "post")]
+            async fn test(&self, _a: Json<A>) {}
+        }
+
+        let api_service = OpenApiService::new(Api, "demo", "1.0");
+        let spec: Value = serde_json::from_str(&api_service.spec()).unwrap();
+        assert_eq!(spec["openapi"], "3.0.0");
+        assert_eq!(
+            spec["components"]["schemas"]["A"]["properties"]["n"]["nullable"],
+            true
+        );
+
+        let api_service = api_service.openapi_version(OpenApiVersion::V3_1);
+        let spec: Value = serde_json::from_str(&api_service.spec()).unwrap();
+        assert_eq!(spec["openapi"], "3.1.0");
+        assert_eq!(
+            spec["jsonSchemaDialect"],
+            "https://spec.openapis.org/oas/3.1/dialect/base"
+        );
+        assert_eq!(
+            spec["components"]["schemas"]["A"]["properties"]["n"],
+            json!({
+                "type": ["integer", "null"],
+                "format": "int32",
+                "exclusiveMaximum": 10.0,
+            })
+        );
+        assert_eq!(
+            spec["components"]["schemas"]["A"]["properties"]["b"],
+            json!({
+                "anyOf": [
+                    { "$ref": "#/components/schemas/B" },
+                    { "type": "null" },
+                ],
+            })
+        );
+        assert_eq!(
+            spec["components"]["schemas"]["B"]["examples"],
+            json!([{ "value": 1 }])
+        );
+        assert_eq!(
+            spec["components"]["schemas"]["B"]["properties"]["value"]["minimum"],
+            1.0
+        );
+        assert_eq!(
+            spec["paths"]["/"]["post"]["requestBody"]["content"]["application/json; charset=utf-8"]
+                ["schema"],
+            json!({ "$ref": "#/components/schemas/A" })
+        );
+
+        // the YAML output is converted as well
+        let spec: Value = serde_yaml::from_str(&api_service.spec_yaml()).unwrap();
+        assert_eq!(spec["openapi"], "3.1.0");
+        assert_eq!(
+            spec["components"]["schemas"]["A"]["properties"]["n"]["type"],
+            json!(["integer", "null"])
+        );
+    }
 }
@@ -0,0 +1,117 @@
+//! Helper types for the paginated operations.
+//!
+//! The [`PaginationParams`] are extracted with [`Query`](crate::param::Query),
+//! e.g. `/users?limit=10&offset=20`, and the items are returned in a [`Page`]
+//! or a [`CursorPage`].
+//!
+//! # Examples
+//!
+//! ```
+//! use poem_openapi::{
+//!     OpenApi,
+//!     pagination::{Page, PaginationParams},
+//!     param::Query,
+//!     payload::Json,
+//! };
+//!
+//! struct Api {
+//!     users: Vec<String>,
+//! }
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/users", method = "get")]
+//!     async fn users(&self, Query(params): Query<PaginationParams>) -> Json<Page<String>> {
+//!         Json(Page::new(
+//!             self.users
+//!                 .iter()
+//!                 .skip(params.offset as usize)
+//!                 .take(params.limit as usize)
+//!                 .cloned()
+//!                 .collect(),
+//!             self.users.len() as u64,
+//!         ))
+//!     }
+//! }
+//! ```
+
+use crate::{
+    Object,
+    types::{ParseFromJSON, ToJSON},
+};
+
+/// The default number of items of a page.
+pub const DEFAULT_LIMIT: u32 = 20;
+
+/// The maximum number of items of a page.
+pub const MAX_LIMIT: u32 = 100;
+
+fn default_limit() -> u32 {
+    DEFAULT_LIMIT
+}
+
+/// The pagination parameters of the query string.
+///
+/// The pages are selected with `limit` and `offset`, or with `limit` and the
+/// `cursor` returned by the previous page. The `limit` must be between 1 and
+/// 100, and defaults to 20.
+#[derive(Debug, Clone, PartialEq, Eq, Object)]
+#[oai(internal, parameter)]
+pub struct PaginationParams {
+    /// The maximum number of items to return.
+    #[oai(
+        default = "default_limit",
+        validator(minimum(value = "1"), maximum(value = "100"))
+    )]
+    pub limit: u32,
+    /// The number of items to skip.
+    #[oai(default)]
+    pub offset: u64,
+    /// The cursor returned by the previous page.
+    pub cursor: Option<String>,
+}
+
+impl Default for PaginationParams {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            offset: 0,
+            cursor: None,
+        }
+    }
+}
+
+/// A page of items selected with an offset.
+#[derive(Debug, Clone, PartialEq, Eq, Object)]
+#[oai(internal, rename = "{T}Page")]
+pub struct Page<T: ParseFromJSON + ToJSON> {
+    /// The items of the page.
+    pub items: Vec<T>,
+    /// The total number of items.
+    pub total: u64,
+}
+
+impl<T: ParseFromJSON + ToJSON> Page<T> {
+    /// Create a page of items.
+    pub fn new(items: Vec<T>, total: u64) -> Self {
+        Self { items, total }
+    }
+}
+
+/// A page of items selected with a cursor.
+#[derive(Debug, Clone, PartialEq, Eq, Object)]
+#[oai(internal, rename = "{T}CursorPage")]
+pub struct CursorPage<T: ParseFromJSON + ToJSON> {
+    /// The items of the page.
+    pub items: Vec<T>,
+    /// The cursor of the next page, it is absent on the last page.
+    #[oai(skip_serializing_if_is_none)]
+    pub next_cursor: Option<String>,
+}
+
+impl<T: ParseFromJSON + ToJSON> CursorPage<T> {
+    /// Create a page of items.
+    pub fn new(items: Vec<T>, next_cursor: Option<String>) -> Self {
+        Self { items, next_cursor }
+    }
+}
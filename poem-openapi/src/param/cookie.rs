@@ -123,10 +123,16 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for CookiePrivate<T> {
         _body: &mut RequestBody,
         param_opts: ExtractParamOptions<Self::ParamType>,
     ) -> Result<Self> {
-        let value = request
-            .cookie()
+        let cookie_jar = request.cookie();
+        let value = cookie_jar
             .private()
             .get_value(param_opts.name, param_opts.ignore_case);
+        check_tampered(
+            cookie_jar,
+            value.as_deref(),
+            &param_opts,
+            "the cookie cannot be decrypted",
+        )?;
         let value = match (value, &param_opts.default_value) {
             (Some(value), _) => Some(value),
             (None, Some(default_value)) => return Ok(Self(default_value())),
@@ -136,10 +142,10 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for CookiePrivate<T> {
         ParseFromParameter::parse_from_parameters(value.as_deref())
             .map(Self)
             .map_err(|err| {
-                dbg!(ParseParamError {
+                ParseParamError {
                     name: param_opts.name,
                     reason: err.into_message(),
-                })
+                }
                 .into()
             })
     }
@@ -190,10 +196,16 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for CookieSigned<T> {
         _body: &mut RequestBody,
         param_opts: ExtractParamOptions<Self::ParamType>,
     ) -> Result<Self> {
-        let value = request
-            .cookie()
+        let cookie_jar = request.cookie();
+        let value = cookie_jar
             .signed()
             .get_value(param_opts.name, param_opts.ignore_case);
+        check_tampered(
+            cookie_jar,
+            value.as_deref(),
+            &param_opts,
+            "the signature of the cookie is invalid",
+        )?;
         let value = match (value, &param_opts.default_value) {
             (Some(value), _) => Some(value),
             (None, Some(default_value)) => return Ok(Self(default_value())),
@@ -212,6 +224,28 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for CookieSigned<T> {
     }
 }
 
+/// Returns an error if the cookie is sent but cannot be verified, instead of
+/// treating it as missing.
+fn check_tampered<T>(
+    cookie_jar: &CookieJar,
+    value: Option<&str>,
+    param_opts: &ExtractParamOptions<T>,
+    reason: &str,
+) -> Result<()> {
+    if value.is_none()
+        && cookie_jar
+            .get_value(param_opts.name, param_opts.ignore_case)
+            .is_some()
+    {
+        return Err(ParseParamError {
+            name: param_opts.name,
+            reason: reason.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 trait GetValueFromCookie {
     fn get_value(&self, name: &str, ignore_case: bool) -> Option<String>;
 }
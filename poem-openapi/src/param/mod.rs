@@ -4,6 +4,7 @@ mod cookie;
 mod header;
 mod path;
 mod query;
+pub(crate) mod style;
 
 #[cfg(feature = "cookie")]
 pub use cookie::{Cookie, CookiePrivate, CookieSigned};
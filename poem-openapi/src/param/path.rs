@@ -5,6 +5,7 @@ use poem::{Request, RequestBody, Result};
 use crate::{
     ApiExtractor, ApiExtractorType, ExtractParamOptions,
     error::ParsePathError,
+    param::style::{StyleValue, decode_path, split_properties},
     registry::{MetaParamIn, MetaSchemaRef, Registry},
     types::ParseFromParameter,
 };
@@ -63,14 +64,36 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for Path<T> {
             (None, _) => None,
         };
 
-        ParseFromParameter::parse_from_parameters(value)
-            .map(Self)
-            .map_err(|err| {
-                ParsePathError {
-                    name: param_opts.name,
-                    reason: err.into_message(),
+        let parsed = match (param_opts.style, value) {
+            (Some(style), Some(value)) => {
+                match decode_path(value, style, param_opts.explode, T::IS_OBJECT) {
+                    Ok(StyleValue::Values(values)) => {
+                        ParseFromParameter::parse_from_parameters(values)
+                    }
+                    Ok(StyleValue::Properties(properties)) => {
+                        ParseFromParameter::parse_from_properties(&properties)
+                    }
+                    Err(reason) => {
+                        return Err(ParsePathError {
+                            name: param_opts.name,
+                            reason,
+                        }
+                        .into());
+                    }
                 }
-                .into()
-            })
+            }
+            (None, Some(value)) if T::IS_OBJECT => {
+                ParseFromParameter::parse_from_properties(&split_properties(value))
+            }
+            _ => ParseFromParameter::parse_from_parameters(value),
+        };
+
+        parsed.map(Self).map_err(|err| {
+            ParsePathError {
+                name: param_opts.name,
+                reason: err.into_message(),
+            }
+            .into()
+        })
     }
 }
@@ -4,9 +4,10 @@ use itertools::Either;
 use poem::{Request, RequestBody, Result};
 
 use crate::{
-    ApiExtractor, ApiExtractorType, ExtractParamOptions,
+    ApiExtractor, ApiExtractorType, ExtractParamOptions, ParameterStyle,
     base::UrlQuery,
     error::ParseParamError,
+    param::style::{deep_object_properties, query_separator, split_properties},
     registry::{MetaParamIn, MetaSchemaRef, Registry},
     types::ParseFromParameter,
 };
@@ -64,6 +65,45 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for Query<T> {
         }
         .peekable();
 
+        if T::IS_OBJECT {
+            let properties = match (param_opts.style, param_opts.explode) {
+                (Some(ParameterStyle::DeepObject), _) => {
+                    deep_object_properties(url_query, param_opts.name, param_opts.ignore_case)
+                }
+                // the properties are the parameters of the query string
+                (None | Some(ParameterStyle::Form), true) => url_query
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+                    .collect(),
+                (None | Some(ParameterStyle::Form), false) => values
+                    .next()
+                    .map(|value| split_properties(value))
+                    .unwrap_or_default(),
+                (Some(style), _) => {
+                    return Err(ParseParamError {
+                        name: param_opts.name,
+                        reason: format!(
+                            "the `{style:?}` style is not supported by object parameters"
+                        ),
+                    }
+                    .into());
+                }
+            };
+
+            return match &param_opts.default_value {
+                Some(default_value) if properties.is_empty() => Ok(Self(default_value())),
+                _ => ParseFromParameter::parse_from_properties(&properties)
+                    .map(Self)
+                    .map_err(|err| {
+                        ParseParamError {
+                            name: param_opts.name,
+                            reason: err.into_message(),
+                        }
+                        .into()
+                    }),
+            };
+        }
+
         match &param_opts.default_value {
             Some(default_value) if values.peek().is_none() => {
                 return Ok(Self(default_value()));
@@ -82,7 +122,8 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for Query<T> {
                     .into()
                 })
         } else {
-            let values = values.next().unwrap().split(',').map(|v| v.trim());
+            let separator = query_separator(param_opts.style);
+            let values = values.next().unwrap().split(separator).map(|v| v.trim());
             ParseFromParameter::parse_from_parameters(values)
                 .map(Self)
                 .map_err(|err| {
@@ -0,0 +1,109 @@
+use crate::ParameterStyle;
+
+/// Splits the properties of a non-exploded object parameter, e.g.
+/// `role,admin,firstName,Alex`.
+pub fn split_properties(value: &str) -> Vec<(&str, &str)> {
+    let mut items = value.split(',');
+    let mut properties = Vec::new();
+    while let Some(name) = items.next() {
+        properties.push((name, items.next().unwrap_or_default()));
+    }
+    properties
+}
+
+/// Splits the properties of an exploded object parameter, e.g.
+/// `role=admin,firstName=Alex`.
+fn split_exploded_properties(value: &str, separator: char) -> Vec<(&str, &str)> {
+    value
+        .split(separator)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.split_once('=').unwrap_or((item, "")))
+        .collect()
+}
+
+/// The decoded value of a parameter.
+pub(crate) enum StyleValue<'a> {
+    Values(Vec<&'a str>),
+    Properties(Vec<(&'a str, &'a str)>),
+}
+
+/// Decodes the value of a path parameter serialized with the `simple`,
+/// `label` or `matrix` style.
+pub(crate) fn decode_path<'a>(
+    value: &'a str,
+    style: ParameterStyle,
+    explode: bool,
+    is_object: bool,
+) -> Result<StyleValue<'a>, String> {
+    match style {
+        ParameterStyle::Simple => Ok(match (is_object, explode) {
+            (true, true) => StyleValue::Properties(split_exploded_properties(value, ',')),
+            (true, false) => StyleValue::Properties(split_properties(value)),
+            (false, _) => StyleValue::Values(value.split(',').collect()),
+        }),
+        ParameterStyle::Label => {
+            let value = value
+                .strip_prefix('.')
+                .ok_or_else(|| "expected a `.` prefix".to_string())?;
+            Ok(match (is_object, explode) {
+                (true, true) => StyleValue::Properties(split_exploded_properties(value, '.')),
+                (true, false) => StyleValue::Properties(split_properties(value)),
+                (false, true) => StyleValue::Values(value.split('.').collect()),
+                (false, false) => StyleValue::Values(value.split(',').collect()),
+            })
+        }
+        ParameterStyle::Matrix => {
+            let value = value
+                .strip_prefix(';')
+                .ok_or_else(|| "expected a `;` prefix".to_string())?;
+            // the path segment only contains this parameter, so the names are not
+            // checked, as the route does not know the name of the parameter
+            let strip_name = |item: &'a str| {
+                item.split_once('=')
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| "expected a `name=value` pair".to_string())
+            };
+            Ok(match (is_object, explode) {
+                (true, true) => StyleValue::Properties(split_exploded_properties(value, ';')),
+                (true, false) => StyleValue::Properties(split_properties(strip_name(value)?)),
+                (false, true) => {
+                    StyleValue::Values(value.split(';').map(strip_name).collect::<Result<_, _>>()?)
+                }
+                (false, false) => StyleValue::Values(strip_name(value)?.split(',').collect()),
+            })
+        }
+        _ => Err(format!(
+            "the `{style:?}` style is not supported by path parameters"
+        )),
+    }
+}
+
+/// Returns the properties of a query parameter serialized with the
+/// `deepObject` style, e.g. `filter[role]=admin&filter[firstName]=Alex`.
+pub(crate) fn deep_object_properties<'a>(
+    query: &'a [(String, String)],
+    name: &str,
+    ignore_case: bool,
+) -> Vec<(&'a str, &'a str)> {
+    query
+        .iter()
+        .filter_map(|(key, value)| {
+            let (prefix, property) = key.strip_suffix(']')?.split_once('[')?;
+            let matched = match ignore_case {
+                true => prefix.eq_ignore_ascii_case(name),
+                false => prefix == name,
+            };
+            matched.then_some((property, value.as_str()))
+        })
+        .collect()
+}
+
+/// Returns the separator of the array values of a non-exploded query
+/// parameter.
+pub(crate) fn query_separator(style: Option<ParameterStyle>) -> char {
+    match style {
+        Some(ParameterStyle::SpaceDelimited) => ' ',
+        Some(ParameterStyle::PipeDelimited) => '|',
+        _ => ',',
+    }
+}
@@ -24,6 +24,18 @@ pub fn join_path(base: &str, path: &str) -> String {
     }
 }
 
+/// Converts the path parameters from `:name` to `{name}`.
+#[doc(hidden)]
+pub fn convert_path_params(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{name}}}"),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,4 +54,11 @@ mod tests {
         assert_eq!(join_path("/abc/", "/def"), "/abc/def");
         assert_eq!(join_path("/abc/", "/def/"), "/abc/def/");
     }
+
+    #[test]
+    fn test_convert_path_params() {
+        assert_eq!(convert_path_params("/abc"), "/abc");
+        assert_eq!(convert_path_params("/v1/:id/abc"), "/v1/{id}/abc");
+        assert_eq!(convert_path_params(":a/:b"), "{a}/{b}");
+    }
 }
@@ -1,6 +1,13 @@
-use std::fmt::Write;
+use std::{fmt::Write, path::Path};
 
-use poem::{Body, IntoResponse, Response, http::header::CONTENT_DISPOSITION};
+use poem::{
+    Body, IntoResponse, Response,
+    http::{
+        HeaderValue,
+        header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE},
+    },
+};
+use tokio::io::AsyncRead;
 
 use crate::{
     ApiResponse,
@@ -10,6 +17,7 @@ use crate::{
 };
 
 const CONTENT_DISPOSITION_DESC: &str = "Indicate if the content is expected to be displayed inline in the browser, that is, as a Web page or as part of a Web page, or as an attachment, that is downloaded and saved locally.";
+const CONTENT_LENGTH_DESC: &str = "The size of the file in bytes, if it is known in advance.";
 
 /// Attachment type
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -32,11 +40,43 @@ impl AttachmentType {
 }
 
 /// A binary payload for download file.
+///
+/// The data can be anything that converts into a [`Body`], including a
+/// stream created with [`Attachment::from_async_read`] or a file opened with
+/// [`Attachment::open`].
+///
+/// # Examples
+///
+/// ```
+/// use poem::{Body, Result, error::NotFound};
+/// use poem_openapi::{
+///     OpenApi,
+///     param::Path,
+///     payload::{Attachment, AttachmentType},
+/// };
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/reports/:name", method = "get")]
+///     async fn report(&self, name: Path<String>) -> Result<Attachment<Body>> {
+///         let attachment = Attachment::open(format!("reports/{}.pdf", name.0))
+///             .await
+///             .map_err(NotFound)?;
+///         Ok(attachment
+///             .content_type("application/pdf")
+///             .attachment_type(AttachmentType::Inline))
+///     }
+/// }
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Attachment<T> {
     data: Binary<T>,
     ty: AttachmentType,
     filename: Option<String>,
+    content_type: Option<String>,
+    content_length: Option<u64>,
 }
 
 impl<T: Into<Body> + Send> Attachment<T> {
@@ -46,16 +86,22 @@ impl<T: Into<Body> + Send> Attachment<T> {
             data: Binary(data),
             ty: AttachmentType::Attachment,
             filename: None,
+            content_type: None,
+            content_length: None,
         }
     }
 
-    /// Specify the attachment. (defaults to: [`AttachmentType::Inline`])
+    /// Specify the attachment. (defaults to: [`AttachmentType::Attachment`])
     #[must_use]
     pub fn attachment_type(self, ty: AttachmentType) -> Self {
         Self { ty, ..self }
     }
 
     /// Specify the file name.
+    ///
+    /// Names that are not plain ASCII are sent with the `filename*` parameter
+    /// of [RFC 5987](https://www.rfc-editor.org/rfc/rfc5987), along with an
+    /// ASCII fallback for older clients.
     #[must_use]
     pub fn filename(self, filename: impl Into<String>) -> Self {
         Self {
@@ -64,23 +110,87 @@ impl<T: Into<Body> + Send> Attachment<T> {
         }
     }
 
+    /// Specify the content type of the file. (defaults to:
+    /// `application/octet-stream`)
+    #[must_use]
+    pub fn content_type(self, content_type: impl Into<String>) -> Self {
+        Self {
+            content_type: Some(content_type.into()),
+            ..self
+        }
+    }
+
+    /// Specify the size of the file in bytes, so that the response is sent
+    /// with a `Content-Length` header even if the data is streamed.
+    #[must_use]
+    pub fn content_length(self, len: u64) -> Self {
+        Self {
+            content_length: Some(len),
+            ..self
+        }
+    }
+
     fn content_disposition(&self) -> String {
         let mut content_disposition = self.ty.as_str().to_string();
 
-        if let Some(legal_filename) = self.filename.as_ref().map(|filename| {
-            filename
+        if let Some(filename) = &self.filename {
+            let legal_filename = filename
+                .chars()
+                .map(|c| if c.is_ascii() { c } else { '_' })
+                .collect::<String>()
                 .replace('\\', "\\\\")
                 .replace('\"', "\\\"")
                 .replace('\r', "\\\r")
-                .replace('\n', "\\\n")
-        }) {
+                .replace('\n', "\\\n");
             _ = write!(content_disposition, "; filename=\"{legal_filename}\"");
+
+            if !filename.is_ascii() {
+                _ = write!(
+                    content_disposition,
+                    "; filename*=UTF-8''{}",
+                    encode_ext_value(filename)
+                );
+            }
         }
 
         content_disposition
     }
 }
 
+impl Attachment<Body> {
+    /// Create an attachment that streams the data from an [`AsyncRead`].
+    pub fn from_async_read(reader: impl AsyncRead + Send + 'static) -> Self {
+        Self::new(Body::from_async_read(reader))
+    }
+
+    /// Open a file and create an attachment that streams its content.
+    ///
+    /// The file name and the content length are taken from the file.
+    pub async fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+        let mut attachment = Self::from_async_read(file).content_length(len);
+        if let Some(filename) = path.file_name() {
+            attachment = attachment.filename(filename.to_string_lossy());
+        }
+        Ok(attachment)
+    }
+}
+
+/// Percent-encodes a value for the `ext-value` syntax of RFC 5987.
+fn encode_ext_value(value: &str) -> String {
+    let mut encoded = String::new();
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+            encoded.push(b as char);
+        } else {
+            _ = write!(encoded, "%{b:02X}");
+        }
+    }
+    encoded
+}
+
 impl<T: Into<Body> + Send> Payload for Attachment<T> {
     const CONTENT_TYPE: &'static str = Binary::<T>::CONTENT_TYPE;
 
@@ -92,9 +202,20 @@ impl<T: Into<Body> + Send> Payload for Attachment<T> {
 impl<T: Into<Body> + Send> IntoResponse for Attachment<T> {
     fn into_response(self) -> Response {
         let content_disposition = self.content_disposition();
-        self.data
+        let mut resp = self
+            .data
             .with_header(CONTENT_DISPOSITION, content_disposition)
-            .into_response()
+            .into_response();
+        if let Some(content_type) = self
+            .content_type
+            .and_then(|content_type| HeaderValue::try_from(content_type).ok())
+        {
+            resp.headers_mut().insert(CONTENT_TYPE, content_type);
+        }
+        if let Some(len) = self.content_length {
+            resp.headers_mut().insert(CONTENT_LENGTH, len.into());
+        }
+        resp
     }
 }
 
@@ -108,14 +229,26 @@ impl<T: Into<Body> + Send> ApiResponse for Attachment<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
-                headers: vec![MetaHeader {
-                    name: "Content-Disposition".to_string(),
-                    description: Some(CONTENT_DISPOSITION_DESC.to_string()),
-                    required: true,
-                    deprecated: false,
-                    schema: String::schema_ref(),
-                }],
+                headers: vec![
+                    MetaHeader {
+                        name: "Content-Disposition".to_string(),
+                        description: Some(CONTENT_DISPOSITION_DESC.to_string()),
+                        required: true,
+                        deprecated: false,
+                        schema: String::schema_ref(),
+                    },
+                    MetaHeader {
+                        name: "Content-Length".to_string(),
+                        description: Some(CONTENT_LENGTH_DESC.to_string()),
+                        required: false,
+                        deprecated: false,
+                        schema: u64::schema_ref(),
+                    },
+                ],
+                links: vec![],
             }],
         }
     }
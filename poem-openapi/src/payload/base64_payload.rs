@@ -153,8 +153,11 @@ impl<T: AsRef<[u8]> + Send> ApiResponse for Base64<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
@@ -141,8 +141,11 @@ impl<T: Into<Body> + Send> ApiResponse for Binary<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
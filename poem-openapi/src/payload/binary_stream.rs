@@ -0,0 +1,166 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::Error as IoError,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use bytes::{Buf, Bytes};
+use futures_util::{Stream, StreamExt, stream::BoxStream};
+use poem::{Body, IntoResponse, Request, RequestBody, Response, Result};
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::{
+    ApiResponse,
+    payload::{ParsePayload, Payload},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+};
+
+/// A streaming binary payload.
+///
+/// As a request payload, the body is not buffered, it is read as fast as
+/// the handler consumes it with [`AsyncRead`] or as a [`Stream`] of chunks.
+/// This allows large uploads to be forwarded without holding them in
+/// memory, unlike [`Binary<Vec<u8>>`](crate::payload::Binary).
+///
+/// As a response payload, the chunks of the stream are written to the body.
+///
+/// # Examples
+///
+/// ```
+/// use poem::{Result, error::InternalServerError};
+/// use poem_openapi::{
+///     OpenApi,
+///     payload::{BinaryStream, Json},
+/// };
+/// use tokio::io::AsyncReadExt;
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/upload", method = "post")]
+///     async fn upload(&self, mut data: BinaryStream) -> Result<Json<usize>> {
+///         let mut buf = [0; 8192];
+///         let mut size = 0;
+///         loop {
+///             let n = data.read(&mut buf).await.map_err(InternalServerError)?;
+///             if n == 0 {
+///                 break;
+///             }
+///             // write the chunk to the object storage
+///             size += n;
+///         }
+///         Ok(Json(size))
+///     }
+/// }
+/// ```
+pub struct BinaryStream {
+    stream: BoxStream<'static, Result<Bytes, IoError>>,
+    /// The remaining bytes of the last chunk that was partially read.
+    chunk: Bytes,
+}
+
+impl BinaryStream {
+    /// Create a binary stream payload.
+    pub fn new(stream: impl Stream<Item = Result<Bytes, IoError>> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+            chunk: Bytes::new(),
+        }
+    }
+
+    /// Create a binary stream payload from an [`AsyncRead`].
+    pub fn from_async_read(reader: impl AsyncRead + Send + 'static) -> Self {
+        Self::new(Body::from_async_read(reader).into_bytes_stream())
+    }
+}
+
+impl Debug for BinaryStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BinaryStream").finish_non_exhaustive()
+    }
+}
+
+impl Stream for BinaryStream {
+    type Item = Result<Bytes, IoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if !self.chunk.is_empty() {
+            return Poll::Ready(Some(Ok(std::mem::take(&mut self.chunk))));
+        }
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl AsyncRead for BinaryStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        while self.chunk.is_empty() {
+            match ready!(self.stream.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => self.chunk = chunk,
+                Some(Err(err)) => return Poll::Ready(Err(err)),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+        let len = self.chunk.len().min(buf.remaining());
+        buf.put_slice(&self.chunk[..len]);
+        self.chunk.advance(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Payload for BinaryStream {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+
+    fn check_content_type(_content_type: &str) -> bool {
+        true
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "binary")))
+    }
+}
+
+impl ParsePayload for BinaryStream {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(_request: &Request, body: &mut RequestBody) -> Result<Self> {
+        Ok(Self::new(body.take()?.into_bytes_stream()))
+    }
+}
+
+impl IntoResponse for BinaryStream {
+    fn into_response(self) -> Response {
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(Body::from_bytes_stream(self))
+    }
+}
+
+impl ApiResponse for BinaryStream {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                status_range: None,
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+impl_apirequest_for_payload!(BinaryStream);
@@ -0,0 +1,220 @@
+use std::time::SystemTime;
+
+use poem::{
+    Endpoint, Error, IntoResponse, Request, Result,
+    endpoint::ShutdownHooks,
+    http::{Method, StatusCode, header},
+    web::headers::{ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch, LastModified},
+};
+
+use crate::{
+    ApiResponse,
+    registry::{MetaHeader, MetaResponse, MetaResponses, Registry},
+    types::Type,
+};
+
+/// A response type wrapper that answers the conditional requests.
+///
+/// It adds the `ETag` and `Last-Modified` headers to the response, and the
+/// `GET` and `HEAD` requests with a matching `If-None-Match` or
+/// `If-Modified-Since` header are answered with `304 Not Modified` without
+/// the body.
+///
+/// # Examples
+///
+/// ```
+/// use poem::{http::StatusCode, test::TestClient};
+/// use poem_openapi::{
+///     OpenApi, OpenApiService,
+///     payload::{Conditional, Json},
+/// };
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/test", method = "get")]
+///     async fn test(&self) -> Conditional<Json<i32>> {
+///         Conditional::new(Json(100)).etag("v1")
+///     }
+/// }
+///
+/// let api = OpenApiService::new(MyApi, "Demo", "0.1.0");
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let cli = TestClient::new(api);
+///
+/// let resp = cli.get("/test").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_header("etag", "\"v1\"");
+///
+/// let resp = cli
+///     .get("/test")
+///     .header("if-none-match", "\"v1\"")
+///     .send()
+///     .await;
+/// resp.assert_status(StatusCode::NOT_MODIFIED);
+/// # });
+/// ```
+pub struct Conditional<T> {
+    inner: T,
+    etag: Option<ETag>,
+    last_modified: Option<SystemTime>,
+}
+
+impl<T> Conditional<T> {
+    /// Create a conditional response object.
+    #[must_use]
+    pub fn new(resp: T) -> Self {
+        Self {
+            inner: resp,
+            etag: None,
+            last_modified: None,
+        }
+    }
+
+    /// Sets the entity tag of the response.
+    ///
+    /// The tag is quoted if it isn't already, a weak tag can be specified
+    /// with the `W/"..."` syntax.
+    #[must_use]
+    pub fn etag(self, etag: impl AsRef<str>) -> Self {
+        let etag = etag.as_ref();
+        let etag = match etag.starts_with('"') || etag.starts_with("W/\"") {
+            true => etag.parse(),
+            false => format!("\"{etag}\"").parse(),
+        };
+        Self {
+            etag: etag.ok(),
+            ..self
+        }
+    }
+
+    /// Sets the time the resource was last modified.
+    #[must_use]
+    pub fn last_modified(self, last_modified: SystemTime) -> Self {
+        Self {
+            last_modified: Some(last_modified),
+            ..self
+        }
+    }
+}
+
+/// Marks the responses of [`Conditional`], with the validators to check.
+#[derive(Clone)]
+struct Validators {
+    etag: Option<ETag>,
+    last_modified: Option<SystemTime>,
+}
+
+impl<T: IntoResponse> IntoResponse for Conditional<T> {
+    fn into_response(self) -> poem::Response {
+        let mut resp = self.inner.into_response();
+        if let Some(etag) = &self.etag {
+            resp.headers_mut().typed_insert(etag.clone());
+        }
+        if let Some(last_modified) = self.last_modified {
+            resp.headers_mut()
+                .typed_insert(LastModified::from(last_modified));
+        }
+        resp.set_data(Validators {
+            etag: self.etag,
+            last_modified: self.last_modified,
+        });
+        resp
+    }
+}
+
+impl<T: ApiResponse> ApiResponse for Conditional<T> {
+    const BAD_REQUEST_HANDLER: bool = T::BAD_REQUEST_HANDLER;
+
+    fn meta() -> MetaResponses {
+        let mut meta = T::meta();
+        for resp in &mut meta.responses {
+            let is_success = resp
+                .status
+                .is_some_and(|status| (200..300).contains(&status))
+                || resp.status_range.as_deref() == Some("2XX");
+            if is_success {
+                resp.headers.push(MetaHeader {
+                    name: "ETag".to_string(),
+                    description: Some("The entity tag of the resource.".to_string()),
+                    required: false,
+                    deprecated: false,
+                    schema: String::schema_ref(),
+                });
+                resp.headers.push(MetaHeader {
+                    name: "Last-Modified".to_string(),
+                    description: Some("The time the resource was last modified.".to_string()),
+                    required: false,
+                    deprecated: false,
+                    schema: String::schema_ref(),
+                });
+            }
+        }
+        if !meta.responses.iter().any(|resp| resp.status == Some(304)) {
+            meta.responses.push(MetaResponse {
+                description: "Not modified",
+                status: Some(304),
+                status_range: None,
+                content: vec![],
+                headers: vec![],
+                links: vec![],
+            });
+        }
+        meta
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+
+    fn from_parse_request_error(err: Error) -> Self {
+        Self::new(T::from_parse_request_error(err))
+    }
+}
+
+/// An endpoint that answers the conditional requests to the operations that
+/// return [`Conditional`].
+pub(crate) struct ConditionalEndpoint<E> {
+    pub(crate) inner: E,
+}
+
+impl<E: Endpoint> Endpoint for ConditionalEndpoint<E> {
+    type Output = poem::Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let is_safe = matches!(*req.method(), Method::GET | Method::HEAD);
+        let if_none_match = req.headers().typed_get::<IfNoneMatch>();
+        let if_modified_since = req.headers().typed_get::<IfModifiedSince>();
+        let mut resp = self.inner.call(req).await?.into_response();
+
+        let Some(validators) = resp.data::<Validators>() else {
+            return Ok(resp);
+        };
+        if !is_safe || !resp.status().is_success() {
+            return Ok(resp);
+        }
+
+        // `If-Modified-Since` is ignored when `If-None-Match` is present
+        let not_modified = match (if_none_match, &validators.etag) {
+            (Some(if_none_match), Some(etag)) => !if_none_match.precondition_passes(etag),
+            (Some(_), None) => false,
+            (None, _) => match (if_modified_since, validators.last_modified) {
+                (Some(since), Some(last_modified)) => !since.is_modified(last_modified),
+                _ => false,
+            },
+        };
+        if not_modified {
+            resp.set_status(StatusCode::NOT_MODIFIED);
+            resp.headers_mut().remove(header::CONTENT_TYPE);
+            resp.headers_mut().remove(header::CONTENT_LENGTH);
+            resp.set_body(());
+        }
+        Ok(resp)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
@@ -0,0 +1,756 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::Error as IoError,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use csv_core::ReadRecordResult;
+use futures_util::{Stream, StreamExt, stream::BoxStream};
+use poem::{
+    Body, FromRequest, IntoResponse, Request, RequestBody, Response, Result, error::ReadBodyError,
+    http::StatusCode,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    ApiResponse,
+    error::ParseRequestPayloadError,
+    payload::{ParsePayload, Payload},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseFromJSON, ToJSON, Type},
+};
+
+const CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+#[derive(Debug, Copy, Clone)]
+struct CsvOptions {
+    delimiter: u8,
+    headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            headers: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Reads the options of a request from its content type, the header row
+    /// can be omitted with the `header=absent` parameter of
+    /// [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180).
+    fn from_request(request: &Request) -> Self {
+        let mut options = Self::default();
+        if let Some(mime) = request
+            .content_type()
+            .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
+        {
+            if mime.subtype() == "tab-separated-values" {
+                options.delimiter = b'\t';
+            }
+            if mime
+                .get_param("header")
+                .is_some_and(|header| header.as_str().eq_ignore_ascii_case("absent"))
+            {
+                options.headers = false;
+            }
+        }
+        options
+    }
+
+    fn content_type(&self) -> &'static str {
+        match (self.delimiter, self.headers) {
+            (b'\t', true) => "text/tab-separated-values; charset=utf-8",
+            (b'\t', false) => "text/tab-separated-values; charset=utf-8; header=absent",
+            (_, true) => CONTENT_TYPE,
+            (_, false) => "text/csv; charset=utf-8; header=absent",
+        }
+    }
+}
+
+fn check_content_type(content_type: &str) -> bool {
+    matches!(content_type.parse::<mime::Mime>(), Ok(content_type) if content_type.type_() == "text"
+            && matches!(content_type.subtype().as_str(), "csv" | "tab-separated-values"))
+}
+
+fn rows_schema_ref<T: Type>() -> MetaSchemaRef {
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        items: Some(Box::new(T::schema_ref())),
+        ..MetaSchema::new("array")
+    }))
+}
+
+/// Maps the properties of a row type to the columns of a CSV record.
+struct Columns {
+    /// The name and schema type of each column.
+    columns: Vec<(&'static str, &'static str)>,
+    /// `true` if the row type is not an object and is stored in a single
+    /// column.
+    scalar: bool,
+}
+
+fn parse_cell(ty: &str, cell: String) -> Value {
+    match ty {
+        "integer" | "number" | "boolean" | "array" | "object" => {
+            serde_json::from_str(&cell).unwrap_or(Value::String(cell))
+        }
+        _ => Value::String(cell),
+    }
+}
+
+fn schema_type(schema: &MetaSchemaRef, registry: &Registry) -> &'static str {
+    match schema {
+        MetaSchemaRef::Reference(name) => registry
+            .schemas
+            .get(name)
+            .map(|schema| schema.ty)
+            .unwrap_or("object"),
+        MetaSchemaRef::Inline(schema) if schema.ty.is_empty() && !schema.all_of.is_empty() => {
+            schema_type(&schema.all_of[0], registry)
+        }
+        MetaSchemaRef::Inline(schema) => schema.ty,
+    }
+}
+
+impl Columns {
+    fn new<T: Type>() -> Self {
+        let mut registry = Registry::new();
+        let schema = registry.create_fake_schema::<T>();
+        if schema.ty != "object" || schema.properties.is_empty() {
+            return Self {
+                columns: vec![("value", schema.ty)],
+                scalar: true,
+            };
+        }
+
+        Self {
+            columns: schema
+                .properties
+                .iter()
+                .map(|(name, schema)| (*name, schema_type(schema, &registry)))
+                .collect(),
+            scalar: false,
+        }
+    }
+
+    fn header(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|(name, _)| *name)
+    }
+
+    fn value_to_record(&self, value: Value) -> Vec<String> {
+        fn to_cell(value: Value) -> String {
+            match value {
+                Value::Null => String::new(),
+                Value::String(s) => s,
+                value => value.to_string(),
+            }
+        }
+
+        match value {
+            Value::Object(mut map) if !self.scalar => self
+                .columns
+                .iter()
+                .map(|(name, _)| to_cell(map.remove(*name).unwrap_or_default()))
+                .collect(),
+            value => vec![to_cell(value)],
+        }
+    }
+
+    fn record_to_value(&self, header: Option<&[String]>, record: Vec<String>) -> Value {
+        if self.scalar {
+            let cell = record.into_iter().next().unwrap_or_default();
+            return parse_cell(self.columns[0].1, cell);
+        }
+
+        let mut map = Map::new();
+        for (idx, cell) in record.into_iter().enumerate() {
+            let (name, ty) = match header {
+                Some(header) => match header.get(idx) {
+                    Some(name) => {
+                        let ty = self
+                            .columns
+                            .iter()
+                            .find(|(column, _)| column == name)
+                            .map(|(_, ty)| *ty)
+                            .unwrap_or("string");
+                        (name.clone(), ty)
+                    }
+                    None => continue,
+                },
+                None => match self.columns.get(idx) {
+                    Some((name, ty)) => (name.to_string(), *ty),
+                    None => continue,
+                },
+            };
+            // empty cells are missing values
+            if !cell.is_empty() {
+                map.insert(name, parse_cell(ty, cell));
+            }
+        }
+        Value::Object(map)
+    }
+}
+
+fn writer_builder(options: CsvOptions) -> csv::WriterBuilder {
+    let mut builder = csv::WriterBuilder::new();
+    builder
+        .delimiter(options.delimiter)
+        .terminator(csv::Terminator::CRLF)
+        .flexible(true);
+    builder
+}
+
+fn write_record<I>(builder: &csv::WriterBuilder, record: I) -> csv::Result<Vec<u8>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut writer = builder.from_writer(Vec::new());
+    writer.write_record(record)?;
+    writer.into_inner().map_err(|err| err.into_error().into())
+}
+
+fn write_rows<T: ToJSON>(rows: &[T], options: CsvOptions) -> csv::Result<Vec<u8>> {
+    let columns = Columns::new::<T>();
+    let mut writer = writer_builder(options).from_writer(Vec::new());
+    if options.headers {
+        writer.write_record(columns.header())?;
+    }
+    for row in rows {
+        writer.write_record(columns.value_to_record(row.to_json().unwrap_or_default()))?;
+    }
+    writer.into_inner().map_err(|err| err.into_error().into())
+}
+
+fn is_blank(record: &[String]) -> bool {
+    record.len() == 1 && record[0].is_empty()
+}
+
+/// Reads CSV records from a body, parsing each one as soon as it is complete.
+struct RecordReader {
+    body: BoxStream<'static, Result<Bytes, IoError>>,
+    reader: csv_core::Reader,
+    buf: Bytes,
+    eof: bool,
+    output: Vec<u8>,
+    ends: Vec<usize>,
+}
+
+impl RecordReader {
+    fn new(body: Body, delimiter: u8) -> Self {
+        Self {
+            body: body.into_bytes_stream().boxed(),
+            reader: csv_core::ReaderBuilder::new().delimiter(delimiter).build(),
+            buf: Bytes::new(),
+            eof: false,
+            output: vec![0; 1024],
+            ends: vec![0; 32],
+        }
+    }
+
+    async fn next_record(&mut self) -> Option<Result<Vec<String>>> {
+        loop {
+            let record = match self.read_record().await? {
+                Ok(record) => record,
+                Err(err) => return Some(Err(err)),
+            };
+            // skip blank lines
+            if !is_blank(&record) {
+                return Some(Ok(record));
+            }
+        }
+    }
+
+    async fn read_record(&mut self) -> Option<Result<Vec<String>>> {
+        let mut output_len = 0;
+        let mut ends_len = 0;
+
+        loop {
+            if self.buf.is_empty() && !self.eof {
+                match self.body.next().await {
+                    Some(Ok(data)) => self.buf = data,
+                    Some(Err(err)) => {
+                        self.eof = true;
+                        return Some(Err(ReadBodyError::Io(err).into()));
+                    }
+                    None => self.eof = true,
+                }
+                continue;
+            }
+
+            let (res, read, written, ends) = self.reader.read_record(
+                &self.buf,
+                &mut self.output[output_len..],
+                &mut self.ends[ends_len..],
+            );
+            self.buf.advance(read);
+            output_len += written;
+            ends_len += ends;
+
+            match res {
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::OutputFull => self.output.resize(self.output.len() * 2, 0),
+                ReadRecordResult::OutputEndsFull => self.ends.resize(self.ends.len() * 2, 0),
+                ReadRecordResult::Record => {
+                    let mut start = 0;
+                    let record = self.ends[..ends_len]
+                        .iter()
+                        .map(|&end| {
+                            let field = String::from_utf8(self.output[start..end].to_vec());
+                            start = end;
+                            field
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| {
+                            ParseRequestPayloadError {
+                                reason: "invalid utf-8 string".to_string(),
+                            }
+                            .into()
+                        });
+                    return Some(record);
+                }
+                ReadRecordResult::End => return None,
+            }
+        }
+    }
+}
+
+fn parse_row<T: ParseFromJSON>(
+    columns: &Columns,
+    header: Option<&[String]>,
+    record: Vec<String>,
+    row: usize,
+) -> Result<T> {
+    T::parse_from_json(Some(columns.record_to_value(header, record))).map_err(|err| {
+        ParseRequestPayloadError {
+            reason: format!("row {row}: {}", err.into_message()),
+        }
+        .into()
+    })
+}
+
+/// A CSV payload.
+///
+/// Each row is converted with the same [`ToJSON`] and [`ParseFromJSON`]
+/// implementations used by [`Json`](crate::payload::Json), and the columns are
+/// the properties of the row [`Object`](crate::Object) in declaration order.
+/// Nested objects and arrays are stored as JSON text, and empty cells are
+/// parsed as missing values.
+///
+/// Requests with the `header=absent` content type parameter are parsed
+/// without a header row, and `text/tab-separated-values` requests are parsed
+/// with a tab delimiter.
+///
+/// # Examples
+///
+/// ```
+/// use poem_openapi::{Object, OpenApi, payload::Csv};
+///
+/// #[derive(Object)]
+/// struct User {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users", method = "get")]
+///     async fn export(&self) -> Csv<User> {
+///         Csv::new(vec![User {
+///             id: 1,
+///             name: "sunli".to_string(),
+///         }])
+///         .delimiter(b';')
+///     }
+/// }
+/// ```
+pub struct Csv<T> {
+    rows: Vec<T>,
+    options: CsvOptions,
+}
+
+impl<T> Csv<T> {
+    /// Create a CSV payload.
+    pub fn new(rows: Vec<T>) -> Self {
+        Self {
+            rows,
+            options: CsvOptions::default(),
+        }
+    }
+
+    /// Sets the field delimiter, defaults to `,`.
+    #[must_use]
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.options.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the first record is a header row, defaults to `true`.
+    #[must_use]
+    pub fn headers(mut self, headers: bool) -> Self {
+        self.options.headers = headers;
+        self
+    }
+
+    /// Consumes this payload and returns the rows.
+    pub fn into_inner(self) -> Vec<T> {
+        self.rows
+    }
+}
+
+impl<T: Debug> Debug for Csv<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Csv").field(&self.rows).finish()
+    }
+}
+
+impl<T> Deref for Csv<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rows
+    }
+}
+
+impl<T> DerefMut for Csv<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rows
+    }
+}
+
+impl<T: Type> Payload for Csv<T> {
+    const CONTENT_TYPE: &'static str = CONTENT_TYPE;
+
+    fn check_content_type(content_type: &str) -> bool {
+        check_content_type(content_type)
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        rows_schema_ref::<T>()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ParseFromJSON> ParsePayload for Csv<T> {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let options = CsvOptions::from_request(request);
+        let data = Vec::<u8>::from_request(request, body).await?;
+        let columns = Columns::new::<T>();
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(data.as_slice());
+        let mut header = None;
+        let mut rows = Vec::new();
+
+        for record in reader.records() {
+            let record = record
+                .map_err(|err| ParseRequestPayloadError {
+                    reason: err.to_string(),
+                })?
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            if is_blank(&record) {
+                continue;
+            }
+            if options.headers && header.is_none() {
+                header = Some(record);
+                continue;
+            }
+            rows.push(parse_row(
+                &columns,
+                header.as_deref(),
+                record,
+                rows.len() + 1,
+            )?);
+        }
+
+        Ok(Self { rows, options })
+    }
+}
+
+impl<T: ToJSON> IntoResponse for Csv<T> {
+    fn into_response(self) -> Response {
+        let data = match write_rows(&self.rows, self.options) {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(err.to_string());
+            }
+        };
+        Response::builder()
+            .content_type(self.options.content_type())
+            .body(data)
+    }
+}
+
+impl<T: ToJSON> ApiResponse for Csv<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                status_range: None,
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl_apirequest_for_payload!(Csv<T>, T: ParseFromJSON);
+
+/// A streaming CSV payload.
+///
+/// This is the streaming counterpart of [`Csv`], rows of a request are parsed
+/// while the handler polls the stream and rows of a response are written as
+/// they are produced.
+///
+/// # Examples
+///
+/// ```
+/// use futures_util::{StreamExt, stream};
+/// use poem_openapi::{Object, OpenApi, payload::CsvStream};
+///
+/// #[derive(Object)]
+/// struct Record {
+///     id: u64,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/import", method = "post")]
+///     async fn import(&self, mut records: CsvStream<Record>) -> poem::Result<()> {
+///         while let Some(record) = records.next().await {
+///             let record = record?;
+///             // store the record
+///         }
+///         Ok(())
+///     }
+///
+///     #[oai(path = "/export", method = "get")]
+///     async fn export(&self) -> CsvStream<Record> {
+///         CsvStream::new(stream::iter((0..100).map(|id| Record { id })))
+///     }
+/// }
+/// ```
+pub struct CsvStream<T> {
+    stream: BoxStream<'static, Result<T>>,
+    options: CsvOptions,
+}
+
+impl<T: Send + 'static> CsvStream<T> {
+    /// Create a streaming CSV payload.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self::try_new(stream.map(Ok))
+    }
+
+    /// Create a streaming CSV payload from a stream of results.
+    ///
+    /// When used as a response, the body is terminated at the first error.
+    pub fn try_new(stream: impl Stream<Item = Result<T>> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+            options: CsvOptions::default(),
+        }
+    }
+
+    /// Sets the field delimiter, defaults to `,`.
+    #[must_use]
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.options.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the first record is a header row, defaults to `true`.
+    #[must_use]
+    pub fn headers(mut self, headers: bool) -> Self {
+        self.options.headers = headers;
+        self
+    }
+}
+
+impl<T> Debug for CsvStream<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsvStream").finish_non_exhaustive()
+    }
+}
+
+impl<T> Stream for CsvStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<T: Type> Payload for CsvStream<T> {
+    const CONTENT_TYPE: &'static str = CONTENT_TYPE;
+
+    fn check_content_type(content_type: &str) -> bool {
+        check_content_type(content_type)
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        rows_schema_ref::<T>()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ParseFromJSON + Send + 'static> ParsePayload for CsvStream<T> {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let options = CsvOptions::from_request(request);
+        let reader = RecordReader::new(body.take()?, options.delimiter);
+        let state = (reader, Columns::new::<T>(), None::<Vec<String>>, 0);
+        let stream = futures_util::stream::unfold(
+            state,
+            move |(mut reader, columns, mut header, mut row)| async move {
+                loop {
+                    let record = match reader.next_record().await? {
+                        Ok(record) => record,
+                        Err(err) => return Some((Err(err), (reader, columns, header, row))),
+                    };
+                    if options.headers && header.is_none() {
+                        header = Some(record);
+                        continue;
+                    }
+                    row += 1;
+                    let res = parse_row(&columns, header.as_deref(), record, row);
+                    return Some((res, (reader, columns, header, row)));
+                }
+            },
+        );
+
+        Ok(Self {
+            stream: stream.boxed(),
+            options,
+        })
+    }
+}
+
+impl<T: ToJSON + Send + 'static> IntoResponse for CsvStream<T> {
+    fn into_response(self) -> Response {
+        let options = self.options;
+        let columns = Columns::new::<T>();
+        let builder = writer_builder(options);
+        let header = options
+            .headers
+            .then(|| write_record(&builder, columns.header()).map_err(IoError::other));
+        let rows = self.stream.map(move |row| {
+            let record =
+                columns.value_to_record(row.map_err(IoError::other)?.to_json().unwrap_or_default());
+            write_record(&builder, record).map_err(IoError::other)
+        });
+        Response::builder()
+            .content_type(options.content_type())
+            .body(Body::from_bytes_stream(
+                futures_util::stream::iter(header).chain(rows),
+            ))
+    }
+}
+
+impl<T: ToJSON + Send + 'static> ApiResponse for CsvStream<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                status_range: None,
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl_apirequest_for_payload!(CsvStream<T>, T: ParseFromJSON + Send + 'static);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read_all(chunks: &[&str], delimiter: u8) -> Result<Vec<Vec<String>>> {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok::<_, IoError>(chunk.to_string()))
+            .collect::<Vec<_>>();
+        let body = Body::from_bytes_stream(futures_util::stream::iter(chunks));
+        let mut reader = RecordReader::new(body, delimiter);
+        let mut records = Vec::new();
+        while let Some(record) = reader.next_record().await {
+            records.push(record?);
+        }
+        Ok(records)
+    }
+
+    #[tokio::test]
+    async fn read_records() {
+        let data = "a,b\r\n1,\"x,\"\"y\"\"\nz\"\n\n2,";
+        let expected = vec![vec!["a", "b"], vec!["1", "x,\"y\"\nz"], vec!["2", ""]];
+        assert_eq!(read_all(&[data], b',').await.unwrap(), expected);
+
+        // records split across the chunks of the body
+        let chunks = (0..data.len())
+            .map(|idx| &data[idx..idx + 1])
+            .collect::<Vec<_>>();
+        assert_eq!(read_all(&chunks, b',').await.unwrap(), expected);
+
+        assert_eq!(
+            read_all(&["a;b\n1;2"], b';').await.unwrap(),
+            vec![vec!["a", "b"], vec!["1", "2"]]
+        );
+    }
+
+    #[test]
+    fn write_records() {
+        let builder = writer_builder(CsvOptions::default());
+        let mut data = write_record(&builder, ["a", "b,c", "d\"e", "f\ng"]).unwrap();
+        data.extend(write_record(&builder, ["a", ""]).unwrap());
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            "a,\"b,c\",\"d\"\"e\",\"f\ng\"\r\na,\r\n"
+        );
+    }
+}
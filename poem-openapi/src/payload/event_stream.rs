@@ -15,9 +15,128 @@ use crate::{
 
 type ToEventFn<T> = Box<dyn (FnMut(T) -> Event) + Send + 'static>;
 
+/// A typed server-sent event, with an optional event name and id.
+///
+/// The data is encoded as JSON.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SseEvent<T> {
+    data: T,
+    event_type: Option<String>,
+    id: Option<String>,
+}
+
+impl<T> SseEvent<T> {
+    /// Create a server-sent event with data.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            event_type: None,
+            id: None,
+        }
+    }
+
+    /// Set the event name, which is dispatched to the listeners of that name
+    /// on the client.
+    #[must_use]
+    pub fn event_type(self, event_type: impl Into<String>) -> Self {
+        Self {
+            event_type: Some(event_type.into()),
+            ..self
+        }
+    }
+
+    /// Set the event id, which the client sends back in the `Last-Event-ID`
+    /// header when reconnecting.
+    #[must_use]
+    pub fn id(self, id: impl Into<String>) -> Self {
+        Self {
+            id: Some(id.into()),
+            ..self
+        }
+    }
+
+    /// Consumes this object and returns the data.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+/// Represents an item of an [`EventStream`].
+pub trait EventStreamItem {
+    /// The type of the event data.
+    type Data: Type + ToJSON;
+
+    /// Convert the item to a server-sent event.
+    fn into_event(self) -> Event;
+}
+
+impl<T: Type + ToJSON> EventStreamItem for T {
+    type Data = T;
+
+    fn into_event(self) -> Event {
+        Event::message(self.to_json_string())
+    }
+}
+
+impl<T: Type + ToJSON> EventStreamItem for SseEvent<T> {
+    type Data = T;
+
+    fn into_event(self) -> Event {
+        let mut event = Event::message(self.data.to_json_string());
+        if let Some(event_type) = self.event_type {
+            event = event.event_type(event_type);
+        }
+        if let Some(id) = self.id {
+            event = event.id(id);
+        }
+        event
+    }
+}
+
 /// An event stream payload.
 ///
+/// The items of the stream are either types that are sent as unnamed events,
+/// or [`SseEvent`]s that can also carry an event name and id. In the spec,
+/// the payload is an array of the data type with the `event-stream` format,
+/// and the layout of each event is described by the `x-sse-schema` extension.
+///
 /// Reference: <https://github.com/OAI/OpenAPI-Specification/issues/396#issuecomment-894718960>
+///
+/// # Examples
+///
+/// ```
+/// use futures_util::stream;
+/// use poem_openapi::{
+///     Object, OpenApi,
+///     payload::{EventStream, SseEvent},
+/// };
+///
+/// #[derive(Object)]
+/// struct Progress {
+///     percent: u8,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/progress", method = "get")]
+///     async fn progress(
+///         &self,
+///     ) -> EventStream<stream::Iter<std::vec::IntoIter<SseEvent<Progress>>>> {
+///         EventStream::new(stream::iter(
+///             (0..=100)
+///                 .step_by(10)
+///                 .map(|percent| {
+///                     SseEvent::new(Progress { percent })
+///                         .event_type("progress")
+///                         .id(percent.to_string())
+///                 })
+///                 .collect::<Vec<_>>(),
+///         ))
+///     }
+/// }
+/// ```
 pub struct EventStream<T: Stream + Send + 'static> {
     stream: T,
     keep_alive: Option<Duration>,
@@ -75,32 +194,60 @@ impl<T: Stream + Send + 'static> EventStream<T> {
     }
 }
 
-impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON> Payload for EventStream<T> {
+fn sse_schema<T: Type>() -> MetaSchemaRef {
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        required: vec!["data"],
+        properties: vec![
+            (
+                "event",
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    description: Some("The event name."),
+                    ..MetaSchema::new("string")
+                })),
+            ),
+            (
+                "id",
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    description: Some("The event id."),
+                    ..MetaSchema::new("string")
+                })),
+            ),
+            (
+                "retry",
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    description: Some("The reconnection time in milliseconds."),
+                    ..MetaSchema::new("integer")
+                })),
+            ),
+            ("data", T::schema_ref()),
+        ],
+        ..MetaSchema::new("object")
+    }))
+}
+
+impl<T: Stream<Item = E> + Send + 'static, E: EventStreamItem> Payload for EventStream<T> {
     const CONTENT_TYPE: &'static str = "text/event-stream";
 
     fn schema_ref() -> MetaSchemaRef {
         MetaSchemaRef::Inline(Box::new(MetaSchema {
-            items: Some(Box::new(E::schema_ref())),
+            items: Some(Box::new(E::Data::schema_ref())),
+            sse_schema: Some(Box::new(sse_schema::<E::Data>())),
             ..MetaSchema::new_with_format("array", "event-stream")
         }))
     }
 
     fn register(registry: &mut Registry) {
-        E::register(registry);
+        E::Data::register(registry);
     }
 }
 
-impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON + 'static> IntoResponse
+impl<T: Stream<Item = E> + Send + 'static, E: EventStreamItem + 'static> IntoResponse
     for EventStream<T>
 {
     fn into_response(self) -> Response {
         let mut sse = match self.to_event {
             Some(to_event) => SSE::new(self.stream.map(to_event)),
-            None => SSE::new(
-                self.stream
-                    .map(|message| message.to_json_string())
-                    .map(Event::message),
-            ),
+            None => SSE::new(self.stream.map(E::into_event)),
         };
 
         if let Some(keep_alive) = self.keep_alive {
@@ -111,7 +258,7 @@ impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON + 'static> IntoRespo
     }
 }
 
-impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON> ApiResponse for EventStream<T> {
+impl<T: Stream<Item = E> + Send + 'static, E: EventStreamItem> ApiResponse for EventStream<T> {
     fn meta() -> MetaResponses {
         MetaResponses {
             responses: vec![MetaResponse {
@@ -121,13 +268,16 @@ impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON> ApiResponse for Eve
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
 
     fn register(registry: &mut Registry) {
-        E::register(registry);
+        E::Data::register(registry);
     }
 }
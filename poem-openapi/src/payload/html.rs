@@ -67,8 +67,11 @@ impl<T: Into<String> + Send> ApiResponse for Html<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
@@ -15,6 +15,14 @@ use crate::{
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct Json<T>(pub T);
 
+/// Converts an error into the body of a response, which is used by the
+/// `from_error` attribute of [`ApiResponse`](derive@crate::ApiResponse).
+impl<T: From<poem::Error>> From<poem::Error> for Json<T> {
+    fn from(err: poem::Error) -> Self {
+        Self(T::from(err))
+    }
+}
+
 impl<T> Deref for Json<T> {
     type Target = T;
 
@@ -86,8 +94,11 @@ impl<T: ToJSON> ApiResponse for Json<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
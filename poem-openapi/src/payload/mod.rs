@@ -3,12 +3,24 @@
 mod attachment;
 mod base64_payload;
 mod binary;
+mod binary_stream;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod conditional;
+#[cfg(feature = "csv")]
+mod csv;
 mod event_stream;
 mod form;
 mod html;
 mod json;
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+mod json_value;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod ndjson;
 mod plain_text;
 mod response;
+mod with_headers;
 mod xml;
 mod yaml;
 
@@ -16,16 +28,30 @@ use std::future::Future;
 
 use poem::{Request, RequestBody, Result};
 
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+pub use self::cbor::Cbor;
+pub(crate) use self::conditional::ConditionalEndpoint;
+#[cfg(feature = "csv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+pub use self::csv::{Csv, CsvStream};
+#[cfg(feature = "msgpack")]
+#[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+pub use self::msgpack::MsgPack;
 pub use self::{
     attachment::{Attachment, AttachmentType},
     base64_payload::Base64,
     binary::Binary,
-    event_stream::EventStream,
+    binary_stream::BinaryStream,
+    conditional::Conditional,
+    event_stream::{EventStream, EventStreamItem, SseEvent},
     form::Form,
     html::Html,
     json::Json,
+    ndjson::NdJsonStream,
     plain_text::PlainText,
     response::Response,
+    with_headers::WithHeaders,
     xml::Xml,
     yaml::Yaml,
 };
@@ -0,0 +1,123 @@
+use std::ops::{Deref, DerefMut};
+
+use poem::{FromRequest, IntoResponse, Request, RequestBody, Response, Result, http::StatusCode};
+use serde_json::Value;
+
+use crate::{
+    ApiResponse,
+    error::ParseRequestPayloadError,
+    payload::{ParsePayload, Payload, json_value::JsonValue},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
+    types::{ParseFromJSON, ToJSON, Type},
+};
+
+/// A MessagePack payload.
+///
+/// The value is converted with the same [`ToJSON`] and [`ParseFromJSON`]
+/// implementations used by [`Json`](crate::payload::Json), so any type that
+/// can be sent as JSON can also be sent as MessagePack. Binary data in the
+/// request is decoded as base64 strings, which is the JSON representation of
+/// [`Base64`](crate::types::Base64), the extension types are decoded as
+/// `[type, data]` arrays and the numbers used as map keys are converted to
+/// strings.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct MsgPack<T>(pub T);
+
+impl<T> Deref for MsgPack<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MsgPack<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Type> Payload for MsgPack<T> {
+    const CONTENT_TYPE: &'static str = "application/msgpack";
+
+    fn check_content_type(content_type: &str) -> bool {
+        matches!(content_type.parse::<mime::Mime>(), Ok(content_type) if content_type.type_() == "application"
+                && (matches!(content_type.subtype().as_str(), "msgpack" | "x-msgpack" | "vnd.msgpack")
+                || content_type
+                    .suffix()
+                    .is_some_and(|v| v == "msgpack")))
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        T::schema_ref()
+    }
+
+    #[allow(unused_variables)]
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl<T: ParseFromJSON> ParsePayload for MsgPack<T> {
+    const IS_REQUIRED: bool = T::IS_REQUIRED;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let data = Vec::<u8>::from_request(request, body).await?;
+        let value = if data.is_empty() {
+            Value::Null
+        } else {
+            rmp_serde::from_slice::<JsonValue>(&data)
+                .map_err(|err| ParseRequestPayloadError {
+                    reason: err.to_string(),
+                })?
+                .0
+        };
+
+        let value = T::parse_from_json(Some(value)).map_err(|err| ParseRequestPayloadError {
+            reason: err.into_message(),
+        })?;
+        Ok(Self(value))
+    }
+}
+
+impl<T: ToJSON> IntoResponse for MsgPack<T> {
+    fn into_response(self) -> Response {
+        let data = match rmp_serde::to_vec(&self.0.to_json().unwrap_or_default()) {
+            Ok(data) => data,
+            Err(err) => {
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(err.to_string());
+            }
+        };
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(data)
+    }
+}
+
+impl<T: ToJSON> ApiResponse for MsgPack<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                status_range: None,
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl_apirequest_for_payload!(MsgPack<T>, T: ParseFromJSON);
@@ -0,0 +1,215 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::Error as IoError,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_util::{Stream, StreamExt, TryStreamExt, stream::BoxStream};
+use poem::{Body, IntoResponse, Request, RequestBody, Response, Result, error::ReadBodyError};
+
+use crate::{
+    ApiResponse,
+    error::ParseRequestPayloadError,
+    payload::{ParsePayload, Payload},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseFromJSON, ToJSON, Type},
+};
+
+/// A newline-delimited JSON ([NDJSON](https://github.com/ndjson/ndjson-spec),
+/// also known as JSON Lines) stream payload.
+///
+/// As a request payload, the items are parsed lazily while the handler polls
+/// the stream, so the body is only read as fast as it is consumed. A line
+/// that cannot be parsed yields an error, after which the following lines
+/// can still be read.
+///
+/// As a response payload, each item of the stream is written as one line.
+///
+/// # Examples
+///
+/// ```
+/// use futures_util::{StreamExt, stream};
+/// use poem_openapi::{Object, OpenApi, payload::NdJsonStream};
+///
+/// #[derive(Object)]
+/// struct Record {
+///     id: u64,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/import", method = "post")]
+///     async fn import(&self, mut records: NdJsonStream<Record>) -> poem::Result<()> {
+///         while let Some(record) = records.next().await {
+///             let record = record?;
+///             // store the record
+///         }
+///         Ok(())
+///     }
+///
+///     #[oai(path = "/export", method = "get")]
+///     async fn export(&self) -> NdJsonStream<Record> {
+///         NdJsonStream::new(stream::iter((0..100).map(|id| Record { id })))
+///     }
+/// }
+/// ```
+pub struct NdJsonStream<T> {
+    stream: BoxStream<'static, Result<T>>,
+}
+
+impl<T: Send + 'static> NdJsonStream<T> {
+    /// Create a NDJSON stream payload.
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self {
+            stream: stream.map(Ok).boxed(),
+        }
+    }
+
+    /// Create a NDJSON stream payload from a stream of results.
+    ///
+    /// When used as a response, the body is terminated at the first error.
+    pub fn try_new(stream: impl Stream<Item = Result<T>> + Send + 'static) -> Self {
+        Self {
+            stream: stream.boxed(),
+        }
+    }
+}
+
+impl<T> Debug for NdJsonStream<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NdJsonStream").finish_non_exhaustive()
+    }
+}
+
+impl<T> Stream for NdJsonStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<T: Type> Payload for NdJsonStream<T> {
+    const CONTENT_TYPE: &'static str = "application/x-ndjson";
+
+    fn check_content_type(content_type: &str) -> bool {
+        matches!(content_type.parse::<mime::Mime>(), Ok(content_type) if content_type.type_() == "application"
+                && matches!(content_type.subtype().as_str(), "x-ndjson" | "ndjson" | "jsonl" | "x-jsonlines"))
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(T::schema_ref())),
+            ..MetaSchema::new_with_format("array", "ndjson")
+        }))
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+fn parse_line<T: ParseFromJSON>(line: &[u8]) -> Result<T> {
+    let value = serde_json::from_slice(line).map_err(|err| ParseRequestPayloadError {
+        reason: err.to_string(),
+    })?;
+    T::parse_from_json(Some(value)).map_err(|err| {
+        ParseRequestPayloadError {
+            reason: err.into_message(),
+        }
+        .into()
+    })
+}
+
+/// Returns the next non-empty line in `buf`, including the last line if
+/// `eof` is `true`.
+fn next_line(buf: &mut BytesMut, eof: bool) -> Option<Bytes> {
+    loop {
+        let line = match buf.iter().position(|b| *b == b'\n') {
+            Some(idx) => {
+                let line = buf.split_to(idx + 1);
+                line.freeze().slice(..idx)
+            }
+            None if eof && !buf.is_empty() => buf.split().freeze(),
+            None => return None,
+        };
+        if !line.trim_ascii().is_empty() {
+            return Some(line);
+        }
+    }
+}
+
+impl<T: ParseFromJSON + Send + 'static> ParsePayload for NdJsonStream<T> {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(_request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let body = body.take()?.into_bytes_stream().boxed();
+        let stream = futures_util::stream::unfold(
+            (body, BytesMut::new(), false),
+            |(mut body, mut buf, mut eof)| async move {
+                loop {
+                    if let Some(line) = next_line(&mut buf, eof) {
+                        return Some((parse_line(&line), (body, buf, eof)));
+                    }
+                    if eof {
+                        return None;
+                    }
+                    match body.next().await {
+                        Some(Ok(data)) => buf.extend_from_slice(&data),
+                        Some(Err(err)) => {
+                            return Some((
+                                Err(ReadBodyError::Io(err).into()),
+                                (body, BytesMut::new(), true),
+                            ));
+                        }
+                        None => eof = true,
+                    }
+                }
+            },
+        );
+        Ok(Self::try_new(stream))
+    }
+}
+
+impl<T: ToJSON + Send + 'static> IntoResponse for NdJsonStream<T> {
+    fn into_response(self) -> Response {
+        let stream = self.stream.map_err(IoError::other).map_ok(|item| {
+            let mut data = serde_json::to_vec(&item.to_json()).unwrap_or_default();
+            data.push(b'\n');
+            data
+        });
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(Body::from_bytes_stream(stream))
+    }
+}
+
+impl<T: ToJSON + Send + 'static> ApiResponse for NdJsonStream<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                status_range: None,
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
+                }],
+                headers: vec![],
+                links: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl_apirequest_for_payload!(NdJsonStream<T>, T: ParseFromJSON + Send + 'static);
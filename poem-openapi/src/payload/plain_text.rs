@@ -13,6 +13,15 @@ use crate::{
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PlainText<T>(pub T);
 
+/// Converts an error into a response body containing the error message, which
+/// is used by the `from_error` attribute of
+/// [`ApiResponse`](derive@crate::ApiResponse).
+impl From<poem::Error> for PlainText<String> {
+    fn from(err: poem::Error) -> Self {
+        Self(err.to_string())
+    }
+}
+
 impl<T> Deref for PlainText<T> {
     type Target = T;
 
@@ -67,8 +76,11 @@ impl<T: Into<String> + Send> ApiResponse for PlainText<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
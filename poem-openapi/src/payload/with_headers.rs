@@ -0,0 +1,85 @@
+use poem::IntoResponse;
+
+use crate::{
+    ApiResponse, ResponseHeaders,
+    registry::{MetaResponses, Registry},
+};
+
+/// A response type wrapper that adds typed headers to a response.
+///
+/// The headers are declared in the spec for every response of `T`.
+///
+/// # Examples
+///
+/// ```
+/// use poem::test::TestClient;
+/// use poem_openapi::{
+///     OpenApi, OpenApiService, ResponseHeaders,
+///     payload::{Json, WithHeaders},
+/// };
+///
+/// #[derive(ResponseHeaders)]
+/// struct RateLimit {
+///     /// The number of requests left for the time window.
+///     #[oai(name = "X-RateLimit-Remaining")]
+///     remaining: u32,
+/// }
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/test", method = "get")]
+///     async fn test(&self) -> WithHeaders<Json<i32>, RateLimit> {
+///         WithHeaders::new(Json(100), RateLimit { remaining: 99 })
+///     }
+/// }
+///
+/// let api = OpenApiService::new(MyApi, "Demo", "0.1.0");
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = TestClient::new(api).get("/test").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_header("X-RateLimit-Remaining", "99");
+/// resp.assert_text("100").await;
+/// # });
+/// ```
+pub struct WithHeaders<T, H> {
+    inner: T,
+    headers: H,
+}
+
+impl<T, H> WithHeaders<T, H> {
+    /// Create a response with headers.
+    pub fn new(inner: T, headers: H) -> Self {
+        Self { inner, headers }
+    }
+
+    /// Consumes this object and returns the response and the headers.
+    pub fn into_parts(self) -> (T, H) {
+        (self.inner, self.headers)
+    }
+}
+
+impl<T: IntoResponse, H: ResponseHeaders> IntoResponse for WithHeaders<T, H> {
+    fn into_response(self) -> poem::Response {
+        let mut resp = self.inner.into_response();
+        self.headers.insert_into(resp.headers_mut());
+        resp
+    }
+}
+
+impl<T: ApiResponse, H: ResponseHeaders> ApiResponse for WithHeaders<T, H> {
+    fn meta() -> MetaResponses {
+        let mut meta = T::meta();
+        for response in &mut meta.responses {
+            response.headers.extend(H::meta());
+        }
+        meta
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+        H::register(registry);
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
-use poem::{FromRequest, IntoResponse, Request, RequestBody, Response, Result};
+use poem::{FromRequest, IntoResponse, Request, RequestBody, Response, Result, http::StatusCode};
 use serde_json::Value;
 
 use crate::{
@@ -58,11 +58,9 @@ impl<T: ParseFromXML> ParsePayload for Xml<T> {
         let value = if data.is_empty() {
             Value::Null
         } else {
-            quick_xml::de::from_str(&String::from_utf8(data).unwrap_or_default()).map_err(
-                |err| ParseRequestPayloadError {
-                    reason: err.to_string(),
-                },
-            )?
+            crate::xml::read_document(&data)
+                .map_err(|reason| ParseRequestPayloadError { reason })?
+                .1
         };
 
         let value = T::parse_from_xml(Some(value)).map_err(|err| ParseRequestPayloadError {
@@ -74,7 +72,14 @@ impl<T: ParseFromXML> ParsePayload for Xml<T> {
 
 impl<T: ToXML> IntoResponse for Xml<T> {
     fn into_response(self) -> Response {
-        poem::web::Xml(self.0.to_xml()).into_response()
+        match crate::xml::write_document::<T>(self.0.to_xml()) {
+            Ok(data) => Response::builder()
+                .content_type(Self::CONTENT_TYPE)
+                .body(data),
+            Err(err) => Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(err.to_string()),
+        }
     }
 }
 
@@ -88,8 +93,11 @@ impl<T: ToXML> ApiResponse for Xml<T> {
                 content: vec![MetaMediaType {
                     content_type: Self::CONTENT_TYPE,
                     schema: Self::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
                 headers: vec![],
+                links: vec![],
             }],
         }
     }
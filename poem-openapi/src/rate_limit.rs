@@ -0,0 +1,274 @@
+//! Rate limits of the operations.
+//!
+//! The rate limit of an operation is declared with the
+//! `#[oai(rate_limit(requests = 100, period = 60))]` attribute, which allows
+//! `requests` requests every `period` seconds. The limit is added to the
+//! operation of the specification as the `x-rate-limit` extension, with a
+//! `429 Too Many Requests` response.
+//!
+//! The limits are enforced by the [`RateLimiter`] set with
+//! [`OpenApiService::rate_limiter`](crate::OpenApiService::rate_limiter).
+//! Without a rate limiter, they are only documented in the specification.
+//!
+//! # Examples
+//!
+//! ```
+//! use poem::{http::StatusCode, test::TestClient};
+//! use poem_openapi::{OpenApi, OpenApiService, rate_limit::MemoryRateLimiter};
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(
+//!         path = "/search",
+//!         method = "get",
+//!         rate_limit(requests = 1, period = 60)
+//!     )]
+//!     async fn search(&self) {}
+//! }
+//!
+//! let api_service =
+//!     OpenApiService::new(Api, "Demo", "1.0").rate_limiter(MemoryRateLimiter::new());
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let cli = TestClient::new(api_service);
+//! cli.get("/search").send().await.assert_status_is_ok();
+//! cli.get("/search")
+//!     .send()
+//!     .await
+//!     .assert_status(StatusCode::TOO_MANY_REQUESTS);
+//! # });
+//! ```
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_util::future::BoxFuture;
+use poem::{
+    Endpoint, EndpointExt, IntoResponse, Request, Response, Result,
+    endpoint::{BoxEndpoint, ShutdownHooks},
+    hooks::SharedClock,
+    http::Method,
+};
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+use serde_json::Value;
+
+use crate::{
+    error::TooManyRequestsError, registry::MetaOperation, schema_validation::map_operations,
+};
+
+/// The rate limit of an operation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RateLimit {
+    /// The number of requests that are allowed in a period.
+    pub requests: u64,
+    /// The length of a period.
+    pub period: Duration,
+}
+
+impl Serialize for RateLimit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("RateLimit", 2)?;
+        s.serialize_field("requests", &self.requests)?;
+        s.serialize_field("period", &self.period.as_secs())?;
+        s.end()
+    }
+}
+
+/// Enforces the rate limits of the operations.
+pub trait RateLimiter: Send + Sync + 'static {
+    /// Checks that a request to an operation does not exceed its rate limit.
+    ///
+    /// The operation is identified by its method and path in the
+    /// specification, e.g. `GET /users/{id}`. The error is returned as the response of the
+    /// request, it is usually a [`TooManyRequestsError`].
+    fn check<'a>(
+        &'a self,
+        req: &'a Request,
+        operation: &'a str,
+        limit: RateLimit,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+/// The operation and the IP address of a client.
+type ClientKey = (String, Option<IpAddr>);
+
+/// A [`RateLimiter`] that counts the requests of each client in memory.
+///
+/// The clients are identified by their IP addresses, and the requests are
+/// counted in fixed windows of one period. The time is read from the
+/// [`SharedClock`] of the request, and the expired windows are evicted at most
+/// once per second when checking a request.
+#[derive(Default)]
+pub struct MemoryRateLimiter {
+    windows: Mutex<Windows>,
+}
+
+#[derive(Default)]
+struct Windows {
+    /// The current window of each client.
+    clients: HashMap<ClientKey, Window>,
+    last_sweep: Option<Instant>,
+}
+
+struct Window {
+    start: Instant,
+    period: Duration,
+    count: u64,
+}
+
+impl Window {
+    fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.start) >= self.period
+    }
+}
+
+/// The minimum interval between two evictions of the expired windows.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+impl Windows {
+    fn sweep(&mut self, now: Instant) {
+        if self
+            .last_sweep
+            .is_none_or(|last_sweep| now.duration_since(last_sweep) >= SWEEP_INTERVAL)
+        {
+            self.clients.retain(|_, window| !window.is_expired(now));
+            self.last_sweep = Some(now);
+        }
+    }
+}
+
+impl MemoryRateLimiter {
+    /// Create a rate limiter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RateLimiter for MemoryRateLimiter {
+    fn check<'a>(
+        &'a self,
+        req: &'a Request,
+        operation: &'a str,
+        limit: RateLimit,
+    ) -> BoxFuture<'a, Result<()>> {
+        let now = SharedClock::from_request(req).now();
+        let client = req.remote_addr().as_socket_addr().map(|addr| addr.ip());
+        let mut windows = self.windows.lock().unwrap();
+        windows.sweep(now);
+        let window = windows
+            .clients
+            .entry((operation.to_string(), client))
+            .or_insert(Window {
+                start: now,
+                period: limit.period,
+                count: 0,
+            });
+        if window.is_expired(now) {
+            window.start = now;
+            window.count = 0;
+        }
+        window.period = limit.period;
+        let res = match window.count < limit.requests {
+            true => {
+                window.count += 1;
+                Ok(())
+            }
+            false => Err(TooManyRequestsError {
+                retry_after: limit.period - now.duration_since(window.start),
+            }
+            .into()),
+        };
+        Box::pin(async move { res })
+    }
+}
+
+/// Wraps the endpoints of the operations with a rate limit with the
+/// enforcement of the rate limiter.
+pub(crate) fn wrap_routes(
+    limiter: &Arc<dyn RateLimiter>,
+    operations: &HashMap<String, MetaOperation>,
+    spec: &Value,
+    url_prefix: Option<&str>,
+    routes: &mut HashMap<String, HashMap<Method, BoxEndpoint<'static>>>,
+) {
+    map_operations(spec, url_prefix, routes, |name, _, ep| {
+        match operations
+            .get(name)
+            .and_then(|operation| operation.rate_limit)
+        {
+            Some(limit) => RateLimitEndpoint {
+                inner: ep,
+                limiter: limiter.clone(),
+                operation: name.to_string(),
+                limit,
+            }
+            .boxed(),
+            None => ep,
+        }
+    });
+}
+
+struct RateLimitEndpoint<E> {
+    inner: E,
+    limiter: Arc<dyn RateLimiter>,
+    operation: String,
+    limit: RateLimit,
+}
+
+impl<E: Endpoint> Endpoint for RateLimitEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        self.limiter
+            .check(&req, &self.operation, self.limit)
+            .await?;
+        self.inner.call(req).await.map(IntoResponse::into_response)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::test::MockClock;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn evict_expired_windows() {
+        let clock = MockClock::new();
+        let limiter = MemoryRateLimiter::new();
+        let mut req = Request::default();
+        req.set_data(SharedClock::new(clock.clone()));
+        let limit = |secs| RateLimit {
+            requests: 1,
+            period: Duration::from_secs(secs),
+        };
+
+        limiter.check(&req, "GET /a", limit(10)).await.unwrap();
+        limiter.check(&req, "GET /b", limit(60)).await.unwrap();
+        assert_eq!(limiter.windows.lock().unwrap().clients.len(), 2);
+
+        clock.advance(Duration::from_secs(10));
+        limiter.check(&req, "GET /c", limit(10)).await.unwrap();
+        let mut operations = limiter
+            .windows
+            .lock()
+            .unwrap()
+            .clients
+            .keys()
+            .map(|(operation, _)| operation.clone())
+            .collect::<Vec<_>>();
+        operations.sort();
+        assert_eq!(operations, ["GET /b", "GET /c"]);
+        assert!(limiter.check(&req, "GET /b", limit(60)).await.is_err());
+    }
+}
@@ -32,6 +32,10 @@ impl<'a> Document<'a> {
             self.traverse_schema(used_types, schema_ref);
         }
 
+        if let Some(schema_ref) = &schema.sse_schema {
+            self.traverse_schema(used_types, schema_ref);
+        }
+
         for schema_ref in &schema.any_of {
             self.traverse_schema(used_types, schema_ref);
         }
@@ -63,6 +67,12 @@ impl<'a> Document<'a> {
         for response in &operation.responses.responses {
             self.traverse_media_types(used_types, &response.content);
         }
+
+        for callback in &operation.callbacks {
+            for operation in &callback.operations {
+                self.traverse_operation(used_types, operation);
+            }
+        }
     }
 
     pub(crate) fn remove_unused_schemas(&mut self) {
@@ -80,6 +90,10 @@ impl<'a> Document<'a> {
             self.traverse_operation(&mut used_types, &api.operation);
         }
 
+        for schema_ref in &self.extra_schemas {
+            self.traverse_schema(&mut used_types, schema_ref);
+        }
+
         let all_schemas = self
             .registry
             .schemas
@@ -12,7 +12,7 @@ pub(crate) use ser::Document;
 use serde::{Serialize, Serializer, ser::SerializeMap};
 use serde_json::Value;
 
-use crate::{ParameterStyle, types::Type};
+use crate::{ParameterStyle, rate_limit::RateLimit, types::Type};
 
 #[allow(clippy::trivially_copy_pass_by_ref)]
 #[inline]
@@ -42,6 +42,21 @@ fn serialize_mapping<S: Serializer>(
     s.end()
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaXml {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<&'static str>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub attribute: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub wrapped: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaSchema {
@@ -91,6 +106,10 @@ pub struct MetaSchema {
     pub write_only: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xml: Option<MetaXml>,
+    #[serde(rename = "x-sse-schema", skip_serializing_if = "Option::is_none")]
+    pub sse_schema: Option<Box<MetaSchemaRef>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub multiple_of: Option<f64>,
@@ -118,6 +137,12 @@ pub struct MetaSchema {
     pub max_properties: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_properties: Option<usize>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+    /// The encoding of the properties of a multipart request, which is
+    /// serialized by the media type.
+    #[serde(skip)]
+    pub encoding: Vec<(&'static str, MetaEncoding)>,
 }
 
 fn serialize_properties<S: Serializer>(
@@ -154,6 +179,8 @@ impl MetaSchema {
         write_only: false,
         nullable: false,
         example: None,
+        xml: None,
+        sse_schema: None,
         multiple_of: None,
         maximum: None,
         exclusive_maximum: None,
@@ -167,6 +194,8 @@ impl MetaSchema {
         unique_items: None,
         max_properties: None,
         min_properties: None,
+        extensions: BTreeMap::new(),
+        encoding: vec![],
     };
 
     pub fn new(ty: &'static str) -> Self {
@@ -200,6 +229,7 @@ impl MetaSchema {
             items,
             additional_properties,
             example,
+            xml,
             multiple_of,
             maximum,
             exclusive_maximum,
@@ -237,6 +267,7 @@ impl MetaSchema {
             description,
             external_docs,
             example,
+            xml,
             multiple_of,
             maximum,
             exclusive_maximum,
@@ -376,13 +407,84 @@ pub struct MetaOperationParam {
     pub explode: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<ParameterStyle>,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_examples"
+    )]
+    pub examples: Vec<MetaExample>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, PartialEq, Serialize)]
-pub struct MetaMediaType {
+pub struct MetaExample {
     #[serde(skip)]
+    pub name: &'static str,
+    pub value: Value,
+}
+
+fn serialize_examples<S: Serializer>(
+    examples: &[MetaExample],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for example in examples {
+        s.serialize_entry(example.name, example)?;
+    }
+    s.end()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaEncoding {
+    pub content_type: &'static str,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MetaMediaType {
     pub content_type: &'static str,
     pub schema: MetaSchemaRef,
+    pub example: Option<Value>,
+    pub examples: Vec<MetaExample>,
+}
+
+impl Serialize for MetaMediaType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Examples<'a>(&'a [MetaExample]);
+
+        impl Serialize for Examples<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_examples(self.0, serializer)
+            }
+        }
+
+        struct Encoding<'a>(&'a [(&'static str, MetaEncoding)]);
+
+        impl Serialize for Encoding<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut s = serializer.serialize_map(None)?;
+                for (name, encoding) in self.0 {
+                    s.serialize_entry(name, encoding)?;
+                }
+                s.end()
+            }
+        }
+
+        let mut s = serializer.serialize_map(None)?;
+        s.serialize_entry("schema", &self.schema)?;
+        if let Some(example) = &self.example {
+            s.serialize_entry("example", example)?;
+        }
+        if !self.examples.is_empty() {
+            s.serialize_entry("examples", &Examples(&self.examples))?;
+        }
+        if let MetaSchemaRef::Inline(schema) = &self.schema {
+            if !schema.encoding.is_empty() {
+                s.serialize_entry("encoding", &Encoding(&schema.encoding))?;
+            }
+        }
+        s.end()
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize)]
@@ -443,6 +545,47 @@ pub struct MetaResponse {
         serialize_with = "serialize_headers"
     )]
     pub headers: Vec<MetaHeader>,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_links"
+    )]
+    pub links: Vec<MetaLink>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaLink {
+    #[serde(skip)]
+    pub name: &'static str,
+    pub operation_id: &'static str,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_link_parameters"
+    )]
+    pub parameters: Vec<(&'static str, &'static str)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'static str>,
+}
+
+fn serialize_links<S: Serializer>(links: &[MetaLink], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for link in links {
+        s.serialize_entry(link.name, link)?;
+    }
+    s.end()
+}
+
+fn serialize_link_parameters<S: Serializer>(
+    parameters: &[(&'static str, &'static str)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for (name, value) in parameters {
+        s.serialize_entry(name, value)?;
+    }
+    s.end()
 }
 
 fn serialize_headers<S: Serializer>(
@@ -463,6 +606,24 @@ pub struct MetaWebhook {
     pub operation: MetaOperation,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct MetaCallback {
+    pub name: &'static str,
+    pub url: &'static str,
+    pub operations: Vec<MetaOperation>,
+}
+
+fn serialize_callbacks<S: Serializer>(
+    callbacks: &[MetaCallback],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for callback in callbacks {
+        s.serialize_entry(callback.name, callback)?;
+    }
+    s.end()
+}
+
 #[derive(Debug, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaCodeSample {
@@ -490,6 +651,11 @@ pub struct MetaOperation {
     #[serde(rename = "requestBody", skip_serializing_if = "Option::is_none")]
     pub request: Option<MetaRequest>,
     pub responses: MetaResponses,
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_callbacks"
+    )]
+    pub callbacks: Vec<MetaCallback>,
     #[serde(skip_serializing_if = "is_false")]
     pub deprecated: bool,
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -498,6 +664,16 @@ pub struct MetaOperation {
     pub operation_id: Option<&'static str>,
     #[serde(rename = "x-code-samples", skip_serializing_if = "Vec::is_empty")]
     pub code_samples: Vec<MetaCodeSample>,
+    #[serde(rename = "x-rate-limit", skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    #[serde(rename = "x-max-request-size", skip_serializing_if = "Option::is_none")]
+    pub max_request_size: Option<u64>,
+    /// The versions of the API that contain the operation, it is contained in
+    /// all versions if empty.
+    #[serde(skip)]
+    pub versions: Vec<&'static str>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -578,6 +754,23 @@ pub struct MetaTag {
     pub description: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<MetaExternalDocument>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+/// The metadata of a tag defined by the service, which overrides the
+/// metadata of the tag with the same name defined by [`Tags`](crate::Tags).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MetaTagDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    pub external_docs: Option<MetaExternalDocument>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct MetaTagGroup {
+    pub name: String,
+    pub tags: Vec<String>,
 }
 
 impl PartialEq for MetaTag {
@@ -652,7 +845,7 @@ pub struct MetaOAuthFlows {
     pub authorization_code: Option<MetaOAuthFlow>,
 }
 
-#[derive(Debug, Serialize, Eq, PartialEq)]
+#[derive(Debug, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaSecurityScheme {
     #[serde(rename = "type")]
@@ -671,6 +864,8 @@ pub struct MetaSecurityScheme {
     pub flows: Option<MetaOAuthFlows>,
     #[serde(rename = "openIdConnectUrl", skip_serializing_if = "Option::is_none")]
     pub openid_connect_url: Option<&'static str>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -1,13 +1,143 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use serde::{Serialize, Serializer, ser::SerializeMap};
+use serde::{
+    Serialize, Serializer,
+    ser::{SerializeMap, SerializeSeq},
+};
+use serde_json::Value;
 
-use crate::registry::{
-    MetaApi, MetaExternalDocument, MetaInfo, MetaPath, MetaResponses, MetaSchema, MetaSchemaRef,
-    MetaSecurityScheme, MetaServer, MetaWebhook, Registry,
+use crate::{
+    OpenApiVersion,
+    registry::{
+        MetaApi, MetaCallback, MetaExternalDocument, MetaInfo, MetaPath, MetaResponses, MetaSchema,
+        MetaSchemaRef, MetaSecurityScheme, MetaServer, MetaTag, MetaTagDefinition, MetaTagGroup,
+        MetaWebhook, Registry,
+    },
 };
 
-const OPENAPI_VERSION: &str = "3.0.0";
+const JSON_SCHEMA_DIALECT_3_1: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// The keys whose values are examples rather than parts of the document.
+const EXAMPLE_KEYS: &[&str] = &["example", "examples", "default", "enum", "const"];
+
+/// Converts the schema objects in `value` from OpenAPI 3.0 to the JSON Schema
+/// 2020-12 dialect used by OpenAPI 3.1.
+fn convert_to_v3_1(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if EXAMPLE_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                if key == "schema" {
+                    convert_schema_to_v3_1(value);
+                }
+                convert_to_v3_1(value);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(convert_to_v3_1),
+        _ => {}
+    }
+}
+
+fn convert_schema_to_v3_1(value: &mut Value) {
+    let Value::Object(schema) = value else {
+        return;
+    };
+
+    for (key, value) in schema.iter_mut() {
+        match key.as_str() {
+            "items" | "additionalProperties" | "not" => convert_schema_to_v3_1(value),
+            "properties" => {
+                if let Value::Object(properties) = value {
+                    properties.values_mut().for_each(convert_schema_to_v3_1);
+                }
+            }
+            "anyOf" | "oneOf" | "allOf" => {
+                if let Value::Array(items) = value {
+                    items.iter_mut().for_each(convert_schema_to_v3_1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // `allOf: [{ $ref }, { nullable: true }]` is how 3.0 expresses a nullable
+    // reference. Once `nullable` has been handled the patch schema is empty, and
+    // since `$ref` may have siblings in 3.1 the remaining item can be inlined.
+    if let Some(Value::Array(mut items)) = schema.remove("allOf") {
+        items.retain(|item| !matches!(item, Value::Object(item) if item.is_empty()));
+        match items.as_mut_slice() {
+            [Value::Object(item)] if item.keys().all(|key| !schema.contains_key(key)) => {
+                schema.extend(std::mem::take(item));
+            }
+            _ => {
+                schema.insert("allOf".to_string(), Value::Array(items));
+            }
+        }
+    }
+
+    for (exclusive, limit) in [
+        ("exclusiveMaximum", "maximum"),
+        ("exclusiveMinimum", "minimum"),
+    ] {
+        if let Some(Value::Bool(is_exclusive)) = schema.remove(exclusive) {
+            if is_exclusive {
+                if let Some(limit) = schema.remove(limit) {
+                    schema.insert(exclusive.to_string(), limit);
+                }
+            }
+        }
+    }
+
+    if let Some(example) = schema.remove("example") {
+        schema.insert("examples".to_string(), Value::Array(vec![example]));
+    }
+
+    if schema.remove("nullable") == Some(Value::Bool(true)) && !schema.is_empty() {
+        match schema.get_mut("type") {
+            Some(Value::String(ty)) => {
+                let ty = std::mem::take(ty);
+                schema.insert("type".to_string(), serde_json::json!([ty, "null"]));
+            }
+            _ => {
+                let inner = std::mem::take(schema);
+                schema.insert(
+                    "anyOf".to_string(),
+                    serde_json::json!([inner, { "type": "null" }]),
+                );
+            }
+        }
+    }
+}
+
+/// Serializes `value`, converting its schemas for OpenAPI 3.1 if needed.
+fn serialize_versioned<S, T>(
+    s: &mut S,
+    key: &str,
+    value: &T,
+    version: OpenApiVersion,
+    is_schema_map: bool,
+) -> Result<(), S::Error>
+where
+    S: SerializeMap,
+    T: Serialize,
+{
+    match version {
+        OpenApiVersion::V3_0 => s.serialize_entry(key, value),
+        OpenApiVersion::V3_1 => {
+            let mut value = serde_json::to_value(value).map_err(serde::ser::Error::custom)?;
+            if is_schema_map {
+                if let Value::Object(schemas) = &mut value {
+                    schemas.values_mut().for_each(convert_schema_to_v3_1);
+                }
+            } else {
+                convert_to_v3_1(&mut value);
+            }
+            s.serialize_entry(key, &value)
+        }
+    }
+}
 
 impl Serialize for MetaSchemaRef {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -39,6 +169,49 @@ impl Serialize for PathMap<'_> {
     }
 }
 
+/// The tags defined by the service in order, merged with the tags of the
+/// registry, followed by the other tags of the registry.
+struct TagList<'a>(&'a BTreeSet<MetaTag>, &'a [MetaTagDefinition]);
+
+impl Serialize for TagList<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Tag<'a> {
+            name: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            description: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            external_docs: Option<&'a MetaExternalDocument>,
+            #[serde(flatten)]
+            extensions: Option<&'a BTreeMap<String, Value>>,
+        }
+
+        let mut s = serializer.serialize_seq(None)?;
+        for definition in self.1 {
+            let tag = self.0.iter().find(|tag| tag.name == definition.name);
+            s.serialize_element(&Tag {
+                name: &definition.name,
+                description: definition
+                    .description
+                    .as_deref()
+                    .or_else(|| tag.and_then(|tag| tag.description)),
+                external_docs: definition
+                    .external_docs
+                    .as_ref()
+                    .or_else(|| tag.and_then(|tag| tag.external_docs.as_ref())),
+                extensions: tag.map(|tag| &tag.extensions),
+            })?;
+        }
+        for tag in self.0 {
+            if !self.1.iter().any(|definition| definition.name == tag.name) {
+                s.serialize_element(tag)?;
+            }
+        }
+        s.end()
+    }
+}
+
 impl Serialize for MetaPath {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(None)?;
@@ -51,6 +224,19 @@ impl Serialize for MetaPath {
     }
 }
 
+impl Serialize for MetaCallback {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut operations = BTreeMap::new();
+        for operation in &self.operations {
+            operations.insert(operation.method.to_string().to_lowercase(), operation);
+        }
+
+        let mut s = serializer.serialize_map(Some(1))?;
+        s.serialize_entry(self.url, &operations)?;
+        s.end()
+    }
+}
+
 impl Serialize for MetaResponses {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(None)?;
@@ -89,36 +275,69 @@ pub(crate) struct Document<'a> {
     pub(crate) servers: &'a [MetaServer],
     pub(crate) apis: Vec<MetaApi>,
     pub(crate) webhooks: Vec<MetaWebhook>,
+    pub(crate) extra_schemas: Vec<MetaSchemaRef>,
     pub(crate) registry: Registry,
     pub(crate) external_document: Option<&'a MetaExternalDocument>,
+    pub(crate) tags: &'a [MetaTagDefinition],
+    pub(crate) tag_groups: &'a [MetaTagGroup],
     pub(crate) url_prefix: Option<&'a str>,
+    pub(crate) version: OpenApiVersion,
+    pub(crate) extensions: &'a BTreeMap<String, Value>,
 }
 
 impl Serialize for Document<'_> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        #[derive(Serialize)]
-        #[serde(rename_all = "camelCase")]
         struct Components<'a> {
             schemas: &'a BTreeMap<String, MetaSchema>,
-            #[serde(skip_serializing_if = "BTreeMap::is_empty")]
             security_schemes: &'a BTreeMap<&'static str, MetaSecurityScheme>,
+            version: OpenApiVersion,
+        }
+
+        impl Serialize for Components<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut s = serializer.serialize_map(None)?;
+                serialize_versioned(&mut s, "schemas", self.schemas, self.version, true)?;
+                if !self.security_schemes.is_empty() {
+                    s.serialize_entry("securitySchemes", self.security_schemes)?;
+                }
+                s.end()
+            }
         }
 
         let mut s = serializer.serialize_map(None)?;
 
-        s.serialize_entry("openapi", OPENAPI_VERSION)?;
+        match self.version {
+            OpenApiVersion::V3_0 => s.serialize_entry("openapi", "3.0.0")?,
+            OpenApiVersion::V3_1 => {
+                s.serialize_entry("openapi", "3.1.0")?;
+                s.serialize_entry("jsonSchemaDialect", JSON_SCHEMA_DIALECT_3_1)?;
+            }
+        }
         s.serialize_entry("info", &self.info)?;
         s.serialize_entry("servers", self.servers)?;
-        s.serialize_entry("tags", &self.registry.tags)?;
+        s.serialize_entry("tags", &TagList(&self.registry.tags, self.tags))?;
         if !self.webhooks.is_empty() {
-            s.serialize_entry("webhooks", &WebhookMap(&self.webhooks))?;
+            serialize_versioned(
+                &mut s,
+                "webhooks",
+                &WebhookMap(&self.webhooks),
+                self.version,
+                false,
+            )?;
         }
-        s.serialize_entry("paths", &PathMap(&self.apis, self.url_prefix))?;
+        serialize_versioned(
+            &mut s,
+            "paths",
+            &PathMap(&self.apis, self.url_prefix),
+            self.version,
+            false,
+        )?;
         s.serialize_entry(
             "components",
             &Components {
                 schemas: &self.registry.schemas,
                 security_schemes: &self.registry.security_schemes,
+                version: self.version,
             },
         )?;
 
@@ -126,6 +345,14 @@ impl Serialize for Document<'_> {
             s.serialize_entry("externalDocs", &external_document)?;
         }
 
+        if !self.tag_groups.is_empty() {
+            s.serialize_entry("x-tagGroups", self.tag_groups)?;
+        }
+
+        for (name, value) in self.extensions {
+            s.serialize_entry(name, value)?;
+        }
+
         s.end()
     }
 }
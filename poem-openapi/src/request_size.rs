@@ -0,0 +1,118 @@
+//! The maximum sizes of the request payloads.
+//!
+//! The limit of an operation is declared with the
+//! `#[oai(max_request_size = "5MB")]` attribute, and the default limit of the
+//! other operations with
+//! [`OpenApiService::max_request_size`](crate::OpenApiService::max_request_size).
+
+use std::{
+    collections::HashMap,
+    io::Error as IoError,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use futures_util::StreamExt;
+use poem::{
+    Body, Endpoint, EndpointExt, Request, Result,
+    endpoint::{BoxEndpoint, ShutdownHooks},
+    http::Method,
+};
+use serde_json::Value;
+
+use crate::{
+    error::PayloadTooLargeError,
+    registry::{MetaOperation, MetaResponse},
+    schema_validation::map_operations,
+};
+
+/// Wraps the endpoint of an operation with a maximum size of the request
+/// payload.
+#[doc(hidden)]
+pub fn limit_request_size<E: Endpoint>(ep: E, limit: u64) -> impl Endpoint<Output = E::Output> {
+    RequestSizeLimitEndpoint { inner: ep, limit }
+}
+
+/// Returns the response of the requests with too large payloads.
+#[doc(hidden)]
+pub fn payload_too_large_response() -> MetaResponse {
+    MetaResponse {
+        description: "Payload too large",
+        status: Some(413),
+        status_range: None,
+        content: Vec::new(),
+        headers: Vec::new(),
+        links: Vec::new(),
+    }
+}
+
+/// Wraps the endpoints of the operations without a maximum size with the
+/// default maximum size.
+pub(crate) fn wrap_routes(
+    limit: u64,
+    operations: &HashMap<String, MetaOperation>,
+    spec: &Value,
+    url_prefix: Option<&str>,
+    routes: &mut HashMap<String, HashMap<Method, BoxEndpoint<'static>>>,
+) {
+    map_operations(spec, url_prefix, routes, |name, _, ep| {
+        match operations
+            .get(name)
+            .and_then(|operation| operation.max_request_size)
+        {
+            Some(_) => ep,
+            None => limit_request_size(ep, limit).boxed(),
+        }
+    });
+}
+
+struct RequestSizeLimitEndpoint<E> {
+    inner: E,
+    limit: u64,
+}
+
+impl<E: Endpoint> Endpoint for RequestSizeLimitEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let limit = self.limit;
+        let content_length = req
+            .headers()
+            .get(poem::http::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if content_length.is_some_and(|len| len > limit) {
+            return Err(PayloadTooLargeError { limit }.into());
+        }
+
+        // the payload is cut when the limit is exceeded, so that the
+        // extractors fail without reading the rest of it
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let mut size = 0;
+        let stream = req.take_body().into_bytes_stream().map({
+            let exceeded = exceeded.clone();
+            move |chunk| {
+                let chunk = chunk?;
+                size += chunk.len() as u64;
+                if size > limit {
+                    exceeded.store(true, Ordering::Relaxed);
+                    return Err(IoError::other(PayloadTooLargeError { limit }));
+                }
+                Ok(chunk)
+            }
+        });
+        req.set_body(Body::from_bytes_stream(stream));
+
+        let res = self.inner.call(req).await;
+        match exceeded.load(Ordering::Relaxed) {
+            true => Err(PayloadTooLargeError { limit }.into()),
+            false => res,
+        }
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, error::Error as StdError};
+
+use poem::{
+    Error, IntoResponse, Response,
+    error::{ErrorCode, ErrorCodeInfo, ResponseError},
+};
+use serde_json::Value;
+
+use crate::{
+    ApiResponse,
+    payload::{Json, Payload},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+};
+
+/// An error response for types that implement
+/// [`ErrorCode`](trait@ErrorCode).
+///
+/// Each status code of the error type is documented as a response whose
+/// JSON body contains the possible codes for that status.
+///
+/// # Examples
+///
+/// ```
+/// use poem::error::ErrorCode;
+/// use poem_openapi::{ErrorCodeResponse, OpenApi, param::Path, payload::PlainText};
+///
+/// #[derive(Debug, thiserror::Error, ErrorCode)]
+/// enum UserError {
+///     /// The user does not exist.
+///     #[error("user not found")]
+///     #[error_code(code = "USER_NOT_FOUND", status = 404)]
+///     NotFound,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/user/:id", method = "get")]
+///     async fn get(
+///         &self,
+///         id: Path<i64>,
+///     ) -> Result<PlainText<String>, ErrorCodeResponse<UserError>> {
+///         match id.0 {
+///             1 => Ok(PlainText("sunli".to_string())),
+///             _ => Err(UserError::NotFound.into()),
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ErrorCodeResponse<E>(pub E);
+
+impl<E: ErrorCode> From<E> for ErrorCodeResponse<E> {
+    fn from(err: E) -> Self {
+        Self(err)
+    }
+}
+
+impl<E> From<ErrorCodeResponse<E>> for Error
+where
+    E: ResponseError + StdError + Send + Sync + 'static,
+{
+    fn from(resp: ErrorCodeResponse<E>) -> Self {
+        Error::from(resp.0)
+    }
+}
+
+impl<E> IntoResponse for ErrorCodeResponse<E>
+where
+    E: ResponseError + StdError + Send + Sync + 'static,
+{
+    fn into_response(self) -> Response {
+        Error::from(self.0).into_response()
+    }
+}
+
+impl<E> ApiResponse for ErrorCodeResponse<E>
+where
+    E: ErrorCode,
+{
+    fn meta() -> MetaResponses {
+        let mut codes = BTreeMap::<u16, Vec<ErrorCodeInfo>>::new();
+        for info in E::error_codes() {
+            codes.entry(info.status.as_u16()).or_default().push(info);
+        }
+
+        MetaResponses {
+            responses: codes
+                .into_iter()
+                .map(|(status, infos)| MetaResponse {
+                    description: match infos.as_slice() {
+                        [info] => info.description.unwrap_or_default(),
+                        _ => infos[0].status.canonical_reason().unwrap_or_default(),
+                    },
+                    status: Some(status),
+                    status_range: None,
+                    content: vec![MetaMediaType {
+                        content_type: Json::<()>::CONTENT_TYPE,
+                        schema: error_body_schema(&infos),
+                        example: None,
+                        examples: vec![],
+                    }],
+                    headers: vec![],
+                    links: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+fn error_body_schema(infos: &[ErrorCodeInfo]) -> MetaSchemaRef {
+    let code = MetaSchema {
+        enum_items: infos
+            .iter()
+            .map(|info| Value::String(info.code.to_string()))
+            .collect(),
+        ..MetaSchema::new("string")
+    };
+
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        required: vec!["code", "message"],
+        properties: vec![
+            ("code", MetaSchemaRef::Inline(Box::new(code))),
+            (
+                "message",
+                MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+            ),
+        ],
+        ..MetaSchema::new("object")
+    }))
+}
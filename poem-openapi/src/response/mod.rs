@@ -1,4 +1,7 @@
 //! Commonly used response types.
 
+mod error_code;
 #[cfg(feature = "static-files")]
 mod static_file;
+
+pub use error_code::ErrorCodeResponse;
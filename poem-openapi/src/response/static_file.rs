@@ -22,6 +22,8 @@ impl ApiResponse for StaticFileResponse {
                     content: vec![MetaMediaType {
                         content_type: Binary::<Body>::CONTENT_TYPE,
                         schema: Binary::<Body>::schema_ref(),
+                        example: None,
+                        examples: vec![],
                     }],
                     headers: vec![
                         MetaHeader {
@@ -46,6 +48,7 @@ impl ApiResponse for StaticFileResponse {
                             schema: String::schema_ref(),
                         },
                     ],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "Not modified",
@@ -53,6 +56,7 @@ impl ApiResponse for StaticFileResponse {
                     status_range: None,
                     content: vec![],
                     headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "Bad request",
@@ -60,6 +64,7 @@ impl ApiResponse for StaticFileResponse {
                     status_range: None,
                     content: vec![],
                     headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "Resource was not found",
@@ -67,6 +72,7 @@ impl ApiResponse for StaticFileResponse {
                     status_range: None,
                     content: vec![],
                     headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "Precondition failed",
@@ -74,6 +80,7 @@ impl ApiResponse for StaticFileResponse {
                     status_range: None,
                     content: vec![],
                     headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "The Content-Range response HTTP header indicates where in a full body message a partial message belongs.",
@@ -81,6 +88,7 @@ impl ApiResponse for StaticFileResponse {
                     status_range: None,
                     content: vec![],
                     headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "Internal server error",
@@ -88,6 +96,7 @@ impl ApiResponse for StaticFileResponse {
                     status_range: None,
                     content: vec![],
                     headers: vec![],
+                    links: vec![],
                 },
             ],
         }
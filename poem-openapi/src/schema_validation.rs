@@ -0,0 +1,694 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::Arc,
+};
+
+use poem::{
+    Endpoint, EndpointExt, IntoResponse, Request, Response, Result,
+    endpoint::{BoxEndpoint, ShutdownHooks},
+    http::{Method, header},
+};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::error::SchemaValidationError;
+
+/// The maximum number of nested references that are followed without
+/// descending into the value, which prevents the infinite recursions.
+const MAX_DEPTH: usize = 64;
+
+/// Validation of the requests and responses against the schemas of the
+/// specification at runtime.
+///
+/// Only the JSON payloads are validated. This checks what the type system
+/// cannot enforce, e.g. the formats and the validators of the
+/// [`serde_json::Value`] passthroughs, or the unknown properties.
+///
+/// By default the mismatches are logged, use
+/// [`SchemaValidation::reject`] to reject them. A request that does not
+/// match its schema is rejected with `400 Bad Request`, and a response with
+/// `500 Internal Server Error`.
+///
+/// # Examples
+///
+/// ```
+/// use poem_openapi::{OpenApi, OpenApiService, SchemaValidation};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {}
+///
+/// let api_service = OpenApiService::new(Api, "Demo", "1.0").schema_validation(
+///     SchemaValidation::new()
+///         .reject(true)
+///         .deny_unknown_properties(true),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchemaValidation {
+    requests: bool,
+    responses: bool,
+    reject: bool,
+    deny_unknown_properties: bool,
+}
+
+impl Default for SchemaValidation {
+    fn default() -> Self {
+        Self {
+            requests: true,
+            responses: true,
+            reject: false,
+            deny_unknown_properties: false,
+        }
+    }
+}
+
+impl SchemaValidation {
+    /// Create a schema validation of the requests and responses that logs
+    /// the mismatches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the validation of the requests, default is
+    /// `true`.
+    #[must_use]
+    pub fn requests(self, enabled: bool) -> Self {
+        Self {
+            requests: enabled,
+            ..self
+        }
+    }
+
+    /// Enables or disables the validation of the responses, default is
+    /// `true`.
+    #[must_use]
+    pub fn responses(self, enabled: bool) -> Self {
+        Self {
+            responses: enabled,
+            ..self
+        }
+    }
+
+    /// Rejects the mismatches instead of logging them, default is `false`.
+    #[must_use]
+    pub fn reject(self, reject: bool) -> Self {
+        Self { reject, ..self }
+    }
+
+    /// Treats the properties that are not declared by an object schema as
+    /// mismatches, unless the schema specifies `additionalProperties`.
+    #[must_use]
+    pub fn deny_unknown_properties(self, deny: bool) -> Self {
+        Self {
+            deny_unknown_properties: deny,
+            ..self
+        }
+    }
+
+    /// Wraps the endpoints of the routes with the validation of the
+    /// operations of the specification.
+    pub(crate) fn wrap_routes(
+        &self,
+        spec: Value,
+        url_prefix: Option<&str>,
+        routes: &mut HashMap<String, HashMap<Method, BoxEndpoint<'static>>>,
+    ) {
+        let spec = Arc::new(spec);
+        map_operations(&spec, url_prefix, routes, |name, operation, ep| {
+            SchemaValidationEndpoint {
+                inner: ep,
+                config: self.clone(),
+                spec: spec.clone(),
+                operation: operation.clone(),
+                name: name.to_string(),
+            }
+            .boxed()
+        });
+    }
+}
+
+/// Maps the endpoints of the routes that are operations of the
+/// specification, the function receives the name of the operation, e.g.
+/// `GET /users/{id}`, and the operation of the specification.
+pub(crate) fn map_operations(
+    spec: &Value,
+    url_prefix: Option<&str>,
+    routes: &mut HashMap<String, HashMap<Method, BoxEndpoint<'static>>>,
+    mut f: impl FnMut(&str, &Value, BoxEndpoint<'static>) -> BoxEndpoint<'static>,
+) {
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return;
+    };
+    let paths: Vec<_> = paths
+        .iter()
+        .map(|(spec_path, item)| {
+            let path = url_prefix
+                .and_then(|prefix| spec_path.strip_prefix(prefix.trim_end_matches('/')))
+                .unwrap_or(spec_path);
+            (normalize_path(path), (spec_path, item))
+        })
+        .collect();
+
+    for (path, methods) in routes.iter_mut() {
+        let normalized = normalize_path(path);
+        let Some((_, (spec_path, item))) = paths.iter().find(|(path, _)| *path == normalized)
+        else {
+            continue;
+        };
+        *methods = std::mem::take(methods)
+            .into_iter()
+            .map(|(method, ep)| {
+                let ep = match item.get(method.as_str().to_ascii_lowercase()) {
+                    Some(operation) => f(&format!("{method} {spec_path}"), operation, ep),
+                    None => ep,
+                };
+                (method, ep)
+            })
+            .collect();
+    }
+}
+
+/// Replaces the path parameters by `{}`, so that the paths of the routes
+/// (`/:param0`) and of the specification (`/{id}`) can be compared.
+fn normalize_path(path: &str) -> String {
+    path.split('/')
+        .map(
+            |segment| match segment.starts_with(':') || segment.starts_with('{') {
+                true => "{}",
+                false => segment,
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub(crate) struct SchemaValidationEndpoint<E> {
+    inner: E,
+    config: SchemaValidation,
+    spec: Arc<Value>,
+    operation: Value,
+    name: String,
+}
+
+fn is_json(content_type: &str) -> bool {
+    content_type.parse::<mime::Mime>().is_ok_and(|mime| {
+        mime.type_() == "application"
+            && (mime.subtype() == "json" || mime.suffix().is_some_and(|suffix| suffix == "json"))
+    })
+}
+
+fn essence(content_type: &str) -> String {
+    match content_type.parse::<mime::Mime>() {
+        Ok(mime) => mime.essence_str().to_string(),
+        Err(_) => content_type.to_string(),
+    }
+}
+
+/// Returns the schema of the content with the specified content type.
+fn content_schema<'a>(content: &'a Value, content_type: &str) -> Option<&'a Value> {
+    let content_type = essence(content_type);
+    content
+        .get("content")?
+        .as_object()?
+        .iter()
+        .find(|(ty, _)| essence(ty) == content_type)
+        .and_then(|(_, media)| media.get("schema"))
+}
+
+impl<E: Endpoint> SchemaValidationEndpoint<E> {
+    fn validator(&self) -> Validator<'_> {
+        Validator {
+            schemas: self.spec["components"]["schemas"].as_object(),
+            deny_unknown_properties: self.config.deny_unknown_properties,
+        }
+    }
+
+    fn request_schema(&self, req: &Request) -> Option<&Value> {
+        let content_type = req.content_type().filter(|ty| is_json(ty))?;
+        content_schema(self.operation.get("requestBody")?, content_type)
+    }
+
+    fn response_schema(&self, resp: &Response) -> Option<&Value> {
+        let content_type = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .filter(|ty| is_json(ty))?;
+        let responses = self.operation.get("responses")?;
+        let status = resp.status().as_u16().to_string();
+        let range = format!("{}XX", &status[..1]);
+        let response = responses
+            .get(&status)
+            .or_else(|| responses.get(&range))
+            .or_else(|| responses.get("default"))?;
+        content_schema(response, content_type)
+    }
+
+    fn report(&self, err: SchemaValidationError) -> Result<()> {
+        match self.config.reject {
+            true => Err(err.into()),
+            false => {
+                tracing::warn!(operation = %self.name, error = %err, "schema validation failed");
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<E: Endpoint> Endpoint for SchemaValidationEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        if self.config.requests {
+            if let Some(schema) = self.request_schema(&req) {
+                let data = req.take_body().into_bytes().await?;
+                if let Ok(value) = serde_json::from_slice::<Value>(&data) {
+                    let errors = self.validator().validate(schema, &value);
+                    if !errors.is_empty() {
+                        self.report(SchemaValidationError::Request(errors))?;
+                    }
+                }
+                req.set_body(data);
+            }
+        }
+
+        let mut resp = self.inner.call(req).await?.into_response();
+
+        if self.config.responses {
+            if let Some(schema) = self.response_schema(&resp) {
+                let schema = schema.clone();
+                let data = resp.take_body().into_bytes().await?;
+                if let Ok(value) = serde_json::from_slice::<Value>(&data) {
+                    let errors = self.validator().validate(&schema, &value);
+                    if !errors.is_empty() {
+                        self.report(SchemaValidationError::Response(errors))?;
+                    }
+                }
+                resp.set_body(data);
+            }
+        }
+
+        Ok(resp)
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+struct Validator<'a> {
+    schemas: Option<&'a Map<String, Value>>,
+    deny_unknown_properties: bool,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(&self, schema: &'a Value, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        self.validate_value(schema, value, "$", true, 0, &mut errors);
+        errors
+    }
+
+    fn resolve(&self, schema: &'a Value) -> Option<&'a Value> {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self
+                .schemas?
+                .get(reference.strip_prefix("#/components/schemas/")?),
+            None => Some(schema),
+        }
+    }
+
+    fn validate_value(
+        &self,
+        schema: &'a Value,
+        value: &Value,
+        path: &str,
+        check_unknown: bool,
+        depth: usize,
+        errors: &mut Vec<String>,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let Some(schema) = self.resolve(schema) else {
+            return;
+        };
+        if schema.get("$ref").is_some() {
+            return self.validate_value(schema, value, path, check_unknown, depth + 1, errors);
+        }
+
+        for item in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.validate_value(item, value, path, false, depth + 1, errors);
+        }
+        if let Some(items) = schema.get("anyOf").and_then(Value::as_array) {
+            self.validate_any(items, value, path, depth, errors);
+        }
+        if let Some(items) = schema.get("oneOf").and_then(Value::as_array) {
+            match self.discriminated(schema, value) {
+                Some(item) => self.validate_value(item, value, path, true, depth + 1, errors),
+                None => self.validate_any(items, value, path, depth, errors),
+            }
+        }
+
+        if value.is_null() {
+            let nullable = schema.get("nullable") == Some(&Value::Bool(true))
+                || match schema.get("type") {
+                    Some(Value::String(ty)) => ty == "null",
+                    Some(Value::Array(types)) => types.iter().any(|ty| ty == "null"),
+                    _ => true,
+                };
+            if !nullable {
+                errors.push(format!("{path}: expected a value, found null"));
+            }
+            return;
+        }
+
+        if let Some(ty) = schema.get("type") {
+            let types: Vec<&str> = match ty {
+                Value::String(ty) => vec![ty.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !types.is_empty() && !types.iter().any(|ty| is_type(value, ty)) {
+                errors.push(format!(
+                    "{path}: expected {}, found {}",
+                    types.join(" or "),
+                    type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(value) {
+                errors.push(format!("{path}: {value} is not an allowed value"));
+            }
+        }
+
+        match value {
+            Value::String(s) => self.validate_string(schema, s, path, errors),
+            Value::Number(n) => {
+                if let Some(n) = n.as_f64() {
+                    validate_number(schema, n, path, errors);
+                }
+            }
+            Value::Array(items) => {
+                let len = items.len();
+                if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                    if (len as u64) < min {
+                        errors.push(format!("{path}: expected at least {min} items"));
+                    }
+                }
+                if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                    if len as u64 > max {
+                        errors.push(format!("{path}: expected at most {max} items"));
+                    }
+                }
+                if schema.get("uniqueItems") == Some(&Value::Bool(true))
+                    && items
+                        .iter()
+                        .enumerate()
+                        .any(|(idx, item)| items[..idx].contains(item))
+                {
+                    errors.push(format!("{path}: expected unique items"));
+                }
+                if let Some(item_schema) = schema.get("items") {
+                    for (idx, item) in items.iter().enumerate() {
+                        self.validate_value(
+                            item_schema,
+                            item,
+                            &format!("{path}[{idx}]"),
+                            true,
+                            0,
+                            errors,
+                        );
+                    }
+                }
+            }
+            Value::Object(object) => {
+                self.validate_object(schema, object, path, check_unknown, errors)
+            }
+            _ => {}
+        }
+    }
+
+    fn validate_any(
+        &self,
+        items: &'a [Value],
+        value: &Value,
+        path: &str,
+        depth: usize,
+        errors: &mut Vec<String>,
+    ) {
+        let matched = items.iter().any(|item| {
+            let mut item_errors = Vec::new();
+            self.validate_value(item, value, path, true, depth + 1, &mut item_errors);
+            item_errors.is_empty()
+        });
+        if !matched {
+            errors.push(format!("{path}: does not match any of the allowed schemas"));
+        }
+    }
+
+    /// Returns the schema selected by the discriminator property.
+    fn discriminated(&self, schema: &'a Value, value: &Value) -> Option<&'a Value> {
+        let discriminator = schema.get("discriminator")?;
+        let property = discriminator.get("propertyName")?.as_str()?;
+        let name = value.get(property)?.as_str()?;
+        let reference = match discriminator
+            .get("mapping")
+            .and_then(|mapping| mapping.get(name))
+        {
+            Some(reference) => reference.as_str()?.to_string(),
+            None => format!("#/components/schemas/{name}"),
+        };
+        schema
+            .get("oneOf")?
+            .as_array()?
+            .iter()
+            .find(|item| item.get("$ref").and_then(Value::as_str) == Some(&reference))
+    }
+
+    fn validate_string(&self, schema: &Value, s: &str, path: &str, errors: &mut Vec<String>) {
+        let len = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if len < min {
+                errors.push(format!("{path}: expected at least {min} characters"));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if len > max {
+                errors.push(format!("{path}: expected at most {max} characters"));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if Regex::new(pattern).is_ok_and(|re| !re.is_match(s)) {
+                errors.push(format!("{path}: does not match the pattern `{pattern}`"));
+            }
+        }
+        if let Some(format) = schema.get("format").and_then(Value::as_str) {
+            if !is_format(format, s) {
+                errors.push(format!("{path}: is not a valid `{format}`"));
+            }
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &'a Value,
+        object: &Map<String, Value>,
+        path: &str,
+        check_unknown: bool,
+        errors: &mut Vec<String>,
+    ) {
+        for name in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(name) = name.as_str() {
+                if !object.contains_key(name) {
+                    errors.push(format!("{path}: missing the required property `{name}`"));
+                }
+            }
+        }
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if (object.len() as u64) < min {
+                errors.push(format!("{path}: expected at least {min} properties"));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if object.len() as u64 > max {
+                errors.push(format!("{path}: expected at most {max} properties"));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (name, value) in object {
+            let property_path = format!("{path}.{name}");
+            match properties.and_then(|properties| properties.get(name)) {
+                Some(property) => {
+                    self.validate_value(property, value, &property_path, true, 0, errors)
+                }
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        errors.push(format!("{property_path}: unknown property"))
+                    }
+                    Some(Value::Bool(true)) => {}
+                    Some(additional) => {
+                        self.validate_value(additional, value, &property_path, true, 0, errors)
+                    }
+                    None => {
+                        if check_unknown
+                            && self.deny_unknown_properties
+                            && !self.known_properties(schema, 0).contains(name.as_str())
+                            && self.is_closed(schema, 0)
+                        {
+                            errors.push(format!("{property_path}: unknown property"));
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    /// Returns the properties declared by a schema and the schemas it
+    /// extends.
+    fn known_properties(&self, schema: &'a Value, depth: usize) -> HashSet<&'a str> {
+        let mut names = HashSet::new();
+        let Some(schema) = self.resolve(schema).filter(|_| depth <= MAX_DEPTH) else {
+            return names;
+        };
+        names.extend(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flat_map(|properties| properties.keys().map(String::as_str)),
+        );
+        for item in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            names.extend(self.known_properties(item, depth + 1));
+        }
+        names
+    }
+
+    /// Returns `true` if a schema declares its properties and does not accept
+    /// other ones.
+    fn is_closed(&self, schema: &'a Value, depth: usize) -> bool {
+        let Some(schema) = self.resolve(schema).filter(|_| depth <= MAX_DEPTH) else {
+            return false;
+        };
+        if schema.get("additionalProperties").is_some()
+            || schema.get("anyOf").is_some()
+            || schema.get("oneOf").is_some()
+        {
+            return false;
+        }
+        let all_of = schema.get("allOf").and_then(Value::as_array);
+        (schema.get("properties").is_some() || all_of.is_some())
+            && all_of
+                .into_iter()
+                .flatten()
+                .all(|item| self.is_closed(item, depth + 1))
+    }
+}
+
+fn is_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "string" => value.is_string(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn validate_number(schema: &Value, n: f64, path: &str, errors: &mut Vec<String>) {
+    // `exclusiveMaximum` is a boolean in OpenAPI 3.0 and a number in 3.1
+    match (
+        schema.get("maximum").and_then(Value::as_f64),
+        schema.get("exclusiveMaximum"),
+    ) {
+        (Some(max), Some(Value::Bool(true))) if n >= max => {
+            errors.push(format!("{path}: expected a value less than {max}"))
+        }
+        (_, Some(Value::Number(max))) if max.as_f64().is_some_and(|max| n >= max) => {
+            errors.push(format!("{path}: expected a value less than {max}"))
+        }
+        (Some(max), _) if n > max => errors.push(format!(
+            "{path}: expected a value less than or equal to {max}"
+        )),
+        _ => {}
+    }
+    match (
+        schema.get("minimum").and_then(Value::as_f64),
+        schema.get("exclusiveMinimum"),
+    ) {
+        (Some(min), Some(Value::Bool(true))) if n <= min => {
+            errors.push(format!("{path}: expected a value greater than {min}"))
+        }
+        (_, Some(Value::Number(min))) if min.as_f64().is_some_and(|min| n <= min) => {
+            errors.push(format!("{path}: expected a value greater than {min}"))
+        }
+        (Some(min), _) if n < min => errors.push(format!(
+            "{path}: expected a value greater than or equal to {min}"
+        )),
+        _ => {}
+    }
+    if let Some(multiple_of) = schema.get("multipleOf").and_then(Value::as_f64) {
+        if multiple_of > 0.0 && (n / multiple_of).fract() != 0.0 {
+            errors.push(format!("{path}: expected a multiple of {multiple_of}"));
+        }
+    }
+}
+
+fn is_format(format: &str, s: &str) -> bool {
+    fn matches(pattern: &str, s: &str) -> bool {
+        Regex::new(pattern).is_ok_and(|re| re.is_match(s))
+    }
+
+    match format {
+        "date" => matches(r"^\d{4}-\d{2}-\d{2}$", s),
+        "date-time" => matches(
+            r"^\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$",
+            s,
+        ),
+        "uuid" => matches(
+            r"^[0-9a-fA-F]{8}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{12}$",
+            s,
+        ),
+        "email" => matches(r"^[^@\s]+@[^@\s]+$", s),
+        "ipv4" => s.parse::<IpAddr>().is_ok_and(|ip| ip.is_ipv4()),
+        "ipv6" => s.parse::<IpAddr>().is_ok_and(|ip| ip.is_ipv6()),
+        _ => true,
+    }
+}
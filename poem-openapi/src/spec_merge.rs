@@ -0,0 +1,159 @@
+use serde_json::Value;
+
+/// Merges a fragment into the specification, and returns the sorted JSON
+/// pointers of the conflicting values.
+///
+/// The objects are merged recursively and the arrays are merged without
+/// duplicates. The operations and the components are not merged, they
+/// conflict if both are defined and not equal. The version and the info of
+/// the fragment are ignored.
+pub(crate) fn merge_spec(spec: &mut Value, fragment: &Value) -> Vec<String> {
+    let mut conflicts = Vec::new();
+    let mut fragment = fragment.clone();
+    if let Some(fragment) = fragment.as_object_mut() {
+        fragment.remove("openapi");
+        fragment.remove("info");
+    }
+    merge_value(spec, &fragment, &mut Vec::new(), &mut conflicts);
+    // the iteration order of the objects depends on the `preserve_order`
+    // feature of `serde_json`
+    conflicts.sort();
+    conflicts
+}
+
+/// Returns `true` if the value at the path is a single definition, i.e. an
+/// operation of `/paths` or a component of `/components`.
+fn is_definition(path: &[&str]) -> bool {
+    matches!(
+        path,
+        ["paths", _, _] | ["webhooks", _, _] | ["components", _, _]
+    )
+}
+
+fn pointer(path: &[&str]) -> String {
+    path.iter()
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn merge_value<'a>(
+    target: &mut Value,
+    fragment: &'a Value,
+    path: &mut Vec<&'a str>,
+    conflicts: &mut Vec<String>,
+) {
+    match (target, fragment) {
+        (Value::Object(target), Value::Object(fragment)) => {
+            for (key, value) in fragment {
+                path.push(key);
+                match target.get_mut(key) {
+                    Some(target) if is_definition(path) => {
+                        if target != value {
+                            conflicts.push(pointer(path));
+                        }
+                    }
+                    Some(target) => merge_value(target, value, path, conflicts),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(target), Value::Array(fragment)) => {
+            for value in fragment {
+                if !target.contains(value) {
+                    target.push(value.clone());
+                }
+            }
+        }
+        (target, fragment) => {
+            if target != fragment {
+                conflicts.push(pointer(path));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let mut spec = json!({
+            "tags": [{ "name": "a" }],
+            "paths": {
+                "/a": { "get": { "operationId": "a" } },
+            },
+            "components": {
+                "schemas": { "A": { "type": "string" } },
+            },
+        });
+        let conflicts = merge_spec(
+            &mut spec,
+            &json!({
+                "tags": [{ "name": "a" }, { "name": "b" }],
+                "paths": {
+                    "/a": { "post": { "operationId": "b" } },
+                    "/b": { "get": { "operationId": "c" } },
+                },
+                "components": {
+                    "schemas": { "A": { "type": "string" }, "B": { "type": "integer" } },
+                    "securitySchemes": { "key": { "type": "apiKey" } },
+                },
+            }),
+        );
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            spec,
+            json!({
+                "tags": [{ "name": "a" }, { "name": "b" }],
+                "paths": {
+                    "/a": {
+                        "get": { "operationId": "a" },
+                        "post": { "operationId": "b" },
+                    },
+                    "/b": { "get": { "operationId": "c" } },
+                },
+                "components": {
+                    "schemas": { "A": { "type": "string" }, "B": { "type": "integer" } },
+                    "securitySchemes": { "key": { "type": "apiKey" } },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut spec = json!({
+            "openapi": "3.0.0",
+            "servers": [{ "url": "http://localhost" }],
+            "paths": {
+                "/a/{id}": { "get": { "operationId": "a" } },
+            },
+            "components": {
+                "schemas": { "A": { "type": "string" } },
+            },
+        });
+        let conflicts = merge_spec(
+            &mut spec,
+            &json!({
+                "openapi": "3.1.0",
+                "servers": { "url": "http://localhost" },
+                "paths": {
+                    "/a/{id}": { "get": { "operationId": "b" } },
+                },
+                "components": {
+                    "schemas": { "A": { "type": "integer" } },
+                },
+            }),
+        );
+        assert_eq!(
+            conflicts,
+            vec!["/components/schemas/A", "/paths/~1a~1{id}/get", "/servers"]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use bigdecimal::BigDecimal;
+use poem::{http::HeaderValue, web::Field};
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{
+        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
+        ToHeader, ToJSON, Type,
+    },
+};
+
+impl Type for BigDecimal {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string_decimal".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "decimal")))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl ParseFromJSON for BigDecimal {
+    fn parse_from_json(value: Option<Value>) -> ParseResult<Self> {
+        let value = value.unwrap_or_default();
+        match value {
+            Value::String(value) => Ok(value.parse()?),
+            // the number is parsed from its text to keep all the digits
+            Value::Number(num) => Ok(num.to_string().parse()?),
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+}
+
+impl ParseFromParameter for BigDecimal {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        value.parse().map_err(ParseError::custom)
+    }
+}
+
+impl ParseFromMultipartField for BigDecimal {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(field.text().await?.parse()?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl ToJSON for BigDecimal {
+    fn to_json(&self) -> Option<Value> {
+        Some(Value::String(self.normalized().to_plain_string()))
+    }
+}
+
+impl ToHeader for BigDecimal {
+    fn to_header(&self) -> Option<HeaderValue> {
+        HeaderValue::from_str(&self.normalized().to_plain_string()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_decimal() {
+        let value = "123456789012345678901234567890.120"
+            .parse::<BigDecimal>()
+            .unwrap();
+        assert_eq!(
+            value.to_json(),
+            Some(Value::String(
+                "123456789012345678901234567890.12".to_string()
+            ))
+        );
+        assert_eq!(
+            BigDecimal::parse_from_json(Some(Value::String(
+                "123456789012345678901234567890.12".to_string()
+            )))
+            .unwrap(),
+            value
+        );
+        assert_eq!(
+            "1000".parse::<BigDecimal>().unwrap().to_json(),
+            Some(Value::String("1000".to_string()))
+        );
+        assert_eq!(
+            BigDecimal::parse_from_json(Some(serde_json::json!(1.5))).unwrap(),
+            "1.5".parse::<BigDecimal>().unwrap()
+        );
+        assert!(BigDecimal::parse_from_parameter("abc").is_err());
+    }
+}
@@ -3,8 +3,9 @@ use std::{borrow::Cow, collections::BTreeMap, fmt::Display, str::FromStr};
 use serde_json::Value;
 
 use crate::{
+    param::style::split_properties,
     registry::{MetaSchema, MetaSchemaRef, Registry},
-    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 impl<K, V> Type for BTreeMap<K, V>
@@ -72,6 +73,32 @@ where
     }
 }
 
+impl<K, V> ParseFromParameter for BTreeMap<K, V>
+where
+    K: ToString + FromStr + Ord + Sync + Send,
+    K::Err: Display,
+    V: ParseFromParameter,
+{
+    const IS_OBJECT: bool = true;
+
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Self::parse_from_properties(&split_properties(value))
+    }
+
+    fn parse_from_properties(properties: &[(&str, &str)]) -> ParseResult<Self> {
+        let mut obj = BTreeMap::new();
+        for (key, value) in properties {
+            let key = key
+                .parse()
+                .map_err(|err| ParseError::custom(format!("object key: {err}")))?;
+            let value =
+                V::parse_from_parameters(std::iter::once(value)).map_err(ParseError::propagate)?;
+            obj.insert(key, value);
+        }
+        Ok(obj)
+    }
+}
+
 impl<K, V> ToJSON for BTreeMap<K, V>
 where
     K: ToString + FromStr + Ord + Sync + Send,
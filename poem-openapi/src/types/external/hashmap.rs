@@ -9,8 +9,9 @@ use std::{
 use serde_json::Value;
 
 use crate::{
+    param::style::split_properties,
     registry::{MetaSchema, MetaSchemaRef, Registry},
-    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 impl<K, V, R> Type for HashMap<K, V, R>
@@ -80,6 +81,33 @@ where
     }
 }
 
+impl<K, V, R> ParseFromParameter for HashMap<K, V, R>
+where
+    K: ToString + FromStr + Eq + Hash + Sync + Send,
+    K::Err: Display,
+    V: ParseFromParameter,
+    R: Sync + Send + Default + BuildHasher,
+{
+    const IS_OBJECT: bool = true;
+
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Self::parse_from_properties(&split_properties(value))
+    }
+
+    fn parse_from_properties(properties: &[(&str, &str)]) -> ParseResult<Self> {
+        let mut obj = HashMap::with_hasher(R::default());
+        for (key, value) in properties {
+            let key = key
+                .parse()
+                .map_err(|err| ParseError::custom(format!("object key: {err}")))?;
+            let value =
+                V::parse_from_parameters(std::iter::once(value)).map_err(ParseError::propagate)?;
+            obj.insert(key, value);
+        }
+        Ok(obj)
+    }
+}
+
 impl<K, V, R> ToJSON for HashMap<K, V, R>
 where
     K: ToString + FromStr + Eq + Hash + Sync + Send,
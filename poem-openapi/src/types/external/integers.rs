@@ -66,6 +66,10 @@ macro_rules! impl_type_for_integers {
         impl ParseFromXML for $ty {
             fn parse_from_xml(value: Option<Value>) -> ParseResult<Self> {
                 let value = value.unwrap_or_default();
+                if let Value::String(s) = &value {
+                    // XML elements are always read as text
+                    return s.trim().parse().map_err(ParseError::custom);
+                }
                 if let Value::Number(n) = value {
                     let n = n
                         .as_i64()
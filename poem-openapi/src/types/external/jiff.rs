@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+
+use jiff::{
+    SignedDuration, Timestamp,
+    civil::{Date, DateTime, Time},
+};
+use poem::{http::HeaderValue, web::Field};
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{
+        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
+        ToHeader, ToJSON, Type,
+    },
+};
+
+macro_rules! impl_jiff_types {
+    ($ty:ty, $type_name:literal, $format:literal) => {
+        impl Type for $ty {
+            const IS_REQUIRED: bool = true;
+
+            type RawValueType = Self;
+
+            type RawElementValueType = Self;
+
+            fn name() -> Cow<'static, str> {
+                concat!($type_name, "_", $format).into()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format($type_name, $format)))
+            }
+
+            fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+                Some(self)
+            }
+
+            fn raw_element_iter<'a>(
+                &'a self,
+            ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+                Box::new(self.as_raw_value().into_iter())
+            }
+        }
+
+        impl ParseFromJSON for $ty {
+            fn parse_from_json(value: Option<Value>) -> ParseResult<Self> {
+                let value = value.unwrap_or_default();
+                if let Value::String(value) = value {
+                    Ok(value.parse()?)
+                } else {
+                    Err(ParseError::expected_type(value))
+                }
+            }
+        }
+
+        impl ParseFromParameter for $ty {
+            fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+                Ok(value.parse()?)
+            }
+        }
+
+        impl ParseFromMultipartField for $ty {
+            async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+                match field {
+                    Some(field) => Ok(field.text().await?.parse()?),
+                    None => Err(ParseError::expected_input()),
+                }
+            }
+        }
+
+        impl ToJSON for $ty {
+            fn to_json(&self) -> Option<Value> {
+                Some(Value::String(self.to_string()))
+            }
+        }
+
+        impl ToHeader for $ty {
+            fn to_header(&self) -> Option<HeaderValue> {
+                HeaderValue::from_str(&self.to_string()).ok()
+            }
+        }
+    };
+}
+
+impl_jiff_types!(Timestamp, "string", "date-time");
+impl_jiff_types!(DateTime, "string", "naive-date-time");
+impl_jiff_types!(Date, "string", "naive-date");
+impl_jiff_types!(Time, "string", "naive-time");
+impl_jiff_types!(SignedDuration, "string", "duration");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp() {
+        let ts = Timestamp::from_second(1442620564).unwrap();
+        assert_eq!(
+            ts.to_json(),
+            Some(Value::String("2015-09-18T23:56:04Z".to_string()))
+        );
+        assert_eq!(
+            Timestamp::parse_from_json(Some(Value::String(
+                "2015-09-19T01:56:04+02:00".to_string()
+            )))
+            .unwrap(),
+            ts
+        );
+        assert!(Timestamp::parse_from_parameter("2015-09-18").is_err());
+    }
+
+    #[test]
+    fn civil() {
+        let dt = jiff::civil::date(2015, 9, 18).at(23, 56, 4, 0);
+        assert_eq!(
+            dt.to_json(),
+            Some(Value::String("2015-09-18T23:56:04".to_string()))
+        );
+        assert_eq!(
+            DateTime::parse_from_parameter("2015-09-18T23:56:04").unwrap(),
+            dt
+        );
+        assert_eq!(
+            Date::parse_from_json(Some(Value::String("2015-09-18".to_string()))).unwrap(),
+            dt.date()
+        );
+        assert_eq!(
+            Time::parse_from_json(Some(Value::String("23:56:04".to_string()))).unwrap(),
+            dt.time()
+        );
+        assert!(Date::parse_from_parameter("2015-02-30").is_err());
+    }
+
+    #[test]
+    fn signed_duration() {
+        let duration = SignedDuration::from_secs(9000);
+        assert_eq!(
+            duration.to_json(),
+            Some(Value::String("PT2H30M".to_string()))
+        );
+        assert_eq!(
+            SignedDuration::parse_from_parameter("PT2H30M").unwrap(),
+            duration
+        );
+        assert_eq!(
+            SignedDuration::parse_from_parameter("2h 30m").unwrap(),
+            duration
+        );
+    }
+}
@@ -1,4 +1,6 @@
 mod array;
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal;
 mod bool;
 #[cfg(feature = "bson")]
 mod bson;
@@ -20,6 +22,8 @@ mod humantime;
 mod humantime_wrapper;
 mod integers;
 mod ip;
+#[cfg(feature = "jiff")]
+mod jiff;
 mod non_zero;
 mod optional;
 #[cfg(feature = "prost-wkt-types")]
@@ -64,6 +64,8 @@ impl<T: ParseFromJSON> ParseFromJSON for Option<T> {
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
+    const IS_OBJECT: bool = T::IS_OBJECT;
+
     fn parse_from_parameter(_value: &str) -> ParseResult<Self> {
         unreachable!()
     }
@@ -81,9 +83,22 @@ impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
             .map_err(ParseError::propagate)
             .map(Some)
     }
+
+    fn parse_from_properties(properties: &[(&str, &str)]) -> ParseResult<Self> {
+        if properties.is_empty() {
+            return Ok(None);
+        }
+
+        T::parse_from_properties(properties)
+            .map_err(ParseError::propagate)
+            .map(Some)
+    }
 }
 
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Option<T> {
+    const CONTENT_TYPE: Option<&'static str> = T::CONTENT_TYPE;
+    const IS_STREAMING: bool = T::IS_STREAMING;
+
     async fn parse_from_multipart(value: Option<PoemField>) -> ParseResult<Self> {
         match value {
             Some(value) => T::parse_from_multipart(Some(value))
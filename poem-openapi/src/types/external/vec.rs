@@ -84,6 +84,8 @@ impl<T: ParseFromParameter> ParseFromParameter for Vec<T> {
 }
 
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Vec<T> {
+    const CONTENT_TYPE: Option<&'static str> = T::CONTENT_TYPE;
+
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
         match field {
             Some(field) => {
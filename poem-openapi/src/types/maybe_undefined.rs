@@ -365,6 +365,8 @@ impl<T: ParseFromJSON> ParseFromJSON for MaybeUndefined<T> {
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for MaybeUndefined<T> {
+    const IS_OBJECT: bool = T::IS_OBJECT;
+
     fn parse_from_parameter(_value: &str) -> ParseResult<Self> {
         unreachable!()
     }
@@ -382,9 +384,22 @@ impl<T: ParseFromParameter> ParseFromParameter for MaybeUndefined<T> {
             .map_err(ParseError::propagate)
             .map(MaybeUndefined::Value)
     }
+
+    fn parse_from_properties(properties: &[(&str, &str)]) -> ParseResult<Self> {
+        if properties.is_empty() {
+            return Ok(MaybeUndefined::Undefined);
+        }
+
+        T::parse_from_properties(properties)
+            .map_err(ParseError::propagate)
+            .map(MaybeUndefined::Value)
+    }
 }
 
 impl<T: ParseFromMultipartField> ParseFromMultipartField for MaybeUndefined<T> {
+    const CONTENT_TYPE: Option<&'static str> = T::CONTENT_TYPE;
+    const IS_STREAMING: bool = T::IS_STREAMING;
+
     async fn parse_from_multipart(value: Option<PoemField>) -> ParseResult<Self> {
         match value {
             Some(value) => T::parse_from_multipart(Some(value))
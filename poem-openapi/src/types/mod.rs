@@ -104,9 +104,8 @@ pub trait ParseFromXML: Sized + Type {
 
     /// Parse from XML string.
     fn parse_from_xml_string(s: &str) -> ParseResult<Self> {
-        let value =
-            quick_xml::de::from_str(s).map_err(|err| ParseError::custom(err.to_string()))?;
-        Self::parse_from_xml(value)
+        let (_, value) = crate::xml::read_document(s.as_bytes()).map_err(ParseError::custom)?;
+        Self::parse_from_xml(Some(value))
     }
 }
 
@@ -125,6 +124,11 @@ pub trait ParseFromYAML: Sized + Type {
 /// Represents a type that can parsing from parameter. (header, query, path,
 /// cookie)
 pub trait ParseFromParameter: Sized + Type {
+    /// If it is `true`, this type is an object that is parsed from the
+    /// properties of the parameter with
+    /// [`ParseFromParameter::parse_from_properties`].
+    const IS_OBJECT: bool = false;
+
     /// Parse from parameter.
     fn parse_from_parameter(value: &str) -> ParseResult<Self>;
 
@@ -138,10 +142,23 @@ pub trait ParseFromParameter: Sized + Type {
             None => Err(ParseError::expected_input()),
         }
     }
+
+    /// Parse from the properties of an object parameter.
+    fn parse_from_properties(_properties: &[(&str, &str)]) -> ParseResult<Self> {
+        Err(ParseError::custom("object parameters are not supported"))
+    }
 }
 
 /// Represents a type that can parsing from multipart.
 pub trait ParseFromMultipartField: Sized + Type {
+    /// The content type of the field declared in the encoding of the
+    /// multipart request.
+    const CONTENT_TYPE: Option<&'static str> = None;
+
+    /// If it is `true`, the field keeps reading the request body after it has
+    /// been parsed, so the following fields of the request are ignored.
+    const IS_STREAMING: bool = false;
+
     /// Parse from multipart field.
     fn parse_from_multipart(
         field: Option<PoemField>,
@@ -174,7 +191,7 @@ pub trait ToXML: Type {
 
     /// Convert this value to XML string.
     fn to_xml_string(&self) -> String {
-        quick_xml::se::to_string(&self.to_xml()).unwrap_or_default()
+        crate::xml::write_document::<Self>(self.to_xml()).unwrap_or_default()
     }
 }
 
@@ -374,6 +391,8 @@ impl<T: ParseFromXML> ParseFromXML for Box<T> {
 }
 
 impl<T: ParseFromParameter> ParseFromParameter for Box<T> {
+    const IS_OBJECT: bool = T::IS_OBJECT;
+
     fn parse_from_parameter(_value: &str) -> ParseResult<Self> {
         unreachable!()
     }
@@ -385,9 +404,18 @@ impl<T: ParseFromParameter> ParseFromParameter for Box<T> {
             .map_err(ParseError::propagate)
             .map(Box::new)
     }
+
+    fn parse_from_properties(properties: &[(&str, &str)]) -> ParseResult<Self> {
+        T::parse_from_properties(properties)
+            .map_err(ParseError::propagate)
+            .map(Box::new)
+    }
 }
 
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Box<T> {
+    const CONTENT_TYPE: Option<&'static str> = T::CONTENT_TYPE;
+    const IS_STREAMING: bool = T::IS_STREAMING;
+
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
         T::parse_from_multipart(field)
             .await
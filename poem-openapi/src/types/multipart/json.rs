@@ -45,6 +45,8 @@ impl<T: Type> Type for JsonField<T> {
 }
 
 impl<T: ParseFromJSON> ParseFromMultipartField for JsonField<T> {
+    const CONTENT_TYPE: Option<&'static str> = Some("application/json");
+
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
         let value = match field {
             Some(field) => {
@@ -2,6 +2,8 @@
 
 mod json;
 mod upload;
+mod upload_stream;
 
 pub use json::JsonField;
 pub use upload::Upload;
+pub use upload_stream::UploadStream;
@@ -0,0 +1,114 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Formatter},
+    io::Result as IoResult,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use poem::web::Field as PoemField;
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromMultipartField, ParseResult, Type},
+};
+
+/// A uploaded file for multipart that is read from the request body as it
+/// arrives, without being buffered to memory or disk first.
+///
+/// The fields of a multipart request are read in order, so the fields that
+/// follow this one in the request are ignored. It should be the last field
+/// sent by the clients.
+pub struct UploadStream {
+    file_name: Option<String>,
+    content_type: Option<String>,
+    // a `Mutex` makes the type `Sync`, it is never locked since reading
+    // requires a mutable reference
+    reader: Mutex<Pin<Box<dyn AsyncRead + Send>>>,
+}
+
+impl Debug for UploadStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("UploadStream");
+        if let Some(file_name) = self.file_name() {
+            d.field("filename", &file_name);
+        }
+        if let Some(content_type) = self.content_type() {
+            d.field("content_type", &content_type);
+        }
+        d.finish()
+    }
+}
+
+impl UploadStream {
+    /// Get the content type of the field.
+    #[inline]
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// The file name found in the `Content-Disposition` header.
+    #[inline]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Consumes this object to return a reader.
+    pub fn into_async_read(self) -> impl AsyncRead + Send + Unpin + 'static {
+        self
+    }
+}
+
+impl AsyncRead for UploadStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let reader = self.get_mut().reader.get_mut().unwrap();
+        reader.as_mut().poll_read(cx, buf)
+    }
+}
+
+impl Type for UploadStream {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string_binary".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "binary")))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl ParseFromMultipartField for UploadStream {
+    const IS_STREAMING: bool = true;
+
+    async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Ok(Self {
+                content_type: field.content_type().map(ToString::to_string),
+                file_name: field.file_name().map(ToString::to_string),
+                reader: Mutex::new(Box::pin(field.into_async_read())),
+            }),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+// which options are used depends on the UIs enabled by features
+#![allow(dead_code)]
+
+use serde_json::{Map, Value};
+
+/// Configuration of the built-in documentation UIs.
+///
+/// The options that are not supported by a UI are ignored by it, see the
+/// documentation of each method. Options of the UIs without a typed
+/// equivalent can be passed through with [`UiConfig::option`].
+///
+/// # Examples
+///
+/// ```
+/// use poem_openapi::{OpenApiService, UiConfig};
+///
+/// struct Api;
+///
+/// #[poem_openapi::OpenApi]
+/// impl Api {}
+///
+/// let api_service = OpenApiService::new(Api, "Demo", "1.0").ui_config(
+///     UiConfig::new()
+///         .try_it_out(false)
+///         .persist_authorization(true)
+///         .theme("dark")
+///         .custom_css("body { font-size: 14px; }"),
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiConfig {
+    pub(crate) try_it_out: Option<bool>,
+    pub(crate) default_models_expand_depth: Option<i32>,
+    pub(crate) persist_authorization: Option<bool>,
+    pub(crate) theme: Option<String>,
+    pub(crate) oauth2_redirect_url: Option<String>,
+    pub(crate) custom_css: Vec<String>,
+    pub(crate) custom_js: Vec<String>,
+    pub(crate) options: Map<String, Value>,
+}
+
+impl UiConfig {
+    /// Create a default UI configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables sending requests from the UI.
+    ///
+    /// Supported by Swagger UI, RapiDoc, Scalar, OpenAPI Explorer and
+    /// Stoplight Elements.
+    #[must_use]
+    pub fn try_it_out(self, enabled: bool) -> Self {
+        Self {
+            try_it_out: Some(enabled),
+            ..self
+        }
+    }
+
+    /// Sets the depth to which the models are expanded by default, `-1`
+    /// hides the models.
+    ///
+    /// Supported by Swagger UI, RapiDoc and OpenAPI Explorer.
+    #[must_use]
+    pub fn default_models_expand_depth(self, depth: i32) -> Self {
+        Self {
+            default_models_expand_depth: Some(depth),
+            ..self
+        }
+    }
+
+    /// Keeps the authorization data when the page is reloaded.
+    ///
+    /// Supported by Swagger UI and RapiDoc.
+    #[must_use]
+    pub fn persist_authorization(self, enabled: bool) -> Self {
+        Self {
+            persist_authorization: Some(enabled),
+            ..self
+        }
+    }
+
+    /// Sets the theme, e.g. `light` or `dark` for RapiDoc, `purple` or
+    /// `moon` for Scalar.
+    ///
+    /// Supported by RapiDoc and Scalar.
+    #[must_use]
+    pub fn theme(self, theme: impl Into<String>) -> Self {
+        Self {
+            theme: Some(theme.into()),
+            ..self
+        }
+    }
+
+    /// Sets the OAuth2 redirect URL.
+    ///
+    /// By default, the `oauth-receiver.html` page served next to the UI is
+    /// used. Supported by Swagger UI and RapiDoc.
+    #[must_use]
+    pub fn oauth2_redirect_url(self, url: impl Into<String>) -> Self {
+        Self {
+            oauth2_redirect_url: Some(url.into()),
+            ..self
+        }
+    }
+
+    /// Appends a stylesheet to the page.
+    #[must_use]
+    pub fn custom_css(mut self, css: impl Into<String>) -> Self {
+        self.custom_css.push(css.into());
+        self
+    }
+
+    /// Appends a script to the end of the page.
+    #[must_use]
+    pub fn custom_js(mut self, js: impl Into<String>) -> Self {
+        self.custom_js.push(js.into());
+        self
+    }
+
+    /// Sets an option of the UI, overriding the typed options.
+    ///
+    /// For Swagger UI, Redoc and Scalar this is a property of the
+    /// configuration object, for RapiDoc, OpenAPI Explorer and Stoplight
+    /// Elements this is an attribute of the element.
+    #[must_use]
+    pub fn option(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.options.insert(name.into(), value.into());
+        self
+    }
+
+    /// Merges the options into the configuration object of a UI.
+    pub(crate) fn merge_options(&self, mut config: Map<String, Value>) -> Map<String, Value> {
+        config.extend(self.options.clone());
+        config
+    }
+
+    /// Injects the custom stylesheets and scripts into the template of a
+    /// page.
+    pub(crate) fn inject(&self, mut html: String) -> String {
+        if !self.custom_css.is_empty() {
+            let style = format!("<style>{}</style>\n", self.custom_css.join("\n"));
+            if let Some(idx) = html.rfind("</head>") {
+                html.insert_str(idx, &style);
+            }
+        }
+        if !self.custom_js.is_empty() {
+            let script = format!("<script>{}</script>\n", self.custom_js.join("\n"));
+            if let Some(idx) = html.rfind("</body>") {
+                html.insert_str(idx, &script);
+            }
+        }
+        html
+    }
+}
+
+/// Serializes a value to be embedded in a `<script>` element.
+pub(crate) fn to_script_json(value: &Map<String, Value>) -> String {
+    serde_json::to_string(value)
+        .unwrap_or_default()
+        .replace("</", "<\\/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inject() {
+        let config = UiConfig::new().custom_css("a{}").custom_js("run()");
+        assert_eq!(
+            config.inject("<html><head></head><body></body></html>".to_string()),
+            "<html><head><style>a{}</style>\n</head><body><script>run()</script>\n</body></html>"
+        );
+    }
+
+    #[test]
+    fn script_json() {
+        let mut value = Map::new();
+        value.insert("a".to_string(), "</script>".into());
+        assert_eq!(to_script_json(&value), r#"{"a":"<\/script>"}"#);
+    }
+}
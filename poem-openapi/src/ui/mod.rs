@@ -1,3 +1,4 @@
+mod config;
 #[cfg(feature = "openapi-explorer")]
 pub(crate) mod openapi_explorer;
 #[cfg(feature = "rapidoc")]
@@ -10,3 +11,7 @@ pub(crate) mod scalar;
 pub(crate) mod stoplight_elements;
 #[cfg(feature = "swagger-ui")]
 pub(crate) mod swagger_ui;
+
+pub use config::UiConfig;
+#[allow(unused_imports)]
+pub(crate) use config::to_script_json;
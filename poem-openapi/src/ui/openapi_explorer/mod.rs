@@ -1,4 +1,7 @@
 use poem::{Endpoint, endpoint::make_sync, web::Html};
+use serde_json::{Map, Value};
+
+use crate::ui::{UiConfig, to_script_json};
 
 const REDOC_JS: &str = include_str!("openapi-explorer.min.js");
 
@@ -24,19 +27,37 @@ const REDOC_TEMPLATE: &str = r#"
     
     <script>
         let spec = {:spec};
-        document.getElementsByTagName('openapi-explorer')[0].loadSpec(spec).catch(console.error);
+        let explorer = document.getElementsByTagName('openapi-explorer')[0];
+        let config = {:config};
+        for (const [name, value] of Object.entries(config)) {
+            explorer.setAttribute(name, value);
+        }
+        explorer.loadSpec(spec).catch(console.error);
     </script>
   </body>
 </html>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    REDOC_TEMPLATE
+fn create_config(ui_config: &UiConfig) -> Map<String, Value> {
+    let mut config = Map::new();
+    if let Some(try_it_out) = ui_config.try_it_out {
+        config.insert("enable-console".to_string(), try_it_out.into());
+    }
+    if let Some(depth) = ui_config.default_models_expand_depth {
+        config.insert("schema-expand-level".to_string(), depth.into());
+    }
+    ui_config.merge_options(config)
+}
+
+pub(crate) fn create_html(document: &str, ui_config: &UiConfig) -> String {
+    ui_config
+        .inject(REDOC_TEMPLATE.to_string())
         .replace("{:script}", REDOC_JS)
+        .replace("{:config}", &to_script_json(&create_config(ui_config)))
         .replace("{:spec}", document)
 }
 
-pub(crate) fn create_endpoint(document: String) -> impl Endpoint + 'static {
-    let ui_html = create_html(&document);
+pub(crate) fn create_endpoint(document: String, ui_config: &UiConfig) -> impl Endpoint + use<> {
+    let ui_html = create_html(&document, ui_config);
     poem::Route::new().at("/", make_sync(move |_| Html(ui_html.clone())))
 }
@@ -1,4 +1,7 @@
 use poem::{Endpoint, endpoint::make_sync, web::Html};
+use serde_json::{Map, Value};
+
+use crate::ui::{UiConfig, to_script_json};
 
 const RAPIDOC_JS: &str = include_str!("rapidoc-min.js");
 const OAUTH_RECEIVER_HTML: &str = include_str!("oauth-receiver.html");
@@ -32,6 +35,10 @@ const RAPIDOC_TEMPLATE: &str = r#"
     <script>
     document.addEventListener('DOMContentLoaded', (event) => {
         let docEl = document.getElementById("thedoc");
+        let config = {:config};
+        for (const [name, value] of Object.entries(config)) {
+            docEl.setAttribute(name, value);
+        }
         docEl.loadSpec({:spec});
     })
     </script>
@@ -39,14 +46,36 @@ const RAPIDOC_TEMPLATE: &str = r#"
 </body>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    RAPIDOC_TEMPLATE
+fn create_config(ui_config: &UiConfig) -> Map<String, Value> {
+    let mut config = Map::new();
+    if let Some(try_it_out) = ui_config.try_it_out {
+        config.insert("allow-try".to_string(), try_it_out.into());
+    }
+    if let Some(depth) = ui_config.default_models_expand_depth {
+        config.insert("schema-expand-level".to_string(), depth.into());
+    }
+    if let Some(persist_authorization) = ui_config.persist_authorization {
+        config.insert("persist-auth".to_string(), persist_authorization.into());
+    }
+    if let Some(theme) = &ui_config.theme {
+        config.insert("theme".to_string(), theme.clone().into());
+    }
+    if let Some(url) = &ui_config.oauth2_redirect_url {
+        config.insert("oauth-receiver".to_string(), url.clone().into());
+    }
+    ui_config.merge_options(config)
+}
+
+pub(crate) fn create_html(document: &str, ui_config: &UiConfig) -> String {
+    ui_config
+        .inject(RAPIDOC_TEMPLATE.to_string())
         .replace("{:script}", RAPIDOC_JS)
+        .replace("{:config}", &to_script_json(&create_config(ui_config)))
         .replace("{:spec}", document)
 }
 
-pub(crate) fn create_endpoint(document: String) -> impl Endpoint {
-    let ui_html = create_html(&document);
+pub(crate) fn create_endpoint(document: String, ui_config: &UiConfig) -> impl Endpoint + use<> {
+    let ui_html = create_html(&document, ui_config);
     let oauth_receiver_html = OAUTH_RECEIVER_HTML.replace("{:script}", RAPIDOC_JS);
 
     poem::Route::new()
@@ -1,4 +1,7 @@
 use poem::{Endpoint, endpoint::make_sync, web::Html};
+use serde_json::{Map, Value};
+
+use crate::ui::{UiConfig, to_script_json};
 
 const REDOC_JS: &str = include_str!("redoc.standalone.js");
 
@@ -28,21 +31,27 @@ const REDOC_TEMPLATE: &str = r#"
     
     <script>
         let spec = {:spec};
-        Redoc.init(spec, {
-          scrollYOffset: 50
-        }, document.getElementById('redoc-container'));
+        Redoc.init(spec, {:config}, document.getElementById('redoc-container'));
     </script>
   </body>
 </html>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    REDOC_TEMPLATE
+fn create_config(ui_config: &UiConfig) -> Map<String, Value> {
+    let mut config = Map::new();
+    config.insert("scrollYOffset".to_string(), 50.into());
+    ui_config.merge_options(config)
+}
+
+pub(crate) fn create_html(document: &str, ui_config: &UiConfig) -> String {
+    ui_config
+        .inject(REDOC_TEMPLATE.to_string())
         .replace("{:script}", REDOC_JS)
+        .replace("{:config}", &to_script_json(&create_config(ui_config)))
         .replace("{:spec}", document)
 }
 
-pub(crate) fn create_endpoint(document: String) -> impl Endpoint {
-    let ui_html = create_html(&document);
+pub(crate) fn create_endpoint(document: String, ui_config: &UiConfig) -> impl Endpoint + use<> {
+    let ui_html = create_html(&document, ui_config);
     poem::Route::new().at("/", make_sync(move |_| Html(ui_html.clone())))
 }
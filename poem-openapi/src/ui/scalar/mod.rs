@@ -1,4 +1,7 @@
 use poem::{Endpoint, endpoint::make_sync, web::Html};
+use serde_json::{Map, Value};
+
+use crate::ui::UiConfig;
 
 const SCALAR_JS: &str = include_str!("scalar.min.js");
 
@@ -22,6 +25,7 @@ const SCALAR_TEMPLATE: &str = r#"
     <script
       id="api-reference"
       type="application/json"
+      data-configuration="{:config}"
     >
       {:spec}
     </script>
@@ -30,13 +34,32 @@ const SCALAR_TEMPLATE: &str = r#"
 </html>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    SCALAR_TEMPLATE
+fn create_config(ui_config: &UiConfig) -> Map<String, Value> {
+    let mut config = Map::new();
+    if let Some(try_it_out) = ui_config.try_it_out {
+        config.insert("hideTestRequestButton".to_string(), (!try_it_out).into());
+    }
+    if let Some(theme) = &ui_config.theme {
+        config.insert("theme".to_string(), theme.clone().into());
+    }
+    ui_config.merge_options(config)
+}
+
+pub(crate) fn create_html(document: &str, ui_config: &UiConfig) -> String {
+    // the configuration is stored in an attribute
+    let config = serde_json::to_string(&create_config(ui_config))
+        .unwrap_or_default()
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;");
+    ui_config
+        .inject(SCALAR_TEMPLATE.to_string())
         .replace("{:script}", SCALAR_JS)
+        .replace("{:config}", &config)
         .replace("{:spec}", document)
 }
 
-pub(crate) fn create_endpoint(document: String) -> impl Endpoint + 'static {
-    let ui_html = create_html(&document);
+pub(crate) fn create_endpoint(document: String, ui_config: &UiConfig) -> impl Endpoint + use<> {
+    let ui_html = create_html(&document, ui_config);
     poem::Route::new().at("/", make_sync(move |_| Html(ui_html.clone())))
 }
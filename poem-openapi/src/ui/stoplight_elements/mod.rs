@@ -1,12 +1,26 @@
 use poem::{Endpoint, endpoint::make_sync, web::Html};
+use serde_json::{Map, Value};
+
+use crate::ui::{UiConfig, to_script_json};
 
 const TEMPLATE: &str = include_str!("stoplight-elements.html");
 
-pub(crate) fn create_html(document: &str) -> String {
-    TEMPLATE.replace("'{:spec}'", document)
+fn create_config(ui_config: &UiConfig) -> Map<String, Value> {
+    let mut config = Map::new();
+    if let Some(try_it_out) = ui_config.try_it_out {
+        config.insert("hideTryIt".to_string(), (!try_it_out).into());
+    }
+    ui_config.merge_options(config)
+}
+
+pub(crate) fn create_html(document: &str, ui_config: &UiConfig) -> String {
+    ui_config
+        .inject(TEMPLATE.to_string())
+        .replace("{:config}", &to_script_json(&create_config(ui_config)))
+        .replace("'{:spec}'", document)
 }
 
-pub(crate) fn create_endpoint(document: String) -> impl Endpoint {
-    let ui_html = create_html(&document);
+pub(crate) fn create_endpoint(document: String, ui_config: &UiConfig) -> impl Endpoint + use<> {
+    let ui_html = create_html(&document, ui_config);
     poem::Route::new().at("/", make_sync(move |_| Html(ui_html.clone())))
 }
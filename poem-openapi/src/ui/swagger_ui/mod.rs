@@ -1,4 +1,7 @@
 use poem::{Endpoint, endpoint::make_sync, web::Html};
+use serde_json::{Map, Value};
+
+use crate::ui::{UiConfig, to_script_json};
 
 const SWAGGER_UI_JS: &str = include_str!("swagger-ui-bundle.js");
 const SWAGGER_UI_CSS: &str = include_str!("swagger-ui.css");
@@ -17,42 +20,70 @@ const SWAGGER_UI_TEMPLATE: &str = r#"
 <div id="ui"></div>
 <script>
     let spec = {:spec};
-    let oauth2RedirectUrl;
+    let config = {:config};
 
-    let query = window.location.href.indexOf("?");
-    if (query > 0) {
-        oauth2RedirectUrl = window.location.href.substring(0, query);
-    } else {
-        oauth2RedirectUrl = window.location.href;
-    }
+    if (!config.oauth2RedirectUrl) {
+        let oauth2RedirectUrl;
 
-    if (!oauth2RedirectUrl.endsWith("/")) {
-        oauth2RedirectUrl += "/";
+        let query = window.location.href.indexOf("?");
+        if (query > 0) {
+            oauth2RedirectUrl = window.location.href.substring(0, query);
+        } else {
+            oauth2RedirectUrl = window.location.href;
+        }
+
+        if (!oauth2RedirectUrl.endsWith("/")) {
+            oauth2RedirectUrl += "/";
+        }
+        oauth2RedirectUrl += "oauth-receiver.html";
+        config.oauth2RedirectUrl = oauth2RedirectUrl;
     }
-    oauth2RedirectUrl += "oauth-receiver.html";
-
-    SwaggerUIBundle({
-        dom_id: '#ui',
-        spec: spec,
-        filter: false,
-        oauth2RedirectUrl: oauth2RedirectUrl,
-        deepLinking: true,
-    })
+
+    config.dom_id = '#ui';
+    config.spec = spec;
+    SwaggerUIBundle(config)
 </script>
 
 </body>
 </html>
 "#;
 
-pub(crate) fn create_html(document: &str) -> String {
-    SWAGGER_UI_TEMPLATE
+fn create_config(ui_config: &UiConfig) -> Map<String, Value> {
+    let mut config = Map::new();
+    config.insert("filter".to_string(), false.into());
+    config.insert("deepLinking".to_string(), true.into());
+    if let Some(try_it_out) = ui_config.try_it_out {
+        config.insert("tryItOutEnabled".to_string(), try_it_out.into());
+        if !try_it_out {
+            config.insert("supportedSubmitMethods".to_string(), Value::Array(vec![]));
+        }
+    }
+    if let Some(depth) = ui_config.default_models_expand_depth {
+        config.insert("defaultModelsExpandDepth".to_string(), depth.into());
+    }
+    if let Some(persist_authorization) = ui_config.persist_authorization {
+        config.insert(
+            "persistAuthorization".to_string(),
+            persist_authorization.into(),
+        );
+    }
+    if let Some(url) = &ui_config.oauth2_redirect_url {
+        config.insert("oauth2RedirectUrl".to_string(), url.clone().into());
+    }
+    ui_config.merge_options(config)
+}
+
+pub(crate) fn create_html(document: &str, ui_config: &UiConfig) -> String {
+    ui_config
+        .inject(SWAGGER_UI_TEMPLATE.to_string())
         .replace("{:style}", SWAGGER_UI_CSS)
         .replace("{:script}", SWAGGER_UI_JS)
+        .replace("{:config}", &to_script_json(&create_config(ui_config)))
         .replace("{:spec}", document)
 }
 
-pub(crate) fn create_endpoint(document: String) -> impl Endpoint {
-    let ui_html = create_html(&document);
+pub(crate) fn create_endpoint(document: String, ui_config: &UiConfig) -> impl Endpoint + use<> {
+    let ui_html = create_html(&document, ui_config);
     poem::Route::new()
         .at("/", make_sync(move |_| Html(ui_html.clone())))
         .at(
@@ -60,3 +91,33 @@ pub(crate) fn create_endpoint(document: String) -> impl Endpoint {
             make_sync(move |_| Html(OAUTH_RECEIVER_HTML.to_string())),
         )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config() {
+        let html = create_html(
+            "{}",
+            &UiConfig::new()
+                .try_it_out(false)
+                .persist_authorization(true)
+                .option("filter", true)
+                .custom_css(".topbar { display: none; }"),
+        );
+        let (_, config) = html.split_once("let config = ").unwrap();
+        let (config, _) = config.split_once(";\n").unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(config).unwrap(),
+            serde_json::json!({
+                "deepLinking": true,
+                "filter": true,
+                "persistAuthorization": true,
+                "supportedSubmitMethods": [],
+                "tryItOutEnabled": false,
+            })
+        );
+        assert!(html.contains("<style>.topbar { display: none; }</style>\n</head>"));
+    }
+}
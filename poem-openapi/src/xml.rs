@@ -0,0 +1,557 @@
+//! Conversion between XML documents and the JSON values used by the types.
+//!
+//! XML has no notion of numbers, booleans or arrays, so a document is first
+//! read into a loosely typed [`Value`] and then reshaped according to the
+//! schema of the target type, including the `xml` object of its properties.
+
+use quick_xml::{DeError, Reader, events::Event};
+use serde::{
+    Serialize, Serializer,
+    ser::{SerializeMap, SerializeSeq},
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef, MetaXml, Registry},
+    types::Type,
+};
+
+const TEXT_KEY: &str = "$text";
+
+struct Element {
+    name: String,
+    attributes: Map<String, Value>,
+    children: Map<String, Value>,
+    text: String,
+}
+
+impl Element {
+    fn new(name: String, attributes: Map<String, Value>) -> Self {
+        Self {
+            name,
+            attributes,
+            children: Map::new(),
+            text: String::new(),
+        }
+    }
+
+    fn add_child(&mut self, name: String, value: Value) {
+        match self.children.get_mut(&name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(prev) => {
+                let prev = prev.take();
+                self.children.insert(name, Value::Array(vec![prev, value]));
+            }
+            None => {
+                self.children.insert(name, value);
+            }
+        }
+    }
+
+    fn into_value(self) -> (String, Value) {
+        if self.attributes.is_empty() && self.children.is_empty() {
+            return (self.name, Value::String(self.text));
+        }
+
+        let mut map = self.attributes;
+        map.extend(self.children);
+        if !self.text.is_empty() {
+            map.insert(TEXT_KEY.to_string(), Value::String(self.text));
+        }
+        (self.name, Value::Object(map))
+    }
+}
+
+fn read_element(start: &quick_xml::events::BytesStart<'_>) -> Result<Element, String> {
+    let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+    let mut attributes = Map::new();
+    for attr in start.attributes() {
+        let attr = attr.map_err(|err| err.to_string())?;
+        if attr.key.as_namespace_binding().is_some() {
+            continue;
+        }
+        let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        let value = attr.unescape_value().map_err(|err| err.to_string())?;
+        attributes.insert(format!("@{key}"), Value::String(value.into_owned()));
+    }
+    Ok(Element::new(name, attributes))
+}
+
+/// Reads an XML document, returning the name of the root element and its
+/// content.
+///
+/// Elements without attributes and children become strings, everything else
+/// becomes an object whose attributes are prefixed with `@`. Repeated child
+/// elements are collected into arrays.
+pub(crate) fn read_document(data: &[u8]) -> Result<(String, Value), String> {
+    let mut reader = Reader::from_reader(data);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(|err| err.to_string())? {
+            Event::Start(start) => stack.push(read_element(&start)?),
+            Event::Empty(start) => {
+                let (name, value) = read_element(&start)?.into_value();
+                match stack.last_mut() {
+                    Some(parent) => parent.add_child(name, value),
+                    None => return Ok((name, value)),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(&text.unescape().map_err(|err| err.to_string())?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                let (name, value) = stack
+                    .pop()
+                    .ok_or_else(|| "unexpected end tag".to_string())?
+                    .into_value();
+                match stack.last_mut() {
+                    Some(parent) => parent.add_child(name, value),
+                    None => return Ok((name, value)),
+                }
+            }
+            Event::Eof => return Err("missing root element".to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// Writes `value` as an XML document, using the `xml` object of the schema of
+/// `T` to name the root element.
+pub(crate) fn write_document<T: Type + ?Sized>(value: Option<Value>) -> Result<String, DeError> {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    let schema_ref = T::schema_ref();
+    let schema = resolve(&schema_ref, &registry);
+    let xml = xml_of(&schema_ref, &registry);
+
+    let mut value = value.unwrap_or_default();
+    if let (Some(schema), Value::Array(items)) = (schema, &mut value) {
+        if let Some(item_schema) = &schema.items {
+            let item_xml = xml_of(item_schema, &registry);
+            let item_name = match (item_xml.and_then(|xml| xml.name), &**item_schema) {
+                (Some(name), _) => qualified_name(name, item_xml),
+                (None, MetaSchemaRef::Reference(name)) => name.clone(),
+                (None, MetaSchemaRef::Inline(_)) => "item".to_string(),
+            };
+            value = Value::Object(Map::from_iter([(
+                item_name,
+                Value::Array(std::mem::take(items)),
+            )]));
+        }
+    }
+
+    let root = match (xml.and_then(|xml| xml.name), &schema_ref) {
+        (Some(name), _) => qualified_name(name, xml),
+        (None, MetaSchemaRef::Reference(name)) => name.clone(),
+        (None, MetaSchemaRef::Inline(_)) => "root".to_string(),
+    };
+    quick_xml::se::to_string_with_root(
+        &root,
+        &ordered(with_namespace(value, xml), schema, &registry),
+    )
+}
+
+/// A value to write as XML, whose objects keep the order of their fields.
+enum XmlValue {
+    Value(Value),
+    Array(Vec<XmlValue>),
+    Object(Vec<(String, XmlValue)>),
+}
+
+impl Serialize for XmlValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            XmlValue::Value(value) => value.serialize(serializer),
+            XmlValue::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            XmlValue::Object(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (key, value) in fields {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Orders the elements of a value reshaped by [`to_xml`] like the properties
+/// of its schema, since [`Map`] sorts its keys. The attributes are written
+/// first.
+fn ordered(value: Value, schema: Option<&MetaSchema>, registry: &Registry) -> XmlValue {
+    // the items of an array, or the elements holding them
+    let items = schema
+        .filter(|schema| schema.ty == "array")
+        .and_then(|schema| schema.items.as_deref())
+        .and_then(|items| resolve(items, registry));
+
+    match value {
+        Value::Array(values) => XmlValue::Array(
+            values
+                .into_iter()
+                .map(|item| ordered(item, items.or(schema), registry))
+                .collect(),
+        ),
+        Value::Object(mut map) => {
+            let mut fields = Vec::new();
+
+            if let Some(schema) = schema.filter(|schema| schema.ty == "object") {
+                for (name, prop_schema) in &schema.properties {
+                    let layout = PropertyLayout::new(name, prop_schema, registry);
+                    let key = match layout.items {
+                        Some((_, item_name, false)) if !layout.attribute => item_name,
+                        _ => layout.name,
+                    };
+                    if let Some(value) = map.remove(&key) {
+                        let value = ordered(value, resolve(prop_schema, registry), registry);
+                        fields.push((key, value));
+                    }
+                }
+            }
+
+            let rest = match schema {
+                Some(schema) if schema.ty == "object" => schema
+                    .additional_properties
+                    .as_deref()
+                    .and_then(|schema| resolve(schema, registry)),
+                _ => items,
+            };
+            for (key, value) in map {
+                let value = if key == TEXT_KEY || key.starts_with('@') {
+                    XmlValue::Value(value)
+                } else {
+                    ordered(value, rest, registry)
+                };
+                fields.push((key, value));
+            }
+
+            fields.sort_by_key(|(key, _)| !key.starts_with('@'));
+            XmlValue::Object(fields)
+        }
+        value => XmlValue::Value(value),
+    }
+}
+
+/// Reshapes a value read by [`read_document`] into the JSON representation
+/// expected by `T`.
+#[doc(hidden)]
+pub fn from_xml_value<T: Type>(value: Value) -> Value {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    from_xml(value, &T::schema_ref(), &registry)
+}
+
+/// Reshapes the JSON representation of `T` so that it is written as XML
+/// according to the `xml` objects of its schema.
+#[doc(hidden)]
+pub fn to_xml_value<T: Type>(value: Value) -> Value {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    to_xml(value, &T::schema_ref(), &registry)
+}
+
+/// Returns the schema a value is actually shaped by, following references and
+/// the `allOf` that wraps a reference with field-level metadata.
+fn resolve<'a>(schema: &'a MetaSchemaRef, registry: &'a Registry) -> Option<&'a MetaSchema> {
+    match schema {
+        MetaSchemaRef::Reference(name) => registry.schemas.get(name),
+        MetaSchemaRef::Inline(schema) if schema.ty.is_empty() && !schema.all_of.is_empty() => {
+            resolve(&schema.all_of[0], registry)
+        }
+        MetaSchemaRef::Inline(schema) => Some(schema),
+    }
+}
+
+fn xml_of<'a>(schema: &'a MetaSchemaRef, registry: &'a Registry) -> Option<&'a MetaXml> {
+    match schema {
+        MetaSchemaRef::Inline(schema) if schema.xml.is_some() => schema.xml.as_ref(),
+        _ => resolve(schema, registry).and_then(|schema| schema.xml.as_ref()),
+    }
+}
+
+fn qualified_name(name: &str, xml: Option<&MetaXml>) -> String {
+    match xml.and_then(|xml| xml.prefix) {
+        Some(prefix) => format!("{prefix}:{name}"),
+        None => name.to_string(),
+    }
+}
+
+fn with_namespace(value: Value, xml: Option<&MetaXml>) -> Value {
+    let Some(namespace) = xml.and_then(|xml| xml.namespace) else {
+        return value;
+    };
+    let key = match xml.and_then(|xml| xml.prefix) {
+        Some(prefix) => format!("@xmlns:{prefix}"),
+        None => "@xmlns".to_string(),
+    };
+
+    match value {
+        Value::Object(mut map) => {
+            map.insert(key, Value::String(namespace.to_string()));
+            Value::Object(map)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| with_namespace(item, xml))
+                .collect(),
+        ),
+        value => Value::Object(Map::from_iter([
+            (key, Value::String(namespace.to_string())),
+            (TEXT_KEY.to_string(), value),
+        ])),
+    }
+}
+
+/// Describes where a property is found in the XML representation of an object.
+struct PropertyLayout<'a> {
+    /// The element or attribute name of the property.
+    name: String,
+    xml: Option<&'a MetaXml>,
+    attribute: bool,
+    /// For arrays, the schema and element name of the items, and whether they
+    /// are wrapped in an element named `name`.
+    items: Option<(&'a MetaSchemaRef, String, bool)>,
+}
+
+impl<'a> PropertyLayout<'a> {
+    fn new(name: &'a str, schema: &'a MetaSchemaRef, registry: &'a Registry) -> Self {
+        // only the `xml` object declared on the property itself applies here,
+        // the one of a referenced type names the root element of that type
+        let xml = match schema {
+            MetaSchemaRef::Inline(schema) => schema.xml.as_ref(),
+            MetaSchemaRef::Reference(_) => None,
+        };
+        let name = xml.and_then(|xml| xml.name).unwrap_or(name);
+        let attribute = xml.is_some_and(|xml| xml.attribute);
+        let items = resolve(schema, registry)
+            .filter(|schema| schema.ty == "array")
+            .and_then(|schema| schema.items.as_deref())
+            .map(|items| {
+                let item_xml = xml_of(items, registry);
+                let item_name = match item_xml.and_then(|xml| xml.name) {
+                    Some(item_name) => qualified_name(item_name, item_xml),
+                    None => qualified_name(name, xml),
+                };
+                (items, item_name, xml.is_some_and(|xml| xml.wrapped))
+            });
+
+        let name = if attribute {
+            format!("@{}", qualified_name(name, xml))
+        } else {
+            qualified_name(name, xml)
+        };
+        Self {
+            name,
+            xml,
+            attribute,
+            items,
+        }
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    match name.split_once(':') {
+        Some((_, name)) => name,
+        None => name,
+    }
+}
+
+fn into_items(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items,
+        Value::String(s) if s.is_empty() => Vec::new(),
+        value => vec![value],
+    }
+}
+
+fn from_xml(value: Value, schema: &MetaSchemaRef, registry: &Registry) -> Value {
+    let Some(schema) = resolve(schema, registry) else {
+        return value;
+    };
+
+    // scalars with attributes keep their content in `$text`
+    let value = match value {
+        Value::Object(mut map)
+            if !matches!(schema.ty, "object" | "array") && map.contains_key(TEXT_KEY) =>
+        {
+            map.remove(TEXT_KEY).unwrap_or_default()
+        }
+        value => value,
+    };
+
+    match (schema.ty, value) {
+        ("object", Value::String(s)) if s.is_empty() => Value::Object(Map::new()),
+        ("object", Value::Object(mut map)) => {
+            let mut object = Map::new();
+
+            for (name, prop_schema) in &schema.properties {
+                let layout = PropertyLayout::new(name, prop_schema, registry);
+                let key = if layout.attribute {
+                    format!("@{}", local_name(&layout.name[1..]))
+                } else {
+                    local_name(&layout.name).to_string()
+                };
+
+                let value = match &layout.items {
+                    Some((items, item_name, wrapped)) if !layout.attribute => {
+                        let value = if *wrapped {
+                            match map.remove(&key) {
+                                Some(Value::Object(mut inner)) => {
+                                    Some(inner.remove(local_name(item_name)).unwrap_or_default())
+                                }
+                                Some(_) => Some(Value::Array(Vec::new())),
+                                None => None,
+                            }
+                        } else {
+                            map.remove(local_name(item_name))
+                        };
+                        value.map(|value| {
+                            Value::Array(
+                                into_items(value)
+                                    .into_iter()
+                                    .map(|item| from_xml(item, items, registry))
+                                    .collect(),
+                            )
+                        })
+                    }
+                    _ => map
+                        .remove(&key)
+                        .map(|value| from_xml(value, prop_schema, registry)),
+                };
+
+                if let Some(value) = value {
+                    object.insert(name.to_string(), value);
+                }
+            }
+
+            for (key, value) in map {
+                if key == TEXT_KEY || key.starts_with('@') {
+                    continue;
+                }
+                let value = match &schema.additional_properties {
+                    Some(additional_properties) => from_xml(value, additional_properties, registry),
+                    None => value,
+                };
+                object.insert(key, value);
+            }
+
+            Value::Object(object)
+        }
+        ("array", value) => match &schema.items {
+            Some(items) => Value::Array(
+                into_items(value)
+                    .into_iter()
+                    .map(|item| from_xml(item, items, registry))
+                    .collect(),
+            ),
+            None => value,
+        },
+        ("integer", Value::String(s)) => match s.trim().parse::<i64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => match s.trim().parse::<u64>() {
+                Ok(n) => Value::from(n),
+                Err(_) => Value::String(s),
+            },
+        },
+        ("number", Value::String(s)) => match s.trim().parse::<f64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => Value::String(s),
+        },
+        ("boolean", Value::String(s)) => match s.trim() {
+            "true" | "1" => Value::Bool(true),
+            "false" | "0" => Value::Bool(false),
+            _ => Value::String(s),
+        },
+        (_, value) => value,
+    }
+}
+
+fn to_xml(value: Value, schema: &MetaSchemaRef, registry: &Registry) -> Value {
+    let Some(schema) = resolve(schema, registry) else {
+        return value;
+    };
+
+    match (schema.ty, value) {
+        ("object", Value::Object(mut map)) => {
+            let mut object = Map::new();
+
+            for (name, prop_schema) in &schema.properties {
+                let Some(value) = map.remove(*name) else {
+                    continue;
+                };
+                if value.is_null() {
+                    continue;
+                }
+
+                let layout = PropertyLayout::new(name, prop_schema, registry);
+                match &layout.items {
+                    Some((items, item_name, wrapped)) if !layout.attribute => {
+                        let items = Value::Array(
+                            into_items(value)
+                                .into_iter()
+                                .map(|item| to_xml(item, items, registry))
+                                .collect(),
+                        );
+                        if *wrapped {
+                            let inner = Map::from_iter([(item_name.clone(), items)]);
+                            object.insert(
+                                layout.name,
+                                with_namespace(Value::Object(inner), layout.xml),
+                            );
+                        } else {
+                            object.insert(item_name.clone(), with_namespace(items, layout.xml));
+                        }
+                    }
+                    _ if layout.attribute => {
+                        object.insert(layout.name, value);
+                    }
+                    _ => {
+                        object.insert(
+                            layout.name,
+                            with_namespace(to_xml(value, prop_schema, registry), layout.xml),
+                        );
+                    }
+                }
+            }
+
+            for (key, value) in map {
+                let value = match &schema.additional_properties {
+                    Some(additional_properties) => to_xml(value, additional_properties, registry),
+                    None => value,
+                };
+                object.insert(key, value);
+            }
+
+            Value::Object(object)
+        }
+        ("array", Value::Array(values)) => match &schema.items {
+            Some(items) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|item| to_xml(item, items, registry))
+                    .collect(),
+            ),
+            None => Value::Array(values),
+        },
+        (_, value) => value,
+    }
+}
@@ -1,15 +1,25 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+
 use poem::{
-    Endpoint, EndpointExt, Error,
+    Endpoint, EndpointExt, Error, Request,
     http::{Method, StatusCode},
-    test::TestClient,
+    test::{MockClock, TestClient},
     web::Data,
 };
 use poem_openapi::{
-    ApiRequest, ApiResponse, Object, OpenApi, OpenApiService, ParameterStyle, Tags,
-    param::{Path, Query},
+    ApiRequest, ApiResponse, DocsGuard, Object, OpenApi, OpenApiService, ParameterStyle,
+    SchemaValidation, SecurityScheme, Tags, Webhook,
+    auth::ApiKey,
+    param::{Header, Path, Query},
     payload::{Binary, Json, Payload, PlainText},
+    rate_limit::{MemoryRateLimiter, RateLimit, RateLimiter},
     registry::{MetaApi, MetaExternalDocument, MetaOperation, MetaParamIn, MetaSchema, Registry},
-    types::Type,
+    types::{Example, Type},
 };
 
 #[tokio::test]
@@ -951,6 +961,150 @@ async fn code_samples() {
     assert_eq!(code_sample.source, "Google Go");
 }
 
+#[tokio::test]
+async fn callbacks() {
+    #[derive(Debug, Object)]
+    struct Event {
+        id: u64,
+    }
+
+    #[Webhook]
+    #[allow(dead_code)]
+    trait EventCallback {
+        #[oai(method = "post")]
+        fn event(&self, event: Json<Event>);
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(
+            path = "/subscribe",
+            method = "post",
+            callback(
+                name = "onEvent",
+                url = "{$request.query.url}",
+                ty = "&dyn EventCallback"
+            )
+        )]
+        async fn subscribe(&self, url: Query<String>) {
+            let _ = url;
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(operation.callbacks.len(), 1);
+    assert_eq!(operation.callbacks[0].name, "onEvent");
+    assert_eq!(operation.callbacks[0].url, "{$request.query.url}");
+    assert_eq!(operation.callbacks[0].operations[0].method, Method::POST);
+
+    let mut registry = Registry::new();
+    Api::register(&mut registry);
+    assert!(registry.schemas.contains_key("Event"));
+
+    let spec: serde_json::Value =
+        serde_json::from_str(&OpenApiService::new(Api, "test", "1.0").spec()).unwrap();
+    assert_eq!(
+        spec["paths"]["/subscribe"]["post"]["callbacks"]["onEvent"]["{$request.query.url}"]["post"]
+            ["requestBody"]["content"]["application/json; charset=utf-8"]["schema"],
+        serde_json::json!({ "$ref": "#/components/schemas/Event" })
+    );
+    assert!(spec["components"]["schemas"]["Event"].is_object());
+}
+
+#[tokio::test]
+async fn extensions() {
+    #[derive(Object)]
+    #[oai(extension(name = "x-internal", value = "true"))]
+    struct MyObj {
+        value: i32,
+    }
+
+    #[derive(Tags)]
+    enum MyTags {
+        #[oai(extension(name = "x-display-name", value = r#""My Tag""#))]
+        Tag1,
+    }
+
+    #[derive(SecurityScheme)]
+    #[oai(
+        ty = "api_key",
+        key_name = "X-API-Key",
+        key_in = "header",
+        extension(name = "x-amazon-apigateway-authtype", value = r#""custom""#)
+    )]
+    #[allow(dead_code)]
+    struct MyApiKey(ApiKey);
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(
+            path = "/test",
+            method = "post",
+            tag = "MyTags::Tag1",
+            extension(
+                name = "x-amazon-apigateway-integration",
+                value = r#"{"type": "mock", "passthroughBehavior": "when_no_match"}"#
+            ),
+            extension(name = "x-codegen-request-body-name", value = r#""body""#)
+        )]
+        async fn test(
+            &self,
+            _auth: MyApiKey,
+            #[oai(extension(name = "x-example-source", value = r#""docs""#))] limit: Query<i32>,
+            obj: Json<MyObj>,
+        ) {
+            let _ = (limit, obj);
+        }
+    }
+
+    let spec: serde_json::Value = serde_json::from_str(
+        &OpenApiService::new(Api, "test", "1.0")
+            .extension(
+                "x-logo",
+                serde_json::json!({ "url": "https://example.com/logo.png" }),
+            )
+            .spec(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        spec["x-logo"],
+        serde_json::json!({ "url": "https://example.com/logo.png" })
+    );
+    let operation = &spec["paths"]["/test"]["post"];
+    assert_eq!(
+        operation["x-amazon-apigateway-integration"],
+        serde_json::json!({ "type": "mock", "passthroughBehavior": "when_no_match" })
+    );
+    assert_eq!(operation["x-codegen-request-body-name"], "body");
+    assert_eq!(operation["parameters"][0]["x-example-source"], "docs");
+    assert_eq!(spec["tags"][0]["x-display-name"], "My Tag");
+    assert_eq!(spec["components"]["schemas"]["MyObj"]["x-internal"], true);
+    assert_eq!(
+        spec["components"]["securitySchemes"]["MyApiKey"]["x-amazon-apigateway-authtype"],
+        "custom"
+    );
+}
+
+#[test]
+#[should_panic(expected = "the name of an extension must start with `x-`")]
+fn invalid_extension_name() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self) {}
+    }
+
+    let _ = OpenApiService::new(Api, "test", "1.0").extension("logo", "abc");
+}
+
 #[tokio::test]
 async fn hidden() {
     #[derive(Debug, Object)]
@@ -1084,3 +1238,958 @@ async fn parameter_style_none() {
     let spec = OpenApiService::new(Api {}, "test", "1.0").spec();
     assert!(!spec.contains("\"style\"") && !spec.contains("\"style\": null"));
 }
+
+#[tokio::test]
+async fn docs_guard() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn index(&self) {}
+    }
+
+    let service =
+        OpenApiService::new(Api, "test", "1.0").docs_guard(DocsGuard::basic("admin", "123456"));
+    let cli = TestClient::new(service.spec_endpoint());
+    let resp = cli.get("/").send().await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+    resp.assert_header("www-authenticate", "Basic realm=\"API documentation\"");
+    cli.get("/")
+        .typed_header(poem::web::headers::Authorization::basic("admin", "abc"))
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.get("/")
+        .typed_header(poem::web::headers::Authorization::basic("admin", "123456"))
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    // the api itself is not guarded
+    TestClient::new(service)
+        .get("/")
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let service = OpenApiService::new(Api, "test", "1.0").docs_guard(DocsGuard::checker(|req| {
+        req.remote_addr()
+            .as_socket_addr()
+            .is_none_or(|addr| addr.ip().is_loopback())
+    }));
+    let cli = TestClient::new(service.spec_endpoint_yaml());
+    cli.get("/").send().await.assert_status_is_ok();
+
+    #[derive(SecurityScheme)]
+    #[oai(ty = "api_key", key_name = "key", key_in = "query")]
+    #[allow(dead_code)]
+    struct MyApiKey(ApiKey);
+
+    let service = OpenApiService::new(Api, "test", "1.0")
+        .docs_guard(DocsGuard::security_scheme::<MyApiKey>());
+    let cli = TestClient::new(service.spec_endpoint());
+    cli.get("/")
+        .send()
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    cli.get("/")
+        .query("key", &"abc")
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn schema_validation() {
+    #[derive(Object)]
+    struct Item {
+        name: String,
+        #[oai(validator(max_length = 3))]
+        code: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/items/:id", method = "post")]
+        async fn create(&self, id: Path<i32>, item: Json<Item>) -> Json<Item> {
+            Json(Item {
+                name: item.0.name,
+                code: match id.0 {
+                    1 => item.0.code,
+                    _ => "toolong".to_string(),
+                },
+            })
+        }
+    }
+
+    let cli = TestClient::new(
+        OpenApiService::new(Api, "test", "1.0").schema_validation(
+            SchemaValidation::new()
+                .reject(true)
+                .deny_unknown_properties(true),
+        ),
+    );
+    cli.post("/items/1")
+        .body_json(&serde_json::json!({"name": "a", "code": "abc"}))
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.post("/items/1")
+        .body_json(&serde_json::json!({"name": "a", "code": "abc", "other": 1}))
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    cli.post("/items/2")
+        .body_json(&serde_json::json!({"name": "a", "code": "abc"}))
+        .send()
+        .await
+        .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+
+    // the mismatches are only logged by default
+    let cli = TestClient::new(
+        OpenApiService::new(Api, "test", "1.0").schema_validation(SchemaValidation::new()),
+    );
+    cli.post("/items/2")
+        .body_json(&serde_json::json!({"name": "a", "code": "abc", "other": 1}))
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn error_mapping() {
+    #[derive(Debug, thiserror::Error)]
+    #[error("user `{0}` not found")]
+    struct UserNotFound(i32);
+
+    #[derive(Object)]
+    struct ErrorBody {
+        message: String,
+    }
+
+    #[derive(ApiResponse)]
+    enum UserNotFoundResponse {
+        /// The user does not exist.
+        #[oai(status = 404)]
+        NotFound(Json<ErrorBody>),
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/user/:id", method = "get")]
+        async fn user(&self, id: Path<i32>) -> poem::Result<PlainText<String>> {
+            match id.0 {
+                1 => Ok(PlainText("sunli".to_string())),
+                2 => Err(Error::from_status(StatusCode::FORBIDDEN)),
+                id => Err(Error::new(
+                    UserNotFound(id),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )),
+            }
+        }
+    }
+
+    let api_service =
+        OpenApiService::new(Api, "test", "1.0").error_mapping(|err: &UserNotFound| {
+            UserNotFoundResponse::NotFound(Json(ErrorBody {
+                message: err.to_string(),
+            }))
+        });
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    let responses = &spec["paths"]["/user/{id}"]["get"]["responses"];
+    assert_eq!(responses["404"]["description"], "The user does not exist.");
+    assert_eq!(
+        responses["404"]["content"]["application/json; charset=utf-8"]["schema"]["$ref"],
+        "#/components/schemas/ErrorBody"
+    );
+    assert!(spec["components"]["schemas"]["ErrorBody"].is_object());
+
+    let cli = TestClient::new(api_service);
+
+    let resp = cli.get("/user/1").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_text("sunli").await;
+
+    let resp = cli.get("/user/2").send().await;
+    resp.assert_status(StatusCode::FORBIDDEN);
+
+    let resp = cli.get("/user/3").send().await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+    resp.assert_json(serde_json::json!({"message": "user `3` not found"}))
+        .await;
+}
+
+#[tokio::test]
+async fn shared_params() {
+    fn add_header(ep: impl Endpoint) -> impl Endpoint {
+        ep.map_to_response().map(|mut resp| async move {
+            resp.headers_mut()
+                .insert("X-Group", "tenants".parse().unwrap());
+            resp
+        })
+    }
+
+    struct Api;
+
+    #[OpenApi(
+        prefix_path = "/tenants/:tenant_id",
+        transform = "add_header",
+        shared_params = "TenantParams",
+        shared_param(name = "tenant_id", ty = "Path<i32>", description = "The tenant id"),
+        shared_param(name = "X-Org", ident = "org", ty = "Header<Option<String>>")
+    )]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get")]
+        async fn user(&self, params: TenantParams, id: Path<i32>) -> PlainText<String> {
+            PlainText(format!(
+                "{}/{}/{}",
+                params.tenant_id.0,
+                params.org.0.as_deref().unwrap_or("-"),
+                id.0
+            ))
+        }
+
+        #[oai(path = "/ping", method = "get")]
+        async fn ping(&self) {}
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(meta.paths[0].path, "/tenants/{tenant_id}/users/{id}");
+    for path in &meta.paths {
+        let params = &path.operations[0].params;
+        assert_eq!(params[0].name, "tenant_id");
+        assert_eq!(params[0].in_type, MetaParamIn::Path);
+        assert_eq!(params[0].description.as_deref(), Some("The tenant id"));
+        assert!(params[0].required);
+        assert_eq!(params[1].name, "X-Org");
+        assert_eq!(params[1].in_type, MetaParamIn::Header);
+        assert!(!params[1].required);
+    }
+    assert_eq!(meta.paths[0].operations[0].params[2].name, "id");
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+
+    let resp = cli
+        .get("/tenants/1/users/2")
+        .header("X-Org", "acme")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_header("X-Group", "tenants");
+    resp.assert_text("1/acme/2").await;
+
+    let resp = cli.get("/tenants/abc/ping").send().await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_text(
+        "failed to parse path `tenant_id`: failed to parse \"integer_int32\": invalid digit found in string",
+    )
+    .await;
+}
+
+#[test]
+fn extra_types() {
+    #[derive(Object)]
+    struct Tenant {
+        id: i32,
+    }
+
+    #[derive(Object)]
+    struct TenantCreated {
+        tenant: Tenant,
+    }
+
+    #[derive(Object)]
+    struct User {
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/user", method = "get")]
+        async fn user(&self) -> Json<User> {
+            todo!()
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").extra_type::<TenantCreated>();
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+    assert_eq!(
+        schemas.keys().collect::<Vec<_>>(),
+        vec!["Tenant", "TenantCreated", "User"]
+    );
+
+    let schemas: serde_json::Value = serde_json::from_str(&api_service.schemas()).unwrap();
+    assert_eq!(schemas, spec["components"]["schemas"]);
+    assert_eq!(
+        schemas["TenantCreated"]["properties"]["tenant"]["$ref"],
+        "#/components/schemas/Tenant"
+    );
+
+    let schemas_yaml: serde_json::Value =
+        serde_yaml::from_str(&api_service.schemas_yaml()).unwrap();
+    assert_eq!(schemas_yaml, schemas);
+}
+
+#[tokio::test]
+async fn mock() {
+    #[derive(Object)]
+    struct Pet {
+        id: u64,
+        name: String,
+        #[oai(validator(min_length = 12))]
+        tag: Option<String>,
+        owner: Owner,
+    }
+
+    #[derive(Object)]
+    #[oai(example)]
+    struct Owner {
+        name: String,
+    }
+
+    impl Example for Owner {
+        fn example() -> Self {
+            Owner {
+                name: "sunli".to_string(),
+            }
+        }
+    }
+
+    #[derive(ApiResponse)]
+    #[allow(dead_code)]
+    enum CreatePetResponse {
+        /// Created
+        #[oai(status = 201)]
+        Created(PlainText<String>),
+        /// Bad request
+        #[oai(status = 400)]
+        BadRequest,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/pets/:id", method = "get")]
+        async fn get(&self, _id: Path<u64>) -> Json<Vec<Pet>> {
+            unimplemented!()
+        }
+
+        #[oai(path = "/pets", method = "post")]
+        async fn create(&self, _pet: Json<Pet>) -> CreatePetResponse {
+            unimplemented!()
+        }
+
+        #[oai(path = "/pets/:id", method = "delete")]
+        async fn delete(&self, _id: Path<u64>) {
+            unimplemented!()
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0").mock());
+
+    let resp = cli.get("/pets/1").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_json(serde_json::json!([{
+        "id": 0,
+        "name": "string",
+        "tag": "stringxxxxxx",
+        "owner": { "name": "sunli" },
+    }]))
+    .await;
+
+    let resp = cli
+        .post("/pets")
+        .body_json(&serde_json::json!({}))
+        .send()
+        .await;
+    resp.assert_status(StatusCode::CREATED);
+    resp.assert_content_type("text/plain; charset=utf-8");
+    resp.assert_text("string").await;
+
+    let resp = cli.delete("/pets/1").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_text("").await;
+}
+
+#[test]
+fn merge_spec() {
+    #[derive(Object)]
+    struct User {
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/user", method = "get")]
+        async fn user(&self) -> Json<User> {
+            todo!()
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").merge_spec(serde_json::json!({
+        "paths": {
+            "/health": {
+                "get": { "responses": { "200": { "description": "Healthy" } } }
+            }
+        },
+        "components": {
+            "schemas": {
+                "Health": { "type": "string" }
+            }
+        }
+    }));
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    let mut paths = spec["paths"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(paths, vec!["/health", "/user"]);
+    let mut schemas = spec["components"]["schemas"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>();
+    schemas.sort();
+    assert_eq!(schemas, vec!["Health", "User"]);
+    assert_eq!(spec["info"]["title"], "test");
+
+    let spec_yaml: serde_json::Value = serde_yaml::from_str(&api_service.spec_yaml()).unwrap();
+    assert_eq!(spec_yaml, spec);
+}
+
+#[test]
+#[should_panic(expected = "/components/schemas/User")]
+fn merge_spec_conflict() {
+    #[derive(Object)]
+    struct User {
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/user", method = "get")]
+        async fn user(&self) -> Json<User> {
+            todo!()
+        }
+    }
+
+    OpenApiService::new(Api, "test", "1.0")
+        .merge_spec(serde_json::json!({
+            "components": {
+                "schemas": {
+                    "User": { "type": "string" }
+                }
+            }
+        }))
+        .spec();
+}
+
+#[cfg(feature = "i18n")]
+#[tokio::test]
+async fn i18n() {
+    use poem::i18n::I18NResources;
+
+    /// i18n:user
+    #[derive(Object)]
+    struct User {
+        /// i18n:user-name
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        /// i18n:get-user
+        #[oai(path = "/user", method = "get")]
+        async fn user(&self, id: Query<i32>) -> Json<User> {
+            Json(User {
+                name: id.to_string(),
+            })
+        }
+    }
+
+    let resources = I18NResources::builder()
+        .add_ftl(
+            "en-US",
+            "user = A user\nget-user = Get a user\nopenapi-parse-param-error = Invalid `{ $name }`",
+        )
+        .add_ftl(
+            "fr",
+            "user = Un utilisateur\nget-user = Obtenir un utilisateur",
+        )
+        .build()
+        .unwrap();
+    let api_service = OpenApiService::new(Api, "test", "1.0").i18n(resources);
+
+    // the default language, and the missing messages are replaced with their ids
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(spec["paths"]["/user"]["get"]["summary"], "Get a user");
+    assert_eq!(
+        spec["components"]["schemas"]["User"]["description"],
+        "A user"
+    );
+    assert_eq!(
+        spec["components"]["schemas"]["User"]["properties"]["name"]["description"],
+        "user-name"
+    );
+
+    let cli = TestClient::new(api_service.spec_endpoint());
+    let resp = cli
+        .get("/")
+        .header("accept-language", "de;q=0.9, fr")
+        .send()
+        .await;
+    let spec: serde_json::Value = resp.0.into_body().into_json().await.unwrap();
+    assert_eq!(
+        spec["paths"]["/user"]["get"]["summary"],
+        "Obtenir un utilisateur"
+    );
+    assert_eq!(
+        spec["components"]["schemas"]["User"]["description"],
+        "Un utilisateur"
+    );
+
+    let cli = TestClient::new(api_service.spec_endpoint_yaml());
+    let resp = cli.get("/").query("lang", &"fr").send().await;
+    let spec: serde_json::Value =
+        serde_yaml::from_str(&resp.0.into_body().into_string().await.unwrap()).unwrap();
+    assert_eq!(
+        spec["paths"]["/user"]["get"]["summary"],
+        "Obtenir un utilisateur"
+    );
+
+    let cli = TestClient::new(api_service);
+    let resp = cli.get("/user").query("id", &"abc").send().await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_text("Invalid `\u{2068}id\u{2069}`").await;
+
+    let resp = cli.get("/user").query("id", &1).send().await;
+    resp.assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn rate_limit() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/a", method = "get", rate_limit(requests = 2, period = 60))]
+        async fn a(&self) {}
+
+        #[oai(path = "/b", method = "get")]
+        async fn b(&self) {}
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(
+        operation.rate_limit,
+        Some(RateLimit {
+            requests: 2,
+            period: Duration::from_secs(60),
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(operation).unwrap()["x-rate-limit"],
+        serde_json::json!({ "requests": 2, "period": 60 })
+    );
+    let resp = operation
+        .responses
+        .responses
+        .iter()
+        .find(|resp| resp.status == Some(429))
+        .unwrap();
+    assert_eq!(resp.headers[0].name, "Retry-After");
+    assert!(meta.paths[1].operations[0].rate_limit.is_none());
+
+    let clock = MockClock::new();
+    let cli = TestClient::new(
+        OpenApiService::new(Api, "test", "1.0").rate_limiter(MemoryRateLimiter::new()),
+    )
+    .clock(clock.clone());
+
+    cli.get("/a").send().await.assert_status_is_ok();
+    cli.get("/a").send().await.assert_status_is_ok();
+    let resp = cli.get("/a").send().await;
+    resp.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    resp.assert_header("retry-after", "60");
+    for _ in 0..3 {
+        cli.get("/b").send().await.assert_status_is_ok();
+    }
+
+    clock.advance(Duration::from_secs(45));
+    cli.get("/a")
+        .send()
+        .await
+        .assert_header("retry-after", "15");
+
+    clock.advance(Duration::from_secs(15));
+    cli.get("/a").send().await.assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn rate_limit_operations() {
+    #[derive(Default, Clone)]
+    struct RecordOperations(Arc<Mutex<Vec<String>>>);
+
+    impl RateLimiter for RecordOperations {
+        fn check<'a>(
+            &'a self,
+            _req: &'a Request,
+            operation: &'a str,
+            _limit: RateLimit,
+        ) -> BoxFuture<'a, poem::Result<()>> {
+            self.0.lock().unwrap().push(operation.to_string());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(
+            path = "/users/:id",
+            method = "get",
+            rate_limit(requests = 10, period = 60)
+        )]
+        async fn user(&self, id: Path<i32>) -> PlainText<String> {
+            PlainText(id.0.to_string())
+        }
+
+        #[oai(
+            path = "/internal",
+            method = "get",
+            extension(name = "x-rate-limit", value = r#"{ "requests": 1, "period": 60 }"#)
+        )]
+        async fn internal(&self) {}
+    }
+
+    let limiter = RecordOperations::default();
+    let cli =
+        TestClient::new(OpenApiService::new(Api, "test", "1.0").rate_limiter(limiter.clone()));
+
+    cli.get("/users/1").send().await.assert_status_is_ok();
+    // the extensions of the users are not enforced
+    cli.get("/internal").send().await.assert_status_is_ok();
+    cli.get("/internal").send().await.assert_status_is_ok();
+    assert_eq!(*limiter.0.lock().unwrap(), ["GET /users/{id}"]);
+}
+
+#[tokio::test]
+async fn deprecation_headers() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(
+            path = "/v1/users",
+            method = "get",
+            deprecated,
+            sunset = "2025-12-31",
+            replacement = "/v2/users"
+        )]
+        async fn users_v1(&self) -> PlainText<&'static str> {
+            PlainText("v1")
+        }
+
+        #[oai(path = "/v1/pets", method = "get", deprecated)]
+        async fn pets_v1(&self) -> Result<(), Error> {
+            Err(Error::from_status(StatusCode::NOT_FOUND))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert!(operation.deprecated);
+    assert_eq!(operation.extensions["x-sunset"], "2025-12-31");
+    assert_eq!(operation.extensions["x-replacement"], "/v2/users");
+    assert_eq!(
+        operation.responses.responses[0]
+            .headers
+            .iter()
+            .map(|header| header.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Deprecation", "Sunset", "Link"]
+    );
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+
+    let resp = cli.get("/v1/users").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_header("deprecation", "true");
+    resp.assert_header("sunset", "Wed, 31 Dec 2025 00:00:00 GMT");
+    resp.assert_header("link", "</v2/users>; rel=\"successor-version\"");
+
+    let resp = cli.get("/v1/pets").send().await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+    resp.assert_header("deprecation", "true");
+    resp.assert_header_is_not_exist("sunset");
+}
+
+#[tokio::test]
+async fn operation_transforms() {
+    mod middlewares {
+        use poem::{Endpoint, EndpointExt, IntoResponse};
+
+        pub(super) fn trace(ep: impl Endpoint) -> impl Endpoint {
+            ep.map_to_response().map(|mut resp| async move {
+                let trace = match resp.headers().get("X-Trace") {
+                    Some(trace) => format!("{},operation", trace.to_str().unwrap()),
+                    None => "operation".to_string(),
+                };
+                resp.headers_mut().insert("X-Trace", trace.parse().unwrap());
+                resp
+            })
+        }
+
+        pub(super) fn exempt(ep: impl Endpoint) -> impl Endpoint {
+            ep.map_to_response()
+                .map(|resp| async move { resp.with_header("X-Exempt", "1").into_response() })
+        }
+    }
+
+    fn api_trace(ep: impl Endpoint) -> impl Endpoint {
+        ep.map_to_response().map(|mut resp| async move {
+            let trace = format!(
+                "{},api",
+                resp.headers()
+                    .get("X-Trace")
+                    .map(|trace| trace.to_str().unwrap())
+                    .unwrap_or_default()
+            );
+            resp.headers_mut().insert("X-Trace", trace.parse().unwrap());
+            resp
+        })
+    }
+
+    struct Api;
+
+    #[OpenApi(transform = "api_trace")]
+    impl Api {
+        #[oai(
+            path = "/a",
+            method = "get",
+            transform = "middlewares::trace",
+            transform = "middlewares::exempt"
+        )]
+        async fn a(&self) {}
+
+        #[oai(path = "/b", method = "get")]
+        async fn b(&self) {}
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+
+    let resp = cli.get("/a").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_header("X-Trace", "operation,api");
+    resp.assert_header("X-Exempt", "1");
+
+    let resp = cli.get("/b").send().await;
+    resp.assert_header("X-Trace", ",api");
+    resp.assert_header_is_not_exist("X-Exempt");
+}
+
+#[tokio::test]
+async fn versions() {
+    #[derive(Object)]
+    struct UserV1 {
+        name: String,
+    }
+
+    #[derive(Object)]
+    struct UserV2 {
+        first_name: String,
+        last_name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) -> PlainText<&'static str> {
+            PlainText("hello")
+        }
+
+        #[oai(path = "/hello", method = "get", version = "v2")]
+        async fn hello_v2(&self) -> PlainText<&'static str> {
+            PlainText("hello v2")
+        }
+
+        #[oai(path = "/user", method = "get", version = "v1")]
+        async fn user_v1(&self) -> Json<UserV1> {
+            Json(UserV1 {
+                name: "sunli".to_string(),
+            })
+        }
+
+        #[oai(path = "/user", method = "get", version = "v2")]
+        async fn user_v2(&self) -> Json<UserV2> {
+            Json(UserV2 {
+                first_name: "sun".to_string(),
+                last_name: "li".to_string(),
+            })
+        }
+
+        #[oai(path = "/legacy", method = "get", version = "v1")]
+        async fn legacy(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").versions(["v1", "v2"]);
+
+    let paths = |spec: &serde_json::Value| {
+        let mut paths = spec["paths"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    };
+    let spec: serde_json::Value = serde_json::from_str(&api_service.version_spec("v1")).unwrap();
+    assert_eq!(paths(&spec), ["/v1/hello", "/v1/legacy", "/v1/user"]);
+    assert!(spec["components"]["schemas"]["UserV1"].is_object());
+    assert!(spec["components"]["schemas"]["UserV2"].is_null());
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.version_spec("v2")).unwrap();
+    assert_eq!(paths(&spec), ["/v2/hello", "/v2/user"]);
+    assert!(spec["components"]["schemas"]["UserV1"].is_null());
+    assert!(spec["components"]["schemas"]["UserV2"].is_object());
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(
+        paths(&spec),
+        [
+            "/v1/hello",
+            "/v1/legacy",
+            "/v1/user",
+            "/v2/hello",
+            "/v2/user"
+        ]
+    );
+
+    let cli = TestClient::new(api_service);
+    cli.get("/v1/hello").send().await.assert_text("hello").await;
+    cli.get("/v2/hello")
+        .send()
+        .await
+        .assert_text("hello v2")
+        .await;
+    cli.get("/v1/user")
+        .send()
+        .await
+        .assert_json(serde_json::json!({ "name": "sunli" }))
+        .await;
+    cli.get("/v2/user")
+        .send()
+        .await
+        .assert_json(serde_json::json!({ "first_name": "sun", "last_name": "li" }))
+        .await;
+    cli.get("/v1/legacy").send().await.assert_status_is_ok();
+    cli.get("/v2/legacy")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    cli.get("/hello")
+        .send()
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn max_request_size() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/small", method = "post", max_request_size = "1KB")]
+        async fn small(&self, data: Binary<Vec<u8>>) -> PlainText<String> {
+            PlainText(data.0.len().to_string())
+        }
+
+        #[oai(path = "/default", method = "post")]
+        async fn default(&self, data: Binary<Vec<u8>>) -> PlainText<String> {
+            PlainText(data.0.len().to_string())
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").max_request_size(2048);
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(spec["paths"]["/small"]["post"]["x-max-request-size"], 1024);
+    assert!(spec["paths"]["/default"]["post"]["x-max-request-size"].is_null());
+    for path in ["/small", "/default"] {
+        assert_eq!(
+            spec["paths"][path]["post"]["responses"]["413"]["description"],
+            "Payload too large"
+        );
+    }
+
+    let cli = TestClient::new(api_service);
+
+    cli.post("/small")
+        .content_type("application/octet-stream")
+        .body(vec![0; 1024])
+        .send()
+        .await
+        .assert_text("1024")
+        .await;
+    cli.post("/small")
+        .content_type("application/octet-stream")
+        .body(vec![0; 1025])
+        .send()
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    // the limit of the operation overrides the default limit
+    cli.post("/small")
+        .content_type("application/octet-stream")
+        .body(vec![0; 2048])
+        .send()
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    cli.post("/default")
+        .content_type("application/octet-stream")
+        .body(vec![0; 2048])
+        .send()
+        .await
+        .assert_text("2048")
+        .await;
+    cli.post("/default")
+        .content_type("application/octet-stream")
+        .body(vec![0; 2049])
+        .send()
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    // the payloads are counted while they are read, so that the limit can't be
+    // bypassed with a wrong `Content-Length`
+    cli.post("/small")
+        .header("content-length", "10")
+        .content_type("application/octet-stream")
+        .body(vec![0; 1025])
+        .send()
+        .await
+        .assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,194 @@
+use poem_openapi::{
+    Enum, Object, OpenApi, OpenApiService,
+    diff::{BreakingChange, BreakingChangeKind, breaking_changes},
+    param::{Path, Query},
+    payload::Json,
+};
+use serde_json::Value;
+
+fn spec<T: OpenApi>(api: T) -> Value {
+    serde_json::from_str(&OpenApiService::new(api, "test", "1.0").spec()).unwrap()
+}
+
+fn change(location: &str, kind: BreakingChangeKind) -> BreakingChange {
+    BreakingChange {
+        location: location.to_string(),
+        kind,
+    }
+}
+
+#[test]
+fn no_changes() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/pets/:id", method = "get")]
+        async fn get(&self, id: Path<i32>, name: Query<Option<String>>) -> Json<String> {
+            Json(format!("{}{:?}", id.0, name.0))
+        }
+    }
+
+    assert_eq!(breaking_changes(&spec(Api), &spec(Api)), vec![]);
+}
+
+#[test]
+fn operations_and_params() {
+    struct OldApi;
+
+    #[OpenApi]
+    impl OldApi {
+        #[oai(path = "/pets/:id", method = "get")]
+        async fn get(&self, id: Path<i32>, name: Query<Option<String>>) {
+            let _ = (id, name);
+        }
+
+        #[oai(path = "/pets/:id", method = "delete")]
+        async fn delete(&self, id: Path<i32>) {
+            let _ = id;
+        }
+    }
+
+    struct NewApi;
+
+    #[OpenApi]
+    impl NewApi {
+        #[oai(path = "/pets/:pet_id", method = "get")]
+        async fn get(
+            &self,
+            pet_id: Path<i32>,
+            name: Query<String>,
+            age: Query<i32>,
+            color: Query<Option<String>>,
+        ) {
+            let _ = (pet_id, name, age, color);
+        }
+    }
+
+    let changes = breaking_changes(&spec(OldApi), &spec(NewApi));
+    assert_eq!(
+        changes,
+        vec![
+            change("DELETE /pets/{id}", BreakingChangeKind::OperationRemoved),
+            change(
+                "GET /pets/{id}",
+                BreakingChangeKind::ParameterBecameRequired("name".to_string())
+            ),
+            change(
+                "GET /pets/{id}",
+                BreakingChangeKind::RequiredParameterAdded("age".to_string())
+            ),
+        ]
+    );
+    assert_eq!(
+        changes[1].to_string(),
+        "GET /pets/{id}: the parameter `name` became required"
+    );
+}
+
+#[test]
+fn schemas() {
+    mod old {
+        use super::*;
+
+        #[derive(Enum)]
+        pub enum Color {
+            Red,
+            Green,
+        }
+
+        #[derive(Object)]
+        pub struct Pet {
+            pub name: String,
+            pub color: Color,
+            pub age: Option<i32>,
+            pub tags: Vec<String>,
+        }
+
+        pub struct Api;
+
+        #[OpenApi]
+        impl Api {
+            #[oai(path = "/pets", method = "post")]
+            async fn create(&self, pet: Json<Pet>) -> Json<Pet> {
+                pet
+            }
+        }
+    }
+
+    mod new {
+        use super::*;
+
+        #[derive(Enum)]
+        pub enum Color {
+            Red,
+            Blue,
+        }
+
+        #[derive(Object)]
+        pub struct Pet {
+            #[oai(validator(max_length = 32))]
+            pub name: String,
+            pub color: Color,
+            pub age: Option<String>,
+            pub owner: String,
+        }
+
+        pub struct Api;
+
+        #[OpenApi]
+        impl Api {
+            #[oai(path = "/pets", method = "post")]
+            async fn create(&self, pet: Json<Pet>) -> Json<Pet> {
+                pet
+            }
+        }
+    }
+
+    let changes = breaking_changes(&spec(old::Api), &spec(new::Api));
+    let location = |s: &str| s.to_string();
+    let request = "POST /pets request body application/json; charset=utf-8";
+    let response = "POST /pets response 200 application/json; charset=utf-8";
+    assert_eq!(
+        changes
+            .into_iter()
+            .map(|change| (change.location, change.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                location(request),
+                BreakingChangeKind::RequiredPropertyAdded("owner".to_string())
+            ),
+            (
+                format!("{request} .age"),
+                BreakingChangeKind::TypeChanged {
+                    old: "integer".to_string(),
+                    new: "string".to_string()
+                }
+            ),
+            (
+                format!("{request} .color"),
+                BreakingChangeKind::EnumValueRemoved("Green".to_string())
+            ),
+            (
+                format!("{request} .name"),
+                BreakingChangeKind::ConstraintNarrowed("maxLength")
+            ),
+            (
+                location(response),
+                BreakingChangeKind::PropertyRemoved("tags".to_string())
+            ),
+            (
+                format!("{response} .age"),
+                BreakingChangeKind::TypeChanged {
+                    old: "integer".to_string(),
+                    new: "string".to_string()
+                }
+            ),
+            (
+                format!("{response} .color"),
+                BreakingChangeKind::EnumValueAdded("Blue".to_string())
+            ),
+        ]
+    );
+}
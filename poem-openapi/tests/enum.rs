@@ -1,7 +1,7 @@
 use poem_openapi::{
     Enum,
     registry::{MetaExternalDocument, MetaSchemaRef, Registry},
-    types::{ParseFromJSON, ToJSON, Type},
+    types::{ParseFromJSON, ParseFromParameter, ToJSON, Type},
 };
 use serde_json::{Value, json};
 
@@ -221,3 +221,94 @@ async fn external_docs() {
         })
     );
 }
+
+#[test]
+fn integer() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(integer)]
+    enum Code {
+        Unknown,
+        NotFound = 5,
+        AlreadyExists,
+        Internal = -1,
+    }
+
+    let mut registry = Registry::new();
+    Code::register(&mut registry);
+    let meta = registry.schemas.remove("Code").unwrap();
+    assert_eq!(meta.ty, "integer");
+    assert_eq!(
+        meta.enum_items,
+        vec![json!(0), json!(5), json!(6), json!(-1)]
+    );
+
+    assert_eq!(Code::AlreadyExists.to_json(), Some(json!(6)));
+    assert_eq!(
+        Code::parse_from_json(Some(json!(-1))).unwrap(),
+        Code::Internal
+    );
+    assert!(Code::parse_from_json(Some(json!(7))).is_err());
+    assert!(Code::parse_from_json(Some(json!("5"))).is_err());
+    assert_eq!(Code::parse_from_parameter("5").unwrap(), Code::NotFound);
+    assert!(Code::parse_from_parameter("a").is_err());
+}
+
+#[test]
+fn other() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    enum Status {
+        Active,
+        Inactive,
+        #[oai(other)]
+        Other(String),
+    }
+
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(integer)]
+    enum Code {
+        Ok,
+        Cancelled,
+        #[oai(other)]
+        Unrecognized(i64),
+    }
+
+    let mut registry = Registry::new();
+    Status::register(&mut registry);
+    let meta = registry.schemas.remove("Status").unwrap();
+    assert!(meta.enum_items.is_empty());
+    assert_eq!(
+        meta.extensions["x-extensible-enum"],
+        json!(["Active", "Inactive"])
+    );
+
+    assert_eq!(
+        Status::parse_from_json(Some(json!("Active"))).unwrap(),
+        Status::Active
+    );
+    assert_eq!(
+        Status::parse_from_json(Some(json!("Deleted"))).unwrap(),
+        Status::Other("Deleted".to_string())
+    );
+    assert_eq!(
+        Status::Other("Deleted".to_string()).to_json(),
+        Some(json!("Deleted"))
+    );
+    assert_eq!(
+        Status::parse_from_parameter("Deleted").unwrap(),
+        Status::Other("Deleted".to_string())
+    );
+
+    assert_eq!(
+        Code::parse_from_json(Some(json!(1))).unwrap(),
+        Code::Cancelled
+    );
+    assert_eq!(
+        Code::parse_from_json(Some(json!(42))).unwrap(),
+        Code::Unrecognized(42)
+    );
+    assert_eq!(Code::Unrecognized(42).to_json(), Some(json!(42)));
+    assert_eq!(
+        Code::parse_from_parameter("42").unwrap(),
+        Code::Unrecognized(42)
+    );
+}
@@ -4,10 +4,10 @@ use poem::{Request, RequestBody};
 use poem_openapi::{
     Enum, Multipart, Object,
     payload::{ParsePayload, Payload},
-    registry::{MetaSchema, MetaSchemaRef},
+    registry::{MetaEncoding, MetaMediaType, MetaSchema, MetaSchemaRef},
     types::{
         Binary,
-        multipart::{JsonField, Upload},
+        multipart::{JsonField, Upload, UploadStream},
     },
 };
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
@@ -549,3 +549,144 @@ async fn deny_unknown_fields() {
     .unwrap_err();
     assert_eq!(err.to_string(), "parse multipart error: unknown field `c`");
 }
+
+#[tokio::test]
+async fn upload_multiple_files() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        files: Vec<Upload>,
+    }
+
+    let data = create_multipart_payload(&[
+        ("files", Some("1.txt"), &[1, 2, 3]),
+        ("files", Some("2.txt"), &[4, 5]),
+    ]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(a.files.len(), 2);
+    assert_eq!(a.files[0].file_name(), Some("1.txt"));
+    assert_eq!(a.files[1].file_name(), Some("2.txt"));
+    assert_eq!(a.files[1].size(), 2);
+}
+
+#[tokio::test]
+async fn limit() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        #[oai(limit = 3)]
+        files: Vec<Upload>,
+    }
+
+    let data = create_multipart_payload(&[
+        ("files", Some("1.txt"), &[1, 2, 3]),
+        ("files", Some("2.txt"), &[4, 5]),
+    ]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(a.files.len(), 2);
+
+    let data = create_multipart_payload(&[
+        ("files", Some("1.txt"), &[1, 2, 3]),
+        ("files", Some("2.txt"), &[4, 5, 6, 7]),
+    ]);
+    let err = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("the field exceeds the size limit"));
+}
+
+#[test]
+fn encoding() {
+    #[derive(Multipart)]
+    #[allow(dead_code)]
+    struct A {
+        name: String,
+        tags: JsonField<Vec<String>>,
+        #[oai(content_type = "image/png")]
+        image: Upload,
+    }
+
+    let schema_ref = A::schema_ref();
+    let schema: &MetaSchema = schema_ref.unwrap_inline();
+    assert_eq!(
+        schema.encoding,
+        vec![
+            (
+                "tags",
+                MetaEncoding {
+                    content_type: "application/json"
+                }
+            ),
+            (
+                "image",
+                MetaEncoding {
+                    content_type: "image/png"
+                }
+            ),
+        ]
+    );
+
+    let media_type = MetaMediaType {
+        content_type: "multipart/form-data",
+        schema: A::schema_ref(),
+        example: None,
+        examples: vec![],
+    };
+    assert_eq!(
+        serde_json::to_value(&media_type).unwrap()["encoding"],
+        serde_json::json!({
+            "tags": {"contentType": "application/json"},
+            "image": {"contentType": "image/png"},
+        })
+    );
+}
+
+#[tokio::test]
+async fn upload_stream() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        name: String,
+        file: UploadStream,
+    }
+
+    let data = create_multipart_payload(&[
+        ("name", None, b"abc"),
+        ("file", Some("1.txt"), &[1, 2, 3]),
+        ("other", None, b"ignored"),
+    ]);
+    let a = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(data.into()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(a.name, "abc");
+    assert_eq!(a.file.file_name(), Some("1.txt"));
+
+    let mut data = Vec::new();
+    a.file
+        .into_async_read()
+        .read_to_end(&mut data)
+        .await
+        .unwrap();
+    assert_eq!(data, vec![1, 2, 3]);
+}
@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use poem_openapi::{
     Enum, NewType, Object, OpenApi,
-    registry::{MetaExternalDocument, MetaSchema, MetaSchemaRef, Registry},
-    types::{Example, ParseFromJSON, ToJSON, Type},
+    registry::{MetaExternalDocument, MetaSchema, MetaSchemaRef, MetaXml, Registry},
+    types::{Example, ParseFromJSON, ParseFromXML, ToJSON, ToXML, Type},
 };
 use serde_json::json;
 
@@ -62,6 +62,66 @@ fn generics() {
     assert_eq!(meta.properties[1].1.unwrap_inline().format, Some("double"));
 }
 
+#[test]
+fn generics_without_bounds() {
+    #[derive(Debug, Object)]
+    struct Paginated<T> {
+        items: Vec<T>,
+        total: u64,
+        #[oai(skip)]
+        _marker: std::marker::PhantomData<fn(T)>,
+    }
+
+    #[derive(Debug, Object)]
+    struct User {
+        id: i32,
+    }
+
+    assert_eq!(<Paginated<User>>::name(), "Paginated_User");
+    assert_eq!(<Paginated<i32>>::name(), "Paginated_integer_int32");
+    let meta = get_meta::<Paginated<User>>();
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().items,
+        Some(Box::new(MetaSchemaRef::Reference("User".to_string())))
+    );
+
+    let value = Paginated::<User>::parse_from_json(Some(json!({
+        "items": [{"id": 1}, {"id": 2}],
+        "total": 2,
+    })))
+    .unwrap();
+    assert_eq!(value.items.len(), 2);
+    assert_eq!(
+        value.to_json(),
+        Some(json!({"items": [{"id": 1}, {"id": 2}], "total": 2}))
+    );
+}
+
+#[test]
+fn generics_name_template() {
+    #[derive(Object)]
+    #[oai(rename = "{T}Page")]
+    struct Paginated<T> {
+        items: Vec<T>,
+    }
+
+    #[derive(Object)]
+    #[oai(rename = "Pair_{A}_and_{B}")]
+    struct Pair<A, B> {
+        a: A,
+        b: B,
+    }
+
+    #[derive(Object)]
+    struct User {
+        id: i32,
+    }
+
+    assert_eq!(<Paginated<User>>::name(), "UserPage");
+    assert_eq!(<Pair<User, bool>>::name(), "Pair_User_and_boolean");
+    assert_eq!(get_meta::<Paginated<User>>().properties[0].0, "items");
+}
+
 #[test]
 fn deprecated() {
     #[derive(Object)]
@@ -397,6 +457,46 @@ fn read_only() {
     );
 }
 
+#[test]
+fn ignore_read_only() {
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(ignore_read_only, deny_unknown_fields)]
+    struct Obj {
+        #[oai(read_only)]
+        id: i32,
+        value: i32,
+        #[oai(write_only)]
+        password: String,
+    }
+
+    assert_eq!(
+        Obj::parse_from_json(Some(serde_json::json!({
+            "id": 99,
+            "value": 100,
+            "password": "abc",
+        })))
+        .unwrap(),
+        Obj {
+            id: 0,
+            value: 100,
+            password: "abc".to_string()
+        }
+    );
+
+    assert_eq!(
+        Obj {
+            id: 99,
+            value: 100,
+            password: "abc".to_string()
+        }
+        .to_json(),
+        Some(serde_json::json!({
+            "id": 99,
+            "value": 100,
+        }))
+    );
+}
+
 #[cfg(feature = "time")]
 #[test]
 fn read_only_with_default() {
@@ -711,6 +811,109 @@ fn flatten_hash_map() {
     );
 }
 
+#[test]
+fn flatten_hash_map_remaining_properties() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj1 {
+        a: i32,
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        #[oai(flatten)]
+        extra: HashMap<String, i32>,
+        #[oai(flatten)]
+        obj1: Obj1,
+        b: i32,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.required, vec!["a", "b"]);
+    assert_eq!(
+        meta.additional_properties,
+        Some(Box::new(i32::schema_ref()))
+    );
+
+    let obj = Obj::parse_from_json(Some(json!({"a": 1, "b": 2, "c": 3}))).unwrap();
+    assert_eq!(
+        obj,
+        Obj {
+            extra: HashMap::from([("c".to_string(), 3)]),
+            obj1: Obj1 { a: 1 },
+            b: 2,
+        }
+    );
+    assert_eq!(obj.to_json(), Some(json!({"a": 1, "b": 2, "c": 3})));
+}
+
+#[test]
+fn field_alias() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        #[oai(alias = "userName", alias = "user")]
+        user_name: String,
+        #[oai(alias = "n", default)]
+        count: i32,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.properties[0].0, "user_name");
+
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"userName": "a", "n": 1}))).unwrap(),
+        Obj {
+            user_name: "a".to_string(),
+            count: 1,
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(Some(json!({"user": "b", "user_name": "a"}))).unwrap(),
+        Obj {
+            user_name: "a".to_string(),
+            count: 0,
+        }
+    );
+    assert_eq!(
+        Obj {
+            user_name: "a".to_string(),
+            count: 1,
+        }
+        .to_json(),
+        Some(json!({"user_name": "a", "count": 1}))
+    );
+}
+
+#[test]
+fn field_skip_serializing_and_deserializing() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    #[oai(deny_unknown_fields)]
+    struct Obj {
+        #[oai(skip_serializing)]
+        password: String,
+        #[oai(skip_deserializing)]
+        id: i32,
+        name: String,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert!(meta.properties[0].1.unwrap_inline().write_only);
+    assert!(meta.properties[1].1.unwrap_inline().read_only);
+    assert_eq!(meta.required, vec!["password", "id", "name"]);
+
+    // the skipped values are ignored instead of rejected
+    let obj =
+        Obj::parse_from_json(Some(json!({"password": "secret", "id": 5, "name": "a"}))).unwrap();
+    assert_eq!(
+        obj,
+        Obj {
+            password: "secret".to_string(),
+            id: 0,
+            name: "a".to_string(),
+        }
+    );
+    assert_eq!(obj.to_json(), Some(json!({"id": 0, "name": "a"})));
+}
+
 #[test]
 fn remote() {
     mod remote_types {
@@ -751,6 +954,74 @@ fn remote() {
     );
 }
 
+#[test]
+fn remote_field() {
+    mod remote_types {
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        pub struct Range {
+            pub start: i32,
+            pub end: i32,
+        }
+    }
+
+    /// A range of numbers
+    #[derive(Object)]
+    #[oai(remote = "remote_types::Range", rename = "Range")]
+    struct RangeDef {
+        start: i32,
+        end: i32,
+    }
+
+    #[derive(Debug, Object, Eq, PartialEq)]
+    struct Query {
+        #[oai(with = "RangeDef")]
+        range: remote_types::Range,
+        #[oai(with = "RangeDef", default = "default_range")]
+        page: remote_types::Range,
+    }
+
+    fn default_range() -> remote_types::Range {
+        remote_types::Range { start: 0, end: 10 }
+    }
+
+    let mut registry = Registry::new();
+    Query::register(&mut registry);
+    let range = registry.schemas.get("Range").unwrap();
+    assert_eq!(range.description, Some("A range of numbers"));
+    let meta = get_meta::<Query>();
+    assert_eq!(meta.required, vec!["range"]);
+    assert_eq!(
+        meta.properties[0].1,
+        MetaSchemaRef::Reference("Range".to_string())
+    );
+    assert_eq!(
+        meta.properties[1].1.unwrap_inline().default,
+        Some(json!({"start": 0, "end": 10}))
+    );
+
+    let query = Query::parse_from_json(Some(json!({"range": {"start": 1, "end": 2}}))).unwrap();
+    assert_eq!(
+        query,
+        Query {
+            range: remote_types::Range { start: 1, end: 2 },
+            page: default_range(),
+        }
+    );
+    assert_eq!(
+        query.to_json(),
+        Some(json!({
+            "range": {"start": 1, "end": 2},
+            "page": {"start": 0, "end": 10},
+        }))
+    );
+    assert_eq!(
+        Query::parse_from_json(Some(json!({"range": {"start": 1}})))
+            .unwrap_err()
+            .into_message(),
+        "Expected input type \"integer_int32\", found null. (occurred while parsing \"Range\") (occurred while parsing \"Query\")"
+    );
+}
+
 #[test]
 fn skip_serializing_if_is_none() {
     #[derive(Debug, Object, Eq, PartialEq)]
@@ -1132,3 +1403,89 @@ fn deserialize_with() {
         Obj { a: 7 }
     );
 }
+
+#[test]
+fn xml() {
+    #[derive(Debug, PartialEq, Object)]
+    #[oai(xml(name = "book", namespace = "http://example.com/book"))]
+    struct Book {
+        #[oai(xml(attribute))]
+        id: i32,
+        title: String,
+        #[oai(xml(name = "author"))]
+        authors: Vec<String>,
+        #[oai(xml(wrapped))]
+        tags: Vec<String>,
+    }
+
+    let meta = get_meta::<Book>();
+    assert_eq!(
+        meta.xml,
+        Some(MetaXml {
+            name: Some("book"),
+            namespace: Some("http://example.com/book"),
+            prefix: None,
+            attribute: false,
+            wrapped: false,
+        })
+    );
+    assert!(
+        meta.properties[0]
+            .1
+            .unwrap_inline()
+            .xml
+            .as_ref()
+            .unwrap()
+            .attribute
+    );
+    assert_eq!(
+        meta.properties[2]
+            .1
+            .unwrap_inline()
+            .xml
+            .as_ref()
+            .unwrap()
+            .name,
+        Some("author")
+    );
+    assert!(
+        meta.properties[3]
+            .1
+            .unwrap_inline()
+            .xml
+            .as_ref()
+            .unwrap()
+            .wrapped
+    );
+
+    let book = Book {
+        id: 1,
+        title: "Rust".to_string(),
+        authors: vec!["a".to_string(), "b".to_string()],
+        tags: vec!["x".to_string()],
+    };
+    let xml = book.to_xml_string();
+    assert_eq!(
+        xml,
+        r#"<book id="1" xmlns="http://example.com/book"><title>Rust</title><author>a</author><author>b</author><tags><tags>x</tags></tags></book>"#
+    );
+    assert_eq!(Book::parse_from_xml_string(&xml).unwrap(), book);
+
+    assert_eq!(
+        Book::parse_from_xml_string(
+            r#"<book id="2"><title>Poem</title><author>c</author><tags/></book>"#
+        )
+        .unwrap(),
+        Book {
+            id: 2,
+            title: "Poem".to_string(),
+            authors: vec!["c".to_string()],
+            tags: vec![],
+        }
+    );
+    // the json representation is not affected
+    assert_eq!(
+        book.to_json(),
+        Some(json!({"id": 1, "title": "Rust", "authors": ["a", "b"], "tags": ["x"]}))
+    );
+}
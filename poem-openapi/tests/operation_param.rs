@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
+
 use poem::test::TestClient;
 #[cfg(feature = "cookie")]
 use poem::{
-    http::header,
+    http::{StatusCode, header},
     web::cookie::{Cookie, CookieJar, CookieKey},
 };
 #[cfg(feature = "cookie")]
 use poem_openapi::param::{Cookie as ParamCookie, CookiePrivate, CookieSigned};
 use poem_openapi::{
-    OpenApi, OpenApiService,
+    Object, OpenApi, OpenApiService,
     param::{Header, Path, Query},
     payload::Json,
     registry::{MetaApi, MetaParamIn, MetaSchema, MetaSchemaRef},
@@ -366,6 +368,161 @@ async fn path() {
         .assert_status_is_ok();
 }
 
+#[derive(Object, Debug, PartialEq)]
+#[oai(parameter)]
+struct Filter {
+    role: String,
+    first_name: Option<String>,
+    #[oai(default)]
+    limit: i32,
+}
+
+#[tokio::test]
+async fn query_deep_object() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(
+            &self,
+            #[oai(style = "deep_object")] filter: Query<Filter>,
+            #[oai(style = "deep_object")] tags: Query<Option<BTreeMap<String, i32>>>,
+        ) {
+            assert_eq!(
+                filter.0,
+                Filter {
+                    role: "admin".to_string(),
+                    first_name: Some("Alex".to_string()),
+                    limit: 10,
+                }
+            );
+            assert_eq!(tags.0, Some(BTreeMap::from([("a".to_string(), 1)])));
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+    cli.get("/")
+        .query("filter[role]", &"admin")
+        .query("filter[first_name]", &"Alex")
+        .query("filter[limit]", &10)
+        .query("tags[a]", &1)
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/")
+        .query("filter[first_name]", &"Alex")
+        .send()
+        .await
+        .assert_status(poem::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn query_object_form() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/explode", method = "get")]
+        async fn explode(&self, filter: Query<Filter>) {
+            assert_eq!(filter.role, "admin");
+            assert_eq!(filter.limit, 10);
+        }
+
+        #[oai(path = "/no_explode", method = "get")]
+        async fn no_explode(&self, #[oai(explode = false)] filter: Query<Filter>) {
+            assert_eq!(filter.role, "admin");
+            assert_eq!(filter.first_name.as_deref(), Some("Alex"));
+        }
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+    cli.get("/explode")
+        .query("role", &"admin")
+        .query("limit", &10)
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/no_explode")
+        .query("filter", &"role,admin,first_name,Alex")
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn query_pipe_delimited() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(style = "pipe_delimited", explode = false)] v: Query<Vec<i32>>) {
+            assert_eq!(v.0, vec![10, 20, 30]);
+        }
+    }
+
+    TestClient::new(OpenApiService::new(Api, "test", "1.0"))
+        .get("/")
+        .query("v", &"10|20|30")
+        .send()
+        .await
+        .assert_status_is_ok();
+}
+
+#[tokio::test]
+async fn path_styles() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/simple/:v", method = "get")]
+        async fn simple(&self, #[oai(style = "simple", explode = false)] v: Path<Vec<i32>>) {
+            assert_eq!(v.0, vec![1, 2, 3]);
+        }
+
+        #[oai(path = "/label/:v", method = "get")]
+        async fn label(&self, #[oai(style = "label")] v: Path<Filter>) {
+            assert_eq!(v.role, "admin");
+            assert_eq!(v.first_name.as_deref(), Some("Alex"));
+        }
+
+        #[oai(path = "/matrix/:v", method = "get")]
+        async fn matrix(&self, #[oai(style = "matrix")] v: Path<Vec<i32>>) {
+            assert_eq!(v.0, vec![1, 2]);
+        }
+
+        #[oai(path = "/matrix_object/:v", method = "get")]
+        async fn matrix_object(&self, #[oai(style = "matrix", explode = false)] v: Path<Filter>) {
+            assert_eq!(v.role, "admin");
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let param = &meta.paths[1].operations[0].params[0];
+    assert_eq!(param.style, Some(poem_openapi::ParameterStyle::Label));
+    assert!(param.explode);
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+    cli.get("/simple/1,2,3").send().await.assert_status_is_ok();
+    cli.get("/label/.role=admin.first_name=Alex")
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/matrix/;v=1;v=2")
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/matrix_object/;v=role,admin")
+        .send()
+        .await
+        .assert_status_is_ok();
+    cli.get("/matrix/1,2")
+        .send()
+        .await
+        .assert_status(poem::http::StatusCode::BAD_REQUEST);
+}
+
 #[cfg(feature = "cookie")]
 #[tokio::test]
 async fn cookie() {
@@ -407,6 +564,54 @@ async fn cookie() {
         .assert_status_is_ok();
 }
 
+#[cfg(feature = "cookie")]
+#[tokio::test]
+async fn cookie_tampered() {
+    use poem_openapi::payload::PlainText;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/private", method = "get")]
+        async fn private(&self, v: CookiePrivate<i32>) -> PlainText<String> {
+            PlainText(v.0.to_string())
+        }
+
+        #[oai(path = "/signed", method = "get")]
+        async fn signed(&self, v: CookieSigned<i32>) -> PlainText<String> {
+            PlainText(v.0.to_string())
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0].in_type,
+        MetaParamIn::Cookie
+    );
+
+    let api = OpenApiService::new(Api, "test", "1.0").cookie_key(CookieKey::generate());
+    let cli = TestClient::new(api);
+
+    let resp = cli
+        .get("/private")
+        .header(header::COOKIE, "v=100")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_text("failed to parse parameter `v`: the cookie cannot be decrypted")
+        .await;
+
+    let resp = cli
+        .get("/signed")
+        .header(header::COOKIE, "v=100")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_text("failed to parse parameter `v`: the signature of the cookie is invalid")
+        .await;
+}
+
 #[cfg(feature = "cookie")]
 #[tokio::test]
 async fn cookie_default() {
@@ -625,6 +830,51 @@ async fn example() {
     );
 }
 
+#[tokio::test]
+async fn named_examples() {
+    struct Api;
+
+    #[OpenApi]
+    #[allow(unused_variables)]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn test(
+            &self,
+            #[oai(examples(small = "small", large = "large"))] a: Query<i32>,
+            #[oai(examples(empty = "empty_list"))] b: Json<Vec<i32>>,
+        ) {
+            todo!();
+        }
+    }
+
+    fn small() -> i32 {
+        1
+    }
+
+    fn large() -> i32 {
+        1000
+    }
+
+    fn empty_list() -> Vec<i32> {
+        vec![]
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(
+        serde_json::to_value(&operation.params[0]).unwrap()["examples"],
+        json!({
+            "small": { "value": 1 },
+            "large": { "value": 1000 },
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(operation.request.as_ref().unwrap()).unwrap()["content"]["application/json; charset=utf-8"]
+            ["examples"],
+        json!({ "empty": { "value": [] } })
+    );
+}
+
 #[tokio::test]
 async fn required_params() {
     struct Api;
@@ -1,9 +1,14 @@
-use poem::{Error, http::StatusCode, test::TestClient};
+use futures_util::{StreamExt, stream};
+use poem::{Body, Error, http::StatusCode, test::TestClient};
 use poem_openapi::{
-    ApiResponse, OpenApi, OpenApiService,
+    ApiResponse, Object, OpenApi, OpenApiService,
     param::Query,
-    payload::{Json, Response},
+    payload::{
+        Attachment, AttachmentType, BinaryStream, Conditional, EventStream, Json, NdJsonStream,
+        Response, SseEvent, Xml,
+    },
 };
+use tokio::io::AsyncReadExt;
 
 #[tokio::test]
 async fn response_wrapper() {
@@ -51,3 +56,602 @@ async fn response_wrapper() {
     resp.assert_status(StatusCode::BAD_REQUEST);
     resp.assert_header("MY-HEADER1", "def");
 }
+
+#[tokio::test]
+async fn xml() {
+    #[derive(Object)]
+    struct Author {
+        #[oai(xml(attribute))]
+        id: u32,
+        name: String,
+    }
+
+    #[derive(Object)]
+    #[oai(xml(name = "book"))]
+    struct Book {
+        title: String,
+        pages: Option<u32>,
+        #[oai(xml(name = "author"))]
+        authors: Vec<Author>,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn index(&self, mut book: Xml<Book>) -> Xml<Book> {
+            book.title = book.title.to_uppercase();
+            book.pages = book.pages.map(|pages| pages + 1);
+            book
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let cli = TestClient::new(ep);
+
+    let resp = cli
+        .post("/")
+        .content_type("application/xml")
+        .body(
+            r#"<book><title>poem</title><pages>10</pages><author id="1"><name>a</name></author><author id="2"><name>b</name></author></book>"#,
+        )
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("application/xml; charset=utf-8");
+    resp.assert_text(
+        r#"<book><title>POEM</title><pages>11</pages><author id="1"><name>a</name></author><author id="2"><name>b</name></author></book>"#,
+    )
+    .await;
+}
+
+#[cfg(feature = "cbor")]
+#[tokio::test]
+async fn cbor() {
+    use std::collections::HashMap;
+
+    use poem_openapi::{payload::Cbor, types::Base64};
+
+    #[derive(Object)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Object)]
+    struct Blob {
+        data: Base64<Vec<u8>>,
+        sizes: HashMap<String, i32>,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/point", method = "post")]
+        async fn point(&self, point: Cbor<Point>) -> Cbor<Point> {
+            Cbor(Point {
+                x: point.y,
+                y: point.x,
+            })
+        }
+
+        #[oai(path = "/blob", method = "post")]
+        async fn blob(&self, blob: Cbor<Blob>) -> Json<Blob> {
+            Json(blob.0)
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    assert!(ep.spec().contains("application/cbor"));
+    let cli = TestClient::new(ep);
+
+    // {"x": 1, "y": -2}
+    let resp = cli
+        .post("/point")
+        .content_type("application/cbor")
+        .body(vec![0xa2, 0x61, b'x', 0x01, 0x61, b'y', 0x21])
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("application/cbor");
+    resp.assert_bytes(vec![0xa2, 0x61, b'x', 0x21, 0x61, b'y', 0x01])
+        .await;
+
+    // {"data": h'0102', "sizes": {1: 1(5)}}
+    let mut body = vec![0xa2, 0x64];
+    body.extend_from_slice(b"data");
+    body.extend_from_slice(&[0x42, 0x01, 0x02, 0x65]);
+    body.extend_from_slice(b"sizes");
+    body.extend_from_slice(&[0xa1, 0x01, 0xc1, 0x05]);
+    let resp = cli
+        .post("/blob")
+        .content_type("application/cbor")
+        .body(body)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(serde_json::json!({"data": "AQI=", "sizes": {"1": 5}}))
+        .await;
+
+    cli.post("/point")
+        .content_type("application/cbor")
+        .body(vec![0xa2, 0x61])
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn msgpack() {
+    use std::collections::HashMap;
+
+    use poem_openapi::{payload::MsgPack, types::Base64};
+
+    #[derive(Object)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Object)]
+    struct Blob {
+        data: Base64<Vec<u8>>,
+        sizes: HashMap<String, i32>,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/point", method = "post")]
+        async fn point(&self, point: MsgPack<Point>) -> MsgPack<Point> {
+            MsgPack(Point {
+                x: point.y,
+                y: point.x,
+            })
+        }
+
+        #[oai(path = "/blob", method = "post")]
+        async fn blob(&self, blob: MsgPack<Blob>) -> Json<Blob> {
+            Json(blob.0)
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    assert!(ep.spec().contains("application/msgpack"));
+    let cli = TestClient::new(ep);
+
+    // {"x": 1, "y": -2}
+    let resp = cli
+        .post("/point")
+        .content_type("application/x-msgpack")
+        .body(vec![0x82, 0xa1, b'x', 0x01, 0xa1, b'y', 0xfe])
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("application/msgpack");
+    resp.assert_bytes(vec![0x82, 0xa1, b'x', 0xfe, 0xa1, b'y', 0x01])
+        .await;
+
+    // {"data": bin(0102), "sizes": {1: 5}}
+    let mut body = vec![0x82, 0xa4];
+    body.extend_from_slice(b"data");
+    body.extend_from_slice(&[0xc4, 0x02, 0x01, 0x02, 0xa5]);
+    body.extend_from_slice(b"sizes");
+    body.extend_from_slice(&[0x81, 0x01, 0x05]);
+    let resp = cli
+        .post("/blob")
+        .content_type("application/msgpack")
+        .body(body)
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(serde_json::json!({"data": "AQI=", "sizes": {"1": 5}}))
+        .await;
+
+    cli.post("/point")
+        .content_type("application/msgpack")
+        .body(vec![0x82, 0xa1])
+        .send()
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn ndjson_stream() {
+    #[derive(Object)]
+    struct Item {
+        id: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/sum", method = "post")]
+        async fn sum(&self, mut items: NdJsonStream<Item>) -> Json<Vec<String>> {
+            let mut results = Vec::new();
+            while let Some(item) = items.next().await {
+                results.push(match item {
+                    Ok(item) => item.id.to_string(),
+                    Err(err) => err.to_string(),
+                });
+            }
+            Json(results)
+        }
+
+        #[oai(path = "/export", method = "get")]
+        async fn export(&self) -> NdJsonStream<Item> {
+            NdJsonStream::new(stream::iter((1..=3).map(|id| Item { id })))
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let spec = ep.spec();
+    assert!(spec.contains("application/x-ndjson"));
+    let cli = TestClient::new(ep);
+
+    // lines may be split across chunks
+    let chunks = [
+        "{\"id\": 1}\n{\"i",
+        "d\": 2}\n\n",
+        "{\"name\": 3}\n",
+        "{\"id\": 4}",
+    ];
+    let resp = cli
+        .post("/sum")
+        .content_type("application/x-ndjson")
+        .body(Body::from_bytes_stream(stream::iter(
+            chunks.map(Ok::<_, std::io::Error>),
+        )))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(&[
+        "1",
+        "2",
+        "parse request payload error: Expected input type \"integer_int32\", found null. (occurred while parsing \"Item\")",
+        "4",
+    ])
+    .await;
+
+    let resp = cli.get("/export").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("application/x-ndjson");
+    resp.assert_text("{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n")
+        .await;
+}
+
+#[tokio::test]
+async fn binary_stream() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/upload", method = "post")]
+        async fn upload(&self, mut data: BinaryStream) -> Json<Vec<usize>> {
+            // the first chunk is read partially, and its remaining bytes are
+            // yielded first by the stream
+            let mut buf = [0; 2];
+            let mut sizes = vec![data.read(&mut buf).await.unwrap()];
+            while let Some(chunk) = data.next().await {
+                sizes.push(chunk.unwrap().len());
+            }
+            Json(sizes)
+        }
+
+        #[oai(path = "/download", method = "get")]
+        async fn download(&self) -> BinaryStream {
+            BinaryStream::from_async_read(&b"abcdef"[..])
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let spec: serde_json::Value = serde_json::from_str(&ep.spec()).unwrap();
+    assert_eq!(
+        spec["paths"]["/upload"]["post"]["requestBody"],
+        serde_json::json!({
+            "content": {
+                "application/octet-stream": {
+                    "schema": { "type": "string", "format": "binary" }
+                }
+            },
+            "required": true
+        })
+    );
+    let cli = TestClient::new(ep);
+
+    let resp = cli
+        .post("/upload")
+        .content_type("application/octet-stream")
+        .body(Body::from_bytes_stream(stream::iter(
+            ["abcde", "fgh"].map(Ok::<_, std::io::Error>),
+        )))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(&[2, 3, 3]).await;
+
+    let resp = cli.get("/download").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("application/octet-stream");
+    resp.assert_text("abcdef").await;
+}
+
+#[cfg(feature = "csv")]
+#[tokio::test]
+async fn csv() {
+    use poem_openapi::payload::{Csv, CsvStream};
+
+    #[derive(Object, Clone)]
+    struct User {
+        id: u64,
+        name: String,
+        email: Option<String>,
+        active: bool,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/import", method = "post")]
+        async fn import(&self, users: Csv<User>) -> Csv<User> {
+            Csv::new(users.into_inner()).delimiter(b';')
+        }
+
+        #[oai(path = "/stream", method = "post")]
+        async fn stream(&self, users: CsvStream<User>) -> CsvStream<User> {
+            CsvStream::try_new(users).headers(false)
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    assert!(ep.spec().contains("text/csv"));
+    let cli = TestClient::new(ep);
+
+    let resp = cli
+        .post("/import")
+        .content_type("text/csv")
+        .body("name,id,active,email\r\n\"Doe, John\",1,true,\r\nJane,2,false,jane@example.com\r\n")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("text/csv; charset=utf-8");
+    resp.assert_text(
+        "id;name;email;active\r\n1;Doe, John;;true\r\n2;Jane;jane@example.com;false\r\n",
+    )
+    .await;
+
+    let resp = cli
+        .post("/import")
+        .content_type("text/csv; header=absent")
+        .body("1,a,,yes\r\n")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_text("parse request payload error: row 1: Expected input type \"boolean\", found \"yes\". (occurred while parsing \"User\")")
+        .await;
+
+    let resp = cli
+        .post("/stream")
+        .content_type("text/tab-separated-values; header=absent")
+        .body(Body::from_bytes_stream(stream::iter(
+            ["1\ta\t\ttr", "ue\n2\tb\tb@example.com\tfalse"].map(Ok::<_, std::io::Error>),
+        )))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("text/csv; charset=utf-8; header=absent");
+    resp.assert_text("1,a,,true\r\n2,b,b@example.com,false\r\n")
+        .await;
+}
+
+#[tokio::test]
+async fn attachment() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/download", method = "get")]
+        async fn download(&self, name: Query<String>) -> Attachment<Body> {
+            Attachment::from_async_read(&b"hello"[..])
+                .filename(name.0)
+                .content_length(5)
+                .content_type("text/plain")
+        }
+
+        #[oai(path = "/inline", method = "get")]
+        async fn inline(&self) -> Attachment<Vec<u8>> {
+            Attachment::new(b"abc".to_vec()).attachment_type(AttachmentType::Inline)
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let spec = ep.spec();
+    assert!(spec.contains("Content-Disposition"));
+    assert!(spec.contains("Content-Length"));
+    let cli = TestClient::new(ep);
+
+    let resp = cli
+        .get("/download")
+        .query("name", &"report.txt")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("text/plain");
+    resp.assert_header("content-length", "5");
+    resp.assert_header("content-disposition", "attachment; filename=\"report.txt\"");
+    resp.assert_text("hello").await;
+
+    let resp = cli
+        .get("/download")
+        .query("name", &"résumé \"1\".pdf")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_header(
+        "content-disposition",
+        "attachment; filename=\"r_sum_ \\\"1\\\".pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9%20%221%22.pdf",
+    );
+
+    let resp = cli.get("/inline").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_content_type("application/octet-stream");
+    resp.assert_header("content-disposition", "inline");
+    resp.assert_bytes(b"abc").await;
+}
+
+#[tokio::test]
+async fn typed_event_stream() {
+    #[derive(Object)]
+    struct Progress {
+        percent: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/progress", method = "get")]
+        async fn progress(
+            &self,
+        ) -> EventStream<futures_util::stream::BoxStream<'static, SseEvent<Progress>>> {
+            EventStream::new(
+                stream::iter([50, 100].map(|percent| {
+                    SseEvent::new(Progress { percent })
+                        .event_type("progress")
+                        .id(percent.to_string())
+                }))
+                .boxed(),
+            )
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let spec: serde_json::Value = serde_json::from_str(&ep.spec()).unwrap();
+    let schema = &spec["paths"]["/progress"]["get"]["responses"]["200"]["content"]["text/event-stream"]
+        ["schema"];
+    assert_eq!(schema["format"], "event-stream");
+    assert_eq!(schema["items"]["$ref"], "#/components/schemas/Progress");
+    assert_eq!(
+        schema["x-sse-schema"]["properties"]["data"]["$ref"],
+        "#/components/schemas/Progress"
+    );
+    assert_eq!(
+        schema["x-sse-schema"]["properties"]["event"]["type"],
+        "string"
+    );
+    let cli = TestClient::new(ep);
+
+    let resp = cli.get("/progress").send().await;
+    resp.assert_status_is_ok();
+    let events = resp.sse_stream().collect::<Vec<_>>().await;
+    assert_eq!(
+        events,
+        vec![
+            poem::web::sse::Event::message("{\"percent\":50}")
+                .event_type("progress")
+                .id("50"),
+            poem::web::sse::Event::message("{\"percent\":100}")
+                .event_type("progress")
+                .id("100"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn conditional() {
+    use std::time::{Duration, SystemTime};
+
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    struct Api {
+        modified: SystemTime,
+    }
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/etag", method = "get", cache_control = "max-age=60")]
+        async fn etag(&self) -> Conditional<Json<i32>> {
+            Conditional::new(Json(100)).etag("v1")
+        }
+
+        #[oai(path = "/modified", method = "get")]
+        async fn modified(&self) -> Conditional<Json<i32>> {
+            Conditional::new(Json(100)).last_modified(self.modified)
+        }
+
+        #[oai(path = "/etag", method = "put")]
+        async fn put(&self) -> Conditional<Json<i32>> {
+            Conditional::new(Json(100)).etag("v1")
+        }
+    }
+
+    let service = OpenApiService::new(Api { modified }, "test", "1.0");
+    let spec = serde_json::from_str::<serde_json::Value>(&service.spec()).unwrap();
+    let responses = &spec["paths"]["/etag"]["get"]["responses"];
+    let mut headers = responses["200"]["headers"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>();
+    headers.sort();
+    assert_eq!(headers, vec!["Cache-Control", "ETag", "Last-Modified"]);
+    assert_eq!(responses["304"]["description"], "Not modified");
+    assert!(responses["304"]["headers"]["Cache-Control"].is_object());
+
+    let cli = TestClient::new(service);
+
+    let resp = cli.get("/etag").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_header("etag", "\"v1\"");
+    resp.assert_header("cache-control", "max-age=60");
+    resp.assert_json(100).await;
+
+    let resp = cli
+        .get("/etag")
+        .header("if-none-match", "\"v1\"")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::NOT_MODIFIED);
+    resp.assert_header("etag", "\"v1\"");
+    resp.assert_header("cache-control", "max-age=60");
+    resp.assert_text("").await;
+
+    let resp = cli
+        .get("/etag")
+        .header("if-none-match", "\"v2\"")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+
+    // only the safe methods are answered with `304 Not Modified`
+    let resp = cli
+        .put("/etag")
+        .header("if-none-match", "\"v1\"")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+
+    let resp = cli
+        .get("/modified")
+        .header(
+            "if-modified-since",
+            "Tue, 14 Nov 2023 22:13:20 GMT", // the modification time
+        )
+        .send()
+        .await;
+    resp.assert_status(StatusCode::NOT_MODIFIED);
+
+    let resp = cli
+        .get("/modified")
+        .header("if-modified-since", "Tue, 14 Nov 2023 22:13:19 GMT")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_header("last-modified", "Tue, 14 Nov 2023 22:13:20 GMT");
+}
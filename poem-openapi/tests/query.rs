@@ -5,6 +5,7 @@ use poem::{
 };
 use poem_openapi::{
     ApiResponse, OpenApi, OpenApiService,
+    pagination::{CursorPage, Page, PaginationParams},
     param::Query,
     payload::{Json, PlainText},
     registry::MetaApi,
@@ -66,3 +67,67 @@ async fn query_explode_false() {
     resp.assert_status_is_ok();
     resp.assert_json(Value::Null).await;
 }
+
+#[tokio::test]
+async fn pagination() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/items", method = "get")]
+        async fn items(&self, Query(params): Query<PaginationParams>) -> Json<Page<u64>> {
+            let items = (params.offset..100).take(params.limit as usize).collect();
+            Json(Page::new(items, 100))
+        }
+
+        #[oai(path = "/cursor", method = "get")]
+        async fn cursor(&self, Query(params): Query<PaginationParams>) -> Json<CursorPage<u64>> {
+            let start = params.cursor.map(|c| c.parse().unwrap()).unwrap_or(0u64);
+            let end = (start + params.limit as u64).min(5);
+            Json(CursorPage::new(
+                (start..end).collect(),
+                (end < 5).then(|| end.to_string()),
+            ))
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0");
+    let spec: Value = serde_json::from_str(&ep.spec()).unwrap();
+    assert_eq!(
+        spec["components"]["schemas"]["integer(uint64)Page"]["required"],
+        serde_json::json!(["items", "total"])
+    );
+    let limit = &spec["components"]["schemas"]["PaginationParams"]["properties"]["limit"];
+    assert_eq!(limit["minimum"], 1.0);
+    assert_eq!(limit["maximum"], 100.0);
+    assert_eq!(limit["default"], 20);
+
+    let cli = TestClient::new(ep);
+
+    let resp = cli.get("/items").query("offset", &95).send().await;
+    resp.assert_status_is_ok();
+    resp.assert_json(serde_json::json!({ "items": [95, 96, 97, 98, 99], "total": 100 }))
+        .await;
+
+    let resp = cli.get("/items").send().await;
+    resp.assert_status_is_ok();
+    let value = resp.json().await;
+    assert_eq!(value.value().object().get("items").array().len(), 20);
+
+    let resp = cli.get("/items").query("limit", &101).send().await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+
+    let resp = cli
+        .get("/cursor")
+        .query("limit", &3)
+        .query("cursor", &"3")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_json(serde_json::json!({ "items": [3, 4] }))
+        .await;
+
+    let resp = cli.get("/cursor").query("limit", &3).send().await;
+    resp.assert_json(serde_json::json!({ "items": [0, 1, 2], "next_cursor": "3" }))
+        .await;
+}
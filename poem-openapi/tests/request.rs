@@ -1,9 +1,11 @@
+use poem::{http::StatusCode, test::TestClient};
 use poem_openapi::{
-    ApiExtractor, ApiRequest, Object,
-    payload::{Json, PlainText},
-    registry::{MetaMediaType, MetaRequest, MetaSchema, MetaSchemaRef},
+    ApiExtractor, ApiRequest, Object, OpenApi, OpenApiService,
+    payload::{Form, Json, PlainText, Xml},
+    registry::{MetaExample, MetaMediaType, MetaRequest, MetaSchema, MetaSchemaRef},
     types::ParseFromJSON,
 };
+use serde::Deserialize;
 
 #[derive(Debug, Object, Eq, PartialEq)]
 struct CreateUser {
@@ -30,10 +32,14 @@ fn meta() {
                 MetaMediaType {
                     content_type: "application/json; charset=utf-8",
                     schema: MetaSchemaRef::Reference("CreateUser".to_string()),
+                    example: None,
+                    examples: vec![],
                 },
                 MetaMediaType {
                     content_type: "text/plain; charset=utf-8",
                     schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+                    example: None,
+                    examples: vec![],
                 }
             ],
             required: true
@@ -113,6 +119,8 @@ async fn generic() {
             content: vec![MetaMediaType {
                 content_type: "application/json; charset=utf-8",
                 schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+                example: None,
+                examples: vec![],
             },],
             required: true
         }
@@ -144,6 +152,8 @@ async fn item_content_type() {
                 schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format(
                     "integer", "int32"
                 ))),
+                example: None,
+                examples: vec![],
             },],
             required: true
         }
@@ -159,4 +169,133 @@ async fn item_content_type() {
             .unwrap(),
         Req::Create(Json(100))
     );
+
+    // the parameters and the case of the content type are ignored
+    let request = poem::Request::builder()
+        .content_type("Application/JSON+abc; charset=utf-8")
+        .body("200".to_string());
+    let (request, mut body) = request.split();
+    assert_eq!(
+        Req::from_request(&request, &mut body, Default::default())
+            .await
+            .unwrap(),
+        Req::Create(Json(200))
+    );
+}
+
+#[tokio::test]
+async fn multiple_content_types() {
+    #[derive(Debug, Object, Deserialize, Eq, PartialEq)]
+    struct Pet {
+        name: String,
+    }
+
+    #[derive(ApiRequest)]
+    enum CreatePet {
+        Json(Json<Pet>),
+        Xml(Xml<Pet>),
+        Form(Form<Pet>),
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/pets", method = "post")]
+        async fn create(&self, req: CreatePet) -> PlainText<String> {
+            match req {
+                CreatePet::Json(pet) => PlainText(format!("json: {}", pet.name)),
+                CreatePet::Xml(pet) => PlainText(format!("xml: {}", pet.name)),
+                CreatePet::Form(pet) => PlainText(format!("form: {}", pet.name)),
+            }
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0");
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    let mut content_types = spec["paths"]["/pets"]["post"]["requestBody"]["content"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect::<Vec<_>>();
+    content_types.sort();
+    assert_eq!(
+        content_types,
+        vec![
+            "application/json; charset=utf-8",
+            "application/x-www-form-urlencoded",
+            "application/xml; charset=utf-8"
+        ]
+    );
+
+    let cli = TestClient::new(api_service);
+
+    let resp = cli
+        .post("/pets")
+        .content_type("application/json")
+        .body(r#"{"name": "a"}"#)
+        .send()
+        .await;
+    resp.assert_text("json: a").await;
+
+    let resp = cli
+        .post("/pets")
+        .content_type("application/xml")
+        .body("<Pet><name>b</name></Pet>")
+        .send()
+        .await;
+    resp.assert_text("xml: b").await;
+
+    let resp = cli
+        .post("/pets")
+        .content_type("application/x-www-form-urlencoded")
+        .body("name=c")
+        .send()
+        .await;
+    resp.assert_text("form: c").await;
+
+    let resp = cli
+        .post("/pets")
+        .content_type("text/csv")
+        .body("name\nd")
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn examples() {
+    fn create_user() -> CreateUser {
+        CreateUser {
+            user: "sunli".to_string(),
+            password: "123456".to_string(),
+        }
+    }
+
+    fn hello() -> String {
+        "hello".to_string()
+    }
+
+    #[derive(Debug, ApiRequest, Eq, PartialEq)]
+    enum Req {
+        #[oai(example = "create_user")]
+        CreateByJson(Json<CreateUser>),
+        #[oai(examples(hello = "hello"))]
+        CreateByPlainText(PlainText<String>),
+    }
+
+    let meta = Req::request_meta().unwrap();
+    assert_eq!(
+        meta.content[0].example,
+        Some(serde_json::json!({ "user": "sunli", "password": "123456" }))
+    );
+    assert!(meta.content[0].examples.is_empty());
+    assert_eq!(meta.content[1].example, None);
+    assert_eq!(
+        meta.content[1].examples,
+        vec![MetaExample {
+            name: "hello",
+            value: serde_json::json!("hello"),
+        }]
+    );
 }
@@ -8,11 +8,12 @@ use poem::{
     test::TestClient,
 };
 use poem_openapi::{
-    ApiResponse, Object, OpenApi, OpenApiService,
+    ApiResponse, Object, OpenApi, OpenApiService, ResponseHeaders,
     param::Query,
-    payload::{Binary, Json, Payload, PlainText, Yaml},
+    payload::{Binary, Json, Payload, PlainText, WithHeaders, Yaml},
     registry::{
-        MetaApi, MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry,
+        MetaApi, MetaHeader, MetaLink, MetaMediaType, MetaResponse, MetaResponses, MetaSchema,
+        MetaSchemaRef, Registry,
     },
     types::{ToJSON, Type},
 };
@@ -53,7 +54,8 @@ fn meta() {
                     status: Some(200),
                     status_range: None,
                     content: vec![],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "A\nB\n\nC",
@@ -61,9 +63,12 @@ fn meta() {
                     status_range: None,
                     content: vec![MetaMediaType {
                         content_type: "application/json; charset=utf-8",
-                        schema: MetaSchemaRef::Reference("BadRequestResult".to_string())
+                        schema: MetaSchemaRef::Reference("BadRequestResult".to_string()),
+                        example: None,
+                        examples: vec![],
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "yaml response",
@@ -71,9 +76,12 @@ fn meta() {
                     status_range: None,
                     content: vec![MetaMediaType {
                         content_type: "application/yaml; charset=utf-8",
-                        schema: MetaSchemaRef::Reference("BadRequestResult".to_string())
+                        schema: MetaSchemaRef::Reference("BadRequestResult".to_string()),
+                        example: None,
+                        examples: vec![],
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "",
@@ -82,8 +90,11 @@ fn meta() {
                     content: vec![MetaMediaType {
                         content_type: "text/plain; charset=utf-8",
                         schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+                        example: None,
+                        examples: vec![],
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![],
                 }
             ],
         },
@@ -279,9 +290,12 @@ async fn generic() {
                 status_range: None,
                 content: vec![MetaMediaType {
                     content_type: "application/json; charset=utf-8",
-                    schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
+                    schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+                    example: None,
+                    examples: vec![],
                 }],
-                headers: vec![]
+                headers: vec![],
+                links: vec![],
             },],
         },
     );
@@ -316,9 +330,12 @@ async fn item_content_type() {
                         content_type: "application/json2",
                         schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format(
                             "integer", "int32"
-                        )))
+                        ))),
+                        example: None,
+                        examples: vec![],
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![],
                 },
                 MetaResponse {
                     description: "",
@@ -328,9 +345,12 @@ async fn item_content_type() {
                         content_type: "application/json3",
                         schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format(
                             "integer", "int32"
-                        )))
+                        ))),
+                        example: None,
+                        examples: vec![],
                     }],
-                    headers: vec![]
+                    headers: vec![],
+                    links: vec![],
                 }
             ],
         },
@@ -613,3 +633,315 @@ async fn status_range() {
     let resp = cli.get("/?error=server").send().await;
     resp.assert_status(StatusCode::INSUFFICIENT_STORAGE);
 }
+
+#[tokio::test]
+async fn default_response_from_error() {
+    #[derive(Debug, Object)]
+    struct ErrorBody {
+        message: String,
+    }
+
+    impl From<Error> for ErrorBody {
+        fn from(err: Error) -> Self {
+            Self {
+                message: err.to_string(),
+            }
+        }
+    }
+
+    #[derive(Debug, ApiResponse)]
+    #[oai(from_error)]
+    enum ErrorResponse {
+        #[oai(status = 404)]
+        NotFound,
+        #[oai(status = 409)]
+        Conflict(PlainText<String>),
+        #[oai(status_range = "5XX")]
+        ServerError(StatusCode),
+        #[oai(default)]
+        Default(StatusCode, Json<ErrorBody>),
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, code: Query<u16>) -> Result<PlainText<String>, ErrorResponse> {
+            Err(Error::from_string("failed", StatusCode::from_u16(code.0).unwrap()).into())
+        }
+    }
+
+    let service = OpenApiService::new(Api, "test", "1.0");
+    let spec = serde_json::from_str::<Value>(&service.spec()).unwrap();
+    let responses = &spec["paths"]["/"]["get"]["responses"];
+    assert_eq!(
+        responses
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        vec!["200", "404", "409", "5XX", "default"]
+    );
+    assert_eq!(
+        responses["default"]["content"]["application/json; charset=utf-8"]["schema"]["$ref"],
+        "#/components/schemas/ErrorBody"
+    );
+
+    let cli = TestClient::new(service);
+
+    let resp = cli.get("/").query("code", &404).send().await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+
+    let resp = cli.get("/").query("code", &409).send().await;
+    resp.assert_status(StatusCode::CONFLICT);
+    resp.assert_text("failed").await;
+
+    let resp = cli.get("/").query("code", &503).send().await;
+    resp.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+    let resp = cli.get("/").query("code", &403).send().await;
+    resp.assert_status(StatusCode::FORBIDDEN);
+    resp.assert_json(json!({"message": "failed"})).await;
+
+    // the errors of parsing the request use the default response
+    let resp = cli.get("/").query("code", &"abc").send().await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_json(json!({
+        "message": "failed to parse parameter `code`: failed to parse \"integer(uint16)\": invalid digit found in string"
+    }))
+    .await;
+}
+
+#[tokio::test]
+async fn error_code_response() {
+    #[derive(Debug, thiserror::Error, poem::error::ErrorCode)]
+    enum UserError {
+        /// The user does not exist.
+        #[error("user not found")]
+        #[error_code(code = "USER_NOT_FOUND", status = 404)]
+        NotFound,
+        #[error("user is disabled")]
+        #[error_code(status = 403)]
+        Disabled,
+        #[error("user is locked")]
+        #[error_code(status = 403)]
+        Locked,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn get(
+            &self,
+            id: Query<i32>,
+        ) -> Result<PlainText<String>, poem_openapi::ErrorCodeResponse<UserError>> {
+            match id.0 {
+                1 => Ok(PlainText("sunli".to_string())),
+                2 => Err(UserError::Disabled.into()),
+                3 => Err(UserError::Locked.into()),
+                _ => Err(UserError::NotFound.into()),
+            }
+        }
+    }
+
+    let service = OpenApiService::new(Api, "test", "1.0");
+    let spec = serde_json::from_str::<Value>(&service.spec()).unwrap();
+    let responses = &spec["paths"]["/"]["get"]["responses"];
+    assert_eq!(responses["404"]["description"], "The user does not exist.");
+    assert_eq!(
+        responses["404"]["content"]["application/json; charset=utf-8"]["schema"]["properties"]["code"]
+            ["enum"],
+        json!(["USER_NOT_FOUND"])
+    );
+    assert_eq!(responses["403"]["description"], "Forbidden");
+    assert_eq!(
+        responses["403"]["content"]["application/json; charset=utf-8"]["schema"]["properties"]["code"]
+            ["enum"],
+        json!(["DISABLED", "LOCKED"])
+    );
+
+    let cli = TestClient::new(service);
+    let resp = cli.get("/").query("id", &3).send().await;
+    resp.assert_status(StatusCode::FORBIDDEN);
+    resp.assert_json(json!({"code": "LOCKED", "message": "user is locked"}))
+        .await;
+}
+
+#[tokio::test]
+async fn with_headers() {
+    #[derive(ResponseHeaders)]
+    struct RateLimit {
+        /// Remaining requests
+        #[oai(name = "X-RateLimit-Remaining")]
+        remaining: u32,
+        #[oai(deprecated)]
+        retry_after: Option<u64>,
+    }
+
+    #[derive(ApiResponse)]
+    enum CreateResponse {
+        #[oai(status = 201)]
+        Created(Json<i32>),
+        #[oai(status = 409)]
+        Conflict,
+    }
+
+    let headers = vec![
+        MetaHeader {
+            name: "X-RateLimit-Remaining".to_string(),
+            description: Some("Remaining requests".to_string()),
+            required: true,
+            deprecated: false,
+            schema: u32::schema_ref(),
+        },
+        MetaHeader {
+            name: "retry-after".to_string(),
+            description: None,
+            required: false,
+            deprecated: true,
+            schema: u64::schema_ref(),
+        },
+    ];
+    let meta = <WithHeaders<CreateResponse, RateLimit>>::meta();
+    assert_eq!(meta.responses.len(), 2);
+    assert_eq!(meta.responses[0].status, Some(201));
+    assert_eq!(meta.responses[0].headers, headers);
+    assert_eq!(meta.responses[1].status, Some(409));
+    assert_eq!(meta.responses[1].headers, headers);
+
+    let resp = WithHeaders::new(
+        CreateResponse::Created(Json(1)),
+        RateLimit {
+            remaining: 10,
+            retry_after: None,
+        },
+    )
+    .into_response();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(
+        resp.headers().get("x-ratelimit-remaining"),
+        Some(&HeaderValue::from_static("10"))
+    );
+    assert!(!resp.headers().contains_key("retry-after"));
+
+    let resp = WithHeaders::new(
+        CreateResponse::Conflict,
+        RateLimit {
+            remaining: 0,
+            retry_after: Some(30),
+        },
+    )
+    .into_response();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        resp.headers().get("retry-after"),
+        Some(&HeaderValue::from_static("30"))
+    );
+}
+
+#[tokio::test]
+async fn links() {
+    #[derive(ApiResponse)]
+    #[allow(dead_code)]
+    enum CreateUserResponse {
+        #[oai(
+            status = 201,
+            link(
+                name = "GetUser",
+                operation_id = "getUser",
+                parameter(name = "id", value = "$response.body#/id"),
+                description = "Get the created user"
+            ),
+            link(
+                name = "DeleteUser",
+                operation_id = "deleteUser",
+                parameter(name = "id", value = "$response.body#/id"),
+                parameter(name = "version", value = "1"),
+                request_body = "$request.body"
+            )
+        )]
+        Created(Json<i32>),
+    }
+
+    let meta = CreateUserResponse::meta();
+    assert_eq!(
+        meta.responses[0].links,
+        vec![
+            MetaLink {
+                name: "GetUser",
+                operation_id: "getUser",
+                parameters: vec![("id", "$response.body#/id")],
+                request_body: None,
+                description: Some("Get the created user"),
+            },
+            MetaLink {
+                name: "DeleteUser",
+                operation_id: "deleteUser",
+                parameters: vec![("id", "$response.body#/id"), ("version", "1")],
+                request_body: Some("$request.body"),
+                description: None,
+            },
+        ]
+    );
+
+    let value = serde_json::to_value(&meta.responses[0]).unwrap();
+    assert_eq!(
+        value["links"],
+        serde_json::json!({
+            "GetUser": {
+                "operationId": "getUser",
+                "parameters": { "id": "$response.body#/id" },
+                "description": "Get the created user",
+            },
+            "DeleteUser": {
+                "operationId": "deleteUser",
+                "parameters": { "id": "$response.body#/id", "version": "1" },
+                "requestBody": "$request.body",
+            },
+        })
+    );
+}
+
+#[tokio::test]
+async fn examples() {
+    #[derive(Debug, Object)]
+    struct User {
+        id: i64,
+    }
+
+    fn user() -> User {
+        User { id: 1 }
+    }
+
+    fn admin() -> User {
+        User { id: 0 }
+    }
+
+    #[derive(ApiResponse)]
+    #[allow(dead_code)]
+    enum GetUserResponse {
+        #[oai(status = 200, example = "user")]
+        Ok(Json<User>),
+        #[oai(status = 201, examples(user = "user", admin = "admin"))]
+        Created(Json<User>),
+    }
+
+    let meta = GetUserResponse::meta();
+    let value = serde_json::to_value(&meta.responses[0]).unwrap();
+    assert_eq!(
+        value["content"]["application/json; charset=utf-8"]["example"],
+        serde_json::json!({ "id": 1 })
+    );
+    let value = serde_json::to_value(&meta.responses[1]).unwrap();
+    assert_eq!(
+        value["content"]["application/json; charset=utf-8"]["examples"],
+        serde_json::json!({
+            "user": { "value": { "id": 1 } },
+            "admin": { "value": { "id": 0 } },
+        })
+    );
+}
@@ -20,15 +20,21 @@ async fn meta() {
         vec![
             MetaMediaType {
                 content_type: <Json<i32>>::CONTENT_TYPE,
-                schema: <Json<i32>>::schema_ref()
+                schema: <Json<i32>>::schema_ref(),
+                example: None,
+                examples: vec![],
             },
             MetaMediaType {
                 content_type: <PlainText<String>>::CONTENT_TYPE,
-                schema: <PlainText<String>>::schema_ref()
+                schema: <PlainText<String>>::schema_ref(),
+                example: None,
+                examples: vec![],
             },
             MetaMediaType {
                 content_type: <Binary<Vec<u8>>>::CONTENT_TYPE,
-                schema: <Binary<Vec<u8>>>::schema_ref()
+                schema: <Binary<Vec<u8>>>::schema_ref(),
+                example: None,
+                examples: vec![],
             }
         ]
     );
@@ -65,7 +71,8 @@ async fn use_in_api_response() {
                 status: Some(200),
                 status_range: None,
                 content: MyResponseContent::media_types(),
-                headers: vec![]
+                headers: vec![],
+                links: vec![],
             }]
         }
     );
@@ -87,7 +94,9 @@ async fn content_type() {
         MyResp::media_types(),
         vec![MetaMediaType {
             content_type: "application/json2",
-            schema: <Json<i32>>::schema_ref()
+            schema: <Json<i32>>::schema_ref(),
+            example: None,
+            examples: vec![],
         }]
     );
 
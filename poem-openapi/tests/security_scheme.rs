@@ -94,7 +94,8 @@ async fn basic_auth() {
             scheme: Some("basic"),
             bearer_format: None,
             flows: None,
-            openid_connect_url: None
+            openid_connect_url: None,
+            extensions: Default::default(),
         }
     );
     assert!(!MySecurityScheme::has_security_fallback());
@@ -137,7 +138,8 @@ async fn bearer_auth() {
             scheme: Some("bearer"),
             bearer_format: None,
             flows: None,
-            openid_connect_url: None
+            openid_connect_url: None,
+            extensions: Default::default(),
         }
     );
     assert!(!MySecurityScheme::has_security_fallback());
@@ -194,7 +196,8 @@ async fn api_key_auth() {
             scheme: None,
             bearer_format: None,
             flows: None,
-            openid_connect_url: None
+            openid_connect_url: None,
+            extensions: Default::default(),
         }
     );
 
@@ -211,7 +214,8 @@ async fn api_key_auth() {
             scheme: None,
             bearer_format: None,
             flows: None,
-            openid_connect_url: None
+            openid_connect_url: None,
+            extensions: Default::default(),
         }
     );
 
@@ -228,7 +232,8 @@ async fn api_key_auth() {
             scheme: None,
             bearer_format: None,
             flows: None,
-            openid_connect_url: None
+            openid_connect_url: None,
+            extensions: Default::default(),
         }
     );
 
@@ -286,6 +291,117 @@ async fn api_key_auth() {
     }
 }
 
+#[tokio::test]
+async fn api_key_multiple_locations() {
+    struct User(String);
+
+    async fn api_key_checker(_req: &Request, api_key: ApiKey) -> Option<User> {
+        Some(User(api_key.key))
+    }
+
+    /// Api key
+    #[derive(SecurityScheme)]
+    #[oai(
+        ty = "api_key",
+        key_location(key_in = "header", key_name = "X-API-Key"),
+        key_location(key_in = "query", key_name = "api_key"),
+        key_location(key_in = "cookie", key_name = "key", rename = "ApiKeyCookie"),
+        checker = "api_key_checker"
+    )]
+    struct MyApiKey(User);
+
+    let mut registry = Registry::new();
+    MyApiKey::register(&mut registry);
+    assert_eq!(
+        registry.security_schemes.get("MyApiKey_query").unwrap(),
+        &MetaSecurityScheme {
+            ty: "apiKey",
+            description: Some("Api key"),
+            name: Some("api_key"),
+            key_in: Some("query"),
+            scheme: None,
+            bearer_format: None,
+            flows: None,
+            openid_connect_url: None,
+            extensions: Default::default(),
+        }
+    );
+    assert_eq!(
+        registry
+            .security_schemes
+            .keys()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec!["ApiKeyCookie", "MyApiKey_header", "MyApiKey_query"]
+    );
+    assert_eq!(
+        MyApiKey::security_requirements(),
+        vec![
+            vec!["MyApiKey_header"],
+            vec!["MyApiKey_query"],
+            vec!["ApiKeyCookie"]
+        ]
+    );
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/test", method = "get")]
+        async fn test(&self, auth: MyApiKey) -> PlainText<String> {
+            PlainText(auth.0.0)
+        }
+    }
+
+    let service = OpenApiService::new(MyApi, "test", "1.0");
+    let spec = serde_json::from_str::<Value>(&service.spec()).unwrap();
+    assert_eq!(
+        &spec["paths"]["/test"]["get"]["security"],
+        &json!([
+            { "MyApiKey_header": [] },
+            { "MyApiKey_query": [] },
+            { "ApiKeyCookie": [] }
+        ])
+    );
+
+    let cli = TestClient::new(service);
+
+    let resp = cli.get("/test").header("X-API-Key", "abc").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_text("abc").await;
+
+    let resp = cli.get("/test").query("api_key", &"def").send().await;
+    resp.assert_status_is_ok();
+    resp.assert_text("def").await;
+
+    // the header has priority over the query string
+    let resp = cli
+        .get("/test")
+        .header("X-API-Key", "abc")
+        .query("api_key", &"def")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("abc").await;
+
+    #[cfg(feature = "cookie")]
+    {
+        let resp = cli
+            .get("/test")
+            .header(
+                header::COOKIE,
+                Cookie::new_with_str("key", "ghi").to_string(),
+            )
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("ghi").await;
+    }
+
+    let resp = cli.get("/test").send().await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn oauth2_scopes_rename_all() {
     #[derive(OAuthScopes)]
@@ -443,7 +559,8 @@ async fn oauth2_auth() {
                     scopes: vec![]
                 })
             }),
-            openid_connect_url: None
+            openid_connect_url: None,
+            extensions: Default::default(),
         }
     );
     assert!(!MySecurityScheme::has_security_fallback())
@@ -681,3 +798,290 @@ async fn fallback() {
         ])
     )
 }
+
+#[tokio::test]
+async fn combined() {
+    #[derive(SecurityScheme)]
+    #[oai(ty = "basic")]
+    struct MyBasic(Basic);
+
+    #[derive(SecurityScheme)]
+    #[oai(ty = "api_key", key_name = "X-API-Key", key_in = "header")]
+    struct MyApiKey(ApiKey);
+
+    #[derive(SecurityScheme)]
+    #[oai(ty = "bearer")]
+    struct MyBearer(Bearer);
+
+    /// Both the basic authentication and the api key are required.
+    #[derive(SecurityScheme)]
+    struct BasicAndApiKey(MyBasic, MyApiKey);
+
+    #[derive(SecurityScheme)]
+    enum MySecurityScheme {
+        BasicAndApiKey(BasicAndApiKey),
+        Bearer(MyBearer),
+        #[oai(fallback)]
+        NoAuth,
+    }
+
+    assert_eq!(
+        BasicAndApiKey::security_requirements(),
+        vec![vec!["MyBasic", "MyApiKey"]]
+    );
+    assert!(!BasicAndApiKey::has_security_fallback());
+    assert_eq!(
+        MySecurityScheme::security_requirements(),
+        vec![vec!["MyBasic", "MyApiKey"], vec!["MyBearer"], vec![]]
+    );
+
+    let mut registry = Registry::new();
+    MySecurityScheme::register(&mut registry);
+    assert!(registry.security_schemes.contains_key("MyBasic"));
+    assert!(registry.security_schemes.contains_key("MyApiKey"));
+    assert!(registry.security_schemes.contains_key("MyBearer"));
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/test", method = "get")]
+        async fn test(&self, auth: MySecurityScheme) -> PlainText<String> {
+            match auth {
+                MySecurityScheme::BasicAndApiKey(BasicAndApiKey(basic, api_key)) => {
+                    PlainText(format!("{}:{}", basic.0.username, api_key.0.key))
+                }
+                MySecurityScheme::Bearer(bearer) => PlainText(bearer.0.token),
+                MySecurityScheme::NoAuth => PlainText("NoAuth".to_string()),
+            }
+        }
+
+        #[oai(path = "/both", method = "get")]
+        async fn both(&self, basic: MyBasic, bearer: MyBearer) -> PlainText<String> {
+            PlainText(format!("{}:{}", basic.0.username, bearer.0.token))
+        }
+    }
+
+    let service = OpenApiService::new(MyApi, "test", "1.0");
+    let spec = serde_json::from_str::<Value>(&service.spec()).unwrap();
+    let client = TestClient::new(service);
+
+    let resp = client
+        .get("/test")
+        .typed_header(headers::Authorization::basic("sunli", "password"))
+        .header("X-API-Key", "abc")
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("sunli:abc").await;
+
+    let resp = client
+        .get("/test")
+        .typed_header(headers::Authorization::basic("sunli", "password"))
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("NoAuth").await;
+
+    let resp = client
+        .get("/both")
+        .typed_header(headers::Authorization::basic("sunli", "password"))
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+
+    assert_eq!(
+        &spec["paths"]["/test"]["get"]["security"],
+        &json!([
+            { "MyBasic": [], "MyApiKey": [] },
+            { "MyBearer": [] },
+            {}
+        ])
+    );
+    assert_eq!(
+        &spec["paths"]["/both"]["get"]["security"],
+        &json!([{ "MyBasic": [], "MyBearer": [] }])
+    );
+}
+
+#[cfg(feature = "openid-connect")]
+#[tokio::test]
+async fn openid_connect_verify_jwt() {
+    use jsonwebtoken::{Algorithm, EncodingKey, Header};
+    use poem::{
+        Route, Server, get, handler,
+        listener::{Listener, TcpListener},
+        web::Json,
+    };
+    use serde::Deserialize;
+
+    #[handler]
+    fn discovery() -> Json<Value> {
+        Json(json!({
+            "issuer": "http://127.0.0.1:38951",
+            "jwks_uri": "http://127.0.0.1:38951/jwks",
+        }))
+    }
+
+    #[handler]
+    fn jwks() -> Json<Value> {
+        Json(json!({
+            "keys": [
+                { "kty": "oct", "kid": "key1", "alg": "HS256", "k": "c2VjcmV0" },
+                { "kty": "oct", "kid": "key3", "alg": "HS512", "k": "c2VjcmV0" },
+            ],
+        }))
+    }
+
+    let acceptor = TcpListener::bind("127.0.0.1:38951")
+        .into_acceptor()
+        .await
+        .unwrap();
+    tokio::spawn(
+        Server::new_with_acceptor(acceptor).run(
+            Route::new()
+                .at("/.well-known/openid-configuration", get(discovery))
+                .at("/other/.well-known/openid-configuration", get(discovery))
+                .at("/jwks", get(jwks)),
+        ),
+    );
+
+    #[derive(Deserialize)]
+    struct Claims {
+        sub: String,
+    }
+
+    #[derive(SecurityScheme)]
+    #[oai(
+        ty = "openid_connect",
+        openid_connect_url = "http://127.0.0.1:38951/.well-known/openid-configuration",
+        verify_jwt,
+        audience = "my-api",
+        required_scope = "read"
+    )]
+    struct MyOpenIdConnect(Claims);
+
+    struct User(String);
+
+    async fn check_user(_req: &Request, claims: Claims) -> Option<User> {
+        Some(User(claims.sub.to_uppercase()))
+    }
+
+    #[derive(SecurityScheme)]
+    #[oai(
+        ty = "openid_connect",
+        openid_connect_url = "http://127.0.0.1:38951/.well-known/openid-configuration",
+        verify_jwt,
+        checker = "check_user"
+    )]
+    struct MyUser(User);
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/claims", method = "get")]
+        async fn claims(&self, auth: MyOpenIdConnect) -> PlainText<String> {
+            PlainText(auth.0.sub)
+        }
+
+        #[oai(path = "/user", method = "get")]
+        async fn user(&self, auth: MyUser) -> PlainText<String> {
+            PlainText(auth.0.0)
+        }
+    }
+
+    let client = TestClient::new(OpenApiService::new(MyApi, "test", "1.0"));
+    let token = |kid: &str, claims: Value| {
+        let header = Header {
+            kid: Some(kid.to_string()),
+            ..Header::new(Algorithm::HS256)
+        };
+        jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(b"secret")).unwrap()
+    };
+    let claims = json!({
+        "sub": "sunli",
+        "iss": "http://127.0.0.1:38951",
+        "aud": "my-api",
+        "exp": 32503680000u64,
+        "scope": "read write",
+    });
+
+    let resp = client
+        .get("/claims")
+        .typed_header(headers::Authorization::bearer(&token("key1", claims.clone())).unwrap())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("sunli").await;
+
+    let resp = client
+        .get("/user")
+        .typed_header(headers::Authorization::bearer(&token("key1", claims.clone())).unwrap())
+        .send()
+        .await;
+    resp.assert_status_is_ok();
+    resp.assert_text("SUNLI").await;
+
+    for (kid, patch, status) in [
+        (
+            "key1",
+            json!({ "exp": 1000000000u64 }),
+            StatusCode::UNAUTHORIZED,
+        ),
+        (
+            "key1",
+            json!({ "aud": "other-api" }),
+            StatusCode::UNAUTHORIZED,
+        ),
+        (
+            "key1",
+            json!({ "iss": "https://other.example.com" }),
+            StatusCode::UNAUTHORIZED,
+        ),
+        ("key1", json!({ "scope": "write" }), StatusCode::FORBIDDEN),
+        ("key2", json!({}), StatusCode::UNAUTHORIZED),
+        // the algorithm of the token is not the one of the key
+        ("key3", json!({}), StatusCode::UNAUTHORIZED),
+    ] {
+        let mut claims = claims.clone();
+        claims
+            .as_object_mut()
+            .unwrap()
+            .extend(patch.as_object().unwrap().clone());
+        let resp = client
+            .get("/claims")
+            .typed_header(headers::Authorization::bearer(&token(kid, claims)).unwrap())
+            .send()
+            .await;
+        resp.assert_status(status);
+    }
+
+    let resp = client
+        .get("/claims")
+        .typed_header(headers::Authorization::bearer("invalid").unwrap())
+        .send()
+        .await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+
+    // the issuer of the discovery document is not the issuer URL
+    let verifier = poem_openapi::auth::OpenIdConnectVerifier::new(
+        "http://127.0.0.1:38951/other/.well-known/openid-configuration",
+    );
+    let err = verifier
+        .verify::<Value>(&token("key1", claims.clone()))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let verifier = poem_openapi::auth::OpenIdConnectVerifier::new(
+        "http://127.0.0.1:38951/other/.well-known/openid-configuration",
+    )
+    .issuer("http://127.0.0.1:38951");
+    assert_eq!(
+        verifier
+            .verify::<Value>(&token("key1", claims))
+            .await
+            .unwrap()["sub"],
+        "sunli"
+    );
+}
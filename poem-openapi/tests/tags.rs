@@ -1,9 +1,10 @@
 use std::collections::BTreeSet;
 
 use poem_openapi::{
-    Tags,
+    ExternalDocumentObject, OpenApi, OpenApiService, TagObject, Tags,
     registry::{MetaExternalDocument, MetaTag, Registry},
 };
+use serde_json::json;
 
 #[tokio::test]
 async fn rename_all() {
@@ -62,12 +63,14 @@ async fn meta() {
             MetaTag {
                 name: "UserOperations",
                 description: Some("User operations"),
-                external_docs: None
+                external_docs: None,
+                extensions: Default::default(),
             },
             MetaTag {
                 name: "PetOperations",
                 description: Some("Pet operations"),
-                external_docs: None
+                external_docs: None,
+                extensions: Default::default(),
             }
         ]
         .into_iter()
@@ -97,7 +100,89 @@ async fn external_docs() {
                 url: "https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md"
                     .to_string(),
                 description: None
-            })
+            }),
+            extensions: Default::default(),
         }
     );
 }
+
+#[tokio::test]
+async fn service_tags() {
+    #[derive(Tags)]
+    enum MyTags {
+        /// User operations
+        #[oai(extension(name = "x-display-name", value = r#""Users""#))]
+        Users,
+        /// Pet operations
+        Pets,
+        /// Store operations
+        Store,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "get", tag = "MyTags::Users")]
+        async fn users(&self) {}
+
+        #[oai(
+            path = "/pets",
+            method = "get",
+            tag = "MyTags::Pets",
+            operation_id = "pets"
+        )]
+        async fn pets(&self) {}
+
+        #[oai(path = "/store", method = "get", tag = "MyTags::Store")]
+        async fn store(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0")
+        .tag(TagObject::new("Users").description("Manage the users"))
+        .tag(
+            TagObject::new("Admin")
+                .description("Administration")
+                .external_document(
+                    ExternalDocumentObject::new("https://example.com/admin")
+                        .description("Admin guide"),
+                ),
+        )
+        .tag_group("Accounts", ["Users", "Admin"])
+        .tag_group("Shop", ["Pets", "Store"])
+        .operation_external_document("pets", "https://example.com/pets");
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+
+    assert_eq!(
+        spec["tags"],
+        json!([
+            {
+                "name": "Users",
+                "description": "Manage the users",
+                "x-display-name": "Users",
+            },
+            {
+                "name": "Admin",
+                "description": "Administration",
+                "externalDocs": {
+                    "url": "https://example.com/admin",
+                    "description": "Admin guide",
+                },
+            },
+            { "name": "Pets", "description": "Pet operations" },
+            { "name": "Store", "description": "Store operations" },
+        ])
+    );
+    assert_eq!(
+        spec["x-tagGroups"],
+        json!([
+            { "name": "Accounts", "tags": ["Users", "Admin"] },
+            { "name": "Shop", "tags": ["Pets", "Store"] },
+        ])
+    );
+    assert_eq!(
+        spec["paths"]["/pets"]["get"]["externalDocs"],
+        json!({ "url": "https://example.com/pets" })
+    );
+    assert!(spec["paths"]["/users"]["get"]["externalDocs"].is_null());
+}
@@ -844,3 +844,95 @@ fn with_externally_tagged_mapping() {
         }))
     );
 }
+
+#[test]
+fn with_adjacently_tagged() {
+    #[derive(Object, Debug, PartialEq)]
+    struct A {
+        v1: i32,
+    }
+
+    #[derive(Union, Debug, PartialEq)]
+    #[oai(
+        discriminator_name = "type",
+        content = "data",
+        rename_all = "snake_case"
+    )]
+    enum MyObj {
+        A(A),
+        Count(i32),
+        Name(Option<String>),
+    }
+
+    let schema = get_meta::<MyObj>();
+    assert_eq!(
+        schema.discriminator,
+        Some(MetaDiscriminatorObject {
+            property_name: "type",
+            mapping: vec![
+                ("a".to_string(), "#/components/schemas/MyObj_A".to_string()),
+                (
+                    "count".to_string(),
+                    "#/components/schemas/MyObj_Count".to_string()
+                ),
+                (
+                    "name".to_string(),
+                    "#/components/schemas/MyObj_Name".to_string()
+                ),
+            ],
+        })
+    );
+
+    let schema_myobj_count = get_meta_by_name::<MyObj>("MyObj_Count");
+    assert_eq!(
+        schema_myobj_count,
+        MetaSchema {
+            required: vec!["type", "data"],
+            properties: vec![
+                (
+                    "type",
+                    MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        ty: "string",
+                        enum_items: vec![json!("count")],
+                        example: Some(json!("count")),
+                        ..MetaSchema::ANY
+                    })),
+                ),
+                ("data", i32::schema_ref()),
+            ],
+            ..MetaSchema::new("object")
+        }
+    );
+    assert_eq!(
+        get_meta_by_name::<MyObj>("MyObj_Name").required,
+        vec!["type"]
+    );
+
+    assert_eq!(
+        MyObj::parse_from_json(Some(json!({"type": "a", "data": {"v1": 1}}))).unwrap(),
+        MyObj::A(A { v1: 1 })
+    );
+    assert_eq!(
+        MyObj::parse_from_json(Some(json!({"type": "count", "data": 10}))).unwrap(),
+        MyObj::Count(10)
+    );
+    assert_eq!(
+        MyObj::parse_from_json(Some(json!({"type": "name"}))).unwrap(),
+        MyObj::Name(None)
+    );
+    assert!(MyObj::parse_from_json(Some(json!({"type": "count", "data": "x"}))).is_err());
+    assert!(MyObj::parse_from_json(Some(json!({"type": "other", "data": 1}))).is_err());
+
+    assert_eq!(
+        MyObj::A(A { v1: 1 }).to_json(),
+        Some(json!({"type": "a", "data": {"v1": 1}}))
+    );
+    assert_eq!(
+        MyObj::Count(10).to_json(),
+        Some(json!({"type": "count", "data": 10}))
+    );
+    assert_eq!(
+        MyObj::Name(None).to_json(),
+        Some(json!({"type": "name", "data": null}))
+    );
+}
@@ -765,3 +765,93 @@ fn test_custom_validator() {
         "failed to parse \"A\": field `value` verification failed. MyIntValidator"
     );
 }
+
+#[tokio::test]
+async fn test_custom_fn_validator() {
+    fn is_even(value: &i32) -> Result<(), String> {
+        if value % 2 == 0 {
+            Ok(())
+        } else {
+            Err(format!("{value} is not even"))
+        }
+    }
+
+    fn check_range(range: &Range) -> Result<(), &'static str> {
+        if range.start <= range.end {
+            Ok(())
+        } else {
+            Err("`start` must not be greater than `end`")
+        }
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    #[oai(validate = "check_range")]
+    struct Range {
+        start: i32,
+        end: i32,
+        #[oai(validator(custom_fn = "is_even"))]
+        step: Option<i32>,
+    }
+
+    assert_eq!(
+        Range::parse_from_json(Some(json!({"start": 1, "end": 4, "step": 2}))).unwrap(),
+        Range {
+            start: 1,
+            end: 4,
+            step: Some(2)
+        }
+    );
+    assert_eq!(
+        Range::parse_from_json(Some(json!({"start": 1, "end": 4}))).unwrap(),
+        Range {
+            start: 1,
+            end: 4,
+            step: None
+        }
+    );
+    assert_eq!(
+        Range::parse_from_json(Some(json!({"start": 1, "end": 4, "step": 3})))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Range\": field `step` verification failed. 3 is not even"
+    );
+    assert_eq!(
+        Range::parse_from_json(Some(json!({"start": 5, "end": 4})))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Range\": `start` must not be greater than `end`"
+    );
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(validator(custom_fn = "is_even"))] v: Query<i32>) {
+            _ = v;
+        }
+
+        #[oai(path = "/range", method = "post")]
+        async fn range(&self, _range: poem_openapi::payload::Json<Range>) {}
+    }
+
+    let cli = TestClient::new(OpenApiService::new(Api, "test", "1.0"));
+
+    cli.get("/")
+        .query("v", &2)
+        .send()
+        .await
+        .assert_status_is_ok();
+
+    let resp = cli.get("/").query("v", &3).send().await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    resp.assert_text("failed to parse parameter `v`: verification failed. 3 is not even")
+        .await;
+
+    let resp = cli
+        .post("/range")
+        .body_json(&json!({"start": 5, "end": 4}))
+        .send()
+        .await;
+    resp.assert_status(StatusCode::BAD_REQUEST);
+}
@@ -127,6 +127,8 @@ async fn parameters() {
                 deprecated: false,
                 explode: true,
                 style: None,
+                examples: vec![],
+                extensions: Default::default(),
             },
             MetaOperationParam {
                 name: "b".to_string(),
@@ -137,6 +139,8 @@ async fn parameters() {
                 deprecated: false,
                 explode: true,
                 style: None,
+                examples: vec![],
+                extensions: Default::default(),
             }
         ]
     );
@@ -158,6 +162,8 @@ async fn request_body() {
             content: vec![MetaMediaType {
                 content_type: "application/json; charset=utf-8",
                 schema: i32::schema_ref(),
+                example: None,
+                examples: vec![],
             }],
             required: true
         })
@@ -183,8 +189,11 @@ async fn response() {
                 content: vec![MetaMediaType {
                     content_type: "application/json; charset=utf-8",
                     schema: i32::schema_ref(),
+                    example: None,
+                    examples: vec![],
                 }],
-                headers: vec![]
+                headers: vec![],
+                links: vec![],
             }]
         }
     );
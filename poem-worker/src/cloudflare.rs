@@ -3,6 +3,9 @@ use poem::{FromRequest, Request, RequestBody};
 use serde::de::DeserializeOwned;
 use worker::{Cf, TlsClientAuth};
 
+/// The properties of the request provided by Cloudflare
+///
+/// It implements [`poem::FromRequest`], so it can be used as an extractor.
 pub struct CloudflareProperties(Cf);
 
 impl CloudflareProperties {
@@ -3,6 +3,9 @@ use std::sync::Arc;
 use http::StatusCode;
 use poem::{FromRequest, Request, RequestBody};
 
+/// The execution context of the worker
+///
+/// It implements [`poem::FromRequest`], so it can be used as an extractor.
 #[derive(Clone)]
 pub struct Context(Arc<worker::Context>);
 
@@ -8,6 +8,9 @@ use worker::{
     ObjectNamespace, Secret, Var, kv::KvStore,
 };
 
+/// The bindings of the worker
+///
+/// It implements [`poem::FromRequest`], so it can be used as an extractor.
 #[derive(Clone)]
 pub struct Env(pub(crate) Arc<worker::Env>);
 
@@ -1,3 +1,14 @@
+//! Poem for Cloudflare Workers.
+//!
+//! The app is registered with [`Server::run`] in the `start` event of the
+//! worker, and the `fetch` event handler provided by this crate converts the
+//! requests and the responses of the Workers runtime. The handlers can use
+//! the [`Env`], [`Context`] and [`CloudflareProperties`] extractors to access
+//! the bindings, the execution context and the properties of the request.
+
+#![doc(html_favicon_url = "https://raw.githubusercontent.com/poem-web/poem/master/favicon.ico")]
+#![doc(html_logo_url = "https://raw.githubusercontent.com/poem-web/poem/master/logo.png")]
+
 pub(crate) mod body;
 pub(crate) mod req;
 
@@ -11,7 +11,9 @@ use worker::{HttpRequest, Result};
 pub fn build_poem_req(req: HttpRequest) -> Result<poem::Request> {
     let headers = req.headers();
 
-    let local_addr = if let Some(client_ip) = headers.get("cf-connecting-ip") {
+    // the address of the client is sent by Cloudflare in the `cf-connecting-ip`
+    // header
+    let remote_addr = if let Some(client_ip) = headers.get("cf-connecting-ip") {
         let client_ip = client_ip
             .to_str()
             .map_err(|e| worker::Error::RustError(format!("{e}")))?;
@@ -22,13 +24,16 @@ pub fn build_poem_req(req: HttpRequest) -> Result<poem::Request> {
 
         let addr = SocketAddr::new(ip_addr, 0);
 
-        LocalAddr(poem::Addr::SocketAddr(addr))
+        RemoteAddr(poem::Addr::SocketAddr(addr))
     } else {
-        LocalAddr::default()
+        RemoteAddr(poem::Addr::Custom("worker", "".into()))
     };
 
-    let remote_addr = RemoteAddr(poem::Addr::Custom("worker", "".into()));
-    let scheme = Scheme::HTTPS;
+    let local_addr = LocalAddr::default();
+    let scheme = match req.uri().scheme() {
+        Some(scheme) => scheme.clone(),
+        None => Scheme::HTTPS,
+    };
 
     let (parts, body) = req.into_parts();
     let body = crate::body::WorkerBody(body);
@@ -1,12 +1,15 @@
 use async_trait::async_trait;
-use poem::endpoint::Endpoint;
+use http::StatusCode;
+use poem::{IntoResponse, endpoint::Endpoint};
 use tokio::sync::OnceCell;
 
+/// The `fetch` handler of the worker, which calls the app registered with
+/// [`Server::run`].
 #[worker::event(fetch)]
 pub async fn fetch(
     request: worker::Request,
-    _env: worker::Env,
-    _ctx: worker::Context,
+    env: worker::Env,
+    ctx: worker::Context,
 ) -> Result<worker::Response, worker::Error> {
     let cf = request.cf().cloned();
 
@@ -17,12 +20,17 @@ pub async fn fetch(
         poem_req.set_data(cf);
     }
 
-    poem_req.set_data(crate::Env::new(_env));
+    poem_req.set_data(crate::Env::new(env));
+    poem_req.set_data(crate::Context::new(ctx));
 
-    // TODO: handle error
-    let app = SERVER_INSTANCE.get().unwrap();
-
-    let resp = app.get_poem_response(poem_req).await;
+    let resp = match SERVER_INSTANCE.get() {
+        Some(app) => app.get_poem_response(poem_req).await,
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "no app has been registered with `Server::run`",
+        )
+            .into_response(),
+    };
     let worker_resp = crate::req::build_worker_resp(resp)?;
     let resp = worker::Response::try_from(worker_resp)?;
 
@@ -41,6 +49,27 @@ impl<E: Endpoint + Send + Sync + 'static> GetResponseInner for E {
     }
 }
 
+/// Registers the app served by the worker.
+///
+/// It must be called in the `start` event of the worker.
+///
+/// # Example
+///
+/// ```ignore
+/// use poem::{Route, get, handler};
+/// use poem_worker::Server;
+/// use worker::event;
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// #[event(start)]
+/// fn start() {
+///     Server::new().run(Route::new().at("/", get(index)));
+/// }
+/// ```
 pub struct Server {}
 
 type BoxedGetResponseInner = Box<dyn GetResponseInner>;
@@ -54,10 +83,16 @@ impl Default for Server {
 }
 
 impl Server {
+    /// Create a `Server`.
     pub fn new() -> Self {
         Self {}
     }
 
+    /// Registers the app.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an app has already been registered.
     pub fn run(&self, app: impl Endpoint + 'static) {
         SERVER_INSTANCE
             .set(Box::new(app))
@@ -0,0 +1,477 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use headers::{Header, HeaderMapExt};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Uri, Version, header, uri::PathAndQuery};
+use http_body_util::BodyExt;
+use hyper_util::{
+    client::legacy::Client as HyperClient,
+    rt::{TokioExecutor, TokioTimer},
+};
+use serde::Serialize;
+
+#[cfg(feature = "client-rustls")]
+use tokio_rustls::rustls::ClientConfig;
+
+use crate::{
+    Body, Endpoint, Error, IntoResponse, Request, Response, Result,
+    body::BoxBody,
+    connector::{Connector, ConnectorConfig, supports_scheme},
+    error::ClientError,
+};
+
+/// An endpoint that sends the requests to the servers.
+///
+/// The request uri must be an absolute `http` uri, the `https` scheme requires
+/// the `client-rustls` feature.
+///
+/// # Errors
+///
+/// - [`ClientError`]
+#[derive(Clone)]
+pub struct HttpTransport {
+    client: HyperClient<Connector, BoxBody>,
+    connector: ConnectorConfig,
+    http2_only: bool,
+    pool_idle_timeout: Option<Duration>,
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self::build(
+            ConnectorConfig::default(),
+            false,
+            Some(Duration::from_secs(90)),
+        )
+    }
+}
+
+impl HttpTransport {
+    /// Create a `HttpTransport`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn build(
+        connector: ConnectorConfig,
+        http2_only: bool,
+        pool_idle_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            client: HyperClient::builder(TokioExecutor::new())
+                .pool_timer(TokioTimer::new())
+                .pool_idle_timeout(pool_idle_timeout)
+                .http2_only(http2_only)
+                .build(connector.connector(http2_only)),
+            connector,
+            http2_only,
+            pool_idle_timeout,
+        }
+    }
+
+    /// Use HTTP/2 with prior knowledge (h2c) to connect to the servers.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn http2_only(self, value: bool) -> Self {
+        Self::build(self.connector, value, self.pool_idle_timeout)
+    }
+
+    /// Sets how long an idle connection is kept in the pool, `None` means
+    /// forever.
+    ///
+    /// Default is `90 seconds`.
+    #[must_use]
+    pub fn pool_idle_timeout(self, timeout: Option<Duration>) -> Self {
+        Self::build(self.connector, self.http2_only, timeout)
+    }
+
+    /// Sets the TLS configuration used to connect to the `https` servers.
+    ///
+    /// Default trusts the Mozilla root certificates.
+    #[cfg(feature = "client-rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "client-rustls")))]
+    #[must_use]
+    pub fn tls_config(self, config: ClientConfig) -> Self {
+        Self::build(
+            ConnectorConfig {
+                tls_config: Some(Arc::new(config)),
+            },
+            self.http2_only,
+            self.pool_idle_timeout,
+        )
+    }
+}
+
+impl Endpoint for HttpTransport {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        if !req.uri().scheme_str().is_some_and(supports_scheme) {
+            return Err(ClientError::InvalidUri(req.uri().to_string()).into());
+        }
+        req.set_version(if self.http2_only {
+            Version::HTTP_2
+        } else {
+            Version::HTTP_11
+        });
+
+        let mut hyper_req: hyper::Request<BoxBody> = req.into();
+        hyper_req.extensions_mut().clear();
+
+        let resp = self.client.request(hyper_req).await.map_err(|err| {
+            if err.is_connect() {
+                ClientError::Connect(err.to_string())
+            } else {
+                ClientError::Request(err.to_string())
+            }
+        })?;
+        Ok(resp.map(|body| body.map_err(std::io::Error::other)).into())
+    }
+}
+
+/// An HTTP client.
+///
+/// The requests are sent to an endpoint, usually a [`HttpTransport`] with
+/// some middlewares. Cloning a `Client` is cheap, the clones share the same
+/// connection pool.
+///
+/// # Example
+///
+/// ```
+/// use poem::{Request, client::Client, endpoint::make_sync};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// // any endpoint can be used to send the requests, which is useful for testing
+/// let client = Client::new(make_sync(|req| req.uri().to_string()));
+/// let resp = client
+///     .get("http://example.com/users")
+///     .query(&[("id", 1)])
+///     .send()
+///     .await
+///     .unwrap();
+/// assert_eq!(
+///     resp.into_body().into_string().await.unwrap(),
+///     "http://example.com/users?id=1"
+/// );
+/// # });
+/// ```
+pub struct Client<E = HttpTransport> {
+    ep: Arc<E>,
+}
+
+impl<E> Clone for Client<E> {
+    fn clone(&self) -> Self {
+        Self {
+            ep: self.ep.clone(),
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new(HttpTransport::new())
+    }
+}
+
+impl<E: Endpoint> Client<E> {
+    /// Create a `Client` that sends the requests to the specified endpoint.
+    pub fn new(ep: E) -> Self {
+        Self { ep: Arc::new(ep) }
+    }
+
+    /// Sends a request.
+    pub async fn send(&self, req: Request) -> Result<Response> {
+        self.ep.call(req).await.map(IntoResponse::into_response)
+    }
+
+    /// Create a [`ClientRequestBuilder`] with the method and uri.
+    pub fn request(&self, method: Method, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        ClientRequestBuilder {
+            client: self,
+            method,
+            uri: Uri::from_str(uri.as_ref())
+                .map_err(|_| ClientError::InvalidUri(uri.as_ref().to_string())),
+            headers: HeaderMap::new(),
+            body: Ok(Body::empty()),
+        }
+    }
+
+    /// Create a [`ClientRequestBuilder`] with `GET` method.
+    pub fn get(&self, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        self.request(Method::GET, uri)
+    }
+
+    /// Create a [`ClientRequestBuilder`] with `POST` method.
+    pub fn post(&self, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        self.request(Method::POST, uri)
+    }
+
+    /// Create a [`ClientRequestBuilder`] with `PUT` method.
+    pub fn put(&self, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        self.request(Method::PUT, uri)
+    }
+
+    /// Create a [`ClientRequestBuilder`] with `DELETE` method.
+    pub fn delete(&self, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        self.request(Method::DELETE, uri)
+    }
+
+    /// Create a [`ClientRequestBuilder`] with `PATCH` method.
+    pub fn patch(&self, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        self.request(Method::PATCH, uri)
+    }
+
+    /// Create a [`ClientRequestBuilder`] with `HEAD` method.
+    pub fn head(&self, uri: impl AsRef<str>) -> ClientRequestBuilder<'_, E> {
+        self.request(Method::HEAD, uri)
+    }
+}
+
+/// A request builder created by [`Client`].
+pub struct ClientRequestBuilder<'a, E> {
+    client: &'a Client<E>,
+    method: Method,
+    uri: Result<Uri, ClientError>,
+    headers: HeaderMap,
+    body: Result<Body, ClientError>,
+}
+
+impl<E: Endpoint> ClientRequestBuilder<'_, E> {
+    /// Appends a header to this request.
+    #[must_use]
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        let key = key.try_into();
+        let value = value.try_into();
+        if let (Ok(key), Ok(value)) = (key, value) {
+            self.headers.append(key, value);
+        }
+        self
+    }
+
+    /// Inserts a typed header to this request.
+    #[must_use]
+    pub fn typed_header<T: Header>(mut self, header: T) -> Self {
+        self.headers.typed_insert(header);
+        self
+    }
+
+    /// Sets the `Content-Type` header to this request.
+    #[must_use]
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        if let Ok(value) = content_type.try_into() {
+            self.headers.insert(header::CONTENT_TYPE, value);
+        }
+        self
+    }
+
+    /// Appends the query parameters to the uri of this request.
+    #[must_use]
+    pub fn query<T: Serialize + ?Sized>(mut self, params: &T) -> Self {
+        self.uri = self.uri.and_then(|uri| {
+            let params = serde_urlencoded::to_string(params)
+                .map_err(|_| ClientError::InvalidUri(uri.to_string()))?;
+            if params.is_empty() {
+                return Ok(uri);
+            }
+            let path_and_query = match uri.query() {
+                Some(query) => format!("{}?{}&{}", uri.path(), query, params),
+                None => format!("{}?{}", uri.path(), params),
+            };
+            let mut parts = uri.into_parts();
+            parts.path_and_query = Some(
+                PathAndQuery::from_str(&path_and_query)
+                    .map_err(|err| ClientError::InvalidUri(err.to_string()))?,
+            );
+            Uri::from_parts(parts).map_err(|err| ClientError::InvalidUri(err.to_string()))
+        });
+        self
+    }
+
+    /// Sets the body of this request.
+    #[must_use]
+    pub fn body(self, body: impl Into<Body>) -> Self {
+        Self {
+            body: Ok(body.into()),
+            ..self
+        }
+    }
+
+    /// Sets the JSON body of this request with `Content-Type:
+    /// application/json`.
+    ///
+    /// If the value cannot be serialized, [`send`](Self::send) returns
+    /// [`ClientError::InvalidBody`].
+    #[must_use]
+    pub fn json(self, value: &impl Serialize) -> Self {
+        let body = Body::from_json(value).map_err(|err| ClientError::InvalidBody(err.to_string()));
+        Self {
+            body,
+            ..self.content_type("application/json")
+        }
+    }
+
+    /// Sets the form body of this request with `Content-Type:
+    /// application/x-www-form-urlencoded`.
+    ///
+    /// If the value cannot be serialized, [`send`](Self::send) returns
+    /// [`ClientError::InvalidBody`].
+    #[must_use]
+    pub fn form(self, value: &impl Serialize) -> Self {
+        let body = serde_urlencoded::to_string(value)
+            .map(Body::from)
+            .map_err(|err| ClientError::InvalidBody(err.to_string()));
+        Self {
+            body,
+            ..self.content_type("application/x-www-form-urlencoded")
+        }
+    }
+
+    /// Sends this request.
+    pub async fn send(self) -> Result<Response> {
+        let uri = self.uri.map_err(Error::from)?;
+        let body = self.body.map_err(Error::from)?;
+        let mut req = Request::builder().method(self.method).uri(uri).body(body);
+        *req.headers_mut() = self.headers;
+        self.client.send(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{client::start_server, endpoint::make_sync, handler, http::StatusCode, web::Json};
+
+    #[handler(internal)]
+    fn echo(req: &Request, body: String) -> Json<Value> {
+        Json(json!({
+            "method": req.method().as_str(),
+            "uri": req.uri().to_string(),
+            "host": req.header("host"),
+            "content-type": req.header("content-type"),
+            "body": body,
+        }))
+    }
+
+    #[tokio::test]
+    async fn send() {
+        let server = start_server(echo).await;
+        let authority = server.trim_start_matches("http://").to_string();
+        let client = Client::default();
+
+        let resp = client
+            .post(format!("{server}/users?a=1"))
+            .query(&[("b", 2)])
+            .json(&json!({ "name": "sunli" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.into_body().into_json::<Value>().await.unwrap(),
+            json!({
+                "method": "POST",
+                "uri": "/users?a=1&b=2",
+                "host": authority,
+                "content-type": "application/json",
+                "body": r#"{"name":"sunli"}"#,
+            })
+        );
+
+        let resp = client
+            .send(Request::builder().uri_str(format!("{server}/")).finish())
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.into_body().into_json::<Value>().await.unwrap()["method"],
+            "GET"
+        );
+    }
+
+    #[tokio::test]
+    async fn status() {
+        let server = start_server(make_sync(|_| StatusCode::NOT_FOUND)).await;
+        let resp = Client::default().get(server).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn http2_only() {
+        let server = start_server(make_sync(|req| format!("{:?}", req.version()))).await;
+        let client = Client::new(HttpTransport::new().http2_only(true));
+        let resp = client.get(server).send().await.unwrap();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "HTTP/2.0");
+    }
+
+    #[cfg(all(feature = "client-rustls", feature = "rustls"))]
+    #[tokio::test]
+    async fn https() {
+        let (server, tls_config) = crate::connector::start_tls_server(make_sync(|req| {
+            format!("{} {}", req.scheme(), req.uri())
+        }))
+        .await;
+        let client = Client::new(HttpTransport::new().tls_config(tls_config));
+        let resp = client.get(format!("{server}/users")).send().await.unwrap();
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "https /users"
+        );
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let client = Client::default();
+
+        let err = client.get("/users").send().await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ClientError>(),
+            Some(&ClientError::InvalidUri("/users".to_string()))
+        );
+
+        let err = client.get("http://a b").send().await.unwrap_err();
+        assert!(err.is::<ClientError>());
+
+        let err = client
+            .post("http://localhost/users")
+            .json(&std::collections::HashMap::from([((1, 2), 3)]))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClientError>(),
+            Some(ClientError::InvalidBody(_))
+        ));
+
+        let err = client
+            .post("http://localhost/users")
+            .form(&1)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClientError>(),
+            Some(ClientError::InvalidBody(_))
+        ));
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let err = client
+            .get(format!("http://{addr}"))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClientError>(),
+            Some(ClientError::Connect(_))
+        ));
+        assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
+    }
+}
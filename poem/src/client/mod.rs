@@ -0,0 +1,66 @@
+//! HTTP client.
+//!
+//! [`HttpTransport`] is an [`Endpoint`](crate::Endpoint) that sends the
+//! requests to the servers, so the middlewares can be applied to it with
+//! [`EndpointExt::with`](crate::EndpointExt::with) like to any other endpoint,
+//! and [`Client`] provides the methods to build and send the requests.
+//!
+//! The requests, responses and errors are the same types used by the server,
+//! which makes it easy to forward them in gateways.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use poem::{
+//!     EndpointExt,
+//!     client::{Client, HttpTransport, Retry, Timeout},
+//!     middleware::SetHeader,
+//! };
+//!
+//! # async fn run() -> poem::Result<()> {
+//! let client = Client::new(
+//!     HttpTransport::new()
+//!         .with(Timeout::new(Duration::from_secs(5)))
+//!         .with(Retry::new().max_retries(3))
+//!         .with(SetHeader::new().overriding("user-agent", "my-service")),
+//! );
+//!
+//! let resp = client
+//!     .get("http://127.0.0.1:3000/users")
+//!     .query(&[("page", 1)])
+//!     .send()
+//!     .await?;
+//! let users: serde_json::Value = resp.into_body().into_json().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod http;
+#[cfg(feature = "opentelemetry")]
+mod propagation;
+mod retry;
+mod timeout;
+
+pub use http::{Client, ClientRequestBuilder, HttpTransport};
+#[cfg(feature = "opentelemetry")]
+pub use propagation::{OpenTelemetryPropagation, OpenTelemetryPropagationEndpoint};
+pub use retry::{Retry, RetryEndpoint};
+pub use timeout::{Timeout, TimeoutEndpoint};
+
+#[cfg(test)]
+async fn start_server(ep: impl crate::Endpoint + 'static) -> String {
+    use crate::{
+        Server,
+        listener::{Acceptor, Listener, TcpListener},
+    };
+
+    let acceptor = TcpListener::bind("127.0.0.1:0")
+        .into_acceptor()
+        .await
+        .unwrap();
+    let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+    tokio::spawn(Server::new_with_acceptor(acceptor).run(ep));
+    format!("http://{addr}")
+}
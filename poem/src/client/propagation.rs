@@ -0,0 +1,47 @@
+use libopentelemetry::{Context, global};
+use opentelemetry_http::HeaderInjector;
+
+use crate::{Endpoint, Middleware, Request, Result};
+
+/// Middleware to propagate the current OpenTelemetry context to the servers.
+///
+/// The context is injected into the request headers with the global text map
+/// propagator, so the spans of the servers become the children of the
+/// current span, e.g. the span created by the
+/// [`OpenTelemetryTracing`](crate::middleware::OpenTelemetryTracing)
+/// middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+#[derive(Default)]
+pub struct OpenTelemetryPropagation;
+
+impl OpenTelemetryPropagation {
+    /// Create `OpenTelemetryPropagation` middleware.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for OpenTelemetryPropagation {
+    type Output = OpenTelemetryPropagationEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        OpenTelemetryPropagationEndpoint { inner: ep }
+    }
+}
+
+/// Endpoint for the `OpenTelemetryPropagation` middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+pub struct OpenTelemetryPropagationEndpoint<E> {
+    inner: E,
+}
+
+impl<E: Endpoint> Endpoint for OpenTelemetryPropagationEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&Context::current(), &mut HeaderInjector(req.headers_mut()))
+        });
+        self.inner.call(req).await
+    }
+}
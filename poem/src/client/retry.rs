@@ -0,0 +1,269 @@
+use std::{sync::Arc, time::Duration};
+
+use http::{Method, StatusCode};
+
+use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result, error::ClientError};
+
+/// Middleware to retry the failed requests.
+///
+/// A request is retried when the [`ClientError`] other than
+/// [`ClientError::InvalidUri`] is returned, or the response status is one of
+/// the [`Retry::statuses`], waiting an exponential backoff between the
+/// attempts.
+///
+/// The request body is buffered in memory to be sent again, and by default
+/// only the requests with idempotent methods are retried.
+pub struct Retry {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    statuses: Arc<[StatusCode]>,
+    non_idempotent: bool,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            statuses: Arc::new([
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ]),
+            non_idempotent: false,
+        }
+    }
+}
+
+impl Retry {
+    /// Create `Retry` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of retries.
+    ///
+    /// Default is `3`.
+    #[must_use]
+    pub fn max_retries(self, max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            ..self
+        }
+    }
+
+    /// Sets the backoff before the first retry, which is doubled on each
+    /// retry up to `max`.
+    ///
+    /// Default is `100ms` and `5s`.
+    #[must_use]
+    pub fn backoff(self, initial: Duration, max: Duration) -> Self {
+        Self {
+            initial_backoff: initial,
+            max_backoff: max,
+            ..self
+        }
+    }
+
+    /// Sets the response statuses that should be retried.
+    ///
+    /// Default is `502`, `503` and `504`.
+    #[must_use]
+    pub fn statuses(self, statuses: impl IntoIterator<Item = StatusCode>) -> Self {
+        Self {
+            statuses: statuses.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Specifies whether the requests with non-idempotent methods (e.g.
+    /// `POST`) should be retried.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn non_idempotent(self, value: bool) -> Self {
+        Self {
+            non_idempotent: value,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Retry {
+    type Output = RetryEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RetryEndpoint {
+            inner: ep,
+            max_retries: self.max_retries,
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            statuses: self.statuses.clone(),
+            non_idempotent: self.non_idempotent,
+        }
+    }
+}
+
+/// Endpoint for the `Retry` middleware.
+pub struct RetryEndpoint<E> {
+    inner: E,
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    statuses: Arc<[StatusCode]>,
+    non_idempotent: bool,
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE | Method::PUT | Method::DELETE
+    )
+}
+
+impl<E: Endpoint> Endpoint for RetryEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        if self.max_retries == 0 || !(self.non_idempotent || is_idempotent(req.method())) {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let body = req.take_body().into_bytes().await?;
+        let mut backoff = self.initial_backoff;
+        let mut retries = 0;
+
+        loop {
+            let mut attempt = Request::builder()
+                .method(req.method().clone())
+                .uri(req.uri().clone())
+                .version(req.version())
+                .body(body.clone());
+            *attempt.headers_mut() = req.headers().clone();
+            *attempt.extensions_mut() = req.extensions().clone();
+
+            let res = self
+                .inner
+                .call(attempt)
+                .await
+                .map(IntoResponse::into_response);
+            let retry = match &res {
+                Ok(resp) => self.statuses.contains(&resp.status()),
+                Err(err) => matches!(
+                    err.downcast_ref::<ClientError>(),
+                    Some(ClientError::Timeout | ClientError::Connect(_) | ClientError::Request(_))
+                ),
+            };
+            if !retry || retries == self.max_retries {
+                return res;
+            }
+
+            retries += 1;
+            tracing::debug!(
+                method = %req.method(),
+                uri = %req.uri(),
+                retries,
+                "retry request"
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{EndpointExt, Error, client::Client, endpoint::make};
+
+    fn flaky(failures: usize) -> (Arc<AtomicUsize>, impl Endpoint<Output = Response>) {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let ep = make({
+            let counter = counter.clone();
+            move |mut req| {
+                let counter = counter.clone();
+                async move {
+                    let body = req.take_body().into_string().await?;
+                    match counter.fetch_add(1, Ordering::SeqCst) {
+                        n if n < failures && n % 2 == 0 => {
+                            Err(ClientError::Connect("refused".to_string()).into())
+                        }
+                        n if n < failures => Ok(StatusCode::SERVICE_UNAVAILABLE.into_response()),
+                        _ => Ok::<_, Error>(body.into_response()),
+                    }
+                }
+            }
+        });
+        (counter, ep)
+    }
+
+    #[tokio::test]
+    async fn retry() {
+        let (counter, ep) = flaky(3);
+        let client = Client::new(ep.with(Retry::new().backoff(Duration::ZERO, Duration::ZERO)));
+
+        let resp = client
+            .put("http://localhost/")
+            .body("hello")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "hello");
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn max_retries() {
+        let (counter, ep) = flaky(10);
+        let client = Client::new(
+            ep.with(
+                Retry::new()
+                    .max_retries(2)
+                    .backoff(Duration::ZERO, Duration::ZERO),
+            ),
+        );
+
+        let err = client.get("http://localhost/").send().await.unwrap_err();
+        assert!(err.is::<ClientError>());
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn non_idempotent() {
+        let (counter, ep) = flaky(1);
+        let client = Client::new(ep.with(Retry::new().backoff(Duration::ZERO, Duration::ZERO)));
+        client.post("http://localhost/").send().await.unwrap_err();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        let (counter, ep) = flaky(1);
+        let client = Client::new(
+            ep.with(
+                Retry::new()
+                    .non_idempotent(true)
+                    .backoff(Duration::ZERO, Duration::ZERO),
+            ),
+        );
+        client.post("http://localhost/").send().await.unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn statuses() {
+        let (counter, ep) = flaky(2);
+        let client = Client::new(
+            ep.with(
+                Retry::new()
+                    .statuses([])
+                    .backoff(Duration::ZERO, Duration::ZERO),
+            ),
+        );
+
+        let resp = client.get("http://localhost/").send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+}
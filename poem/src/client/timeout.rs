@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result, error::ClientError};
+
+/// Middleware to limit the time to receive the response headers.
+///
+/// The body of the response is not covered by the timeout.
+///
+/// # Errors
+///
+/// - [`ClientError::Timeout`]
+pub struct Timeout {
+    timeout: Duration,
+}
+
+impl Timeout {
+    /// Create `Timeout` middleware with the duration.
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Timeout {
+    type Output = TimeoutEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TimeoutEndpoint {
+            inner: ep,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Endpoint for the `Timeout` middleware.
+pub struct TimeoutEndpoint<E> {
+    inner: E,
+    timeout: Duration,
+}
+
+impl<E: Endpoint> Endpoint for TimeoutEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        match tokio::time::timeout(self.timeout, self.inner.call(req)).await {
+            Ok(res) => res.map(IntoResponse::into_response),
+            Err(_) => Err(ClientError::Timeout.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EndpointExt, client::Client, endpoint::make, http::StatusCode};
+
+    #[tokio::test]
+    async fn timeout() {
+        let client = Client::new(
+            make(|req| async move {
+                if req.uri().path() == "/slow" {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+                "ok"
+            })
+            .with(Timeout::new(Duration::from_millis(50))),
+        );
+
+        let resp = client.get("http://localhost/").send().await.unwrap();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "ok");
+
+        let err = client
+            .get("http://localhost/slow")
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ClientError>(),
+            Some(&ClientError::Timeout)
+        );
+        assert_eq!(err.status(), StatusCode::GATEWAY_TIMEOUT);
+    }
+}
@@ -0,0 +1,125 @@
+//! The connector shared by the HTTP client and the reverse proxy.
+
+#[cfg(feature = "client-rustls")]
+use std::sync::Arc;
+
+use hyper_util::client::legacy::connect::HttpConnector;
+#[cfg(feature = "client-rustls")]
+use tokio_rustls::rustls::{
+    ClientConfig, DEFAULT_VERSIONS,
+    crypto::{CryptoProvider, aws_lc_rs},
+};
+
+#[cfg(feature = "client-rustls")]
+pub(crate) type Connector = hyper_rustls::HttpsConnector<HttpConnector>;
+#[cfg(not(feature = "client-rustls"))]
+pub(crate) type Connector = HttpConnector;
+
+#[derive(Clone, Default)]
+pub(crate) struct ConnectorConfig {
+    /// The TLS configuration of the `https` connections, the Mozilla root
+    /// certificates are trusted if it is `None`.
+    #[cfg(feature = "client-rustls")]
+    pub(crate) tls_config: Option<Arc<ClientConfig>>,
+}
+
+/// Returns `true` if the connector supports the scheme.
+pub(crate) fn supports_scheme(scheme: &str) -> bool {
+    match scheme {
+        "http" => true,
+        #[cfg(feature = "client-rustls")]
+        "https" => true,
+        _ => false,
+    }
+}
+
+impl ConnectorConfig {
+    /// Creates a connector, `http2_only` selects the protocol negotiated
+    /// with ALPN in the `https` connections.
+    pub(crate) fn connector(&self, http2_only: bool) -> Connector {
+        #[cfg(feature = "client-rustls")]
+        {
+            use hyper_rustls::HttpsConnectorBuilder;
+
+            let mut http = HttpConnector::new();
+            http.enforce_http(false);
+            let tls_config = match &self.tls_config {
+                Some(tls_config) => ClientConfig::clone(tls_config),
+                None => default_tls_config(),
+            };
+            let builder = HttpsConnectorBuilder::new()
+                .with_tls_config(tls_config)
+                .https_or_http();
+            if http2_only {
+                builder.enable_http2().wrap_connector(http)
+            } else {
+                builder.enable_http1().wrap_connector(http)
+            }
+        }
+
+        #[cfg(not(feature = "client-rustls"))]
+        {
+            let _ = http2_only;
+            HttpConnector::new()
+        }
+    }
+}
+
+// Uses the aws_lc_rs provider if there is no process-level default provider,
+// like the rustls listener.
+#[cfg(feature = "client-rustls")]
+fn default_tls_config() -> ClientConfig {
+    use hyper_rustls::ConfigBuilderExt;
+
+    if CryptoProvider::get_default().is_none() {
+        let _ = aws_lc_rs::default_provider().install_default();
+    }
+
+    // SAFETY: `CryptoProvider::get_default()` must be non-null at this point
+    let provider = CryptoProvider::get_default().unwrap();
+
+    // SAFETY: process-level default provider is usable with the supplied versions
+    ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(DEFAULT_VERSIONS)
+        .unwrap()
+        .with_webpki_roots()
+        .with_no_client_auth()
+}
+
+/// Starts a server with the certificate of `localhost` signed by
+/// `listener/certs/chain1.pem`, and returns its base uri with a TLS
+/// configuration trusting it.
+#[cfg(all(test, feature = "client-rustls", feature = "rustls"))]
+pub(crate) async fn start_tls_server(ep: impl crate::Endpoint + 'static) -> (String, ClientConfig) {
+    use tokio_rustls::rustls::RootCertStore;
+
+    use crate::{
+        Server,
+        listener::{Acceptor, Listener, RustlsCertificate, RustlsConfig, TcpListener},
+    };
+
+    let acceptor = TcpListener::bind("127.0.0.1:0")
+        .rustls(
+            RustlsConfig::new().fallback(
+                RustlsCertificate::new()
+                    .cert(include_bytes!("listener/certs/cert1.pem").as_ref())
+                    .key(include_bytes!("listener/certs/key1.pem").as_ref()),
+            ),
+        )
+        .into_acceptor()
+        .await
+        .unwrap();
+    let port = acceptor.local_addr()[0].as_socket_addr().unwrap().port();
+    tokio::spawn(Server::new_with_acceptor(acceptor).run(ep));
+
+    let mut roots = RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut include_bytes!("listener/certs/chain1.pem").as_ref()) {
+        roots.add(cert.unwrap()).unwrap();
+    }
+    let config = ClientConfig::builder_with_provider(Arc::new(aws_lc_rs::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    (format!("https://localhost:{port}"), config)
+}
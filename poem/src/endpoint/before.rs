@@ -1,6 +1,6 @@
 use std::future::Future;
 
-use crate::{Endpoint, Request, Result};
+use crate::{Endpoint, Request, Result, endpoint::ShutdownHooks};
 
 /// Endpoint for the [`before`](super::EndpointExt::before) method.
 pub struct Before<E, F> {
@@ -26,4 +26,8 @@ where
     async fn call(&self, req: Request) -> Result<Self::Output> {
         self.inner.call((self.f)(req).await?).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
@@ -3,8 +3,8 @@ use std::{future::Future, marker::PhantomData, sync::Arc};
 use futures_util::{FutureExt, future::BoxFuture};
 
 use super::{
-    After, AndThen, Around, Before, CatchAllError, CatchError, InspectAllError, InspectError, Map,
-    MapToResponse, ToResponse,
+    After, AndThen, Around, Before, CatchAllError, CatchError, ErrorFormatter, FormatError,
+    InspectAllError, InspectError, Map, MapToResponse, OnShutdown, ShutdownHooks, ToResponse,
 };
 use crate::{
     Error, IntoResponse, Middleware, Request, Response, Result,
@@ -54,6 +54,18 @@ pub trait Endpoint: Send + Sync {
                 .unwrap_or_else(|err| err.into_response())
         }
     }
+
+    /// Collects the hooks registered with [`EndpointExt::on_shutdown`] in this
+    /// endpoint, which are called by the [`Server`](crate::Server) running it
+    /// when the graceful shutdown is initiated.
+    ///
+    /// The endpoints which wrap other endpoints should forward this call to
+    /// them. The hooks which are not collected are called by the first server
+    /// that shuts down, which is only a problem if several servers are run in
+    /// the same process. Default does nothing.
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        let _ = hooks;
+    }
 }
 
 struct SyncFnEndpoint<T, F> {
@@ -115,6 +127,13 @@ where
             EitherEndpoint::B(b) => b.call(req).await.map(IntoResponse::into_response),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        match self {
+            EitherEndpoint::A(a) => a.collect_shutdown_hooks(hooks),
+            EitherEndpoint::B(b) => b.collect_shutdown_hooks(hooks),
+        }
+    }
 }
 
 /// Create an endpoint with a function.
@@ -184,6 +203,10 @@ impl<T: Endpoint + ?Sized> Endpoint for &T {
     async fn call(&self, req: Request) -> Result<Self::Output> {
         T::call(self, req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        T::collect_shutdown_hooks(self, hooks);
+    }
 }
 
 impl<T: Endpoint + ?Sized> Endpoint for Box<T> {
@@ -192,6 +215,10 @@ impl<T: Endpoint + ?Sized> Endpoint for Box<T> {
     async fn call(&self, req: Request) -> Result<Self::Output> {
         self.as_ref().call(req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.as_ref().collect_shutdown_hooks(hooks);
+    }
 }
 
 impl<T: Endpoint + ?Sized> Endpoint for Arc<T> {
@@ -200,6 +227,10 @@ impl<T: Endpoint + ?Sized> Endpoint for Arc<T> {
     async fn call(&self, req: Request) -> Result<Self::Output> {
         self.as_ref().call(req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.as_ref().collect_shutdown_hooks(hooks);
+    }
 }
 
 /// A `endpoint` that can be dynamically dispatched.
@@ -209,6 +240,14 @@ pub trait DynEndpoint: Send + Sync {
 
     /// Get the response to the request.
     fn call(&self, req: Request) -> BoxFuture<'_, Result<Self::Output>>;
+
+    /// Collects the hooks registered with [`EndpointExt::on_shutdown`], see
+    /// [`Endpoint::collect_shutdown_hooks`].
+    ///
+    /// Default does nothing.
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        let _ = hooks;
+    }
 }
 
 /// A [`Endpoint`] wrapper used to implement [`DynEndpoint`].
@@ -224,6 +263,11 @@ where
     fn call(&self, req: Request) -> BoxFuture<'_, Result<Self::Output>> {
         self.0.call(req).boxed()
     }
+
+    #[inline]
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.0.collect_shutdown_hooks(hooks);
+    }
 }
 
 impl<T> Endpoint for dyn DynEndpoint<Output = T> + '_
@@ -236,6 +280,11 @@ where
     async fn call(&self, req: Request) -> Result<Self::Output> {
         DynEndpoint::call(self, req).await
     }
+
+    #[inline]
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        DynEndpoint::collect_shutdown_hooks(self, hooks);
+    }
 }
 
 /// An owned dynamically typed `Endpoint` for use in cases where you can’t
@@ -711,6 +760,43 @@ pub trait EndpointExt: IntoEndpoint {
         CatchError::new(self, f)
     }
 
+    /// Convert all errors into responses with the specified
+    /// [`ErrorFormatter`].
+    ///
+    /// Use [`DefaultErrorFormatter`](super::DefaultErrorFormatter) to return
+    /// structured JSON errors to the clients that accept them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     EndpointExt, Route, endpoint::DefaultErrorFormatter, handler, http::StatusCode,
+    ///     test::TestClient,
+    /// };
+    /// use serde_json::json;
+    ///
+    /// #[handler]
+    /// async fn index() {}
+    ///
+    /// let app = Route::new()
+    ///     .at("/index", index)
+    ///     .format_error(DefaultErrorFormatter);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = TestClient::new(app).get("/abc").send().await;
+    /// resp.assert_status(StatusCode::NOT_FOUND);
+    /// resp.assert_json(json!({"code": "NOT_FOUND", "message": "not found"}))
+    ///     .await;
+    /// # })
+    /// ```
+    fn format_error<F>(self, formatter: F) -> FormatError<Self::Endpoint, F>
+    where
+        F: ErrorFormatter,
+        Self: Sized,
+    {
+        FormatError::new(self.into_endpoint(), formatter)
+    }
+
     /// Does something with each error.
     ///
     /// # Example
@@ -757,6 +843,39 @@ pub trait EndpointExt: IntoEndpoint {
     {
         InspectError::new(self, f)
     }
+
+    /// Registers an async callback which is called when the server shuts
+    /// down.
+    ///
+    /// The callback is called when the graceful shutdown is initiated, and
+    /// the server waits for it to complete before stopping. It allows
+    /// stateful endpoints to persist their state or notify the clients.
+    ///
+    /// NOTE: The callback is called once, by the [`Server`](crate::Server)
+    /// running this endpoint if the middlewares wrapping it forward
+    /// [`Endpoint::collect_shutdown_hooks`], otherwise by the first server
+    /// that shuts down.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{EndpointExt, Route, handler};
+    ///
+    /// #[handler]
+    /// fn index() {}
+    ///
+    /// let app = Route::new().at("/", index).on_shutdown(|| async {
+    ///     println!("flush the state");
+    /// });
+    /// ```
+    fn on_shutdown<F, Fut>(self, f: F) -> OnShutdown<Self::Endpoint>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+        Self: Sized,
+    {
+        OnShutdown::new(self.into_endpoint(), super::on_shutdown::boxed_hook(f))
+    }
 }
 
 impl<T: IntoEndpoint> EndpointExt for T {}
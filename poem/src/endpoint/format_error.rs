@@ -0,0 +1,352 @@
+use http::{HeaderMap, Method, StatusCode, Uri, header};
+use mime::Mime;
+use serde::Serialize;
+
+use crate::{
+    Endpoint, Error, IntoResponse, Request, Response, Result,
+    endpoint::ShutdownHooks,
+    error::ErrorCodeInfo,
+    web::{Json, parse_accept},
+};
+
+const X_REQUEST_ID: &str = "x-request-id";
+
+/// Information about the request that caused an error.
+///
+/// It is captured before the request is handled and passed to the
+/// [`ErrorFormatter`].
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    method: Method,
+    uri: Uri,
+    accept: HeaderMap,
+    request_id: Option<String>,
+}
+
+impl ErrorContext {
+    fn new(req: &Request) -> Self {
+        let mut accept = HeaderMap::new();
+        for value in req.headers().get_all(header::ACCEPT) {
+            accept.append(header::ACCEPT, value.clone());
+        }
+
+        #[cfg(feature = "requestid")]
+        let request_id = req
+            .data::<crate::middleware::ReqId>()
+            .map(ToString::to_string);
+        #[cfg(not(feature = "requestid"))]
+        let request_id = None;
+
+        Self {
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            accept,
+            request_id: request_id.or_else(|| req.header(X_REQUEST_ID).map(ToString::to_string)),
+        }
+    }
+
+    /// Returns the method of the request.
+    #[inline]
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the uri of the request.
+    #[inline]
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Returns the media types accepted by the client, sorted by quality.
+    pub fn accept(&self) -> Vec<Mime> {
+        parse_accept(&self.accept)
+    }
+
+    /// Returns the request id.
+    ///
+    /// It is taken from the [`RequestId`](crate::middleware::RequestId)
+    /// middleware if present, otherwise from the `x-request-id` header.
+    #[inline]
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Returns `true` if the client prefers a JSON response.
+    ///
+    /// This is the case when there is no `Accept` header, or the first
+    /// acceptable media type is not `text/*`.
+    pub fn prefers_json(&self) -> bool {
+        match self.accept().first() {
+            Some(mime) => mime.type_() != mime::TEXT,
+            None => true,
+        }
+    }
+}
+
+/// Represents a type that converts errors into responses.
+///
+/// It is implemented for functions with the signature
+/// `Fn(Error, &ErrorContext) -> Response`.
+pub trait ErrorFormatter: Send + Sync {
+    /// Converts the error into a response.
+    fn format(&self, err: Error, ctx: &ErrorContext) -> Response;
+}
+
+impl<F> ErrorFormatter for F
+where
+    F: Fn(Error, &ErrorContext) -> Response + Send + Sync,
+{
+    fn format(&self, err: Error, ctx: &ErrorContext) -> Response {
+        (self)(err, ctx)
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    code: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<&'a str>,
+}
+
+/// The default [`ErrorFormatter`].
+///
+/// If the client prefers JSON, the body of the error response is replaced
+/// with a JSON object containing the error code, the message and the request
+/// id. Like the plain text response, the message does not include the
+/// [context](Error::context) of the error. The code is taken from the [`ErrorCode`](trait@crate::error::ErrorCode)
+/// of the error, or derived from the status code:
+///
+/// ```json
+/// {"code": "NOT_FOUND", "message": "not found", "request_id": "..."}
+/// ```
+///
+/// Otherwise, or if the error was created with [`Error::from_response`] or
+/// has a content type set with [`Error::with_content_type`], the response is
+/// left unchanged. The status code, headers and extensions of the
+/// error response are always preserved.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DefaultErrorFormatter;
+
+impl ErrorFormatter for DefaultErrorFormatter {
+    fn format(&self, err: Error, ctx: &ErrorContext) -> Response {
+        if err.is_from_response()
+            || err.header(header::CONTENT_TYPE).is_some()
+            || !ctx.prefers_json()
+        {
+            return err.into_response();
+        }
+
+        let message = err.message();
+        let mut resp = err.into_response();
+        let body = ErrorBody {
+            code: match resp.data::<ErrorCodeInfo>() {
+                Some(info) => info.code.to_string(),
+                None => status_code_name(resp.status()),
+            },
+            message,
+            request_id: ctx.request_id(),
+        };
+        let (json_parts, json_body) = Json(body).into_response().into_parts();
+        if let Some(content_type) = json_parts.headers.get(header::CONTENT_TYPE) {
+            resp.headers_mut()
+                .insert(header::CONTENT_TYPE, content_type.clone());
+        }
+        resp.set_body(json_body);
+        resp
+    }
+}
+
+/// Returns the name of the status code in `SCREAMING_SNAKE_CASE`, e.g.
+/// `NOT_FOUND`.
+pub(crate) fn status_code_name(status: StatusCode) -> String {
+    match status.canonical_reason() {
+        Some(reason) => reason
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect(),
+        None => status.as_str().to_string(),
+    }
+}
+
+/// Endpoint for the [`format_error`](super::EndpointExt::format_error)
+/// method.
+pub struct FormatError<E, F> {
+    inner: E,
+    formatter: F,
+}
+
+impl<E, F> FormatError<E, F> {
+    #[inline]
+    pub(crate) fn new(inner: E, formatter: F) -> FormatError<E, F> {
+        Self { inner, formatter }
+    }
+}
+
+impl<E, F> Endpoint for FormatError<E, F>
+where
+    E: Endpoint,
+    F: ErrorFormatter,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let ctx = ErrorContext::new(&req);
+        match self.inner.call(req).await {
+            Ok(resp) => Ok(resp.into_response()),
+            Err(err) => Ok(self.formatter.format(err, &ctx)),
+        }
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        EndpointExt, Route, error::NotFoundError, handler, http::HeaderValue, test::TestClient,
+    };
+
+    #[handler(internal)]
+    fn index() -> Result<()> {
+        Err(Error::from_string("bad input", StatusCode::BAD_REQUEST))
+    }
+
+    #[tokio::test]
+    async fn json_format() {
+        let cli = TestClient::new(
+            Route::new()
+                .at("/", index)
+                .at(
+                    "/context",
+                    crate::endpoint::make_sync(|_| {
+                        Err::<(), _>(Error::from(NotFoundError).context("load user 1 from db"))
+                    }),
+                )
+                .format_error(DefaultErrorFormatter),
+        );
+
+        let resp = cli.get("/").header(X_REQUEST_ID, "abc").send().await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_content_type("application/json; charset=utf-8");
+        resp.assert_json(json!({
+            "code": "BAD_REQUEST",
+            "message": "bad input",
+            "request_id": "abc",
+        }))
+        .await;
+
+        let resp = cli
+            .get("/missing")
+            .header(header::ACCEPT, "application/json")
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_json(json!({
+            "code": "NOT_FOUND",
+            "message": "not found",
+        }))
+        .await;
+
+        let resp = cli
+            .get("/context")
+            .header(header::ACCEPT, "application/json")
+            .send()
+            .await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_json(json!({
+            "code": "NOT_FOUND",
+            "message": "not found",
+        }))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn text_format() {
+        let cli = TestClient::new(index.format_error(DefaultErrorFormatter));
+        let resp = cli
+            .get("/")
+            .header(header::ACCEPT, "text/plain, application/json;q=0.5")
+            .send()
+            .await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_text("bad input").await;
+    }
+
+    #[tokio::test]
+    async fn from_response_is_unchanged() {
+        let ep = crate::endpoint::make_sync(|_| {
+            Err::<(), _>(Error::from_response(
+                Response::builder()
+                    .status(StatusCode::CONFLICT)
+                    .body("conflict"),
+            ))
+        });
+        let cli = TestClient::new(ep.format_error(DefaultErrorFormatter));
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::CONFLICT);
+        resp.assert_text("conflict").await;
+    }
+
+    #[tokio::test]
+    async fn custom_formatter() {
+        let cli = TestClient::new(Route::new().format_error(|err: Error, ctx: &ErrorContext| {
+            let mut resp = Response::builder().status(err.status()).body(format!(
+                "{} {}: {}",
+                ctx.method(),
+                ctx.uri(),
+                err
+            ));
+            if err.is::<NotFoundError>() {
+                resp.headers_mut()
+                    .insert("x-not-found", HeaderValue::from_static("1"));
+            }
+            resp
+        }));
+
+        let resp = cli.get("/a").send().await;
+        resp.assert_status(StatusCode::NOT_FOUND);
+        resp.assert_header("x-not-found", "1");
+        resp.assert_text("GET /a: not found").await;
+    }
+
+    #[tokio::test]
+    async fn error_code() {
+        #[derive(Debug, thiserror::Error, crate::error::ErrorCode)]
+        #[error_code(internal)]
+        enum MyError {
+            #[error("slow down")]
+            #[error_code(status = 429)]
+            RateLimited,
+        }
+
+        let ep = crate::endpoint::make_sync(|_| Err::<(), Error>(MyError::RateLimited.into()));
+        let cli = TestClient::new(ep.format_error(DefaultErrorFormatter));
+        let resp = cli.get("/").header(X_REQUEST_ID, "abc").send().await;
+        resp.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        resp.assert_json(json!({
+            "code": "RATE_LIMITED",
+            "message": "slow down",
+            "request_id": "abc",
+        }))
+        .await;
+    }
+
+    #[test]
+    fn test_status_code_name() {
+        assert_eq!(status_code_name(StatusCode::NOT_FOUND), "NOT_FOUND");
+        assert_eq!(status_code_name(StatusCode::IM_A_TEAPOT), "I_M_A_TEAPOT");
+        assert_eq!(status_code_name(StatusCode::from_u16(599).unwrap()), "599");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Endpoint, Error, Request, Result};
+use crate::{Endpoint, Error, Request, Result, endpoint::ShutdownHooks};
 
 /// Endpoint for the [`inspect_all_err`](super::EndpointExt::inspect_all_err)
 /// method.
@@ -30,4 +30,8 @@ where
             }
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
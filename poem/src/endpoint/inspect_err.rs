@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use crate::{Endpoint, Request, Result};
+use crate::{Endpoint, Request, Result, endpoint::ShutdownHooks};
 
 /// Endpoint for the
 /// [`inspect_err`](super::EndpointExt::inspect_err) method.
@@ -39,4 +39,8 @@ where
             Err(err) => Err(err),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
@@ -1,6 +1,6 @@
 use std::future::Future;
 
-use crate::{Endpoint, IntoResponse, Request, Result};
+use crate::{Endpoint, IntoResponse, Request, Result, endpoint::ShutdownHooks};
 
 /// Endpoint for the [`map_ok`](super::EndpointExt::map) method.
 pub struct Map<E, F> {
@@ -29,4 +29,8 @@ where
         let resp = self.inner.call(req).await?;
         Ok((self.f)(resp).await)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
@@ -10,10 +10,12 @@ mod catch_error;
 mod embed;
 #[allow(clippy::module_inception)]
 mod endpoint;
+mod format_error;
 mod inspect_all_err;
 mod inspect_err;
 mod map;
 mod map_to_response;
+mod on_shutdown;
 #[cfg(feature = "prometheus")]
 mod prometheus_exporter;
 #[cfg(feature = "static-files")]
@@ -34,10 +36,16 @@ pub use endpoint::{
     BoxEndpoint, DynEndpoint, EitherEndpoint, Endpoint, EndpointExt, IntoEndpoint, ToDynEndpoint,
     make, make_sync,
 };
+pub use format_error::{DefaultErrorFormatter, ErrorContext, ErrorFormatter, FormatError};
 pub use inspect_all_err::InspectAllError;
 pub use inspect_err::InspectError;
 pub use map::Map;
 pub use map_to_response::MapToResponse;
+#[cfg(all(test, feature = "server"))]
+pub(crate) use on_shutdown::SHUTDOWN_TEST_LOCK;
+pub use on_shutdown::{OnShutdown, ShutdownHooks};
+#[cfg(feature = "server")]
+pub(crate) use on_shutdown::{ShutdownHook, boxed_hook, take_uncollected_hooks};
 #[cfg(feature = "prometheus")]
 pub use prometheus_exporter::PrometheusExporter;
 #[cfg(feature = "static-files")]
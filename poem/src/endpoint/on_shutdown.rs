@@ -0,0 +1,257 @@
+use std::{
+    future::Future,
+    sync::{Arc, Weak},
+};
+
+use futures_util::future::BoxFuture;
+use parking_lot::Mutex;
+
+use crate::{Endpoint, Request, Result};
+
+pub(crate) type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+type HookSlot = Mutex<Option<ShutdownHook>>;
+
+/// The hooks of the live [`OnShutdown`] endpoints.
+///
+/// A server takes the hooks it collects from its endpoint when it starts, the
+/// remaining ones are behind endpoints which don't forward
+/// [`Endpoint::collect_shutdown_hooks`], and are taken by the first server
+/// that shuts down.
+static REGISTERED_HOOKS: Mutex<Vec<Weak<HookSlot>>> = Mutex::new(Vec::new());
+
+pub(crate) fn boxed_hook<F, Fut>(f: F) -> ShutdownHook
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    Box::new(move || Box::pin(f()))
+}
+
+/// Takes the hooks of the live [`OnShutdown`] endpoints which were not
+/// collected by a server.
+#[cfg(feature = "server")]
+pub(crate) fn take_uncollected_hooks() -> Vec<ShutdownHook> {
+    let mut registered = REGISTERED_HOOKS.lock();
+    let hooks = registered
+        .iter()
+        .filter_map(Weak::upgrade)
+        .filter_map(|slot| slot.lock().take())
+        .collect();
+    registered.clear();
+    hooks
+}
+
+/// The hooks collected by [`Endpoint::collect_shutdown_hooks`].
+#[derive(Default)]
+pub struct ShutdownHooks(Vec<ShutdownHook>);
+
+impl ShutdownHooks {
+    #[cfg(feature = "server")]
+    pub(crate) fn into_inner(self) -> Vec<ShutdownHook> {
+        self.0
+    }
+}
+
+/// Endpoint for the [`on_shutdown`](super::EndpointExt::on_shutdown) method.
+pub struct OnShutdown<E> {
+    inner: E,
+    slot: Arc<HookSlot>,
+}
+
+impl<E> OnShutdown<E> {
+    pub(crate) fn new(inner: E, hook: ShutdownHook) -> Self {
+        let slot = Arc::new(Mutex::new(Some(hook)));
+        let mut registered = REGISTERED_HOOKS.lock();
+        registered.retain(|slot| slot.strong_count() > 0);
+        registered.push(Arc::downgrade(&slot));
+        Self { inner, slot }
+    }
+}
+
+impl<E: Endpoint> Endpoint for OnShutdown<E> {
+    type Output = E::Output;
+
+    #[inline]
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        self.inner.call(req).await
+    }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+        hooks.0.extend(self.slot.lock().take());
+    }
+}
+
+/// Serializes the tests which shut down a server, since the hooks which are
+/// not collected can be taken by any of them.
+#[cfg(all(test, feature = "server"))]
+pub(crate) static SHUTDOWN_TEST_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{
+        EndpointExt, IntoResponse, Middleware, Response, Route, Server,
+        endpoint::make_sync,
+        http::HeaderValue,
+        listener::{Acceptor, Listener, TcpListener},
+    };
+
+    fn hook(called: Arc<AtomicUsize>, n: usize) -> impl FnOnce() -> BoxFuture<'static, ()> {
+        move || {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                called.fetch_add(n, Ordering::SeqCst);
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_hooks() {
+        let _guard = SHUTDOWN_TEST_LOCK.lock().await;
+        let called = Arc::new(AtomicUsize::new(0));
+        let ep = Route::new()
+            .at("/a", make_sync(|_| ()).on_shutdown(hook(called.clone(), 1)))
+            .nest(
+                "/b",
+                make_sync(|_| ()).on_shutdown(hook(called.clone(), 10)),
+            )
+            .on_shutdown(hook(called.clone(), 100));
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(
+            Server::new(TcpListener::bind("127.0.0.1:0"))
+                .on_shutdown(hook(called.clone(), 1000))
+                .run_with_graceful_shutdown(
+                    ep,
+                    async move {
+                        _ = rx.await;
+                    },
+                    None,
+                ),
+        );
+
+        tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        assert_eq!(called.load(Ordering::SeqCst), 1111);
+    }
+
+    #[tokio::test]
+    async fn shutdown_hooks_per_server() {
+        let _guard = SHUTDOWN_TEST_LOCK.lock().await;
+        let called_a = Arc::new(AtomicUsize::new(0));
+        let called_b = Arc::new(AtomicUsize::new(0));
+        let ep_a = make_sync(|_| ()).on_shutdown(hook(called_a.clone(), 1));
+        let ep_b = make_sync(|_| ()).on_shutdown(hook(called_b.clone(), 1));
+
+        let (tx_a, rx_a) = tokio::sync::oneshot::channel();
+        let server_a = tokio::spawn(
+            Server::new(TcpListener::bind("127.0.0.1:0")).run_with_graceful_shutdown(
+                ep_a,
+                async move {
+                    _ = rx_a.await;
+                },
+                None,
+            ),
+        );
+        let acceptor_b = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr_b = acceptor_b
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let (tx_b, rx_b) = tokio::sync::oneshot::channel();
+        let server_b = tokio::spawn(
+            Server::new_with_acceptor(acceptor_b).run_with_graceful_shutdown(
+                ep_b,
+                async move {
+                    _ = rx_b.await;
+                },
+                None,
+            ),
+        );
+
+        // wait for the server b to be started, which collects its hooks
+        let mut stream = tokio::net::TcpStream::connect(addr_b).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        stream.read_to_end(&mut Vec::new()).await.unwrap();
+
+        tx_a.send(()).unwrap();
+        server_a.await.unwrap().unwrap();
+        assert_eq!(called_a.load(Ordering::SeqCst), 1);
+        assert_eq!(called_b.load(Ordering::SeqCst), 0);
+
+        tx_b.send(()).unwrap();
+        server_b.await.unwrap().unwrap();
+        assert_eq!(called_b.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_hooks_behind_middleware() {
+        struct AddHeader;
+
+        impl<E: Endpoint> Middleware<E> for AddHeader {
+            type Output = AddHeaderImpl<E>;
+
+            fn transform(&self, ep: E) -> Self::Output {
+                AddHeaderImpl(ep)
+            }
+        }
+
+        // does not forward `collect_shutdown_hooks`
+        struct AddHeaderImpl<E>(E);
+
+        impl<E: Endpoint> Endpoint for AddHeaderImpl<E> {
+            type Output = Response;
+
+            async fn call(&self, req: Request) -> Result<Self::Output> {
+                let mut resp = self.0.call(req).await?.into_response();
+                resp.headers_mut()
+                    .insert("x-a", HeaderValue::from_static("1"));
+                Ok(resp)
+            }
+        }
+
+        let _guard = SHUTDOWN_TEST_LOCK.lock().await;
+        let called = Arc::new(AtomicUsize::new(0));
+        let ep = Route::new().at(
+            "/",
+            make_sync(|_| ())
+                .on_shutdown(hook(called.clone(), 1))
+                .with(AddHeader),
+        );
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(
+            Server::new(TcpListener::bind("127.0.0.1:0")).run_with_graceful_shutdown(
+                ep,
+                async move {
+                    _ = rx.await;
+                },
+                None,
+            ),
+        );
+
+        tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        assert_eq!(called.load(Ordering::SeqCst), 1);
+    }
+}
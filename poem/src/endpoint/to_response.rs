@@ -1,4 +1,4 @@
-use crate::{Endpoint, Request, Response, Result};
+use crate::{Endpoint, Request, Response, Result, endpoint::ShutdownHooks};
 
 /// Endpoint for the [`to_response`](super::EndpointExt::to_response)
 /// method.
@@ -19,4 +19,8 @@ impl<E: Endpoint> Endpoint for ToResponse<E> {
     async fn call(&self, req: Request) -> Result<Self::Output> {
         Ok(self.inner.get_response(req).await)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
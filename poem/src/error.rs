@@ -1,16 +1,18 @@
 //! Some common error types.
 
 use std::{
+    backtrace::{Backtrace, BacktraceStatus},
     convert::Infallible,
     error::Error as StdError,
     fmt::{self, Debug, Display, Formatter},
+    panic::Location,
     string::FromUtf8Error,
 };
 
 use headers::{ContentRange, HeaderMapExt};
-use http::{Extensions, Method};
+use http::{Extensions, HeaderMap, HeaderName, HeaderValue, Method, header};
 
-use crate::{IntoResponse, Response, http::StatusCode};
+use crate::{IntoResponse, Response, http::StatusCode, web::Json};
 
 macro_rules! define_http_error {
     ($($(#[$docs:meta])* ($name:ident, $status:ident);)*) => {
@@ -18,6 +20,7 @@ macro_rules! define_http_error {
         $(#[$docs])*
         #[allow(non_snake_case)]
         #[inline]
+        #[track_caller]
         pub fn $name(err: impl StdError + Send + Sync + 'static) -> Error {
             Error::new(err, StatusCode::$status)
         }
@@ -41,6 +44,96 @@ pub trait ResponseError {
     }
 }
 
+/// Information about an error code.
+///
+/// See [`ErrorCode`](trait@ErrorCode).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ErrorCodeInfo {
+    /// The stable string code, e.g. `USER_NOT_FOUND`.
+    pub code: &'static str,
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The description of the error.
+    pub description: Option<&'static str>,
+}
+
+/// Represents an error type whose values map to stable error codes.
+///
+/// This trait can be derived together with [`ResponseError`]. Each variant
+/// must have an `#[error_code(status = ...)]` attribute, the code defaults to
+/// the variant name in `SCREAMING_SNAKE_CASE` and the description is taken
+/// from the doc comments.
+///
+/// The derived [`ResponseError`] responds with a JSON body containing the
+/// code and the message, and the [`ErrorCodeInfo`] is attached to the
+/// response, so that the
+/// [`DefaultErrorFormatter`](crate::endpoint::DefaultErrorFormatter) uses the
+/// same code.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     Error,
+///     error::{ErrorCode, ErrorCodeInfo},
+///     http::StatusCode,
+/// };
+///
+/// #[derive(Debug, thiserror::Error, ErrorCode)]
+/// enum UserError {
+///     /// The user does not exist.
+///     #[error("user `{0}` not found")]
+///     #[error_code(status = 404)]
+///     NotFound(String),
+///     #[error("user is disabled")]
+///     #[error_code(code = "USER_DISABLED", status = 403)]
+///     Disabled,
+/// }
+///
+/// let err = UserError::NotFound("sunli".to_string());
+/// assert_eq!(
+///     err.error_code(),
+///     ErrorCodeInfo {
+///         code: "NOT_FOUND",
+///         status: StatusCode::NOT_FOUND,
+///         description: Some("The user does not exist."),
+///     }
+/// );
+/// assert_eq!(UserError::error_codes().len(), 2);
+///
+/// let err = Error::from(UserError::Disabled);
+/// assert_eq!(err.status(), StatusCode::FORBIDDEN);
+/// ```
+pub trait ErrorCode {
+    /// Returns the information about the error code of this value.
+    fn error_code(&self) -> ErrorCodeInfo;
+
+    /// Returns all error codes of this type.
+    fn error_codes() -> Vec<ErrorCodeInfo>
+    where
+        Self: Sized;
+
+    /// Creates a response with a JSON body like
+    /// `{"code": "USER_NOT_FOUND", "message": "user not found"}`.
+    fn error_code_response(&self) -> Response
+    where
+        Self: Display,
+    {
+        let info = self.error_code();
+        let mut resp = Json(serde_json::json!({
+            "code": info.code,
+            "message": self.to_string(),
+        }))
+        .into_response();
+        resp.set_status(info.status);
+        resp.set_data(info);
+        resp
+    }
+}
+
+/// Derive macro for the [`ErrorCode`](trait@ErrorCode) trait.
+pub use poem_derive::ErrorCode;
+
 enum ErrorSource {
     BoxedError(Box<dyn StdError + Send + Sync>),
     #[cfg(feature = "anyhow")]
@@ -61,6 +154,92 @@ impl Debug for ErrorSource {
     }
 }
 
+impl ErrorSource {
+    fn as_std_error(&self) -> &(dyn StdError + 'static) {
+        match self {
+            ErrorSource::BoxedError(err) => err.as_ref(),
+            #[cfg(feature = "anyhow")]
+            ErrorSource::Anyhow(err) => err.as_ref(),
+            #[cfg(feature = "eyre06")]
+            ErrorSource::Eyre06(err) => err.as_ref(),
+        }
+    }
+
+    /// Returns the source without the context layers.
+    fn without_context(&self) -> Option<&ErrorSource> {
+        match self {
+            ErrorSource::BoxedError(err) => match err.downcast_ref::<ContextError>() {
+                Some(err) => err.source.as_ref()?.without_context(),
+                None => Some(self),
+            },
+            #[allow(unreachable_patterns)]
+            _ => Some(self),
+        }
+    }
+
+    fn downcast_ref<T: StdError + Send + Sync + 'static>(&self) -> Option<&T> {
+        match self.without_context()? {
+            ErrorSource::BoxedError(err) => err.downcast_ref::<T>(),
+            #[cfg(feature = "anyhow")]
+            ErrorSource::Anyhow(err) => err.downcast_ref::<T>(),
+            #[cfg(feature = "eyre06")]
+            ErrorSource::Eyre06(err) => err.downcast_ref::<T>(),
+        }
+    }
+
+    fn downcast<T: StdError + Send + Sync + 'static>(self) -> Result<T, ErrorSource> {
+        match self {
+            ErrorSource::BoxedError(err) => match err.downcast::<ContextError>() {
+                Ok(err) => {
+                    let ContextError { context, source } = *err;
+                    let rewrap = |source| {
+                        ErrorSource::BoxedError(Box::new(ContextError { context, source }))
+                    };
+                    match source {
+                        Some(source) => source.downcast::<T>().map_err(|err| rewrap(Some(err))),
+                        None => Err(rewrap(None)),
+                    }
+                }
+                Err(err) => err
+                    .downcast::<T>()
+                    .map(|err| *err)
+                    .map_err(ErrorSource::BoxedError),
+            },
+            #[cfg(feature = "anyhow")]
+            ErrorSource::Anyhow(err) => err.downcast::<T>().map_err(ErrorSource::Anyhow),
+            #[cfg(feature = "eyre06")]
+            ErrorSource::Eyre06(err) => err.downcast::<T>().map_err(ErrorSource::Eyre06),
+        }
+    }
+}
+
+/// A context message attached with [`Error::context`].
+struct ContextError {
+    context: String,
+    source: Option<ErrorSource>,
+}
+
+impl Debug for ContextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("context", &self.context)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl Display for ContextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.context)
+    }
+}
+
+impl StdError for ContextError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.as_ref().map(ErrorSource::as_std_error)
+    }
+}
+
 type AsResponseFn = fn(&Error) -> Response;
 type GetStatusFn = fn(&Error) -> StatusCode;
 
@@ -189,14 +368,26 @@ impl AsResponse {
 pub struct Error {
     as_response: AsResponse,
     source: Option<ErrorSource>,
+    headers: Option<Box<HeaderMap>>,
     extensions: Extensions,
     msg: Option<String>,
+    location: &'static Location<'static>,
+    backtrace: Option<Box<Backtrace>>,
+}
+
+fn capture_backtrace() -> Option<Box<Backtrace>> {
+    let backtrace = Backtrace::capture();
+    match backtrace.status() {
+        BacktraceStatus::Captured => Some(Box::new(backtrace)),
+        _ => None,
+    }
 }
 
 impl Debug for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Error")
             .field("source", &self.source)
+            .field("location", &self.location)
             .finish()
     }
 }
@@ -204,7 +395,9 @@ impl Debug for Error {
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         if let Some(msg) = &self.msg {
-            return write!(f, "{msg}");
+            if !self.has_context() {
+                return write!(f, "{msg}");
+            }
         }
 
         match &self.source {
@@ -212,7 +405,7 @@ impl Display for Error {
             #[cfg(feature = "anyhow")]
             Some(ErrorSource::Anyhow(err)) => write!(f, "{err:#}"),
             #[cfg(feature = "eyre06")]
-            Some(ErrorSource::Eyre06(err)) => Display::fmt(err, f),
+            Some(ErrorSource::Eyre06(err)) => write!(f, "{err:#}"),
             None => write!(f, "{}", self.status()),
         }
     }
@@ -220,14 +413,25 @@ impl Display for Error {
 
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
-        match &self.source {
-            Some(ErrorSource::BoxedError(err)) => Some(err.as_ref()),
-            #[cfg(feature = "anyhow")]
-            Some(ErrorSource::Anyhow(err)) => Some(err.as_ref()),
-            #[cfg(feature = "eyre06")]
-            Some(ErrorSource::Eyre06(err)) => Some(err.as_ref()),
-            None => None,
-        }
+        self.source.as_ref().map(ErrorSource::as_std_error)
+    }
+}
+
+/// An iterator over an error and its sources.
+///
+/// See [`Error::chain`].
+#[derive(Clone)]
+pub struct Chain<'a> {
+    next: Option<&'a (dyn StdError + 'static)>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn StdError + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next?;
+        self.next = next.source();
+        Some(next)
     }
 }
 
@@ -238,82 +442,108 @@ impl From<Infallible> for Error {
 }
 
 impl<T: ResponseError + StdError + Send + Sync + 'static> From<T> for Error {
+    #[track_caller]
     fn from(err: T) -> Self {
         Error {
             as_response: AsResponse::from_type::<T>(),
             source: Some(ErrorSource::BoxedError(Box::new(err))),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 }
 
 impl From<Box<dyn StdError + Send + Sync>> for Error {
+    #[track_caller]
     fn from(err: Box<dyn StdError + Send + Sync>) -> Self {
         (StatusCode::INTERNAL_SERVER_ERROR, err).into()
     }
 }
 
 impl From<(StatusCode, Box<dyn StdError + Send + Sync>)> for Error {
+    #[track_caller]
     fn from((status, err): (StatusCode, Box<dyn StdError + Send + Sync>)) -> Self {
         Error {
             as_response: AsResponse::from_status(status),
             source: Some(ErrorSource::BoxedError(err)),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 }
 
 #[cfg(feature = "anyhow")]
 impl From<anyhow::Error> for Error {
+    #[track_caller]
     fn from(err: anyhow::Error) -> Self {
         Error {
             as_response: AsResponse::from_status(StatusCode::INTERNAL_SERVER_ERROR),
             source: Some(ErrorSource::Anyhow(err)),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 }
 
 #[cfg(feature = "eyre06")]
 impl From<eyre06::Error> for Error {
+    #[track_caller]
     fn from(err: eyre06::Error) -> Self {
         Error {
             as_response: AsResponse::from_status(StatusCode::INTERNAL_SERVER_ERROR),
             source: Some(ErrorSource::Eyre06(err)),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 }
 
 #[cfg(feature = "anyhow")]
 impl From<(StatusCode, anyhow::Error)> for Error {
+    #[track_caller]
     fn from((status, err): (StatusCode, anyhow::Error)) -> Self {
         Error {
             as_response: AsResponse::from_status(status),
             source: Some(ErrorSource::Anyhow(err)),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 }
 
 #[cfg(feature = "eyre06")]
 impl From<(StatusCode, eyre06::Report)> for Error {
+    #[track_caller]
     fn from((status, err): (StatusCode, eyre06::Report)) -> Self {
         Error {
             as_response: AsResponse::from_status(status),
             source: Some(ErrorSource::Eyre06(err)),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 }
 
 impl From<StatusCode> for Error {
+    #[track_caller]
     fn from(status: StatusCode) -> Self {
         Error::from_status(status)
     }
@@ -322,26 +552,35 @@ impl From<StatusCode> for Error {
 impl Error {
     /// Create a new error object from any error type with a status code.
     #[inline]
+    #[track_caller]
     pub fn new<T: StdError + Send + Sync + 'static>(err: T, status: StatusCode) -> Self {
         Self {
             as_response: AsResponse::from_status(status),
             source: Some(ErrorSource::BoxedError(Box::new(err))),
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 
     /// Create a new error object from response.
+    #[track_caller]
     pub fn from_response(resp: Response) -> Self {
         Self {
             as_response: AsResponse::Response(Box::new(resp)),
             source: None,
+            headers: None,
             extensions: Extensions::default(),
             msg: None,
+            location: Location::caller(),
+            backtrace: capture_backtrace(),
         }
     }
 
     /// create a new error object from status code.
+    #[track_caller]
     pub fn from_status(status: StatusCode) -> Self {
         #[derive(Debug, thiserror::Error)]
         #[error("{0}")]
@@ -364,6 +603,7 @@ impl Error {
     }
 
     /// Create a new error object from a string with a status code.
+    #[track_caller]
     pub fn from_string(msg: impl Into<String>, status: StatusCode) -> Self {
         #[derive(Debug, thiserror::Error)]
         #[error("{0}")]
@@ -375,89 +615,147 @@ impl Error {
     /// Downcast this error object by reference.
     #[inline]
     pub fn downcast_ref<T: StdError + Send + Sync + 'static>(&self) -> Option<&T> {
-        match &self.source {
-            Some(ErrorSource::BoxedError(err)) => err.downcast_ref::<T>(),
-            #[cfg(feature = "anyhow")]
-            Some(ErrorSource::Anyhow(err)) => err.downcast_ref::<T>(),
-            #[cfg(feature = "eyre06")]
-            Some(ErrorSource::Eyre06(err)) => err.downcast_ref::<T>(),
-            None => None,
-        }
+        self.source.as_ref()?.downcast_ref::<T>()
     }
 
     /// Attempts to downcast the error to a concrete error type.
     #[inline]
-    pub fn downcast<T: StdError + Send + Sync + 'static>(self) -> Result<T, Error> {
-        let as_response = self.as_response;
-        let extensions = self.extensions;
-        let msg = self.msg;
-
-        match self.source {
-            Some(ErrorSource::BoxedError(err)) => match err.downcast::<T>() {
-                Ok(err) => Ok(*err),
-                Err(err) => Err(Error {
-                    as_response,
-                    source: Some(ErrorSource::BoxedError(err)),
-                    extensions,
-                    msg,
-                }),
-            },
-            #[cfg(feature = "anyhow")]
-            Some(ErrorSource::Anyhow(err)) => match err.downcast::<T>() {
-                Ok(err) => Ok(err),
-                Err(err) => Err(Error {
-                    as_response,
-                    source: Some(ErrorSource::Anyhow(err)),
-                    extensions,
-                    msg,
-                }),
-            },
-            #[cfg(feature = "eyre06")]
-            Some(ErrorSource::Eyre06(err)) => match err.downcast::<T>() {
-                Ok(err) => Ok(err),
-                Err(err) => Err(Error {
-                    as_response,
-                    source: Some(ErrorSource::Eyre06(err)),
-                    extensions,
-                    msg,
-                }),
-            },
-            None => Err(Error {
-                as_response,
-                source: None,
-                extensions,
-                msg,
-            }),
+    pub fn downcast<T: StdError + Send + Sync + 'static>(mut self) -> Result<T, Error> {
+        if let Some(source) = self.source.take() {
+            match source.downcast::<T>() {
+                Ok(err) => return Ok(err),
+                Err(source) => self.source = Some(source),
+            }
         }
+        Err(self)
     }
 
     /// Returns `true` if the error type is the same as `T`.
     #[inline]
     pub fn is<T: StdError + Debug + Send + Sync + 'static>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+
+    /// Wraps the error with additional context.
+    ///
+    /// The context becomes the description of the error, and the previous
+    /// description is available as its [`source`](StdError::source). The
+    /// status code, the response and downcasting are not affected, so the
+    /// context is only visible in logs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::{Error as IoError, ErrorKind};
+    ///
+    /// use poem::{Error, http::StatusCode};
+    ///
+    /// let err = Error::new(
+    ///     IoError::new(ErrorKind::NotFound, "file not found"),
+    ///     StatusCode::NOT_FOUND,
+    /// )
+    /// .context("failed to load config");
+    ///
+    /// assert_eq!(err.to_string(), "failed to load config");
+    /// assert!(err.is::<IoError>());
+    /// assert_eq!(
+    ///     err.chain().map(ToString::to_string).collect::<Vec<_>>(),
+    ///     vec!["failed to load config", "file not found"]
+    /// );
+    /// assert_eq!(err.root_cause().to_string(), "file not found");
+    /// ```
+    #[must_use]
+    pub fn context<C: Display>(mut self, context: C) -> Self {
+        let source = match &self.msg {
+            // Keep the custom message in the chain, it is still used for the
+            // response.
+            Some(msg) if !self.has_context() => {
+                Some(ErrorSource::BoxedError(Box::new(ContextError {
+                    context: msg.clone(),
+                    source: self.source.take(),
+                })))
+            }
+            _ => self.source.take(),
+        };
+        self.source = Some(ErrorSource::BoxedError(Box::new(ContextError {
+            context: context.to_string(),
+            source,
+        })));
+        self
+    }
+
+    /// Wraps the error with additional context that is evaluated lazily.
+    ///
+    /// See [`Error::context`].
+    #[must_use]
+    pub fn with_context<C, F>(self, f: F) -> Self
+    where
+        C: Display,
+        F: FnOnce() -> C,
+    {
+        self.context(f())
+    }
+
+    /// Returns `true` if the error was wrapped with [`Error::context`].
+    fn has_context(&self) -> bool {
+        matches!(&self.source, Some(ErrorSource::BoxedError(err)) if err.is::<ContextError>())
+    }
+
+    /// Returns an iterator over this error and its sources, starting with
+    /// this error.
+    ///
+    /// If this error has no message or has a context, it is described by its
+    /// source, so the chain starts with the source instead.
+    pub fn chain(&self) -> Chain<'_> {
         match &self.source {
-            Some(ErrorSource::BoxedError(err)) => err.is::<T>(),
+            Some(source) if self.msg.is_none() || self.has_context() => Chain {
+                next: Some(source.as_std_error()),
+            },
+            _ => Chain { next: Some(self) },
+        }
+    }
+
+    /// Returns the lowest level source of this error.
+    pub fn root_cause(&self) -> &(dyn StdError + 'static) {
+        self.chain().last().expect("chain is not empty")
+    }
+
+    /// Returns the message of the error without the context.
+    pub(crate) fn message(&self) -> String {
+        if let Some(msg) = &self.msg {
+            return msg.clone();
+        }
+
+        match self.source.as_ref().map(ErrorSource::without_context) {
+            Some(Some(ErrorSource::BoxedError(err))) => err.to_string(),
             #[cfg(feature = "anyhow")]
-            Some(ErrorSource::Anyhow(err)) => err.is::<T>(),
+            Some(Some(ErrorSource::Anyhow(err))) => format!("{err:#}"),
             #[cfg(feature = "eyre06")]
-            Some(ErrorSource::Eyre06(err)) => err.is::<T>(),
-            None => false,
+            Some(Some(ErrorSource::Eyre06(err))) => format!("{err:#}"),
+            Some(None) | None => self.status().to_string(),
         }
     }
 
     /// Consumes this to return a response object.
     pub fn into_response(self) -> Response {
         let mut resp = match self.as_response {
-            AsResponse::Status(status) => Response::builder().status(status).body(self.to_string()),
+            AsResponse::Status(status) => Response::builder().status(status).body(self.message()),
             AsResponse::Fn(ref f, _) => f(&self),
             AsResponse::Response(resp) => *resp,
         };
-        *resp.extensions_mut() = self.extensions;
+        if let Some(headers) = self.headers {
+            resp.headers_mut().extend(*headers);
+        }
+        resp.extensions_mut().extend(self.extensions);
         resp
     }
 
     /// Returns whether the error has a source or not.
     pub fn has_source(&self) -> bool {
-        self.source.is_some()
+        self.source
+            .as_ref()
+            .and_then(ErrorSource::without_context)
+            .is_some()
     }
 
     /// Inserts a value to extensions
@@ -480,11 +778,98 @@ impl Error {
         self.extensions.insert(data);
     }
 
+    /// Inserts a value to extensions and returns this error.
+    ///
+    /// See [`Error::set_data`].
+    #[must_use]
+    pub fn with_data(mut self, data: impl Clone + Send + Sync + 'static) -> Self {
+        self.set_data(data);
+        self
+    }
+
     /// Get a reference from extensions
     pub fn data<T: Send + Sync + 'static>(&self) -> Option<&T> {
         self.extensions.get()
     }
 
+    /// Appends a header that is added to the response of this error.
+    ///
+    /// The headers of the error replace the headers with the same name in
+    /// the response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::{Error, http::StatusCode};
+    ///
+    /// let err = Error::from_string("slow down", StatusCode::TOO_MANY_REQUESTS)
+    ///     .with_header("retry-after", "30")
+    ///     .with_data("rate_limited");
+    /// assert_eq!(err.header("retry-after"), Some("30"));
+    ///
+    /// let resp = err.into_response();
+    /// assert_eq!(resp.header("retry-after"), Some("30"));
+    /// assert_eq!(resp.data::<&str>(), Some(&"rate_limited"));
+    /// ```
+    #[must_use]
+    pub fn with_header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        V: TryInto<HeaderValue>,
+    {
+        let key = key.try_into();
+        let value = value.try_into();
+        if let (Ok(key), Ok(value)) = (key, value) {
+            self.headers_mut().append(key, value);
+        }
+        self
+    }
+
+    /// Sets the `Content-Type` header of the response of this error.
+    #[must_use]
+    pub fn with_content_type(mut self, content_type: impl AsRef<str>) -> Self {
+        if let Ok(value) = HeaderValue::from_str(content_type.as_ref()) {
+            self.headers_mut().insert(header::CONTENT_TYPE, value);
+        }
+        self
+    }
+
+    /// Returns the string value of the header that is added to the response
+    /// of this error.
+    ///
+    /// If the header has multiple values, the first value is returned.
+    pub fn header(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.headers
+            .as_ref()?
+            .get(name.as_ref())
+            .and_then(|value| value.to_str().ok())
+    }
+
+    /// Returns a mutable reference to the headers that are added to the
+    /// response of this error.
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.headers.get_or_insert_with(Default::default)
+    }
+
+    /// Returns the location in the source code where the error was created.
+    ///
+    /// For errors converted with the `?` operator, this is the location of
+    /// the `?`.
+    #[inline]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns the backtrace captured when the error was created.
+    ///
+    /// A backtrace is only captured if the `RUST_BACKTRACE` or
+    /// `RUST_LIB_BACKTRACE` environment variable is set, see
+    /// [`Backtrace::capture`].
+    #[inline]
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+
     /// Get the status code of the error
     pub fn status(&self) -> StatusCode {
         match &self.as_response {
@@ -1086,6 +1471,79 @@ impl ResponseError for CorsError {
     }
 }
 
+/// A possible error value occurred in the `ProxyEndpoint`.
+#[cfg(feature = "proxy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum ProxyError {
+    /// No healthy upstream is available
+    #[error("no available upstream")]
+    NoAvailableUpstream,
+
+    /// Invalid upstream uri
+    #[error("invalid upstream uri: {0}")]
+    InvalidUri(String),
+
+    /// The upstream did not respond in time
+    #[error("upstream timeout")]
+    Timeout,
+
+    /// Failed to request the upstream
+    #[error("upstream: {0}")]
+    Upstream(String),
+}
+
+#[cfg(feature = "proxy")]
+impl ResponseError for ProxyError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ProxyError::NoAvailableUpstream => StatusCode::SERVICE_UNAVAILABLE,
+            ProxyError::InvalidUri(_) => StatusCode::BAD_GATEWAY,
+            ProxyError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            ProxyError::Upstream(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+/// A possible error value occurred in the [`Client`](crate::client::Client).
+#[cfg(feature = "client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum ClientError {
+    /// Invalid request uri
+    #[error("invalid uri: {0}")]
+    InvalidUri(String),
+
+    /// Failed to serialize the request body
+    #[error("invalid body: {0}")]
+    InvalidBody(String),
+
+    /// The server did not respond in time
+    #[error("request timeout")]
+    Timeout,
+
+    /// Failed to connect to the server
+    #[error("connect: {0}")]
+    Connect(String),
+
+    /// Failed to send the request or receive the response
+    #[error("request: {0}")]
+    Request(String),
+}
+
+#[cfg(feature = "client")]
+impl ResponseError for ClientError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ClientError::InvalidUri(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ClientError::InvalidBody(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ClientError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            ClientError::Connect(_) => StatusCode::BAD_GATEWAY,
+            ClientError::Request(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
 /// A possible error value occurred when loading i18n resources.
 #[cfg(feature = "i18n")]
 #[derive(Debug, thiserror::Error)]
@@ -1125,6 +1583,46 @@ impl ResponseError for I18NError {
     }
 }
 
+/// A possible error value occurred when enqueuing a background job.
+#[cfg(feature = "server")]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum TaskError {
+    /// No queue is registered for the job type.
+    #[error("no queue is registered for `{0}`")]
+    QueueNotFound(&'static str),
+
+    /// The queue is full.
+    #[error("the queue is full")]
+    QueueFull,
+
+    /// The server is shutting down.
+    #[error("the server is shutting down")]
+    ShuttingDown,
+}
+
+#[cfg(feature = "server")]
+impl ResponseError for TaskError {
+    fn status(&self) -> StatusCode {
+        match self {
+            TaskError::QueueNotFound(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            TaskError::QueueFull | TaskError::ShuttingDown => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// A possible error value occurred when parsing a cron expression.
+#[cfg(feature = "cron")]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum ParseCronError {
+    /// The expression does not contain 5 or 6 fields.
+    #[error("expected 5 or 6 fields, found {0}")]
+    InvalidFieldCount(usize),
+
+    /// Invalid field.
+    #[error("invalid field `{0}`")]
+    InvalidField(String),
+}
+
 /// A possible error value occurred when deal with redis session.
 #[cfg(feature = "redis-session")]
 #[derive(Debug, thiserror::Error)]
@@ -1216,6 +1714,24 @@ mod tests {
         assert_eq!(err.into_response().status(), StatusCode::BAD_GATEWAY);
     }
 
+    #[cfg(feature = "eyre06")]
+    #[tokio::test]
+    async fn test_eyre06_context() {
+        use eyre06::WrapErr;
+
+        let err: Error = Err::<(), _>(IoError::new(ErrorKind::NotFound, "aaa"))
+            .wrap_err("bbb")
+            .unwrap_err()
+            .into();
+        assert!(err.is::<IoError>());
+        assert_eq!(err.to_string(), "bbb: aaa");
+        assert_eq!(err.root_cause().to_string(), "aaa");
+
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "bbb: aaa");
+    }
+
     #[tokio::test]
     async fn test_custom_as_response() {
         #[derive(Debug, thiserror::Error)]
@@ -1243,4 +1759,144 @@ mod tests {
             "my error message"
         );
     }
+
+    #[tokio::test]
+    async fn test_context() {
+        let err = Error::new(
+            IoError::new(ErrorKind::NotFound, "file not found"),
+            StatusCode::NOT_FOUND,
+        )
+        .context("failed to read file")
+        .with_context(|| "failed to load config");
+
+        assert_eq!(err.to_string(), "failed to load config");
+        assert_eq!(
+            err.chain().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "failed to load config",
+                "failed to read file",
+                "file not found"
+            ]
+        );
+        assert_eq!(err.root_cause().to_string(), "file not found");
+        assert!(err.has_source());
+        assert!(err.is::<IoError>());
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+
+        let err = err.downcast::<NotFoundError>().unwrap_err();
+        assert_eq!(err.to_string(), "failed to load config");
+        let io_err = err.downcast::<IoError>().unwrap();
+        assert_eq!(io_err.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_source() {
+        let err = Error::new(
+            IoError::new(ErrorKind::NotFound, "file not found"),
+            StatusCode::NOT_FOUND,
+        );
+        let source = err.source().unwrap();
+        assert!(source.is::<IoError>());
+        assert_eq!(source.to_string(), "file not found");
+        assert_eq!(
+            err.chain().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["file not found"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_context_response() {
+        let err = Error::from(NotFoundError).context("missing user");
+        assert_eq!(err.to_string(), "missing user");
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "not found");
+
+        let err = Error::from_string("bad input", StatusCode::BAD_REQUEST).context("parse body");
+        assert_eq!(
+            err.chain().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["parse body", "bad input"]
+        );
+        assert_eq!(
+            err.into_response().into_body().into_string().await.unwrap(),
+            "bad input"
+        );
+
+        let err = Error::from_status(StatusCode::FORBIDDEN).context("not allowed");
+        assert_eq!(
+            err.chain().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["not allowed", "403 Forbidden"]
+        );
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+
+        let mut err = Error::new(
+            IoError::new(ErrorKind::NotFound, "file not found"),
+            StatusCode::NOT_FOUND,
+        );
+        err.set_error_message("user not found");
+        let err = err.context("load user").context("get profile");
+        assert_eq!(err.to_string(), "get profile");
+        assert_eq!(
+            err.chain().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "get profile",
+                "load user",
+                "user not found",
+                "file not found"
+            ]
+        );
+        assert!(err.is::<IoError>());
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "user not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_parts() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct ErrorCode(&'static str);
+
+        let err = Error::from_response(
+            Response::builder()
+                .status(StatusCode::TOO_MANY_REQUESTS)
+                .header("retry-after", "10")
+                .header("x-a", "1")
+                .body("{}"),
+        )
+        .with_header("retry-after", "30")
+        .with_content_type("application/json")
+        .with_data(ErrorCode("rate_limited"));
+        assert_eq!(err.data::<ErrorCode>(), Some(&ErrorCode("rate_limited")));
+
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            resp.headers()
+                .get_all("retry-after")
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["30"]
+        );
+        assert_eq!(resp.header("x-a"), Some("1"));
+        assert_eq!(resp.content_type(), Some("application/json"));
+        assert_eq!(resp.data::<ErrorCode>(), Some(&ErrorCode("rate_limited")));
+    }
+
+    #[test]
+    fn test_location() {
+        fn not_found() -> Result<()> {
+            Err(NotFoundError)?
+        }
+
+        let line = line!() + 1;
+        let err = Error::from_status(StatusCode::BAD_REQUEST);
+        assert_eq!(err.location().file(), file!());
+        assert_eq!(err.location().line(), line);
+
+        let err = not_found().unwrap_err();
+        assert_eq!(err.location().line(), line - 4);
+    }
 }
@@ -0,0 +1,168 @@
+//! Hooks to replace the sources of time and unique ids.
+//!
+//! Middlewares that depend on the current time or generate unique ids look up
+//! a [`SharedClock`] or a [`SharedIdGenerator`] in the request extensions and
+//! fall back to the system clock and random ids. This allows them to be
+//! driven deterministically in tests, see
+//! [`TestClient::clock`](crate::test::TestClient::clock) and
+//! [`TestClient::id_generator`](crate::test::TestClient::id_generator).
+//!
+//! The following middlewares support these hooks:
+//!
+//! - [`RequestId`](crate::middleware::RequestId) uses the [`IdGenerator`] for
+//!   the request ids.
+//! - [`Tracing`](crate::middleware::Tracing) uses the [`Clock`] to measure the
+//!   duration of the requests.
+//! - [`Csrf`](crate::middleware::Csrf) uses the [`Clock`] for the expiration
+//!   of the tokens.
+//!
+//! Some components are configured with hooks directly instead:
+//!
+//! - [`MemoryStorage::clock`](crate::session::MemoryStorage::clock) sets the
+//!   [`Clock`] for the expiration of the sessions.
+//! - [`ServerSession::id_generator`](crate::session::ServerSession::id_generator)
+//!   sets the [`IdGenerator`] for the session ids, which are never taken
+//!   from the request extensions since they must be unpredictable.
+//!
+//! # Example
+//!
+//! ```
+//! use poem::{
+//!     EndpointExt, Route, handler,
+//!     hooks::{IdGenerator, SharedIdGenerator},
+//!     test::TestClient,
+//!     web::Data,
+//! };
+//!
+//! struct FixedId;
+//!
+//! impl IdGenerator for FixedId {
+//!     fn generate(&self) -> String {
+//!         "fixed".to_string()
+//!     }
+//! }
+//!
+//! #[handler]
+//! fn index(Data(id_generator): Data<&SharedIdGenerator>) -> String {
+//!     id_generator.generate()
+//! }
+//!
+//! let app = Route::new()
+//!     .at("/", index)
+//!     .data(SharedIdGenerator::new(FixedId));
+//! let cli = TestClient::new(app);
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let resp = cli.get("/").send().await;
+//! resp.assert_text("fixed").await;
+//! # });
+//! ```
+
+use std::{
+    sync::Arc,
+    time::{Instant, SystemTime},
+};
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the current monotonic time.
+    fn now(&self) -> Instant;
+
+    /// Returns the current system time.
+    fn system_now(&self) -> SystemTime;
+}
+
+/// The [`Clock`] backed by the operating system.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    #[inline]
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A generator of unique ids.
+pub trait IdGenerator: Send + Sync + 'static {
+    /// Generates a new id.
+    fn generate(&self) -> String;
+}
+
+/// A shared [`Clock`] that can be stored in the request extensions.
+///
+/// It can be extracted in handlers, and is the [`SystemClock`] if no clock
+/// is stored in the request extensions.
+#[derive(Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl SharedClock {
+    /// Create a shared clock.
+    pub fn new(clock: impl Clock) -> Self {
+        Self(Arc::new(clock))
+    }
+
+    /// Returns the clock stored in the request extensions, or the
+    /// [`SystemClock`].
+    pub fn from_request(req: &Request) -> Self {
+        req.data::<SharedClock>().cloned().unwrap_or_default()
+    }
+
+    /// Returns the current monotonic time.
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.0.now()
+    }
+
+    /// Returns the current system time.
+    #[inline]
+    pub fn system_now(&self) -> SystemTime {
+        self.0.system_now()
+    }
+}
+
+impl<'a> FromRequest<'a> for SharedClock {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(SharedClock::from_request(req))
+    }
+}
+
+/// A shared [`IdGenerator`] that can be stored in the request extensions.
+#[derive(Clone)]
+pub struct SharedIdGenerator(Arc<dyn IdGenerator>);
+
+impl SharedIdGenerator {
+    /// Create a shared id generator.
+    pub fn new(generator: impl IdGenerator) -> Self {
+        Self(Arc::new(generator))
+    }
+
+    /// Generates a new id.
+    #[inline]
+    pub fn generate(&self) -> String {
+        self.0.generate()
+    }
+}
+
+/// Generates an id with the generator stored in the request extensions, or
+/// with `default` if there is none.
+#[cfg_attr(not(feature = "requestid"), allow(dead_code))]
+pub(crate) fn generate_id(req: &Request, default: impl FnOnce() -> String) -> String {
+    match req.data::<SharedIdGenerator>() {
+        Some(generator) => generator.generate(),
+        None => default(),
+    }
+}
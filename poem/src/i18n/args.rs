@@ -17,6 +17,20 @@ impl<'a> I18NArgs<'a> {
         self.0.set(key, value);
         self
     }
+
+    /// Returns the value of the argument with the specified key.
+    ///
+    /// This is useful for implementing
+    /// [`I18NBackend`](crate::i18n::I18NBackend).
+    pub fn get(&self, key: &str) -> Option<&FluentValue<'_>> {
+        self.iter()
+            .find_map(|(name, value)| (name == key).then_some(value))
+    }
+
+    /// Returns an iterator over the arguments.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FluentValue<'_>)> {
+        self.0.iter()
+    }
 }
 
 impl<'a, K, V, R> From<HashMap<K, V, R>> for I18NArgs<'a>
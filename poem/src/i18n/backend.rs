@@ -0,0 +1,84 @@
+use unic_langid::LanguageIdentifier;
+
+use crate::{error::I18NError, i18n::I18NArgs};
+
+/// A source of localization messages.
+///
+/// By default the messages are loaded from Fluent resources, implement this
+/// trait to use other formats such as gettext PO files or JSON catalogs with
+/// the same [`Locale`](crate::i18n::Locale) extractor.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use poem::{
+///     error::I18NError,
+///     i18n::{FluentValue, I18NArgs, I18NBackend, I18NResources},
+/// };
+/// use unic_langid::{LanguageIdentifier, langid};
+///
+/// struct MapBackend(HashMap<LanguageIdentifier, HashMap<String, String>>);
+///
+/// impl I18NBackend for MapBackend {
+///     fn languages(&self) -> Vec<LanguageIdentifier> {
+///         self.0.keys().cloned().collect()
+///     }
+///
+///     fn format(
+///         &self,
+///         language: &LanguageIdentifier,
+///         id: &str,
+///         args: &I18NArgs<'_>,
+///     ) -> Option<Result<String, I18NError>> {
+///         let mut text = self.0.get(language)?.get(id)?.clone();
+///         for (key, value) in args.iter() {
+///             if let FluentValue::String(value) = value {
+///                 text = text.replace(&format!("{{{key}}}"), value);
+///             }
+///         }
+///         Some(Ok(text))
+///     }
+/// }
+///
+/// let backend = MapBackend(HashMap::from([(
+///     langid!("en-US"),
+///     HashMap::from([("welcome".to_string(), "Welcome {name}!".to_string())]),
+/// )]));
+///
+/// let resources = I18NResources::builder()
+///     .add_backend(backend)
+///     .build()
+///     .unwrap();
+/// let bundle = resources.negotiate_languages(&[langid!("en-US")]);
+/// assert_eq!(
+///     bundle
+///         .text_with_args("welcome", (("name", "sunli"),))
+///         .unwrap(),
+///     "Welcome sunli!"
+/// );
+/// ```
+pub trait I18NBackend: Send + Sync + 'static {
+    /// Returns the languages provided by this backend.
+    fn languages(&self) -> Vec<LanguageIdentifier>;
+
+    /// Formats the message with the specified id in the specified language.
+    ///
+    /// Returns `None` if the message does not exist, so that the next
+    /// language in the fallback chain is tried.
+    fn format(
+        &self,
+        language: &LanguageIdentifier,
+        id: &str,
+        args: &I18NArgs<'_>,
+    ) -> Option<Result<String, I18NError>>;
+
+    /// Reloads the messages, called by
+    /// [`I18NResources::reload`](crate::i18n::I18NResources::reload).
+    ///
+    /// Default does nothing.
+    fn reload(&self) -> Result<(), I18NError> {
+        Ok(())
+    }
+}
@@ -55,19 +55,29 @@
 //!     .unwrap();
 //! ```
 //!
+//! # Reload resources
+//!
+//! The resources can be reloaded at runtime with [`I18NResources::reload`].
+//! In debug builds, the resources directories are also reloaded
+//! automatically when their files are modified, see
+//! [`I18NResourcesBuilder::hot_reload`].
+//!
 //! # Use extractor
 //!
 //! See also: [`crate::i18n::Locale`]
 
 mod args;
+mod backend;
 mod locale;
 mod resources;
 
+pub use fluent::FluentValue;
 pub use fluent_langneg::NegotiationStrategy;
 pub use unic_langid;
 
 pub use self::{
     args::I18NArgs,
+    backend::I18NBackend,
     locale::Locale,
     resources::{I18NBundle, I18NResources, I18NResourcesBuilder},
 };
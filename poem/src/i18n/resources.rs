@@ -3,10 +3,12 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
+    time::{Duration, Instant, SystemTime},
 };
 
 use fluent::{FluentMessage, FluentResource};
 use intl_memoizer::concurrent::IntlLangMemoizer;
+use parking_lot::{Mutex, RwLock};
 use smallvec::SmallVec;
 use unic_langid::{LanguageIdentifier, langid};
 
@@ -16,21 +18,139 @@ type FluentBundle = fluent::bundle::FluentBundle<FluentResource, IntlLangMemoize
 
 use fluent_langneg::NegotiationStrategy;
 
-use crate::i18n::I18NArgs;
+use crate::i18n::{I18NArgs, I18NBackend};
 
-struct InnerResources {
+/// The minimum interval between two checks for modified resources.
+const HOT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Loaded {
     available_languages: Vec<LanguageIdentifier>,
     bundles: HashMap<LanguageIdentifier, Arc<FluentBundle>>,
+    backends: Vec<(Arc<dyn I18NBackend>, Vec<LanguageIdentifier>)>,
+}
+
+struct HotReload {
+    last_check: Instant,
+    modified: Option<SystemTime>,
+}
+
+struct InnerResources {
+    paths: Vec<PathBuf>,
+    resources: Vec<(String, String)>,
+    backends: Vec<Arc<dyn I18NBackend>>,
+    loaded: RwLock<Arc<Loaded>>,
     default_language: LanguageIdentifier,
     strategy: NegotiationStrategy,
+    fallbacks: HashMap<LanguageIdentifier, Vec<LanguageIdentifier>>,
+    hot_reload: Option<Mutex<HotReload>>,
+}
+
+fn load(
+    paths: &[PathBuf],
+    resources: &[(String, String)],
+    backends: &[Arc<dyn I18NBackend>],
+) -> Result<Loaded, I18NError> {
+    let mut bundles = HashMap::new();
+
+    for path in paths {
+        load_resources_from_path(&mut bundles, path)?;
+    }
+
+    for (language, ftl) in resources {
+        let language = LanguageIdentifier::from_str(language)?;
+        let resource = FluentResource::try_new(ftl.clone())
+            .map_err(|(_, errors)| I18NError::FluentParser(errors))?;
+
+        bundles
+            .entry(language.clone())
+            .or_insert_with(|| FluentBundle::new_concurrent(vec![language]))
+            .add_resource(resource)
+            .map_err(I18NError::Fluent)?;
+    }
+
+    let mut available_languages: Vec<LanguageIdentifier> = bundles.keys().cloned().collect();
+    let backends = backends
+        .iter()
+        .map(|backend| {
+            let languages = backend.languages();
+            for language in &languages {
+                if !available_languages.contains(language) {
+                    available_languages.push(language.clone());
+                }
+            }
+            (backend.clone(), languages)
+        })
+        .collect();
+
+    Ok(Loaded {
+        available_languages,
+        bundles: bundles
+            .into_iter()
+            .map(|(key, value)| (key, Arc::new(value)))
+            .collect(),
+        backends,
+    })
+}
+
+impl InnerResources {
+    fn load(&self) -> Result<Loaded, I18NError> {
+        load(&self.paths, &self.resources, &self.backends)
+    }
+
+    fn check_hot_reload(self: &Arc<Self>) {
+        let Some(hot_reload) = &self.hot_reload else {
+            return;
+        };
+        let Some(mut hot_reload) = hot_reload.try_lock() else {
+            return;
+        };
+        if hot_reload.last_check.elapsed() < HOT_RELOAD_INTERVAL {
+            return;
+        }
+        hot_reload.last_check = Instant::now();
+        drop(hot_reload);
+
+        // the file system is not accessed on the executor, the requests use
+        // the loaded resources until the check is done
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let inner = self.clone();
+                handle.spawn_blocking(move || inner.reload_if_modified());
+            }
+            Err(_) => self.reload_if_modified(),
+        }
+    }
+
+    fn reload_if_modified(&self) {
+        let Some(hot_reload) = &self.hot_reload else {
+            return;
+        };
+        let modified = last_modified(&self.paths);
+        {
+            let mut hot_reload = hot_reload.lock();
+            if modified == hot_reload.modified {
+                return;
+            }
+            hot_reload.modified = modified;
+        }
+
+        tracing::debug!("fluent resources modified, reloading");
+        match self.load() {
+            Ok(loaded) => *self.loaded.write() = Arc::new(loaded),
+            Err(err) => tracing::error!(error = %err, "failed to reload fluent resources"),
+        }
+    }
 }
 
 /// I18N resources builder.
 pub struct I18NResourcesBuilder {
     paths: Vec<PathBuf>,
     resources: Vec<(String, String)>,
+    backends: Vec<Arc<dyn I18NBackend>>,
     default_language: LanguageIdentifier,
     strategy: NegotiationStrategy,
+    fallbacks: HashMap<LanguageIdentifier, Vec<LanguageIdentifier>>,
+    hot_reload: bool,
 }
 
 impl I18NResourcesBuilder {
@@ -106,40 +226,114 @@ impl I18NResourcesBuilder {
         self
     }
 
-    /// Consumes this builder and returns a [`I18NResources`] object.
-    pub fn build(self) -> Result<I18NResources, I18NError> {
-        let mut bundles = HashMap::new();
+    /// Add a backend providing messages in other formats.
+    ///
+    /// See also: [`I18NBackend`]
+    #[must_use]
+    pub fn add_backend(mut self, backend: impl I18NBackend) -> Self {
+        self.backends.push(Arc::new(backend));
+        self
+    }
 
-        for path in self.paths {
-            load_resources_from_path(&mut bundles, path)?;
-        }
+    /// Sets the languages to try, in order, when a message is missing in the
+    /// specified language.
+    ///
+    /// The fallback languages are inserted after `language` in the list of
+    /// negotiated languages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::i18n::I18NResources;
+    /// use unic_langid::{langid, langids};
+    ///
+    /// let resources = I18NResources::builder()
+    ///     .add_ftl("en-US", "hello-world = Hello world!\nbye = Bye!")
+    ///     .add_ftl("es", "hello-world = ¡Hola mundo!")
+    ///     .add_ftl("ca", "")
+    ///     .fallback_languages(langid!("ca"), langids!("es"))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let bundle = resources.negotiate_languages(&[langid!("ca")]);
+    /// assert_eq!(bundle.text("hello-world").unwrap(), "¡Hola mundo!");
+    /// assert_eq!(bundle.text("bye").unwrap(), "Bye!");
+    /// ```
+    #[must_use]
+    pub fn fallback_languages(
+        mut self,
+        language: LanguageIdentifier,
+        fallbacks: impl IntoIterator<Item = LanguageIdentifier>,
+    ) -> Self {
+        self.fallbacks
+            .insert(language, fallbacks.into_iter().collect());
+        self
+    }
 
-        for (language, ftl) in self.resources {
-            let language = LanguageIdentifier::from_str(&language)?;
-            let resource = FluentResource::try_new(ftl)
-                .map_err(|(_, errors)| I18NError::FluentParser(errors))?;
+    /// Reloads the resources directories when their files are modified.
+    ///
+    /// The directories are checked at most once per second when negotiating
+    /// languages, on a blocking thread of the Tokio runtime if there is one.
+    /// Default is enabled in debug builds.
+    #[must_use]
+    pub fn hot_reload(mut self, enable: bool) -> Self {
+        self.hot_reload = enable;
+        self
+    }
 
-            bundles
-                .entry(language.clone())
-                .or_insert_with(|| FluentBundle::new_concurrent(vec![language]))
-                .add_resource(resource)
-                .map_err(I18NError::Fluent)?;
-        }
+    /// Consumes this builder and returns a [`I18NResources`] object.
+    pub fn build(self) -> Result<I18NResources, I18NError> {
+        let hot_reload = (self.hot_reload && !self.paths.is_empty()).then(|| {
+            Mutex::new(HotReload {
+                last_check: Instant::now(),
+                modified: last_modified(&self.paths),
+            })
+        });
+
+        let loaded = load(&self.paths, &self.resources, &self.backends)?;
 
         Ok(I18NResources {
             inner: Arc::new(InnerResources {
-                available_languages: bundles.keys().cloned().collect(),
-                bundles: bundles
-                    .into_iter()
-                    .map(|(key, value)| (key, Arc::new(value)))
-                    .collect(),
+                paths: self.paths,
+                resources: self.resources,
+                backends: self.backends,
+                loaded: RwLock::new(Arc::new(loaded)),
                 default_language: self.default_language,
                 strategy: self.strategy,
+                fallbacks: self.fallbacks,
+                hot_reload,
             }),
         })
     }
 }
 
+/// Returns the latest modification time of the language directories and the
+/// files they contain.
+fn last_modified(paths: &[PathBuf]) -> Option<SystemTime> {
+    let mut modified = None;
+    let mut update = |path: &Path| {
+        if let Ok(time) = std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            modified = modified.max(Some(time));
+        }
+    };
+
+    for path in paths {
+        update(path);
+        for language_dir in std::fs::read_dir(path).into_iter().flatten().flatten() {
+            update(&language_dir.path());
+            for resource in std::fs::read_dir(language_dir.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+            {
+                update(&resource.path());
+            }
+        }
+    }
+
+    modified
+}
+
 fn load_resources_from_path(
     bundles: &mut HashMap<LanguageIdentifier, FluentBundle>,
     path: impl AsRef<Path>,
@@ -193,46 +387,87 @@ impl I18NResources {
         I18NResourcesBuilder {
             paths: vec![],
             resources: vec![],
+            backends: vec![],
             default_language: langid!("en-US"),
             strategy: NegotiationStrategy::Filtering,
+            fallbacks: HashMap::new(),
+            hot_reload: cfg!(debug_assertions),
         }
     }
 
+    /// Reloads all the resources and backends.
+    ///
+    /// If an error occurs, the previously loaded resources are kept.
+    pub fn reload(&self) -> Result<(), I18NError> {
+        for backend in &self.inner.backends {
+            backend.reload()?;
+        }
+        let loaded = self.inner.load()?;
+        *self.inner.loaded.write() = Arc::new(loaded);
+        Ok(())
+    }
+
     /// Negotiate the language according to the input language id list and
     /// return the [`I18NBundle`].
     pub fn negotiate_languages(&self, languages: &[impl AsRef<LanguageIdentifier>]) -> I18NBundle {
+        self.inner.check_hot_reload();
+
+        let loaded = self.inner.loaded.read().clone();
         let resolved_languages = fluent_langneg::negotiate_languages(
             languages,
-            &self.inner.available_languages,
+            &loaded.available_languages,
             Some(&self.inner.default_language),
             self.inner.strategy,
         );
 
-        I18NBundle(
-            resolved_languages
-                .into_iter()
-                .filter_map(|language| self.inner.bundles.get(language))
-                .cloned()
-                .collect(),
-        )
+        let mut chain = SmallVec::<[&LanguageIdentifier; 8]>::new();
+        for language in resolved_languages {
+            for language in std::iter::once(language)
+                .chain(self.inner.fallbacks.get(language).into_iter().flatten())
+            {
+                if !chain.contains(&language) {
+                    chain.push(language);
+                }
+            }
+        }
+
+        let mut entries = SmallVec::new();
+        for language in chain {
+            if let Some(bundle) = loaded.bundles.get(language) {
+                entries.push(BundleEntry::Fluent(bundle.clone()));
+            }
+            for (backend, languages) in &loaded.backends {
+                if languages.contains(language) {
+                    entries.push(BundleEntry::Backend(backend.clone(), language.clone()));
+                }
+            }
+        }
+
+        I18NBundle(entries)
     }
 }
 
+enum BundleEntry {
+    Fluent(Arc<FluentBundle>),
+    Backend(Arc<dyn I18NBackend>, LanguageIdentifier),
+}
+
 /// A collection of localization messages.
-pub struct I18NBundle(SmallVec<[Arc<FluentBundle>; 8]>);
+pub struct I18NBundle(SmallVec<[BundleEntry; 8]>);
 
 impl I18NBundle {
-    fn message(
-        &self,
-        id: impl AsRef<str>,
-    ) -> Result<(&'_ FluentBundle, FluentMessage<'_>), I18NError> {
-        let id = id.as_ref();
-        for bundle in &self.0 {
-            if let Some(message) = bundle.get_message(id) {
-                return Ok((bundle, message));
-            }
+    fn format_fluent(
+        bundle: &FluentBundle,
+        message: FluentMessage<'_>,
+        args: &I18NArgs<'_>,
+    ) -> Result<String, I18NError> {
+        let mut errors = Vec::new();
+        let value = message.value().ok_or(I18NError::FluentNoValue)?;
+        let s = bundle.format_pattern(value, Some(&args.0), &mut errors);
+        if !errors.is_empty() {
+            return Err(I18NError::Fluent(errors));
         }
-        Err(I18NError::FluentMessageNotFound { id: id.to_string() })
+        Ok(s.into_owned())
     }
 
     /// Gets the text with arguments.
@@ -268,15 +503,23 @@ impl I18NBundle {
         id: impl AsRef<str>,
         args: impl Into<I18NArgs<'a>>,
     ) -> Result<String, I18NError> {
-        let mut errors = Vec::new();
-        let (bundle, message) = self.message(id.as_ref())?;
+        let id = id.as_ref();
         let args = args.into();
-        let value = message.value().ok_or(I18NError::FluentNoValue)?;
-        let s = bundle.format_pattern(value, Some(&args.0), &mut errors);
-        if !errors.is_empty() {
-            return Err(I18NError::Fluent(errors));
+        for entry in &self.0 {
+            match entry {
+                BundleEntry::Fluent(bundle) => {
+                    if let Some(message) = bundle.get_message(id) {
+                        return Self::format_fluent(bundle, message, &args);
+                    }
+                }
+                BundleEntry::Backend(backend, language) => {
+                    if let Some(res) = backend.format(language, id, &args) {
+                        return res;
+                    }
+                }
+            }
         }
-        Ok(s.into_owned())
+        Err(I18NError::FluentMessageNotFound { id: id.to_string() })
     }
 
     /// Gets the text.
@@ -299,3 +542,186 @@ impl I18NBundle {
         self.text_with_args(id, I18NArgs::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use unic_langid::langids;
+
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("poem-i18n-{name}-{}", std::process::id()));
+            _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(path.join("en-US")).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, ftl: &str) {
+            std::fs::write(self.0.join("en-US").join("simple.ftl"), ftl).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    struct UpperBackend;
+
+    impl I18NBackend for UpperBackend {
+        fn languages(&self) -> Vec<LanguageIdentifier> {
+            langids!("en-US", "fr")
+        }
+
+        fn format(
+            &self,
+            language: &LanguageIdentifier,
+            id: &str,
+            _args: &I18NArgs<'_>,
+        ) -> Option<Result<String, I18NError>> {
+            id.starts_with("backend-")
+                .then(|| Ok(format!("{language}:{}", id.to_uppercase())))
+        }
+    }
+
+    #[test]
+    fn reload() {
+        let dir = TempDir::new("reload");
+        dir.write("hello = Hello!");
+
+        let resources = I18NResources::builder()
+            .add_path(&dir.0)
+            .hot_reload(false)
+            .build()
+            .unwrap();
+        let text = || {
+            resources
+                .negotiate_languages(&[langid!("en-US")])
+                .text("hello")
+                .unwrap()
+        };
+        assert_eq!(text(), "Hello!");
+
+        dir.write("hello = Hi!");
+        assert_eq!(text(), "Hello!");
+        resources.reload().unwrap();
+        assert_eq!(text(), "Hi!");
+
+        dir.write("hello = {");
+        assert!(resources.reload().is_err());
+        assert_eq!(text(), "Hi!");
+    }
+
+    #[test]
+    fn hot_reload() {
+        let dir = TempDir::new("hot-reload");
+        dir.write("hello = Hello!");
+
+        let resources = I18NResources::builder()
+            .add_path(&dir.0)
+            .hot_reload(true)
+            .build()
+            .unwrap();
+        let text = || {
+            resources
+                .negotiate_languages(&[langid!("en-US")])
+                .text("hello")
+                .unwrap()
+        };
+        assert_eq!(text(), "Hello!");
+
+        dir.write("hello = Hi!");
+        let file = std::fs::File::options()
+            .write(true)
+            .open(dir.0.join("en-US").join("simple.ftl"))
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(text(), "Hello!");
+
+        resources
+            .inner
+            .hot_reload
+            .as_ref()
+            .unwrap()
+            .lock()
+            .last_check -= HOT_RELOAD_INTERVAL;
+        assert_eq!(text(), "Hi!");
+    }
+
+    #[tokio::test]
+    async fn hot_reload_in_runtime() {
+        let dir = TempDir::new("hot-reload-runtime");
+        dir.write("hello = Hello!");
+
+        let resources = I18NResources::builder()
+            .add_path(&dir.0)
+            .hot_reload(true)
+            .build()
+            .unwrap();
+        let text = || {
+            resources
+                .negotiate_languages(&[langid!("en-US")])
+                .text("hello")
+                .unwrap()
+        };
+
+        dir.write("hello = Hi!");
+        let file = std::fs::File::options()
+            .write(true)
+            .open(dir.0.join("en-US").join("simple.ftl"))
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        resources
+            .inner
+            .hot_reload
+            .as_ref()
+            .unwrap()
+            .lock()
+            .last_check -= HOT_RELOAD_INTERVAL;
+
+        // the resources are reloaded in the background
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while text() != "Hi!" {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn backend_and_fallbacks() {
+        let resources = I18NResources::builder()
+            .add_ftl("en-US", "hello = Hello!\nbye = Bye!")
+            .add_ftl("es", "hello = ¡Hola!")
+            .add_ftl("ca", "bye = Adéu!")
+            .add_backend(UpperBackend)
+            .fallback_languages(langid!("ca"), langids!("es"))
+            .build()
+            .unwrap();
+
+        let bundle = resources.negotiate_languages(&[langid!("ca")]);
+        assert_eq!(bundle.text("hello").unwrap(), "¡Hola!");
+        assert_eq!(bundle.text("bye").unwrap(), "Adéu!");
+        assert_eq!(bundle.text("backend-msg").unwrap(), "en-US:BACKEND-MSG");
+
+        let bundle = resources.negotiate_languages(&[langid!("es")]);
+        assert_eq!(bundle.text("bye").unwrap(), "Bye!");
+        assert_eq!(bundle.text("backend-msg").unwrap(), "en-US:BACKEND-MSG");
+
+        let bundle = resources.negotiate_languages(&[langid!("fr")]);
+        assert_eq!(bundle.text("hello").unwrap(), "Hello!");
+        assert_eq!(bundle.text("backend-msg").unwrap(), "fr:BACKEND-MSG");
+        assert!(matches!(
+            bundle.text("missing"),
+            Err(I18NError::FluentMessageNotFound { .. })
+        ));
+    }
+}
@@ -207,6 +207,10 @@
 //!         });
 //! ```
 //!
+//! To return structured errors for every error type, use
+//! [`format_error`](EndpointExt::format_error) with an
+//! [`ErrorFormatter`](endpoint::ErrorFormatter).
+//!
 //! # Middleware
 //!
 //! You can call the [`with`](EndpointExt::with) method on the [`Endpoint`] to
@@ -231,14 +235,19 @@
 //! |Feature           |Description                     |
 //! |------------------|--------------------------------|
 //! | server | Server and listener APIs(enable by default) |
+//! |client            | Support for HTTP client        |
+//! |client-rustls     | Support for `https` in the HTTP client and the reverse proxy with [`rustls`](https://crates.io/crates/rustls) |
 //! |compression  | Support decompress request body and compress response body |
 //! |cookie            | Support for Cookie             |
+//! |cron              | Support for cron schedules of background tasks |
 //! |csrf | Support for Cross-Site Request Forgery (CSRF) protection |
+//! |long-running      | Support for long running jobs with status polling |
 //! |multipart         | Support for Multipart          |
 //! |native-tls        | Support for HTTP server over TLS with [`native-tls`](https://crates.io/crates/native-tls)  |
 //! |openssl-tls        | Support for HTTP server over TLS with [`openssl-tls`](https://crates.io/crates/openssl)  |
 //! |opentelemetry     | Support for opentelemetry    |
 //! |prometheus        | Support for Prometheus       |
+//! |proxy             | Support for reverse proxy      |
 //! |redis-session     | Support for RedisSession     |
 //! |rustls            | Support for HTTP server over TLS with [`rustls`](https://crates.io/crates/rustls)  |
 //! |session           | Support for session    |
@@ -266,8 +275,12 @@
 #![warn(rustdoc::broken_intra_doc_links)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+pub mod client;
 pub mod endpoint;
 pub mod error;
+pub mod hooks;
 #[cfg(feature = "i18n")]
 #[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
 pub mod i18n;
@@ -275,9 +288,15 @@ pub mod i18n;
 #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
 pub mod listener;
 pub mod middleware;
+#[cfg(feature = "proxy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
+pub mod proxy;
 #[cfg(feature = "session")]
 #[cfg_attr(docsrs, doc(cfg(feature = "session")))]
 pub mod session;
+#[cfg(feature = "server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+pub mod task;
 #[cfg(feature = "test")]
 #[cfg_attr(docsrs, doc(cfg(feature = "test")))]
 pub mod test;
@@ -288,6 +307,8 @@ pub use http;
 
 mod addr;
 mod body;
+#[cfg(any(feature = "client", feature = "proxy"))]
+mod connector;
 mod request;
 mod response;
 mod route;
@@ -1,4 +1,4 @@
-use crate::{Endpoint, Middleware, Request, Result};
+use crate::{Endpoint, Middleware, Request, Result, endpoint::ShutdownHooks};
 
 /// Middleware for adding any data to a request.
 pub struct AddData<T> {
@@ -44,6 +44,10 @@ where
         req.extensions_mut().insert(self.value.clone());
         self.inner.call(req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
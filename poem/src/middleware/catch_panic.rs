@@ -3,7 +3,9 @@ use std::{any::Any, panic::AssertUnwindSafe};
 use futures_util::FutureExt;
 use http::StatusCode;
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use crate::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+};
 
 /// Panics handler
 pub trait PanicHandler: Clone + Sync + Send + 'static {
@@ -137,4 +139,8 @@ impl<E: Endpoint, H: PanicHandler> Endpoint for CatchPanicEndpoint<E, H> {
             Err(err) => Ok(self.panic_handler.get_response(err).into_response()),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
@@ -4,6 +4,7 @@ use headers::HeaderMap;
 
 use crate::{
     Body, Endpoint, IntoResponse, Middleware, Request, Response, Result,
+    endpoint::ShutdownHooks,
     http::header,
     web::{Compress, CompressionAlgo, CompressionLevel},
 };
@@ -179,6 +180,10 @@ impl<E: Endpoint> Endpoint for CompressionEndpoint<E> {
             None => Ok(resp.into_response()),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.ep.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
@@ -2,14 +2,15 @@ use std::sync::Arc;
 
 use crate::{
     Endpoint, IntoResponse, Middleware, Request, Response, Result,
-    web::cookie::{CookieJar, CookieKey},
+    endpoint::ShutdownHooks,
+    web::cookie::{CookieJar, CookieKey, CookieKeys},
 };
 
 /// Middleware for CookieJar support.
 #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
 #[derive(Default)]
 pub struct CookieJarManager {
-    key: Option<Arc<CookieKey>>,
+    keys: Option<Arc<CookieKeys>>,
 }
 
 impl CookieJarManager {
@@ -22,8 +23,21 @@ impl CookieJarManager {
     /// Specify the `CookieKey` used for the `CookieJar::private` and
     /// `CookieJar::signed` methods.
     pub fn with_key(key: CookieKey) -> Self {
+        Self::with_keys(key, [])
+    }
+
+    /// Specify the current `CookieKey` and the previous keys used for the
+    /// `CookieJar::private` and `CookieJar::signed` methods.
+    ///
+    /// The cookies are always encrypted or signed with the current key, but
+    /// the cookies issued under one of the previous keys can still be
+    /// decrypted or verified, which allows rotating the key.
+    pub fn with_keys(key: CookieKey, previous_keys: impl IntoIterator<Item = CookieKey>) -> Self {
         Self {
-            key: Some(Arc::new(key)),
+            keys: Some(Arc::new(CookieKeys {
+                current: key,
+                previous: previous_keys.into_iter().collect(),
+            })),
         }
     }
 }
@@ -37,7 +51,7 @@ where
     fn transform(&self, ep: E) -> Self::Output {
         CookieJarManagerEndpoint {
             inner: ep,
-            key: self.key.clone(),
+            keys: self.keys.clone(),
         }
     }
 }
@@ -46,7 +60,7 @@ where
 #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
 pub struct CookieJarManagerEndpoint<E> {
     inner: E,
-    key: Option<Arc<CookieKey>>,
+    keys: Option<Arc<CookieKeys>>,
 }
 
 impl<E: Endpoint> Endpoint for CookieJarManagerEndpoint<E> {
@@ -55,7 +69,7 @@ impl<E: Endpoint> Endpoint for CookieJarManagerEndpoint<E> {
     async fn call(&self, mut req: Request) -> Result<Self::Output> {
         if req.state().cookie_jar.is_none() {
             let mut cookie_jar = CookieJar::extract_from_headers(req.headers());
-            cookie_jar.key.clone_from(&self.key);
+            cookie_jar.keys.clone_from(&self.keys);
             req.state_mut().cookie_jar = Some(cookie_jar.clone());
             let mut resp = self.inner.call(req).await?.into_response();
             cookie_jar.append_delta_to_headers(resp.headers_mut());
@@ -64,12 +78,22 @@ impl<E: Endpoint> Endpoint for CookieJarManagerEndpoint<E> {
             self.inner.call(req).await.map(IntoResponse::into_response)
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use super::*;
-    use crate::{EndpointExt, handler, test::TestClient, web::cookie::Cookie};
+    use crate::{
+        EndpointExt, handler,
+        test::TestClient,
+        web::cookie::{Cookie, derive_cookie_key},
+    };
 
     #[tokio::test]
     async fn test_cookie_jar_manager() {
@@ -122,4 +146,65 @@ mod tests {
             .await
             .assert_status_is_ok();
     }
+
+    #[tokio::test]
+    async fn test_cookie_jar_manager_with_keys() {
+        #[handler(internal)]
+        async fn index(cookie_jar: &CookieJar) {
+            assert_eq!(
+                cookie_jar.private().get("value1").unwrap().value_str(),
+                "88"
+            );
+            assert_eq!(cookie_jar.signed().get("value2").unwrap().value_str(), "99");
+            cookie_jar
+                .private()
+                .add(Cookie::new_with_str("value3", "77"));
+        }
+
+        let old_key = derive_cookie_key(&[1; 32], "v1");
+        let new_key = derive_cookie_key(&[1; 32], "v2");
+        let cli = TestClient::new(index.with(CookieJarManager::with_keys(
+            new_key.clone(),
+            [old_key.clone()],
+        )));
+
+        let cookie_jar = CookieJar::default();
+        cookie_jar
+            .private_with_key(&old_key)
+            .add(Cookie::new_with_str("value1", "88"));
+        cookie_jar
+            .signed_with_key(&old_key)
+            .add(Cookie::new_with_str("value2", "99"));
+
+        let resp = cli
+            .get("/")
+            .header(
+                "cookie",
+                format!(
+                    "value1={}; value2={}",
+                    cookie_jar.get("value1").unwrap().value_str(),
+                    cookie_jar.get("value2").unwrap().value_str()
+                ),
+            )
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+
+        let set_cookie = resp
+            .0
+            .headers()
+            .get("set-cookie")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let cookie_jar = CookieJar::from_str(set_cookie).unwrap();
+        assert_eq!(
+            cookie_jar
+                .private_with_key(&new_key)
+                .get("value3")
+                .unwrap()
+                .value_str(),
+            "77"
+        );
+    }
 }
@@ -7,7 +7,7 @@ use wildmatch::WildMatch;
 
 use crate::{
     IntoResponse, Result,
-    endpoint::Endpoint,
+    endpoint::{Endpoint, ShutdownHooks},
     error::CorsError,
     http::{
         Method, header,
@@ -415,6 +415,10 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
 
         Ok(resp)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
 
 use base64::{Engine, engine::general_purpose::STANDARD};
 use libcsrf::{
@@ -8,10 +12,13 @@ use libcsrf::{
 
 use crate::{
     Endpoint, Middleware, Request, Result,
+    endpoint::ShutdownHooks,
+    hooks::SharedClock,
     middleware::{CookieJarManager, CookieJarManagerEndpoint},
     web::{
         CsrfToken, CsrfVerifier,
         cookie::{Cookie, SameSite},
+        csrf::{EXPIRES_LEN, token_expires},
     },
 };
 
@@ -166,6 +173,8 @@ impl Csrf {
     /// expiry and the time window over which CSRF tokens are considered
     /// valid.
     ///
+    /// The time window is measured with the [`SharedClock`] of the request.
+    ///
     /// The default for this value is one day.
     #[must_use]
     pub fn ttl(self, ttl: Duration) -> Self {
@@ -204,23 +213,30 @@ pub struct CsrfEndpoint<E> {
 }
 
 impl<E> CsrfEndpoint<E> {
+    /// Generates a token pair whose value starts with the expiration time of
+    /// the cookie, followed by the random part of the existing cookie if it
+    /// is not expired, so that the previous tokens remain valid.
     fn generate_token(
         &self,
         existing_cookie: Option<&UnencryptedCsrfCookie>,
+        now: i64,
     ) -> (RawCsrfToken, RawCsrfCookie) {
-        let existing_cookie_bytes = existing_cookie.and_then(|c| {
-            let c = c.value();
-            if c.len() < 64 {
-                None
-            } else {
-                let mut buf = [0; 64];
-                buf.copy_from_slice(c);
-                Some(buf)
-            }
-        });
+        let mut value = [0; 64];
+        match existing_cookie
+            .map(|cookie| cookie.value())
+            .filter(|value| token_expires(value).is_some_and(|expires| expires > now))
+        {
+            Some(existing) => value.copy_from_slice(existing),
+            None => self
+                .protect
+                .random_bytes(&mut value)
+                .expect("couldn't generate random bytes"),
+        }
+        let expires = now.saturating_add(self.ttl.as_secs() as i64);
+        value[..EXPIRES_LEN].copy_from_slice(&expires.to_be_bytes());
 
         self.protect
-            .generate_token_pair(existing_cookie_bytes.as_ref(), self.ttl.as_secs() as i64)
+            .generate_token_pair(Some(&value), self.ttl.as_secs() as i64)
             .expect("couldn't generate token/cookie pair")
     }
 }
@@ -235,7 +251,13 @@ impl<E: Endpoint> Endpoint for CsrfEndpoint<E> {
             .and_then(|cookie| STANDARD.decode(cookie.value_str()).ok())
             .and_then(|value| self.protect.parse_cookie(&value).ok());
 
-        let (token, cookie) = self.generate_token(existing_cookie.as_ref());
+        let clock = SharedClock::from_request(&req);
+        let now = clock
+            .system_now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+        let (token, cookie) = self.generate_token(existing_cookie.as_ref(), now);
         let csrf_cookie = {
             let mut cookie =
                 Cookie::new_with_str(&self.cookie_name, STANDARD.encode(cookie.value()));
@@ -250,11 +272,18 @@ impl<E: Endpoint> Endpoint for CsrfEndpoint<E> {
         req.cookie().add(csrf_cookie);
         req.extensions_mut()
             .insert(CsrfToken(STANDARD.encode(token.value())));
-        req.extensions_mut()
-            .insert(CsrfVerifier::new(existing_cookie, self.protect.clone()));
+        req.extensions_mut().insert(CsrfVerifier::new(
+            existing_cookie,
+            self.protect.clone(),
+            now,
+        ));
 
         self.inner.call(req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
@@ -262,7 +291,7 @@ mod tests {
     use http::{Method, StatusCode, header};
 
     use super::*;
-    use crate::{EndpointExt, Error, IntoResponse, Result, get, handler};
+    use crate::{EndpointExt, Error, IntoResponse, Result, get, handler, test::MockClock};
 
     const CSRF_TOKEN_NAME: &str = "X-CSRF-Token";
 
@@ -335,4 +364,77 @@ mod tests {
             "invalid token"
         );
     }
+
+    #[tokio::test]
+    async fn test_csrf_expires() {
+        #[handler(internal)]
+        fn login_ui(token: &CsrfToken) -> impl IntoResponse {
+            token.0.to_string()
+        }
+
+        #[handler(internal)]
+        fn login(verifier: &CsrfVerifier, req: &Request) -> impl IntoResponse {
+            let token = req.header(CSRF_TOKEN_NAME).unwrap_or_default();
+            match verifier.validate(token) {
+                Ok(()) => "ok".to_string(),
+                Err(err) => err.to_string(),
+            }
+        }
+
+        let clock = MockClock::new();
+        let app = get(login_ui)
+            .post(login)
+            .with(Csrf::new().ttl(Duration::from_secs(60)))
+            .data(SharedClock::new(clock.clone()));
+
+        let issue = |cookie: Option<String>| {
+            let app = &app;
+            async move {
+                let mut req = Request::builder();
+                if let Some(cookie) = cookie {
+                    req = req.header(header::COOKIE, cookie);
+                }
+                let resp = app.call(req.finish()).await.unwrap();
+                let cookie = resp
+                    .header(header::SET_COOKIE)
+                    .map(|cookie| cookie.split(';').next().unwrap().to_string())
+                    .unwrap();
+                let token = resp.into_body().into_string().await.unwrap();
+                (cookie, token)
+            }
+        };
+        let verify = |cookie: String, token: String| {
+            let app = &app;
+            async move {
+                app.call(
+                    Request::builder()
+                        .method(Method::POST)
+                        .header(CSRF_TOKEN_NAME, token)
+                        .header(header::COOKIE, cookie)
+                        .finish(),
+                )
+                .await
+                .unwrap()
+                .into_body()
+                .into_string()
+                .await
+                .unwrap()
+            }
+        };
+
+        let (cookie, token) = issue(None).await;
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(verify(cookie.clone(), token.clone()).await, "ok");
+
+        // renewing the cookie keeps the previous tokens valid
+        let (cookie, _) = issue(Some(cookie)).await;
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(verify(cookie.clone(), token.clone()).await, "ok");
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(
+            verify(cookie, token).await,
+            "Validation failed: Cookie expired"
+        );
+    }
 }
@@ -2,7 +2,10 @@ use std::{borrow::Cow, sync::Arc};
 
 use http::{Uri, header, uri::Scheme};
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result, web::Redirect};
+use crate::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+    web::Redirect,
+};
 
 type FilterFn = Arc<dyn Fn(&Request) -> bool + Send + Sync>;
 
@@ -86,6 +89,10 @@ where
 
         self.inner.call(req).await.map(IntoResponse::into_response)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 fn redirect_host(host: &str, https_port: Option<u16>) -> Cow<'_, str> {
@@ -18,6 +18,7 @@ mod opentelemetry_tracing;
 mod propagate_header;
 #[cfg(feature = "requestid")]
 mod requestid;
+mod sampler;
 mod sensitive_header;
 mod set_header;
 mod size_limit;
@@ -52,10 +53,11 @@ pub use self::{
     force_https::ForceHttps,
     normalize_path::{NormalizePath, NormalizePathEndpoint, TrailingSlash},
     propagate_header::{PropagateHeader, PropagateHeaderEndpoint},
+    sampler::Sampler,
     sensitive_header::{SensitiveHeader, SensitiveHeaderEndpoint},
     set_header::{SetHeader, SetHeaderEndpoint},
     size_limit::{SizeLimit, SizeLimitEndpoint},
-    tracing_mw::{Tracing, TracingEndpoint},
+    tracing_mw::{SampledTracing, Tracing, TracingEndpoint},
 };
 use crate::endpoint::{EitherEndpoint, Endpoint};
 
@@ -3,7 +3,7 @@ use std::str::FromStr;
 use http::{Uri, uri::PathAndQuery};
 use regex::Regex;
 
-use crate::{Endpoint, Middleware, Request, Result};
+use crate::{Endpoint, Middleware, Request, Result, endpoint::ShutdownHooks};
 
 /// Determines the behavior of the [`NormalizePath`] middleware.
 #[derive(Debug, Clone, Copy, Default)]
@@ -116,6 +116,10 @@ impl<E: Endpoint> Endpoint for NormalizePathEndpoint<E> {
 
         self.inner.call(req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
@@ -6,7 +6,10 @@ use libopentelemetry::{
 };
 use opentelemetry_semantic_conventions::trace;
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result, route::PathPattern};
+use crate::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+    route::PathPattern,
+};
 
 /// Middleware for metrics with OpenTelemetry.
 #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
@@ -119,4 +122,8 @@ impl<E: Endpoint> Endpoint for OpenTelemetryMetricsEndpoint<E> {
 
         res
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
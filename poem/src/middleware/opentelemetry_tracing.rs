@@ -1,14 +1,19 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::SystemTime};
 
 use libopentelemetry::{
     Context, Key, KeyValue, global,
-    trace::{FutureExt, Span, SpanKind, TraceContextExt, Tracer},
+    trace::{
+        FutureExt, Span, SpanContext, SpanId, SpanKind, SpanRef, TraceContextExt, TraceFlags,
+        TraceId, Tracer,
+    },
 };
 use opentelemetry_http::HeaderExtractor;
 use opentelemetry_semantic_conventions::{attribute, resource};
 
 use crate::{
     Endpoint, FromRequest, IntoResponse, Middleware, Request, Response, Result,
+    endpoint::ShutdownHooks,
+    middleware::{Sampler, sampler::random_u64},
     route::PathPattern,
     web::{RealIp, headers::HeaderMapExt},
 };
@@ -17,6 +22,7 @@ use crate::{
 #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
 pub struct OpenTelemetryTracing<T> {
     tracer: Arc<T>,
+    sampler: Option<Arc<Sampler>>,
 }
 
 impl<T> OpenTelemetryTracing<T> {
@@ -24,6 +30,22 @@ impl<T> OpenTelemetryTracing<T> {
     pub fn new(tracer: T) -> Self {
         Self {
             tracer: Arc::new(tracer),
+            sampler: None,
+        }
+    }
+
+    /// Only traces the requests selected by the sampler.
+    ///
+    /// The requests that are not sampled propagate a non-sampled span context
+    /// to the inner endpoint. The sampler of the tracer provider still applies
+    /// to the sampled requests.
+    ///
+    /// See also [`Sampler`].
+    #[must_use]
+    pub fn sampler(self, sampler: Sampler) -> Self {
+        Self {
+            sampler: Some(Arc::new(sampler)),
+            ..self
         }
     }
 }
@@ -39,6 +61,7 @@ where
     fn transform(&self, ep: E) -> Self::Output {
         OpenTelemetryTracingEndpoint {
             tracer: self.tracer.clone(),
+            sampler: self.sampler.clone(),
             inner: ep,
         }
     }
@@ -48,6 +71,7 @@ where
 #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
 pub struct OpenTelemetryTracingEndpoint<T, E> {
     tracer: Arc<T>,
+    sampler: Option<Arc<Sampler>>,
     inner: E,
 }
 
@@ -96,71 +120,116 @@ where
         ));
 
         let method = req.method().to_string();
-        let mut span = self
+        let builder = self
             .tracer
             .span_builder(format!("{} {}", method, req.uri()))
             .with_kind(SpanKind::Server)
-            .with_attributes(attributes)
-            .start_with_context(&*self.tracer, &parent_cx);
+            .with_attributes(attributes);
+
+        let sampled = self
+            .sampler
+            .as_ref()
+            .is_none_or(|sampler| sampler.should_sample(req.uri().path()));
+
+        if !sampled {
+            // propagates the sampling decision to the downstream services
+            let start_time = SystemTime::now();
+            let cx = parent_cx.with_remote_span_context(unsampled_span_context(&parent_cx));
+            let res = self
+                .inner
+                .call(req)
+                .with_context(cx)
+                .await
+                .map(IntoResponse::into_response);
+
+            if self
+                .sampler
+                .as_ref()
+                .is_some_and(|sampler| sampler.should_sample_result(&res))
+            {
+                let mut span = builder
+                    .with_start_time(start_time)
+                    .start_with_context(&*self.tracer, &parent_cx);
+                span.add_event_with_timestamp("request.started".to_string(), start_time, vec![]);
+                let cx = parent_cx.with_span(span);
+                record_result(&cx.span(), &method, &res);
+                cx.span().end();
+            }
+            return res;
+        }
 
+        let mut span = builder.start_with_context(&*self.tracer, &parent_cx);
         span.add_event("request.started".to_string(), vec![]);
 
         async move {
-            let res = self.inner.call(req).await;
-            let cx = Context::current();
-            let span = cx.span();
-
-            match res {
-                Ok(resp) => {
-                    let resp = resp.into_response();
-
-                    if let Some(path_pattern) = resp.data::<PathPattern>() {
-                        const HTTP_PATH_PATTERN: Key = Key::from_static_str("http.path_pattern");
-                        span.update_name(format!("{} {}", method, path_pattern.0));
-                        span.set_attribute(KeyValue::new(
-                            HTTP_PATH_PATTERN,
-                            path_pattern.0.to_string(),
-                        ));
-                    }
-
-                    span.add_event("request.completed".to_string(), vec![]);
-                    span.set_attribute(KeyValue::new(
-                        attribute::HTTP_RESPONSE_STATUS_CODE,
-                        resp.status().as_u16() as i64,
-                    ));
-                    if let Some(content_length) =
-                        resp.headers().typed_get::<headers::ContentLength>()
-                    {
-                        span.set_attribute(KeyValue::new(
-                            attribute::HTTP_RESPONSE_BODY_SIZE,
-                            content_length.0 as i64,
-                        ));
-                    }
-                    Ok(resp)
-                }
-                Err(err) => {
-                    if let Some(path_pattern) = err.data::<PathPattern>() {
-                        const HTTP_PATH_PATTERN: Key = Key::from_static_str("http.path_pattern");
-                        span.update_name(format!("{} {}", method, path_pattern.0));
-                        span.set_attribute(KeyValue::new(
-                            HTTP_PATH_PATTERN,
-                            path_pattern.0.to_string(),
-                        ));
-                    }
-
-                    span.set_attribute(KeyValue::new(
-                        attribute::HTTP_RESPONSE_STATUS_CODE,
-                        err.status().as_u16() as i64,
-                    ));
-                    span.add_event(
-                        "request.error".to_string(),
-                        vec![KeyValue::new(attribute::EXCEPTION_MESSAGE, err.to_string())],
-                    );
-                    Err(err)
-                }
-            }
+            let res = self.inner.call(req).await.map(IntoResponse::into_response);
+            record_result(&Context::current().span(), &method, &res);
+            res
         }
         .with_context(Context::current_with_span(span))
         .await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+/// Creates the context of a span which is not sampled, in the trace of the
+/// parent or a new trace.
+fn unsampled_span_context(parent_cx: &Context) -> SpanContext {
+    let parent = parent_cx.span();
+    let parent = parent.span_context();
+    let trace_id = if parent.is_valid() {
+        parent.trace_id()
+    } else {
+        TraceId::from(((random_u64() as u128) << 64) | random_u64() as u128)
+    };
+    SpanContext::new(
+        trace_id,
+        SpanId::from(random_u64()),
+        TraceFlags::default(),
+        true,
+        parent.trace_state().clone(),
+    )
+}
+
+fn record_result(span: &SpanRef<'_>, method: &str, res: &Result<Response>) {
+    const HTTP_PATH_PATTERN: Key = Key::from_static_str("http.path_pattern");
+
+    match res {
+        Ok(resp) => {
+            if let Some(path_pattern) = resp.data::<PathPattern>() {
+                span.update_name(format!("{} {}", method, path_pattern.0));
+                span.set_attribute(KeyValue::new(HTTP_PATH_PATTERN, path_pattern.0.to_string()));
+            }
+
+            span.add_event("request.completed".to_string(), vec![]);
+            span.set_attribute(KeyValue::new(
+                attribute::HTTP_RESPONSE_STATUS_CODE,
+                resp.status().as_u16() as i64,
+            ));
+            if let Some(content_length) = resp.headers().typed_get::<headers::ContentLength>() {
+                span.set_attribute(KeyValue::new(
+                    attribute::HTTP_RESPONSE_BODY_SIZE,
+                    content_length.0 as i64,
+                ));
+            }
+        }
+        Err(err) => {
+            if let Some(path_pattern) = err.data::<PathPattern>() {
+                span.update_name(format!("{} {}", method, path_pattern.0));
+                span.set_attribute(KeyValue::new(HTTP_PATH_PATTERN, path_pattern.0.to_string()));
+            }
+
+            span.set_attribute(KeyValue::new(
+                attribute::HTTP_RESPONSE_STATUS_CODE,
+                err.status().as_u16() as i64,
+            ));
+            span.add_event(
+                "request.error".to_string(),
+                vec![KeyValue::new(attribute::EXCEPTION_MESSAGE, err.to_string())],
+            );
+        }
+    }
 }
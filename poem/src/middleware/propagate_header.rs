@@ -2,7 +2,9 @@ use std::collections::HashSet;
 
 use http::{HeaderMap, header::HeaderName};
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use crate::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+};
 
 /// Middleware to propagate a header from the request to the response.
 #[derive(Default)]
@@ -63,6 +65,10 @@ impl<E: Endpoint> Endpoint for PropagateHeaderEndpoint<E> {
         resp.headers_mut().extend(headers);
         Ok(resp)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
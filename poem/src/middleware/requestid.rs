@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::{
     Endpoint, Error, FromRequest, IntoResponse, Middleware, Request, Response, Result,
-    http::StatusCode,
+    endpoint::ShutdownHooks, hooks::generate_id, http::StatusCode,
 };
 
 const X_REQUEST_ID: &str = "x-request-id";
@@ -84,12 +84,13 @@ impl<E: Endpoint> Endpoint for RequestIdEndpoint<E> {
     type Output = Response;
 
     async fn call(&self, mut request: Request) -> Result<Self::Output> {
+        let generate = || generate_id(&request, || Uuid::new_v4().to_string());
         let request_id = if self.use_incoming_id == ReuseId::Use {
             request
                 .header(&self.header_name)
-                .map_or_else(|| Uuid::new_v4().to_string(), ToString::to_string)
+                .map_or_else(generate, ToString::to_string)
         } else {
-            Uuid::new_v4().to_string()
+            generate()
         };
         request.set_data(ReqId(request_id.clone()));
         let response = self.next.call(request);
@@ -101,6 +102,10 @@ impl<E: Endpoint> Endpoint for RequestIdEndpoint<E> {
             Err(e) => Err(e),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.next.collect_shutdown_hooks(hooks);
+    }
 }
 
 /// A request ID which can be extracted in handler functions.
@@ -139,7 +144,10 @@ impl IntoResponse for ReqId {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{EndpointExt, Route, get, handler, test::TestClient};
+    use crate::{
+        EndpointExt, Route, get, handler,
+        test::{SequentialIdGenerator, TestClient},
+    };
 
     #[handler(internal)]
     fn reply_with_req_id(req_id: ReqId) -> ReqId {
@@ -231,4 +239,20 @@ mod tests {
         response.assert_header_exist(header_name);
         assert_ne!(response.0.header(header_name), Some(id));
     }
+
+    #[tokio::test]
+    async fn id_generator() {
+        let app = app(RequestId::default().reuse_id(ReuseId::Use));
+        let cli = TestClient::new(app).id_generator(SequentialIdGenerator::new("req"));
+
+        let response = cli.get("/").send().await;
+        response.assert_header(X_REQUEST_ID, "req-1");
+        response.assert_text("req-1").await;
+
+        let response = cli.get("/").header(X_REQUEST_ID, "foobar").send().await;
+        response.assert_header(X_REQUEST_ID, "foobar");
+
+        let response = cli.get("/").send().await;
+        response.assert_header(X_REQUEST_ID, "req-2");
+    }
 }
@@ -0,0 +1,164 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use http::StatusCode;
+use wildmatch::WildMatch;
+
+use crate::{Response, Result};
+
+/// Head-based sampling configuration for the [`Tracing`](super::Tracing) and
+/// [`OpenTelemetryTracing`](super::OpenTelemetryTracing) middlewares.
+///
+/// Whether a request is traced is decided before it is handled, with the
+/// ratio of the first route pattern matching the path of the request, or the
+/// default ratio. The requests that are not sampled are still traced if they
+/// fail with a server error (`5xx`), unless
+/// [`Sampler::always_sample_errors`] is disabled.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     EndpointExt, Route,
+///     middleware::{Sampler, Tracing},
+/// };
+///
+/// let app = Route::new().with(
+///     Tracing.sampler(
+///         Sampler::ratio(0.1)
+///             .route("/health", 0.0)
+///             .route("/api/payments/*", 1.0),
+///     ),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    ratio: f64,
+    routes: Vec<(WildMatch, f64)>,
+    always_sample_errors: bool,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::ratio(1.0)
+    }
+}
+
+impl Sampler {
+    /// Create a `Sampler` which samples the specified ratio of the requests,
+    /// from `0.0` (none) to `1.0` (all).
+    pub fn ratio(ratio: f64) -> Self {
+        Self {
+            ratio,
+            routes: Vec::new(),
+            always_sample_errors: true,
+        }
+    }
+
+    /// Overrides the ratio for the requests whose path matches the pattern.
+    ///
+    /// The pattern can contain the wildcards `*` (any number of characters)
+    /// and `?` (a single character), and the first matching pattern is used.
+    #[must_use]
+    pub fn route(mut self, pattern: impl AsRef<str>, ratio: f64) -> Self {
+        self.routes.push((WildMatch::new(pattern.as_ref()), ratio));
+        self
+    }
+
+    /// Specifies whether the requests that are not sampled are still traced
+    /// when they fail with a server error.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn always_sample_errors(self, value: bool) -> Self {
+        Self {
+            always_sample_errors: value,
+            ..self
+        }
+    }
+
+    pub(crate) fn ratio_for(&self, path: &str) -> f64 {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map(|(_, ratio)| *ratio)
+            .unwrap_or(self.ratio)
+    }
+
+    pub(crate) fn should_sample(&self, path: &str) -> bool {
+        let ratio = self.ratio_for(path);
+        if ratio >= 1.0 {
+            true
+        } else if ratio <= 0.0 {
+            false
+        } else {
+            (random_u64() as f64 / u64::MAX as f64) < ratio
+        }
+    }
+
+    pub(crate) fn should_sample_result(&self, res: &Result<Response>) -> bool {
+        self.always_sample_errors && result_status(res).is_server_error()
+    }
+}
+
+fn result_status(res: &Result<Response>) -> StatusCode {
+    match res {
+        Ok(resp) => resp.status(),
+        Err(err) => err.status(),
+    }
+}
+
+/// A fast random number, which is good enough for sampling.
+pub(crate) fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn ratio() {
+        let sampler = Sampler::ratio(0.0);
+        assert!((0..100).all(|_| !sampler.should_sample("/")));
+
+        let sampler = Sampler::default();
+        assert!((0..100).all(|_| sampler.should_sample("/")));
+
+        let sampler = Sampler::ratio(0.5);
+        let sampled = (0..10000).filter(|_| sampler.should_sample("/")).count();
+        assert!((4000..6000).contains(&sampled));
+    }
+
+    #[test]
+    fn routes() {
+        let sampler = Sampler::ratio(0.5)
+            .route("/health", 0.0)
+            .route("/api/*", 1.0)
+            .route("/api/internal/*", 0.0);
+        assert_eq!(sampler.ratio_for("/health"), 0.0);
+        assert_eq!(sampler.ratio_for("/healthz"), 0.5);
+        assert_eq!(sampler.ratio_for("/api/users"), 1.0);
+        assert_eq!(sampler.ratio_for("/api/internal/a"), 1.0);
+        assert_eq!(sampler.ratio_for("/"), 0.5);
+    }
+
+    #[test]
+    fn errors() {
+        let sampler = Sampler::ratio(0.0);
+        assert!(sampler.should_sample_result(&Ok(StatusCode::BAD_GATEWAY.into())));
+        assert!(
+            sampler
+                .should_sample_result(&Err(Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)))
+        );
+        assert!(!sampler.should_sample_result(&Ok(StatusCode::OK.into())));
+        assert!(!sampler.should_sample_result(&Err(Error::from_status(StatusCode::NOT_FOUND))));
+
+        let sampler = sampler.always_sample_errors(false);
+        assert!(!sampler.should_sample_result(&Ok(StatusCode::BAD_GATEWAY.into())));
+    }
+}
@@ -2,7 +2,9 @@ use std::collections::HashSet;
 
 use http::{HeaderMap, header::HeaderName};
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use crate::{
+    Endpoint, IntoResponse, Middleware, Request, Response, Result, endpoint::ShutdownHooks,
+};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 enum AppliedTo {
@@ -107,6 +109,10 @@ impl<E: Endpoint> Endpoint for SensitiveHeaderEndpoint<E> {
 
         Ok(resp)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[allow(clippy::mutable_key_type)]
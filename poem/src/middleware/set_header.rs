@@ -1,5 +1,6 @@
 use crate::{
     Endpoint, IntoResponse, Middleware, Request, Response, Result,
+    endpoint::ShutdownHooks,
     http::{HeaderValue, header::HeaderName},
 };
 
@@ -126,6 +127,10 @@ impl<E: Endpoint> Endpoint for SetHeaderEndpoint<E> {
 
         Ok(resp)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
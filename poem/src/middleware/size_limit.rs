@@ -1,5 +1,6 @@
 use crate::{
-    Endpoint, Middleware, Request, Result, error::SizedLimitError, web::headers::HeaderMapExt,
+    Endpoint, Middleware, Request, Result, endpoint::ShutdownHooks, error::SizedLimitError,
+    web::headers::HeaderMapExt,
 };
 
 /// Middleware to limit the request payload size.
@@ -53,6 +54,10 @@ impl<E: Endpoint> Endpoint for SizeLimitEndpoint<E> {
 
         self.inner.call(req).await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,10 @@
-use std::time::Instant;
+use std::{sync::Arc, time::Duration};
 
-use tracing::{Instrument, Level};
+use tracing::{Instrument, Level, Span};
 
 use crate::{
-    Endpoint, FromRequest, IntoResponse, Middleware, Request, Response, Result, route::PathPattern,
+    Endpoint, FromRequest, IntoResponse, Middleware, Request, Response, Result,
+    endpoint::ShutdownHooks, hooks::SharedClock, middleware::Sampler, route::PathPattern,
     web::RealIp,
 };
 
@@ -11,102 +12,265 @@ use crate::{
 #[derive(Default)]
 pub struct Tracing;
 
+impl Tracing {
+    /// Only traces the requests selected by the sampler.
+    ///
+    /// See also [`Sampler`].
+    pub fn sampler(self, sampler: Sampler) -> SampledTracing {
+        SampledTracing {
+            sampler: Arc::new(sampler),
+        }
+    }
+}
+
 impl<E: Endpoint> Middleware<E> for Tracing {
     type Output = TracingEndpoint<E>;
 
     fn transform(&self, ep: E) -> Self::Output {
-        TracingEndpoint { inner: ep }
+        TracingEndpoint {
+            inner: ep,
+            sampler: None,
+        }
+    }
+}
+
+/// Middleware for [`tracing`](https://crates.io/crates/tracing) with a
+/// [`Sampler`], created by [`Tracing::sampler`].
+pub struct SampledTracing {
+    sampler: Arc<Sampler>,
+}
+
+impl<E: Endpoint> Middleware<E> for SampledTracing {
+    type Output = TracingEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TracingEndpoint {
+            inner: ep,
+            sampler: Some(self.sampler.clone()),
+        }
     }
 }
 
 /// Endpoint for the `Tracing` middleware.
 pub struct TracingEndpoint<E> {
     inner: E,
+    sampler: Option<Arc<Sampler>>,
 }
 
-impl<E: Endpoint> Endpoint for TracingEndpoint<E> {
-    type Output = Response;
+/// The fields of the request span.
+struct RequestInfo {
+    remote_addr: String,
+    version: http::Version,
+    method: http::Method,
+    uri: http::Uri,
+    #[cfg(feature = "requestid")]
+    request_id: Option<crate::middleware::requestid::ReqId>,
+    path_pattern: Option<Arc<str>>,
+}
 
-    async fn call(&self, req: Request) -> Result<Self::Output> {
-        let remote_addr = RealIp::from_request_without_body(&req)
+impl RequestInfo {
+    async fn new(req: &Request) -> Self {
+        let remote_addr = RealIp::from_request_without_body(req)
             .await
             .ok()
             .and_then(|real_ip| real_ip.0)
             .map(|addr| addr.to_string())
             .unwrap_or_else(|| req.remote_addr().to_string());
 
+        Self {
+            remote_addr,
+            version: req.version(),
+            method: req.method().clone(),
+            uri: req.original_uri().clone(),
+            #[cfg(feature = "requestid")]
+            request_id: req
+                .extensions()
+                .get::<crate::middleware::requestid::ReqId>()
+                .cloned(),
+            path_pattern: req
+                .data::<PathPattern>()
+                .map(|path_pattern| path_pattern.0.clone()),
+        }
+    }
+
+    fn span(&self) -> Span {
         #[cfg(not(feature = "requestid"))]
         let span = tracing::span!(
             target: module_path!(),
             Level::INFO,
             "request",
-            remote_addr = %remote_addr,
-            version = ?req.version(),
-            method = %req.method(),
-            uri = %req.original_uri(),
+            remote_addr = %self.remote_addr,
+            version = ?self.version,
+            method = %self.method,
+            uri = %self.uri,
         );
         #[cfg(feature = "requestid")]
         let span = {
-            req.extensions()
-                .get::<crate::middleware::requestid::ReqId>()
-                .map_or_else(
-                    || {
-                        tracing::span!(
-                            target: module_path!(),
-                            Level::INFO,
-                            "request",
-                            remote_addr = %remote_addr,
-                            version = ?req.version(),
-                            method = %req.method(),
-                            uri = %req.original_uri(),
-                        )
-                    },
-                    |request_id| {
-                        tracing::span!(
-                            target: module_path!(),
-                            Level::INFO,
-                            "request",
-                            remote_addr = %remote_addr,
-                            version = ?req.version(),
-                            method = %req.method(),
-                            uri = %req.original_uri(),
-                            %request_id
-                        )
-                    },
-                )
+            self.request_id.as_ref().map_or_else(
+                || {
+                    tracing::span!(
+                        target: module_path!(),
+                        Level::INFO,
+                        "request",
+                        remote_addr = %self.remote_addr,
+                        version = ?self.version,
+                        method = %self.method,
+                        uri = %self.uri,
+                    )
+                },
+                |request_id| {
+                    tracing::span!(
+                        target: module_path!(),
+                        Level::INFO,
+                        "request",
+                        remote_addr = %self.remote_addr,
+                        version = ?self.version,
+                        method = %self.method,
+                        uri = %self.uri,
+                        %request_id
+                    )
+                },
+            )
         };
 
-        if let Some(path_pattern) = req.data::<PathPattern>() {
-            span.record("path_pattern", path_pattern.0.as_ref());
+        if let Some(path_pattern) = &self.path_pattern {
+            span.record("path_pattern", path_pattern.as_ref());
+        }
+        span
+    }
+}
+
+fn log_result(res: &Result<Response>, duration: Duration) {
+    match res {
+        Ok(resp) => {
+            tracing::info!(
+                status = %resp.status(),
+                duration = ?duration,
+                "response"
+            );
         }
+        Err(err) => {
+            tracing::info!(
+                status = %err.status(),
+                error = %err,
+                root_cause = %err.root_cause(),
+                location = %err.location(),
+                duration = ?duration,
+                "error"
+            );
+        }
+    }
+}
 
-        async move {
-            let now = Instant::now();
-            let res = self.inner.call(req).await;
-            let duration = now.elapsed();
-
-            match res {
-                Ok(resp) => {
-                    let resp = resp.into_response();
-                    tracing::info!(
-                        status = %resp.status(),
-                        duration = ?duration,
-                        "response"
-                    );
-                    Ok(resp)
-                }
-                Err(err) => {
-                    tracing::info!(
-                        status = %err.status(),
-                        error = %err,
-                        duration = ?duration,
-                        "error"
-                    );
-                    Err(err)
-                }
+impl<E: Endpoint> Endpoint for TracingEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let info = RequestInfo::new(&req).await;
+        let clock = SharedClock::from_request(&req);
+        let sampled = self
+            .sampler
+            .as_ref()
+            .is_none_or(|sampler| sampler.should_sample(req.uri().path()));
+
+        if !sampled {
+            let now = clock.now();
+            let res = self.inner.call(req).await.map(IntoResponse::into_response);
+            let duration = clock.now().saturating_duration_since(now);
+
+            if self
+                .sampler
+                .as_ref()
+                .is_some_and(|sampler| sampler.should_sample_result(&res))
+            {
+                info.span().in_scope(|| log_result(&res, duration));
             }
+            return res;
         }
-        .instrument(span)
+
+        async move {
+            let now = clock.now();
+            let res = self.inner.call(req).await.map(IntoResponse::into_response);
+            let duration = clock.now().saturating_duration_since(now);
+            log_result(&res, duration);
+            res
+        }
+        .instrument(info.span())
         .await
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tracing::{
+        Event, Metadata, Subscriber,
+        span::{Attributes, Id, Record},
+    };
+
+    use super::*;
+    use crate::{
+        EndpointExt, Error, Route, endpoint::make_sync, get, http::StatusCode, test::TestClient,
+    };
+
+    #[derive(Clone, Default)]
+    struct CountSpans(Arc<AtomicUsize>);
+
+    impl Subscriber for CountSpans {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            if attrs.metadata().name() == "request" {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn sampler() {
+        let spans = CountSpans::default();
+        let _guard = tracing::subscriber::set_default(spans.clone());
+
+        let app = Route::new()
+            .at("/ok", get(make_sync(|_| "ok")))
+            .at(
+                "/error",
+                get(make_sync(|_| {
+                    Err::<(), _>(Error::from_status(StatusCode::INTERNAL_SERVER_ERROR))
+                })),
+            )
+            .at("/traced", get(make_sync(|_| "ok")))
+            .with(Tracing.sampler(Sampler::ratio(0.0).route("/traced", 1.0)));
+        let cli = TestClient::new(app);
+
+        cli.get("/ok").send().await.assert_status_is_ok();
+        assert_eq!(spans.0.load(Ordering::SeqCst), 0);
+
+        cli.get("/traced").send().await.assert_status_is_ok();
+        assert_eq!(spans.0.load(Ordering::SeqCst), 1);
+
+        cli.get("/error")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(spans.0.load(Ordering::SeqCst), 2);
+    }
 }
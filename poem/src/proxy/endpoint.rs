@@ -0,0 +1,736 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Once, Weak},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::TryFutureExt;
+use http::{
+    HeaderMap, HeaderName, HeaderValue, StatusCode, Uri, Version,
+    header::{self, CONNECTION, HOST, TE, UPGRADE},
+};
+use http_body_util::BodyExt;
+use hyper::body::{Body as HttpBody, Frame, SizeHint};
+use hyper_util::{
+    client::legacy::Client,
+    rt::{TokioExecutor, TokioIo},
+};
+#[cfg(feature = "client-rustls")]
+use tokio_rustls::rustls::ClientConfig;
+
+use super::upstream::{HealthCheck, Upstream, UpstreamGuard, UpstreamPool};
+use crate::{
+    Addr, Body, Endpoint, OnUpgrade, Request, Response, Result,
+    body::BoxBody,
+    connector::{Connector, ConnectorConfig},
+    error::ProxyError,
+};
+
+const HOP_BY_HOP_HEADERS: [HeaderName; 9] = [
+    header::CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    HeaderName::from_static("proxy-connection"),
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+/// An endpoint that forwards requests to upstream servers.
+///
+/// The path and query of the incoming request are appended to the base URI
+/// of the selected upstream, so when nested under a prefix, only the
+/// remaining part of the path is forwarded.
+///
+/// By default, the `Host` header is rewritten to the upstream authority and
+/// `X-Forwarded-For`, `X-Forwarded-Host` and `X-Forwarded-Proto` headers are
+/// added.
+///
+/// Upgrade requests (e.g. WebSocket) are forwarded with their `Upgrade`
+/// header, and if the upstream switches protocols, the upgraded connections
+/// on both sides are tunneled to each other until one of them is closed.
+///
+/// # Errors
+///
+/// - [`ProxyError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{Route, proxy::ProxyEndpoint};
+///
+/// let app = Route::new().nest("/api", ProxyEndpoint::new("http://127.0.0.1:8080/v1"));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
+pub struct ProxyEndpoint {
+    pool: Arc<UpstreamPool>,
+    client: Client<Connector, BoxBody>,
+    connector: ConnectorConfig,
+    preserve_host: bool,
+    forwarded_headers: bool,
+    http2_only: bool,
+    timeout: Option<Duration>,
+    health_check_started: Once,
+}
+
+impl ProxyEndpoint {
+    /// Create a proxy endpoint for an upstream or an [`UpstreamPool`].
+    pub fn new(upstreams: impl Into<UpstreamPool>) -> Self {
+        let connector = ConnectorConfig::default();
+        Self {
+            pool: Arc::new(upstreams.into()),
+            client: build_client(&connector, false),
+            connector,
+            preserve_host: false,
+            forwarded_headers: true,
+            http2_only: false,
+            timeout: None,
+            health_check_started: Once::new(),
+        }
+    }
+
+    /// Forward the original `Host` header instead of replacing it with the
+    /// upstream authority.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn preserve_host(self, value: bool) -> Self {
+        Self {
+            preserve_host: value,
+            ..self
+        }
+    }
+
+    /// Specifies whether the `X-Forwarded-*` headers should be added to the
+    /// upstream request.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn forwarded_headers(self, value: bool) -> Self {
+        Self {
+            forwarded_headers: value,
+            ..self
+        }
+    }
+
+    /// Use HTTP/2 with prior knowledge (h2c) to connect to the upstreams.
+    ///
+    /// This is required to proxy gRPC services, which rely on HTTP/2
+    /// streaming and trailers.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn http2_only(self, value: bool) -> Self {
+        Self {
+            client: build_client(&self.connector, value),
+            http2_only: value,
+            ..self
+        }
+    }
+
+    /// Sets the TLS configuration used to connect to the `https` upstreams.
+    ///
+    /// Default is to trust the Mozilla root certificates.
+    #[cfg(feature = "client-rustls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "client-rustls")))]
+    #[must_use]
+    pub fn tls_config(self, config: ClientConfig) -> Self {
+        let connector = ConnectorConfig {
+            tls_config: Some(Arc::new(config)),
+        };
+        Self {
+            client: build_client(&connector, self.http2_only),
+            connector,
+            ..self
+        }
+    }
+
+    /// Sets the timeout for receiving the response headers from the upstream.
+    ///
+    /// Default is no timeout.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    fn start_health_check(&self) {
+        if let Some(health_check) = &self.pool.health_check {
+            self.health_check_started.call_once(|| {
+                tokio::spawn(health_check_loop(
+                    Arc::downgrade(&self.pool),
+                    self.client.clone(),
+                    health_check.clone(),
+                ));
+            });
+        }
+    }
+
+    fn prepare_headers(&self, req: &mut Request, upgrade: Option<HeaderValue>) {
+        let host = req.headers().get(HOST).cloned().or_else(|| {
+            req.original_uri()
+                .authority()
+                .and_then(|authority| HeaderValue::from_str(authority.as_str()).ok())
+        });
+        let remote_addr = req.remote_addr().0.clone();
+        let proto = HeaderValue::from_str(req.scheme().as_str()).ok();
+        let te_trailers = req
+            .headers()
+            .get_all(TE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .any(|value| value.split(',').any(|v| v.trim() == "trailers"));
+        let headers = req.headers_mut();
+
+        remove_hop_by_hop_headers(headers);
+        if let Some(upgrade) = upgrade {
+            headers.insert(CONNECTION, HeaderValue::from_static("upgrade"));
+            headers.insert(UPGRADE, upgrade);
+        }
+        if te_trailers {
+            // Required by gRPC, and the only value allowed by HTTP/2.
+            headers.insert(TE, HeaderValue::from_static("trailers"));
+        }
+
+        if self.forwarded_headers {
+            if let Addr::SocketAddr(addr) = remote_addr {
+                let ip = addr.ip().to_string();
+                let value = match headers.get(&X_FORWARDED_FOR).and_then(|v| v.to_str().ok()) {
+                    Some(prev) => format!("{prev}, {ip}"),
+                    None => ip,
+                };
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    headers.insert(X_FORWARDED_FOR, value);
+                }
+            }
+            if let Some(host) = &host {
+                headers.insert(X_FORWARDED_HOST, host.clone());
+            }
+            if let Some(proto) = proto {
+                headers.insert(X_FORWARDED_PROTO, proto);
+            }
+        }
+
+        if self.preserve_host {
+            if let Some(host) = host {
+                headers.insert(HOST, host);
+            }
+        } else {
+            // The client fills it with the upstream authority.
+            headers.remove(HOST);
+        }
+    }
+
+    async fn send(
+        &self,
+        upstream: &Upstream,
+        req: hyper::Request<BoxBody>,
+    ) -> Result<hyper::Response<hyper::body::Incoming>, ProxyError> {
+        let fut = self.client.request(req);
+        let res = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, fut)
+                .await
+                .map_err(|_| ProxyError::Timeout)?,
+            None => fut.await,
+        };
+
+        res.map_err(|err| {
+            if err.is_connect() {
+                self.pool.report_failure(upstream);
+            }
+            ProxyError::Upstream(err.to_string())
+        })
+    }
+}
+
+impl Endpoint for ProxyEndpoint {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        self.start_health_check();
+
+        let guard = self
+            .pool
+            .select(req.remote_addr())
+            .ok_or(ProxyError::NoAvailableUpstream)?;
+        let upstream = guard.upstream();
+
+        let uri = upstream_uri(&upstream.uri, req.uri())?;
+        let (upgrade, on_upgrade) = match upgrade_protocol(req.headers()) {
+            Some(protocol) if !self.http2_only => match req.take_upgrade() {
+                Ok(on_upgrade) => (Some(protocol), Some(on_upgrade)),
+                Err(_) => (None, None),
+            },
+            _ => (None, None),
+        };
+        self.prepare_headers(&mut req, upgrade);
+        *req.uri_mut() = uri;
+        req.set_version(if self.http2_only {
+            Version::HTTP_2
+        } else {
+            Version::HTTP_11
+        });
+
+        let mut upstream_req: hyper::Request<BoxBody> = req.into();
+        upstream_req.extensions_mut().clear();
+
+        let mut resp = self.send(upstream, upstream_req).await?;
+        if resp.status() == StatusCode::SWITCHING_PROTOCOLS {
+            let on_upgrade = on_upgrade.ok_or_else(|| {
+                ProxyError::Upstream("unexpected switching protocols response".to_string())
+            })?;
+            let upgrade = resp.headers().get(UPGRADE).cloned();
+            let upstream_upgrade = hyper::upgrade::on(&mut resp);
+            tokio::spawn(tunnel(on_upgrade, upstream_upgrade, guard));
+
+            remove_hop_by_hop_headers(resp.headers_mut());
+            if let Some(upgrade) = upgrade {
+                resp.headers_mut()
+                    .insert(CONNECTION, HeaderValue::from_static("upgrade"));
+                resp.headers_mut().insert(UPGRADE, upgrade);
+            }
+            let (parts, _) = resp.into_parts();
+            return Ok(hyper::Response::from_parts(parts, Body::empty().0).into());
+        }
+
+        remove_hop_by_hop_headers(resp.headers_mut());
+        Ok(resp
+            .map(|body| GuardedBody {
+                inner: body.map_err(std::io::Error::other),
+                _guard: guard,
+            })
+            .into())
+    }
+}
+
+fn build_client(connector: &ConnectorConfig, http2_only: bool) -> Client<Connector, BoxBody> {
+    Client::builder(TokioExecutor::new())
+        .http2_only(http2_only)
+        .build(connector.connector(http2_only))
+}
+
+pin_project_lite::pin_project! {
+    /// Keeps the upstream marked as busy until the response body is dropped.
+    struct GuardedBody<B> {
+        #[pin]
+        inner: B,
+        _guard: UpstreamGuard,
+    }
+}
+
+impl<B: HttpBody> HttpBody for GuardedBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    #[inline]
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        self.project().inner.poll_frame(cx)
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Returns the requested protocol if this is an upgrade request.
+fn upgrade_protocol(headers: &HeaderMap) -> Option<HeaderValue> {
+    let connection_upgrade = headers
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim().eq_ignore_ascii_case("upgrade"));
+    if connection_upgrade {
+        headers.get(UPGRADE).cloned()
+    } else {
+        None
+    }
+}
+
+async fn tunnel(downstream: OnUpgrade, upstream: hyper::upgrade::OnUpgrade, _guard: UpstreamGuard) {
+    let res = async {
+        let (mut downstream, upstream) = tokio::try_join!(
+            downstream.map_err(std::io::Error::other),
+            upstream.map_err(std::io::Error::other)
+        )?;
+        tokio::io::copy_bidirectional(&mut downstream, &mut TokioIo::new(upstream)).await
+    }
+    .await;
+
+    if let Err(err) = res {
+        tracing::debug!(error = %err, "proxy tunnel closed");
+    }
+}
+
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let connection_headers = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect::<Vec<_>>();
+
+    for name in connection_headers.iter().chain(&HOP_BY_HOP_HEADERS) {
+        headers.remove(name);
+    }
+}
+
+fn upstream_uri(base: &Uri, uri: &Uri) -> Result<Uri, ProxyError> {
+    let mut path_and_query = format!("{}{}", base.path().trim_end_matches('/'), uri.path());
+    if let Some(query) = uri.query() {
+        path_and_query.push('?');
+        path_and_query.push_str(query);
+    }
+
+    let mut builder = Uri::builder().path_and_query(path_and_query);
+    if let Some(scheme) = base.scheme() {
+        builder = builder.scheme(scheme.clone());
+    }
+    if let Some(authority) = base.authority() {
+        builder = builder.authority(authority.clone());
+    }
+    builder
+        .build()
+        .map_err(|err| ProxyError::InvalidUri(err.to_string()))
+}
+
+async fn health_check_loop(
+    pool: Weak<UpstreamPool>,
+    client: Client<Connector, BoxBody>,
+    health_check: HealthCheck,
+) {
+    let Ok(path) = health_check.path.parse::<Uri>() else {
+        tracing::error!(path = %health_check.path, "invalid health check path");
+        return;
+    };
+    let mut interval = tokio::time::interval(health_check.interval);
+
+    loop {
+        interval.tick().await;
+        let Some(pool) = pool.upgrade() else {
+            break;
+        };
+
+        let checks = pool.upstreams.iter().map(|upstream| {
+            let client = &client;
+            let path = &path;
+            async move {
+                let healthy = match upstream_uri(&upstream.uri, path) {
+                    Ok(uri) => {
+                        let req = hyper::Request::get(uri)
+                            .body(Body::empty().into())
+                            .expect("valid request");
+                        matches!(
+                            tokio::time::timeout(health_check.timeout, client.request(req)).await,
+                            Ok(Ok(resp)) if resp.status().is_success()
+                        )
+                    }
+                    Err(_) => false,
+                };
+
+                if healthy != upstream.is_healthy() {
+                    if healthy {
+                        tracing::info!(upstream = %upstream.uri, "upstream is healthy");
+                    } else {
+                        tracing::warn!(upstream = %upstream.uri, "upstream is unhealthy");
+                    }
+                }
+                upstream.set_healthy(healthy);
+            }
+        });
+        futures_util::future::join_all(checks).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{
+        EndpointExt, Route, Server, handler,
+        http::StatusCode,
+        listener::{Acceptor, Listener, TcpListener},
+        proxy::LoadBalance,
+        test::TestClient,
+        web::Json,
+    };
+
+    async fn start_upstream(ep: impl Endpoint + 'static) -> String {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(ep));
+        format!("http://{addr}")
+    }
+
+    #[handler(internal)]
+    fn echo(req: &Request, body: String) -> Json<Value> {
+        Json(json!({
+            "uri": req.uri().to_string(),
+            "host": req.header("host"),
+            "x-forwarded-host": req.header("x-forwarded-host"),
+            "x-forwarded-proto": req.header("x-forwarded-proto"),
+            "keep-alive": req.header("keep-alive"),
+            "body": body,
+        }))
+    }
+
+    #[tokio::test]
+    async fn forward() {
+        let upstream = start_upstream(echo).await;
+        let authority = upstream.trim_start_matches("http://").to_string();
+        let app = Route::new().nest("/api", ProxyEndpoint::new(format!("{upstream}/v1")));
+        let cli = TestClient::new(app);
+
+        let resp = cli
+            .post("/api/users?a=1")
+            .header(HOST, "example.com")
+            .header("keep-alive", "timeout=5")
+            .body("hello")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_json(json!({
+            "uri": "/v1/users?a=1",
+            "host": authority,
+            "x-forwarded-host": "example.com",
+            "x-forwarded-proto": "http",
+            "keep-alive": null,
+            "body": "hello",
+        }))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn preserve_host() {
+        let upstream = start_upstream(echo).await;
+        let cli = TestClient::new(
+            ProxyEndpoint::new(upstream)
+                .preserve_host(true)
+                .forwarded_headers(false),
+        );
+
+        let resp = cli.get("/").header(HOST, "example.com").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_json(json!({
+            "uri": "/",
+            "host": "example.com",
+            "x-forwarded-host": null,
+            "x-forwarded-proto": null,
+            "keep-alive": null,
+            "body": "",
+        }))
+        .await;
+    }
+
+    #[tokio::test]
+    async fn upstream_status_and_headers() {
+        let upstream = start_upstream(
+            crate::endpoint::make_sync(|_| (StatusCode::IM_A_TEAPOT, "teapot"))
+                .with(crate::middleware::SetHeader::new().overriding("x-custom", "1")),
+        )
+        .await;
+        let cli = TestClient::new(ProxyEndpoint::new(upstream));
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status(StatusCode::IM_A_TEAPOT);
+        resp.assert_header("x-custom", "1");
+        resp.assert_text("teapot").await;
+    }
+
+    #[tokio::test]
+    async fn load_balance() {
+        let a = start_upstream(crate::endpoint::make_sync(|_| "a")).await;
+        let b = start_upstream(crate::endpoint::make_sync(|_| "b")).await;
+        let cli = TestClient::new(ProxyEndpoint::new(
+            UpstreamPool::new([a, b]).load_balance(LoadBalance::RoundRobin),
+        ));
+
+        cli.get("/").send().await.assert_text("a").await;
+        cli.get("/").send().await.assert_text("b").await;
+        cli.get("/").send().await.assert_text("a").await;
+    }
+
+    #[tokio::test]
+    async fn least_connections_until_body_dropped() {
+        let a = start_upstream(crate::endpoint::make_sync(|_| "a")).await;
+        let b = start_upstream(crate::endpoint::make_sync(|_| "b")).await;
+        let cli = TestClient::new(ProxyEndpoint::new(
+            UpstreamPool::new([a, b]).load_balance(LoadBalance::LeastConnections),
+        ));
+
+        let resp = cli.get("/").send().await;
+        cli.get("/").send().await.assert_text("b").await;
+        resp.assert_text("a").await;
+        cli.get("/").send().await.assert_text("a").await;
+    }
+
+    #[cfg(all(feature = "client-rustls", feature = "rustls"))]
+    #[tokio::test]
+    async fn https_upstream() {
+        let (upstream, tls_config) =
+            crate::connector::start_tls_server(crate::endpoint::make_sync(|req| {
+                format!("{} {}", req.scheme(), req.uri())
+            }))
+            .await;
+        let cli = TestClient::new(
+            Route::new().nest("/api", ProxyEndpoint::new(upstream).tls_config(tls_config)),
+        );
+
+        cli.get("/api/users")
+            .send()
+            .await
+            .assert_text("https /users")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn unavailable_upstream() {
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let cli = TestClient::new(ProxyEndpoint::new(
+            UpstreamPool::new([format!("http://{addr}")])
+                .health_check(HealthCheck::new("/").interval(Duration::from_secs(60))),
+        ));
+
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_GATEWAY);
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn health_check_recovers() {
+        let upstream = start_upstream(crate::endpoint::make_sync(|_| "ok")).await;
+        let ep = ProxyEndpoint::new(
+            UpstreamPool::new([upstream])
+                .health_check(HealthCheck::new("/health").interval(Duration::from_millis(50))),
+        );
+        ep.pool.upstreams[0].set_healthy(false);
+        let cli = TestClient::new(ep);
+
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        cli.get("/").send().await.assert_text("ok").await;
+    }
+
+    #[tokio::test]
+    async fn upgrade() {
+        #[handler(internal)]
+        fn upgrade_echo(req: &Request) -> Result<Response> {
+            let on_upgrade = req.take_upgrade()?;
+            tokio::spawn(async move {
+                if let Ok(upgraded) = on_upgrade.await {
+                    let (mut reader, mut writer) = tokio::io::split(upgraded);
+                    let _ = tokio::io::copy(&mut reader, &mut writer).await;
+                }
+            });
+            Ok(Response::builder()
+                .status(StatusCode::SWITCHING_PROTOCOLS)
+                .header(CONNECTION, "upgrade")
+                .header(UPGRADE, "echo")
+                .finish())
+        }
+
+        let upstream = start_upstream(upgrade_echo).await;
+        let proxy = start_upstream(ProxyEndpoint::new(upstream)).await;
+
+        let mut stream = tokio::net::TcpStream::connect(proxy.trim_start_matches("http://"))
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: upgrade\r\nUpgrade: echo\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        let head = String::from_utf8(head).unwrap().to_ascii_lowercase();
+        assert!(head.starts_with("http/1.1 101"));
+        assert!(head.contains("upgrade: echo"));
+
+        for msg in [&b"hello"[..], b"world"] {
+            stream.write_all(msg).await.unwrap();
+            let mut buf = vec![0; msg.len()];
+            stream.read_exact(&mut buf).await.unwrap();
+            assert_eq!(buf, msg);
+        }
+    }
+
+    #[tokio::test]
+    async fn http2_only() {
+        let upstream = start_upstream(crate::endpoint::make_sync(|req| {
+            format!("{:?} {:?}", req.version(), req.header("te"))
+        }))
+        .await;
+        let cli = TestClient::new(ProxyEndpoint::new(upstream).http2_only(true));
+
+        cli.get("/")
+            .header(TE, "trailers")
+            .send()
+            .await
+            .assert_text(r#"HTTP/2.0 Some("trailers")"#)
+            .await;
+    }
+
+    #[test]
+    fn test_upgrade_protocol() {
+        let mut headers = HeaderMap::new();
+        headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+        assert_eq!(upgrade_protocol(&headers), None);
+        headers.insert(CONNECTION, HeaderValue::from_static("keep-alive, Upgrade"));
+        assert_eq!(
+            upgrade_protocol(&headers),
+            Some(HeaderValue::from_static("websocket"))
+        );
+    }
+
+    #[test]
+    fn test_upstream_uri() {
+        let base = "http://127.0.0.1:8080/v1/".parse::<Uri>().unwrap();
+        assert_eq!(
+            upstream_uri(&base, &"/users?id=1".parse().unwrap()).unwrap(),
+            "http://127.0.0.1:8080/v1/users?id=1"
+        );
+
+        let base = "http://127.0.0.1:8080".parse::<Uri>().unwrap();
+        assert_eq!(
+            upstream_uri(&base, &"/".parse().unwrap()).unwrap(),
+            "http://127.0.0.1:8080/"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Reverse proxy.
+//!
+//! [`ProxyEndpoint`] forwards requests to one or more upstream servers,
+//! streaming the request and response bodies in both directions.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use poem::{
+//!     Route, Server,
+//!     listener::TcpListener,
+//!     proxy::{HealthCheck, LoadBalance, ProxyEndpoint, UpstreamPool},
+//! };
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), std::io::Error> {
+//!     let pool = UpstreamPool::new(["http://127.0.0.1:8001", "http://127.0.0.1:8002"])
+//!         .load_balance(LoadBalance::LeastConnections)
+//!         .health_check(HealthCheck::new("/health").interval(Duration::from_secs(5)));
+//!
+//!     let app = Route::new().nest("/api", ProxyEndpoint::new(pool));
+//!     Server::new(TcpListener::bind("0.0.0.0:3000"))
+//!         .run(app)
+//!         .await
+//! }
+//! ```
+
+mod endpoint;
+mod upstream;
+
+pub use endpoint::ProxyEndpoint;
+pub use upstream::{HealthCheck, LoadBalance, UpstreamPool};
@@ -0,0 +1,309 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use http::Uri;
+
+use crate::{Addr, connector::supports_scheme};
+
+/// The strategy used by [`UpstreamPool`] to pick an upstream for each
+/// request.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum LoadBalance {
+    /// Pick the upstreams in turn.
+    #[default]
+    RoundRobin,
+    /// Pick the upstream with the fewest in-flight requests.
+    LeastConnections,
+    /// Pick the upstream by hashing the client address, so that requests
+    /// from the same client always reach the same upstream.
+    IpHash,
+}
+
+/// Active health check for the upstreams of an [`UpstreamPool`].
+///
+/// Each upstream is periodically requested with `GET <path>`, and is
+/// considered healthy if it responds with a `2xx` status code within the
+/// timeout.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pub(crate) path: String,
+    pub(crate) interval: Duration,
+    pub(crate) timeout: Duration,
+}
+
+impl HealthCheck {
+    /// Create a health check that requests the specified path.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(3),
+        }
+    }
+
+    /// Sets the interval between two checks.
+    ///
+    /// Default is `10s`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    #[must_use]
+    pub fn interval(self, interval: Duration) -> Self {
+        assert!(
+            !interval.is_zero(),
+            "the health check interval must be non-zero"
+        );
+        Self { interval, ..self }
+    }
+
+    /// Sets the timeout of each check.
+    ///
+    /// Default is `3s`.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+}
+
+pub(crate) struct Upstream {
+    pub(crate) uri: Uri,
+    healthy: AtomicBool,
+    connections: AtomicUsize,
+}
+
+impl Upstream {
+    #[inline]
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn set_healthy(&self, healthy: bool) {
+        self.healthy.store(healthy, Ordering::Relaxed);
+    }
+}
+
+/// Keeps an upstream marked as busy until it is dropped.
+pub(crate) struct UpstreamGuard(Arc<Upstream>);
+
+impl UpstreamGuard {
+    #[inline]
+    pub(crate) fn upstream(&self) -> &Upstream {
+        &self.0
+    }
+}
+
+impl Drop for UpstreamGuard {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A set of upstream servers that requests are balanced across.
+///
+/// The `https` upstreams require the `client-rustls` feature.
+///
+/// # Panics
+///
+/// Panics if any of the upstream URIs is invalid.
+pub struct UpstreamPool {
+    pub(crate) upstreams: Vec<Arc<Upstream>>,
+    load_balance: LoadBalance,
+    pub(crate) health_check: Option<HealthCheck>,
+    next: AtomicUsize,
+}
+
+impl UpstreamPool {
+    /// Create an upstream pool from a list of base URIs, for example
+    /// `http://127.0.0.1:8080/api`.
+    pub fn new<I, T>(upstreams: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let upstreams = upstreams
+            .into_iter()
+            .map(|uri| {
+                let uri = uri.as_ref();
+                let parsed = uri
+                    .parse::<Uri>()
+                    .unwrap_or_else(|err| panic!("invalid upstream uri `{uri}`: {err}"));
+                assert!(
+                    parsed.scheme_str().is_some_and(supports_scheme)
+                        && parsed.authority().is_some(),
+                    "invalid upstream uri `{uri}`: expect `http://host[:port][/path]`"
+                );
+                Arc::new(Upstream {
+                    uri: parsed,
+                    healthy: AtomicBool::new(true),
+                    connections: AtomicUsize::new(0),
+                })
+            })
+            .collect();
+
+        Self {
+            upstreams,
+            load_balance: LoadBalance::default(),
+            health_check: None,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sets the load balancing strategy.
+    ///
+    /// Default is [`LoadBalance::RoundRobin`].
+    #[must_use]
+    pub fn load_balance(self, load_balance: LoadBalance) -> Self {
+        Self {
+            load_balance,
+            ..self
+        }
+    }
+
+    /// Enable active health checks.
+    ///
+    /// When enabled, an upstream that fails to accept a connection is also
+    /// taken out of rotation until the next successful check.
+    #[must_use]
+    pub fn health_check(self, health_check: HealthCheck) -> Self {
+        Self {
+            health_check: Some(health_check),
+            ..self
+        }
+    }
+
+    pub(crate) fn select(&self, remote_addr: &Addr) -> Option<UpstreamGuard> {
+        let healthy = self
+            .upstreams
+            .iter()
+            .filter(|upstream| upstream.is_healthy())
+            .collect::<Vec<_>>();
+        if healthy.is_empty() {
+            return None;
+        }
+
+        let upstream = match self.load_balance {
+            LoadBalance::RoundRobin => {
+                healthy[self.next.fetch_add(1, Ordering::Relaxed) % healthy.len()]
+            }
+            LoadBalance::LeastConnections => healthy
+                .iter()
+                .min_by_key(|upstream| upstream.connections.load(Ordering::Relaxed))
+                .copied()?,
+            LoadBalance::IpHash => {
+                let mut hasher = DefaultHasher::new();
+                match remote_addr {
+                    Addr::SocketAddr(addr) => addr.ip().hash(&mut hasher),
+                    addr => addr.to_string().hash(&mut hasher),
+                }
+                healthy[hasher.finish() as usize % healthy.len()]
+            }
+        };
+
+        upstream.connections.fetch_add(1, Ordering::Relaxed);
+        Some(UpstreamGuard(upstream.clone()))
+    }
+
+    pub(crate) fn report_failure(&self, upstream: &Upstream) {
+        // Without active checks nothing would bring the upstream back.
+        if self.health_check.is_some() {
+            upstream.set_healthy(false);
+        }
+    }
+}
+
+impl From<&str> for UpstreamPool {
+    fn from(uri: &str) -> Self {
+        UpstreamPool::new([uri])
+    }
+}
+
+impl From<String> for UpstreamPool {
+    fn from(uri: String) -> Self {
+        UpstreamPool::new([uri])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+
+    fn addr(s: &str) -> Addr {
+        Addr::SocketAddr(s.parse::<SocketAddr>().unwrap())
+    }
+
+    fn select(pool: &UpstreamPool, remote_addr: &Addr) -> String {
+        pool.select(remote_addr)
+            .unwrap()
+            .upstream()
+            .uri
+            .authority()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn round_robin() {
+        let pool = UpstreamPool::new(["http://a", "http://b", "http://c"]);
+        let remote_addr = addr("127.0.0.1:1000");
+        assert_eq!(select(&pool, &remote_addr), "a");
+        assert_eq!(select(&pool, &remote_addr), "b");
+        assert_eq!(select(&pool, &remote_addr), "c");
+        assert_eq!(select(&pool, &remote_addr), "a");
+
+        pool.upstreams[1].set_healthy(false);
+        assert_eq!(select(&pool, &remote_addr), "a");
+        assert_eq!(select(&pool, &remote_addr), "c");
+    }
+
+    #[test]
+    fn least_connections() {
+        let pool =
+            UpstreamPool::new(["http://a", "http://b"]).load_balance(LoadBalance::LeastConnections);
+        let remote_addr = addr("127.0.0.1:1000");
+
+        let guard = pool.select(&remote_addr).unwrap();
+        assert_eq!(guard.upstream().uri.authority().unwrap(), "a");
+        assert_eq!(select(&pool, &remote_addr), "b");
+        drop(guard);
+        assert_eq!(select(&pool, &remote_addr), "a");
+    }
+
+    #[test]
+    fn ip_hash() {
+        let pool = UpstreamPool::new(["http://a", "http://b", "http://c"])
+            .load_balance(LoadBalance::IpHash);
+        let first = select(&pool, &addr("10.0.0.1:1000"));
+        for port in 1001..1010 {
+            assert_eq!(select(&pool, &addr(&format!("10.0.0.1:{port}"))), first);
+        }
+    }
+
+    #[test]
+    fn no_healthy_upstream() {
+        let pool = UpstreamPool::new(["http://a"]).health_check(HealthCheck::new("/health"));
+        pool.report_failure(&pool.upstreams[0]);
+        assert!(pool.select(&addr("127.0.0.1:1000")).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_uri() {
+        UpstreamPool::new(["127.0.0.1:8080"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_health_check_interval() {
+        let _ = HealthCheck::new("/health").interval(Duration::ZERO);
+    }
+}
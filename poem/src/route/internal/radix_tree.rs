@@ -194,6 +194,23 @@ struct Node<T> {
 }
 
 impl<T> Node<T> {
+    fn for_each(&self, f: &mut impl FnMut(&T)) {
+        if let Some(data) = &self.data {
+            f(&data.data);
+        }
+        for child in &self.children {
+            child.for_each(f);
+        }
+        for child in self
+            .param_children
+            .iter()
+            .chain(&self.regex_children)
+            .chain(&self.catch_all_child)
+        {
+            child.for_each(f);
+        }
+    }
+
     fn find_static_child(&self, prefix: u8) -> Option<usize> {
         (0..self.indices.len()).find(|&i| self.indices[i] == prefix)
     }
@@ -505,6 +522,11 @@ impl<T> RadixTree<T> {
         }
     }
 
+    /// Calls `f` with the data of all the nodes.
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&T)) {
+        self.root.for_each(&mut f);
+    }
+
     pub(crate) fn matches(&self, path: &str) -> Option<Matches<'_, T>> {
         if path.is_empty() {
             return None;
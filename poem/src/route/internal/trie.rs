@@ -76,6 +76,22 @@ impl<T> Trie<T> {
         }
     }
 
+    /// Calls `f` with the data of all the nodes.
+    pub(crate) fn for_each(&self, mut f: impl FnMut(&T)) {
+        Self::internal_for_each(&self.root, &mut f);
+    }
+
+    fn internal_for_each(node: &Node<T>, f: &mut impl FnMut(&T)) {
+        node.data.iter().chain(&node.star_child).for_each(&mut *f);
+        for child in node
+            .named_children
+            .values()
+            .chain(node.plus_child.as_deref())
+        {
+            Self::internal_for_each(child, f);
+        }
+    }
+
     pub(crate) fn matches(&self, domain: &str) -> Option<&T> {
         if domain.is_empty() {
             return self.root.star_child.as_ref();
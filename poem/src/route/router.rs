@@ -4,7 +4,7 @@ use regex::Regex;
 
 use crate::{
     Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response, Result,
-    endpoint::BoxEndpoint,
+    endpoint::{BoxEndpoint, ShutdownHooks},
     error::{NotFoundError, ParsePathError, RouteError},
     http::{Uri, uri::PathAndQuery},
     route::{check_result, internal::radix_tree::RadixTree},
@@ -271,6 +271,10 @@ impl Route {
                 req.set_data(PathPrefix(self.prefix_for_path_pattern));
                 Ok(self.inner.call(req).await?.into_response())
             }
+
+            fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+                self.inner.collect_shutdown_hooks(hooks);
+            }
         }
 
         assert!(
@@ -370,6 +374,10 @@ impl Endpoint for Route {
             None => Err(NotFoundError.into()),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.tree.for_each(|ep| ep.collect_shutdown_hooks(hooks));
+    }
 }
 
 fn normalize_path(path: &str) -> String {
@@ -1,6 +1,6 @@
 use crate::{
     Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result,
-    endpoint::BoxEndpoint,
+    endpoint::{BoxEndpoint, ShutdownHooks},
     error::{NotFoundError, RouteError},
     http::header,
     route::{check_result, internal::trie::Trie},
@@ -101,6 +101,10 @@ impl Endpoint for RouteDomain {
             None => Err(NotFoundError.into()),
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.tree.for_each(|ep| ep.collect_shutdown_hooks(hooks));
+    }
 }
 
 #[cfg(test)]
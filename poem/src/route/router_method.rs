@@ -3,8 +3,10 @@ use std::future::Future;
 use futures_util::{FutureExt, future::Either};
 
 use crate::{
-    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result, endpoint::BoxEndpoint,
-    error::MethodNotAllowedError, http::Method,
+    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result,
+    endpoint::{BoxEndpoint, ShutdownHooks},
+    error::MethodNotAllowedError,
+    http::Method,
 };
 
 /// Routing object for HTTP methods
@@ -194,6 +196,12 @@ impl Endpoint for RouteMethod {
             }
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        for (_, ep) in &self.methods {
+            ep.collect_shutdown_hooks(hooks);
+        }
+    }
 }
 
 /// A helper function, similar to `RouteMethod::new().get(ep)`.
@@ -1,7 +1,8 @@
 use http::uri::Scheme;
 
 use crate::{
-    Endpoint, EndpointExt, IntoEndpoint, Request, Response, endpoint::BoxEndpoint,
+    Endpoint, EndpointExt, IntoEndpoint, Request, Response,
+    endpoint::{BoxEndpoint, ShutdownHooks},
     error::NotFoundError,
 };
 
@@ -89,4 +90,13 @@ impl Endpoint for RouteScheme {
             },
         }
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        for (_, ep) in &self.schemes {
+            ep.collect_shutdown_hooks(hooks);
+        }
+        if let Some(ep) = &self.fallback {
+            ep.collect_shutdown_hooks(hooks);
+        }
+    }
 }
@@ -22,15 +22,30 @@ use tokio::{
     sync::{Notify, oneshot},
     time::Duration,
 };
-use tokio_util::sync::CancellationToken;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use crate::{
     Endpoint, EndpointExt, IntoEndpoint, Response,
-    endpoint::{DynEndpoint, ToDynEndpoint},
+    endpoint::{
+        DynEndpoint, ShutdownHook, ShutdownHooks, ToDynEndpoint, boxed_hook, take_uncollected_hooks,
+    },
     listener::{Acceptor, AcceptorExt, Listener},
+    task::TaskManager,
     web::{LocalAddr, RemoteAddr},
 };
 
+/// The graceful shutdown signal of a server, which is added to the
+/// extensions of the requests.
+///
+/// The upgraded connections (e.g. WebSocket) outlive the HTTP connections, so
+/// they are spawned on the `tracker` to let the server wait for them.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "websocket"), allow(dead_code))]
+pub(crate) struct GracefulShutdown {
+    pub(crate) token: CancellationToken,
+    pub(crate) tracker: TaskTracker,
+}
+
 enum Either<L, A> {
     Listener(L),
     Acceptor(A),
@@ -45,6 +60,12 @@ pub struct Server<L, A> {
     http2_max_concurrent_streams: Option<u32>,
     http2_max_pending_accept_reset_streams: Option<u32>,
     http2_max_header_list_size: u32,
+    http2_keep_alive_interval: Option<Duration>,
+    http2_keep_alive_timeout: Option<Duration>,
+    http2_initial_stream_window_size: Option<u32>,
+    http2_initial_connection_window_size: Option<u32>,
+    tasks: Option<TaskManager>,
+    shutdown_hooks: Vec<ShutdownHook>,
 }
 
 impl<L: Listener> Server<L, Infallible> {
@@ -57,6 +78,12 @@ impl<L: Listener> Server<L, Infallible> {
             http2_max_concurrent_streams: None,
             http2_max_pending_accept_reset_streams: Some(20),
             http2_max_header_list_size: 16384,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: None,
+            http2_initial_stream_window_size: None,
+            http2_initial_connection_window_size: None,
+            tasks: None,
+            shutdown_hooks: Vec::new(),
         }
     }
 }
@@ -71,6 +98,12 @@ impl<A: Acceptor> Server<Infallible, A> {
             http2_max_concurrent_streams: None,
             http2_max_pending_accept_reset_streams: Some(20),
             http2_max_header_list_size: 16384,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: None,
+            http2_initial_stream_window_size: None,
+            http2_initial_connection_window_size: None,
+            tasks: None,
+            shutdown_hooks: Vec::new(),
         }
     }
 }
@@ -135,6 +168,84 @@ where
         }
     }
 
+    /// Sets an interval for HTTP2 Ping frames should be sent to keep a
+    /// connection alive.
+    ///
+    /// Default is `None`, which disables the keep-alive.
+    pub fn http2_keep_alive_interval(self, interval: impl Into<Option<Duration>>) -> Self {
+        Self {
+            http2_keep_alive_interval: interval.into(),
+            ..self
+        }
+    }
+
+    /// Sets a timeout for receiving an acknowledgement of the keep-alive ping.
+    ///
+    /// If the ping is not acknowledged within the timeout, the connection will
+    /// be closed. Does nothing if `http2_keep_alive_interval` is disabled.
+    ///
+    /// Default is 20 seconds.
+    pub fn http2_keep_alive_timeout(self, timeout: Duration) -> Self {
+        Self {
+            http2_keep_alive_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Sets the [`SETTINGS_INITIAL_WINDOW_SIZE`][spec] option for HTTP2
+    /// stream-level flow control.
+    ///
+    /// Passing `None` will use the default of the [`h2` crate](https://crates.io/crates/h2).
+    ///
+    /// [spec]: https://http2.github.io/http2-spec/#SETTINGS_INITIAL_WINDOW_SIZE
+    pub fn http2_initial_stream_window_size(self, size: impl Into<Option<u32>>) -> Self {
+        Self {
+            http2_initial_stream_window_size: size.into(),
+            ..self
+        }
+    }
+
+    /// Sets the max connection-level flow control for HTTP2.
+    ///
+    /// Passing `None` will use the default of the [`h2` crate](https://crates.io/crates/h2).
+    pub fn http2_initial_connection_window_size(self, size: impl Into<Option<u32>>) -> Self {
+        Self {
+            http2_initial_connection_window_size: size.into(),
+            ..self
+        }
+    }
+
+    /// Sets the background tasks that run with this server.
+    ///
+    /// The tasks start with the server, receive the shutdown signal when the
+    /// graceful shutdown is initiated, and are awaited after all connections
+    /// are closed. The [`TaskHandle`](crate::task::TaskHandle) extractor can
+    /// be used in the handlers.
+    ///
+    /// See also: [`crate::task`]
+    #[must_use]
+    pub fn tasks(self, tasks: TaskManager) -> Self {
+        Self {
+            tasks: Some(tasks),
+            ..self
+        }
+    }
+
+    /// Registers an async callback which is called when the graceful shutdown
+    /// is initiated.
+    ///
+    /// The server waits for the callbacks to complete before stopping. See
+    /// also [`EndpointExt::on_shutdown`].
+    #[must_use]
+    pub fn on_shutdown<F, Fut>(mut self, f: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown_hooks.push(boxed_hook(f));
+        self
+    }
+
     /// Run this server.
     pub async fn run<E>(self, ep: E) -> IoResult<()>
     where
@@ -156,7 +267,6 @@ where
         E: IntoEndpoint,
         E::Endpoint: 'static,
     {
-        let ep = Arc::new(ToDynEndpoint(ep.into_endpoint().map_to_response()));
         let Server {
             listener,
             name,
@@ -164,12 +274,32 @@ where
             http2_max_concurrent_streams,
             http2_max_pending_accept_reset_streams,
             http2_max_header_list_size,
+            http2_keep_alive_interval,
+            http2_keep_alive_timeout,
+            http2_initial_stream_window_size,
+            http2_initial_connection_window_size,
+            tasks,
+            mut shutdown_hooks,
         } = self;
+        let tasks = tasks.map(TaskManager::start);
+        let ep = ep.into_endpoint().map_to_response();
+        let ep: Arc<dyn DynEndpoint<Output = Response>> = match &tasks {
+            Some(tasks) => Arc::new(ToDynEndpoint(ep.data(tasks.handle()))),
+            None => Arc::new(ToDynEndpoint(ep)),
+        };
         let name = name.as_deref();
         let alive_connections = Arc::new(AtomicUsize::new(0));
         let notify = Arc::new(Notify::new());
         let timeout_token = CancellationToken::new();
         let server_graceful_shutdown_token = CancellationToken::new();
+        let upgraded_connections = TaskTracker::new();
+        let mut shutdown_hooks_handle = None;
+
+        // the hooks of the endpoint are collected now, so that they are not
+        // taken by another server which shuts down first
+        let mut endpoint_hooks = ShutdownHooks::default();
+        ep.collect_shutdown_hooks(&mut endpoint_hooks);
+        shutdown_hooks.extend(endpoint_hooks.into_inner());
 
         let mut acceptor = match listener {
             Either::Listener(listener) => listener.into_acceptor().await?.boxed(),
@@ -187,6 +317,16 @@ where
             tokio::select! {
                 _ = &mut signal => {
                     server_graceful_shutdown_token.cancel();
+                    if let Some(tasks) = &tasks {
+                        tasks.signal_shutdown();
+                    }
+                    shutdown_hooks.extend(take_uncollected_hooks());
+                    if !shutdown_hooks.is_empty() {
+                        let hooks = std::mem::take(&mut shutdown_hooks);
+                        shutdown_hooks_handle = Some(tokio::spawn(
+                            futures_util::future::join_all(hooks.into_iter().map(|hook| hook())),
+                        ));
+                    }
                     if let Some(timeout) = timeout {
                         tracing::info!(
                             name = name,
@@ -214,6 +354,10 @@ where
                         let timeout_token = timeout_token.clone();
                         let server_graceful_shutdown_token = server_graceful_shutdown_token.clone();
                         let server_graceful_shutdown_token_clone = server_graceful_shutdown_token.clone();
+                        let graceful_shutdown = GracefulShutdown {
+                            token: server_graceful_shutdown_token.clone(),
+                            tracker: upgraded_connections.clone(),
+                        };
 
                         let spawn_fut = AssertUnwindSafe(async move {
                             let serve_connection = serve_connection(ConnectionOptions{
@@ -223,10 +367,15 @@ where
                                 scheme,
                                 ep,
                                 server_graceful_shutdown_token: server_graceful_shutdown_token.clone(),
+                                graceful_shutdown,
                                 idle_connection_close_timeout: idle_timeout,
                                 http2_max_concurrent_streams,
                                 http2_max_pending_accept_reset_streams,
                                 http2_max_header_list_size,
+                                http2_keep_alive_interval,
+                                http2_keep_alive_timeout,
+                                http2_initial_stream_window_size,
+                                http2_initial_connection_window_size,
                             });
 
                             if timeout.is_some() {
@@ -265,6 +414,49 @@ where
             notify.notified().await;
         }
 
+        upgraded_connections.close();
+        if !upgraded_connections.is_empty() {
+            tracing::info!(name = name, "wait for all upgraded connections to close.");
+            if timeout.is_some() {
+                tokio::select! {
+                    _ = upgraded_connections.wait() => {}
+                    _ = timeout_token.cancelled() => {
+                        tracing::warn!(name = name, "upgraded connections did not close in time");
+                    }
+                }
+            } else {
+                upgraded_connections.wait().await;
+            }
+        }
+
+        if let Some(handle) = shutdown_hooks_handle {
+            tracing::info!(name = name, "wait for all shutdown hooks to complete.");
+            if timeout.is_some() {
+                tokio::select! {
+                    _ = handle => {}
+                    _ = timeout_token.cancelled() => {
+                        tracing::warn!(name = name, "shutdown hooks did not complete in time");
+                    }
+                }
+            } else {
+                _ = handle.await;
+            }
+        }
+
+        if let Some(tasks) = tasks {
+            tracing::info!(name = name, "wait for all background tasks to complete.");
+            if timeout.is_some() {
+                tokio::select! {
+                    _ = tasks.shutdown() => {}
+                    _ = timeout_token.cancelled() => {
+                        tracing::warn!(name = name, "background tasks did not complete in time");
+                    }
+                }
+            } else {
+                tasks.shutdown().await;
+            }
+        }
+
         tracing::info!(name = name, "server stopped");
         Ok(())
     }
@@ -388,10 +580,15 @@ struct ConnectionOptions<Io> {
     scheme: Scheme,
     ep: Arc<dyn DynEndpoint<Output = Response>>,
     server_graceful_shutdown_token: CancellationToken,
+    graceful_shutdown: GracefulShutdown,
     idle_connection_close_timeout: Option<Duration>,
     http2_max_concurrent_streams: Option<u32>,
     http2_max_pending_accept_reset_streams: Option<u32>,
     http2_max_header_list_size: u32,
+    http2_keep_alive_interval: Option<Duration>,
+    http2_keep_alive_timeout: Option<Duration>,
+    http2_initial_stream_window_size: Option<u32>,
+    http2_initial_connection_window_size: Option<u32>,
 }
 
 async fn serve_connection<Io>(opts: ConnectionOptions<Io>)
@@ -405,10 +602,15 @@ where
         scheme,
         ep,
         server_graceful_shutdown_token,
+        graceful_shutdown,
         idle_connection_close_timeout,
         http2_max_concurrent_streams,
         http2_max_pending_accept_reset_streams,
         http2_max_header_list_size,
+        http2_keep_alive_interval,
+        http2_keep_alive_timeout,
+        http2_initial_stream_window_size,
+        http2_initial_connection_window_size,
     } = opts;
 
     let connection_shutdown_token = CancellationToken::new();
@@ -416,7 +618,8 @@ where
     let service = hyper::service::service_fn({
         let remote_addr = remote_addr.clone();
 
-        move |req: http::Request<Incoming>| {
+        move |mut req: http::Request<Incoming>| {
+            req.extensions_mut().insert(graceful_shutdown.clone());
             let ep = ep.clone();
             let local_addr = local_addr.clone();
             let remote_addr = remote_addr.clone();
@@ -454,7 +657,14 @@ where
         .max_pending_accept_reset_streams(
             http2_max_pending_accept_reset_streams.map(|x| x as usize),
         )
-        .max_header_list_size(http2_max_header_list_size);
+        .max_header_list_size(http2_max_header_list_size)
+        .initial_stream_window_size(http2_initial_stream_window_size)
+        .initial_connection_window_size(http2_initial_connection_window_size)
+        .timer(hyper_util::rt::TokioTimer::new())
+        .keep_alive_interval(http2_keep_alive_interval);
+    if let Some(timeout) = http2_keep_alive_timeout {
+        builder.keep_alive_timeout(timeout);
+    }
 
     let conn =
         builder.serve_connection_with_upgrades(hyper_util::rt::TokioIo::new(socket), service);
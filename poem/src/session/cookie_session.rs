@@ -4,6 +4,7 @@ use serde_json::Value;
 
 use crate::{
     Endpoint, Middleware, Request, Result,
+    endpoint::ShutdownHooks,
     middleware::{CookieJarManager, CookieJarManagerEndpoint},
     session::{CookieConfig, Session, SessionStatus},
 };
@@ -88,6 +89,10 @@ impl<E: Endpoint> Endpoint for CookieSessionEndpoint<E> {
 
         Ok(resp)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
 
 #[cfg(test)]
@@ -9,17 +9,28 @@ use parking_lot::Mutex;
 use priority_queue::PriorityQueue;
 use serde_json::Value;
 
-use crate::{Result, session::SessionStorage};
+use crate::{
+    Result,
+    hooks::{Clock, SharedClock},
+    session::SessionStorage,
+};
 
 struct InnerStorage {
     sessions: HashMap<String, BTreeMap<String, Value>>,
     timeout_queue: PriorityQueue<String, Reverse<Instant>>,
+    clock: SharedClock,
 }
 
 impl InnerStorage {
+    fn is_expired(&self, session_id: &str) -> bool {
+        self.timeout_queue
+            .get_priority(session_id)
+            .is_some_and(|expire_at| expire_at.0 <= self.clock.now())
+    }
+
     fn cleanup(&mut self) {
         loop {
-            let now = Instant::now();
+            let now = self.clock.now();
             if let Some((_, expire_at)) = self.timeout_queue.peek() {
                 if expire_at.0 > now {
                     break;
@@ -44,6 +55,7 @@ impl Default for MemoryStorage {
         let inner = Arc::new(Mutex::new(InnerStorage {
             sessions: HashMap::new(),
             timeout_queue: PriorityQueue::new(),
+            clock: SharedClock::default(),
         }));
         tokio::spawn({
             let inner = Arc::downgrade(&inner);
@@ -66,6 +78,15 @@ impl MemoryStorage {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Sets the [`Clock`] used for the expiration of the sessions.
+    ///
+    /// Default is the [`SystemClock`](crate::hooks::SystemClock).
+    #[must_use]
+    pub fn clock(self, clock: impl Clock) -> Self {
+        self.inner.lock().clock = SharedClock::new(clock);
+        self
+    }
 }
 
 impl SessionStorage for MemoryStorage {
@@ -74,6 +95,9 @@ impl SessionStorage for MemoryStorage {
         session_id: &'a str,
     ) -> Result<Option<BTreeMap<String, Value>>> {
         let inner = self.inner.lock();
+        if inner.is_expired(session_id) {
+            return Ok(None);
+        }
         Ok(inner.sessions.get(session_id).cloned())
     }
 
@@ -89,9 +113,10 @@ impl SessionStorage for MemoryStorage {
             .sessions
             .insert(session_id.to_string(), entries.clone());
         if let Some(expires) = expires {
+            let expire_at = inner.clock.now() + expires;
             inner
                 .timeout_queue
-                .push(session_id.to_string(), Reverse(Instant::now() + expires));
+                .push(session_id.to_string(), Reverse(expire_at));
         }
         Ok(())
     }
@@ -113,6 +138,7 @@ mod tests {
             CookieConfig, ServerSession,
             test_harness::{TestClient, index},
         },
+        test::{MockClock, SequentialIdGenerator},
     };
 
     #[tokio::test]
@@ -136,6 +162,36 @@ mod tests {
         client.assert_cookies(vec![]);
     }
 
+    #[tokio::test]
+    async fn session_id_generator() {
+        let app = Route::new().at("/:action", index).with(
+            ServerSession::new(CookieConfig::default(), MemoryStorage::new())
+                .id_generator(SequentialIdGenerator::new("sid")),
+        );
+        let mut client = TestClient::default();
+
+        client.call(&app, 1).await;
+        client.assert_cookies(vec![("poem-session", "sid-1")]);
+    }
+
+    #[tokio::test]
+    async fn clock() {
+        let clock = MockClock::new();
+        let storage = MemoryStorage::new().clock(clock.clone());
+        let mut values = BTreeMap::new();
+        values.insert("value".to_string(), "1".into());
+
+        storage
+            .update_session("a", &values, Some(Duration::from_secs(60)))
+            .await
+            .unwrap();
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(storage.load_session("a").await.unwrap(), Some(values));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(storage.load_session("a").await.unwrap(), None);
+    }
+
     #[tokio::test]
     async fn timeout() {
         let storage = MemoryStorage::new();
@@ -5,6 +5,8 @@ use rand::{Rng, rng};
 
 use crate::{
     Endpoint, Middleware, Request, Result,
+    endpoint::ShutdownHooks,
+    hooks::{IdGenerator, SharedIdGenerator},
     middleware::{CookieJarManager, CookieJarManagerEndpoint},
     session::{CookieConfig, Session, SessionStatus, session_storage::SessionStorage},
 };
@@ -13,6 +15,7 @@ use crate::{
 pub struct ServerSession<T> {
     config: Arc<CookieConfig>,
     storage: Arc<T>,
+    id_generator: Option<SharedIdGenerator>,
 }
 
 impl<T> ServerSession<T> {
@@ -21,6 +24,19 @@ impl<T> ServerSession<T> {
         Self {
             config: Arc::new(config),
             storage: Arc::new(storage),
+            id_generator: None,
+        }
+    }
+
+    /// Sets the [`IdGenerator`] for the session ids.
+    ///
+    /// The session ids must be unpredictable, so this should only be used in
+    /// tests. Default is 256-bit random ids.
+    #[must_use]
+    pub fn id_generator(self, id_generator: impl IdGenerator) -> Self {
+        Self {
+            id_generator: Some(SharedIdGenerator::new(id_generator)),
+            ..self
         }
     }
 }
@@ -33,6 +49,7 @@ impl<T: SessionStorage, E: Endpoint> Middleware<E> for ServerSession<T> {
             inner: ep,
             config: self.config.clone(),
             storage: self.storage.clone(),
+            id_generator: self.id_generator.clone(),
         })
     }
 }
@@ -40,9 +57,14 @@ impl<T: SessionStorage, E: Endpoint> Middleware<E> for ServerSession<T> {
 /// Session key generation routine that follows [OWASP recommendations].
 ///
 /// [OWASP recommendations]: https://cheatsheetseries.owasp.org/cheatsheets/Session_Management_Cheat_Sheet.html#session-id-entropy
-fn generate_session_id() -> String {
-    let random_bytes = rng().random::<[u8; 32]>();
-    URL_SAFE_NO_PAD.encode(random_bytes)
+fn generate_session_id(id_generator: Option<&SharedIdGenerator>) -> String {
+    match id_generator {
+        Some(id_generator) => id_generator.generate(),
+        None => {
+            let random_bytes = rng().random::<[u8; 32]>();
+            URL_SAFE_NO_PAD.encode(random_bytes)
+        }
+    }
 }
 
 /// Endpoint for `ServerSession` middleware.
@@ -50,6 +72,7 @@ pub struct ServerSessionEndpoint<T, E> {
     inner: E,
     config: Arc<CookieConfig>,
     storage: Arc<T>,
+    id_generator: Option<SharedIdGenerator>,
 }
 
 impl<T, E> Endpoint for ServerSessionEndpoint<T, E>
@@ -84,7 +107,7 @@ where
                         .await?;
                 }
                 None => {
-                    let session_id = generate_session_id();
+                    let session_id = generate_session_id(self.id_generator.as_ref());
                     self.config.set_cookie_value(&cookie_jar, &session_id);
                     self.storage
                         .update_session(&session_id, &session.entries(), self.config.ttl())
@@ -96,7 +119,7 @@ where
                     self.storage.remove_session(&session_id).await?;
                 }
 
-                let session_id = generate_session_id();
+                let session_id = generate_session_id(self.id_generator.as_ref());
                 self.config.set_cookie_value(&cookie_jar, &session_id);
                 self.storage
                     .update_session(&session_id, &session.entries(), self.config.ttl())
@@ -113,4 +136,8 @@ where
 
         Ok(resp)
     }
+
+    fn collect_shutdown_hooks(&self, hooks: &mut ShutdownHooks) {
+        self.inner.collect_shutdown_hooks(hooks);
+    }
 }
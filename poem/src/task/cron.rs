@@ -0,0 +1,234 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+use crate::error::ParseCronError;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// The maximum number of years searched for the next occurrence.
+const MAX_YEARS: i32 = 5;
+
+/// A cron schedule.
+///
+/// The expression contains 5 fields (`minute hour day-of-month month
+/// day-of-week`), or 6 fields with a leading `second` field. Each field is
+/// either `*`, a value, a range `a-b`, or a comma-separated list of them, and
+/// can be followed by a step `/n`. Months and days of week can also be
+/// specified by their three letter English names.
+///
+/// All times are in UTC.
+///
+/// # Example
+///
+/// ```
+/// use poem::task::CronSchedule;
+///
+/// // every 15 minutes during working hours on weekdays
+/// let schedule: CronSchedule = "*/15 9-17 * * MON-FRI".parse().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = ParseCronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let (seconds, fields) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => return Err(ParseCronError::InvalidFieldCount(n)),
+        };
+
+        let days_of_week = parse_field(fields[4], 0, 7, &WEEKDAYS, 0)?;
+        Ok(Self {
+            seconds: parse_field(seconds, 0, 59, &[], 0)?,
+            minutes: parse_field(fields[0], 0, 59, &[], 0)?,
+            hours: parse_field(fields[1], 0, 23, &[], 0)?,
+            days_of_month: parse_field(fields[2], 1, 31, &[], 0)?,
+            months: parse_field(fields[3], 1, 12, &MONTHS, 1)?,
+            // 7 is an alias of Sunday
+            days_of_week: (days_of_week | (days_of_week >> 7)) & 0x7f,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+}
+
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    first_name: u32,
+) -> Result<u64, ParseCronError> {
+    let invalid = || ParseCronError::InvalidField(field.to_string());
+    let parse_value = |s: &str| -> Result<u32, ParseCronError> {
+        let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(idx) => idx as u32 + first_name,
+            None => s.parse().map_err(|_| invalid())?,
+        };
+        if !(min..=max).contains(&value) {
+            return Err(invalid());
+        }
+        Ok(value)
+    };
+
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+                None if step > 1 => (parse_value(range)?, max),
+                None => {
+                    let value = parse_value(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[inline]
+fn contains(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+impl CronSchedule {
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = contains(self.days_of_month, date.day());
+        let day_of_week = contains(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+
+    /// Returns the first time strictly after `after` matching this schedule.
+    ///
+    /// Returns `None` if there is no such time in the next 5 years.
+    pub fn next_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.naive_utc().with_nanosecond(0)? + Duration::seconds(1);
+        let mut t = start;
+
+        while t.year() <= start.year() + MAX_YEARS {
+            if !contains(self.months, t.month()) {
+                let (year, month) = match t.month() {
+                    12 => (t.year() + 1, 1),
+                    month => (t.year(), month + 1),
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(t.date()) {
+                t = (t.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+            } else if !contains(self.hours, t.hour()) {
+                t = next_unit(t, 3600);
+            } else if !contains(self.minutes, t.minute()) {
+                t = next_unit(t, 60);
+            } else if !contains(self.seconds, t.second()) {
+                t += Duration::seconds(1);
+            } else {
+                return Some(t.and_utc());
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the start of the next hour or minute.
+fn next_unit(t: NaiveDateTime, seconds: i64) -> NaiveDateTime {
+    let elapsed = i64::from(t.num_seconds_from_midnight()) % seconds;
+    t + Duration::seconds(seconds - elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn next(expr: &str, after: DateTime<Utc>) -> DateTime<Utc> {
+        expr.parse::<CronSchedule>()
+            .unwrap()
+            .next_after(&after)
+            .unwrap()
+    }
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, s).unwrap()
+    }
+
+    #[test]
+    fn next_after() {
+        let now = utc(2024, 2, 28, 23, 59, 30);
+        assert_eq!(next("* * * * *", now), utc(2024, 2, 29, 0, 0, 0));
+        assert_eq!(next("* * * * * *", now), utc(2024, 2, 28, 23, 59, 31));
+        assert_eq!(next("*/15 * * * *", now), utc(2024, 2, 29, 0, 0, 0));
+        assert_eq!(next("30 9-17 * * MON-FRI", now), utc(2024, 2, 29, 9, 30, 0));
+        assert_eq!(next("0 0 1 * *", now), utc(2024, 3, 1, 0, 0, 0));
+        assert_eq!(next("0 12 * JUN 0", now), utc(2024, 6, 2, 12, 0, 0));
+        assert_eq!(next("0 12 * JUN 7", now), utc(2024, 6, 2, 12, 0, 0));
+        assert_eq!(next("0 0 29 2 *", now), utc(2024, 2, 29, 0, 0, 0));
+        assert_eq!(
+            next("0 0 29 2 *", utc(2024, 2, 29, 0, 0, 0)),
+            utc(2028, 2, 29, 0, 0, 0)
+        );
+        // day of month or day of week
+        assert_eq!(next("0 0 15 * SAT", now), utc(2024, 3, 2, 0, 0, 0));
+        assert_eq!(next("10,20/20 0 * * *", now), utc(2024, 2, 29, 0, 10, 0));
+    }
+
+    #[test]
+    fn invalid() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(expr.parse::<CronSchedule>().is_err(), "{expr}");
+        }
+        assert!(
+            "0 0 31 2 *"
+                .parse::<CronSchedule>()
+                .unwrap()
+                .next_after(&Utc::now())
+                .is_none()
+        );
+    }
+}
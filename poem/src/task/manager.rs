@@ -0,0 +1,355 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+};
+
+use futures_util::future::BoxFuture;
+use tokio::sync::mpsc;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+#[cfg(feature = "cron")]
+use crate::task::CronSchedule;
+use crate::{
+    FromRequest, Request, RequestBody, Result,
+    error::{GetDataError, TaskError},
+};
+
+/// A task receives the shutdown signal and the token cancelled when the
+/// queues are closed.
+type TaskFn = Box<dyn FnOnce(ShutdownSignal, CancellationToken) -> BoxFuture<'static, ()> + Send>;
+
+/// A signal notifying the background tasks that the server is shutting
+/// down.
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal(CancellationToken);
+
+impl ShutdownSignal {
+    /// Returns `true` if the shutdown has been initiated.
+    pub fn is_shutdown(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    /// Waits until the shutdown is initiated.
+    pub async fn wait(&self) {
+        self.0.cancelled().await
+    }
+}
+
+/// A registry of background tasks.
+///
+/// See also: [module level documentation](crate::task)
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Vec<(String, TaskFn)>,
+    queues: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl TaskManager {
+    /// Create a task manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a long-running task.
+    ///
+    /// The task should return soon after the [`ShutdownSignal`] fires.
+    #[must_use]
+    pub fn task<F, Fut>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnOnce(ShutdownSignal) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.push((
+            name.into(),
+            Box::new(move |shutdown, _| Box::pin(f(shutdown))),
+        ));
+        self
+    }
+
+    /// Registers a queue of jobs of type `T`, processed one at a time by
+    /// `handler`.
+    ///
+    /// Jobs are enqueued with [`TaskHandle::enqueue`]. The queue accepts jobs
+    /// until all connections are closed, and the remaining jobs are processed
+    /// before the server stops.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a queue for the type `T` is already registered.
+    #[must_use]
+    pub fn queue<T, F, Fut>(mut self, capacity: usize, handler: F) -> Self
+    where
+        T: Send + 'static,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel::<T>(capacity);
+        assert!(
+            self.queues
+                .insert(TypeId::of::<T>(), Box::new(tx))
+                .is_none(),
+            "a queue for `{}` is already registered",
+            type_name::<T>()
+        );
+
+        self.tasks.push((
+            format!("queue<{}>", type_name::<T>()),
+            Box::new(move |_, closed| {
+                Box::pin(async move {
+                    loop {
+                        tokio::select! {
+                            job = rx.recv() => match job {
+                                Some(job) => handler(job).await,
+                                None => return,
+                            },
+                            _ = closed.cancelled() => break,
+                        }
+                    }
+
+                    rx.close();
+                    while let Some(job) = rx.recv().await {
+                        handler(job).await;
+                    }
+                })
+            }),
+        ));
+        self
+    }
+
+    /// Registers a task that runs `f` according to the cron `schedule`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::task::{CronSchedule, TaskManager};
+    ///
+    /// let tasks = TaskManager::new().schedule(
+    ///     "cleanup",
+    ///     "0 */5 * * * *".parse::<CronSchedule>().unwrap(),
+    ///     || async { println!("cleanup") },
+    /// );
+    /// ```
+    #[cfg(feature = "cron")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cron")))]
+    #[must_use]
+    pub fn schedule<F, Fut>(self, name: impl Into<String>, schedule: CronSchedule, f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.task(name, move |shutdown| async move {
+            while let Some(next) = schedule.next_after(&chrono::Utc::now()) {
+                let delay = (next - chrono::Utc::now()).to_std().unwrap_or_default();
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => f().await,
+                    _ = shutdown.wait() => break,
+                }
+            }
+        })
+    }
+
+    /// Starts all the registered tasks.
+    ///
+    /// This is called by the [`Server`](crate::Server), it is only needed to
+    /// run the tasks without a server.
+    pub fn start(self) -> RunningTasks {
+        let shutdown = CancellationToken::new();
+        let closed = CancellationToken::new();
+        let tracker = TaskTracker::new();
+
+        for (name, task) in self.tasks {
+            tracing::debug!(name = %name, "start background task");
+            tracker.spawn(task(ShutdownSignal(shutdown.clone()), closed.clone()));
+        }
+
+        RunningTasks {
+            handle: TaskHandle {
+                queues: Arc::new(self.queues),
+                shutdown,
+                closed,
+                tracker,
+            },
+        }
+    }
+}
+
+/// The tasks started by [`TaskManager::start`].
+pub struct RunningTasks {
+    handle: TaskHandle,
+}
+
+impl RunningTasks {
+    /// Returns a handle to enqueue jobs and spawn tasks.
+    pub fn handle(&self) -> TaskHandle {
+        self.handle.clone()
+    }
+
+    /// Sends the [`ShutdownSignal`] to the tasks.
+    ///
+    /// The queues still accept jobs until [`RunningTasks::shutdown`] is
+    /// called.
+    pub fn signal_shutdown(&self) {
+        self.handle.shutdown.cancel();
+    }
+
+    /// Sends the [`ShutdownSignal`] to the tasks, closes the queues and waits
+    /// for all the tasks to complete.
+    pub async fn shutdown(self) {
+        self.signal_shutdown();
+        self.handle.closed.cancel();
+        self.handle.tracker.close();
+        self.handle.tracker.wait().await;
+    }
+}
+
+/// A handle to enqueue jobs and spawn background tasks.
+///
+/// It can be extracted in the handlers when the server runs with a
+/// [`TaskManager`].
+#[derive(Clone)]
+pub struct TaskHandle {
+    queues: Arc<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+    shutdown: CancellationToken,
+    closed: CancellationToken,
+    tracker: TaskTracker,
+}
+
+impl TaskHandle {
+    fn sender<T: Send + 'static>(&self) -> Result<&mpsc::Sender<T>, TaskError> {
+        self.queues
+            .get(&TypeId::of::<T>())
+            .and_then(|sender| sender.downcast_ref::<mpsc::Sender<T>>())
+            .ok_or(TaskError::QueueNotFound(type_name::<T>()))
+    }
+
+    /// Enqueues a job, waiting if the queue is full.
+    ///
+    /// # Errors
+    ///
+    /// - [`TaskError::QueueNotFound`] if no queue is registered for `T`.
+    /// - [`TaskError::ShuttingDown`] if the queues are closed.
+    pub async fn enqueue<T: Send + 'static>(&self, job: T) -> Result<(), TaskError> {
+        if self.closed.is_cancelled() {
+            return Err(TaskError::ShuttingDown);
+        }
+        self.sender::<T>()?
+            .send(job)
+            .await
+            .map_err(|_| TaskError::ShuttingDown)
+    }
+
+    /// Enqueues a job without waiting.
+    ///
+    /// # Errors
+    ///
+    /// - [`TaskError::QueueNotFound`] if no queue is registered for `T`.
+    /// - [`TaskError::QueueFull`] if the queue is full.
+    /// - [`TaskError::ShuttingDown`] if the queues are closed.
+    pub fn try_enqueue<T: Send + 'static>(&self, job: T) -> Result<(), TaskError> {
+        if self.closed.is_cancelled() {
+            return Err(TaskError::ShuttingDown);
+        }
+        self.sender::<T>()?.try_send(job).map_err(|err| match err {
+            mpsc::error::TrySendError::Full(_) => TaskError::QueueFull,
+            mpsc::error::TrySendError::Closed(_) => TaskError::ShuttingDown,
+        })
+    }
+
+    /// Spawns a task which is awaited before the server stops.
+    pub fn spawn<F>(&self, fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.tracker.spawn(fut);
+    }
+
+    /// Returns the signal notifying that the server is shutting down.
+    pub fn shutdown_signal(&self) -> ShutdownSignal {
+        ShutdownSignal(self.shutdown.clone())
+    }
+}
+
+impl<'a> FromRequest<'a> for TaskHandle {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(req
+            .data::<TaskHandle>()
+            .cloned()
+            .ok_or_else(|| GetDataError(type_name::<TaskHandle>()))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn queue_is_drained_on_shutdown() {
+        let processed = Arc::new(AtomicUsize::new(0));
+        let tasks = TaskManager::new()
+            .queue(16, {
+                let processed = processed.clone();
+                move |n: usize| {
+                    let processed = processed.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        processed.fetch_add(n, Ordering::SeqCst);
+                    }
+                }
+            })
+            .start();
+
+        let handle = tasks.handle();
+        for n in 1..=5usize {
+            handle.try_enqueue(n).unwrap();
+        }
+        assert!(matches!(
+            handle.try_enqueue("abc"),
+            Err(TaskError::QueueNotFound(_))
+        ));
+
+        tasks.signal_shutdown();
+        handle.try_enqueue(5usize).unwrap();
+
+        tasks.shutdown().await;
+        assert_eq!(processed.load(Ordering::SeqCst), 20);
+        assert!(matches!(
+            handle.enqueue(6usize).await,
+            Err(TaskError::ShuttingDown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn tasks_receive_shutdown_signal() {
+        let stopped = Arc::new(AtomicUsize::new(0));
+        let tasks = TaskManager::new()
+            .task("a", {
+                let stopped = stopped.clone();
+                move |shutdown| async move {
+                    shutdown.wait().await;
+                    stopped.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .start();
+
+        let handle = tasks.handle();
+        handle.spawn({
+            let shutdown = handle.shutdown_signal();
+            let stopped = stopped.clone();
+            async move {
+                shutdown.wait().await;
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                stopped.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        tasks.shutdown().await;
+        assert_eq!(stopped.load(Ordering::SeqCst), 2);
+    }
+}
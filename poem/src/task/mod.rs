@@ -0,0 +1,64 @@
+//! Background tasks tied to the server lifecycle.
+//!
+//! Background tasks are registered on a [`TaskManager`] which is passed to
+//! [`Server::tasks`](crate::Server::tasks). The tasks start with the server,
+//! receive a [`ShutdownSignal`] when the graceful shutdown is initiated, and
+//! are awaited before the server stops.
+//!
+//! Handlers can use the [`TaskHandle`] extractor to enqueue jobs or spawn
+//! tasks that are awaited during the shutdown.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use poem::{
+//!     Route, Server, handler,
+//!     listener::TcpListener,
+//!     task::{TaskHandle, TaskManager},
+//! };
+//!
+//! struct SendEmail {
+//!     to: String,
+//! }
+//!
+//! #[handler]
+//! async fn signup(tasks: TaskHandle) -> poem::Result<()> {
+//!     tasks
+//!         .enqueue(SendEmail {
+//!             to: "sunli@example.com".to_string(),
+//!         })
+//!         .await?;
+//!     Ok(())
+//! }
+//!
+//! let tasks = TaskManager::new()
+//!     .queue(128, |job: SendEmail| async move {
+//!         println!("send email to {}", job.to);
+//!     })
+//!     .task("heartbeat", |shutdown| async move {
+//!         while !shutdown.is_shutdown() {
+//!             tokio::select! {
+//!                 _ = tokio::time::sleep(Duration::from_secs(10)) => println!("alive"),
+//!                 _ = shutdown.wait() => {}
+//!             }
+//!         }
+//!     });
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! Server::new(TcpListener::bind("0.0.0.0:3000"))
+//!     .tasks(tasks)
+//!     .run(Route::new().at("/signup", signup))
+//!     .await
+//! # });
+//! ```
+
+#[cfg(feature = "cron")]
+mod cron;
+mod manager;
+
+#[cfg(feature = "cron")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cron")))]
+pub use cron::CronSchedule;
+pub use manager::{RunningTasks, ShutdownSignal, TaskHandle, TaskManager};
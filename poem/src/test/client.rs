@@ -1,6 +1,12 @@
 use http::{HeaderMap, HeaderValue, Method, header, header::HeaderName};
 
-use crate::{Endpoint, IntoEndpoint, test::TestRequestBuilder};
+#[cfg(feature = "cookie")]
+use crate::web::cookie::{Cookie, CookieJar};
+use crate::{
+    Endpoint, IntoEndpoint,
+    hooks::{Clock, IdGenerator, SharedClock, SharedIdGenerator},
+    test::TestRequestBuilder,
+};
 
 macro_rules! impl_methods {
     ($($(#[$docs:meta])* ($name:ident, $method:ident)),*) => {
@@ -17,6 +23,10 @@ macro_rules! impl_methods {
 pub struct TestClient<E> {
     pub(crate) ep: E,
     pub(crate) default_headers: HeaderMap,
+    pub(crate) clock: Option<SharedClock>,
+    pub(crate) id_generator: Option<SharedIdGenerator>,
+    #[cfg(feature = "cookie")]
+    pub(crate) cookie_jar: Option<CookieJar>,
 }
 
 impl<E: Endpoint> TestClient<E> {
@@ -28,6 +38,10 @@ impl<E: Endpoint> TestClient<E> {
         TestClient {
             ep: ep.into_endpoint(),
             default_headers: Default::default(),
+            clock: None,
+            id_generator: None,
+            #[cfg(feature = "cookie")]
+            cookie_jar: None,
         }
     }
 
@@ -123,6 +137,175 @@ impl<E: Endpoint> TestClient<E> {
         self.default_header(header::CONTENT_TYPE, content_type.as_ref())
     }
 
+    /// Sets the [`Clock`] used by the middlewares for each requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use poem::{
+    ///     Route, handler,
+    ///     hooks::SharedClock,
+    ///     test::{MockClock, TestClient},
+    /// };
+    ///
+    /// #[handler]
+    /// fn index(clock: SharedClock) -> String {
+    ///     clock
+    ///         .system_now()
+    ///         .duration_since(std::time::UNIX_EPOCH)
+    ///         .unwrap()
+    ///         .as_secs()
+    ///         .to_string()
+    /// }
+    ///
+    /// let clock = MockClock::at(std::time::UNIX_EPOCH);
+    /// let cli = TestClient::new(Route::new().at("/", index)).clock(clock.clone());
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// clock.advance(Duration::from_secs(60));
+    /// cli.get("/").send().await.assert_text("60").await;
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn clock(mut self, clock: impl Clock) -> Self {
+        self.clock = Some(SharedClock::new(clock));
+        self
+    }
+
+    /// Sets the [`IdGenerator`] used by the middlewares for each requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::{
+    ///     Route, handler,
+    ///     hooks::SharedIdGenerator,
+    ///     test::{SequentialIdGenerator, TestClient},
+    ///     web::Data,
+    /// };
+    ///
+    /// #[handler]
+    /// fn index(Data(id_generator): Data<&SharedIdGenerator>) -> String {
+    ///     id_generator.generate()
+    /// }
+    ///
+    /// let app = Route::new().at("/", index);
+    /// let cli = TestClient::new(app).id_generator(SequentialIdGenerator::new("req"));
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.get("/").send().await.assert_text("req-1").await;
+    /// cli.get("/").send().await.assert_text("req-2").await;
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn id_generator(mut self, id_generator: impl IdGenerator) -> Self {
+        self.id_generator = Some(SharedIdGenerator::new(id_generator));
+        self
+    }
+
+    /// Enables the cookie store.
+    ///
+    /// The cookies set by the responses are stored and sent with the
+    /// subsequent requests. Cookie attributes other than `Max-Age` and
+    /// `Expires` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use poem::{
+    ///     EndpointExt, Route, handler,
+    ///     middleware::CookieJarManager,
+    ///     test::TestClient,
+    ///     web::cookie::{Cookie, CookieJar},
+    /// };
+    ///
+    /// #[handler]
+    /// fn login(cookie_jar: &CookieJar) {
+    ///     cookie_jar.add(Cookie::new_with_str("user", "sunli"));
+    /// }
+    ///
+    /// #[handler]
+    /// fn me(cookie_jar: &CookieJar) -> String {
+    ///     cookie_jar
+    ///         .get("user")
+    ///         .map(|cookie| cookie.value_str().to_string())
+    ///         .unwrap_or_default()
+    /// }
+    ///
+    /// let app = Route::new()
+    ///     .at("/login", login)
+    ///     .at("/me", me)
+    ///     .with(CookieJarManager::new());
+    /// let cli = TestClient::new(app).enable_cookie_store();
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.post("/login").send().await.assert_status_is_ok();
+    /// cli.get("/me").send().await.assert_text("sunli").await;
+    /// # });
+    /// ```
+    #[cfg(feature = "cookie")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
+    #[must_use]
+    pub fn enable_cookie_store(self) -> Self {
+        Self {
+            cookie_jar: Some(CookieJar::default()),
+            ..self
+        }
+    }
+
+    /// Returns the cookie store if it is enabled.
+    #[cfg(feature = "cookie")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
+    pub fn cookie_jar(&self) -> Option<&CookieJar> {
+        self.cookie_jar.as_ref()
+    }
+
+    /// Opens a `WebSocket` connection to the specified uri.
+    ///
+    /// The endpoint is served over an in-memory connection, so no port is
+    /// bound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the endpoint does not accept the upgrade.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures_util::{SinkExt, StreamExt};
+    /// use poem::{
+    ///     IntoResponse, Route, handler,
+    ///     test::TestClient,
+    ///     web::websocket::{Message, WebSocket},
+    /// };
+    ///
+    /// #[handler]
+    /// fn index(ws: WebSocket) -> impl IntoResponse {
+    ///     ws.on_upgrade(|mut socket| async move {
+    ///         while let Some(Ok(Message::Text(text))) = socket.next().await {
+    ///             let _ = socket.send(Message::text(text.to_uppercase())).await;
+    ///         }
+    ///     })
+    /// }
+    ///
+    /// let app = Route::new().at("/ws", index);
+    /// let cli = TestClient::new(app);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut ws = cli.websocket("/ws").await;
+    /// ws.send_text("hello").await;
+    /// ws.assert_text("HELLO").await;
+    /// ws.close().await;
+    /// # });
+    /// ```
+    #[cfg(feature = "websocket")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+    pub async fn websocket(&self, uri: impl Into<String>) -> crate::test::TestWebSocket {
+        crate::test::TestWebSocket::connect(self, uri.into()).await
+    }
+
     /// Create a [`TestRequestBuilder`].
     pub fn request(&self, method: Method, uri: impl Into<String>) -> TestRequestBuilder<'_, E> {
         TestRequestBuilder::new(self, method, uri.into())
@@ -149,3 +332,92 @@ impl<E: Endpoint> TestClient<E> {
         (trace, TRACE)
     );
 }
+
+impl<E> TestClient<E> {
+    /// Appends the stored cookies to the request headers.
+    #[cfg_attr(not(feature = "cookie"), allow(unused_variables))]
+    pub(crate) fn apply_cookies(&self, headers: &mut HeaderMap) {
+        #[cfg(feature = "cookie")]
+        if let Some(cookie_jar) = &self.cookie_jar {
+            let value = cookie_jar.with_cookies(|cookies| {
+                cookies
+                    .map(|cookie| cookie.stripped().encoded().to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            });
+            if let Ok(value) = HeaderValue::from_str(&value)
+                && !value.is_empty()
+            {
+                headers.append(header::COOKIE, value);
+            }
+        }
+    }
+
+    /// Stores the cookies set by the response headers.
+    #[cfg_attr(not(feature = "cookie"), allow(unused_variables))]
+    pub(crate) fn store_cookies(&self, headers: &HeaderMap) {
+        #[cfg(feature = "cookie")]
+        if let Some(cookie_jar) = &self.cookie_jar {
+            for value in headers.get_all(header::SET_COOKIE) {
+                let Some(cookie) = value.to_str().ok().and_then(|s| Cookie::parse(s).ok()) else {
+                    continue;
+                };
+                let expired = cookie.max_age().is_some_and(|max_age| max_age.is_zero())
+                    || cookie
+                        .expires()
+                        .is_some_and(|expires| expires <= chrono::Utc::now());
+                if expired {
+                    cookie_jar.remove(cookie.name());
+                } else {
+                    cookie_jar.add(cookie);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "session"))]
+mod tests {
+    use super::*;
+    use crate::{
+        EndpointExt, Route, handler,
+        session::{CookieConfig, MemoryStorage, ServerSession, Session},
+    };
+
+    #[handler(internal)]
+    fn login(session: &Session) {
+        session.set("user", "sunli");
+    }
+
+    #[handler(internal)]
+    fn logout(session: &Session) {
+        session.purge();
+    }
+
+    #[handler(internal)]
+    fn me(session: &Session) -> String {
+        session.get::<String>("user").unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn session_continuity() {
+        let app = Route::new()
+            .at("/login", login)
+            .at("/logout", logout)
+            .at("/me", me)
+            .with(ServerSession::new(
+                CookieConfig::default(),
+                MemoryStorage::new(),
+            ));
+
+        let cli = TestClient::new(app).enable_cookie_store();
+        cli.get("/me").send().await.assert_text("").await;
+        cli.post("/login").send().await.assert_status_is_ok();
+        assert!(cli.cookie_jar().unwrap().get("poem-session").is_some());
+        cli.get("/me").send().await.assert_text("sunli").await;
+
+        cli.post("/logout").send().await.assert_status_is_ok();
+        assert!(cli.cookie_jar().unwrap().get("poem-session").is_none());
+        cli.get("/me").send().await.assert_text("").await;
+    }
+}
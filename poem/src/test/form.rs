@@ -122,6 +122,58 @@ impl TestForm {
         self
     }
 
+    /// Adds a file field with `application/octet-stream` content type.
+    ///
+    /// Use [`TestForm::field`] to specify another content type.
+    #[must_use]
+    pub fn file(
+        self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        data: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.field(
+            TestFormField::bytes(data)
+                .name(name)
+                .filename(filename)
+                .content_type("application/octet-stream"),
+        )
+    }
+
+    /// Adds a file field with `application/octet-stream` content type, the
+    /// content is streamed from the reader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::test::TestForm;
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let file = tokio::io::repeat(b'a');
+    /// let form = TestForm::new()
+    ///     .text("description", "a large file")
+    ///     .file_reader(
+    ///         "file",
+    ///         "large.bin",
+    ///         tokio::io::AsyncReadExt::take(file, 1 << 20),
+    ///     );
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn file_reader(
+        self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        reader: impl AsyncRead + Send + 'static,
+    ) -> Self {
+        self.field(
+            TestFormField::async_reader(reader)
+                .name(name)
+                .filename(filename)
+                .content_type("application/octet-stream"),
+        )
+    }
+
     #[inline]
     pub(crate) fn boundary(&self) -> &str {
         BOUNDARY_STRING
@@ -290,4 +342,46 @@ mod tests {
             .await;
         resp.assert_status_is_ok();
     }
+
+    #[tokio::test]
+    async fn files() {
+        #[handler(internal)]
+        async fn index(mut multipart: Multipart) -> String {
+            let mut res = Vec::new();
+            while let Some(field) = multipart.next_field().await.unwrap() {
+                let head = format!(
+                    "{}:{}:{}",
+                    field.name().unwrap(),
+                    field.file_name().unwrap_or_default(),
+                    field.content_type().unwrap_or_default(),
+                );
+                res.push(format!("{head}:{}", field.bytes().await.unwrap().len()));
+            }
+            res.join(",")
+        }
+
+        let cli = TestClient::new(index);
+        let resp = cli
+            .post("/")
+            .multipart(
+                TestForm::new()
+                    .text("description", "files")
+                    .file("a", "a.bin", vec![1, 2, 3])
+                    .file_reader("b", "b.bin", tokio::io::repeat(0).take(100_000))
+                    .field(
+                        TestFormField::text("{}")
+                            .name("c")
+                            .filename("c.json")
+                            .content_type("application/json"),
+                    ),
+            )
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text(
+            "description:::5,a:a.bin:application/octet-stream:3,\
+             b:b.bin:application/octet-stream:100000,c:c.json:application/json:2",
+        )
+        .await;
+    }
 }
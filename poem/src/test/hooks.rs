@@ -0,0 +1,107 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use parking_lot::Mutex;
+
+use crate::hooks::{Clock, IdGenerator};
+
+/// A [`Clock`] that only moves forward when it is advanced.
+///
+/// Clones of a `MockClock` share the same time, so a clone can be passed to
+/// [`TestClient::clock`](crate::test::TestClient::clock) and the original
+/// advanced from the test.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{hooks::Clock, test::MockClock};
+///
+/// let clock = MockClock::new();
+/// let start = clock.now();
+/// clock.advance(Duration::from_secs(10));
+/// assert_eq!(clock.now() - start, Duration::from_secs(10));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    inner: Arc<Mutex<(Instant, SystemTime)>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Create a mock clock starting at the current time.
+    pub fn new() -> Self {
+        Self::at(SystemTime::now())
+    }
+
+    /// Create a mock clock whose system time starts at `system_now`.
+    pub fn at(system_now: SystemTime) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new((Instant::now(), system_now))),
+        }
+    }
+
+    /// Advances the clock by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let mut inner = self.inner.lock();
+        inner.0 += duration;
+        inner.1 += duration;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.inner.lock().0
+    }
+
+    fn system_now(&self) -> SystemTime {
+        self.inner.lock().1
+    }
+}
+
+/// An [`IdGenerator`] that generates `{prefix}-1`, `{prefix}-2`, ...
+///
+/// Clones of a `SequentialIdGenerator` share the same counter.
+///
+/// # Example
+///
+/// ```
+/// use poem::{hooks::IdGenerator, test::SequentialIdGenerator};
+///
+/// let generator = SequentialIdGenerator::new("id");
+/// assert_eq!(generator.generate(), "id-1");
+/// assert_eq!(generator.generate(), "id-2");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SequentialIdGenerator {
+    prefix: Arc<str>,
+    counter: Arc<AtomicU64>,
+}
+
+impl SequentialIdGenerator {
+    /// Create a sequential id generator with the specified prefix.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into().into(),
+            counter: Default::default(),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn generate(&self) -> String {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{}-{}", self.prefix, n)
+    }
+}
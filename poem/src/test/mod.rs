@@ -111,12 +111,21 @@
 
 mod client;
 mod form;
+mod hooks;
 mod json;
 mod request_builder;
 mod response;
+mod sse;
+#[cfg(feature = "websocket")]
+mod websocket;
 
 pub use client::TestClient;
 pub use form::{TestForm, TestFormField};
+pub use hooks::{MockClock, SequentialIdGenerator};
 pub use json::{TestJson, TestJsonArray, TestJsonObject, TestJsonValue};
 pub use request_builder::TestRequestBuilder;
 pub use response::TestResponse;
+pub use sse::TestSseStream;
+#[cfg(feature = "websocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub use websocket::TestWebSocket;
@@ -170,7 +170,14 @@ impl<'a, E> TestRequestBuilder<'a, E> {
             .finish();
         req.headers_mut().extend(self.cli.default_headers.clone());
         req.headers_mut().extend(self.headers);
-        *req.extensions_mut() = self.extensions;
+        self.cli.apply_cookies(req.headers_mut());
+        if let Some(clock) = &self.cli.clock {
+            req.extensions_mut().insert(clock.clone());
+        }
+        if let Some(id_generator) = &self.cli.id_generator {
+            req.extensions_mut().insert(id_generator.clone());
+        }
+        req.extensions_mut().extend(self.extensions);
         req.set_body(self.body);
 
         req
@@ -211,9 +218,10 @@ impl<'a, E> TestRequestBuilder<'a, E> {
     where
         E: Endpoint,
     {
-        let ep = &self.cli.ep;
+        let cli = self.cli;
         let req = self.make_request();
-        let resp = ep.get_response(req).await;
+        let resp = cli.ep.get_response(req).await;
+        cli.store_cookies(resp.headers());
         TestResponse::new(resp)
     }
 }
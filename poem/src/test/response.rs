@@ -6,7 +6,11 @@ use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
 use tokio_util::compat::TokioAsyncReadCompatExt;
 
-use crate::{Response, test::json::TestJson, web::sse::Event};
+use crate::{
+    Response,
+    test::{TestSseStream, json::TestJson},
+    web::sse::Event,
+};
 
 /// A response object for testing.
 pub struct TestResponse(pub Response);
@@ -211,6 +215,40 @@ impl TestResponse {
             .boxed()
     }
 
+    /// Consumes this object and return a [`TestSseStream`] with assertions
+    /// for the SSE events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures_util::stream;
+    /// use poem::{
+    ///     handler,
+    ///     test::TestClient,
+    ///     web::sse::{Event, SSE},
+    /// };
+    ///
+    /// #[handler]
+    /// fn index() -> SSE {
+    ///     SSE::new(stream::iter(vec![
+    ///         Event::message("hello"),
+    ///         Event::message("world").event_type("greeting"),
+    ///     ]))
+    /// }
+    ///
+    /// let cli = TestClient::new(index);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let mut stream = cli.get("/").send().await.sse();
+    /// stream.assert_data("hello").await;
+    /// stream.assert_event("greeting", "world").await;
+    /// stream.assert_end().await;
+    /// # });
+    /// ```
+    pub fn sse(self) -> TestSseStream {
+        TestSseStream::new(self.sse_stream().boxed())
+    }
+
     /// Consumes this object and return the SSE events stream which deserialize
     /// the message data to `T`.
     pub fn typed_sse_stream<T: DeserializeOwned + 'static>(
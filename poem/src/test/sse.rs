@@ -0,0 +1,155 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{Stream, StreamExt, stream::BoxStream};
+
+use crate::web::sse::Event;
+
+/// A stream of SSE events for testing, which implements [`Stream<Event>`].
+///
+/// See [`TestResponse::sse`](crate::test::TestResponse::sse).
+pub struct TestSseStream {
+    inner: BoxStream<'static, Event>,
+    timeout: Duration,
+}
+
+impl TestSseStream {
+    pub(crate) fn new(inner: BoxStream<'static, Event>) -> Self {
+        Self {
+            inner,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets the maximum time to wait for each event.
+    ///
+    /// Default is `5s`.
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Returns the next event.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stream ends or no event is received within the timeout.
+    pub async fn next_event(&mut self) -> Event {
+        match tokio::time::timeout(self.timeout, self.inner.next()).await {
+            Ok(Some(event)) => event,
+            Ok(None) => panic!("expect an event, but the stream has ended"),
+            Err(_) => panic!("expect an event within {:?}", self.timeout),
+        }
+    }
+
+    /// Returns the `(id, event, data)` of the next message, skipping the
+    /// retry events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stream ends or no message is received within the
+    /// timeout.
+    pub async fn next_message(&mut self) -> (String, String, String) {
+        loop {
+            if let Event::Message { id, event, data } = self.next_event().await {
+                return (id, event, data);
+            }
+        }
+    }
+
+    /// Asserts that the next message has the specified data.
+    pub async fn assert_data(&mut self, data: impl AsRef<str>) {
+        let (_, _, value) = self.next_message().await;
+        assert_eq!(value, data.as_ref());
+    }
+
+    /// Asserts that the next message has the specified event type and data.
+    pub async fn assert_event(&mut self, event: impl AsRef<str>, data: impl AsRef<str>) {
+        let (_, value_event, value_data) = self.next_message().await;
+        assert_eq!(value_event, event.as_ref());
+        assert_eq!(value_data, data.as_ref());
+    }
+
+    /// Asserts that the next message has the specified id and data.
+    pub async fn assert_id(&mut self, id: impl AsRef<str>, data: impl AsRef<str>) {
+        let (value_id, _, value_data) = self.next_message().await;
+        assert_eq!(value_id, id.as_ref());
+        assert_eq!(value_data, data.as_ref());
+    }
+
+    /// Asserts that the stream ends within the timeout.
+    pub async fn assert_end(&mut self) {
+        match tokio::time::timeout(self.timeout, self.inner.next()).await {
+            Ok(None) => {}
+            Ok(Some(event)) => panic!("expect the stream to end, got {event:?}"),
+            Err(_) => panic!("expect the stream to end within {:?}", self.timeout),
+        }
+    }
+}
+
+impl Stream for TestSseStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        handler,
+        test::TestClient,
+        web::sse::{Event, SSE},
+    };
+
+    #[handler(internal)]
+    fn index() -> SSE {
+        SSE::new(stream::iter(vec![
+            Event::message("a"),
+            Event::message("b").event_type("ping"),
+            Event::message("c").id("3"),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn assertions() {
+        let cli = TestClient::new(index);
+        let mut stream = cli.get("/").send().await.sse();
+        assert_eq!(
+            stream.next_event().await,
+            Event::Message {
+                id: String::new(),
+                event: "message".to_string(),
+                data: "a".to_string(),
+            }
+        );
+        stream.assert_event("ping", "b").await;
+        stream.assert_id("3", "c").await;
+        stream.assert_end().await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn timeout() {
+        #[handler(internal)]
+        fn pending() -> SSE {
+            SSE::new(stream::pending())
+        }
+
+        let cli = TestClient::new(pending);
+        let mut stream = cli
+            .get("/")
+            .send()
+            .await
+            .sse()
+            .timeout(Duration::from_millis(50));
+        stream.next_event().await;
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    convert::Infallible,
+    io::Error as IoError,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use http::uri::Scheme;
+use hyper::body::Incoming;
+use hyper_util::rt::TokioIo;
+use tokio::io::DuplexStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+use crate::{
+    Endpoint, Request,
+    test::TestClient,
+    web::{
+        LocalAddr, RemoteAddr,
+        websocket::{Message, utils::tungstenite_error_to_io_error},
+    },
+};
+
+/// A `WebSocket` connection for testing, which implements
+/// [`Stream<Message>`] and [`Sink<Message>`].
+///
+/// See [`TestClient::websocket`].
+pub struct TestWebSocket {
+    inner: tokio_tungstenite::WebSocketStream<DuplexStream>,
+}
+
+impl TestWebSocket {
+    pub(crate) async fn connect<E: Endpoint>(cli: &TestClient<E>, uri: String) -> Self {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+
+        let mut req = format!("ws://localhost{uri}")
+            .into_client_request()
+            .expect("valid websocket uri");
+        for (name, value) in &cli.default_headers {
+            req.headers_mut().append(name, value.clone());
+        }
+        cli.apply_cookies(req.headers_mut());
+
+        let service = hyper::service::service_fn(|req: http::Request<Incoming>| async {
+            let req = Request::from((
+                req,
+                LocalAddr::default(),
+                RemoteAddr::default(),
+                Scheme::HTTP,
+            ));
+            Ok::<http::Response<_>, Infallible>(cli.ep.get_response(req).await.into())
+        });
+        let conn = hyper::server::conn::http1::Builder::new()
+            .serve_connection(TokioIo::new(server_io), service)
+            .with_upgrades();
+        let client = tokio_tungstenite::client_async(req, client_io);
+        futures_util::pin_mut!(conn, client);
+
+        // The connection must be driven until the upgrade is completed.
+        let mut conn_done = false;
+        let res = tokio::select! {
+            res = &mut client => res,
+            res = &mut conn => {
+                conn_done = true;
+                res.expect("serve connection");
+                client.await
+            }
+        };
+        let (inner, resp) = res.unwrap_or_else(|err| panic!("websocket handshake failed: {err}"));
+        cli.store_cookies(resp.headers());
+        if !conn_done {
+            conn.await.expect("serve connection");
+        }
+
+        Self { inner }
+    }
+
+    /// Sends a message, panics if it fails.
+    pub async fn send_message(&mut self, msg: Message) {
+        SinkExt::send(self, msg)
+            .await
+            .expect("send websocket message");
+    }
+
+    /// Sends a text message, panics if it fails.
+    pub async fn send_text(&mut self, text: impl Into<String>) {
+        self.send_message(Message::text(text)).await;
+    }
+
+    /// Sends a binary message, panics if it fails.
+    pub async fn send_binary(&mut self, data: impl Into<Vec<u8>>) {
+        self.send_message(Message::binary(data)).await;
+    }
+
+    /// Receives the next message, ignoring ping and pong messages.
+    ///
+    /// Returns `None` if the connection is closed, panics if it fails.
+    pub async fn next_message(&mut self) -> Option<Message> {
+        loop {
+            match StreamExt::next(self).await {
+                Some(Ok(msg)) if msg.is_ping() || msg.is_pong() => continue,
+                Some(Ok(msg)) => return Some(msg),
+                Some(Err(err)) => panic!("receive websocket message: {err}"),
+                None => return None,
+            }
+        }
+    }
+
+    /// Asserts that the next message is a text message with the specified
+    /// content.
+    pub async fn assert_text(&mut self, text: impl AsRef<str>) {
+        match self.next_message().await {
+            Some(Message::Text(value)) => assert_eq!(value, text.as_ref()),
+            msg => panic!("expect a text message, got {msg:?}"),
+        }
+    }
+
+    /// Asserts that the next message is a binary message with the specified
+    /// content.
+    pub async fn assert_binary(&mut self, data: impl AsRef<[u8]>) {
+        match self.next_message().await {
+            Some(Message::Binary(value)) => assert_eq!(value, data.as_ref()),
+            msg => panic!("expect a binary message, got {msg:?}"),
+        }
+    }
+
+    /// Asserts that the connection is closed by the server.
+    pub async fn assert_closed(&mut self) {
+        match self.next_message().await {
+            None | Some(Message::Close(_)) => {}
+            msg => panic!("expect the connection to be closed, got {msg:?}"),
+        }
+    }
+
+    /// Closes the connection.
+    pub async fn close(mut self) {
+        SinkExt::close(&mut self)
+            .await
+            .expect("close websocket connection");
+    }
+}
+
+impl Stream for TestWebSocket {
+    type Item = Result<Message, IoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner
+            .poll_next_unpin(cx)
+            .map(|msg| msg.map(|res| res.map(Into::into).map_err(tungstenite_error_to_io_error)))
+    }
+}
+
+impl Sink<Message> for TestWebSocket {
+    type Error = IoError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner
+            .poll_ready_unpin(cx)
+            .map_err(tungstenite_error_to_io_error)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        self.inner
+            .start_send_unpin(item.into())
+            .map_err(tungstenite_error_to_io_error)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner
+            .poll_flush_unpin(cx)
+            .map_err(tungstenite_error_to_io_error)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner
+            .poll_close_unpin(cx)
+            .map_err(tungstenite_error_to_io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        IntoResponse, Route, handler,
+        web::{Query, websocket::WebSocket},
+    };
+
+    #[derive(serde::Deserialize)]
+    struct Params {
+        prefix: String,
+    }
+
+    #[handler(internal)]
+    fn echo(ws: WebSocket, Query(Params { prefix }): Query<Params>) -> impl IntoResponse {
+        ws.on_upgrade(move |mut socket| async move {
+            while let Some(Ok(msg)) = socket.next().await {
+                match msg {
+                    Message::Text(text) => {
+                        let _ = socket.send(Message::text(format!("{prefix}{text}"))).await;
+                    }
+                    Message::Binary(data) => {
+                        let _ = socket.send(Message::binary(data)).await;
+                        let _ = socket.close().await;
+                    }
+                    _ => {}
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn echo_messages() {
+        let cli = TestClient::new(Route::new().at("/ws", echo));
+        let mut ws = cli.websocket("/ws?prefix=a:").await;
+
+        ws.send_text("hello").await;
+        ws.assert_text("a:hello").await;
+
+        ws.send_binary([1, 2, 3]).await;
+        ws.assert_binary([1, 2, 3]).await;
+        ws.assert_closed().await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn no_upgrade() {
+        let cli = TestClient::new(Route::new().at("/ws", echo));
+        cli.websocket("/not-found").await;
+    }
+}
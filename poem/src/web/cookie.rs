@@ -395,7 +395,7 @@ impl From<libcookie::Cookie<'static>> for Cookie {
 #[derive(Default, Clone)]
 pub struct CookieJar {
     jar: Arc<Mutex<libcookie::CookieJar>>,
-    pub(crate) key: Option<Arc<CookieKey>>,
+    pub(crate) keys: Option<Arc<CookieKeys>>,
 }
 
 impl CookieJar {
@@ -477,20 +477,51 @@ impl CookieJar {
     /// assert!(cookie_jar.private_with_key(&key2).get("foo").is_none());
     /// ```
     pub fn private_with_key<'a>(&'a self, key: &'a CookieKey) -> PrivateCookieJar<'a> {
+        self.private_with_keys(key, &[])
+    }
+
+    /// Similar to the `private_with_key` function, but the cookies encrypted
+    /// with one of the `previous_keys` can also be retrieved.
+    ///
+    /// This allows rotating the key without invalidating the cookies issued
+    /// under the previous keys. The cookies added to the jar are always
+    /// encrypted with `key`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::web::cookie::{Cookie, CookieJar, CookieKey};
+    ///
+    /// let old_key = CookieKey::generate();
+    /// let cookie_jar = CookieJar::default();
+    ///
+    /// cookie_jar
+    ///     .private_with_key(&old_key)
+    ///     .add(Cookie::new_with_str("foo", "bar"));
+    ///
+    /// let new_key = CookieKey::generate();
+    /// let previous_keys = [old_key];
+    /// let private = cookie_jar.private_with_keys(&new_key, &previous_keys);
+    /// assert_eq!(private.get("foo").unwrap().value_str(), "bar");
+    /// ```
+    pub fn private_with_keys<'a>(
+        &'a self,
+        key: &'a CookieKey,
+        previous_keys: &'a [CookieKey],
+    ) -> PrivateCookieJar<'a> {
         PrivateCookieJar {
             key,
+            previous_keys,
             cookie_jar: self,
         }
     }
 
-    /// Similar to the `private_with_key` function, but using the key specified
-    /// by the `CookieJarManager::with_key`.
+    /// Similar to the `private_with_keys` function, but using the keys
+    /// specified by the `CookieJarManager::with_key` or
+    /// `CookieJarManager::with_keys`.
     pub fn private(&self) -> PrivateCookieJar<'_> {
-        self.private_with_key(
-            self.key
-                .as_ref()
-                .expect("You must use the `CookieJarManager::with_key` to specify a `CookieKey`."),
-        )
+        let keys = self.manager_keys();
+        self.private_with_keys(&keys.current, &keys.previous)
     }
 
     /// Returns a SignedJar with self as its parent jar using the key
@@ -524,20 +555,39 @@ impl CookieJar {
     /// assert!(cookie_jar.signed_with_key(&key2).get("foo").is_none());
     /// ```
     pub fn signed_with_key<'a>(&'a self, key: &'a CookieKey) -> SignedCookieJar<'a> {
+        self.signed_with_keys(key, &[])
+    }
+
+    /// Similar to the `signed_with_key` function, but the cookies signed with
+    /// one of the `previous_keys` can also be retrieved.
+    ///
+    /// This allows rotating the key without invalidating the cookies issued
+    /// under the previous keys. The cookies added to the jar are always
+    /// signed with `key`.
+    pub fn signed_with_keys<'a>(
+        &'a self,
+        key: &'a CookieKey,
+        previous_keys: &'a [CookieKey],
+    ) -> SignedCookieJar<'a> {
         SignedCookieJar {
             key,
+            previous_keys,
             cookie_jar: self,
         }
     }
 
-    /// Similar to the `signed_with_key` function, but using the key specified
-    /// by the `CookieJarManager::with_key`.
+    /// Similar to the `signed_with_keys` function, but using the keys
+    /// specified by the `CookieJarManager::with_key` or
+    /// `CookieJarManager::with_keys`.
     pub fn signed(&self) -> SignedCookieJar<'_> {
-        self.signed_with_key(
-            self.key
-                .as_ref()
-                .expect("You must use the `CookieJarManager::with_key` to specify a `CookieKey`."),
-        )
+        let keys = self.manager_keys();
+        self.signed_with_keys(&keys.current, &keys.previous)
+    }
+
+    fn manager_keys(&self) -> &CookieKeys {
+        self.keys
+            .as_deref()
+            .expect("You must use the `CookieJarManager::with_key` to specify a `CookieKey`.")
     }
 }
 
@@ -555,7 +605,7 @@ impl FromStr for CookieJar {
 
         Ok(CookieJar {
             jar: Arc::new(Mutex::new(cookie_jar)),
-            key: None,
+            keys: None,
         })
     }
 }
@@ -592,7 +642,7 @@ impl CookieJar {
 
         CookieJar {
             jar: Arc::new(Mutex::new(cookie_jar)),
-            key: None,
+            keys: None,
         }
     }
 
@@ -610,13 +660,62 @@ impl CookieJar {
 /// A cryptographic master key for use with Signed and/or Private jars.
 pub type CookieKey = libcookie::Key;
 
+/// Derives a [`CookieKey`] from a master secret and a context.
+///
+/// Different contexts derive independent keys, so a single master secret can
+/// back several purposes (e.g. `"session"` and `"preferences"`) or key
+/// versions during a rotation.
+///
+/// # Panics
+///
+/// Panics if `master_secret` is shorter than 32 bytes.
+///
+/// # Example
+///
+/// ```
+/// use poem::web::cookie::derive_cookie_key;
+///
+/// let master_secret = [7u8; 32];
+/// let session_key = derive_cookie_key(&master_secret, "session");
+/// let other_key = derive_cookie_key(&master_secret, "preferences");
+/// assert_ne!(session_key, other_key);
+/// assert_eq!(session_key, derive_cookie_key(&master_secret, "session"));
+/// ```
+pub fn derive_cookie_key(master_secret: &[u8], context: &str) -> CookieKey {
+    assert!(
+        master_secret.len() >= 32,
+        "master secret must be at least 32 bytes"
+    );
+    let mut material = master_secret.to_vec();
+    material.push(0);
+    material.extend_from_slice(context.as_bytes());
+    CookieKey::derive_from(&material)
+}
+
+/// The current and previous keys specified by the `CookieJarManager`.
+pub(crate) struct CookieKeys {
+    pub(crate) current: CookieKey,
+    pub(crate) previous: Vec<CookieKey>,
+}
+
 /// A child cookie jar that provides authenticated encryption for its cookies.
 pub struct PrivateCookieJar<'a> {
     key: &'a CookieKey,
+    previous_keys: &'a [CookieKey],
     cookie_jar: &'a CookieJar,
 }
 
 impl PrivateCookieJar<'_> {
+    fn decrypt(
+        &self,
+        cookie_jar: &libcookie::CookieJar,
+        cookie: &libcookie::Cookie<'static>,
+    ) -> Option<libcookie::Cookie<'static>> {
+        std::iter::once(self.key)
+            .chain(self.previous_keys)
+            .find_map(|key| cookie_jar.private(key).decrypt(cookie.clone()))
+    }
+
     /// Adds cookie to the parent jar. The cookie’s value is encrypted with
     /// authenticated encryption assuring confidentiality, integrity, and
     /// authenticity.
@@ -639,8 +738,8 @@ impl PrivateCookieJar<'_> {
     /// authenticate or decrypt, None is returned.
     pub fn get(&self, name: &str) -> Option<Cookie> {
         let cookie_jar = self.cookie_jar.jar.lock();
-        let private_cookie_jar = cookie_jar.private(self.key);
-        private_cookie_jar.get(name).map(Cookie)
+        let cookie = cookie_jar.get(name)?;
+        self.decrypt(&cookie_jar, cookie).map(Cookie)
     }
 
     /// Returns cookie inside this jar with the name ignore the case and
@@ -652,7 +751,7 @@ impl PrivateCookieJar<'_> {
         cookie_jar
             .iter()
             .find(|cookie| cookie.name().eq_ignore_ascii_case(name))
-            .and_then(|cookie| cookie_jar.private(self.key).decrypt(cookie.clone()))
+            .and_then(|cookie| self.decrypt(&cookie_jar, cookie))
             .map(Cookie)
     }
 }
@@ -660,10 +759,21 @@ impl PrivateCookieJar<'_> {
 /// A child cookie jar that authenticates its cookies.
 pub struct SignedCookieJar<'a> {
     key: &'a CookieKey,
+    previous_keys: &'a [CookieKey],
     cookie_jar: &'a CookieJar,
 }
 
 impl SignedCookieJar<'_> {
+    fn verify(
+        &self,
+        cookie_jar: &libcookie::CookieJar,
+        cookie: &libcookie::Cookie<'static>,
+    ) -> Option<libcookie::Cookie<'static>> {
+        std::iter::once(self.key)
+            .chain(self.previous_keys)
+            .find_map(|key| cookie_jar.signed(key).verify(cookie.clone()))
+    }
+
     /// Adds cookie to the parent jar. The cookie’s value is signed assuring
     /// integrity and authenticity.
     pub fn add(&self, cookie: Cookie) {
@@ -685,8 +795,8 @@ impl SignedCookieJar<'_> {
     /// authenticate or decrypt, None is returned.
     pub fn get(&self, name: &str) -> Option<Cookie> {
         let cookie_jar = self.cookie_jar.jar.lock();
-        let signed_cookie_jar = cookie_jar.signed(self.key);
-        signed_cookie_jar.get(name).map(Cookie)
+        let cookie = cookie_jar.get(name)?;
+        self.verify(&cookie_jar, cookie).map(Cookie)
     }
 
     /// Returns cookie inside this jar with the name ignore the case and
@@ -698,7 +808,7 @@ impl SignedCookieJar<'_> {
         cookie_jar
             .iter()
             .find(|cookie| cookie.name().eq_ignore_ascii_case(name))
-            .and_then(|cookie| cookie_jar.signed(self.key).verify(cookie.clone()))
+            .and_then(|cookie| self.verify(&cookie_jar, cookie))
             .map(Cookie)
     }
 }
@@ -793,6 +903,57 @@ mod tests {
         assert_eq!(signed.get("a"), None);
     }
 
+    #[test]
+    fn key_rotation() {
+        let old_key = CookieKey::generate();
+        let new_key = CookieKey::generate();
+        let previous_keys = [old_key.clone()];
+        let cookie_jar = CookieJar::default();
+        cookie_jar
+            .private_with_key(&old_key)
+            .add(Cookie::new_with_str("a", "123"));
+        cookie_jar
+            .signed_with_key(&old_key)
+            .add(Cookie::new_with_str("b", "456"));
+
+        assert_eq!(cookie_jar.private_with_key(&new_key).get("a"), None);
+        assert_eq!(cookie_jar.signed_with_key(&new_key).get("b"), None);
+
+        let private = cookie_jar.private_with_keys(&new_key, &previous_keys);
+        let signed = cookie_jar.signed_with_keys(&new_key, &previous_keys);
+        assert_eq!(private.get("a").unwrap().value_str(), "123");
+        assert_eq!(
+            private.get_ignore_ascii_case("A").unwrap().value_str(),
+            "123"
+        );
+        assert_eq!(signed.get("b").unwrap().value_str(), "456");
+        assert_eq!(
+            signed.get_ignore_ascii_case("B").unwrap().value_str(),
+            "456"
+        );
+
+        // new cookies are issued under the current key
+        private.add(Cookie::new_with_str("c", "789"));
+        signed.add(Cookie::new_with_str("d", "0"));
+        assert_eq!(
+            cookie_jar
+                .private_with_key(&new_key)
+                .get("c")
+                .unwrap()
+                .value_str(),
+            "789"
+        );
+        assert_eq!(
+            cookie_jar
+                .signed_with_key(&new_key)
+                .get("d")
+                .unwrap()
+                .value_str(),
+            "0"
+        );
+        assert_eq!(cookie_jar.private_with_key(&old_key).get("c"), None);
+    }
+
     #[test]
     fn test_extract_from_multiple_cookie_headers() {
         let mut headers = HeaderMap::new();
@@ -37,6 +37,21 @@ impl<'a> FromRequest<'a> for &'a CsrfToken {
 pub struct CsrfVerifier {
     cookie: Option<UnencryptedCsrfCookie>,
     protect: Arc<AesGcmCsrfProtection>,
+    now: i64,
+}
+
+/// The length of the expiration time at the start of the token values.
+pub(crate) const EXPIRES_LEN: usize = 8;
+
+/// Returns the expiration time, in seconds since the Unix epoch, of a token
+/// value.
+pub(crate) fn token_expires(value: &[u8]) -> Option<i64> {
+    if value.len() != 64 {
+        return None;
+    }
+    let mut expires = [0; EXPIRES_LEN];
+    expires.copy_from_slice(&value[..EXPIRES_LEN]);
+    Some(i64::from_be_bytes(expires))
 }
 
 /// Enum representing CSRF validation error
@@ -55,8 +70,13 @@ impl CsrfVerifier {
     pub(crate) fn new(
         cookie: Option<UnencryptedCsrfCookie>,
         protect: Arc<AesGcmCsrfProtection>,
+        now: i64,
     ) -> Self {
-        Self { cookie, protect }
+        Self {
+            cookie,
+            protect,
+            now,
+        }
     }
 }
 
@@ -91,8 +111,18 @@ impl CsrfVerifier {
 
         let token = self.protect.parse_token(&token_data)?;
 
-        self.protect
-            .verify_token_pair(&token, cookie)
-            .map_err(Into::into)
+        // The tokens only have to match the random part of the cookie, since
+        // the expiration time is updated for each request.
+        if token.value().get(EXPIRES_LEN..) != cookie.value().get(EXPIRES_LEN..) {
+            return Err(libcsrf::CsrfError::ValidationFailure(
+                "Token did not match cookie".to_string(),
+            )
+            .into());
+        }
+
+        match token_expires(cookie.value()) {
+            Some(expires) if expires > self.now => Ok(()),
+            _ => Err(libcsrf::CsrfError::ValidationFailure("Cookie expired".to_string()).into()),
+        }
     }
 }
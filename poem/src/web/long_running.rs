@@ -0,0 +1,626 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures_util::{FutureExt, Stream, future::BoxFuture, stream};
+use http::{Method, StatusCode, header};
+use parking_lot::Mutex;
+use rand::{Rng, rng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Notify;
+
+use crate::{
+    Endpoint, Error, IntoResponse, Request, Response, Result,
+    web::{
+        Json,
+        sse::{Event, SSE},
+    },
+};
+
+/// The status of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The job has been accepted but not started yet.
+    Pending,
+    /// The job is running.
+    Running,
+    /// The job completed successfully.
+    Succeeded,
+    /// The job failed.
+    Failed,
+}
+
+impl JobStatus {
+    /// Returns `true` if the job has completed, successfully or not.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Succeeded | JobStatus::Failed)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+/// The state of a job, which is returned by the status endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobState {
+    /// The job id.
+    pub id: String,
+    /// The job status.
+    pub status: JobStatus,
+    /// The output of the job if it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// The error message if the job failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JobState {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            status: JobStatus::Pending,
+            result: None,
+            error: None,
+        }
+    }
+}
+
+/// Represents a back-end job storage.
+pub trait JobStore: Send + Sync {
+    /// Load the state of a job.
+    fn load_job<'a>(
+        &'a self,
+        job_id: &'a str,
+    ) -> impl Future<Output = Result<Option<JobState>>> + Send + 'a;
+
+    /// Insert or update the state of a job.
+    ///
+    /// The `expires` is set when the job has finished, the state can be
+    /// removed after that duration.
+    fn update_job<'a>(
+        &'a self,
+        state: &'a JobState,
+        expires: Option<Duration>,
+    ) -> impl Future<Output = Result<()>> + Send + 'a;
+}
+
+trait DynJobStore: Send + Sync {
+    fn load_job<'a>(&'a self, job_id: &'a str) -> BoxFuture<'a, Result<Option<JobState>>>;
+
+    fn update_job<'a>(
+        &'a self,
+        state: &'a JobState,
+        expires: Option<Duration>,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+impl<T: JobStore> DynJobStore for T {
+    fn load_job<'a>(&'a self, job_id: &'a str) -> BoxFuture<'a, Result<Option<JobState>>> {
+        Box::pin(JobStore::load_job(self, job_id))
+    }
+
+    fn update_job<'a>(
+        &'a self,
+        state: &'a JobState,
+        expires: Option<Duration>,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(JobStore::update_job(self, state, expires))
+    }
+}
+
+struct StoredJob {
+    state: JobState,
+    expire_at: Option<Instant>,
+}
+
+/// A job storage using memory.
+pub struct MemoryJobStore {
+    inner: Arc<Mutex<HashMap<String, StoredJob>>>,
+}
+
+impl Default for MemoryJobStore {
+    fn default() -> Self {
+        let inner = Arc::new(Mutex::new(HashMap::<String, StoredJob>::new()));
+        tokio::spawn({
+            let inner = Arc::downgrade(&inner);
+            async move {
+                loop {
+                    match inner.upgrade() {
+                        Some(inner) => {
+                            let now = Instant::now();
+                            inner.lock().retain(|_, job| match job.expire_at {
+                                Some(expire_at) => expire_at > now,
+                                None => true,
+                            });
+                        }
+                        None => return,
+                    }
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        });
+        Self { inner }
+    }
+}
+
+impl MemoryJobStore {
+    /// Create a `MemoryJobStore`.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl JobStore for MemoryJobStore {
+    async fn load_job<'a>(&'a self, job_id: &'a str) -> Result<Option<JobState>> {
+        Ok(self.inner.lock().get(job_id).map(|job| job.state.clone()))
+    }
+
+    async fn update_job<'a>(
+        &'a self,
+        state: &'a JobState,
+        expires: Option<Duration>,
+    ) -> Result<()> {
+        self.inner.lock().insert(
+            state.id.clone(),
+            StoredJob {
+                state: state.clone(),
+                expire_at: expires.map(|expires| Instant::now() + expires),
+            },
+        );
+        Ok(())
+    }
+}
+
+/// A helper for long running operations.
+///
+/// [`LongRunning::spawn`] runs a job in the background and returns a [`Job`],
+/// which responds `202 Accepted` with a `Location` header pointing to the
+/// status endpoint created by [`LongRunning::status_endpoint`].
+///
+/// The status endpoint responds the [`JobState`] as JSON, or streams the
+/// changes of the state with Server-Sent Events until the job has finished if
+/// the client accepts `text/event-stream`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     EndpointExt, Result, Route, handler,
+///     http::StatusCode,
+///     post,
+///     test::TestClient,
+///     web::{Data, Job, LongRunning, MemoryJobStore},
+/// };
+///
+/// #[handler]
+/// async fn create_report(jobs: Data<&LongRunning>) -> Result<Job> {
+///     jobs.spawn(async {
+///         tokio::time::sleep(Duration::from_millis(10)).await;
+///         Ok("report")
+///     })
+///     .await
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let jobs = LongRunning::new(MemoryJobStore::new()).status_path("/jobs");
+/// let app = Route::new()
+///     .at("/reports", post(create_report))
+///     .nest("/jobs", jobs.status_endpoint())
+///     .data(jobs);
+/// let cli = TestClient::new(app);
+///
+/// let resp = cli.post("/reports").send().await;
+/// resp.assert_status(StatusCode::ACCEPTED);
+/// let location = resp.0.headers()["location"].to_str().unwrap().to_string();
+///
+/// let resp = cli.get(&location).send().await;
+/// resp.assert_status_is_ok();
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct LongRunning {
+    store: Arc<dyn DynJobStore>,
+    notify: Arc<Notify>,
+    status_path: String,
+    retention: Option<Duration>,
+    poll_interval: Duration,
+}
+
+impl LongRunning {
+    /// Create a `LongRunning` with the specified job storage.
+    pub fn new(store: impl JobStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            notify: Default::default(),
+            status_path: "/jobs".to_string(),
+            retention: Some(Duration::from_secs(60 * 60)),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the path where the status endpoint is mounted, it is used to
+    /// generate the `Location` header.
+    ///
+    /// Default is `/jobs`.
+    #[must_use]
+    pub fn status_path(self, path: impl Into<String>) -> Self {
+        Self {
+            status_path: path.into(),
+            ..self
+        }
+    }
+
+    /// Sets how long the state of a finished job is kept in the storage,
+    /// `None` means forever.
+    ///
+    /// Default is `1 hour`.
+    #[must_use]
+    pub fn retention(self, retention: Option<Duration>) -> Self {
+        Self { retention, ..self }
+    }
+
+    /// Sets the interval at which the status endpoint checks the storage for
+    /// changes when streaming with Server-Sent Events.
+    ///
+    /// The changes made by this `LongRunning` are sent immediately, the
+    /// interval only matters for the jobs updated by another process sharing
+    /// the same storage.
+    ///
+    /// Default is `1 second`.
+    #[must_use]
+    pub fn poll_interval(self, interval: Duration) -> Self {
+        Self {
+            poll_interval: interval,
+            ..self
+        }
+    }
+
+    /// Creates an endpoint to query the state of the jobs.
+    ///
+    /// The job id is taken from the last segment of the request path, so it
+    /// can be nested in a route with [`Route::nest`](crate::Route::nest).
+    pub fn status_endpoint(&self) -> JobStatusEndpoint {
+        JobStatusEndpoint { jobs: self.clone() }
+    }
+
+    /// Spawns a job in the background, the output of the job is serialized as
+    /// the result of the [`JobState`].
+    pub async fn spawn<F, T>(&self, fut: F) -> Result<Job>
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+        T: Serialize + Send + 'static,
+    {
+        let id = format!("{:032x}", rng().random::<u128>());
+        let state = JobState::new(id);
+        self.store.update_job(&state, None).await?;
+
+        tokio::spawn({
+            let jobs = self.clone();
+            let mut state = state.clone();
+            async move {
+                state.status = JobStatus::Running;
+                jobs.update(&state).await;
+
+                match AssertUnwindSafe(fut).catch_unwind().await {
+                    Ok(Ok(value)) => match serde_json::to_value(value) {
+                        Ok(value) => {
+                            state.status = JobStatus::Succeeded;
+                            state.result = Some(value);
+                        }
+                        Err(err) => {
+                            state.status = JobStatus::Failed;
+                            state.error = Some(err.to_string());
+                        }
+                    },
+                    Ok(Err(err)) => {
+                        state.status = JobStatus::Failed;
+                        state.error = Some(err.to_string());
+                    }
+                    Err(_) => {
+                        state.status = JobStatus::Failed;
+                        state.error = Some("job panicked".to_string());
+                    }
+                }
+                jobs.update(&state).await;
+            }
+        });
+
+        Ok(Job {
+            location: format!("{}/{}", self.status_path.trim_end_matches('/'), state.id),
+            state,
+        })
+    }
+
+    async fn update(&self, state: &JobState) {
+        let expires = if state.status.is_finished() {
+            self.retention
+        } else {
+            None
+        };
+        if let Err(err) = self.store.update_job(state, expires).await {
+            tracing::error!(job_id = %state.id, error = %err, "failed to update the job state");
+        }
+        self.notify.notify_waiters();
+    }
+
+    fn watch(self, id: String) -> impl Stream<Item = Event> + Send + 'static {
+        stream::unfold(Some((self, id, None)), |ctx| async move {
+            let (jobs, id, last) = ctx?;
+            loop {
+                let notify = jobs.notify.clone();
+                let mut notified = pin!(notify.notified());
+                notified.as_mut().enable();
+
+                let state = match jobs.store.load_job(&id).await {
+                    Ok(Some(state)) => state,
+                    Ok(None) | Err(_) => return None,
+                };
+                if last.as_ref() != Some(&state) {
+                    let event = Event::message(serde_json::to_string(&state).unwrap_or_default())
+                        .event_type(state.status.as_str());
+                    let next = (!state.status.is_finished()).then_some((jobs, id, Some(state)));
+                    return Some((event, next));
+                }
+
+                tokio::select! {
+                    _ = notified => {}
+                    _ = tokio::time::sleep(jobs.poll_interval) => {}
+                }
+            }
+        })
+    }
+}
+
+/// A job handle returned by [`LongRunning::spawn`].
+///
+/// It responds `202 Accepted` with a `Location` header pointing to the status
+/// endpoint of the job, and the [`JobState`] as the body.
+#[derive(Debug, Clone)]
+pub struct Job {
+    state: JobState,
+    location: String,
+}
+
+impl Job {
+    /// Returns the job id.
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.state.id
+    }
+
+    /// Returns the path of the status endpoint of the job.
+    #[inline]
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+}
+
+impl IntoResponse for Job {
+    fn into_response(self) -> Response {
+        Json(self.state)
+            .with_status(StatusCode::ACCEPTED)
+            .with_header(header::LOCATION, self.location)
+            .into_response()
+    }
+}
+
+/// Endpoint for the status of the jobs created by [`LongRunning`].
+pub struct JobStatusEndpoint {
+    jobs: LongRunning,
+}
+
+impl Endpoint for JobStatusEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if req.method() != Method::GET {
+            return Err(Error::from_status(StatusCode::METHOD_NOT_ALLOWED));
+        }
+
+        let id = req.uri().path().rsplit('/').next().unwrap_or_default();
+        let state = self
+            .jobs
+            .store
+            .load_job(id)
+            .await?
+            .ok_or_else(|| Error::from_string("job not found", StatusCode::NOT_FOUND))?;
+
+        let accept_sse = super::parse_accept(req.headers())
+            .first()
+            .is_some_and(|mime| mime.essence_str() == mime::TEXT_EVENT_STREAM.essence_str());
+        if accept_sse {
+            Ok(SSE::new(self.jobs.clone().watch(state.id)).into_response())
+        } else {
+            Ok(Json(state).into_response())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use serde_json::json;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::{EndpointExt, Route, handler, post, test::TestClient, web::Data};
+
+    fn app(jobs: LongRunning) -> impl Endpoint {
+        #[handler(internal)]
+        async fn start(jobs: Data<&LongRunning>, body: String) -> Result<Job> {
+            jobs.spawn(async move {
+                match body.as_str() {
+                    "fail" => Err(Error::from_string("boom", StatusCode::BAD_REQUEST)),
+                    "panic" => panic!("boom"),
+                    _ => Ok(json!({ "echo": body })),
+                }
+            })
+            .await
+        }
+
+        Route::new()
+            .at("/start", post(start))
+            .nest("/jobs", jobs.status_endpoint())
+            .data(jobs)
+    }
+
+    async fn wait_finished(cli: &TestClient<impl Endpoint>, location: &str) -> Value {
+        loop {
+            let resp = cli.get(location).send().await;
+            resp.assert_status_is_ok();
+            let value = resp.0.into_body().into_json::<Value>().await.unwrap();
+            if value["status"] != "pending" && value["status"] != "running" {
+                return value;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn accepted() {
+        let cli = TestClient::new(app(LongRunning::new(MemoryJobStore::new())));
+        let resp = cli.post("/start").body("hello").send().await;
+        resp.assert_status(StatusCode::ACCEPTED);
+        let location = resp.0.headers()[header::LOCATION]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(location.starts_with("/jobs/"));
+
+        let value = resp.0.into_body().into_json::<Value>().await.unwrap();
+        assert_eq!(value["status"], "pending");
+        assert_eq!(location, format!("/jobs/{}", value["id"].as_str().unwrap()));
+
+        let value = wait_finished(&cli, &location).await;
+        assert_eq!(value["status"], "succeeded");
+        assert_eq!(value["result"], json!({ "echo": "hello" }));
+    }
+
+    #[tokio::test]
+    async fn failed() {
+        let cli = TestClient::new(app(LongRunning::new(MemoryJobStore::new())));
+
+        let resp = cli.post("/start").body("fail").send().await;
+        let location = resp.0.headers()[header::LOCATION].to_str().unwrap();
+        let value = wait_finished(&cli, location).await;
+        assert_eq!(value["status"], "failed");
+        assert_eq!(value["error"], "boom");
+
+        let resp = cli.post("/start").body("panic").send().await;
+        let location = resp.0.headers()[header::LOCATION].to_str().unwrap();
+        let value = wait_finished(&cli, location).await;
+        assert_eq!(value["status"], "failed");
+        assert_eq!(value["error"], "job panicked");
+    }
+
+    #[tokio::test]
+    async fn not_found() {
+        let cli = TestClient::new(app(LongRunning::new(MemoryJobStore::new())));
+        cli.get("/jobs/abc")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        cli.post("/jobs/abc")
+            .send()
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn status_path() {
+        let jobs = LongRunning::new(MemoryJobStore::new()).status_path("/api/jobs/");
+        let job = jobs.spawn(async { Ok(1) }).await.unwrap();
+        assert_eq!(job.location(), format!("/api/jobs/{}", job.id()));
+    }
+
+    #[tokio::test]
+    async fn retention() {
+        let store = Arc::new(MemoryJobStore::new());
+        let jobs = LongRunning::new(MemoryJobStoreRef(store.clone()))
+            .retention(Some(Duration::from_millis(100)));
+        let job = jobs.spawn(async { Ok(1) }).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            JobStore::load_job(&*store, job.id())
+                .await
+                .unwrap()
+                .is_some()
+        );
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(
+            JobStore::load_job(&*store, job.id())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    struct MemoryJobStoreRef(Arc<MemoryJobStore>);
+
+    impl JobStore for MemoryJobStoreRef {
+        async fn load_job<'a>(&'a self, job_id: &'a str) -> Result<Option<JobState>> {
+            JobStore::load_job(&*self.0, job_id).await
+        }
+
+        async fn update_job<'a>(
+            &'a self,
+            state: &'a JobState,
+            expires: Option<Duration>,
+        ) -> Result<()> {
+            JobStore::update_job(&*self.0, state, expires).await
+        }
+    }
+
+    #[tokio::test]
+    async fn sse() {
+        let jobs = LongRunning::new(MemoryJobStore::new()).poll_interval(Duration::from_secs(60));
+        let (tx, rx) = oneshot::channel::<()>();
+        let job = jobs
+            .spawn(async move {
+                rx.await.ok();
+                Ok("done")
+            })
+            .await
+            .unwrap();
+        let cli = TestClient::new(app(jobs));
+
+        let resp = cli
+            .get(job.location())
+            .header(header::ACCEPT, "text/event-stream")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("text/event-stream");
+        let mut stream = resp.typed_sse_stream::<JobState>();
+
+        let mut state = stream.next().await.unwrap();
+        if state.status == JobStatus::Pending {
+            state = stream.next().await.unwrap();
+        }
+        assert_eq!(state.status, JobStatus::Running);
+
+        tx.send(()).unwrap();
+        let state = stream.next().await.unwrap();
+        assert_eq!(state.status, JobStatus::Succeeded);
+        assert_eq!(state.result, Some(json!("done")));
+        assert!(stream.next().await.is_none());
+    }
+}
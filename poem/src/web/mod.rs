@@ -10,6 +10,8 @@ pub mod cookie;
 mod data;
 mod form;
 mod json;
+#[cfg(feature = "long-running")]
+mod long_running;
 #[cfg(feature = "multipart")]
 mod multipart;
 mod path;
@@ -30,7 +32,7 @@ mod yaml;
 #[doc(inline)]
 pub use headers;
 #[cfg(feature = "csrf")]
-mod csrf;
+pub(crate) mod csrf;
 mod typed_header;
 #[cfg(feature = "websocket")]
 #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
@@ -48,9 +50,12 @@ use http::header;
 pub use self::compress::{Compress, CompressionAlgo};
 #[cfg(feature = "csrf")]
 pub use self::csrf::{CsrfToken, CsrfVerifier};
+#[cfg(feature = "long-running")]
+pub use self::long_running::{
+    Job, JobState, JobStatus, JobStatusEndpoint, JobStore, LongRunning, MemoryJobStore,
+};
 #[cfg(feature = "multipart")]
 pub use self::multipart::{Field, Multipart};
-pub(crate) use self::path::PathDeserializer;
 #[cfg(feature = "static-files")]
 pub use self::static_file::{StaticFileRequest, StaticFileResponse};
 #[cfg(feature = "tempfile")]
@@ -71,6 +76,7 @@ pub use self::{
     redirect::Redirect,
     typed_header::TypedHeader,
 };
+pub(crate) use self::{accept::parse_accept, path::PathDeserializer};
 use crate::{
     body::Body,
     error::{ReadBodyError, Result},
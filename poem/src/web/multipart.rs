@@ -3,7 +3,7 @@ use std::{
     str::FromStr,
 };
 
-use futures_util::TryStreamExt;
+use futures_util::StreamExt;
 use mime::Mime;
 #[cfg(feature = "tempfile")]
 use tokio::fs::File;
@@ -15,7 +15,10 @@ use crate::{FromRequest, Request, RequestBody, Result, error::ParseMultipartErro
 
 /// A single field in a multipart stream.
 #[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
-pub struct Field(multer::Field<'static>);
+pub struct Field {
+    inner: multer::Field<'static>,
+    limit: Option<usize>,
+}
 
 impl Debug for Field {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -41,19 +44,29 @@ impl Field {
     /// Get the content type of the field.
     #[inline]
     pub fn content_type(&self) -> Option<&str> {
-        self.0.content_type().map(|mime| mime.essence_str())
+        self.inner.content_type().map(|mime| mime.essence_str())
     }
 
     /// The file name found in the `Content-Disposition` header.
     #[inline]
     pub fn file_name(&self) -> Option<&str> {
-        self.0.file_name()
+        self.inner.file_name()
     }
 
     /// The name found in the `Content-Disposition` header.
     #[inline]
     pub fn name(&self) -> Option<&str> {
-        self.0.name()
+        self.inner.name()
+    }
+
+    /// Limits the size of the field data, reading more than `limit` bytes
+    /// fails.
+    #[must_use]
+    pub fn limit(self, limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
     }
 
     /// Get the full data of the field as bytes.
@@ -92,9 +105,19 @@ impl Field {
 
     /// Consume this field to return a reader.
     pub fn into_async_read(self) -> impl AsyncRead + Send {
-        tokio_util::io::StreamReader::new(
-            self.0.map_err(|err| std::io::Error::other(err.to_string())),
-        )
+        let mut remaining = self.limit;
+        tokio_util::io::StreamReader::new(self.inner.map(move |res| {
+            let chunk = res.map_err(|err| std::io::Error::other(err.to_string()))?;
+            if let Some(remaining) = &mut remaining {
+                *remaining = remaining.checked_sub(chunk.len()).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::FileTooLarge,
+                        "the field exceeds the size limit",
+                    )
+                })?;
+            }
+            Ok::<_, std::io::Error>(chunk)
+        }))
     }
 }
 
@@ -159,7 +182,10 @@ impl Multipart {
     /// Yields the next [`Field`] if available.
     pub async fn next_field(&mut self) -> Result<Option<Field>, ParseMultipartError> {
         match self.inner.next_field().await? {
-            Some(field) => Ok(Some(Field(field))),
+            Some(field) => Ok(Some(Field {
+                inner: field,
+                limit: None,
+            })),
             None => Ok(None),
         }
     }
@@ -216,4 +242,27 @@ mod tests {
             .await;
         resp.assert_status_is_ok();
     }
+
+    #[tokio::test]
+    async fn test_multipart_field_limit() {
+        #[handler(internal)]
+        async fn index(mut multipart: Multipart) {
+            let field = multipart.next_field().await.unwrap().unwrap();
+            assert_eq!(field.limit(4).text().await.unwrap(), "abcd");
+
+            let field = multipart.next_field().await.unwrap().unwrap();
+            assert!(field.limit(4).text().await.is_err());
+        }
+
+        let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nabcde\r\n--X-BOUNDARY--\r\n";
+        let cli = TestClient::new(index);
+
+        let resp = cli
+            .post("/")
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .body(data)
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+    }
 }
@@ -1,10 +1,10 @@
-use std::{borrow::Cow, future::Future};
+use std::{borrow::Cow, future::Future, time::Duration};
 
 use futures_util::{FutureExt, future::BoxFuture};
 use headers::HeaderMapExt;
 use tokio_tungstenite::tungstenite::protocol::{Role, WebSocketConfig};
 
-use super::{WebSocketStream, utils::sign};
+use super::{CloseCode, WebSocketStream, utils::sign};
 use crate::{
     Body, FromRequest, IntoResponse, OnUpgrade, Request, RequestBody, Response, Result,
     error::WebSocketError,
@@ -25,6 +25,10 @@ pub struct WebSocket {
     protocols: Option<Box<[Cow<'static, str>]>>,
     sec_websocket_protocol: Option<HeaderValue>,
     config: Option<WebSocketConfig>,
+    #[cfg(feature = "server")]
+    graceful_shutdown: Option<crate::server::GracefulShutdown>,
+    shutdown_frame: (CloseCode, String),
+    shutdown_timeout: Duration,
 }
 
 impl WebSocket {
@@ -64,6 +68,13 @@ impl WebSocket {
             protocols: None,
             sec_websocket_protocol,
             config: None,
+            #[cfg(feature = "server")]
+            graceful_shutdown: req
+                .extensions()
+                .get::<crate::server::GracefulShutdown>()
+                .cloned(),
+            shutdown_frame: (CloseCode::Away, String::new()),
+            shutdown_timeout: Duration::from_secs(1),
         })
     }
 }
@@ -119,6 +130,35 @@ impl WebSocket {
         }
     }
 
+    /// Sets the close frame sent to the client when the server initiates a
+    /// graceful shutdown.
+    ///
+    /// Default is [`CloseCode::Away`] without reason.
+    #[must_use]
+    pub fn shutdown_close_frame(
+        self,
+        code: impl Into<CloseCode>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            shutdown_frame: (code.into(), reason.into()),
+            ..self
+        }
+    }
+
+    /// Sets how long to wait for the client to acknowledge the close frame
+    /// sent on the graceful shutdown, the callback is dropped and the
+    /// connection is closed after that.
+    ///
+    /// Default is `1 second`.
+    #[must_use]
+    pub fn shutdown_timeout(self, timeout: Duration) -> Self {
+        Self {
+            shutdown_timeout: timeout,
+            ..self
+        }
+    }
+
     /// Finalize upgrading the connection and call the provided `callback` with
     /// the stream.
     ///
@@ -202,7 +242,19 @@ where
 
         let resp = builder.body(Body::empty());
 
-        tokio::spawn(async move {
+        #[cfg(feature = "server")]
+        let graceful_shutdown = self.websocket.graceful_shutdown;
+        #[cfg(feature = "server")]
+        let shutdown_token = graceful_shutdown
+            .as_ref()
+            .map(|graceful_shutdown| graceful_shutdown.token.clone());
+        #[cfg_attr(not(feature = "server"), allow(unused_variables))]
+        let (shutdown_frame, shutdown_timeout) = (
+            self.websocket.shutdown_frame,
+            self.websocket.shutdown_timeout,
+        );
+
+        let fut = async move {
             let upgraded = match self.websocket.on_upgrade.await {
                 Ok(upgraded) => upgraded,
                 Err(_) => return,
@@ -214,9 +266,31 @@ where
                 self.websocket.config,
             )
             .await;
-            (self.callback)(WebSocketStream::new(stream)).await;
-        });
+            let stream = WebSocketStream::new(stream);
+            #[cfg(feature = "server")]
+            let stream = match shutdown_token {
+                Some(token) => stream.close_on_shutdown(token, shutdown_frame),
+                None => stream,
+            };
+            (self.callback)(stream).await;
+        };
+
+        #[cfg(feature = "server")]
+        if let Some(graceful_shutdown) = graceful_shutdown {
+            let token = graceful_shutdown.token;
+            graceful_shutdown.tracker.spawn(async move {
+                tokio::select! {
+                    _ = fut => {}
+                    _ = async {
+                        token.cancelled().await;
+                        tokio::time::sleep(shutdown_timeout).await;
+                    } => {}
+                }
+            });
+            return resp;
+        }
 
+        tokio::spawn(fut);
         resp
     }
 }
@@ -24,7 +24,7 @@
 mod extractor;
 mod message;
 mod stream;
-mod utils;
+pub(crate) mod utils;
 
 pub use extractor::{BoxWebSocketUpgraded, WebSocket, WebSocketUpgraded};
 pub use message::{CloseCode, Message};
@@ -154,4 +154,128 @@ mod tests {
 
         handle.abort();
     }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown() {
+        use std::time::Duration;
+
+        use tokio::sync::oneshot;
+        use tokio_tungstenite::tungstenite::{self, protocol::frame::coding};
+
+        #[handler(internal)]
+        async fn index(ws: WebSocket) -> impl IntoResponse {
+            ws.shutdown_close_frame(CloseCode::Restart, "restarting")
+                .on_upgrade(|mut stream| async move {
+                    while let Some(Ok(msg)) = stream.next().await {
+                        if let Message::Text(text) = msg {
+                            if stream.send(Message::Text(text)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                })
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let (tx, rx) = oneshot::channel::<()>();
+        let handle = tokio::spawn(
+            Server::new_with_acceptor(acceptor).run_with_graceful_shutdown(
+                index,
+                async move {
+                    rx.await.ok();
+                },
+                None,
+            ),
+        );
+
+        let (mut client_stream, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+        client_stream
+            .send(tungstenite::Message::Text("abc".into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            client_stream.next().await.unwrap().unwrap(),
+            tungstenite::Message::Text("abc".into())
+        );
+
+        tx.send(()).unwrap();
+        match client_stream.next().await.unwrap().unwrap() {
+            tungstenite::Message::Close(Some(frame)) => {
+                assert_eq!(frame.code, coding::CloseCode::Restart);
+                assert_eq!(frame.reason, "restarting");
+            }
+            msg => panic!("unexpected message: {msg:?}"),
+        }
+        // the close frame is acknowledged when reading the stream
+        assert!(client_stream.next().await.is_none());
+
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_timeout() {
+        use std::time::{Duration, Instant};
+
+        use tokio::sync::oneshot;
+
+        // the server takes the shutdown hooks which are not collected
+        let _guard = crate::endpoint::SHUTDOWN_TEST_LOCK.lock().await;
+
+        #[handler(internal)]
+        async fn index(ws: WebSocket) -> impl IntoResponse {
+            ws.shutdown_timeout(Duration::from_millis(100))
+                .on_upgrade(|mut stream| async move { while stream.next().await.is_some() {} })
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let (tx, rx) = oneshot::channel::<()>();
+        let handle = tokio::spawn(
+            Server::new_with_acceptor(acceptor).run_with_graceful_shutdown(
+                index,
+                async move {
+                    rx.await.ok();
+                },
+                None,
+            ),
+        );
+
+        // the client never reads the close frame
+        let (_client_stream, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let now = Instant::now();
+        tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(now.elapsed() >= Duration::from_millis(100));
+    }
 }
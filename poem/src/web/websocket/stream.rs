@@ -1,3 +1,5 @@
+#[cfg(feature = "server")]
+use std::future::Future;
 use std::{
     io::{Error as IoError, Result as IoResult},
     pin::Pin,
@@ -5,19 +7,107 @@ use std::{
 };
 
 use futures_util::{Sink, SinkExt, Stream, StreamExt};
+#[cfg(feature = "server")]
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 
+#[cfg(feature = "server")]
+use super::CloseCode;
 use super::{Message, WebSocketConfig, utils::tungstenite_error_to_io_error};
 use crate::Upgraded;
 
+/// Sends a close frame when the server initiates a graceful shutdown.
+#[cfg(feature = "server")]
+struct ShutdownClose {
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+    frame: Option<(CloseCode, String)>,
+    state: ShutdownState,
+}
+
+#[cfg(feature = "server")]
+enum ShutdownState {
+    Waiting,
+    Sending,
+    Flushing,
+    Done,
+}
+
+#[cfg(feature = "server")]
+impl ShutdownClose {
+    /// Returns `Poll::Pending` only while the close frame is being sent.
+    fn poll(
+        &mut self,
+        inner: &mut tokio_tungstenite::WebSocketStream<Upgraded>,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        loop {
+            match self.state {
+                ShutdownState::Waiting => {
+                    if self.cancelled.as_mut().poll(cx).is_pending() {
+                        return Poll::Ready(());
+                    }
+                    self.state = ShutdownState::Sending;
+                }
+                ShutdownState::Sending => {
+                    match inner.poll_ready_unpin(cx) {
+                        Poll::Ready(Ok(())) => {}
+                        Poll::Ready(Err(_)) => {
+                            self.state = ShutdownState::Done;
+                            continue;
+                        }
+                        Poll::Pending => return Poll::Pending,
+                    }
+                    // fails if the socket is already closing
+                    let msg = Message::Close(self.frame.take());
+                    self.state = match inner.start_send_unpin(msg.into()) {
+                        Ok(()) => ShutdownState::Flushing,
+                        Err(_) => ShutdownState::Done,
+                    };
+                }
+                ShutdownState::Flushing => match inner.poll_flush_unpin(cx) {
+                    Poll::Ready(_) => self.state = ShutdownState::Done,
+                    Poll::Pending => return Poll::Pending,
+                },
+                ShutdownState::Done => return Poll::Ready(()),
+            }
+        }
+    }
+}
+
 /// A `WebSocket` stream, which implements [`Stream<Message>`] and
 /// [`Sink<Message>`].
+///
+/// When the server initiates a graceful shutdown, a close frame is sent to
+/// the client the next time the stream is polled, so the stream should be
+/// read until it ends to receive the acknowledgement of the client.
 pub struct WebSocketStream {
     inner: tokio_tungstenite::WebSocketStream<Upgraded>,
+    #[cfg(feature = "server")]
+    shutdown: Option<ShutdownClose>,
 }
 
 impl WebSocketStream {
     pub(crate) fn new(inner: tokio_tungstenite::WebSocketStream<Upgraded>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            #[cfg(feature = "server")]
+            shutdown: None,
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub(crate) fn close_on_shutdown(
+        self,
+        token: CancellationToken,
+        frame: (CloseCode, String),
+    ) -> Self {
+        Self {
+            shutdown: Some(ShutdownClose {
+                cancelled: Box::pin(token.cancelled_owned()),
+                frame: Some(frame),
+                state: ShutdownState::Waiting,
+            }),
+            ..self
+        }
     }
 
     /// Returns a reference to the configuration of the stream.
@@ -30,6 +120,16 @@ impl Stream for WebSocketStream {
     type Item = IoResult<Message>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        #[cfg(feature = "server")]
+        {
+            let this = &mut *self;
+            if let Some(shutdown) = &mut this.shutdown {
+                if shutdown.poll(&mut this.inner, cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+        }
+
         match self.inner.poll_next_unpin(cx) {
             Poll::Ready(Some(Ok(msg))) => Poll::Ready(Some(Ok(msg.into()))),
             Poll::Ready(Some(Err(err))) => {